@@ -1,13 +1,19 @@
 //! Build script for pdf-renderer
 //! 
 //! 自动下载 PDFium 预编译库并配置链接路径
+//!
+//! 离线构建（无法访问 GitHub 的构建机）可以通过环境变量指定本地来源：
+//! - `PDFIUM_ARCHIVE_PATH`：本地的 pdfium-binaries 压缩包（.tgz），代替下载
+//! - `PDFIUM_PREBUILT_DIR`：已解压的目录（包含 `lib/libpdfium.so` 或直接包含库文件）
+//! - `PDFIUM_SHA256`：压缩包（或预解压目录中库文件）的 SHA-256，不匹配时构建失败
+//! - `PDFIUM_OFFLINE=1`：禁止下载，未指定本地来源时构建失败
 
 extern crate napi_build;
 
 use std::env;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// PDFium 版本和下载源 (bblanchon/pdfium-binaries)
 const PDFIUM_VERSION: &str = "7606";
@@ -22,19 +28,51 @@ fn main() {
     let pdfium_dir = out_dir.join("pdfium");
     let lib_dir = pdfium_dir.join("lib");
     
-    // 检查是否已经下载
-    let lib_name = get_pdfium_lib_name();
-    let lib_path = lib_dir.join(lib_name);
+    // 重新运行条件
+    println!("cargo:rerun-if-env-changed=PDFIUM_DYNAMIC_LIB_PATH");
+    println!("cargo:rerun-if-env-changed=PDFIUM_ARCHIVE_PATH");
+    println!("cargo:rerun-if-env-changed=PDFIUM_PREBUILT_DIR");
+    println!("cargo:rerun-if-env-changed=PDFIUM_SHA256");
+    println!("cargo:rerun-if-env-changed=PDFIUM_OFFLINE");
+    println!("cargo:rerun-if-changed=build.rs");
     
-    if !lib_path.exists() {
-        println!("cargo:warning=Downloading PDFium library...");
-        if let Err(e) = download_pdfium(&pdfium_dir) {
-            println!("cargo:warning=Failed to download PDFium: {}", e);
-            println!("cargo:warning=You may need to manually install PDFium or set PDFIUM_DYNAMIC_LIB_PATH");
-            return;
+    let expected_sha256 = env::var("PDFIUM_SHA256").ok().map(|s| s.trim().to_lowercase());
+    let offline = env::var("PDFIUM_OFFLINE").is_ok_and(|v| v == "1" || v == "true");
+
+    let lib_name = get_pdfium_lib_name();
+    let lib_dir = if let Ok(prebuilt) = env::var("PDFIUM_PREBUILT_DIR") {
+        // 预解压目录：直接使用，不复制
+        match use_prebuilt_dir(&PathBuf::from(prebuilt), expected_sha256.as_deref()) {
+            Ok(dir) => dir,
+            Err(e) => panic!("Invalid PDFIUM_PREBUILT_DIR: {}", e),
         }
-        println!("cargo:warning=PDFium downloaded successfully!");
-    }
+    } else if let Ok(archive) = env::var("PDFIUM_ARCHIVE_PATH") {
+        // 本地压缩包：每次都重新解压，保证与压缩包内容一致
+        let archive = PathBuf::from(archive);
+        println!("cargo:rerun-if-changed={}", archive.display());
+        if let Err(e) = extract_local_archive(&archive, &pdfium_dir, expected_sha256.as_deref()) {
+            panic!("Failed to use PDFIUM_ARCHIVE_PATH: {}", e);
+        }
+        lib_dir
+    } else {
+        // 检查是否已经下载
+        if !lib_dir.join(lib_name).exists() {
+            if offline {
+                panic!("PDFIUM_OFFLINE is set but neither PDFIUM_ARCHIVE_PATH nor PDFIUM_PREBUILT_DIR is provided");
+            }
+            println!("cargo:warning=Downloading PDFium library...");
+            if let Err(e) = download_pdfium(&pdfium_dir, expected_sha256.as_deref()) {
+                if expected_sha256.is_some() {
+                    panic!("Failed to download PDFium: {}", e);
+                }
+                println!("cargo:warning=Failed to download PDFium: {}", e);
+                println!("cargo:warning=You may need to manually install PDFium or set PDFIUM_ARCHIVE_PATH / PDFIUM_PREBUILT_DIR");
+                return;
+            }
+            println!("cargo:warning=PDFium downloaded successfully!");
+        }
+        lib_dir
+    };
     
     // 设置库搜索路径
     if lib_dir.exists() {
@@ -50,10 +88,6 @@ fn main() {
         // 设置环境变量供 pdfium-render 使用
         println!("cargo:rustc-env=PDFIUM_DYNAMIC_LIB_PATH={}", lib_dir.display());
     }
-    
-    // 重新运行条件
-    println!("cargo:rerun-if-env-changed=PDFIUM_DYNAMIC_LIB_PATH");
-    println!("cargo:rerun-if-changed=build.rs");
 }
 
 fn get_platform_name() -> &'static str {
@@ -93,7 +127,7 @@ fn get_pdfium_lib_name() -> &'static str {
     return "pdfium.dll";
 }
 
-fn download_pdfium(pdfium_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn download_pdfium(pdfium_dir: &PathBuf, expected_sha256: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     let platform = get_platform_name();
     // bblanchon 格式: pdfium-linux-x64.tgz
     let url = format!(
@@ -118,9 +152,60 @@ fn download_pdfium(pdfium_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error
     }
     
     let bytes = response.bytes()?;
-    
+    verify_sha256(&bytes, expected_sha256)?;
+    unpack_archive(&bytes, pdfium_dir)
+}
+
+/// 解压本地压缩包（先校验）
+fn extract_local_archive(
+    archive: &PathBuf,
+    pdfium_dir: &PathBuf,
+    expected_sha256: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:warning=Using local PDFium archive: {}", archive.display());
+    let bytes = fs::read(archive).map_err(|e| format!("{}: {}", archive.display(), e))?;
+    verify_sha256(&bytes, expected_sha256)?;
+
+    if pdfium_dir.exists() {
+        fs::remove_dir_all(pdfium_dir)?;
+    }
+    fs::create_dir_all(pdfium_dir)?;
+    unpack_archive(&bytes, pdfium_dir)
+}
+
+/// 在预解压目录中查找库文件，返回其所在目录
+fn use_prebuilt_dir(
+    dir: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let lib_name = get_pdfium_lib_name();
+    let lib_dir = [dir.join("lib"), dir.to_path_buf()]
+        .into_iter()
+        .find(|d| d.join(lib_name).exists())
+        .ok_or_else(|| format!("{} not found in {} or its lib/ directory", lib_name, dir.display()))?;
+
+    let lib_path = lib_dir.join(lib_name);
+    println!("cargo:rerun-if-changed={}", lib_path.display());
+    verify_sha256(&fs::read(&lib_path)?, expected_sha256)?;
+    println!("cargo:warning=Using prebuilt PDFium library: {}", lib_path.display());
+    Ok(lib_dir)
+}
+
+/// 校验 SHA-256（未指定时跳过）
+fn verify_sha256(data: &[u8], expected: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let actual: String = sha256(data).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err(format!("SHA-256 mismatch: expected {}, got {}", expected, actual).into());
+    }
+    Ok(())
+}
+
+fn unpack_archive(bytes: &[u8], pdfium_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     // 解压 tgz
-    let decoder = flate2::read::GzDecoder::new(bytes.as_ref());
+    let decoder = flate2::read::GzDecoder::new(bytes);
     let mut archive = tar::Archive::new(decoder);
     archive.unpack(pdfium_dir)?;
     
@@ -138,7 +223,70 @@ fn download_pdfium(pdfium_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error
     println!("cargo:warning=PDFium directory contents:");
     list_dir_recursive(pdfium_dir, 0)?;
     
-    Err("Could not find PDFium library in downloaded archive".into())
+    Err("Could not find PDFium library in archive".into())
+}
+
+/// SHA-256（FIPS 180-4），构建脚本不为此引入额外依赖
+fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in chunk.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
 }
 
 fn list_dir_recursive(dir: &PathBuf, depth: usize) -> io::Result<()> {
@@ -11,7 +11,12 @@ export interface PageResult {
   width: number
   /** 图像高度 */
   height: number
-  /** 编码后的图像数据 */
+  /**
+   * 编码后的图像数据
+   *
+   * 直接引用原生层分配的内存（外部 Buffer，不复制），归 JS 所有，GC 回收时释放；
+   * 可以长期持有或修改，不会被后续渲染覆盖。缓存命中时是缓存数据的副本。
+   */
   buffer: Buffer
   /** 是否成功 */
   success: boolean
@@ -21,6 +26,266 @@ export interface PageResult {
   renderTime: number
   /** 编码耗时（毫秒） */
   encodeTime: number
+  /**
+   * 是否跳过（未尝试渲染，区别于渲染失败）：超出总时间预算（SKIPPED）、
+   * 页码大于文档页数（OUT_OF_RANGE）或渲染被取消（CANCELLED）
+   */
+  skipped: boolean
+  /** 实际输出格式（webp/png/jpg/tiff），oversizeStrategy 为 fallbackPng 时超限页面为 png */
+  format: string
+  /** 分块结果（oversizeStrategy 为 tile 且页面超出尺寸上限时，此时 buffer 为空） */
+  tiles?: PageTiles
+  /** 实际生效的渲染参数（渲染失败时为空），用于排查“为什么这页很模糊” */
+  renderInfo?: RenderInfo
+  /**
+   * 失败类别（成功时为空），便于按类别制定重试策略：
+   * INVALID_PAGE、LOAD_FAILED、RENDER_FAILED、ENCODE_FAILED、LIMIT_EXCEEDED、
+   * INVALID_OPTIONS、TIMEOUT、RENDER_TIMEOUT、CANCELLED、SKIPPED、OUT_OF_RANGE、PDFIUM_UNAVAILABLE
+   */
+  errorCode?: string
+  /** 由同一张位图额外编码的其他格式（options.alsoEncode），未指定或渲染失败时为空 */
+  alternates?: Array<AlternateEncoding>
+  /** 是否直接取自渲染结果缓存（见 configureRenderCache），此时 renderTime 和 encodeTime 为 0 */
+  cached: boolean
+  /** 是否原样返回了扫描页面内嵌的图片（options.scanPassthrough），此时没有渲染和编码 */
+  passthrough: boolean
+  /**
+   * 是否识别为扫描件（决定是否使用 imageHeavyWidth 和降噪），未做识别（detectScan 关闭且没有 denoise）
+   * 或渲染失败时为空；阈值见 scanMinImageCoverage / scanMaxTextObjects
+   */
+  detectedScan?: boolean
+  /** 页面上的文字对象数（扫描件识别的依据之一），为空的情况同 detectedScan */
+  textObjectCount?: number
+  /**
+   * 图片覆盖页面面积的比例（0-1，各图片外接矩形面积之和，超出 1 按 1 计），
+   * 为空的情况同 detectedScan
+   */
+  imageCoverage?: number
+  /** 是否因 maxPixelsPerPage / maxTotalBytes 缩小了渲染尺寸 */
+  downscaledDueToMemory: boolean
+  /** 输出图像的亮度统计（options.imageStats），未开启或渲染失败时为空 */
+  imageStats?: ImageStats
+  /**
+   * 编码前最终位图的内容哈希（options.contentHash，XXH64 的 16 位十六进制），
+   * 未开启、渲染失败或原样返回（passthrough）时为空
+   */
+  contentHash?: string
+  /** 各阶段的起止时间（options.spanTimings），取自缓存或因超出时间预算跳过时为空 */
+  spans?: PageSpans
+  /**
+   * 输出尺寸是否因输出格式的单边像素上限（WebP 16383，其他格式 32767）被缩小，
+   * 此时图像分辨率低于请求的分辨率
+   */
+  clamped: boolean
+  /** 被缩小前请求的输出尺寸（clamped 为 true 时） */
+  requestedSize?: RequestedSize
+}
+/** 按选项计算出的输出尺寸（像素） */
+export interface RequestedSize {
+  width: number
+  height: number
+}
+/**
+ * 单页各阶段的起止时间（Unix 毫秒，含小数部分）
+ *
+ * 由单调时钟换算，同一进程内不会回退，可直接作为调用方追踪系统中子 span 的
+ * 开始和结束时间。没有到达的阶段为空（如渲染失败时没有编码阶段）。
+ */
+export interface PageSpans {
+  /** 加载页面（取页面、扫描件识别、计算尺寸） */
+  loadStart: number
+  loadEnd: number
+  /** PDFium 光栅化 */
+  renderStart?: number
+  renderEnd?: number
+  /** 滤镜处理和编码（原始位图结果只有整理像素数据） */
+  encodeStart?: number
+  encodeEnd?: number
+}
+/**
+ * 单次调用的资源占用（options.resourceUsage 时）
+ *
+ * CPU 时间包含渲染线程和编码线程；内存只统计 Rust 侧的堆分配（像素缓冲、编码输出等），
+ * PDFium 内部的分配不计入，峰值是近似值。非 Linux 平台 CPU 时间为 0。
+ * 内存统计需要以 `alloc-usage` 特性构建原生模块（替换全局分配器），默认构建不返回。
+ */
+export interface ResourceUsage {
+  /** 用户态 CPU 时间（毫秒） */
+  cpuUserMs: number
+  /** 内核态 CPU 时间（毫秒） */
+  cpuSystemMs: number
+  /** 调用期间已分配未释放内存的峰值（字节） */
+  peakAllocBytes?: number
+  /** 调用期间累计分配的字节数 */
+  totalAllocBytes?: number
+}
+/** 输出图像的亮度统计，用于自动标记过暗或对比度过低的扫描件 */
+export interface ImageStats {
+  /** 256 级亮度直方图（每级的像素数） */
+  histogram: Array<number>
+  /** 平均亮度（0-255） */
+  mean: number
+  /** 亮度标准差（对比度） */
+  stdDev: number
+  /** 直方图的香农熵（0-8 位），空白或纯色页面接近 0 */
+  entropy: number
+  /** 1% 分位亮度 */
+  low: number
+  /** 99% 分位亮度；与 low 的差即有效动态范围 */
+  high: number
+}
+/** 同一页面的附加编码结果 */
+export interface AlternateEncoding {
+  /** 输出格式（webp/png/jpg/tiff） */
+  format: string
+  /** 编码后的图像数据（编码失败时为空） */
+  buffer: Buffer
+  /** 编码耗时（毫秒） */
+  encodeTime: number
+  /** 错误信息（如果编码失败） */
+  error?: string
+}
+/** 单页实际生效的渲染和编码参数 */
+export interface RenderInfo {
+  /** 实际缩放比例（1.0 = 72 DPI） */
+  scale: number
+  /** 决定尺寸的选项：targetWidth、dpi、imageHeavyWidth、imageNative（preferSharpness）、pageWidths、pageDpi、canvas、region（renderPageRegion）或 passthrough（scanPassthrough） */
+  sizeSource: string
+  /** 是否识别为扫描件 */
+  isScan: boolean
+  /** 缩放比例是否被 maxScale 限制 */
+  maxScaleApplied: boolean
+  /** 扫描件降级宽度是否被 maxScale 限制（小尺寸扫描页面），可用 preferSharpness 改按图片原始分辨率渲染 */
+  scaleClamped: boolean
+  /** 是否因输出格式的尺寸上限被缩小 */
+  dimensionClamped: boolean
+  /** 渲染后是否又做了一次缩放（PDFium 输出超出上限时） */
+  resized: boolean
+  /** 实际使用的编码器后端 */
+  encoderBackend: string
+  /** 编码质量（WebP/JPEG 为 quality，PNG 为压缩级别；无损编码时为空） */
+  quality?: number
+  /** WebP 编码方法（仅 WebP，webpMethodAuto 时为按页面尺寸调整后的值） */
+  webpMethod?: number
+  /** 输出图像的有效分辨率（DPI，包含渲染后的缩放） */
+  dpi?: number
+  /**
+   * PDF 坐标（点）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`：
+   * `px = a·x + c·y + e`，`py = b·x + d·y + f`
+   *
+   * 已包含页面旋转、裁剪框偏移、渲染后缩放和画布偏移，按点提取的文字框、
+   * 链接、表单域可直接换算到图像上。
+   */
+  transform?: Array<number>
+  /** 是否按纯文字页面做了灰度编码（smartEncoding） */
+  textOptimized: boolean
+  /** 是否因进程内存超出水位而降级渲染（缩小宽度、使用最快的编码参数） */
+  memoryDegraded: boolean
+  /** 编码超出 encodeBudgetMs，改用了最快的编码参数（webpMethod 0、quality 不超过 75） */
+  encodeBudgetExceeded: boolean
+  /** 经 ICC 变换转换到 sRGB 的图片数（forceSrgb） */
+  colorConvertedImages: number
+  /** 输出是否保留了透明区域（preserveAlpha 且页面实际有透明像素） */
+  hasAlpha: boolean
+  /** 可能没有画出来的页面对象（options.objectDiagnostics），未开启时为空 */
+  objectErrors?: Array<ObjectError>
+}
+/**
+ * 可能绘制失败的页面对象
+ *
+ * PDFium 跳过无法绘制的对象时不报错，只在页面上留下空白；
+ * 区域标出了空白可能出现的位置。
+ */
+export interface ObjectError {
+  /** 页面顶层对象的下标（嵌套在表单 XObject 中的对象报告其所在的顶层对象） */
+  objectIndex: number
+  /** 对象类型：image（图片无法解码）或 unsupported（PDFium 不支持的对象类型） */
+  objectType: string
+  /** 原因（英文） */
+  reason: string
+  /** 对象在输出图像中的区域（像素），对象没有外接矩形时为空 */
+  region?: ObjectRegion
+}
+/** 页面对象在输出图像中的外接矩形（像素） */
+export interface ObjectRegion {
+  x: number
+  y: number
+  width: number
+  height: number
+}
+/** 超大页面的分块网格 */
+export interface PageTiles {
+  /** 分块边长（像素），最后一行/列的分块可能更小 */
+  tileSize: number
+  /** 列数 */
+  columns: number
+  /** 行数 */
+  rows: number
+  /** 分块列表（行优先） */
+  tiles: Array<PageTile>
+}
+/** 单个分块 */
+export interface PageTile {
+  /** 行号（从 0 开始） */
+  row: number
+  /** 列号（从 0 开始） */
+  column: number
+  /** 分块在整页图像中的横坐标 */
+  x: number
+  /** 分块在整页图像中的纵坐标 */
+  y: number
+  /** 分块宽度 */
+  width: number
+  /** 分块高度 */
+  height: number
+  /** 编码后的图像数据 */
+  buffer: Buffer
+}
+/** 深度缩放分块请求 */
+export interface ZoomTileRequest {
+  /** 缩放级别：0 时整页缩放到一个分块以内，每级放大一倍 */
+  zoom: number
+  /** 分块边长（像素，64-4096），默认 256 */
+  tileSize?: number
+  /** 只渲染指定列的分块（从 0 开始，与 y 同时给出） */
+  x?: number
+  /** 只渲染指定行的分块（从 0 开始，与 x 同时给出） */
+  y?: number
+}
+/** 深度缩放级别的分块渲染结果 */
+export interface ZoomTiles {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 缩放级别 */
+  zoom: number
+  /** 最高缩放级别（缩放比例首次达到 maxScale 的级别） */
+  maxZoom: number
+  /** 该级别的缩放比例（像素/点） */
+  scale: number
+  /** 该级别下整页图像的宽度（像素） */
+  width: number
+  /** 该级别下整页图像的高度（像素） */
+  height: number
+  /** 分块边长（像素） */
+  tileSize: number
+  /** 该级别的分块列数 */
+  columns: number
+  /** 该级别的分块行数 */
+  rows: number
+  /** 分块的图像格式 */
+  format: string
+  /** 渲染的分块（行优先，指定 x/y 时只有一块） */
+  tiles: Array<PageTile>
+  /** 渲染耗时（毫秒） */
+  renderTime: number
+  /** 编码耗时（毫秒） */
+  encodeTime: number
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 错误码 */
+  errorCode?: string
 }
 /** 原始位图结果（不编码） */
 export interface RawBitmapResult {
@@ -32,12 +297,108 @@ export interface RawBitmapResult {
   width: number
   /** 图像高度 */
   height: number
-  /** 通道数（固定为 4，RGBA） */
+  /** 通道数（固定为 4，RGBA 或 BGRA） */
   channels: number
-  /** 原始 RGBA 像素数据 */
+  /**
+   * 每行字节数
+   *
+   * 输出始终是紧密排列的（无行尾填充），即 `stride == width * channels`。
+   * 将 buffer 交给 Sharp 等其他原生库时可直接使用此值。
+   */
+  stride: number
+  /** 像素通道顺序：rgba 或 bgra */
+  pixelFormat: string
+  /**
+   * 原始像素数据（通道顺序见 pixel_format）
+   *
+   * 与 PageResult.buffer 相同，直接引用原生层分配的内存，归 JS 所有；大尺寸位图
+   * （如 4000×3000 RGBA 约 48 MB）不会在 Node 堆中再复制一份。
+   */
   buffer: Buffer
   /** 渲染耗时（毫秒） */
   renderTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+  /** 实际生效的渲染参数（encoderBackend 为 none，编码由调用方完成） */
+  renderInfo?: RenderInfo
+  /** 失败类别（成功时为空），取值与 PageResult.errorCode 相同 */
+  errorCode?: string
+  /** 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空） */
+  passwordIndex?: number
+  /** 输出位图的亮度统计（options.imageStats 时） */
+  imageStats?: ImageStats
+  /** 输出位图的内容哈希（options.contentHash 时），含义同 PageResult.contentHash */
+  contentHash?: string
+  /** 各阶段的起止时间（options.spanTimings 时） */
+  spans?: PageSpans
+  /** 本次调用的 CPU 时间和内存分配（options.resourceUsage 时） */
+  resourceUsage?: ResourceUsage
+  /** 扫描件识别结果，含义同 PageResult.detectedScan */
+  detectedScan?: boolean
+  /** 页面上的文字对象数，含义同 PageResult.textObjectCount */
+  textObjectCount?: number
+  /** 图片覆盖页面面积的比例，含义同 PageResult.imageCoverage */
+  imageCoverage?: number
+  /** 是否因 maxPixelsPerPage / maxTotalBytes 缩小了渲染尺寸 */
+  downscaledDueToMemory: boolean
+}
+/** renderPageToRawChunks 交给回调的一段位图 */
+export interface RawBitmapChunk {
+  /** 分块任务 ID，处理完后调用 completeRawChunk(taskId) 确认 */
+  taskId: number
+  /** 分块序号（从 0 开始） */
+  index: number
+  /** 本段第一行在整页中的行号 */
+  y: number
+  /** 本段行数 */
+  rows: number
+  /** 整页宽度 */
+  width: number
+  /** 整页高度 */
+  height: number
+  /** 每行字节数（紧密排列，等于 width * 4） */
+  stride: number
+  /** 像素通道顺序：rgba 或 bgra */
+  pixelFormat: string
+  /** 本段像素数据 */
+  buffer: Buffer
+  /** 是否为最后一段 */
+  last: boolean
+}
+/** 原始位图分块渲染结果（像素已通过回调交出，这里只有元信息） */
+export interface RawChunkResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 失败类别（成功时为空），取值与 PageResult.errorCode 相同 */
+  errorCode?: string
+  /** 整页宽度 */
+  width: number
+  /** 整页高度 */
+  height: number
+  /** 通道数（固定为 4） */
+  channels: number
+  /** 每行字节数 */
+  stride: number
+  /** 像素通道顺序：rgba 或 bgra */
+  pixelFormat: string
+  /** 已交出的分块数 */
+  chunks: number
+  /** 渲染耗时（毫秒，包含等待回调确认的时间） */
+  renderTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+  /** 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空） */
+  passwordIndex?: number
 }
 /** 批量渲染结果 */
 export interface RenderResult {
@@ -51,77 +412,2189 @@ export interface RenderResult {
   pages: Array<PageResult>
   /** 总耗时（毫秒） */
   totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+  /** 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空） */
+  passwordIndex?: number
+  /** 渲染是否被取消（options.cancelToken），pages 中只有取消前完成的页面带有图像 */
+  cancelled: boolean
+  /** 本次调用的 CPU 时间和内存分配（options.resourceUsage 时） */
+  resourceUsage?: ResourceUsage
 }
-/** 渲染配置选项 */
-export interface RenderOptions {
-  /** 目标渲染宽度（默认 1280） */
-  targetWidth?: number
-  /** 扫描件/图片页面的降级宽度（默认 1024） */
-  imageHeavyWidth?: number
-  /** 最大缩放比例（默认 4.0） */
-  maxScale?: number
-  /** 图片质量（1-100，用于 webp/jpg，已废弃，请使用 webp_quality/jpeg_quality） */
-  quality?: number
-  /** 是否启用扫描件检测（默认 true） */
-  detectScan?: boolean
-  /** 输出格式：webp, png, jpg（默认 webp） */
+/** 页面上的矩形区域（点，原点在页面左上角，已考虑页面旋转） */
+export interface PageRegion {
+  /** 左上角 X 坐标（点） */
+  x: number
+  /** 左上角 Y 坐标（点） */
+  y: number
+  /** 区域宽度（点） */
+  width: number
+  /** 区域高度（点） */
+  height: number
+  /** 缩放比例（像素/点），省略时使用 options.maxScale（默认 4） */
+  scale?: number
+}
+/** 版面区域 */
+export interface LayoutRegion {
+  /** 区域类型：text（文本块）或 image（图片） */
+  kind: string
+  /** 左上角 X 坐标（渲染图像像素） */
+  x: number
+  /** 左上角 Y 坐标（渲染图像像素） */
+  y: number
+  /** 区域宽度（像素） */
+  width: number
+  /** 区域高度（像素） */
+  height: number
+}
+/** 单页版面分析结果 */
+export interface PageLayout {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 渲染图像宽度（与相同选项下 renderPages 的输出一致） */
+  width: number
+  /** 渲染图像高度 */
+  height: number
+  /** 缩放比例（像素/点），可用于换算回 PDF 坐标 */
+  scale: number
+  /** 版面区域，按阅读顺序排列 */
+  regions: Array<LayoutRegion>
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 结构树中的图形元素 */
+export interface PageFigure {
+  /** 结构类型：Figure、Formula，或其他带替代文字的元素类型 */
+  kind: string
+  /** 替代文字（/Alt） */
+  altText?: string
+  /** 实际文字（/ActualText） */
+  actualText?: string
+  /** 标题（/T） */
+  title?: string
+  /** 关联题注（Caption）的文字 */
+  caption?: string
+  /** 图形在渲染图像上的区域（找不到对应的页面对象时为空） */
+  region?: LayoutRegion
+}
+/** 单页图形与替代文字 */
+export interface PageFigures {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面是否有结构树（未打标签的页面没有图形信息） */
+  tagged: boolean
+  /** 渲染图像宽度（与相同选项下 renderPages 的输出一致） */
+  width: number
+  /** 渲染图像高度 */
+  height: number
+  /** 图形元素，按结构树顺序排列 */
+  figures: Array<PageFigure>
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 页面上的图片对象 */
+export interface PageImage {
+  /** 在页面顶层对象中的序号（从 0 开始） */
+  index: number
+  /** 图片本身的像素宽度 */
+  pixelWidth: number
+  /** 图片本身的像素高度 */
+  pixelHeight: number
+  /** 外接矩形左下角 X 坐标（PDF 坐标，单位点） */
+  x: number
+  /** 外接矩形左下角 Y 坐标（PDF 坐标，原点在页面左下角） */
+  y: number
+  /** 外接矩形宽度（点） */
+  width: number
+  /** 外接矩形高度（点） */
+  height: number
+  /** 图片水平方向的放置分辨率（DPI） */
+  dpiX: number
+  /** 图片垂直方向的放置分辨率（DPI） */
+  dpiY: number
+  /** 有效分辨率：两个方向中较低的一个 */
+  effectiveDpi: number
+  /** 外接矩形占页面面积的比例（0-1） */
+  coverage: number
+  /** 每像素位数（无法读取时为 0） */
+  bitsPerPixel: number
+}
+/** 单页图片及其分辨率 */
+export interface PageImages {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面顶层的图片对象（表单 XObject 内部的图片不列出） */
+  images: Array<PageImage>
+  /** 覆盖面积最大的图片的有效分辨率（扫描页面即扫描分辨率），没有图片时为空 */
+  dominantDpi?: number
+  /** 所有图片中最低的有效分辨率，没有图片时为空 */
+  minDpi?: number
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 单页文字统计 */
+export interface PageTextStats {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 是否有可提取的文字（没有时通常需要 OCR） */
+  hasText: boolean
+  /** 字符数（不含空白） */
+  charCount: number
+  /** 词数（中日韩文字按字计） */
+  wordCount: number
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 单页概况（不渲染） */
+export interface PageOverview {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面宽度（点，已考虑页面旋转） */
+  width: number
+  /** 页面高度（点，已考虑页面旋转） */
+  height: number
+  /** 页面旋转角度（0、90、180、270） */
+  rotation: number
+  /** 页面标签（如 "iv"、"A-1"，文档未定义时为空） */
+  label?: string
+  /** 是否有可提取的文字 */
+  hasText: boolean
+  /** 页面顶层是否有图片对象 */
+  hasImages: boolean
+  /** 是否有表单控件以外的注释（noAnnotations 只影响这类注释） */
+  hasAnnotations: boolean
+  /** 是否有表单控件（noFormData 只影响表单控件） */
+  hasFormFields: boolean
+  /** 是否被识别为扫描件（有图片、没有文字对象，与渲染时的扫描件检测一致） */
+  isScan: boolean
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 文档所有页面的概况 */
+export interface DocumentLayout {
+  /** PDF 总页数 */
+  numPages: number
+  /** 每页概况（按页码顺序） */
+  pages: Array<PageOverview>
+  /** 被识别为扫描件的页数 */
+  scannedPages: number
+  /** 扫描件页数占成功分析页数的比例（0-1），用于在 OCR 和文字提取之间做选择 */
+  scanRatio: number
+  /** 文档级判定：至少一半页面是扫描件 */
+  isScanned: boolean
+  /** 总耗时（毫秒） */
+  totalTime: number
+}
+/** 一个词或文字段及其位置 */
+export interface TextRect {
+  /** 文字内容 */
+  text: string
+  /** 左上角横坐标（点，原点在页面左上角，已考虑页面旋转） */
+  x: number
+  /** 左上角纵坐标（点） */
+  y: number
+  /** 宽度（点） */
+  width: number
+  /** 高度（点） */
+  height: number
+  /** 在输出图像中的左上角横坐标（像素，固定画布时包含画布偏移） */
+  pixelX: number
+  /** 在输出图像中的左上角纵坐标（像素） */
+  pixelY: number
+  /** 在输出图像中的宽度（像素） */
+  pixelWidth: number
+  /** 在输出图像中的高度（像素） */
+  pixelHeight: number
+}
+/** 单页文字位置（不渲染） */
+export interface PageTextRects {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 输出图像宽度（与相同选项下 renderPages 的输出一致） */
+  width: number
+  /** 输出图像高度 */
+  height: number
+  /** 缩放比例（像素/点） */
+  scale: number
+  /** PDF 坐标（点，原点在左下角）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`，与 renderInfo.transform 相同 */
+  transform: Array<number>
+  /** 实际使用的粒度："word" 或 "run" */
+  granularity: string
+  /** 词或文字段，按页面文字流的顺序排列 */
+  rects: Array<TextRect>
+}
+/** 页面上的一个注释（链接、批注、表单控件等） */
+export interface PageAnnotation {
+  /** 注释类型，与 annotationTypes 的可选值相同（如 link、highlight、widget），其他类型为 unknown */
+  kind: string
+  /** 在输出图像中的左上角横坐标（像素，与相同选项下 renderPages 的输出一致） */
+  x: number
+  /** 在输出图像中的左上角纵坐标（像素） */
+  y: number
+  /** 宽度（像素） */
+  width: number
+  /** 高度（像素） */
+  height: number
+  /** 链接的外部地址（URI 动作） */
+  uri?: string
+  /** 链接指向的本文档页码（从 1 开始） */
+  destPageNum?: number
+  /** 注释的文字内容（批注正文等） */
+  contents?: string
+}
+/** 单页注释（不渲染） */
+export interface PageAnnotations {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 输出图像宽度（与相同选项下 renderPages 的输出一致） */
+  width: number
+  /** 输出图像高度 */
+  height: number
+  /** 缩放比例（像素/点） */
+  scale: number
+  /** PDF 坐标（点）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`，与 renderInfo.transform 相同 */
+  transform: Array<number>
+  /** 注释，按页面中的顺序排列 */
+  annotations: Array<PageAnnotation>
+}
+/** 表单字段（getFormFields 返回，每个控件一项） */
+export interface FormField {
+  /** 完整字段名（如 `applicant.name`） */
+  name?: string
+  /** 字段类型：pushButton、checkbox、radioButton、comboBox、listBox、text、signature 或 unknown */
+  kind: string
+  /** 当前值：文本框的文字、组合框和列表框选中的选项、复选框和单选按钮组的值 */
+  value?: string
+  /** 复选框和单选按钮是否选中 */
+  checked?: boolean
+  /** 是否只读 */
+  readOnly: boolean
+  /** 是否必填 */
+  required: boolean
+  /** 所在页码（从 1 开始） */
+  pageNum: number
+  /** 控件区域左上角 x（像素，与相同选项下 renderPages 的输出一致） */
+  x: number
+  /** 控件区域左上角 y（像素） */
+  y: number
+  /** 控件区域宽度（像素） */
+  width: number
+  /** 控件区域高度（像素） */
+  height: number
+}
+/** 单页尺寸信息（不渲染） */
+export interface PageInfo {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面宽度（点，已考虑页面旋转） */
+  width: number
+  /** 页面高度（点，已考虑页面旋转） */
+  height: number
+  /** 页面旋转角度（0、90、180、270） */
+  rotation: number
+  /** 页面标签（如 "iv"、"A-1"，文档未定义时为空） */
+  label?: string
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 文档元数据和所有页面的尺寸（不渲染） */
+export interface DocumentInfo {
+  /** PDF 总页数 */
+  numPages: number
+  /** 标题 */
+  title?: string
+  /** 作者 */
+  author?: string
+  /** 主题 */
+  subject?: string
+  /** 关键词 */
+  keywords?: string
+  /** 创建文档的应用程序 */
+  creator?: string
+  /** 生成 PDF 的应用程序 */
+  producer?: string
+  /** 创建日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+  creationDate?: string
+  /** 修改日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+  modificationDate?: string
+  /** PDF 版本（如 "1.7"） */
+  pdfVersion?: string
+  /** 每页尺寸（按页码顺序） */
+  pages: Array<PageInfo>
+  /** 总耗时（毫秒） */
+  totalTime: number
+}
+/** 流式渲染中文档加载完成时的信息（onDocument 回调，早于任何页面完成） */
+export interface StreamDocumentInfo {
+  /** PDF 总页数 */
+  numPages: number
+  /** 标题 */
+  title?: string
+  /** 作者 */
+  author?: string
+  /** 主题 */
+  subject?: string
+  /** 关键词 */
+  keywords?: string
+  /** 创建文档的应用程序 */
+  creator?: string
+  /** 生成 PDF 的应用程序 */
+  producer?: string
+  /** 创建日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+  creationDate?: string
+  /** 修改日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+  modificationDate?: string
+  /** PDF 版本（如 "1.7"） */
+  pdfVersion?: string
+  /** 文件总大小（字节，长度未知时为 fetcher 报告的长度） */
+  fileSize: number
+  /** 调用开始到文档加载完成的耗时（毫秒，含排队时间） */
+  loadTime: number
+}
+/** 文档附件（嵌入文件）信息 */
+export interface AttachmentInfo {
+  /** 序号（从 0 开始，传给 getAttachment） */
+  index: number
+  /** 文件名 */
+  name: string
+  /** 解压后的字节数 */
+  size: number
+  /** MIME 类型（如 "text/xml"，文档未指定时为空） */
+  mimeType?: string
+  /** 创建日期（ISO 8601，无法识别的格式原样返回） */
+  creationDate?: string
+  /** 修改日期 */
+  modificationDate?: string
+}
+/** 书签条目及其目标位置 */
+export interface OutlineAnchor {
+  /** 书签标题 */
+  title: string
+  /** 层级（顶层为 0） */
+  level: number
+  /** 目标页码（从 1 开始，书签不指向本文档页面时为空） */
+  pageNum?: number
+  /** 目标位置在渲染图像中的横坐标（像素，未指定时为空） */
+  x?: number
+  /** 目标位置在渲染图像中的纵坐标（像素，从图像顶部算起，未指定时为空） */
+  y?: number
+  /** 目标缩放比例（未指定时为空） */
+  zoom?: number
+  /** 目标页渲染图像宽度（与相同选项下 renderPages 的输出一致） */
+  width: number
+  /** 目标页渲染图像高度 */
+  height: number
+}
+/** 书签树中的一个条目 */
+export interface OutlineItem {
+  /** 书签标题 */
+  title: string
+  /** 目标页码（从 1 开始，书签不指向本文档页面时为空） */
+  pageNum?: number
+  /** 子书签 */
+  children: Array<OutlineItem>
+}
+/** 跨页（对开页）渲染结果 */
+export interface SpreadResult {
+  /** 包含的页码（从左到右，封面或末尾单页时只有一页） */
+  pages: Array<number>
+  /** 图像宽度 */
+  width: number
+  /** 图像高度 */
+  height: number
+  /** 编码后的图像数据 */
+  buffer: Buffer
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 渲染耗时（毫秒） */
+  renderTime: number
+  /** 编码耗时（毫秒） */
+  encodeTime: number
+}
+/** 跨页批量渲染结果 */
+export interface SpreadRenderResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 每个跨页的渲染结果 */
+  spreads: Array<SpreadResult>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 拼图中单页所在的区域 */
+export interface SpriteCell {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面图像在拼图中的横坐标（已在单元格内居中） */
+  x: number
+  /** 页面图像在拼图中的纵坐标 */
+  y: number
+  /** 页面图像宽度（失败时为 0） */
+  width: number
+  /** 页面图像高度 */
+  height: number
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 缩略图拼图结果 */
+export interface ThumbnailStripResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 编码后的拼图 */
+  buffer: Buffer
+  /** 拼图宽度 */
+  width: number
+  /** 拼图高度 */
+  height: number
+  /** 列数 */
+  columns: number
+  /** 行数 */
+  rows: number
+  /** 单元格边长（像素） */
+  cellSize: number
+  /** 每页所在的区域，顺序与请求的页码一致 */
+  cells: Array<SpriteCell>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 页面内嵌缩略图 */
+export interface EmbeddedThumbnail {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 页面是否带有内嵌缩略图 */
+  found: boolean
+  /** 缩略图宽度 */
+  width: number
+  /** 缩略图高度 */
+  height: number
+  /** 缩略图数据（没有缩略图或失败时为空） */
+  buffer: Buffer
+  /** 数据格式：重新编码时为 webp/png/jpg/tiff，原样返回时为 jpg 或 jp2 */
   format?: string
-  /** WebP 编码质量（0-100，默认 80） */
-  webpQuality?: number
-  /** WebP 编码方法/速度（0-6，0最快，6最慢，默认 4） */
-  webpMethod?: number
-  /** JPEG 编码质量（0-100，默认 85） */
-  jpegQuality?: number
-  /** PNG 压缩级别（0-9，默认 6） */
-  pngCompression?: number
+  /** 是否为 PDF 中的原始数据（未解码重新编码） */
+  passthrough: boolean
+  /** 页面没有内嵌缩略图，按 thumbnailFallbackWidth 渲染得到（此时 found 为 false） */
+  rendered: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 内嵌缩略图提取结果 */
+export interface ThumbnailResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 每页的缩略图 */
+  thumbnails: Array<EmbeddedThumbnail>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
 }
+/** 合成渲染的一个来源文档 */
+export interface ComposeSource {
+  /** PDF 文件路径或 Buffer */
+  input: string | Buffer
+  /** 要取用的页码（从 1 开始，按给出的顺序），为空时取全部页面 */
+  pages?: Array<number>
+}
+/** 合成文档中一页的来源 */
+export interface ComposedPageSource {
+  /** 来源在 sources 中的下标 */
+  sourceIndex: number
+  /** 来源文档中的页码（从 1 开始） */
+  pageNum: number
+}
+/** 合成渲染结果 */
+export interface ComposedRenderResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** 合成文档的总页数 */
+  numPages: number
+  /** 各页渲染结果，pageNum 为合成文档中的页码 */
+  pages: Array<PageResult>
+  /** 合成文档每页的来源，第 i 项对应 pageNum 为 i + 1 的页面 */
+  pageSources: Array<ComposedPageSource>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 导出为单页 PDF 的页面 */
+export interface ExtractedPage {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 只包含该页的 PDF 数据（失败时为空） */
+  buffer: Buffer
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 错误码（如果失败）：INVALID_PAGE、OUT_OF_RANGE、RENDER_FAILED */
+  errorCode?: string
+}
+/** 单页 PDF 导出结果 */
+export interface ExtractPagesResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 每页导出的 PDF */
+  pages: Array<ExtractedPage>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 选中页面导出为新 PDF 的结果 */
+export interface ExtractDocumentResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 原 PDF 总页数 */
+  numPages: number
+  /** 导出的页码（从 1 开始），与新 PDF 中的页面顺序一致 */
+  pageNums: Array<number>
+  /** 只包含选中页面的 PDF 数据（失败时为空） */
+  buffer: Buffer
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 两个文档中同一页码的布局比较 */
+export interface PageComparison {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 文档 A 中的页面宽度（点，已考虑页面旋转，页面不存在时为空） */
+  widthA?: number
+  /** 文档 A 中的页面高度 */
+  heightA?: number
+  /** 文档 A 中的页面旋转角度 */
+  rotationA?: number
+  /** 文档 B 中的页面宽度 */
+  widthB?: number
+  /** 文档 B 中的页面高度 */
+  heightB?: number
+  /** 文档 B 中的页面旋转角度 */
+  rotationB?: number
+  /** 页面尺寸不同（容差 0.5 点） */
+  sizeChanged: boolean
+  /** 旋转角度不同 */
+  rotationChanged: boolean
+  /** 文档 A 没有这一页 */
+  missingInA: boolean
+  /** 文档 B 没有这一页 */
+  missingInB: boolean
+  /** 是否有任何差异（页面读取失败也算作有差异） */
+  changed: boolean
+  /** 页面读取失败的原因 */
+  error?: string
+}
+/** 两个文档的布局比较结果 */
+export interface DocumentComparison {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** 文档 A 的总页数 */
+  numPagesA: number
+  /** 文档 B 的总页数 */
+  numPagesB: number
+  /** 页数是否不同 */
+  pageCountChanged: boolean
+  /** 页数相同且每页的尺寸和旋转都一致 */
+  identicalLayout: boolean
+  /** 有差异的页码 */
+  changedPages: Array<number>
+  /** 逐页比较结果（按页码对齐，长度为两个文档页数的较大值） */
+  pages: Array<PageComparison>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 单页的感知哈希 */
+export interface PageHash {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 64 位感知哈希（16 位十六进制字符串），失败时为空 */
+  hash?: string
+  /** 错误信息（如果失败） */
+  error?: string
+}
+/** 一组内容相同或相近的页面 */
+export interface DuplicateCluster {
+  /** 页码（升序） */
+  pages: Array<number>
+  /** 组内两页之间的最大哈希距离（0 表示完全相同） */
+  maxDistance: number
+}
+/** 重复页检测结果 */
+export interface DuplicatePagesResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 每页的哈希 */
+  hashes: Array<PageHash>
+  /** 重复页分组（只包含两页以上的组） */
+  clusters: Array<DuplicateCluster>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/** 渲染配置选项 */
+export interface RenderOptions {
+  /** 目标渲染宽度（默认 1280） */
+  targetWidth?: number
+  /**
+   * 渲染分辨率（DPI，如 150、300），按页面物理尺寸计算渲染尺寸
+   *
+   * 混合 A4、Letter、幻灯片等不同尺寸的文档时，各页的物理分辨率保持一致。
+   * 与 targetWidth 互斥，同时指定时每页以 INVALID_OPTIONS 失败；
+   * 未指定 maxScale 时上限放宽到该 DPI（否则仍受 maxScale 约束，默认 4.0 即 288 DPI）。
+   * 扫描件降级宽度、pageWidths、pageDpi 和 canvas 仍然优先。
+   */
+  dpi?: number
+  /** 扫描件/图片页面的降级宽度（默认 1024） */
+  imageHeavyWidth?: number
+  /** 最大缩放比例（默认 4.0） */
+  maxScale?: number
+  /**
+   * 扫描件降级宽度超出 maxScale 时按内嵌图片的原始分辨率渲染（默认 false）
+   *
+   * 小尺寸扫描页面（如收据）按 imageHeavyWidth 计算的缩放比例常被 maxScale 限制，
+   * 输出比扫描图片本身还模糊。开启后改用图片的原始分辨率（不超过 imageHeavyWidth），
+   * renderInfo.sizeSource 为 imageNative。
+   */
+  preferSharpness?: boolean
+  /** 图片质量（1-100，用于 webp/jpg，已废弃，请使用 webp_quality/jpeg_quality） */
+  quality?: number
+  /** 是否启用扫描件检测（默认 true） */
+  detectScan?: boolean
+  /**
+   * 识别为扫描件所需的最小图片覆盖比例（0-1，默认 0：至少有一张图片即可）
+   *
+   * 设为 0.5 等值可避免带小插图或 logo 的页面被当作扫描件降低宽度。
+   */
+  scanMinImageCoverage?: number
+  /**
+   * 识别为扫描件时允许的最多文字对象数（默认 0）
+   *
+   * 带 OCR 文字层或页眉页码的扫描件有少量文字对象，可以适当放宽。
+   */
+  scanMaxTextObjects?: number
+  /**
+   * 输出格式：webp, png, jpg, tiff, avif（默认 webp）
+   *
+   * avif 使用内置的 ravif 编码（纯 Rust，速度明显慢于 WebP），也可以用 registerEncoder 注册其他编码器
+   */
+  format?: string
+  /** WebP 编码质量（0-100，默认 80） */
+  webpQuality?: number
+  /** WebP 编码方法/速度（0-6，0最快，6最慢，默认 4） */
+  webpMethod?: number
+  /**
+   * 按页面像素数自动调整 WebP 编码方法（默认 false）：缩略图提高 method 以获得更好的压缩，
+   * 超大页面降低 method，单页编码耗时大致恒定；webpMethod 作为常规尺寸页面的基准
+   */
+  webpMethodAuto?: boolean
+  /** JPEG 编码质量（0-100，默认 85） */
+  jpegQuality?: number
+  /**
+   * 输出渐进式 JPEG（默认 false）
+   *
+   * 大尺寸扫描页面在网页中可以先显示模糊的整页，再逐步变清晰。
+   */
+  jpegProgressive?: boolean
+  /**
+   * JPEG 色度抽样："444"（默认，不抽样）或 "420"
+   *
+   * 420 将色度分辨率减半，照片类页面体积约减少 30%，彩色细字边缘会略有发虚。
+   */
+  jpegChromaSubsampling?: string
+  /** AVIF 编码质量（0-100，默认 50） */
+  avifQuality?: number
+  /** AVIF 编码速度（0-10，0 最慢但压缩最好，默认 6） */
+  avifSpeed?: number
+  /** PNG 压缩级别（0-9，默认 6） */
+  pngCompression?: number
+  /**
+   * 位图像素格式：rgba 或 bgra（默认 rgba）
+   *
+   * bgra 保持 PDFium 原生通道顺序，省去每帧的通道转换。
+   * 原始位图接口直接返回 BGRA 数据；WebP 编码通过 libwebp 的 BGRA 导入直接编码。
+   */
+  pixelFormat?: string
+  /**
+   * 调色板颜色数（2-256，默认不量化）
+   *
+   * 设置后渲染结果会被量化为最多 N 种颜色；PNG 输出为调色板（索引色）PNG，
+   * 适合线条图和超小尺寸预览。
+   */
+  colors?: number
+  /**
+   * 二值化（1 位）输出：threshold（固定阈值）或 sauvola（自适应），默认关闭
+   *
+   * 开启后 PNG 输出 1 位灰度 PNG，TIFF 输出 CCITT G4 压缩，适合传真/归档场景。
+   */
+  bilevel?: string
+  /** 固定阈值二值化的阈值（0-255，默认 128） */
+  bilevelThreshold?: number
+  /** Sauvola 二值化的局部窗口大小（像素，默认 25） */
+  sauvolaWindow?: number
+  /** Sauvola 二值化的灵敏度系数 k（默认 0.2） */
+  sauvolaK?: number
+  /**
+   * renderMultipageTiff 的颜色模式：bilevel（CCITT G4）、gray 或 color（LZW）
+   *
+   * 默认：设置了 bilevel 时为 bilevel，否则为 color。
+   */
+  tiffColor?: string
+  /**
+   * 是否启用背景归一化（光照校正，默认 false）
+   *
+   * 在编码/二值化之前消除扫描件的阴影和光照不均，提升可读性和 OCR 准确率。
+   */
+  normalizeBackground?: boolean
+  /** 背景归一化的估计窗口（像素，默认 64，应明显大于文字笔画宽度） */
+  backgroundWindow?: number
+  /**
+   * 扫描件页面的降噪方式：median（中值）或 bilateral（双边），默认关闭
+   *
+   * 只作用于被识别为扫描件的页面，可去除源扫描件中的 JPEG 伪影，显著减小 WebP 体积。
+   */
+  denoise?: string
+  /** 降噪半径（1-3，默认 1） */
+  denoiseRadius?: number
+  /**
+   * PDFium 底层渲染标志（高级选项，未识别的标志会被忽略）
+   *
+   * 可选值：noAnnotations、noFormData、lcdText、noNativeText、grayscale、
+   * limitImageCache、forceHalftone、printMode、noTextSmoothing、noImageSmoothing、
+   * noPathSmoothing、fillsAsStrokes、reverseByteOrder（等价于 pixelFormat: 'rgba'）、
+   * nativeByteOrder（等价于 pixelFormat: 'bgra'）。
+   *
+   * PDFium 默认会缓存已解码的图片以加速重复绘制，长时间运行的 worker 处理大量图片型文档时
+   * 缓存可能累积到数百 MB。limitImageCache 让 PDFium 在绘制完成后尽快释放解码结果，
+   * 以少量重复解码为代价换取稳定的内存占用。
+   */
+  advancedFlags?: Array<string>
+  /**
+   * 只渲染这些类型的注释（如 ['highlight', 'ink']），其余注释隐藏；未识别的类型名会被忽略
+   *
+   * 可选值：text、link、freeText、line、square、circle、polygon、polyline、highlight、
+   * underline、squiggly、strikeOut、stamp、caret、ink、popup、fileAttachment、sound、
+   * movie、widget（表单控件）、screen、printerMark、trapNet、watermark、threeD、
+   * richMedia、redact。advancedFlags 含 noAnnotations 时所有注释都不渲染。
+   */
+  annotationTypes?: Array<string>
+  /**
+   * 表单控件的渲染方式（默认 "render"）
+   *
+   * - "render"：通过 PDFium 表单环境绘制控件和当前填写的值
+   * - "flatten"：渲染前把表单控件和注释按打印效果合并进页面内容（不打印的注释不再显示）
+   * - "omit"：不绘制表单控件，只保留页面内容和其他注释
+   */
+  formMode?: string
+  /**
+   * 渲染注释（默认 true）
+   *
+   * 为 false 时不绘制任何注释，等价于 advancedFlags 中的 noAnnotations，
+   * 适合存档渲染和需要稳定输出的比对工具。
+   */
+  renderAnnotations?: boolean
+  /**
+   * 渲染表单控件（默认 true）
+   *
+   * 为 false 时等价于 formMode: "omit"，优先于 formMode 的其他取值。
+   */
+  renderForms?: boolean
+  /** 关闭文字抗锯齿（默认 false，等价于 advancedFlags 中的 noTextSmoothing） */
+  disableTextAntialiasing?: boolean
+  /** 关闭图片抗锯齿（默认 false，等价于 advancedFlags 中的 noImageSmoothing） */
+  disableImageAntialiasing?: boolean
+  /** 针对 LCD 屏幕优化文字渲染（默认 false，等价于 advancedFlags 中的 lcdText） */
+  optimizeForLcd?: boolean
+  /**
+   * 请求关联 ID（可选）
+   *
+   * 原样回显在渲染结果中，便于把 JS 层和原生层的日志、监控事件串联到同一条链路。
+   */
+  correlationId?: string
+  /** 单次调用最多处理的页数（默认不限制） */
+  maxPages?: number
+  /**
+   * 分析页面时单页最多检查的对象数（默认不限制）
+   *
+   * 扫描件识别和版面分析需要遍历页面对象，恶意构造的页面可能包含数百万个对象。
+   */
+  maxPageObjects?: number
+  /** 输入文档的最大字节数（默认不限制） */
+  maxFileSize?: number
+  /** 单页最多像素数（宽 × 高），超出时按 memoryBudgetAction 处理（默认不限制） */
+  maxPixelsPerPage?: number
+  /** 同时渲染的位图总字节预算，按 RGBA 4 字节/像素 × 并发数估算（默认不限制） */
+  maxTotalBytes?: number
+  /** 超出内存预算时的处理："downscale"（默认，等比缩小到预算内）或 "fail"（该页失败，LIMIT_EXCEEDED） */
+  memoryBudgetAction?: string
+  /**
+   * 单次调用的总时间预算（毫秒，默认不限制）
+   *
+   * 超出后不再渲染剩余页面，这些页面标记为 skipped 并立即返回已完成的结果，
+   * 适合交互式预览：宁可先拿到前几页，也不要等全部页面渲染完。
+   */
+  totalTimeBudgetMs?: number
+  /**
+   * 单页编码时间预算（毫秒），与渲染时间分开计算
+   *
+   * libwebp 有损编码超出预算时放弃，改用 method 0、quality 不超过 75 重新编码
+   * （renderInfo.encodeBudgetExceeded）；其他格式和编码器不能中途放弃，不受影响。
+   */
+  encodeBudgetMs?: number
+  /**
+   * 单页渲染超时（毫秒，默认不限制）
+   *
+   * 损坏的 PDF 可能让 PDFium 在一页上运行几分钟。超出后放弃该页（errorCode 为 RENDER_TIMEOUT），
+   * 继续渲染其余页面。超时在 PDFium 绘制页面对象的间隙检查，单个对象内部（如解码巨大的图片）无法中断。
+   */
+  pageTimeoutMs?: number
+  /**
+   * 流式渲染时允许同时活动的流式任务数上限（仅对 renderPagesFromStream 生效）
+   *
+   * 活动任务数达到该值时本任务排队等待；与 `configureStreamLimits` 的全局上限取较小值。
+   */
+  maxConcurrentStreams?: number
+  /**
+   * 流式渲染的并行请求数（1-8，默认 1，仅对 renderPagesFromStream 生效）
+   *
+   * 大于 1 时，检测到 PDFium 顺序读取后会同时请求后续的若干块，
+   * 适合高延迟的对象存储；随机访问时仍逐块请求。
+   */
+  fetchLanes?: number
+  /**
+   * 流式渲染的合并读取块数（0-32，默认 0，仅对 renderPagesFromStream 生效）
+   *
+   * PDFium 以大量小的顺序读取访问文件，每次缓存未命中都是一次 JS 往返。大于 0 时，
+   * 检测到顺序读取后的缓存未命中会在同一个请求中连同后续的若干块一起获取；
+   * 可与 fetchLanes 同时使用。预取的效果见 streamStats.prefetchHits / prefetchedBlocks。
+   */
+  streamReadahead?: number
+  /**
+   * 流式渲染前先计算所请求页面需要的字节范围，一次批量交给 fetcher（默认 false，仅对 renderPagesFromStream 生效）
+   *
+   * 打开文档后读取 xref 表和页面树，找出页面对象、内容流、资源和注释外观流所在的块，
+   * 以一个带 ranges 的请求发给 fetcher，减少逐块往返。只支持传统 xref 表，
+   * 使用交叉引用流的文件放弃计划（streamStats.fetchPlanError），渲染照常逐块读取。
+   */
+  fetchPlan?: boolean
+  /**
+   * 线性化（Web 优化）文件的快速路径（默认 true，仅对 renderPagesFromStream 生效）
+   *
+   * 所请求的页面包含首页段落中的页面时，打开文档前按文件开头的线性化字典
+   * 并发获取首页段落、提示表和主交叉引用表，首页不必再等 PDFium 逐块往返。
+   * 是否使用见 streamStats.linearizedFastPath。
+   */
+  linearizedFastPath?: boolean
+  /**
+   * 已获取的字节数达到文件大小的该比例时，改为一次获取整个文件（0-1，默认不回退，仅对 renderPagesFromStream 生效）
+   *
+   * 达到条件后的下一次缓存未命中时，以一个带 `full: true` 的请求向 fetcher 要整个文件，
+   * 之后的读取全部从内存返回；获取失败时继续逐块读取（streamStats.fallbackError）。
+   */
+  streamFallbackFraction?: number
+  /**
+   * 已发出的请求数达到该值时改为一次获取整个文件（默认不回退，仅对 renderPagesFromStream 生效）
+   *
+   * 与 streamFallbackFraction 任一满足即回退。
+   */
+  streamFallbackRequests?: number
+  /**
+   * 流式渲染的缓存块大小（字节，16KB-16MB，默认 256KB，仅对 renderPagesFromStream 生效）
+   *
+   * 也是每次向 fetcher 请求的大小。按请求计费或延迟较高的对象存储可以调大以减少请求数，
+   * 代价是每次缓存未命中多读一些数据。
+   */
+  streamBlockSize?: number
+  /**
+   * 流式渲染最多缓存的块数（2-4096，默认 64，仅对 renderPagesFromStream 生效）
+   *
+   * 缓存满后按 LRU 淘汰；streamStats 的 REFETCHED_BLOCKS 建议出现时可以调大。
+   */
+  streamMaxCacheBlocks?: number
+  /** 流式渲染等待 fetcher 返回一个块的超时时间（毫秒，默认 30000，仅对 renderPagesFromStream 生效） */
+  streamFetchTimeoutMs?: number
+  /**
+   * 常驻文档句柄空闲超过该时间（毫秒）后清空流式块缓存（默认不清空，仅对 PdfDocumentHandle 的打开方法生效）
+   *
+   * 调用方忘记 `close()` 时块缓存会一直占用内存。超时后句柄仍然可用，
+   * 之后的渲染按需重新通过 fetcher 获取数据。
+   */
+  documentIdleTimeoutMs?: number
+  /**
+   * 内嵌缩略图是 JPEG/JPEG 2000 时原样返回（默认 false，仅对 getEmbeddedThumbnails 生效）
+   *
+   * 原样返回时不解码也不重新编码，输出格式以结果中的 `format` 为准；
+   * 其他编码方式的缩略图仍按 `format` 选项重新编码。
+   */
+  thumbnailPassthrough?: boolean
+  /**
+   * 没有内嵌缩略图的页面改为按该宽度渲染（像素，默认不回退，仅对 getEmbeddedThumbnails 生效）
+   *
+   * 网格视图可以一次调用填满：有缩略图的页面直接读取，其余页面渲染一张小图，
+   * 结果的 `rendered` 为 true。渲染使用 `format` 等其他选项，只把宽度换成该值。
+   */
+  thumbnailFallbackWidth?: number
+  /**
+   * 两遍渲染：每页先按该宽度渲染一张预览图交给 onPreview，再按正式尺寸渲染（默认 320）
+   *
+   * 只在传入 onPreview 回调时生效，预览与正式渲染使用同一个已加载的文档。
+   * 预览宽度不小于 targetWidth 时不生成预览；命中渲染缓存的页面直接返回最终结果。
+   */
+  previewWidth?: number
+  /**
+   * 扫描页面只有一张铺满页面的 JPEG/JPEG 2000 图片时原样返回图片数据（默认 false）
+   *
+   * 不渲染也不重新编码，输出为图片的原始分辨率，格式以结果中的 `format` 为准，
+   * 结果的 `passthrough` 为 true。页面有注释、旋转，图片经过变换或使用 CMYK 等色彩空间，
+   * 或开启了 canvas、highlights、水印、二值化、调色板、滤镜、alsoEncode、imageStats 等需要像素的选项时照常渲染。
+   * 需要 detectScan（默认开启）。
+   */
+  scanPassthrough?: boolean
+  /**
+   * 按页码指定目标宽度，如 `{ "1": 2560 }`（其余页面仍使用 targetWidth）
+   *
+   * 一次调用中让不同页面使用不同尺寸（例如首页作为大图），只加载一次文档。
+   * 同时受 maxScale 和输出格式的尺寸上限约束。
+   */
+  pageWidths?: Record<string, number>
+  /**
+   * 按页码指定渲染分辨率（DPI，72 为原始尺寸），如 `{ "1": 300 }`
+   *
+   * 同一页同时出现在 pageWidths 中时以 pageWidths 为准。
+   */
+  pageDpi?: Record<string, number>
+  /**
+   * 按页码覆盖扫描件降级宽度，如 `{ "3": "full" }`
+   *
+   * - "full": 即使识别为扫描件也使用 targetWidth（版式接近扫描件、但需要完整宽度的画册页面）
+   * - "scan": 即使未识别为扫描件也使用 imageHeavyWidth
+   * - "auto": 按扫描件检测结果（默认）
+   *
+   * 不受 detectScan 开关影响；pageWidths / pageDpi 指定的页面以它们为准。
+   */
+  pageScanMode?: Record<string, string>
+  /**
+   * 按页码覆盖输出格式，如 `{ "2": "png" }`（其余页面仍使用 format）
+   *
+   * 同一批次中透明或线稿页面输出 PNG、其余页面输出 WebP。渲染流程相同，只有编码器不同；
+   * encoderBackend 不支持该页格式时改用该格式的默认后端。
+   */
+  pageFormats?: Record<string, string>
+  /** 缩略图拼图的列数（默认排成一行，超出尺寸上限后换行，仅对 renderThumbnailStrip 生效） */
+  spriteColumns?: number
+  /**
+   * 跨页渲染时第一页（封面）是否单独成页（默认 true，仅对 renderSpreads 生效）
+   *
+   * 为 true 时按 [1]、[2,3]、[4,5]… 组合，与印刷书籍的左右页一致；
+   * 为 false 时按 [1,2]、[3,4]… 组合。
+   */
+  spreadCover?: boolean
+  /** 文字位置的粒度："word"（默认，按词）或 "run"（PDFium 的文字段），仅对 getPageTextRects 生效 */
+  textGranularity?: string
+  /** 租户标记（仅对 scheduleRender 生效），调度器按租户分别执行并发和 CPU 时间限额 */
+  tenant?: string
+  /**
+   * 固定输出宽度（需同时指定 canvasHeight）
+   *
+   * 页面等比缩放到 canvasWidth × canvasHeight 以内，空白部分用 padColor 填充，
+   * 不同比例的页面得到相同尺寸的图片，适合固定大小的缩略图格子。优先于 targetWidth 等尺寸设置。
+   */
+  canvasWidth?: number
+  /** 固定输出高度（需同时指定 canvasWidth） */
+  canvasHeight?: number
+  /**
+   * 画布填充色：`#rgb`、`#rrggbb`、`#rrggbbaa` 或 `transparent`（默认白色）
+   *
+   * JPEG 不支持透明度，透明填充在 JPEG 中显示为 jpegBackground（默认白色）。
+   */
+  padColor?: string
+  /** 页面在画布中的对齐方式：center（默认）、top、bottom、left、right 或 top-left 等组合 */
+  padAlign?: string
+  /**
+   * 页面尺寸超出 WebP 上限（单边 16383）时的处理方式（默认 "downscale"）
+   *
+   * - "downscale": 等比缩小到上限以内
+   * - "fallbackPng": 保持计算出的尺寸，该页改用 PNG 输出（结果中的 `format` 为 png）
+   * - "tile": 保持计算出的尺寸，切成 tileSize 大小的网格分别编码（结果中的 `tiles`）
+   */
+  oversizeStrategy?: string
+  /** 分块边长（像素，默认 4096，不超过输出格式的尺寸上限） */
+  tileSize?: number
+  /**
+   * 判定为重复页的最大哈希距离（0-64，默认 4，仅对 findDuplicatePages 生效）
+   *
+   * 0 只匹配渲染结果几乎一致的页面；扫描件的重复上传通常在 10 以内。
+   */
+  duplicateThreshold?: number
+  /**
+   * 编码器后端（默认 "auto"，可选值见 getEncoderCapabilities）
+   *
+   * - "libwebp": WebP 有损编码（默认），受 webpQuality / webpMethod 控制
+   * - "image": image crate 的纯 Rust 编码器；WebP 为无损编码
+   * - "ravif": AVIF 编码（avif 的默认后端），受 avifQuality / avifSpeed 控制
+   * - registerEncoder 注册的名称：交给 JS 自定义编码器（仅异步接口）
+   *
+   * 指定的后端未编译进来或不支持当前格式时，该页编码失败并返回错误。
+   */
+  encoderBackend?: string
+  /**
+   * 合成到指定页面上的高亮区域（如评论系统中的用户高亮）
+   *
+   * 以正片叠底方式合成，文字保持清晰。坐标无效的条目会被忽略。
+   */
+  highlights?: Array<HighlightOption>
+  /**
+   * 文字水印，合成到每页渲染结果上（编码之前，缩放和填充画布之前）
+   *
+   * 使用 PDF 标准字体 Helvetica 绘制，只支持拉丁字符；其他文字请使用 stampImage。
+   */
+  stampText?: string
+  /**
+   * 图片水印：紧密排列的 RGBA 像素（非预乘 alpha），需同时指定 stampImageWidth / stampImageHeight
+   *
+   * 与 stampText 同时指定时使用图片。数据长度与宽高不符时每页以 INVALID_OPTIONS 失败。
+   */
+  stampImage?: Buffer
+  /** 图片水印的宽度（像素） */
+  stampImageWidth?: number
+  /** 图片水印的高度（像素） */
+  stampImageHeight?: number
+  /** 文字水印的字号（像素，默认 48，范围 4-1000） */
+  stampFontSize?: number
+  /** 文字水印的颜色：`#rgb`、`#rrggbb` 或 `#rrggbbaa`（默认 #808080） */
+  stampColor?: string
+  /** 水印的不透明度（0-1，默认 0.3），与图片自身的透明度相乘 */
+  stampOpacity?: number
+  /** 水印位置：center（默认）、top、bottom、left、right 或 top-left 等组合，tile 表示铺满整页 */
+  stampPosition?: string
+  /** 水印到图像边缘的距离（像素，默认 24），tile 时为水印之间的间距 */
+  stampMargin?: number
+  /** 不合成水印的区域（如签名、印章所在的位置），坐标无效的条目会被忽略 */
+  stampExclusions?: Array<StampExclusionOption>
+  /**
+   * 输出 JPEG（及 TIFF）时半透明像素混合的背景色：`#rgb` 或 `#rrggbb`，默认白色
+   *
+   * 页面本身通常不透明，主要影响透明的画布填充（padColor: 'transparent'）。
+   */
+  jpegBackground?: string
+  /**
+   * 渲染结果超出尺寸上限的容差（像素，默认 2）
+   *
+   * PDFium 输出的位图偶尔因取整比预先计算的尺寸大 1～2 像素。超出量在容差以内时
+   * 直接裁掉多余的边缘，省去一次 Lanczos 缩放；超出更多时仍然等比缩小。设为 0 总是缩放。
+   */
+  resizeTolerance?: number
+  /**
+   * 页码大于文档页数时的处理方式（默认 "skip"）
+   *
+   * - "skip": 该页标记为跳过（skipped 为 true，errorCode 为 OUT_OF_RANGE），其余页面正常渲染
+   * - "fail": 整个调用失败，不渲染任何页面
+   */
+  outOfRangePages?: string
+  /**
+   * 纯文字页面的编码方式（仅 WebP 输出，默认 "off"）
+   *
+   * 页面只有文字对象、没有图片且渲染结果近似灰度时：
+   * - "grayscale": 转为灰度，用 method 6 有损编码
+   * - "lossless": 转为灰度后无损编码，文字边缘更锐利
+   *
+   * 扫描件、彩色页面及指定了 bilevel/colors 的调用不受影响；
+   * 转换后的页面 renderInfo.textOptimized 为 true。
+   */
+  smartEncoding?: string
+  /**
+   * 文档加密时依次尝试的候选密码
+   *
+   * 先不带密码打开，遇到密码错误再按顺序尝试，结果的 passwordIndex 为成功的下标。
+   * 适合批量处理只用几个已知密码之一加密的归档文档。
+   */
+  passwords?: Array<string>
+  /**
+   * 文档密码（用户输入的单个密码）
+   *
+   * 与 passwords 同时指定时先尝试这个密码（passwordIndex 为 0，passwords 的下标顺延）。
+   * 密码错误或需要密码但没有提供时，错误信息以 `WRONG_PASSWORD:` 开头，
+   * 单页结果的 errorCode 为 WRONG_PASSWORD，调用方可以据此提示用户重新输入。
+   */
+  password?: string
+  /**
+   * 额外输出的格式列表（如 ["jpg"]），默认不输出
+   *
+   * 每页渲染一次，用同一张位图再编码为这些格式，结果放在 PageResult.alternates 中，
+   * 例如主格式为 WebP、同时为旧客户端准备 JPEG，无需整份文档渲染两遍。
+   * 与主格式相同的条目会被忽略；分块输出的超大页面不生成附加编码。
+   */
+  alsoEncode?: Array<string>
+  /**
+   * 渲染前把 CMYK 和 ICCBased 图片经 ICC 变换转换到 sRGB（默认 false）
+   *
+   * PDFium 按近似公式转换 DeviceCMYK，品牌色在印刷素材上容易偏色。开启后 ICCBased 图片
+   * 使用内嵌的配置文件，DeviceCMYK 图片使用 cmykProfile；只处理页面顶层、8 位深度、
+   * 未旋转的图片，矢量图形和文字的颜色不变。结果的 renderInfo.colorConvertedImages 为转换的图片数。
+   */
+  forceSrgb?: boolean
+  /**
+   * DeviceCMYK 图片使用的 CMYK ICC 配置文件（如 FOGRA39、SWOP），仅在 forceSrgb 时生效
+   *
+   * 未提供时 DeviceCMYK 图片仍由 PDFium 转换。
+   */
+  cmykProfile?: Buffer
+  /**
+   * 在结果中返回每页输出图像的亮度统计（直方图、均值、对比度、熵），默认 false
+   *
+   * 统计基于最终输出的位图（包含滤镜和画布填充），分块输出的超大页面同样统计整页。
+   */
+  imageStats?: boolean
+  /**
+   * 在结果中返回每页输出位图的内容哈希 contentHash（默认 false）
+   *
+   * 对编码前的最终位图计算 XXH64，与输出格式和编码参数无关，可以作为 CDN 缓存的键。
+   * 与 deterministic 一起使用时，哈希相同的页面输出的图像也逐字节相同。
+   */
+  contentHash?: boolean
+  /**
+   * 检查页面对象能否正常绘制，结果见 renderInfo.objectErrors（默认 false）
+   *
+   * 图片数据损坏、使用 PDFium 不支持的过滤器或对象类型未知时，PDFium 直接跳过该对象，
+   * 页面上只留下空白。开启后每张图片会额外完整解码一次，用于排查显示不全的问题。
+   */
+  objectDiagnostics?: boolean
+  /**
+   * 保留页面透明区域（默认 false）
+   *
+   * 默认 PDFium 在白色背景上渲染，输出总是不透明。开启后以透明背景渲染，页面实际
+   * 有透明区域时 WebP/PNG 输出保留 alpha 通道（renderInfo.hasAlpha 为 true）；
+   * JPEG/TIFF 仍与 jpegBackground 混合。有透明区域的页面不做 smartEncoding 灰度编码。
+   */
+  preserveAlpha?: boolean
+  /**
+   * 渲染会话（startRenderSession）中最先渲染并交付的页码（从 1 开始）
+   *
+   * 该页在要渲染的页面中时移到最前，其余页面保持原来的顺序，界面可以尽快显示首屏。
+   * 不指定时按 pageNums 的顺序（全部页面时从第 1 页开始）。
+   */
+  heroPage?: number
+  /**
+   * 在结果中返回每页加载、渲染、编码阶段的起止时间（spans，默认 false）
+   *
+   * 时间戳是 Unix 毫秒，调用方可以据此在追踪系统（如 OpenTelemetry）中还原
+   * 跨越原生边界的真实 span，而不只是耗时。
+   */
+  spanTimings?: boolean
+  /**
+   * 在结果中返回本次调用的 CPU 时间和内存分配（resourceUsage，默认 false）
+   *
+   * 多租户服务可以据此按客户文档核算成本。支持 renderPages、renderPagesAsync、
+   * renderPagesFromFile、renderPageRegion、renderPageToRawBitmap、renderPreloadedPages 和文档句柄的 renderPages。
+   */
+  resourceUsage?: boolean
+  /** renderPageToRawChunks 每个分块的行数（默认按约 4 MB 一块计算） */
+  chunkRows?: number
+  /**
+   * 多页渲染时并行编码的线程数（默认 1，最大 16）
+   *
+   * PDFium 不支持多线程，页面位图仍依次渲染；编码（WebP/PNG/JPEG、滤镜、切片）
+   * 交给工作线程并行执行，编码耗时占主要部分的批量渲染可以明显缩短总耗时。
+   * 同时等待编码的位图不超过该数量，内存占用随之增加。
+   *
+   * 流式渲染（renderPagesFromStream、流式打开的文档）即使为 1 也在后台线程编码，
+   * PDFium 线程同时开始下一页并请求它的数据块，网络读取和编码互相重叠。
+   */
+  concurrency?: number
+  /**
+   * 并行编码时 onPage 的回调顺序（默认 "completion"）
+   *
+   * - "completion": 哪页先编码完成先回调哪页，延迟最低
+   * - "input": 按请求的页码顺序回调，先完成的页面等待前面的页面（期间保留其图像数据）
+   *
+   * concurrency 为 1 且不是流式渲染时页面本来就按顺序完成，两者没有区别。
+   */
+  deliveryOrder?: string
+  /**
+   * 取消令牌（createCancelToken 返回的 ID）
+   *
+   * 调用 cancelRender 后，尚未开始的页面和等待编码的页面不再处理，
+   * 以 CANCELLED 错误码返回（skipped 为 true），结果的 cancelled 为 true。
+   */
+  cancelToken?: number
+  /**
+   * 确定性输出（默认 false）：相同的 PDF 和选项总是得到逐字节相同的图像
+   *
+   * 关闭随运行环境变化的自适应行为：内存超出水位时的降级渲染、encodeBudgetMs 超时后改用的快速编码；
+   * 并行编码时 onPage 按页码顺序回调。输出图像本身不含时间戳等元数据。
+   * 未嵌入的字体仍由系统字体替代，不同机器上的字体不同时输出可能不同；自定义编码器的输出不受约束。
+   */
+  deterministic?: boolean
+  /**
+   * 使用 loadProfiles 注册的命名配置
+   *
+   * 本次调用中设置的选项优先，未设置的选项取自该配置；名称未注册时抛出异常。
+   */
+  profile?: string
+}
+/** 高亮区域 */
+export interface HighlightOption {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 左下角 X 坐标（PDF 坐标，单位点，与注释的 /Rect 相同） */
+  x: number
+  /** 左下角 Y 坐标（PDF 坐标，原点在页面左下角） */
+  y: number
+  /** 宽度（点） */
+  width: number
+  /** 高度（点） */
+  height: number
+  /** 颜色：`#rgb`、`#rrggbb` 或 `#rrggbbaa`（alpha 控制强度），默认黄色 */
+  color?: string
+}
+/** 水印排除区域 */
+export interface StampExclusionOption {
+  /** 页码（从 1 开始），不指定时对每一页生效 */
+  pageNum?: number
+  /** 左下角 X 坐标（PDF 坐标，单位点） */
+  x: number
+  /** 左下角 Y 坐标（PDF 坐标，原点在页面左下角） */
+  y: number
+  /** 宽度（点） */
+  width: number
+  /** 高度（点） */
+  height: number
+}
+/**
+ * 从 PDF Buffer 渲染指定页面
+ *
+ * 在调用线程上同步渲染，渲染期间事件循环被阻塞；大批量渲染请使用 `renderPagesAsync`。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串（`"1-5,8,10-"`、`"all"`）、`{ from, to }`
+ * * `options` - 渲染配置选项
+ *
+ * # Returns
+ * 包含所有页面渲染结果的对象
+ */
+export declare function renderPages(pdfBuffer: Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null): RenderResult
+/**
+ * 从 PDF Buffer 渲染指定页面（异步版本）
+ *
+ * 与 `renderPages` 相同，但渲染在后台线程中进行，返回 Promise，
+ * 大批量渲染时不阻塞事件循环。Buffer 数据会先复制一份，调用返回后即可释放或修改。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+ * * `options` - 渲染配置选项
+ * * `on_page` - 每页完成后立即以 `(err, page)` 调用（见 `page_callback`）
+ * * `on_preview` - 两遍渲染：每页先以 `(err, page)` 交付 previewWidth 宽的预览图，再渲染最终结果
+ *
+ * # Returns
+ * Promise<RenderResult>
+ */
+export declare function renderPagesAsync(pdfBuffer: Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null, onPage?: (err: Error | null, page: PageResult) => void, onPreview?: (err: Error | null, page: PageResult) => void): Promise<RenderResult>
+/** 批量渲染中的一个任务 */
+export interface BatchRenderJob {
+  /** PDF 文件路径或 Buffer */
+  source: string | Buffer
+  /** 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`） */
+  pageNums: Array<number> | string | PageRange
+  /** 渲染配置选项（每个任务独立） */
+  options?: RenderOptions
+}
+/** 批量渲染选项 */
+export interface BatchRenderOptions {
+  /** 读取线程数，也是最多提前读入内存的任务数（默认为 CPU 核数，最多 4） */
+  concurrency?: number
+}
+/** 批量渲染的结果 */
+export interface BatchRenderResult {
+  /** 各任务的渲染结果，与 jobs 顺序一致；单个任务失败不影响其他任务 */
+  results: Array<RenderResult>
+  /** 成功的任务数 */
+  succeeded: number
+  /** 失败的任务数（选项无效、读取或加载失败等） */
+  failed: number
+  /** 成功渲染的页数（所有任务之和） */
+  renderedPages: number
+  /** 读取文件耗时之和（毫秒；读取与渲染重叠进行，不计入 totalTime 的关键路径） */
+  loadTime: number
+  /** 各页渲染耗时之和（毫秒） */
+  renderTime: number
+  /** 各页编码耗时之和（毫秒） */
+  encodeTime: number
+  /** 总耗时（毫秒） */
+  totalTime: number
+}
+/**
+ * 在一次调用中渲染多个文档
+ *
+ * 适合一次处理大量小 PDF（如缩略图服务）：所有任务在原生侧处理，PDFium 只初始化一次，
+ * 固定数量的读取线程提前读取文件，省去逐个调用的调度和 JS↔Rust 往返开销。
+ * 结果与 jobs 顺序一致，单个任务的选项无效或渲染失败只体现在该任务的结果中。
+ * Buffer 数据会先复制一份，调用返回后即可释放或修改。
+ */
+export declare function renderBatch(jobs: Array<BatchRenderJob>, options?: BatchRenderOptions | undefined | null): Promise<BatchRenderResult>
+/**
+ * 从文件路径渲染 PDF 页面
+ *
+ * 直接从文件系统读取 PDF，避免在 Node.js 堆中创建大 Buffer。
+ * 这是处理本地大文件的最高效方式。
+ *
+ * 文件读取和渲染都在后台线程中进行，返回 Promise，
+ * 慢速存储（如 NFS 挂载）上的文件不会阻塞事件循环。
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+ * * `options` - 渲染配置选项
+ * * `on_page` - 每页完成后立即以 `(err, page)` 调用（见 `page_callback`）
+ * * `on_preview` - 两遍渲染：每页先以 `(err, page)` 交付 previewWidth 宽的预览图，再渲染最终结果
+ *
+ * # Returns
+ * 包含所有页面渲染结果的对象
+ */
+export declare function renderPagesFromFile(filePath: string, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null, onPage?: (err: Error | null, page: PageResult) => void, onPreview?: (err: Error | null, page: PageResult) => void): Promise<RenderResult>
+/** 写入文件的单页结果 */
+export interface PageFileResult {
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 图像文件路径（渲染或写入失败、分块页面时为空） */
+  path?: string
+  /** 分块文件路径（oversizeStrategy 为 tile 且页面超出尺寸上限时，行优先） */
+  tilePaths?: Array<string>
+  /** 写入的字节数（分块页面为所有分块之和） */
+  size: number
+  /** 图像宽度 */
+  width: number
+  /** 图像高度 */
+  height: number
+  /** 实际输出格式，也是文件扩展名 */
+  format: string
+  /** 是否成功（渲染、编码和写入都成功） */
+  success: boolean
+  /** 错误信息（如果失败） */
+  error?: string
+  /** 失败类别，写入文件失败为 OUTPUT_FAILED，其余同 PageResult.errorCode */
+  errorCode?: string
+  /** 是否跳过（见 PageResult.skipped） */
+  skipped: boolean
+  /** 渲染耗时（毫秒） */
+  renderTime: number
+  /** 编码耗时（毫秒） */
+  encodeTime: number
+  /** 写入文件耗时（毫秒） */
+  writeTime: number
+}
+/** 渲染到文件的结果 */
+export interface FileRenderResult {
+  /** 是否成功 */
+  success: boolean
+  /** 错误信息（如果整体失败） */
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** 每页的写入结果 */
+  pages: Array<PageFileResult>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+  /** 渲染是否被取消（options.cancelToken），取消前完成的页面已写入文件 */
+  cancelled: boolean
+}
+/**
+ * 把 PDF 页面直接渲染到输出目录，不经过 Node Buffer
+ *
+ * 每页编码完成后立即在后台线程写入文件，图像数据不会返回 JS，
+ * 适合结果最终都要落盘的批量转换任务。输出目录不存在时自动创建，同名文件会被覆盖。
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+ * * `output_dir` - 输出目录
+ * * `filename_pattern` - 文件名模板（默认 `page-%d`），`%d` 替换为页码，
+ *   `%0Nd` 补零到 N 位；扩展名按实际输出格式追加
+ * * `options` - 渲染配置选项
+ *
+ * # Returns
+ * 每页的文件路径、大小和耗时
+ */
+export declare function renderPagesToFiles(filePath: string, pageNums: Array<number> | string | PageRange, outputDir: string, filenamePattern?: string | undefined | null, options?: RenderOptions | undefined | null): Promise<FileRenderResult>
+/** 多页 TIFF 中单页的结果 */
+export interface TiffPageResult {
+  pageNum: number
+  /** 是否写入了 TIFF（失败的页面不写入） */
+  success: boolean
+  error?: string
+  errorCode?: string
+  width: number
+  height: number
+  /** 渲染耗时（毫秒） */
+  renderTime: number
+  /** 压缩并写入 TIFF 的耗时（毫秒） */
+  encodeTime: number
+}
+/** 多页 TIFF 渲染结果 */
+export interface MultiPageTiffResult {
+  success: boolean
+  error?: string
+  /** PDF 总页数 */
+  numPages: number
+  /** TIFF 数据（指定了 outputPath 时为空） */
+  buffer?: Buffer
+  /** 写入的文件路径（指定了 outputPath 时） */
+  outputPath?: string
+  /** TIFF 字节数 */
+  size: number
+  /** 每页的结果（按请求顺序，也是 TIFF 中的页面顺序） */
+  pages: Array<TiffPageResult>
+  /** 总耗时（毫秒） */
+  totalTime: number
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
+}
+/**
+ * 把多个页面渲染进同一个多页 TIFF（传真、归档）
+ *
+ * 每页按正常的尺寸选项渲染，`tiffColor` 决定压缩方式：`bilevel` 使用 CCITT G4
+ * （二值化方式沿用 `bilevel` 选项），`gray` / `color` 使用 LZW。未指定时设置了
+ * `bilevel` 选项为 `bilevel`，否则为 `color`。渲染失败的页面不写入 TIFF，
+ * 没有任何页面成功时整体失败。
+ *
+ * # Arguments
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项
+ * * `output_path` - 写入的文件路径；不传时在结果的 buffer 中返回
+ */
+export declare function renderMultipageTiff(input: string | Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null, outputPath?: string | undefined | null): Promise<MultiPageTiffResult>
+/** `{ from, to }` 形式的页码范围 */
+export interface PageRange {
+  /** 起始页（从 1 开始，默认 1） */
+  from?: number
+  /** 结束页（包含），为空表示到最后一页 */
+  to?: number
+}
+/**
+ * 按文档页数展开页码范围（与渲染接口的页码参数规则相同）
+ *
+ * 先取页数再计算页码的调用方（如按页分发到工作线程）用它得到与原生渲染一致的页码数组。
+ *
+ * # Arguments
+ * * `pages` - 页码数组、范围字符串（`"1-5,8,10-"`、`"all"`）或 `{ from, to }`
+ * * `num_pages` - 文档页数
+ *
+ * # Returns
+ * 页码数组（范围中重复的页码只保留一次）；范围格式错误或超出页数时抛出 INVALID_OPTIONS 错误
+ */
+export declare function resolvePageRanges(pages: Array<number> | string | PageRange, numPages: number): Array<number>
+/**
+ * 从文件路径获取 PDF 页数（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+ *
+ * # Returns
+ * PDF 的总页数
+ */
+export declare function getPageCountFromFile(filePath: string, passwords?: string | Array<string> | undefined | null): number
+/**
+ * 获取 PDF 页数（不渲染）
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+ *
+ * # Returns
+ * PDF 的总页数
+ */
+export declare function getPageCount(pdfBuffer: Buffer, passwords?: string | Array<string> | undefined | null): number
+/**
+ * 获取页面的粗粒度版面信息（不渲染）
+ *
+ * 基于 PDFium 的文字和对象几何信息，返回每页的文本块和图片区域。
+ * 坐标与相同选项下 `renderPages` 输出的图像一致，可直接用于裁剪。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 要分析的页码数组（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 每页的版面分析结果
+ */
+export declare function getPageLayout(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageLayout>
+/**
+ * 从文件路径获取页面的粗粒度版面信息（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 要分析的页码数组（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 每页的版面分析结果
+ */
+export declare function getPageLayoutFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageLayout>
+/**
+ * 获取页面中带标签的图形、替代文字和题注（不渲染）
+ *
+ * 读取标签 PDF 的结构树，返回 Figure 等图形元素的替代文字，以及与之关联的题注文字，
+ * 供无障碍流程给生成的页面图像自动附加替代文字。区域坐标与相同选项下 `renderPages`
+ * 输出的图像一致。只读取页面结构树，不解析文档级 XMP 元数据。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 要读取的页码数组（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 每页的图形信息
+ */
+export declare function getPageFigures(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageFigures>
+/**
+ * 从文件路径获取页面中带标签的图形、替代文字和题注（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 要读取的页码数组（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 每页的图形信息
+ */
+export declare function getPageFiguresFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageFigures>
+/**
+ * 一次获取所有页面的尺寸、旋转、标签以及是否有文字和图片（不渲染）
+ *
+ * 供查看器在打开文档时一次性取得整个文档的页面信息，再按需渲染；
+ * 结果同时汇总扫描件页数比例，可据此在 OCR 和文字提取之间选择处理流程。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `options` - 渲染配置选项（只使用资源限制）
+ *
+ * # Returns
+ * 文档页面概况
+ */
+export declare function getDocumentLayout(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): DocumentLayout
+/**
+ * 从文件路径一次获取所有页面的概况（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `options` - 渲染配置选项（只使用资源限制）
+ *
+ * # Returns
+ * 文档页面概况
+ */
+export declare function getDocumentLayoutFromFile(filePath: string, options?: RenderOptions | undefined | null): DocumentLayout
+/**
+ * 获取文档元数据（标题、作者、日期等）和每页的尺寸、旋转、标签（不渲染）
+ *
+ * 查看器在渲染任何页面之前据此计算布局。与 getDocumentLayout 不同，
+ * 不检查页面内容（文字、图片、注释），大文档也能很快返回。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 文档信息
+ */
+export declare function getDocumentInfo(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): DocumentInfo
+/**
+ * 从文件路径获取文档元数据和每页的尺寸（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 文档信息
+ */
+export declare function getDocumentInfoFromFile(filePath: string, options?: RenderOptions | undefined | null): DocumentInfo
+/**
+ * 列出文档中的附件（不渲染，不读取附件内容）
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 附件列表，没有附件时为空
+ */
+export declare function getAttachments(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): Array<AttachmentInfo>
+/**
+ * 从文件路径列出文档中的附件（不渲染，不读取附件内容）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 附件列表，没有附件时为空
+ */
+export declare function getAttachmentsFromFile(filePath: string, options?: RenderOptions | undefined | null): Array<AttachmentInfo>
+/**
+ * 取出附件内容
+ *
+ * 附件解压后的大小同样受 maxFileSize 限制。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `index` - 附件序号（getAttachments 返回的 index）
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 附件内容，序号无效时报错
+ */
+export declare function getAttachment(pdfBuffer: Buffer, index: number, options?: RenderOptions | undefined | null): Buffer
+/**
+ * 从文件路径取出附件内容
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `index` - 附件序号（getAttachments 返回的 index）
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 附件内容，序号无效时报错
+ */
+export declare function getAttachmentFromFile(filePath: string, index: number, options?: RenderOptions | undefined | null): Buffer
+/**
+ * 获取单页的尺寸、旋转和标签（不渲染）
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 页面信息，页码无效时报错
+ */
+export declare function getPageInfo(pdfBuffer: Buffer, pageNum: number, options?: RenderOptions | undefined | null): PageInfo
+/**
+ * 从文件路径获取单页的尺寸、旋转和标签（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 页面信息，页码无效时报错
+ */
+export declare function getPageInfoFromFile(filePath: string, pageNum: number, options?: RenderOptions | undefined | null): PageInfo
+/**
+ * 获取单页文字（词或文字段）及其位置（不渲染）
+ *
+ * 每个词同时给出页面坐标（点）和输出图像坐标（像素），像素坐标与相同选项下 `renderPages`
+ * 输出的图像一致，可直接用于在渲染结果上高亮搜索命中或叠加可选中的文字层。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸，`textGranularity` 控制粒度）
+ *
+ * # Returns
+ * 页面文字位置，页码无效时报错
+ */
+export declare function getPageTextRects(pdfBuffer: Buffer, pageNum: number, options?: RenderOptions | undefined | null): PageTextRects
+/**
+ * 从文件路径获取单页文字（词或文字段）及其位置（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸，`textGranularity` 控制粒度）
+ *
+ * # Returns
+ * 页面文字位置，页码无效时报错
+ */
+export declare function getPageTextRectsFromFile(filePath: string, pageNum: number, options?: RenderOptions | undefined | null): PageTextRects
+/**
+ * 获取单页的链接和其他注释及其位置（不渲染）
+ *
+ * 链接返回外部地址或本文档内的目标页码，区域坐标与相同选项下 `renderPages` 输出的图像一致，
+ * 网页查看器可以直接在渲染图上放置可点击区域。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 页面注释，页码无效时报错
+ */
+export declare function getPageAnnotations(pdfBuffer: Buffer, pageNum: number, options?: RenderOptions | undefined | null): PageAnnotations
+/**
+ * 从文件路径获取单页的链接和其他注释及其位置（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 页面注释，页码无效时报错
+ */
+export declare function getPageAnnotationsFromFile(filePath: string, pageNum: number, options?: RenderOptions | undefined | null): PageAnnotations
+/**
+ * 获取表单字段的名称、类型、当前值和位置（不渲染）
+ *
+ * 区域坐标与相同选项下 `renderPages` 输出的图像一致。渲染时表单的处理方式由 formMode 控制。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_num` - 页码（从 1 开始），为空时列出所有页的字段
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 表单字段，按页码和页面中的顺序排列；页码无效时报错
+ */
+export declare function getFormFields(pdfBuffer: Buffer, pageNum?: number | undefined | null, options?: RenderOptions | undefined | null): Array<FormField>
+/**
+ * 从文件路径获取表单字段的名称、类型、当前值和位置（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_num` - 页码（从 1 开始），为空时列出所有页的字段
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 表单字段，按页码和页面中的顺序排列；页码无效时报错
+ */
+export declare function getFormFieldsFromFile(filePath: string, pageNum?: number | undefined | null, options?: RenderOptions | undefined | null): Array<FormField>
+/**
+ * 列出页面上的图片及其像素尺寸和放置分辨率（不渲染、不解码图片）
+ *
+ * 上传质量检查可以在渲染前拒绝扫描分辨率过低（如低于 150 DPI）的文档，
+ * 不必先按大尺寸渲染再判断。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 每页的图片信息
+ */
+export declare function getPageImages(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageImages>
+/**
+ * 从文件路径列出页面上的图片及其放置分辨率（不渲染、不解码图片）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 每页的图片信息
+ */
+export declare function getPageImagesFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): Array<PageImages>
+/**
+ * 统计页面文字（不渲染、不返回文字内容）
+ *
+ * 返回每页的字符数、词数以及是否有可提取的文字，用于在索引和 OCR 之间做选择。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+ *
+ * # Returns
+ * 每页的文字统计
+ */
+export declare function getTextStats(pdfBuffer: Buffer, pageNums: Array<number>, passwords?: string | Array<string> | undefined | null): Array<PageTextStats>
+/**
+ * 从文件路径统计页面文字（不渲染、不返回文字内容）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+ *
+ * # Returns
+ * 每页的文字统计
+ */
+export declare function getTextStatsFromFile(filePath: string, pageNums: Array<number>, passwords?: string | Array<string> | undefined | null): Array<PageTextStats>
+/**
+ * 渲染跨页（对开页）
+ *
+ * 把相邻的两页按书籍左右页拼成一张图片，整体宽度约为 `targetWidth`，
+ * 两页缩放到相同高度。拼接在原生层完成，避免在 JS 中解码再重新编码。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），返回包含这些页面的跨页；空数组表示全部页面
+ * * `options` - 渲染配置选项（`spreadCover` 控制封面是否单独成页）
+ *
+ * # Returns
+ * 每个跨页的渲染结果
+ */
+export declare function renderSpreads(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): SpreadRenderResult
+/**
+ * 从文件路径渲染跨页（对开页）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），返回包含这些页面的跨页；空数组表示全部页面
+ * * `options` - 渲染配置选项（`spreadCover` 控制封面是否单独成页）
+ *
+ * # Returns
+ * 每个跨页的渲染结果
+ */
+export declare function renderSpreadsFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): SpreadRenderResult
+/**
+ * 渲染缩略图拼图（sprite sheet）
+ *
+ * 每页在边长为 `cell_size` 的正方形单元格内等比缩放并居中，所有单元格拼成一张图片，
+ * 按 `format` 选项编码。查看器的进度条预览只需加载一张图片，比逐页生成小图便宜得多。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `cell_size` - 单元格边长（像素）
+ * * `options` - 渲染配置选项（输出格式、`spriteColumns` 等）
+ *
+ * # Returns
+ * 拼图和每页所在的区域
+ */
+export declare function renderThumbnailStrip(pdfBuffer: Buffer, pageNums: Array<number>, cellSize: number, options?: RenderOptions | undefined | null): ThumbnailStripResult
+/**
+ * 从文件路径渲染缩略图拼图（sprite sheet）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `cell_size` - 单元格边长（像素）
+ * * `options` - 渲染配置选项（输出格式、`spriteColumns` 等）
+ *
+ * # Returns
+ * 拼图和每页所在的区域
+ */
+export declare function renderThumbnailStripFromFile(filePath: string, pageNums: Array<number>, cellSize: number, options?: RenderOptions | undefined | null): ThumbnailStripResult
+/**
+ * 提取页面内嵌的缩略图（不渲染）
+ *
+ * 部分 PDF 生成器会为每页写入预生成的缩略图，读取它们比渲染便宜得多，
+ * 适合列表视图。没有缩略图的页面返回 `found: false`，调用方可以回退到渲染；
+ * 设置 `thumbnailFallbackWidth` 时这些页面直接按该宽度渲染（`rendered: true`）。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（用于输出格式和 `thumbnailPassthrough`）
+ *
+ * # Returns
+ * 每页的缩略图
+ */
+export declare function getEmbeddedThumbnails(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): ThumbnailResult
+/**
+ * 从文件路径提取页面内嵌的缩略图（不渲染）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（用于输出格式和 `thumbnailPassthrough`）
+ *
+ * # Returns
+ * 每页的缩略图
+ */
+export declare function getEmbeddedThumbnailsFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): ThumbnailResult
+/**
+ * 查找内容相同或相近的页面
+ *
+ * 每页渲染成小图并计算感知哈希，哈希距离不超过 `duplicateThreshold` 的页面归为一组，
+ * 用于找出扫描件中重复上传的页面。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（`duplicateThreshold`）
+ *
+ * # Returns
+ * 每页的哈希和重复页分组
+ */
+export declare function findDuplicatePages(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): DuplicatePagesResult
+/**
+ * 从文件路径查找内容相同或相近的页面
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（`duplicateThreshold`）
+ *
+ * # Returns
+ * 每页的哈希和重复页分组
+ */
+export declare function findDuplicatePagesFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): DuplicatePagesResult
+/**
+ * 把多个 PDF 的页面拼成一个虚拟文档并渲染（异步）
+ *
+ * 例如模板中的封面加上上传文件的第 1-3 页：按 `sources` 的顺序把各来源的页面
+ * 复制到一个内存中的新文档，再一次性渲染全部页面，不需要先单独合并成文件。
+ * 所有来源共用 `options` 中的密码；任一来源打开失败或页码无效时整体失败。
+ *
+ * # Arguments
+ * * `sources` - 来源文档及各自取用的页码
+ * * `options` - 渲染配置选项
+ *
+ * # Returns
+ * Promise<ComposedRenderResult>
+ */
+export declare function renderComposedPages(sources: Array<ComposeSource>, options?: RenderOptions | undefined | null): Promise<ComposedRenderResult>
 /**
- * 从 PDF Buffer 渲染指定页面
+ * 把指定页面分别导出为单页 PDF
+ *
+ * 通过 PDFium 的页面导入接口复制页面（文字、矢量图形和字体都保持原样），
+ * 适合在图片预览旁提供"只发送这一页"的 PDF 下载。
  *
  * # Arguments
- * * `pdf_buffer` - PDF 文件的二进制数据
- * * `page_nums` - 要渲染的页码数组（从 1 开始）
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+ * * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+ *
+ * # Returns
+ * 每页的单页 PDF
+ */
+export declare function extractPages(input: string | Buffer, pageNums: number[], options?: RenderOptions | undefined | null): ExtractPagesResult
+/**
+ * 把选中的页面导出为一个新的 PDF
+ *
+ * 通过 PDFium 的页面导入接口一次复制所有选中的页面（文字、矢量图形和字体都保持原样，
+ * 页面之间共享的资源只复制一份），新文档中的页面顺序与页码参数一致。
+ * 适合渲染前先拆分文档，不必再调用外部工具。书签、表单和文档信息不会复制。
+ *
+ * # Arguments
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_nums` - 页码数组（从 1 开始，空数组表示全部页面），或范围字符串、`{ from, to }`（同 `renderPages`）
+ * * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+ *
+ * # Returns
+ * 新 PDF 的数据；任一页码无效时整体失败
+ */
+export declare function extractPagesAsPdf(input: string | Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null): ExtractDocumentResult
+/**
+ * 只渲染页面的一个矩形区域
+ *
+ * 截取签名框、插图等小区域时，按区域尺寸分配位图并通过变换矩阵只绘制区域内的内容，
+ * 不需要先以高倍率渲染整页再裁剪。区域超出页面的部分被裁掉；
+ * 输出格式、编码和滤镜选项与 `renderPages` 相同，尺寸类选项（targetWidth、dpi、canvas 等）不生效。
+ *
+ * # Arguments
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_num` - 页码（从 1 开始）
+ * * `region` - 页面上的区域（点）和缩放比例
  * * `options` - 渲染配置选项
  *
  * # Returns
- * 包含所有页面渲染结果的对象
+ * 只包含该区域图像的渲染结果（pages 中只有一页）
  */
-export declare function renderPages(pdfBuffer: Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null): RenderResult
+export declare function renderPageRegion(input: string | Buffer, pageNum: number, region: PageRegion, options?: RenderOptions | undefined | null): RenderResult
 /**
- * 从文件路径渲染 PDF 页面
+ * 渲染深度缩放查看器（OpenSeadragon、Leaflet 等）的页面分块
  *
- * 直接从文件系统读取 PDF，避免在 Node.js 堆中创建大 Buffer。
- * 这是处理本地大文件的最高效方式。
+ * 级别 0 时整页缩放到一个分块以内，之后每级放大一倍，最高级别的缩放比例不小于
+ * `options.maxScale`。每个分块通过变换矩阵单独渲染，超大幅面的高倍率级别也不需要渲染整页。
+ * 指定 `x`/`y` 时只渲染这一块；不指定时渲染该级别的全部分块（最多 256 块）。
+ *
+ * # Arguments
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_num` - 页码（从 1 开始）
+ * * `request` - 缩放级别、分块大小和分块坐标
+ * * `options` - 渲染配置选项（使用输出格式、编码和渲染标志，尺寸类选项不生效）
+ *
+ * # Returns
+ * 该级别的网格信息和编码后的分块
+ */
+export declare function renderPageTiles(input: string | Buffer, pageNum: number, request: ZoomTileRequest, options?: RenderOptions | undefined | null): ZoomTiles
+/**
+ * 比较两个文档的页数和每页的尺寸、旋转（不渲染）
+ *
+ * 按页码对齐两个文档的页面，只读取页面尺寸和旋转，不渲染也不遍历页面对象，
+ * 可以在生成逐页视觉差异之前快速判断两个版本的版式是否发生了变化。
+ *
+ * # Arguments
+ * * `input_a` - 文档 A 的文件路径或 Buffer
+ * * `input_b` - 文档 B 的文件路径或 Buffer
+ * * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+ *
+ * # Returns
+ * 逐页的布局差异
+ */
+export declare function compareDocuments(inputA: string | Buffer, inputB: string | Buffer, options?: RenderOptions | undefined | null): DocumentComparison
+/**
+ * 提取书签（目录），附带目标位置在渲染图像中的坐标
+ *
+ * 按文档顺序深度优先展开，`level` 表示层级。坐标与相同选项下
+ * `renderPages` 输出的图像一致，点击目录条目时可以直接滚动到对应位置。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
+ *
+ * # Returns
+ * 书签条目列表
+ */
+export declare function getOutlineAnchors(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): Array<OutlineAnchor>
+/**
+ * 从文件路径提取书签（目录），附带目标位置在渲染图像中的坐标
  *
  * # Arguments
  * * `file_path` - PDF 文件的路径
- * * `page_nums` - 要渲染的页码数组（从 1 开始）
- * * `options` - 渲染配置选项
+ * * `options` - 渲染配置选项（用于确定输出图像尺寸）
  *
  * # Returns
- * 包含所有页面渲染结果的对象
+ * 书签条目列表
  */
-export declare function renderPagesFromFile(filePath: string, pageNums: Array<number>, options?: RenderOptions | undefined | null): RenderResult
+export declare function getOutlineAnchorsFromFile(filePath: string, options?: RenderOptions | undefined | null): Array<OutlineAnchor>
 /**
- * 从文件路径获取 PDF 页数（不渲染）
+ * 提取书签（目录）树（不渲染）
+ *
+ * 按 PDF 中的嵌套关系返回，只解析目标页码，适合直接生成目录。
+ * 需要目标位置的像素坐标时使用 `get_outline_anchors`。
+ *
+ * # Arguments
+ * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
+ *
+ * # Returns
+ * 顶层书签列表，没有书签时为空
+ */
+export declare function getOutline(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): Array<OutlineItem>
+/**
+ * 从文件路径提取书签（目录）树（不渲染）
  *
  * # Arguments
  * * `file_path` - PDF 文件的路径
+ * * `options` - 渲染配置选项（只使用密码和资源限制）
  *
  * # Returns
- * PDF 的总页数
+ * 顶层书签列表，没有书签时为空
  */
-export declare function getPageCountFromFile(filePath: string): number
+export declare function getOutlineFromFile(filePath: string, options?: RenderOptions | undefined | null): Array<OutlineItem>
 /**
- * 获取 PDF 页数（不渲染）
+ * 将单页导出为矢量 SVG（实验性）
+ *
+ * 把页面中的路径、文字和图片转换为 SVG 元素，适合需要无限缩放清晰度
+ * 而非像素级还原的场景。裁剪路径、着色对象和嵌入字体不会被保留。
  *
  * # Arguments
  * * `pdf_buffer` - PDF 文件的二进制数据
+ * * `page_num` - 页码（从 1 开始）
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
  *
  * # Returns
- * PDF 的总页数
+ * SVG 文本
+ */
+export declare function renderPageToSvg(pdfBuffer: Buffer, pageNum: number, passwords?: string | Array<string> | undefined | null): string
+/**
+ * 从文件路径将单页导出为矢量 SVG（实验性）
+ *
+ * # Arguments
+ * * `file_path` - PDF 文件的路径
+ * * `page_num` - 页码（从 1 开始）
+ * * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+ *
+ * # Returns
+ * SVG 文本
  */
-export declare function getPageCount(pdfBuffer: Buffer): number
+export declare function renderPageToSvgFromFile(filePath: string, pageNum: number, passwords?: string | Array<string> | undefined | null): string
 /**
  * 渲染单页到原始位图（不编码）
  *
@@ -139,7 +2612,138 @@ export declare function getPageCount(pdfBuffer: Buffer): number
 export declare function renderPageToRawBitmap(filePath: string, pageNum: number, options?: RenderOptions | undefined | null): RawBitmapResult
 /** 从 Buffer 渲染单页到原始位图（不编码） */
 export declare function renderPageToRawBitmapFromBuffer(pdfBuffer: Buffer, pageNum: number, options?: RenderOptions | undefined | null): RawBitmapResult
-/** 检查 PDFium 库是否可用 */
+/**
+ * 按条带渲染单页原始位图，分块交给回调（不编码，不持有整页像素）
+ *
+ * 适合把 600 DPI 等超大位图直接接入流式编码器或上传：每段渲染完通过 `onChunk`
+ * 交给 JS，JS 处理完后调用 `completeRawChunk(taskId)` 确认，同时在途的分块最多两个，
+ * 消费慢时渲染线程等待。确认时传入错误信息会中止渲染。
+ *
+ * 条带直接由 PDFium 渲染，canvas、滤镜和高亮叠加不生效，表单控件按外观流绘制。
+ *
+ * # Arguments
+ * * `input` - PDF 文件路径或 Buffer
+ * * `page_num` - 页码（从 1 开始）
+ * * `options` - 渲染选项（chunkRows 指定每块行数）
+ * * `on_chunk` - 接收 RawBitmapChunk 的回调
+ *
+ * # Returns
+ * Promise<RawChunkResult>，所有分块都确认后完成
+ */
+export declare function renderPageToRawChunks(input: string | Buffer, pageNum: number, options: RenderOptions | null | undefined, onChunk: (err: Error | null, chunk: RawBitmapChunk) => void): Promise<RawChunkResult>
+/**
+ * 确认 renderPageToRawChunks 交出的一个分块
+ *
+ * # Arguments
+ * * `task_id` - 分块中的 taskId
+ * * `error` - 回调处理失败时的错误信息，渲染随之中止
+ */
+export declare function completeRawChunk(taskId: number, error?: string | undefined | null): void
+/** 单个候选库路径的探测结果 */
+export interface LibraryProbe {
+  /** 候选路径（系统库为库名，由动态链接器按搜索路径查找） */
+  path: string
+  /** 是否加载成功 */
+  loaded: boolean
+  /** 加载失败原因 */
+  error?: string
+  /** 加载耗时（毫秒） */
+  loadTime: number
+}
+/** 诊断结果 */
+export interface Diagnostics {
+  /** PDFium 是否可用（有库加载成功且测试渲染通过） */
+  available: boolean
+  /** 实际使用的库路径 */
+  loadedFrom?: string
+  /** 按顺序探测的候选库 */
+  probes: Array<LibraryProbe>
+  /** 绑定所针对的 PDFium API 版本（库加载失败时为空） */
+  pdfiumApiVersion?: string
+  /** 原生模块版本 */
+  moduleVersion: string
+  /** 运行平台（os-arch） */
+  platform: string
+  /** 当前工作目录（相对路径的库在此目录下查找） */
+  cwd: string
+  /** 测试渲染是否成功 */
+  testRenderOk: boolean
+  /** 测试渲染失败原因 */
+  testRenderError?: string
+  /** 测试渲染耗时（毫秒） */
+  testRenderTime: number
+  /** 诊断总耗时（毫秒） */
+  totalTime: number
+  /**
+   * 渲染调用的绑定状态：available（最近一次绑定成功）、unavailable（最近一次绑定失败）、
+   * unknown（尚未渲染过）；与上面的探测结果不同，反映的是实际渲染调用的情况
+   */
+  bindingState: string
+  /** 绑定状态为 unavailable 时为 PDFIUM_UNAVAILABLE，与渲染结果的 errorCode 一致 */
+  errorCode?: string
+  /** 连续绑定失败次数（自动重试后仍失败才计数） */
+  bindFailures: number
+  /** 最近一次绑定失败的原因 */
+  lastBindError?: string
+  /** 进入不可用状态的时间（Unix 毫秒） */
+  unavailableSince?: number
+  /** 库曾经加载成功、之后经自动重试才重新绑定成功的次数 */
+  rebinds: number
+}
+/**
+ * 获取诊断信息
+ *
+ * 返回探测过的库路径、实际加载的库、PDFium API 版本、一次最小测试渲染的结果和耗时。
+ * 同时返回渲染调用的绑定状态（bindingState），库在运行中丢失时为 unavailable。
+ * 该函数不会抛出异常，所有失败都记录在返回结果中。
+ */
+export declare function getDiagnostics(): Diagnostics
+/** 最近一次渲染尝试的记录 */
+export interface RenderAttempt {
+  /** 递增序号 */
+  seq: number
+  /** 开始时间（Unix 毫秒） */
+  startedAt: number
+  /** 文档指纹（buffer:长度:哈希 / file:路径:大小:哈希 / stream:大小:任务ID） */
+  source: string
+  /** 页码（从 1 开始） */
+  pageNum: number
+  /** 渲染宽度（尚未计算出尺寸时为 0） */
+  width: number
+  /** 渲染高度 */
+  height: number
+  /** 已到达的阶段：loading / rendering / encoding / done / failed */
+  stage: string
+  /** 失败原因 */
+  error?: string
+  /** 耗时（毫秒） */
+  elapsed: number
+}
+/**
+ * 获取最近的渲染记录（最多 32 条，从旧到新）
+ *
+ * 停留在 loading / rendering / encoding 阶段的记录表示该页尚未完成，
+ * 进程崩溃后可据此定位触发崩溃的文档和页面。
+ */
+export declare function getRecentRenders(): Array<RenderAttempt>
+/**
+ * 把最近的渲染记录以 JSON 写入文件
+ *
+ * 适合在 worker 的 error / exit 事件或 uncaughtException 处理中调用。
+ */
+export declare function dumpRecentRenders(path: string): void
+/**
+ * 设置崩溃转储路径
+ *
+ * 设置后，原生模块内发生 panic 时会先把最近的渲染记录写入该文件。
+ * 传入 null 取消转储。
+ */
+export declare function setCrashDumpPath(path?: string | undefined | null): void
+/**
+ * 检查 PDFium 库是否可用
+ *
+ * 只返回布尔值；需要排查原因时使用 `getDiagnostics`。
+ */
 export declare function isPdfiumAvailable(): boolean
 /**
  * 预热 PDFium 库
@@ -151,8 +2755,137 @@ export declare function isPdfiumAvailable(): boolean
  * 预热耗时（毫秒）
  */
 export declare function warmup(): number
+/** 样本文档中单页的检查结果 */
+export interface SamplePageReport {
+  pageNum: number
+  success: boolean
+  error?: string
+  errorCode?: string
+  width: number
+  height: number
+  /** 编码后的字节数 */
+  size: number
+  /** 渲染耗时（毫秒） */
+  renderTime: number
+  /** 编码耗时（毫秒） */
+  encodeTime: number
+}
+/** 样本文档预热 / 就绪检查的结果 */
+export interface WarmupReport {
+  /** 文档加载成功且所有页面都渲染成功 */
+  ok: boolean
+  /** 绑定或加载失败的原因 */
+  error?: string
+  /** 绑定 PDFium 的耗时（毫秒） */
+  bindTime: number
+  /** 打开样本文档的耗时（毫秒） */
+  loadTime: number
+  /** 样本文档总页数（加载失败时为 0） */
+  numPages: number
+  pages: Array<SamplePageReport>
+  /** 所有页面编码后的总字节数 */
+  outputBytes: number
+  /** 总耗时（毫秒） */
+  totalTime: number
+}
+/**
+ * 用调用方提供的样本文档预热并检查就绪状态
+ *
+ * 与 `warmup` 只加载内置的最小 PDF 不同，这里对部署实际处理的文档走一遍绑定、加载、
+ * 渲染、编码流程，报告各阶段耗时和输出大小，适合作为就绪探针。
+ * 最多检查 8 页（默认第 1 页），未指定 totalTimeBudgetMs 时预算为 30 秒；
+ * 样本渲染不经过渲染缓存。失败不会抛出异常，原因在结果的 error 和各页的 error 中。
+ *
+ * # Arguments
+ * * `sample` - 样本 PDF 的文件路径或 Buffer
+ * * `page_nums` - 要检查的页码（默认 [1]）
+ * * `options` - 渲染配置选项（与正式渲染使用相同的配置，检查结果才有代表性）
+ */
+export declare function warmupWithSample(sample: string | Buffer, pageNums?: Array<number> | undefined | null, options?: RenderOptions | undefined | null): Promise<WarmupReport>
 /** 获取版本信息 */
 export declare function getVersion(): string
+/** 合成测试 PDF 的参数 */
+export interface TestPdfOptions {
+  /**
+   * 页面内容：text（纯文字）、image（整页灰度 JPEG，识别为扫描件）、
+   * huge（14400 点见方的超大页面）、mixed（按页循环以上三种），默认 text
+   */
+  kind?: string
+  /** 页数（1-1000，默认 3） */
+  pages?: number
+  /** 设置后以该密码加密（RC4 40 位），打开时需要传入 password */
+  password?: string
+}
+/**
+ * 生成合成测试 PDF
+ *
+ * 给下游服务的集成测试使用，覆盖纯文字、扫描件、超大页面和加密文档等情况，
+ * 不需要在仓库中保存二进制样本。不依赖 PDFium，相同参数生成的字节完全相同。
+ */
+export declare function generateTestPdf(options?: TestPdfOptions | undefined | null): Buffer
+/** 已编译的编码器后端 */
+export interface EncoderCapability {
+  /** 输出格式（webp/png/jpg/tiff） */
+  format: string
+  /** 后端名称（可作为 encoderBackend 选项的值） */
+  backend: string
+  /** 是否为该格式的默认后端（encoderBackend 为 auto 时使用） */
+  isDefault: boolean
+  /** 说明 */
+  description: string
+}
+/** 交给自定义编码器的位图 */
+export interface EncodeRequest {
+  /** 请求 ID，编码完成后调用 completeEncodeRequest(requestId, data, error) */
+  requestId: number
+  /** 编码器名称 */
+  encoder: string
+  /** format 选项对应的输出格式（webp/png/jpg/tiff/avif） */
+  format: string
+  /** 质量参数（供编码器参考）：jpg 为 jpegQuality，avif 为 avifQuality，其他格式为 webpQuality */
+  quality: number
+  /** 编码速度（仅 avif，avifSpeed：0 最慢但压缩最好，10 最快） */
+  speed?: number
+  width: number
+  height: number
+  /** 每行字节数（紧密排列，等于 width * 4） */
+  stride: number
+  /** 像素通道顺序：rgba 或 bgra */
+  pixelFormat: string
+  /** 像素数据 */
+  buffer: Buffer
+}
+/**
+ * 注册自定义编码器
+ *
+ * 注册后可以通过 `encoderBackend: name` 选用：每页渲染完成后，位图以 EncodeRequest
+ * 交给 `callback`，JS 编码完成后调用 completeEncodeRequest 交回结果，渲染线程在此期间等待。
+ * 同名编码器会被替换。只能用于异步渲染接口，同步接口选用时该页编码失败。
+ *
+ * # Arguments
+ * * `name` - 编码器名称（不能是 auto/libwebp/image/builtin/none）
+ * * `callback` - 接收 EncodeRequest 的回调
+ * * `format` - 输出格式名（如 avif），写入 PageResult.format；不传时沿用 format 选项
+ */
+export declare function registerEncoder(name: string, callback: (err: Error | null, request: EncodeRequest) => void, format?: string | null): void
+/** 注销自定义编码器，返回是否存在 */
+export declare function unregisterEncoder(name: string): boolean
+/**
+ * 交回自定义编码器的编码结果
+ *
+ * # Arguments
+ * * `request_id` - EncodeRequest.requestId
+ * * `data` - 编码后的数据
+ * * `error` - 错误信息（编码失败时），该页随之编码失败
+ */
+export declare function completeEncodeRequest(requestId: number, data?: Buffer | undefined | null, error?: string | undefined | null): void
+/**
+ * 查询已编译的编码器后端
+ *
+ * 不同构建可能包含不同的编码器，部署时据此选择速度或压缩率优先的后端。
+ * registerEncoder 注册的编码器排在最后，format 为其输出格式名（未指定时为 custom）。
+ */
+export declare function getEncoderCapabilities(): Array<EncoderCapability>
 /** 流式渲染结果（包含额外的统计信息） */
 export interface StreamRenderResult {
   /** 是否成功 */
@@ -167,6 +2900,12 @@ export interface StreamRenderResult {
   totalTime: number
   /** 流式加载统计 */
   streamStats?: StreamStats
+  /** 请求关联 ID（原样回显 options.correlationId） */
+  correlationId?: string
+  /** 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数） */
+  resolvedConfig?: string
+  /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键 */
+  configHash?: string
 }
 /** 流式加载统计信息 */
 export interface StreamStats {
@@ -178,6 +2917,70 @@ export interface StreamStats {
   cacheMisses: number
   /** 总下载字节数 */
   totalBytesFetched: number
+  /** 因并发限制排队等待的时间（毫秒） */
+  queueTime: number
+  /** 由预取（fetchLanes 并行预取、streamReadahead 合并读取）提供、随后被读取的块数 */
+  prefetchHits: number
+  /** 预取请求的块数；prefetchHits 与它的比值即预取的有效程度，过低时出现 WASTED_PREFETCH 建议 */
+  prefetchedBlocks: number
+  /** 由 seedBlocks 预先写入缓存的块数 */
+  seededBlocks: number
+  /** 按取数计划（options.fetchPlan）批量获取的块数 */
+  plannedBlocks: number
+  /** 取数计划失败的原因（渲染照常逐块读取） */
+  fetchPlanError?: string
+  /** 改为获取整个文件的原因（options.streamFallbackFraction / streamFallbackRequests，未回退时为空） */
+  fallbackReason?: string
+  /** 获取整个文件失败的原因（渲染照常逐块读取） */
+  fallbackError?: string
+  /** 被多个页面读取过的块（按偏移排序） */
+  reusedBlocks: Array<ReusedBlock>
+  /** 缓存命中率（缓存命中与预取命中占全部读取的比例，目标 0.8） */
+  hitRatio: number
+  /** 文件是否线性化（按文件开头的 /Linearized 字典判断），未读到文件开头时为空 */
+  linearized?: boolean
+  /** 是否使用了线性化文件的快速路径（options.linearizedFastPath） */
+  linearizedFastPath: boolean
+  /** 根据本次读取模式给出的调优建议 */
+  hints: Array<StreamHint>
+  /** 等待超时或任务结束时仍未得到响应的请求数 */
+  orphanedRequests: number
+}
+/** 流式读取的调优建议 */
+export interface StreamHint {
+  /**
+   * 建议类别
+   *
+   * - FULL_DOWNLOAD：读取量接近文件大小，整体下载更合适
+   * - NOT_LINEARIZED：文档未线性化，页面数据分散在整个文件中
+   * - ENABLE_FETCH_LANES：大部分块按顺序读取，建议设置 fetchLanes 或 streamReadahead
+   * - WASTED_PREFETCH：预取的块大多没有被读取，建议调小 fetchLanes 或 streamReadahead
+   * - REFETCHED_BLOCKS：块被缓存淘汰后重新获取
+   * - LOW_HIT_RATIO：缓存命中率低于目标
+   */
+  code: string
+  /** 说明（英文） */
+  message: string
+}
+/** 本次任务中被多个页面读取过的缓存块 */
+export interface ReusedBlock {
+  /** 块在文件中的偏移 */
+  offset: number
+  /** 块大小（字节） */
+  size: number
+  /** 读取过该块的页码（升序），0 表示打开文档阶段 */
+  pages: Array<number>
+  /** 通过 fetcher 获取的次数，大于 1 说明块被淘汰后又重新获取 */
+  fetches: number
+  /** 是否由 seedBlocks 提供 */
+  seeded: boolean
+}
+/** 调用方已有的文件数据，用于预先填充流式缓存 */
+export interface SeedBlock {
+  /** 数据在文件中的偏移 */
+  offset: number
+  /** 文件数据 */
+  data: Buffer
 }
 /**
  * 从流式数据源渲染 PDF 页面（异步版本）
@@ -187,23 +2990,531 @@ export interface StreamStats {
  *
  * # Arguments
  * * `env` - NAPI 环境
- * * `pdf_size` - PDF 文件的总大小（字节）
- * * `page_nums` - 要渲染的页码数组（从 1 开始）
+ * * `pdf_size` - PDF 文件的总大小（字节）；传入 0 或负数表示长度未知（适合边上传边渲染），
+ *   此时第一个请求的响应需要通过 `completeStreamRequest` 的 `totalSize` 报告总长度
+ * * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）；
+ *   范围在文档加载、页数已知后展开，不需要先单独获取页数
  * * `options` - 渲染配置选项
  * * `fetcher` - JavaScript 回调函数，用于获取指定范围的数据
+ * * `seed_blocks` - 调用方已有的文件数据（例如上传时已经读到的前 1 MB），
+ *   预先写入缓存，覆盖的块不再通过 fetcher 获取
+ * * `on_document` - 文档头和页面树解析完成后、任何页面渲染之前以 `(err, info)` 调用一次，
+ *   界面可以先按页数画出翻页骨架；文档加载失败时不调用
  *
  * # Returns
  * Promise<StreamRenderResult>
  */
-export declare function renderPagesFromStream(pdfSize: number, pageNums: number[], options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number) => void): object
+export declare function renderPagesFromStream(pdfSize: number, pageNums: number[] | string | PageRange, options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number, correlationId?: string) => void, seedBlocks?: SeedBlock[] | null, onDocument?: ((err: Error | null, info: StreamDocumentInfo) => void) | null): object
 /**
  * 完成流式请求
  *
  * 当 JS 端获取到数据后，调用这个函数将数据发送给 Rust 端。
+ * Buffer 不会被复制，直接作为块缓存的数据，调用后不要再修改它。
  *
  * # Arguments
  * * `request_id` - 请求 ID
  * * `data` - 获取到的数据
  * * `error` - 错误信息（如果获取失败）
+ * * `total_size` - 文件总长度（`pdfSize` 未知时必须在第一个请求的响应中提供，例如 HTTP 响应
+ *   Content-Range 中的总长度；返回的数据短于请求的大小不会被当作文件结束）
+ *
+ * 返回请求是否仍在等待；请求已超时或任务已结束时返回 false，计入迟到响应。
+ */
+export declare function completeStreamRequest(requestId: number, data?: Buffer | undefined | null, error?: string | undefined | null, totalSize?: number | undefined | null): boolean
+/** 一个流式请求的响应（见 completeStreamRequests） */
+export interface StreamResponse {
+  /** 请求 ID */
+  requestId: number
+  /** 获取到的数据 */
+  data?: Buffer
+  /** 错误信息（如果获取失败） */
+  error?: string
+  /** 文件总长度（见 completeStreamRequest 的 totalSize） */
+  totalSize?: number
+}
+/**
+ * 一次完成多个流式请求
+ *
+ * 与逐个调用 `completeStreamRequest` 相同，但只需一次 JS→Rust 调用；适合 fetcher 把同一时刻
+ * 到达的多个块（例如一次 HTTP multipart 响应）一起交回。返回仍在等待的请求数，其余计入迟到响应。
+ */
+export declare function completeStreamRequests(responses: Array<StreamResponse>): number
+/** 流式请求状态，用于发现 fetcher 丢失的请求 */
+export interface StreamRequestStats {
+  /** 本实例进行中的流式任务数 */
+  activeTasks: number
+  /** 本实例正在等待 JS 响应的请求数 */
+  pendingRequests: number
+  /** 单个任务最多同时等待的请求数，超出时读取失败 */
+  maxPendingPerTask: number
+  /** 进程内累计的孤立请求数（等待超时或任务结束时仍未响应） */
+  orphanedRequests: number
+  /** 进程内累计的迟到响应数（请求已超时或任务已结束后才调用 completeStreamRequest） */
+  lateResponses: number
+}
+/**
+ * 获取流式请求状态
+ *
+ * `orphanedRequests` 持续增长说明 fetcher 丢弃了请求而没有回调
+ * `completeStreamRequest`；`lateResponses` 增长说明 fetcher 响应太慢。
+ */
+export declare function getStreamRequestStats(): StreamRequestStats
+/** 流式任务全局并发限制 */
+export interface StreamLimitOptions {
+  /** 最多同时活动的流式任务数（不传表示不限制） */
+  maxActiveStreams?: number
+  /** 最多排队的流式任务数，排满后新任务直接失败（不传表示不限制） */
+  maxQueuedStreams?: number
+}
+/** 流式任务并发状态 */
+export interface StreamLimitStats {
+  /** 活动任务数 */
+  active: number
+  /** 排队任务数 */
+  queued: number
+  /** 当前的最大活动任务数 */
+  maxActiveStreams?: number
+  /** 当前的最大排队任务数 */
+  maxQueuedStreams?: number
+}
+/**
+ * 设置流式任务的全局并发限制
+ *
+ * 每个活动的流式任务会占用最多 16 MB 的块缓存和一个阻塞线程。
+ * 超出 `maxActiveStreams` 的任务异步排队，直到有任务结束。
+ */
+export declare function configureStreamLimits(options: StreamLimitOptions): void
+/** 获取流式任务的并发状态 */
+export declare function getStreamLimitStats(): StreamLimitStats
+/** 渲染结果缓存容量 */
+export interface RenderCacheOptions {
+  /** 最多缓存的页数（默认 256） */
+  maxEntries?: number
+  /** 缓存数据的总字节数上限，传 0 关闭缓存 */
+  maxBytes: number
+}
+/** 渲染结果缓存统计 */
+export interface RenderCacheStats {
+  /** 是否已启用 */
+  enabled: boolean
+  /** 当前缓存的页数 */
+  entries: number
+  /** 当前缓存数据的总字节数 */
+  bytes: number
+  /** 最多缓存的页数 */
+  maxEntries: number
+  /** 总字节数上限 */
+  maxBytes: number
+  /** 命中次数 */
+  hits: number
+  /** 未命中次数 */
+  misses: number
+  /** 因容量不足被淘汰的页数 */
+  evictions: number
+}
+/**
+ * 启用或调整进程级的渲染结果缓存（LRU），传 null 关闭并清空
+ *
+ * 同一文档（Buffer 按长度和首尾内容识别，文件按路径、大小和修改时间识别）、同一页、
+ * 同样配置的渲染结果直接从缓存返回，结果的 cached 为 true。适合查看器在页面之间来回跳转。
+ * 流式渲染和原始位图不经过缓存；因内存水位降级渲染的页面不会写入缓存。
+ */
+export declare function configureRenderCache(options?: RenderCacheOptions | undefined | null): void
+/** 获取渲染结果缓存的统计信息 */
+export declare function getRenderCacheStats(): RenderCacheStats
+/** 清空渲染结果缓存（保留容量设置），返回清除的页数 */
+export declare function clearRenderCache(): number
+/** 软内存水位设置 */
+export interface MemoryWatermarkOptions {
+  /** 常驻内存（RSS）上限（字节） */
+  rssBytes: number
+  /** 超出水位后渲染宽度乘以该系数（0.1-1，默认 0.5） */
+  widthFactor?: number
+  /** 降级后的最小渲染宽度（像素，默认 640） */
+  minWidth?: number
+}
+/** 内存水位状态 */
+export interface MemoryStatus {
+  /** 当前进程的常驻内存（字节），当前平台读取不到时为空 */
+  residentBytes?: number
+  /** 当前水位（字节），未设置时为空 */
+  watermarkBytes?: number
+  /** 是否超出水位（新开始的页面会降级渲染） */
+  overWatermark: boolean
+  /** 进程启动以来降级渲染的页数 */
+  degradedPages: number
+  /** 空闲回收的 TTL（毫秒），未启用时为空 */
+  idleTtlMs?: number
+  /** 进行中的任务数（所有模块实例） */
+  activeTasks: number
+  /** 进程内没有进行中任务的时长（毫秒） */
+  idleMs: number
+  /** 空闲回收和 trimMemory 清除的渲染缓存页数（进程启动以来） */
+  reapedCacheEntries: number
+  /** 空闲回收和 trimMemory 清除的渲染缓存字节数 */
+  reapedCacheBytes: number
+  /** 把空闲堆内存归还给操作系统的次数 */
+  allocatorTrims: number
+}
+/** 空闲回收设置 */
+export interface IdleReaperOptions {
+  /**
+   * 空闲时间（毫秒）：渲染缓存中超过该时间未使用的页面被清除，进程内没有进行中的任务
+   * 超过该时间后把空闲堆内存归还给操作系统
+   */
+  idleTtlMs: number
+}
+/** trimMemory 的结果 */
+export interface TrimMemoryResult {
+  /** 清除的渲染缓存页数 */
+  cacheEntries: number
+  /** 清除的渲染缓存字节数 */
+  cacheBytes: number
+  /** 是否把空闲堆内存归还给了操作系统（仅 glibc 上执行） */
+  allocatorTrimmed: boolean
+  /** 回收前后的常驻内存（字节），当前平台读取不到时为空 */
+  residentBefore?: number
+  residentAfter?: number
+}
+/**
+ * 设置进程级的软内存水位，传 null 取消
+ *
+ * 常驻内存超出水位后，之后开始的页面不会失败，而是按 `widthFactor` 缩小渲染宽度、
+ * 改用最快的编码参数（WebP method 0、PNG 压缩级别 1），结果的 renderInfo.memoryDegraded 为 true。
+ * 内存回落到水位以下后自动恢复。常驻内存读取自 /proc，其他平台不会降级。
+ */
+export declare function setMemoryWatermark(options?: MemoryWatermarkOptions | undefined | null): void
+/** 获取当前常驻内存和水位状态 */
+export declare function getMemoryStatus(): MemoryStatus
+/**
+ * 设置空闲回收，传 null 或 idleTtlMs 为 0 时关闭
+ *
+ * 常驻的 worker 在流量高峰之间空闲时，后台线程定期清除超过 TTL 未使用的渲染缓存，
+ * 进程内空闲超过 TTL 后把空闲堆内存归还给操作系统。预加载的文档（preloadDocument）
+ * 由调用方管理，不会被回收。
+ * 设置是进程级的，所有模块实例共享。
+ */
+export declare function configureIdleReaper(options?: IdleReaperOptions | undefined | null): void
+/**
+ * 立即回收空闲资源
+ *
+ * 清空渲染缓存（保留容量设置），并把空闲堆内存归还给操作系统。适合在流量高峰结束、或收到内存告警时调用。
+ */
+export declare function trimMemory(): TrimMemoryResult
+/** PDFium 动态库搜索设置 */
+export interface PdfiumLibraryOptions {
+  /** 依次尝试的库文件名（如 `libpdfium.so.1`），也可以是完整路径 */
+  names?: Array<string>
+  /** 在这些目录中查找 `names`（按顺序）；不传时按文件名交给系统加载器搜索 */
+  dirs?: Array<string>
+  /** 自定义候选都失败后是否继续尝试默认位置（当前目录和系统库，默认 true） */
+  includeDefaults?: boolean
+  /** 库曾经加载成功、之后绑定失败（如容器层变化导致库文件丢失）时的自动重试次数（默认 2，0 表示不重试） */
+  rebindAttempts?: number
+  /** 重试间隔（毫秒，第 n 次重试等待 n 倍，默认 100） */
+  rebindDelayMs?: number
+}
+/**
+ * 设置 PDFium 动态库的文件名和搜索顺序
+ *
+ * 默认只查找平台标准库名（如 `libpdfium.so`），无法加载发行版自带的
+ * `libpdfium.so.1` 或改过名的库。设置后对之后新建的 PDFium 实例生效（已常驻的库被放弃、
+ * 按新的顺序重新探测），可以用 `getDiagnostics` 查看实际的探测顺序和结果。
+ */
+export declare function configurePdfiumLibrary(options: PdfiumLibraryOptions): void
+/**
+ * 注册命名的渲染配置，替换之前注册的全部配置
+ *
+ * `json` 是以配置名为键、RenderOptions 为值的 JSON 对象，如
+ * `{"preview":{"targetWidth":640,"format":"jpg"},"archive":{"dpi":300,"format":"png"}}`。
+ * 之后的调用通过 `profile` 选项引用，调用中设置的选项覆盖配置中的值。
+ * 配置在进程内共享；JSON 无效、选项类型不对或包含 correlationId、cancelToken 时抛出异常，
+ * 已注册的配置保持不变。返回注册的配置名（按名称排序）。
+ */
+export declare function loadProfiles(json: string): Array<string>
+/** initPdfium 的结果 */
+export interface PdfiumInitInfo {
+  /** 实际加载的库路径 */
+  loadedFrom?: string
+  /** 绑定耗时（毫秒） */
+  bindTime: number
+  /** 测试渲染耗时（毫秒） */
+  testRenderTime: number
+}
+/**
+ * 立即加载 PDFium 库并常驻进程
+ *
+ * 传入 `libPath` 时只使用该路径的库（不再回退到当前目录和系统库），适合 Electron、
+ * pkg 等把库打包在自定义位置的应用；不传时按 `configurePdfiumLibrary` 的搜索顺序加载。
+ * 加载成功后执行一次测试渲染，之后的调用直接绑定到这个库，不再探测和重新加载。
+ * 加载或测试渲染失败时抛出 `PDFIUM_UNAVAILABLE` 错误。
+ */
+export declare function initPdfium(libPath?: string | undefined | null): PdfiumInitInfo
+/**
+ * 预加载文档并常驻内存，返回文档 ID
+ *
+ * 文档数据（文件路径或内存数据）和打开时成功的密码在 `releaseDocument` 之前一直保留在原生内存中，
+ * 之后的渲染不再依次尝试候选密码。常驻的不是解析后的文档：每次 `renderPreloadedPages`
+ * 都绑定 PDFium 并重新解析文档，请求之间不占用 PDFium，其他渲染调用照常进行。
+ * 适合查看器会话按需渲染任意页面和缩放级别。`options` 只用于检查文件大小限制和提供候选密码（passwords）。
+ */
+export declare function preloadDocument(input: string | Buffer, options?: RenderOptions): number
+/**
+ * 渲染预加载文档的页面
+ *
+ * 在后台线程中渲染，返回 Promise。同一文档的请求按提交顺序依次处理，
+ * 不同文档之间互不阻塞。
+ */
+export declare function renderPreloadedPages(documentId: number, pageNums: Array<number>, options?: RenderOptions | undefined | null): Promise<RenderResult>
+/**
+ * 释放预加载的文档，返回文档是否存在
+ *
+ * 进行中的渲染会正常完成，之后文档才被销毁。
+ */
+export declare function releaseDocument(documentId: number): boolean
+/** 渲染调度器选项 */
+export interface SchedulerOptions {
+  /** 每个租户同时渲染的最大页数（默认不限制） */
+  tenantConcurrency?: number
+  /**
+   * 每个租户在统计窗口内的 CPU 时间预算（毫秒，默认不限制）
+   *
+   * 超出预算的租户不会被拒绝，只在其他租户都没有可运行的任务时才调度。
+   */
+  tenantCpuTimeMs?: number
+  /** CPU 时间的统计窗口（毫秒，默认 60000） */
+  tenantWindowMs?: number
+  /** 按租户覆盖 tenantConcurrency / tenantCpuTimeMs */
+  tenantQuotas?: Array<TenantQuota>
+}
+/** 单个租户的限额 */
+export interface TenantQuota {
+  /** 租户标记（与 RenderOptions.tenant 一致） */
+  tenant: string
+  /** 同时渲染的最大页数 */
+  concurrency?: number
+  /** 统计窗口内的 CPU 时间预算（毫秒） */
+  cpuTimeMs?: number
+}
+/** 调度任务的渲染结果（通过回调逐页返回） */
+export interface ScheduledPageResult {
+  /** scheduleRender 返回的任务 ID */
+  jobId: number
+  /** 同一次 scheduleRender 调用的页面共享同一个文档 ID */
+  documentId: number
+  /** 任务优先级 */
+  priority: number
+  /** 租户标记 */
+  tenant?: string
+  /** 入队到开始渲染的等待时间（毫秒） */
+  queueTime: number
+  /** 页面结果 */
+  page: PageResult
+}
+/** 渲染调度器统计 */
+export interface SchedulerStats {
+  /** 排队中的任务数 */
+  queued: number
+  /** 正在渲染的任务数 */
+  running: number
+  /** 已完成的任务数 */
+  completed: number
+  /** 有排队、运行中任务或预算用量的租户（按租户标记排序，未标记的任务在最前） */
+  tenants: Array<TenantStats>
+}
+/** 单个租户的调度统计 */
+export interface TenantStats {
+  /** 租户标记（未标记的任务为空） */
+  tenant?: string
+  queued: number
+  running: number
+  /** 当前统计窗口内已用的 CPU 时间（毫秒） */
+  cpuTimeMs: number
+  /** 是否超出 CPU 时间预算 */
+  overBudget: boolean
+}
+/** 关闭调度器选项 */
+export interface CloseSchedulerOptions {
+  /** 是否取消排队中的任务（以 CANCELLED 错误码回调，默认 false：处理完队列后关闭） */
+  cancelPending?: boolean
+}
+/**
+ * 创建跨文档的优先级渲染调度器
+ *
+ * 多个文档的页面可以放入同一个调度器，工作线程按优先级（数值大的先渲染，
+ * 相同优先级按入队顺序）取任务，每页完成后调用 `callback(err, result)`。
+ * 多个租户共用调度器时，可以用 tenantConcurrency / tenantCpuTimeMs 限制单个租户
+ * 同时渲染的页数和 CPU 时间，避免大批量任务拖慢其他租户。
+ * 调度器持有回调，不再使用时需要调用 `closeRenderScheduler`，否则进程不会退出。
+ */
+export declare function createRenderScheduler(options: SchedulerOptions | undefined | null, callback: (err: Error | null, result: ScheduledPageResult) => void): number
+/**
+ * 把一个文档的若干页放入调度器，返回各页的任务 ID（与 pageNums 顺序一致）
+ *
+ * `input` 为文件路径或 PDF Buffer。同一次调用的页面共享文档和渲染选项，
+ * 工作线程会复用最近打开的文档。`options.tenant` 标记任务所属的租户。
+ */
+export declare function scheduleRender(schedulerId: number, input: string | Buffer, pageNums: number[], priority?: number, options?: RenderOptions): Array<number>
+/** 查询调度器的队列状态 */
+export declare function getRenderSchedulerStats(schedulerId: number): SchedulerStats
+/**
+ * 关闭调度器，返回被取消的排队任务数
+ *
+ * 关闭后不再接收新任务。正在渲染的页面会正常完成并回调；排队中的任务
+ * 默认继续处理，`cancelPending` 为 true 时立即以 CANCELLED 错误码回调。
+ */
+export declare function closeRenderScheduler(schedulerId: number, options?: CloseSchedulerOptions | undefined | null): number
+/** 批量渲染会话状态 */
+export interface RenderSessionStatus {
+  /** running、paused、completed、cancelled 或 failed */
+  status: string
+  /** 本次会话要渲染的页数（页码为空时在文档加载后确定） */
+  total: number
+  /** 已完成的页数（包括失败的页面） */
+  completed: number
+  /** 尚未开始渲染的页数 */
+  pending: number
+  /** 被取消的页数 */
+  cancelled: number
+  /** 已完成、尚未被 pollRenderSession 取走的页数 */
+  ready: number
+  /** PDF 总页数（文档加载前为 0） */
+  numPages: number
+  /** 文档加载失败的原因 */
+  error?: string
+  /** 渲染已结束且所有结果都已取走，之后不会再有新结果 */
+  done: boolean
+}
+/** 一次轮询取到的页面 */
+export interface RenderSessionPoll {
+  /** 按完成顺序排列的页面结果 */
+  pages: Array<PageResult>
+  /** 轮询后的会话状态 */
+  status: RenderSessionStatus
+}
+/**
+ * 开始批量渲染会话，返回会话 ID
+ *
+ * 在后台线程中按顺序逐页渲染 `pageNums`（空数组表示全部页面），会话状态保存在原生侧。
+ * 不传 `callback` 时结果暂存，由 `pollRenderSession` 取走；传入时每页完成后调用
+ * `callback(err, page)`，不再暂存。渲染过程中可以暂停、恢复或取消剩余页面，
+ * 适合用户随时可能放弃的整份文档渲染任务。
+ */
+export declare function startRenderSession(input: string | Buffer, pageNums: number[], options?: RenderOptions | undefined | null, callback?: (err: Error | null, page: PageResult) => void): number
+/**
+ * 取走会话中已完成的页面（最多 `maxPages` 个，默认全部）
+ *
+ * 返回的 `status.done` 为 true 时会话已结束且没有剩余结果，会话随之释放，
+ * 之后再访问该 ID 会报错。
+ */
+export declare function pollRenderSession(sessionId: number, maxPages?: number | undefined | null): RenderSessionPoll
+/** 查询会话状态（不取走结果） */
+export declare function getRenderSessionStatus(sessionId: number): RenderSessionStatus
+/**
+ * 暂停会话：正在渲染的页面完成后不再开始新页面。返回状态是否改变
+ *
+ * 暂停期间会话释放 PDFium 和文档，不影响其他渲染调用；恢复后重新打开文档继续渲染。
+ */
+export declare function pauseRenderSession(sessionId: number): boolean
+/** 恢复暂停的会话，返回状态是否改变 */
+export declare function resumeRenderSession(sessionId: number): boolean
+/**
+ * 取消会话的剩余页面，返回取消的页数
+ *
+ * 正在渲染的一页仍会完成；已完成的页面保留，仍可通过 `pollRenderSession` 取走。
+ */
+export declare function cancelRenderSession(sessionId: number): number
+/**
+ * 创建取消令牌，放进 options.cancelToken 后可以用 `cancelRender` 中止渲染
+ *
+ * 令牌用完后调用 `releaseCancelToken` 释放。
+ */
+export declare function createCancelToken(): number
+/**
+ * 取消使用该令牌的渲染，返回令牌是否存在
+ *
+ * 正在渲染的一页仍会完成；尚未开始和等待编码的页面以 CANCELLED 错误码返回。
+ * 令牌被取消后，之后使用它的渲染也会立即结束。
+ */
+export declare function cancelRender(token: number): boolean
+/**
+ * 令牌是否已被取消（令牌不存在时为 false）
+ *
+ * 用于不经过多页渲染的调用方（如逐页渲染原始位图的工作线程）在页与页之间检查。
+ */
+export declare function isRenderCancelled(token: number): boolean
+/** 释放取消令牌，返回令牌是否存在 */
+export declare function releaseCancelToken(token: number): boolean
+/** 关闭选项 */
+export interface ShutdownOptions {
+  /** 等待进行中任务完成的最长时间（毫秒，默认 30000） */
+  drainTimeoutMs?: number
+}
+/** 关闭结果 */
+export interface ShutdownResult {
+  /** 是否所有进行中的任务都已完成 */
+  drained: boolean
+  /** 关闭结束时仍未完成的任务数 */
+  remaining: number
+  /** 因超时被取消的流式任务数 */
+  cancelledStreams: number
+  /** 因超时被取消的调度任务数（排队中、尚未开始渲染的页面） */
+  cancelledJobs: number
+  /** 因超时被取消的会话页面数（包括暂停中的会话） */
+  cancelledSessionPages: number
+  /** 关闭耗时（毫秒） */
+  elapsed: number
+}
+/**
+ * 优雅关闭渲染器
+ *
+ * 立即停止接收新任务（新调用会返回 "Renderer is shutting down" 错误），
+ * 然后等待进行中的渲染、流式任务和调度器中已排队的任务完成。超过 `drainTimeoutMs` 后，
+ * 仍在等待数据的流式任务和调度器中尚未开始的任务会被取消。PDFium 实例随每个任务创建和释放，
+ * 任务全部结束后不再持有任何 PDFium 资源。
+ *
+ * 关闭是单向的，用于滚动发布时进程退出前的清理。只作用于调用所在的
+ * JS 环境（主线程或某个 worker），其他 worker 中加载的模块实例不受影响。
+ */
+export declare function shutdown(options?: ShutdownOptions | undefined | null): Promise<ShutdownResult>
+/**
+ * 常驻文档句柄
+ *
+ * 与 `preloadDocument` 相同，文档来源和打开结果（页数、成功的密码）由专属线程持有，
+ * 之后的渲染和查询不再尝试候选密码，流式来源已获取的块不再重新下载，适合查看器
+ * 从同一个大文件逐页渲染。句柄被垃圾回收或调用 `close()` 后释放文档，进行中的请求会正常完成。
+ *
+ * 句柄不保留解析后的文档：文档线程只在处理请求时绑定 PDFium 并重新解析文档（使用
+ * 打开时成功的密码），请求之间不占用 PDFium，句柄打开期间其他渲染调用照常进行。
+ * 打开方法需要先解析一遍文档，都返回 Promise，不在 JS 线程上等待。打开选项中的
+ * `documentIdleTimeoutMs` 让空闲的句柄清空流式块缓存。
  */
-export declare function completeStreamRequest(requestId: number, data?: Buffer | undefined | null, error?: string | undefined | null): void
+export class PdfDocumentHandle {
+  /** 打开 PDF 文件，返回 Promise（`options` 只用于检查文件大小限制和提供密码） */
+  static openFromFile(filePath: string, options?: RenderOptions | undefined | null): Promise<PdfDocumentHandle>
+  /** 打开内存中的 PDF，返回 Promise（数据会复制一份，之后可以释放传入的 Buffer） */
+  static openFromBuffer(pdfBuffer: Buffer, options?: RenderOptions | undefined | null): Promise<PdfDocumentHandle>
+  /**
+   * 从流式数据源打开 PDF，返回 Promise
+   *
+   * `pdfSize`、`fetcher`、`seedBlocks` 与 `renderPagesFromStream` 相同。文档存活期间
+   * 渲染用到的数据仍按需通过 fetcher 获取（已获取的块会缓存），fetcher 取到数据后
+   * 调用 `completeStreamRequest`。fetcher 不会阻止进程退出。
+   *
+   * 块缓存在多次 `renderPages` 之间共享：每次渲染仍重新解析文档，但 xref 表和页面树
+   * 所在的块已经缓存，不再重新下载；`streamBlockSize` / `streamMaxCacheBlocks` 等在打开时生效。
+   */
+  static openFromStream(pdfSize: number, options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number, correlationId?: string) => void, seedBlocks?: SeedBlock[] | null): Promise<PdfDocumentHandle>
+  /** 文档总页数 */
+   pageCount(): number
+  /** 成功打开文档的密码在 passwords 中的下标（文档未加密时为空） */
+  get passwordIndex(): number | null
+  /** 句柄是否已关闭 */
+  get closed(): boolean
+  /** 渲染页面，返回 Promise；同一文档的请求按提交顺序依次处理 */
+   renderPages(pageNums: Array<number>, options?: RenderOptions | undefined | null): Promise<RenderResult>
+  /** 单页概况（尺寸、旋转、标签、是否有文字和图片），返回 Promise */
+   getPageInfo(pageNum: number): Promise<PageOverview>
+  /** 释放文档，返回句柄此前是否打开；之后调用其他方法会报错 */
+   close(): boolean
+}
@@ -310,16 +310,110 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { renderPages, renderPagesFromFile, getPageCountFromFile, getPageCount, renderPageToRawBitmap, renderPageToRawBitmapFromBuffer, isPdfiumAvailable, warmup, getVersion, renderPagesFromStream, completeStreamRequest } = nativeBinding
+const { renderPages, renderPagesAsync, renderPagesFromFile, renderBatch, renderPagesToFiles, renderMultipageTiff, resolvePageRanges, getPageCountFromFile, getPageCount, getPageLayout, getPageLayoutFromFile, getPageFigures, getPageFiguresFromFile, getDocumentLayout, getDocumentLayoutFromFile, getDocumentInfo, getDocumentInfoFromFile, getAttachments, getAttachmentsFromFile, getAttachment, getAttachmentFromFile, getPageInfo, getPageInfoFromFile, getPageTextRects, getPageTextRectsFromFile, getPageAnnotations, getPageAnnotationsFromFile, getFormFields, getFormFieldsFromFile, getPageImages, getPageImagesFromFile, getTextStats, getTextStatsFromFile, renderSpreads, renderSpreadsFromFile, renderThumbnailStrip, renderThumbnailStripFromFile, getEmbeddedThumbnails, getEmbeddedThumbnailsFromFile, findDuplicatePages, findDuplicatePagesFromFile, renderComposedPages, extractPages, extractPagesAsPdf, renderPageRegion, renderPageTiles, compareDocuments, getOutlineAnchors, getOutlineAnchorsFromFile, getOutline, getOutlineFromFile, renderPageToSvg, renderPageToSvgFromFile, renderPageToRawBitmap, renderPageToRawBitmapFromBuffer, renderPageToRawChunks, completeRawChunk, getDiagnostics, getRecentRenders, dumpRecentRenders, setCrashDumpPath, isPdfiumAvailable, warmup, warmupWithSample, getVersion, generateTestPdf, registerEncoder, unregisterEncoder, completeEncodeRequest, getEncoderCapabilities, renderPagesFromStream, completeStreamRequest, completeStreamRequests, getStreamRequestStats, configureStreamLimits, getStreamLimitStats, configureRenderCache, getRenderCacheStats, clearRenderCache, setMemoryWatermark, getMemoryStatus, configureIdleReaper, trimMemory, configurePdfiumLibrary, loadProfiles, initPdfium, preloadDocument, renderPreloadedPages, releaseDocument, PdfDocumentHandle, createRenderScheduler, scheduleRender, getRenderSchedulerStats, closeRenderScheduler, startRenderSession, pollRenderSession, getRenderSessionStatus, pauseRenderSession, resumeRenderSession, cancelRenderSession, createCancelToken, cancelRender, isRenderCancelled, releaseCancelToken, shutdown } = nativeBinding
 
 module.exports.renderPages = renderPages
+module.exports.renderPagesAsync = renderPagesAsync
 module.exports.renderPagesFromFile = renderPagesFromFile
+module.exports.renderBatch = renderBatch
+module.exports.renderPagesToFiles = renderPagesToFiles
+module.exports.renderMultipageTiff = renderMultipageTiff
+module.exports.resolvePageRanges = resolvePageRanges
 module.exports.getPageCountFromFile = getPageCountFromFile
 module.exports.getPageCount = getPageCount
+module.exports.getPageLayout = getPageLayout
+module.exports.getPageLayoutFromFile = getPageLayoutFromFile
+module.exports.getPageFigures = getPageFigures
+module.exports.getPageFiguresFromFile = getPageFiguresFromFile
+module.exports.getDocumentLayout = getDocumentLayout
+module.exports.getDocumentLayoutFromFile = getDocumentLayoutFromFile
+module.exports.getDocumentInfo = getDocumentInfo
+module.exports.getDocumentInfoFromFile = getDocumentInfoFromFile
+module.exports.getAttachments = getAttachments
+module.exports.getAttachmentsFromFile = getAttachmentsFromFile
+module.exports.getAttachment = getAttachment
+module.exports.getAttachmentFromFile = getAttachmentFromFile
+module.exports.getPageInfo = getPageInfo
+module.exports.getPageInfoFromFile = getPageInfoFromFile
+module.exports.getPageTextRects = getPageTextRects
+module.exports.getPageTextRectsFromFile = getPageTextRectsFromFile
+module.exports.getPageAnnotations = getPageAnnotations
+module.exports.getPageAnnotationsFromFile = getPageAnnotationsFromFile
+module.exports.getFormFields = getFormFields
+module.exports.getFormFieldsFromFile = getFormFieldsFromFile
+module.exports.getPageImages = getPageImages
+module.exports.getPageImagesFromFile = getPageImagesFromFile
+module.exports.getTextStats = getTextStats
+module.exports.getTextStatsFromFile = getTextStatsFromFile
+module.exports.renderSpreads = renderSpreads
+module.exports.renderSpreadsFromFile = renderSpreadsFromFile
+module.exports.renderThumbnailStrip = renderThumbnailStrip
+module.exports.renderThumbnailStripFromFile = renderThumbnailStripFromFile
+module.exports.getEmbeddedThumbnails = getEmbeddedThumbnails
+module.exports.getEmbeddedThumbnailsFromFile = getEmbeddedThumbnailsFromFile
+module.exports.findDuplicatePages = findDuplicatePages
+module.exports.findDuplicatePagesFromFile = findDuplicatePagesFromFile
+module.exports.renderComposedPages = renderComposedPages
+module.exports.extractPages = extractPages
+module.exports.extractPagesAsPdf = extractPagesAsPdf
+module.exports.renderPageRegion = renderPageRegion
+module.exports.renderPageTiles = renderPageTiles
+module.exports.compareDocuments = compareDocuments
+module.exports.getOutlineAnchors = getOutlineAnchors
+module.exports.getOutlineAnchorsFromFile = getOutlineAnchorsFromFile
+module.exports.getOutline = getOutline
+module.exports.getOutlineFromFile = getOutlineFromFile
+module.exports.renderPageToSvg = renderPageToSvg
+module.exports.renderPageToSvgFromFile = renderPageToSvgFromFile
 module.exports.renderPageToRawBitmap = renderPageToRawBitmap
 module.exports.renderPageToRawBitmapFromBuffer = renderPageToRawBitmapFromBuffer
+module.exports.renderPageToRawChunks = renderPageToRawChunks
+module.exports.completeRawChunk = completeRawChunk
+module.exports.getDiagnostics = getDiagnostics
+module.exports.getRecentRenders = getRecentRenders
+module.exports.dumpRecentRenders = dumpRecentRenders
+module.exports.setCrashDumpPath = setCrashDumpPath
 module.exports.isPdfiumAvailable = isPdfiumAvailable
 module.exports.warmup = warmup
+module.exports.warmupWithSample = warmupWithSample
 module.exports.getVersion = getVersion
+module.exports.generateTestPdf = generateTestPdf
+module.exports.registerEncoder = registerEncoder
+module.exports.unregisterEncoder = unregisterEncoder
+module.exports.completeEncodeRequest = completeEncodeRequest
+module.exports.getEncoderCapabilities = getEncoderCapabilities
 module.exports.renderPagesFromStream = renderPagesFromStream
 module.exports.completeStreamRequest = completeStreamRequest
+module.exports.completeStreamRequests = completeStreamRequests
+module.exports.getStreamRequestStats = getStreamRequestStats
+module.exports.configureStreamLimits = configureStreamLimits
+module.exports.getStreamLimitStats = getStreamLimitStats
+module.exports.configureRenderCache = configureRenderCache
+module.exports.getRenderCacheStats = getRenderCacheStats
+module.exports.clearRenderCache = clearRenderCache
+module.exports.setMemoryWatermark = setMemoryWatermark
+module.exports.getMemoryStatus = getMemoryStatus
+module.exports.configureIdleReaper = configureIdleReaper
+module.exports.trimMemory = trimMemory
+module.exports.configurePdfiumLibrary = configurePdfiumLibrary
+module.exports.loadProfiles = loadProfiles
+module.exports.initPdfium = initPdfium
+module.exports.preloadDocument = preloadDocument
+module.exports.renderPreloadedPages = renderPreloadedPages
+module.exports.releaseDocument = releaseDocument
+module.exports.PdfDocumentHandle = PdfDocumentHandle
+module.exports.createRenderScheduler = createRenderScheduler
+module.exports.scheduleRender = scheduleRender
+module.exports.getRenderSchedulerStats = getRenderSchedulerStats
+module.exports.closeRenderScheduler = closeRenderScheduler
+module.exports.startRenderSession = startRenderSession
+module.exports.pollRenderSession = pollRenderSession
+module.exports.getRenderSessionStatus = getRenderSessionStatus
+module.exports.pauseRenderSession = pauseRenderSession
+module.exports.resumeRenderSession = resumeRenderSession
+module.exports.cancelRenderSession = cancelRenderSession
+module.exports.createCancelToken = createCancelToken
+module.exports.cancelRender = cancelRender
+module.exports.isRenderCancelled = isRenderCancelled
+module.exports.releaseCancelToken = releaseCancelToken
+module.exports.shutdown = shutdown
@@ -0,0 +1,277 @@
+//! 页面版面分析
+//!
+//! 基于 PDFium 提供的对象几何信息做粗粒度的版面切分：
+//! 相邻的文字对象合并为文本块，图片对象单独作为图片区域。
+//! 所有坐标均为渲染图像坐标系（左上角为原点，单位像素）。
+
+/// 轴对齐矩形（像素坐标，左上角原点）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Rect {
+    /// 由任意两个对角点构造矩形
+    pub fn from_corners(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self {
+            left: x1.min(x2),
+            top: y1.min(y2),
+            right: x1.max(x2),
+            bottom: y1.max(y2),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+
+    /// 向四周扩展后的矩形
+    fn expand(&self, dx: f32, dy: f32) -> Rect {
+        Rect {
+            left: self.left - dx,
+            top: self.top - dy,
+            right: self.right + dx,
+            bottom: self.bottom + dy,
+        }
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.left <= other.right
+            && other.left <= self.right
+            && self.top <= other.bottom
+            && other.top <= self.bottom
+    }
+
+    /// 裁剪到 [0, width] x [0, height] 范围内
+    pub fn clamp(&self, width: f32, height: f32) -> Rect {
+        Rect {
+            left: self.left.clamp(0.0, width),
+            top: self.top.clamp(0.0, height),
+            right: self.right.clamp(0.0, width),
+            bottom: self.bottom.clamp(0.0, height),
+        }
+    }
+}
+
+/// 水平方向合并间距（相对行高）
+const HORIZONTAL_GAP_FACTOR: f32 = 1.0;
+/// 垂直方向合并间距（相对行高），大约一个行距
+const VERTICAL_GAP_FACTOR: f32 = 0.6;
+
+/// 将文字对象的外接矩形聚合为文本块
+///
+/// 每个矩形按自身高度（近似行高）向四周扩展，扩展后相交的矩形
+/// 视为同一块。返回的文本块按阅读顺序（先上后下、先左后右）排列。
+pub fn cluster_text_blocks(rects: &[Rect]) -> Vec<Rect> {
+    let n = rects.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let expanded: Vec<Rect> = rects
+        .iter()
+        .map(|r| {
+            let line_height = r.height().max(1.0);
+            r.expand(
+                line_height * HORIZONTAL_GAP_FACTOR / 2.0,
+                line_height * VERTICAL_GAP_FACTOR / 2.0,
+            )
+        })
+        .collect();
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if expanded[i].intersects(&expanded[j]) {
+                let a = find(&mut parent, i);
+                let b = find(&mut parent, j);
+                if a != b {
+                    parent[a] = b;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Rect)> = Vec::new();
+    for (i, rect) in rects.iter().enumerate() {
+        let root = find(&mut parent, i);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, block)) => *block = block.union(rect),
+            None => groups.push((root, *rect)),
+        }
+    }
+
+    let mut blocks: Vec<Rect> = groups.into_iter().map(|(_, r)| r).collect();
+    sort_reading_order(&mut blocks);
+    blocks
+}
+
+/// 按阅读顺序排序（先上后下、先左后右）
+pub fn sort_reading_order(rects: &mut [Rect]) {
+    rects.sort_by(|a, b| {
+        a.top
+            .total_cmp(&b.top)
+            .then_with(|| a.left.total_cmp(&b.left))
+    });
+}
+
+/// 统计页面文字的字符数（不含空白和控制字符）和词数
+///
+/// 连续的字母数字计为一个词，中日韩文字每个字计为一个词。
+pub fn count_text(text: &str) -> (u32, u32) {
+    let mut chars = 0u32;
+    let mut words = 0u32;
+    let mut in_word = false;
+    for c in text.chars() {
+        if c.is_whitespace() || c.is_control() || c == '\u{fffe}' {
+            in_word = false;
+            continue;
+        }
+        chars += 1;
+        if is_cjk(c) {
+            words += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                words += 1;
+            }
+            in_word = true;
+        } else {
+            in_word = false;
+        }
+    }
+    (chars, words)
+}
+
+/// 把逐字的外接矩形合并为词，返回词和它的外接矩形
+///
+/// 空白和控制字符分隔词，中日韩文字每个字单独成词；标点与相邻的字母数字连在一起（如 "3.14"）。
+/// 没有空格但位置不连续的字（换行、与前一个字垂直方向不重叠或间距超过一个字高）也会结束当前词。
+/// 矩形使用图像坐标（top < bottom）。
+pub fn group_words(chars: impl IntoIterator<Item = (char, Rect)>) -> Vec<(String, Rect)> {
+    let mut words = Vec::new();
+    let mut current: Option<(String, Rect)> = None;
+    for (c, rect) in chars {
+        if c.is_whitespace() || c.is_control() || c == '\u{fffe}' {
+            words.extend(current.take());
+            continue;
+        }
+        // 没有大小的字（如零宽字符）不影响分词
+        if rect.width() <= 0.0 && rect.height() <= 0.0 {
+            continue;
+        }
+        let continues = current.as_ref().is_some_and(|(text, word)| {
+            let gap = word.height().max(rect.height());
+            let overlap = word.bottom.min(rect.bottom) - word.top.max(rect.top);
+            !text.chars().last().is_some_and(is_cjk)
+                && overlap > 0.0
+                && rect.left <= word.right + gap
+                && rect.left >= word.left - gap
+        });
+        if is_cjk(c) || !continues {
+            words.extend(current.take());
+        }
+        match &mut current {
+            Some((text, word)) => {
+                text.push(c);
+                *word = word.union(&rect);
+            }
+            None => current = Some((c.to_string(), rect)),
+        }
+    }
+    words.extend(current);
+    words
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一表意文字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // CJK 兼容表意文字
+        | 0x20000..=0x2FFFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_merges_lines_of_a_paragraph() {
+        // 两行紧邻的文字 + 远处的一行
+        let rects = [
+            Rect::from_corners(10.0, 10.0, 200.0, 22.0),
+            Rect::from_corners(10.0, 26.0, 180.0, 38.0),
+            Rect::from_corners(10.0, 300.0, 120.0, 312.0),
+        ];
+        let blocks = cluster_text_blocks(&rects);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0], Rect::from_corners(10.0, 10.0, 200.0, 38.0));
+        assert_eq!(blocks[1], rects[2]);
+    }
+
+    #[test]
+    fn test_cluster_keeps_columns_apart() {
+        // 左右两栏之间留有明显空白
+        let rects = [
+            Rect::from_corners(10.0, 10.0, 100.0, 22.0),
+            Rect::from_corners(10.0, 26.0, 100.0, 38.0),
+            Rect::from_corners(200.0, 10.0, 300.0, 22.0),
+            Rect::from_corners(200.0, 26.0, 300.0, 38.0),
+        ];
+        let blocks = cluster_text_blocks(&rects);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].left, 10.0);
+        assert_eq!(blocks[1].left, 200.0);
+    }
+
+    #[test]
+    fn test_count_text() {
+        assert_eq!(count_text("Hello, world!\r\n"), (12, 2));
+        assert_eq!(count_text("PDF 转图片 v2"), (8, 5));
+        assert_eq!(count_text(" \u{fffe}\t"), (0, 0));
+    }
+
+    #[test]
+    fn test_group_words() {
+        let glyph = |c: char, left: f32, top: f32| (c, Rect { left, top, right: left + 8.0, bottom: top + 10.0 });
+        let chars = vec![
+            glyph('P', 0.0, 0.0),
+            glyph('D', 8.0, 0.0),
+            glyph('F', 16.0, 0.0),
+            (' ', Rect { left: 24.0, top: 0.0, right: 28.0, bottom: 10.0 }),
+            glyph('转', 28.0, 0.0),
+            glyph('图', 36.0, 0.0),
+            // 换行后没有空格
+            glyph('v', 0.0, 20.0),
+            glyph('2', 8.0, 20.0),
+        ];
+        let words: Vec<(String, Rect)> = group_words(chars);
+        let texts: Vec<&str> = words.iter().map(|(text, _)| text.as_str()).collect();
+        assert_eq!(texts, ["PDF", "转", "图", "v2"]);
+        assert_eq!(words[0].1.right, 24.0);
+        assert_eq!(words[3].1.top, 20.0);
+    }
+}
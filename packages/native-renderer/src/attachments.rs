@@ -0,0 +1,107 @@
+//! 文档级附件（嵌入文件）
+//!
+//! 发票（ZUGFeRD / Factur-X 等）常把结构化的 XML 作为附件嵌入 PDF。这里列出文档
+//! EmbeddedFiles 名称树中的附件，并按序号取出附件内容。pdfium-render 没有封装附件的
+//! 日期，这部分直接调用 PDFium 的 FPDFAttachment 接口；当前绑定的 PDFium 版本没有读取
+//! 嵌入文件 /Subtype 的接口，MIME 类型按文件扩展名推断。
+
+use crate::config::ResourceLimits;
+use crate::doc_info;
+use crate::structure::read_string;
+use pdfium_render::prelude::*;
+use std::os::raw::c_int;
+
+/// 附件信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    /// 序号（从 0 开始，getAttachment 使用）
+    pub index: u32,
+    pub name: String,
+    /// 解压后的字节数
+    pub size: u64,
+    /// MIME 类型（按扩展名推断），无法识别时为空
+    pub mime_type: Option<String>,
+    /// 创建日期（ISO 8601，无法识别时为原始值）
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+}
+
+pub fn list(document: &PdfDocument) -> Vec<Attachment> {
+    let bindings = document.bindings();
+    let handle = bindings.get_handle_from_document(document);
+    document
+        .attachments()
+        .iter()
+        .enumerate()
+        .map(|(index, attachment)| {
+            let raw = bindings.FPDFDoc_GetAttachment(handle, index as c_int);
+            let string_value = |key: &str| {
+                read_string(|buf, len| bindings.FPDFAttachment_GetStringValue(raw, key, buf as *mut _, len))
+            };
+            let date = |key: &str| string_value(key).map(|value| doc_info::to_iso_date(&value).unwrap_or(value));
+            let name = attachment.name();
+            Attachment {
+                index: index as u32,
+                mime_type: mime_from_name(&name).map(str::to_string),
+                name,
+                size: attachment.len() as u64,
+                creation_date: date("CreationDate"),
+                modification_date: date("ModDate"),
+            }
+        })
+        .collect()
+}
+
+/// 取出附件内容，解压后的大小同样受 maxFileSize 限制
+pub fn data(document: &PdfDocument, index: u32, limits: &ResourceLimits) -> Result<Vec<u8>, String> {
+    let attachments = document.attachments();
+    let count = attachments.len() as u32;
+    if index >= count {
+        return Err(format!("Invalid attachment index: {} (total: {})", index, count));
+    }
+    let attachment = attachments
+        .get(index as PdfAttachmentIndex)
+        .map_err(|e| format!("Failed to get attachment: {}", e))?;
+    limits.check_file_size(attachment.len() as u64).map_err(|e| e.to_string())?;
+    attachment
+        .save_to_bytes()
+        .map_err(|e| format!("Failed to read attachment {}: {}", index, e))
+}
+
+/// 按扩展名推断常见附件的 MIME 类型
+fn mime_from_name(name: &str) -> Option<&'static str> {
+    let (_, extension) = name.rsplit_once('.')?;
+    Some(match extension.to_ascii_lowercase().as_str() {
+        // ZUGFeRD / Factur-X 规范使用 text/xml
+        "xml" => "text/xml",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "tif" | "tiff" => "image/tiff",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_from_name() {
+        assert_eq!(mime_from_name("factur-x.xml"), Some("text/xml"));
+        assert_eq!(mime_from_name("ZUGFeRD-invoice.XML"), Some("text/xml"));
+        assert_eq!(mime_from_name("scan.tar.zip"), Some("application/zip"));
+        assert_eq!(mime_from_name("README"), None);
+        assert_eq!(mime_from_name("data.bin"), None);
+    }
+}
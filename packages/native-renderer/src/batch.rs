@@ -0,0 +1,107 @@
+//! 多文档批量渲染
+//!
+//! 缩略图服务一次请求要处理几百个小 PDF，在 JS 中逐个调用渲染接口时，每个文档都要付出
+//! 一次 PDFium 初始化、任务调度和 JS↔Rust 往返的开销。批量渲染在一次调用中处理所有任务：
+//! 固定数量的读取线程提前把文件读入内存（最多领先渲染 `concurrency` 个任务），
+//! 渲染线程只初始化一次 PDFium，依次渲染读取完成的任务。
+//!
+//! PDFium 同一时间只能被一个线程使用，渲染本身是串行的；单个任务内的编码仍可以用
+//! encodeThreads 并行。
+
+use crate::config::RenderConfig;
+use crate::page_ranges::PageSelection;
+use crate::renderer::PdfRenderer;
+use crate::scheduler::DocumentSource;
+use crate::usage::{self, UsageMeter};
+use crate::PageResult;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 一个批量任务
+pub struct BatchJob {
+    pub source: DocumentSource,
+    pub pages: PageSelection,
+    pub config: RenderConfig,
+    /// options.resourceUsage 时的资源统计，渲染期间登记到渲染线程
+    pub meter: Option<Arc<UsageMeter>>,
+}
+
+/// 单个任务的结果
+pub struct JobOutcome {
+    /// (总页数, 各页结果, 密码下标)，或整个任务失败的原因
+    pub result: Result<(u32, Vec<PageResult>, Option<u32>), String>,
+    /// 读取文件的耗时
+    pub load_time: Duration,
+    /// 渲染（包括编码）的耗时
+    pub render_time: Duration,
+}
+
+/// 读取完成、等待渲染的任务
+struct Loaded {
+    index: usize,
+    job: BatchJob,
+    data: Result<Vec<u8>, String>,
+    load_time: Duration,
+}
+
+/// 渲染所有任务，结果与 `jobs` 顺序一致；PDFium 不可用时返回错误
+pub fn run(jobs: Vec<BatchJob>, concurrency: usize) -> Result<Vec<JobOutcome>, String> {
+    let pdfium = crate::create_pdfium().map_err(|e| e.reason)?;
+    let total = jobs.len();
+    let concurrency = concurrency.clamp(1, total.max(1));
+    let queue = Mutex::new(jobs.into_iter().enumerate().collect::<VecDeque<_>>());
+    let mut outcomes: Vec<Option<JobOutcome>> = (0..total).map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::sync_channel::<Loaded>(concurrency);
+        for _ in 0..concurrency {
+            let sender = sender.clone();
+            let queue = &queue;
+            scope.spawn(move || loop {
+                let Some((index, mut job)) = queue.lock().unwrap().pop_front() else { break };
+                let started = Instant::now();
+                let data = load(&mut job);
+                let loaded = Loaded {
+                    index,
+                    job,
+                    data,
+                    load_time: started.elapsed(),
+                };
+                if sender.send(loaded).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        for Loaded { index, job, data, load_time } in receiver {
+            let started = Instant::now();
+            let result = data.and_then(|data| {
+                let _usage = job.meter.as_ref().map(usage::attach);
+                PdfRenderer::new(&pdfium, job.config).render_from_buffer(&data, &job.pages)
+            });
+            outcomes[index] = Some(JobOutcome {
+                result,
+                load_time,
+                render_time: started.elapsed(),
+            });
+        }
+    });
+
+    Ok(outcomes.into_iter().map(|outcome| outcome.expect("every job is rendered")).collect())
+}
+
+/// 取出任务的文档数据；文件先按大小限制检查，超出时不读取
+fn load(job: &mut BatchJob) -> Result<Vec<u8>, String> {
+    match &mut job.source {
+        DocumentSource::Bytes(bytes) => Ok(std::mem::take(bytes)),
+        DocumentSource::File(path) => {
+            let size = std::fs::metadata(&*path)
+                .map_err(|e| format!("Failed to read PDF file {}: {}", path, e))?
+                .len();
+            job.config.limits.check_file_size(size).map_err(|e| e.to_string())?;
+            std::fs::read(&*path).map_err(|e| format!("Failed to read PDF file {}: {}", path, e))
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! 渲染取消令牌
+//!
+//! JS 侧用 `createCancelToken` 申请令牌，放进 options.cancelToken 传给渲染调用；
+//! `cancelRender` 置位后，渲染在页与页之间（以及编码之前）检查到取消，
+//! 剩余页面以 CANCELLED 错误码返回，已完成的页面照常返回。
+//! 令牌在所有 JS 环境间共享 ID 序列，用完后由调用方 `releaseCancelToken` 释放。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+static TOKENS: Mutex<Option<HashMap<u32, Arc<AtomicBool>>>> = Mutex::new(None);
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// 取消标志，渲染器持有一份
+#[derive(Debug, Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 申请新的取消令牌
+pub fn create() -> u32 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    TOKENS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(id, Arc::new(AtomicBool::new(false)));
+    id
+}
+
+/// 取消令牌对应的渲染，令牌不存在（已释放）时返回 false
+pub fn cancel(id: u32) -> bool {
+    match TOKENS.lock().unwrap().as_ref().and_then(|tokens| tokens.get(&id)) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 释放令牌；正在使用它的渲染仍持有标志，不受影响
+pub fn release(id: u32) -> bool {
+    TOKENS
+        .lock()
+        .unwrap()
+        .as_mut()
+        .is_some_and(|tokens| tokens.remove(&id).is_some())
+}
+
+/// 查找令牌对应的取消标志
+pub fn flag(id: u32) -> Option<CancelFlag> {
+    TOKENS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|tokens| tokens.get(&id))
+        .map(|flag| CancelFlag(Arc::clone(flag)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_sets_flag_until_released() {
+        let id = create();
+        let flag = flag(id).unwrap();
+        assert!(!flag.is_cancelled());
+        assert!(cancel(id));
+        assert!(flag.is_cancelled());
+        assert!(release(id));
+        assert!(!cancel(id));
+        assert!(super::flag(id).is_none());
+        // 释放后已取出的标志仍然有效
+        assert!(flag.is_cancelled());
+    }
+}
@@ -0,0 +1,316 @@
+//! 色彩管理（forceSrgb）
+//!
+//! PDFium 把 DeviceCMYK 图片按内置的近似公式转成 RGB，品牌色在印刷用的 CMYK 素材上
+//! 容易偏色。开启后，渲染前把页面上的 CMYK 和 ICCBased 图片经 ICC 变换转换到 sRGB，
+//! 再替换回页面交给 PDFium 渲染：ICCBased 图片使用内嵌的 ICC 配置文件，DeviceCMYK 图片
+//! 使用调用方提供的 CMYK 配置文件（如印刷厂的 FOGRA39 / SWOP），没有提供时保持 PDFium 的转换。
+//!
+//! 只处理页面顶层、8 位深度、未旋转的图片；矢量图形和文字的颜色仍由 PDFium 转换。
+
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformExecutor, TransformOptions};
+use pdfium_render::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use zune_core::bytestream::ZCursor;
+use zune_core::colorspace::ColorSpace;
+use zune_core::options::DecoderOptions;
+use zune_jpeg::JpegDecoder;
+
+/// 转换结果与 PDFium 自身转换的平均色差（每通道 0-255）超过该值时，认为样本解读有误
+/// （如图片字典中的 /Decode 反相），保持 PDFium 的结果
+const MAX_MEAN_DIFFERENCE: f64 = 40.0;
+
+/// ICC 配置文件数据；Debug 只输出长度和哈希，避免把整个文件写进日志和缓存键
+#[derive(Clone, PartialEq)]
+pub struct IccProfile {
+    data: Arc<Vec<u8>>,
+    hash: u64,
+}
+
+impl IccProfile {
+    pub fn new(data: Vec<u8>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        Self {
+            data: Arc::new(data),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+impl std::fmt::Debug for IccProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IccProfile({} bytes, {:016x})", self.data.len(), self.hash)
+    }
+}
+
+/// 强制 sRGB 转换的设置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorManagement {
+    /// DeviceCMYK 图片使用的 CMYK 配置文件
+    pub cmyk_profile: Option<IccProfile>,
+}
+
+type Transform = Box<dyn TransformExecutor<u8> + Send + Sync>;
+
+/// 按源配置文件缓存的变换（同一页面上的图片通常共用一个配置文件）
+struct Transforms {
+    srgb: ColorProfile,
+    cache: HashMap<Vec<u8>, Option<(Transform, usize)>>,
+}
+
+impl Transforms {
+    fn new() -> Self {
+        Self {
+            srgb: ColorProfile::new_srgb(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// 源配置文件到 sRGB 的变换及源通道数，配置文件无效或不支持时为 None
+    fn get(&mut self, icc: &[u8]) -> Option<&(Transform, usize)> {
+        let srgb = &self.srgb;
+        self.cache
+            .entry(icc.to_vec())
+            .or_insert_with(|| {
+                let profile = ColorProfile::new_from_slice(icc).ok()?;
+                let (layout, components) = match profile.color_space {
+                    DataColorSpace::Gray => (Layout::Gray, 1),
+                    DataColorSpace::Rgb => (Layout::Rgb, 3),
+                    // moxcms 的 8 位 CMYK 与 RGBA 布局相同
+                    DataColorSpace::Cmyk => (Layout::Rgba, 4),
+                    _ => return None,
+                };
+                let transform = profile
+                    .create_transform_8bit(layout, srgb, Layout::Rgb, TransformOptions::default())
+                    .ok()?;
+                Some((transform as Transform, components))
+            })
+            .as_ref()
+    }
+}
+
+/// 把页面上的 CMYK/ICCBased 图片转换到 sRGB 并替换回页面，返回转换的图片数
+///
+/// 单张图片解码或转换失败时跳过，该图片保持 PDFium 的转换结果。
+pub fn convert_page_images(document: &PdfDocument, page: &PdfPage, settings: &ColorManagement) -> u32 {
+    let bindings = page.bindings();
+    let page_handle = bindings.get_handle_from_page(page);
+    let mut transforms = Transforms::new();
+    let mut converted = 0;
+
+    for mut object in page.objects().iter() {
+        let handle = bindings.get_handle_from_object(&object);
+        let Some(image) = object.as_image_object_mut() else {
+            continue;
+        };
+        let icc = match image.color_space() {
+            Ok(PdfColorSpace::CalibratedICCProfile) => read_icc_profile(bindings, handle, page_handle),
+            Ok(PdfColorSpace::DeviceCMYK) => settings.cmyk_profile.as_ref().map(|p| p.data.to_vec()),
+            _ => None,
+        };
+        let Some(icc) = icc else {
+            continue;
+        };
+        let Some((transform, components)) = transforms.get(&icc) else {
+            continue;
+        };
+        let (Ok(width), Ok(height)) = (image.width(), image.height()) else {
+            continue;
+        };
+        if convert_image(document, image, bindings, handle, (width as u32, height as u32), transform, *components) {
+            converted += 1;
+        }
+    }
+    converted
+}
+
+fn convert_image(
+    document: &PdfDocument,
+    image: &mut PdfPageImageObject,
+    bindings: &dyn PdfiumLibraryBindings,
+    handle: FPDF_PAGEOBJECT,
+    (width, height): (u32, u32),
+    transform: &Transform,
+    components: usize,
+) -> bool {
+    // 旋转或倾斜的图片无法把 PDFium 的渲染结果与原始样本逐像素对齐
+    match image.matrix() {
+        Ok(matrix) if matrix.b() == 0.0 && matrix.c() == 0.0 => {}
+        _ => return false,
+    }
+    let pixels = width as usize * height as usize;
+    let Some(samples) = read_samples(image, bindings, handle, components).filter(|s| s.len() == pixels * components) else {
+        return false;
+    };
+
+    // PDFium 按原始尺寸渲染的图片：透明度来自图片遮罩，颜色作为校验参考
+    let Some(reference) = image
+        .get_processed_bitmap(document)
+        .ok()
+        .and_then(|bitmap| fit_reference(bitmap.as_image().to_rgba8(), width, height))
+    else {
+        return false;
+    };
+
+    let mut rgb = vec![0u8; pixels * 3];
+    if transform.transform(&samples, &mut rgb).is_err() {
+        return false;
+    }
+    if mean_difference(&rgb, &reference) > MAX_MEAN_DIFFERENCE {
+        // CMYK JPEG 常以反相形式存储（Adobe 约定），由图片字典的 /Decode 还原
+        if components != 4 {
+            return false;
+        }
+        let inverted: Vec<u8> = samples.iter().map(|v| 255 - v).collect();
+        if transform.transform(&inverted, &mut rgb).is_err() || mean_difference(&rgb, &reference) > MAX_MEAN_DIFFERENCE {
+            return false;
+        }
+    }
+
+    let mut rgba = Vec::with_capacity(pixels * 4);
+    for (color, original) in rgb.chunks_exact(3).zip(reference.chunks_exact(4)) {
+        rgba.extend_from_slice(color);
+        rgba.push(original[3]);
+    }
+    let Some(converted) = image::RgbaImage::from_raw(width, height, rgba) else {
+        return false;
+    };
+    image.set_image(&image::DynamicImage::ImageRgba8(converted)).is_ok()
+}
+
+/// PDFium 按原始尺寸渲染时可能因取整差一两个像素，缩放到样本尺寸；差得更多说明尺寸对不上
+fn fit_reference(reference: image::RgbaImage, width: u32, height: u32) -> Option<Vec<u8>> {
+    if reference.width().abs_diff(width) > 2 || reference.height().abs_diff(height) > 2 {
+        return None;
+    }
+    if reference.dimensions() == (width, height) {
+        return Some(reference.into_raw());
+    }
+    Some(image::imageops::resize(&reference, width, height, image::imageops::FilterType::Nearest).into_raw())
+}
+
+fn read_icc_profile(bindings: &dyn PdfiumLibraryBindings, handle: FPDF_PAGEOBJECT, page: FPDF_PAGE) -> Option<Vec<u8>> {
+    let mut len = 0usize;
+    if !bindings.is_true(bindings.FPDFImageObj_GetIccProfileDataDecoded(handle, page, std::ptr::null_mut(), 0, &mut len)) || len == 0 {
+        return None;
+    }
+    let mut data = vec![0u8; len];
+    bindings
+        .is_true(bindings.FPDFImageObj_GetIccProfileDataDecoded(handle, page, data.as_mut_ptr(), len, &mut len))
+        .then_some(data)
+}
+
+/// 读取图片的 8 位原始样本；JPEG 自行解码，其他图像编码（JPX、JBIG2、CCITT）不支持
+fn read_samples(
+    image: &PdfPageImageObject,
+    bindings: &dyn PdfiumLibraryBindings,
+    handle: FPDF_PAGEOBJECT,
+    components: usize,
+) -> Option<Vec<u8>> {
+    let filters: Vec<String> = image.filters().iter().map(|f| f.name().to_string()).collect();
+    let len = bindings.FPDFImageObj_GetImageDataDecoded(handle, std::ptr::null_mut(), 0) as usize;
+    let mut data = vec![0u8; len];
+    bindings.FPDFImageObj_GetImageDataDecoded(handle, data.as_mut_ptr() as *mut _, len as _);
+
+    // GetImageDataDecoded 不解码图像编码，DCT 图片返回的仍是 JPEG 数据
+    match filters.last().map(String::as_str) {
+        Some("DCTDecode" | "DCT") => decode_jpeg(&data, components),
+        Some("JPXDecode" | "JBIG2Decode" | "CCITTFaxDecode" | "CCF") => None,
+        _ => Some(data),
+    }
+}
+
+/// 按源色彩空间的通道数解码 JPEG：灰度、RGB 或 CMYK（YCCK 先转成 CMYK）
+fn decode_jpeg(data: &[u8], components: usize) -> Option<Vec<u8>> {
+    let mut decoder = JpegDecoder::new(ZCursor::new(data));
+    decoder.decode_headers().ok()?;
+    let input = decoder.input_colorspace()?;
+    let output = match components {
+        1 => ColorSpace::Luma,
+        3 => ColorSpace::RGB,
+        // zune-jpeg 不支持 YCCK 到 CMYK 的转换，取出原始 YCCK 自行转换
+        4 if input == ColorSpace::YCCK => ColorSpace::YCCK,
+        4 => ColorSpace::CMYK,
+        _ => return None,
+    };
+    let mut decoder = JpegDecoder::new_with_options(ZCursor::new(data), DecoderOptions::default().jpeg_set_out_colorspace(output));
+    let mut samples = decoder.decode().ok()?;
+    if output == ColorSpace::YCCK {
+        ycck_to_cmyk(&mut samples);
+    }
+    Some(samples)
+}
+
+/// Adobe YCCK：YCbCr 部分按 JFIF 公式转成 RGB 后取反得到 CMY，K 保持不变
+fn ycck_to_cmyk(samples: &mut [u8]) {
+    for pixel in samples.chunks_exact_mut(4) {
+        let (y, cb, cr) = (pixel[0] as f32, pixel[1] as f32 - 128.0, pixel[2] as f32 - 128.0);
+        let r = y + 1.402 * cr;
+        let g = y - 0.344_136 * cb - 0.714_136 * cr;
+        let b = y + 1.772 * cb;
+        pixel[0] = (255.0 - r).round().clamp(0.0, 255.0) as u8;
+        pixel[1] = (255.0 - g).round().clamp(0.0, 255.0) as u8;
+        pixel[2] = (255.0 - b).round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// RGB 样本与 RGBA 参考图像各通道平均值之差的最大值（只统计不透明像素）
+fn mean_difference(rgb: &[u8], reference: &[u8]) -> f64 {
+    let mut sums = [0f64; 6];
+    let mut count = 0f64;
+    for (color, original) in rgb.chunks_exact(3).zip(reference.chunks_exact(4)) {
+        if original[3] == 0 {
+            continue;
+        }
+        for channel in 0..3 {
+            sums[channel] += color[channel] as f64;
+            sums[channel + 3] += original[channel] as f64;
+        }
+        count += 1.0;
+    }
+    if count == 0.0 {
+        return 0.0;
+    }
+    (0..3).map(|c| (sums[c] - sums[c + 3]).abs() / count).fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ycck_to_cmyk() {
+        // 中性灰：Cb = Cr = 128 时 CMY 均为 255 - Y
+        let mut samples = vec![200, 128, 128, 40, 0, 128, 128, 255];
+        ycck_to_cmyk(&mut samples);
+        assert_eq!(samples, vec![55, 55, 55, 40, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_mean_difference_ignores_transparent_pixels() {
+        let rgb = [100, 100, 100, 0, 0, 0];
+        let reference = [110, 100, 90, 255, 255, 255, 255, 0];
+        assert_eq!(mean_difference(&rgb, &reference), 10.0);
+    }
+
+    #[test]
+    fn test_srgb_transform_is_identity() {
+        let mut transforms = Transforms::new();
+        let srgb = ColorProfile::new_srgb().encode().unwrap();
+        let (transform, components) = transforms.get(&srgb).unwrap();
+        assert_eq!(*components, 3);
+        let mut out = [0u8; 6];
+        transform.transform(&[255, 0, 0, 12, 34, 56], &mut out).unwrap();
+        assert!(out.iter().zip([255, 0, 0, 12, 34, 56]).all(|(a, b)| a.abs_diff(b) <= 1));
+        assert!(transforms.get(b"not an icc profile").is_none());
+    }
+
+    #[test]
+    fn test_icc_profile_debug_is_short() {
+        let profile = IccProfile::new(vec![7; 100_000]);
+        assert!(format!("{:?}", profile).len() < 64);
+    }
+}
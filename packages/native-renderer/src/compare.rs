@@ -0,0 +1,64 @@
+//! 两个文档的页面布局比较（不渲染）
+//!
+//! 版本对比时先按页码对齐两份文档，比较页数、页面尺寸和旋转角度，
+//! 只有布局一致的页面才值得继续做逐像素的视觉比较。
+
+/// 页面尺寸的比较容差（点），吸收不同生成工具的浮点舍入
+pub const SIZE_TOLERANCE: f64 = 0.5;
+
+/// 单页的尺寸和旋转（尺寸已考虑页面旋转）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageGeometry {
+    pub width: f64,
+    pub height: f64,
+    pub rotation: u32,
+}
+
+/// 同一页码在两个文档中的布局差异
+#[derive(Debug, Default, PartialEq)]
+pub struct LayoutDiff {
+    pub size_changed: bool,
+    pub rotation_changed: bool,
+    pub missing_in_a: bool,
+    pub missing_in_b: bool,
+}
+
+impl LayoutDiff {
+    pub fn changed(&self) -> bool {
+        self.size_changed || self.rotation_changed || self.missing_in_a || self.missing_in_b
+    }
+}
+
+/// 比较同一页码的两个页面，页面不存在时传 None
+pub fn diff_page(a: Option<&PageGeometry>, b: Option<&PageGeometry>) -> LayoutDiff {
+    match (a, b) {
+        (Some(a), Some(b)) => LayoutDiff {
+            size_changed: (a.width - b.width).abs() > SIZE_TOLERANCE || (a.height - b.height).abs() > SIZE_TOLERANCE,
+            rotation_changed: a.rotation != b.rotation,
+            ..Default::default()
+        },
+        (a, b) => LayoutDiff {
+            missing_in_a: a.is_none(),
+            missing_in_b: b.is_none(),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_differences() {
+        let a4 = PageGeometry { width: 595.3, height: 841.9, rotation: 0 };
+        let a4_rounded = PageGeometry { width: 595.0, height: 842.0, rotation: 0 };
+        let landscape = PageGeometry { width: 841.9, height: 595.3, rotation: 90 };
+
+        assert!(!diff_page(Some(&a4), Some(&a4_rounded)).changed());
+        let diff = diff_page(Some(&a4), Some(&landscape));
+        assert!(diff.size_changed && diff.rotation_changed);
+        let diff = diff_page(Some(&a4), None);
+        assert!(diff.missing_in_b && !diff.missing_in_a && diff.changed());
+    }
+}
@@ -1,18 +1,65 @@
 //! 渲染配置
 
-use crate::renderer::OutputFormat;
+use crate::color::ColorManagement;
+use crate::error::RenderError;
+use crate::filters::{BilevelMode, DenoiseMode};
+use crate::renderer::{AdvancedFlags, DeliveryOrder, FormMode, OutputFormat, OversizeStrategy, PixelFormat, SmartEncoding};
+use crate::jpeg::ChromaSubsampling;
+use crate::stamp::Stamp;
+use pdfium_render::prelude::PdfPageAnnotationType;
+use crate::flight_recorder;
+use std::collections::BTreeMap;
+
+/// 单页的渲染尺寸覆盖
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageScale {
+    /// 目标宽度（像素）
+    Width(u32),
+    /// 分辨率（DPI，72 DPI 为原始尺寸）
+    Dpi(f32),
+}
+
+/// 单页对扫描件降级宽度的覆盖，不受 detect_scan 影响
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScanWidth {
+    /// 始终使用 target_width（如版式接近扫描件、但需要完整宽度的画册页面）
+    Full,
+    /// 始终使用 image_heavy_width
+    ImageHeavy,
+}
+
+impl ScanWidth {
+    /// "full" 或 "scan"；"auto" 及无法识别的值返回 None（按扫描件检测结果决定）
+    pub fn from_str(mode: &str) -> Option<Self> {
+        match mode.to_lowercase().as_str() {
+            "full" => Some(ScanWidth::Full),
+            "scan" => Some(ScanWidth::ImageHeavy),
+            _ => None,
+        }
+    }
+}
 
 /// 渲染配置参数
 #[derive(Debug, Clone)]
 pub struct RenderConfig {
     /// 目标渲染宽度
     pub target_width: u32,
+    /// 统一的渲染分辨率（DPI），设置后按页面物理尺寸计算渲染尺寸，代替 target_width
+    pub dpi: Option<f32>,
+    /// 尺寸选项冲突的原因（如同时指定 dpi 和 targetWidth），渲染时每页以 INVALID_OPTIONS 失败
+    pub size_error: Option<String>,
     /// 扫描件/图片页面的降级宽度
     pub image_heavy_width: u32,
     /// 最大缩放比例
     pub max_scale: f32,
+    /// 扫描件降级宽度被 max_scale 限制时，按页面内嵌图片的原始分辨率渲染
+    pub prefer_sharpness: bool,
     /// 是否启用扫描件检测
     pub detect_scan: bool,
+    /// 识别为扫描件所需的最小图片覆盖比例（0-1，0 表示至少有一张图片即可）
+    pub scan_min_image_coverage: f64,
+    /// 识别为扫描件时允许的最多文字对象数（OCR 文字层、页眉页码等）
+    pub scan_max_text_objects: u32,
     /// 输出格式
     pub format: OutputFormat,
     /// WebP 编码质量（0-100）
@@ -20,24 +67,460 @@ pub struct RenderConfig {
     /// WebP 编码方法/速度（0-6，0最快，6最慢但压缩最好）
     /// 默认值 4 是速度和压缩率的最佳平衡点
     pub webp_method: i32,
+    /// 按位图像素数调整 WebP method（webp_method 作为常规尺寸页面的基准）
+    pub webp_method_auto: bool,
     /// JPEG 编码质量（0-100）
     pub jpeg_quality: u8,
+    /// 是否输出渐进式 JPEG
+    pub jpeg_progressive: bool,
+    /// JPEG 色度抽样方式
+    pub jpeg_chroma_subsampling: ChromaSubsampling,
+    /// AVIF 编码质量（0-100）
+    pub avif_quality: u8,
+    /// AVIF 编码速度（0-10，0 最慢但压缩最好，10 最快）
+    pub avif_speed: u8,
     /// PNG 压缩级别（0-9，0不压缩，9最大压缩）
     pub png_compression: u8,
+    /// 位图像素通道顺序（RGBA 或 PDFium 原生的 BGRA）
+    pub pixel_format: PixelFormat,
+    /// 调色板颜色数（2-256），None 表示不量化
+    pub colors: Option<u16>,
+    /// 二值化（1 位）输出方式，None 表示不二值化
+    pub bilevel: Option<BilevelMode>,
+    /// 背景归一化窗口（像素），None 表示不做光照校正
+    pub normalize_background: Option<u32>,
+    /// 扫描件页面的降噪方式，None 表示不降噪
+    pub denoise: Option<DenoiseMode>,
+    /// PDFium 底层渲染标志
+    pub advanced_flags: AdvancedFlags,
+    /// 只渲染这些类型的注释，None 表示渲染全部注释
+    pub annotation_types: Option<Vec<PdfPageAnnotationType>>,
+    /// 表单控件的渲染方式
+    pub form_mode: FormMode,
+    /// 文档解析的资源限制
+    pub limits: ResourceLimits,
+    /// 单次调用的总时间预算（毫秒），None 表示不限制
+    pub total_time_budget_ms: Option<u32>,
+    /// 单页编码时间预算（毫秒），超出后 WebP 改用最快的编码参数
+    pub encode_budget_ms: Option<u32>,
+    /// 单页渲染超时（毫秒），超出后该页以 RENDER_TIMEOUT 失败，None 表示不限制
+    pub page_timeout_ms: Option<u32>,
+    /// 内嵌缩略图为 JPEG/JPEG 2000 时原样返回，不解码重新编码
+    pub thumbnail_passthrough: bool,
+    /// 没有内嵌缩略图的页面按该宽度渲染，None 表示不回退
+    pub thumbnail_fallback_width: Option<u32>,
+    /// 两遍渲染的预览宽度（None 使用 DEFAULT_PREVIEW_WIDTH），设置了 onPreview 回调时生效
+    pub preview_width: Option<u32>,
+    /// 扫描页面只有一张整页 JPEG/JPEG 2000 图片时原样返回，不渲染也不编码
+    pub scan_passthrough: bool,
+    /// 按页码覆盖渲染尺寸，优先于 target_width / image_heavy_width
+    pub page_scales: BTreeMap<u32, PageScale>,
+    /// 按页码覆盖扫描件降级宽度，优先于扫描件检测结果
+    pub scan_widths: BTreeMap<u32, ScanWidth>,
+    /// 按页码覆盖输出格式，优先于 format
+    pub page_formats: BTreeMap<u32, OutputFormat>,
+    /// 固定输出尺寸（页面等比缩放后填充到画布中），优先于其他尺寸设置
+    pub canvas: Option<Canvas>,
+    /// 页面尺寸超出 WebP 上限时的处理方式
+    pub oversize_strategy: OversizeStrategy,
+    /// 分块边长（像素）
+    pub tile_size: u32,
+    /// 编码器后端名称（auto 表示按格式使用默认后端）
+    pub encoder_backend: String,
+    /// 按页码合成到渲染结果上的高亮区域
+    pub highlights: BTreeMap<u32, Vec<Highlight>>,
+    /// 合成到每页渲染结果上的水印；选项无效时为错误，渲染时每页以 INVALID_OPTIONS 失败
+    pub stamp: Option<Result<Stamp, String>>,
+    /// 编码为不支持透明度的格式（JPEG、TIFF）时，半透明像素混合的背景色
+    pub jpeg_background: [u8; 3],
+    /// 渲染结果超出尺寸上限不超过该像素数时裁掉边缘而不是重新缩放
+    pub resize_tolerance: u32,
+    /// 页码大于文档页数时整个调用失败，而不是把该页标记为跳过
+    pub fail_out_of_range: bool,
+    /// 页面位图超出内存预算（maxPixelsPerPage / maxTotalBytes）时该页失败，而不是缩小渲染
+    pub fail_over_memory_budget: bool,
+    /// 纯文字页面的编码方式
+    pub smart_encoding: SmartEncoding,
+    /// 文档需要密码时依次尝试的候选密码
+    pub passwords: Vec<String>,
+    /// 用同一张位图额外编码的格式
+    pub also_encode: Vec<OutputFormat>,
+    /// 渲染前把 CMYK/ICC 图片经 ICC 变换转换到 sRGB，None 表示使用 PDFium 的转换
+    pub color_management: Option<ColorManagement>,
+    /// 在结果中返回输出图像的亮度统计
+    pub image_stats: bool,
+    /// 计算输出位图的内容哈希（contentHash）
+    pub content_hash: bool,
+    /// 检查页面对象能否正常绘制
+    pub object_diagnostics: bool,
+    /// 以透明背景渲染，保留页面的透明区域
+    pub preserve_alpha: bool,
+    /// 渲染会话中最先渲染并交付的页码
+    pub hero_page: Option<u32>,
+    /// 在结果中返回各阶段的起止时间
+    pub span_timings: bool,
+    /// 原始位图分块输出时每块的行数
+    pub chunk_rows: Option<u32>,
+    /// 并行编码的线程数，1 表示逐页渲染、编码
+    pub concurrency: u32,
+    /// 并行编码时 onPage 的回调顺序
+    pub delivery_order: DeliveryOrder,
+    /// 流式渲染：concurrency 为 1 时也在后台线程编码，PDFium 线程同时读取下一页的数据
+    pub overlap_fetch: bool,
+    /// 取消令牌 ID（createCancelToken）
+    pub cancel_token: Option<u32>,
+    /// 确定性输出：不做内存降级和编码预算降级
+    pub deterministic: bool,
 }
 
 impl Default for RenderConfig {
     fn default() -> Self {
         Self {
             target_width: 1280,
+            dpi: None,
+            size_error: None,
             image_heavy_width: 1024,
             max_scale: 4.0,
+            prefer_sharpness: false,
             detect_scan: true,
+            scan_min_image_coverage: 0.0,
+            scan_max_text_objects: 0,
             format: OutputFormat::WebP,
             webp_quality: 80,
             webp_method: 4,  // 速度和压缩率的最佳平衡点
+            webp_method_auto: false,
             jpeg_quality: 85,
+            jpeg_progressive: false,
+            jpeg_chroma_subsampling: ChromaSubsampling::Full,
+            avif_quality: 50,
+            avif_speed: 6,
             png_compression: 6,
+            pixel_format: PixelFormat::Rgba,
+            colors: None,
+            bilevel: None,
+            normalize_background: None,
+            denoise: None,
+            advanced_flags: AdvancedFlags::default(),
+            annotation_types: None,
+            form_mode: FormMode::Render,
+            limits: ResourceLimits::default(),
+            total_time_budget_ms: None,
+            encode_budget_ms: None,
+            page_timeout_ms: None,
+            thumbnail_passthrough: false,
+            thumbnail_fallback_width: None,
+            preview_width: None,
+            scan_passthrough: false,
+            page_scales: BTreeMap::new(),
+            scan_widths: BTreeMap::new(),
+            page_formats: BTreeMap::new(),
+            canvas: None,
+            oversize_strategy: OversizeStrategy::Downscale,
+            tile_size: 4096,
+            encoder_backend: "auto".to_string(),
+            highlights: BTreeMap::new(),
+            stamp: None,
+            jpeg_background: [255, 255, 255],
+            resize_tolerance: 2,
+            fail_out_of_range: false,
+            fail_over_memory_budget: false,
+            smart_encoding: SmartEncoding::Off,
+            passwords: Vec::new(),
+            also_encode: Vec::new(),
+            color_management: None,
+            image_stats: false,
+            content_hash: false,
+            object_diagnostics: false,
+            preserve_alpha: false,
+            hero_page: None,
+            span_timings: false,
+            chunk_rows: None,
+            concurrency: 1,
+            delivery_order: DeliveryOrder::Completion,
+            overlap_fetch: false,
+            cancel_token: None,
+            deterministic: false,
+        }
+    }
+}
+
+impl RenderConfig {
+    /// 只保留影响输出图像的设置：密码、时间预算、并发、取消令牌和资源限制等恢复为默认值
+    pub fn output_settings(&self) -> RenderConfig {
+        let defaults = RenderConfig::default();
+        RenderConfig {
+            passwords: Vec::new(),
+            total_time_budget_ms: None,
+            hero_page: None,
+            span_timings: false,
+            chunk_rows: None,
+            concurrency: defaults.concurrency,
+            delivery_order: defaults.delivery_order,
+            overlap_fetch: false,
+            cancel_token: None,
+            limits: defaults.limits,
+            preview_width: None,
+            ..self.clone()
+        }
+    }
+
+    /// 合并默认值、命名配置和旧字段后的完整配置（文字形式，密码只保留个数）
+    pub fn echo(&self) -> String {
+        let redacted = RenderConfig {
+            passwords: vec!["***".to_string(); self.passwords.len()],
+            ..self.clone()
+        };
+        format!("{:?}", redacted)
+    }
+
+    /// 影响输出的配置的哈希（16 位十六进制）
+    ///
+    /// 按页码的设置按页码排序参与计算，同一版本的模块在不同进程中结果一致；
+    /// 升级模块后可能变化。
+    pub fn output_hash(&self) -> String {
+        let settings = format!("{:?}", self.output_settings());
+        format!("{:016x}", flight_recorder::fnv1a(&[settings.as_bytes()]))
+    }
+}
+
+/// 固定尺寸画布：页面等比缩放到画布内，空白部分用填充色补齐
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    /// 填充色（RGBA）
+    pub color: [u8; 4],
+    /// 水平对齐（0 左对齐，0.5 居中，1 右对齐）
+    pub align_x: f32,
+    /// 垂直对齐（0 顶部，0.5 居中，1 底部）
+    pub align_y: f32,
+}
+
+impl Canvas {
+    /// 从选项构建，宽高缺一时返回 None；无法识别的颜色和对齐方式使用默认值（白色、居中）
+    pub fn from_options(width: Option<u32>, height: Option<u32>, color: Option<&str>, align: Option<&str>) -> Option<Self> {
+        let (width, height) = (width.filter(|w| *w > 0)?, height.filter(|h| *h > 0)?);
+        let color = color.and_then(parse_color).unwrap_or([255, 255, 255, 255]);
+        let (align_x, align_y) = align.map(parse_align).unwrap_or((0.5, 0.5));
+        Some(Self {
+            width,
+            height,
+            color,
+            align_x,
+            align_y,
+        })
+    }
+
+    /// 页面图像在画布中的左上角位置
+    pub fn offset(&self, width: u32, height: u32) -> (u32, u32) {
+        (
+            (self.width.saturating_sub(width) as f32 * self.align_x).round() as u32,
+            (self.height.saturating_sub(height) as f32 * self.align_y).round() as u32,
+        )
+    }
+}
+
+/// 高亮区域（PDF 坐标，单位点，原点在页面左下角）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Highlight {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+    /// 高亮颜色（RGBA），alpha 控制强度
+    pub color: [u8; 4],
+}
+
+impl Highlight {
+    /// 默认高亮色：黄色荧光笔
+    const DEFAULT_COLOR: [u8; 4] = [255, 235, 59, 255];
+
+    /// 从左下角坐标和宽高构建；宽高无效时返回 None，无法识别的颜色使用默认黄色
+    pub fn from_options(x: f64, y: f64, width: f64, height: f64, color: Option<&str>) -> Option<Self> {
+        if !(width.is_finite() && height.is_finite() && x.is_finite() && y.is_finite()) || width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            left: x as f32,
+            bottom: y as f32,
+            right: (x + width) as f32,
+            top: (y + height) as f32,
+            color: color.and_then(parse_color).unwrap_or(Self::DEFAULT_COLOR),
+        })
+    }
+}
+
+/// 解析 `#rgb`、`#rrggbb`、`#rrggbbaa` 或 `transparent`
+pub fn parse_color(s: &str) -> Option<[u8; 4]> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("transparent") {
+        // 透明白色：不支持透明度的格式（JPEG）会显示为白色
+        return Some([255, 255, 255, 0]);
+    }
+    let hex = s.strip_prefix('#')?;
+    let digit = |i: usize, len: usize| u8::from_str_radix(hex.get(i..i + len)?, 16).ok();
+    match hex.len() {
+        3 => Some([digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17, 255]),
+        6 => Some([digit(0, 2)?, digit(2, 2)?, digit(4, 2)?, 255]),
+        8 => Some([digit(0, 2)?, digit(2, 2)?, digit(4, 2)?, digit(6, 2)?]),
+        _ => None,
+    }
+}
+
+/// 解析对齐方式：center、top、bottom、left、right 及 top-left 等组合
+pub fn parse_align(s: &str) -> (f32, f32) {
+    let (mut x, mut y) = (0.5, 0.5);
+    for part in s.split(['-', ' ']) {
+        match part.trim().to_ascii_lowercase().as_str() {
+            "left" => x = 0.0,
+            "right" => x = 1.0,
+            "top" => y = 0.0,
+            "bottom" => y = 1.0,
+            _ => {}
+        }
+    }
+    (x, y)
+}
+
+/// 文档解析的资源限制（None 表示不限制）
+///
+/// 超出限制时立即失败，不再继续解析，避免恶意构造的 PDF 拖垮多租户服务。
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLimits {
+    /// 单次调用最多处理的页数
+    pub max_pages: Option<u32>,
+    /// 分析页面（扫描件识别、版面分析）时单页最多检查的对象数
+    pub max_page_objects: Option<u32>,
+    /// 输入文档的最大字节数
+    pub max_file_size: Option<u64>,
+    /// 单页位图的最大像素数
+    pub max_pixels_per_page: Option<u64>,
+    /// 同时渲染的页面位图（RGBA）总字节数上限，按并发页数平分到每页
+    pub max_total_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    fn check(limit: &'static str, actual: u64, max: Option<u64>) -> Result<(), RenderError> {
+        match max {
+            Some(max) if actual > max => Err(RenderError::LimitExceeded { limit, actual, max }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_pages(&self, pages: usize) -> Result<(), RenderError> {
+        Self::check("maxPages", pages as u64, self.max_pages.map(u64::from))
+    }
+
+    pub fn check_page_objects(&self, objects: usize) -> Result<(), RenderError> {
+        Self::check("maxPageObjects", objects as u64, self.max_page_objects.map(u64::from))
+    }
+
+    pub fn check_file_size(&self, size: u64) -> Result<(), RenderError> {
+        Self::check("maxFileSize", size, self.max_file_size)
+    }
+
+    /// 单页位图允许的最大像素数：maxPixelsPerPage 与 maxTotalBytes 按 `concurrency` 平分后
+    /// （每像素 4 字节）的较小值
+    pub fn page_pixel_budget(&self, concurrency: u32) -> Option<u64> {
+        let from_bytes = self.max_total_bytes.map(|bytes| bytes / (4 * concurrency.max(1) as u64));
+        match (self.max_pixels_per_page, from_bytes) {
+            (Some(pixels), Some(bytes)) => Some(pixels.min(bytes)),
+            (pixels, bytes) => pixels.or(bytes),
         }
     }
+
+    /// 检查 `width`×`height` 的页面位图是否在内存预算内
+    pub fn check_page_pixels(&self, width: u32, height: u32, concurrency: u32) -> Result<(), RenderError> {
+        let pixels = width as u64 * height as u64;
+        let in_flight = concurrency.max(1) as u64;
+        Self::check("maxPixelsPerPage", pixels, self.max_pixels_per_page)
+            .and_then(|_| Self::check("maxTotalBytes", pixels * 4 * in_flight, self.max_total_bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_limits() {
+        let limits = ResourceLimits {
+            max_pages: Some(2),
+            max_page_objects: None,
+            max_file_size: Some(1024),
+            max_pixels_per_page: Some(1_000_000),
+            max_total_bytes: Some(8_000_000),
+        };
+        assert!(limits.check_pages(2).is_ok());
+        assert!(limits.check_page_objects(1_000_000).is_ok());
+        let err = limits.check_file_size(2048).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resource limit exceeded: maxFileSize (actual 2048, max 1024)"
+        );
+
+        // 单页 100 万像素；两页并发时每页只能用 400 万字节（100 万像素），四页并发时 50 万像素
+        assert_eq!(limits.page_pixel_budget(1), Some(1_000_000));
+        assert_eq!(limits.page_pixel_budget(4), Some(500_000));
+        assert!(limits.check_page_pixels(1000, 1000, 2).is_ok());
+        assert!(limits.check_page_pixels(1001, 1000, 1).is_err());
+        let err = limits.check_page_pixels(1000, 1000, 4).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Resource limit exceeded: maxTotalBytes (actual 16000000, max 8000000)"
+        );
+        assert_eq!(ResourceLimits::default().page_pixel_budget(4), None);
+    }
+
+    #[test]
+    fn test_canvas_options() {
+        assert_eq!(parse_color("#f00"), Some([255, 0, 0, 255]));
+        assert_eq!(parse_color("#11223380"), Some([0x11, 0x22, 0x33, 0x80]));
+        assert_eq!(parse_color("transparent").map(|c| c[3]), Some(0));
+        assert_eq!(parse_color("red"), None);
+
+        let canvas = Canvas::from_options(Some(200), Some(100), None, Some("top-right")).unwrap();
+        assert_eq!(canvas.color, [255, 255, 255, 255]);
+        assert_eq!(canvas.offset(50, 100), (150, 0));
+        assert!(Canvas::from_options(Some(200), None, None, None).is_none());
+    }
+
+    #[test]
+    fn test_scan_width_from_str() {
+        assert_eq!(ScanWidth::from_str("full"), Some(ScanWidth::Full));
+        assert_eq!(ScanWidth::from_str("Scan"), Some(ScanWidth::ImageHeavy));
+        assert_eq!(ScanWidth::from_str("auto"), None);
+    }
+
+    #[test]
+    fn test_output_hash() {
+        let base = RenderConfig::default().output_hash();
+        assert_eq!(base.len(), 16);
+
+        // 不影响输出的设置不改变哈希
+        let config = RenderConfig {
+            passwords: vec!["secret".to_string()],
+            concurrency: 4,
+            cancel_token: Some(7),
+            ..RenderConfig::default()
+        };
+        assert_eq!(config.output_hash(), base);
+        assert!(!config.echo().contains("secret"));
+
+        // 按页码的设置与插入顺序无关
+        let mut a = RenderConfig::default();
+        a.page_scales.insert(3, PageScale::Width(800));
+        a.page_scales.insert(1, PageScale::Dpi(150.0));
+        let mut b = RenderConfig::default();
+        b.page_scales.insert(1, PageScale::Dpi(150.0));
+        b.page_scales.insert(3, PageScale::Width(800));
+        assert_eq!(a.output_hash(), b.output_hash());
+        assert_ne!(a.output_hash(), base);
+
+        let config = RenderConfig {
+            target_width: 640,
+            ..RenderConfig::default()
+        };
+        assert_ne!(config.output_hash(), base);
+    }
 }
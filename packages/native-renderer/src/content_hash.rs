@@ -0,0 +1,36 @@
+//! 输出位图的内容哈希（contentHash）
+//!
+//! CDN 缓存需要一个与渲染结果一一对应的稳定标识。哈希基于编码前的最终位图（包含高亮、水印、滤镜和画布填充），
+//! 与输出格式、编码参数无关：同一页面改用 PNG 或 WebP 输出时哈希相同，像素有任何变化时哈希不同。
+//!
+//! 使用 XXH64，结果与其他语言的 xxHash 实现一致，不随 Rust 版本或进程变化。
+
+pub use xxhash_rust::xxh64::xxh64;
+
+/// 位图的内容哈希（16 位十六进制）
+///
+/// 宽高组合为种子（宽在高 32 位），像素相同但排列不同的位图哈希不同。
+/// 像素按 pixelFormat 的字节顺序参与计算。
+pub fn bitmap_hash(pixels: &[u8], width: u32, height: u32) -> String {
+    let seed = ((width as u64) << 32) | height as u64;
+    format!("{:016x}", xxh64(pixels, seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_hash() {
+        let pixels = vec![255u8; 4 * 6];
+        let hash = bitmap_hash(&pixels, 3, 2);
+        assert_eq!(hash.len(), 16);
+        // 与其他语言的 xxHash 实现一致，升级依赖后哈希不能改变
+        assert_eq!(hash, "18c939a3ab49a52c");
+        assert_eq!(hash, bitmap_hash(&pixels, 3, 2));
+        assert_ne!(hash, bitmap_hash(&pixels, 2, 3));
+        let mut changed = pixels.clone();
+        changed[5] = 254;
+        assert_ne!(hash, bitmap_hash(&changed, 3, 2));
+    }
+}
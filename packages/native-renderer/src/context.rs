@@ -0,0 +1,188 @@
+//! 模块实例状态
+//!
+//! 同一个进程中，模块可能被多个 JS 环境加载（主线程、多个 worker_threads、
+//! Electron 渲染进程），它们共享同一份动态库和其中的 static 变量。
+//! 流式任务表、任务计数和生命周期状态按实例保存在 NAPI instance data 中，
+//! 各实例互不干扰；一个 worker 调用 shutdown 不会影响其他 worker。
+//!
+//! 流式并发限制（`stream_limits`）和最近渲染记录（`flight_recorder`）
+//! 针对的是进程级资源，仍然是全局的。
+
+use crate::lifecycle::Lifecycle;
+use crate::pinned::PinnedDocument;
+use crate::raw_chunks::ChunkWindow;
+use crate::scheduler::Scheduler;
+use crate::session::RenderSession;
+use crate::stream_reader::SharedState;
+use napi::{Env, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 单个模块实例的可变状态
+#[derive(Default)]
+pub struct ModuleState {
+    /// 生命周期（关闭状态与进行中的任务数）
+    pub lifecycle: Lifecycle,
+    /// 进行中的流式任务（task_id -> 共享状态）
+    stream_states: Mutex<HashMap<u32, Arc<SharedState>>>,
+    /// 进行中的原始位图分块任务（task_id -> 在途分块窗口），与流式任务共用 ID 序列
+    chunk_windows: Mutex<HashMap<u32, Arc<ChunkWindow>>>,
+    /// 下一个流式任务 ID
+    next_task_id: AtomicU32,
+    /// 未关闭的渲染调度器（scheduler_id -> 调度器）
+    schedulers: Mutex<HashMap<u32, Arc<Scheduler>>>,
+    /// 下一个调度器 / 调度任务 / 调度文档 ID
+    next_scheduler_id: AtomicU32,
+    next_job_id: AtomicU32,
+    next_document_id: AtomicU32,
+    /// 预加载的常驻文档（document_id -> 文档）
+    pinned_documents: Mutex<HashMap<u32, Arc<PinnedDocument>>>,
+    /// 批量渲染会话（session_id -> 会话）
+    sessions: Mutex<HashMap<u32, Arc<RenderSession>>>,
+    next_session_id: AtomicU32,
+}
+
+/// 获取当前 JS 环境对应的模块状态，首次调用时创建
+///
+/// instance data 只会在所属环境的 JS 线程上读写，不存在并发初始化。
+pub fn instance(env: &Env) -> Result<Arc<ModuleState>> {
+    if let Some(state) = env.get_instance_data::<Arc<ModuleState>>()? {
+        return Ok(Arc::clone(state));
+    }
+
+    let state = Arc::new(ModuleState::default());
+    env.set_instance_data(Arc::clone(&state), (), |_| {})?;
+    Ok(state)
+}
+
+impl ModuleState {
+    /// 分配流式任务 ID（会与请求序号组合成 request_id）
+    ///
+    /// 32 位计数，长期运行的进程中实际不会回绕，旧任务的迟到响应不会被交给新任务。
+    pub fn next_task_id(&self) -> u32 {
+        self.next_task_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub fn register_stream(&self, task_id: u32, state: Arc<SharedState>) {
+        self.stream_states.lock().unwrap().insert(task_id, state);
+    }
+
+    /// 移除流式任务，并清理它仍在等待的请求（记为孤立请求）
+    pub fn unregister_stream(&self, task_id: u32) {
+        if let Some(state) = self.stream_states.lock().unwrap().remove(&task_id) {
+            state.release_pending();
+        }
+    }
+
+    /// 进行中的流式任务数与它们正在等待的请求总数
+    pub fn stream_request_counts(&self) -> (u32, u32) {
+        let states = self.stream_states.lock().unwrap();
+        let pending = states.values().map(|state| state.pending_count() as u32).sum();
+        (states.len() as u32, pending)
+    }
+
+    pub fn stream(&self, task_id: u32) -> Option<Arc<SharedState>> {
+        self.stream_states.lock().unwrap().get(&task_id).cloned()
+    }
+
+    pub fn register_chunk_window(&self, task_id: u32, window: Arc<ChunkWindow>) {
+        self.chunk_windows.lock().unwrap().insert(task_id, window);
+    }
+
+    pub fn unregister_chunk_window(&self, task_id: u32) {
+        self.chunk_windows.lock().unwrap().remove(&task_id);
+    }
+
+    pub fn chunk_window(&self, task_id: u32) -> Option<Arc<ChunkWindow>> {
+        self.chunk_windows.lock().unwrap().get(&task_id).cloned()
+    }
+
+    /// 取消本实例所有进行中的流式任务（包括原始位图分块任务），返回取消的数量
+    pub fn cancel_streams(&self, reason: &str) -> u32 {
+        let states = self.stream_states.lock().unwrap();
+        for state in states.values() {
+            state.cancel(reason);
+        }
+        let windows = self.chunk_windows.lock().unwrap();
+        for window in windows.values() {
+            window.cancel(reason);
+        }
+        (states.len() + windows.len()) as u32
+    }
+
+    pub fn register_scheduler(&self, scheduler: Arc<Scheduler>) -> u32 {
+        let id = self.next_scheduler_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.schedulers.lock().unwrap().insert(id, scheduler);
+        id
+    }
+
+    pub fn scheduler(&self, scheduler_id: u32) -> Option<Arc<Scheduler>> {
+        self.schedulers.lock().unwrap().get(&scheduler_id).cloned()
+    }
+
+    /// 从表中移除调度器；工作线程退出后回调随之释放
+    pub fn remove_scheduler(&self, scheduler_id: u32) -> Option<Arc<Scheduler>> {
+        self.schedulers.lock().unwrap().remove(&scheduler_id)
+    }
+
+    /// 分配调度任务 ID（从 1 开始，本实例内唯一）
+    pub fn next_job_id(&self) -> u32 {
+        self.next_job_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn next_document_id(&self) -> u32 {
+        self.next_document_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// 取出本实例所有调度器（关闭时使用）
+    pub fn take_schedulers(&self) -> Vec<Arc<Scheduler>> {
+        self.schedulers.lock().unwrap().drain().map(|(_, s)| s).collect()
+    }
+
+    /// 登记常驻文档，ID 与调度文档共用同一序列
+    pub fn pin_document(&self, document: PinnedDocument) -> u32 {
+        let id = self.next_document_id();
+        self.pinned_documents.lock().unwrap().insert(id, Arc::new(document));
+        id
+    }
+
+    pub fn pinned_document(&self, document_id: u32) -> Option<Arc<PinnedDocument>> {
+        self.pinned_documents.lock().unwrap().get(&document_id).cloned()
+    }
+
+    /// 移除常驻文档；进行中的渲染结束后文档才真正销毁
+    pub fn release_document(&self, document_id: u32) -> bool {
+        self.pinned_documents.lock().unwrap().remove(&document_id).is_some()
+    }
+
+    /// 登记批量渲染会话，顺便清理已结束且结果已取完的会话
+    pub fn register_session(&self, session: Arc<RenderSession>) -> u32 {
+        let id = self.next_session_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, s| !s.snapshot().done);
+        sessions.insert(id, session);
+        id
+    }
+
+    pub fn session(&self, session_id: u32) -> Option<Arc<RenderSession>> {
+        self.sessions.lock().unwrap().get(&session_id).cloned()
+    }
+
+    pub fn remove_session(&self, session_id: u32) -> Option<Arc<RenderSession>> {
+        self.sessions.lock().unwrap().remove(&session_id)
+    }
+
+    /// 取消本实例所有会话的剩余页面，返回取消的页数
+    pub fn cancel_sessions(&self) -> u32 {
+        self.sessions.lock().unwrap().values().map(|s| s.cancel()).sum()
+    }
+
+    /// 释放本实例所有常驻文档，返回释放的数量
+    pub fn release_documents(&self) -> u32 {
+        let mut documents = self.pinned_documents.lock().unwrap();
+        let count = documents.len() as u32;
+        documents.clear();
+        count
+    }
+}
@@ -0,0 +1,188 @@
+//! JS 自定义编码器（registerEncoder）
+//!
+//! 调用方可以注册一个 JS 函数作为编码器（内部也可以转调其他原生模块，如自建的 AVIF 编码服务），
+//! 通过 `encoderBackend: <名称>` 选用。渲染、缩放、批量调度仍由本模块完成，编码这一步
+//! 把位图交给 JS，渲染线程等待 JS 调用 completeEncodeRequest 交回编码结果。
+//!
+//! 注册表是进程级的：同一进程中多个 JS 环境注册同名编码器时，后注册的覆盖先注册的。
+
+use crate::renderer::OutputFormat;
+use crate::EncodeRequest;
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::Status;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::ThreadId;
+use std::time::Duration;
+
+/// 等待 JS 编码结果的最长时间
+const ENCODE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 已注册的编码器
+pub struct CustomEncoder {
+    /// 输出格式名（如 avif），写入 PageResult.format；为空时沿用 format 选项
+    pub format: Option<String>,
+    callback: ThreadsafeFunction<EncodeRequest, ErrorStrategy::CalleeHandled>,
+    /// 注册所在的 JS 线程，在该线程上同步等待回调会死锁
+    js_thread: ThreadId,
+}
+
+static ENCODERS: Lazy<Mutex<HashMap<String, Arc<CustomEncoder>>>> = Lazy::new(Default::default);
+static PENDING: Lazy<PendingRequests> = Lazy::new(Default::default);
+static NEXT_REQUEST_ID: AtomicU32 = AtomicU32::new(1);
+
+type EncodeResult = Result<Vec<u8>, String>;
+
+/// 等待 JS 交回的编码请求（request_id -> 结果，未完成时为 None）
+#[derive(Default)]
+struct PendingRequests {
+    results: Mutex<HashMap<u32, Option<EncodeResult>>>,
+    completed: Condvar,
+}
+
+impl PendingRequests {
+    fn begin(&self) -> u32 {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        self.results.lock().unwrap().insert(id, None);
+        id
+    }
+
+    fn complete(&self, request_id: u32, result: EncodeResult) -> bool {
+        let mut results = self.results.lock().unwrap();
+        match results.get_mut(&request_id) {
+            Some(slot) => {
+                *slot = Some(result);
+                self.completed.notify_all();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn wait(&self, request_id: u32, timeout: Duration) -> EncodeResult {
+        let results = self.results.lock().unwrap();
+        let (mut results, _) = self
+            .completed
+            .wait_timeout_while(results, timeout, |r| matches!(r.get(&request_id), Some(None)))
+            .unwrap();
+        match results.remove(&request_id) {
+            Some(Some(result)) => result,
+            _ => Err(format!("Custom encoder timed out after {}s", timeout.as_secs())),
+        }
+    }
+
+    fn abandon(&self, request_id: u32) {
+        self.results.lock().unwrap().remove(&request_id);
+    }
+}
+
+/// 注册（或替换）编码器；名称不能与内置后端重名
+pub fn register(
+    name: &str,
+    format: Option<String>,
+    callback: ThreadsafeFunction<EncodeRequest, ErrorStrategy::CalleeHandled>,
+) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() || ["auto", "libwebp", "image", "builtin", "none"].contains(&name.to_lowercase().as_str()) {
+        return Err(format!("Invalid custom encoder name: {:?}", name));
+    }
+    let encoder = CustomEncoder {
+        format: format.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty()),
+        callback,
+        js_thread: std::thread::current().id(),
+    };
+    ENCODERS.lock().unwrap().insert(name.to_string(), Arc::new(encoder));
+    Ok(())
+}
+
+pub fn unregister(name: &str) -> bool {
+    ENCODERS.lock().unwrap().remove(name.trim()).is_some()
+}
+
+pub fn get(name: &str) -> Option<Arc<CustomEncoder>> {
+    ENCODERS.lock().unwrap().get(name.trim()).cloned()
+}
+
+/// 已注册的编码器（名称, 输出格式名）
+pub fn registered() -> Vec<(String, Option<String>)> {
+    let mut list: Vec<_> = ENCODERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, encoder)| (name.clone(), encoder.format.clone()))
+        .collect();
+    list.sort();
+    list
+}
+
+/// JS 交回编码结果；请求已超时或不存在时忽略
+pub fn complete(request_id: u32, result: EncodeResult) -> bool {
+    PENDING.complete(request_id, result)
+}
+
+/// 编码参数
+pub struct EncodeInput<'a> {
+    pub format: OutputFormat,
+    pub quality: u32,
+    pub speed: Option<u32>,
+    pub pixel_format: &'static str,
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CustomEncoder {
+    /// 把位图交给 JS 编码并等待结果
+    pub fn encode(&self, name: &str, input: EncodeInput) -> EncodeResult {
+        if std::thread::current().id() == self.js_thread {
+            return Err(format!(
+                "Custom encoder {} cannot be used from a synchronous call; use an async render API",
+                name
+            ));
+        }
+
+        let request_id = PENDING.begin();
+        let request = EncodeRequest {
+            request_id,
+            encoder: name.to_string(),
+            format: input.format.as_str().to_string(),
+            quality: input.quality,
+            speed: input.speed,
+            width: input.width,
+            height: input.height,
+            stride: input.width * 4,
+            pixel_format: input.pixel_format.to_string(),
+            buffer: Buffer::from(input.data.to_vec()),
+        };
+        let status = self.callback.call(Ok(request), ThreadsafeFunctionCallMode::NonBlocking);
+        if status != Status::Ok {
+            PENDING.abandon(request_id);
+            return Err(format!("Failed to call custom encoder {}: {}", name, status));
+        }
+        PENDING
+            .wait(request_id, ENCODE_TIMEOUT)
+            .map_err(|e| format!("Custom encoder {} failed: {}", name, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_requests_complete_once() {
+        let pending = PendingRequests::default();
+        let id = pending.begin();
+        assert!(pending.complete(id, Ok(vec![1, 2, 3])));
+        assert_eq!(pending.wait(id, Duration::from_millis(10)), Ok(vec![1, 2, 3]));
+        // 已取走的请求再完成会被忽略
+        assert!(!pending.complete(id, Ok(vec![])));
+
+        let id = pending.begin();
+        assert!(pending.wait(id, Duration::from_millis(10)).is_err());
+        assert!(!pending.complete(id, Err("late".to_string())));
+    }
+}
@@ -0,0 +1,419 @@
+//! 运行环境诊断
+//!
+//! 按与 `create_pdfium` 相同的顺序探测 PDFium 动态库，并执行一次最小渲染，
+//! 让部署失败的实例能够自行报告原因（找不到库、符号不匹配、渲染失败等）。
+//! 同时记录运行期间的绑定状态：库曾经加载成功、之后又无法加载（如容器层变化导致
+//! 库文件丢失）时自动重试绑定，仍然失败则进入 `PDFIUM_UNAVAILABLE` 状态。
+//! 就绪探针还可以用调用方提供的样本文档走一遍完整的加载、渲染、编码流程。
+//!
+//! 绑定成功的库会常驻进程并记住其路径，之后的调用直接绑定到它，不再逐个探测候选库，
+//! 也不会在每次调用结束后卸载、下次重新从磁盘加载。`Pdfium` 实例本身不能缓存：
+//! thread_safe 模式下每个实例在存活期间持有 PDFium 的全局锁，缓存实例会让其他线程永远等待。
+
+use crate::config::RenderConfig;
+use crate::flight_recorder;
+use crate::renderer::{load_error, PdfRenderer};
+use crate::scheduler::DocumentSource;
+use crate::PageResult;
+use pdfium_render::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 自定义的库搜索设置
+#[derive(Debug, Clone)]
+pub(crate) struct LibrarySearch {
+    /// 库文件名（如 `libpdfium.so.1`），也可以是完整路径
+    pub names: Vec<String>,
+    /// 在这些目录中查找 `names`；为空时按文件名交给系统加载器搜索
+    pub dirs: Vec<String>,
+    /// 自定义候选都失败后是否继续尝试默认的库名
+    pub include_defaults: bool,
+    /// 库曾经加载成功、之后绑定失败时的重试次数
+    pub rebind_attempts: u32,
+    /// 重试间隔（毫秒，第 n 次重试等待 n 倍）
+    pub rebind_delay_ms: u32,
+}
+
+/// 常驻的库：持有一份绑定（从不调用其中的函数），让动态库在调用之间保持加载
+struct Resident {
+    label: String,
+    path: Option<PathBuf>,
+    _bindings: Box<dyn PdfiumLibraryBindings>,
+}
+
+// SAFETY: 绑定只用于持有动态库的引用计数，不会调用任何 PDFium 函数；
+// 释放时只卸载动态库（dlclose / FreeLibrary），可以在任意线程进行。
+unsafe impl Send for Resident {}
+
+/// 最近一次绑定成功的库，设置新的搜索顺序时清除
+static RESIDENT: Mutex<Option<Resident>> = Mutex::new(None);
+
+static LIBRARY_SEARCH: Mutex<LibrarySearch> = Mutex::new(LibrarySearch {
+    names: Vec::new(),
+    dirs: Vec::new(),
+    include_defaults: true,
+    rebind_attempts: 2,
+    rebind_delay_ms: 100,
+});
+
+/// 运行期间的 PDFium 绑定状态（进程内所有调用共享）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BindingHealth {
+    /// 是否曾经绑定成功
+    pub ever_loaded: bool,
+    /// 连续绑定失败次数（绑定成功后清零）
+    pub consecutive_failures: u32,
+    /// 最近一次绑定失败的原因
+    pub last_error: Option<String>,
+    /// 进入不可用状态的时间（Unix 毫秒）
+    pub unavailable_since: Option<i64>,
+    /// 经过重试才绑定成功的次数
+    pub rebinds: u32,
+}
+
+impl BindingHealth {
+    const fn new() -> Self {
+        Self {
+            ever_loaded: false,
+            consecutive_failures: 0,
+            last_error: None,
+            unavailable_since: None,
+            rebinds: 0,
+        }
+    }
+
+    fn succeeded(&mut self, retried: bool) {
+        self.ever_loaded = true;
+        self.consecutive_failures = 0;
+        self.unavailable_since = None;
+        self.rebinds += retried as u32;
+    }
+
+    fn failed(&mut self, error: String, now: i64) {
+        self.consecutive_failures += 1;
+        self.last_error = Some(error);
+        self.unavailable_since.get_or_insert(now);
+    }
+
+    /// available：最近一次绑定成功；unavailable：最近一次绑定失败；unknown：尚未绑定过
+    pub fn state(&self) -> &'static str {
+        if self.consecutive_failures > 0 {
+            "unavailable"
+        } else if self.ever_loaded {
+            "available"
+        } else {
+            "unknown"
+        }
+    }
+}
+
+static BINDING_HEALTH: Mutex<BindingHealth> = Mutex::new(BindingHealth::new());
+
+/// 当前的绑定状态
+pub(crate) fn binding_health() -> BindingHealth {
+    BINDING_HEALTH.lock().unwrap().clone()
+}
+
+/// 按搜索顺序绑定 PDFium；库曾经加载成功时，失败后按配置自动重试
+///
+/// 首次加载失败通常是部署问题，直接返回错误；结果都记录到绑定状态中。
+pub(crate) fn bind_with_retry() -> Result<Box<dyn PdfiumLibraryBindings>, String> {
+    let (attempts, delay_ms) = {
+        let search = LIBRARY_SEARCH.lock().unwrap();
+        (search.rebind_attempts, search.rebind_delay_ms)
+    };
+    let mut attempt = 0;
+    loop {
+        match bind_first() {
+            Ok(bindings) => {
+                BINDING_HEALTH.lock().unwrap().succeeded(attempt > 0);
+                return Ok(bindings);
+            }
+            Err(_) if attempt < attempts && binding_health().ever_loaded => {
+                attempt += 1;
+                std::thread::sleep(Duration::from_millis(delay_ms as u64 * attempt as u64));
+            }
+            Err(error) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as i64)
+                    .unwrap_or(0);
+                BINDING_HEALTH.lock().unwrap().failed(error.clone(), now);
+                return Err(error);
+            }
+        }
+    }
+}
+
+/// 绑定常驻的库；没有常驻库时先探测出一个
+///
+/// 常驻库绑定失败时放弃它，重新按搜索顺序探测。
+fn bind_first() -> Result<Box<dyn PdfiumLibraryBindings>, String> {
+    let mut resident = RESIDENT.lock().unwrap();
+    if let Some(library) = resident.as_ref() {
+        match bind_candidate(library.path.as_ref()) {
+            Ok(bindings) => return Ok(bindings),
+            Err(_) => *resident = None,
+        }
+    }
+
+    let library = resident.insert(discover()?);
+    bind_candidate(library.path.as_ref()).map_err(|e| describe_error(&e))
+}
+
+/// 依次尝试所有候选库，第一个绑定成功的库连同这次的绑定成为常驻库；都失败时返回最后一个错误
+fn discover() -> Result<Resident, String> {
+    let mut last_error = None;
+    for (label, path) in library_candidates() {
+        match bind_candidate(path.as_ref()) {
+            Ok(bindings) => return Ok(Resident { label, path, _bindings: bindings }),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.map(|e| describe_error(&e)).unwrap_or_default())
+}
+
+/// 常驻库的路径（尚未绑定成功时为 None）
+pub(crate) fn resident_library() -> Option<String> {
+    RESIDENT.lock().unwrap().as_ref().map(|library| library.label.clone())
+}
+
+/// 当前的库搜索设置
+pub(crate) fn library_search() -> LibrarySearch {
+    LIBRARY_SEARCH.lock().unwrap().clone()
+}
+
+/// 设置库搜索顺序并放弃常驻的库，之后新建的 PDFium 实例生效
+pub(crate) fn set_library_search(search: LibrarySearch) {
+    *LIBRARY_SEARCH.lock().unwrap() = search;
+    *RESIDENT.lock().unwrap() = None;
+}
+
+/// 候选库路径，顺序与 `create_pdfium` 的绑定顺序一致
+pub(crate) fn library_candidates() -> Vec<(String, Option<PathBuf>)> {
+    candidates_for(&LIBRARY_SEARCH.lock().unwrap())
+}
+
+fn candidates_for(search: &LibrarySearch) -> Vec<(String, Option<PathBuf>)> {
+    let mut candidates = Vec::new();
+    for name in &search.names {
+        if search.dirs.is_empty() {
+            candidates.push((name.clone(), Some(PathBuf::from(name))));
+        } else {
+            for dir in &search.dirs {
+                let path = Path::new(dir).join(name);
+                candidates.push((path.display().to_string(), Some(path)));
+            }
+        }
+    }
+
+    if search.names.is_empty() || search.include_defaults {
+        let local = Pdfium::pdfium_platform_library_name_at_path("./");
+        candidates.push((local.display().to_string(), Some(local)));
+        candidates.push((
+            format!("{} (system)", Pdfium::pdfium_platform_library_name().to_string_lossy()),
+            None,
+        ));
+    }
+    candidates
+}
+
+/// 绑定单个候选库，None 表示使用系统库搜索路径
+pub(crate) fn bind_candidate(
+    path: Option<&PathBuf>,
+) -> Result<Box<dyn PdfiumLibraryBindings>, PdfiumError> {
+    match path {
+        Some(path) => Pdfium::bind_to_library(path),
+        None => Pdfium::bind_to_system_library(),
+    }
+}
+
+/// 可读的绑定错误信息
+///
+/// `PdfiumError` 的 Display 输出多行的 Debug 格式；动态库错误的 Display 只有
+/// "dlopen failed"，具体原因（文件不存在、缺少依赖库、架构不符等）在 source 中。
+pub(crate) fn describe_error(error: &PdfiumError) -> String {
+    match error {
+        PdfiumError::LoadLibraryError(e) => std::error::Error::source(e).unwrap_or(e).to_string(),
+        e => e.to_string(),
+    }
+}
+
+/// 加载内置的最小 PDF 并渲染第一页
+pub(crate) fn test_render(pdfium: &Pdfium) -> Result<(), String> {
+    let document = pdfium
+        .load_pdf_from_byte_slice(crate::MINIMAL_PDF, None)
+        .map_err(|e| format!("Failed to load test PDF: {}", e))?;
+    let page = document
+        .pages()
+        .get(0)
+        .map_err(|e| format!("Failed to get test page: {}", e))?;
+    let bitmap = page
+        .render_with_config(&PdfRenderConfig::new().set_target_width(64))
+        .map_err(|e| format!("Failed to render test page: {}", e))?;
+
+    if bitmap.width() <= 0 || bitmap.as_raw_bytes().is_empty() {
+        return Err("Test render produced an empty bitmap".to_string());
+    }
+    Ok(())
+}
+
+/// 样本检查最多渲染的页数
+pub(crate) const SAMPLE_MAX_PAGES: usize = 8;
+
+/// 未指定 totalTimeBudgetMs 时样本检查的时间预算（毫秒），超出后剩余页面跳过
+pub(crate) const SAMPLE_TIME_BUDGET_MS: u32 = 30_000;
+
+/// 样本文档检查的各阶段结果
+pub(crate) struct SampleCheck {
+    /// 绑定 PDFium 的耗时（毫秒）
+    pub bind_time: u32,
+    /// 打开样本文档的耗时（毫秒）
+    pub load_time: u32,
+    pub num_pages: u32,
+    pub pages: Vec<PageResult>,
+    /// 绑定或加载失败的原因
+    pub error: Option<String>,
+}
+
+impl SampleCheck {
+    pub fn failed(error: String) -> Self {
+        Self {
+            bind_time: 0,
+            load_time: 0,
+            num_pages: 0,
+            pages: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// 用样本文档走一遍绑定、加载、渲染、编码流程
+///
+/// 页码去重后最多取 `SAMPLE_MAX_PAGES` 页，不经过渲染缓存，各页的渲染和编码耗时
+/// 在 PageResult 中。
+pub(crate) fn sample_check(source: &DocumentSource, page_nums: &[u32], mut config: RenderConfig) -> SampleCheck {
+    let mut pages: Vec<u32> = Vec::new();
+    for &page_num in page_nums {
+        if !pages.contains(&page_num) && pages.len() < SAMPLE_MAX_PAGES {
+            pages.push(page_num);
+        }
+    }
+    config.total_time_budget_ms.get_or_insert(SAMPLE_TIME_BUDGET_MS);
+
+    let bind_start = std::time::Instant::now();
+    let pdfium = crate::create_pdfium();
+    let bind_time = bind_start.elapsed().as_millis() as u32;
+    let pdfium = match pdfium {
+        Ok(pdfium) => pdfium,
+        Err(e) => return SampleCheck { bind_time, ..SampleCheck::failed(e.reason) },
+    };
+    let mut check = SampleCheck {
+        bind_time,
+        load_time: 0,
+        num_pages: 0,
+        pages: Vec::new(),
+        error: None,
+    };
+    let renderer = PdfRenderer::new(&pdfium, config);
+
+    let load_start = std::time::Instant::now();
+    let (document, fingerprint) = match source {
+        DocumentSource::File(path) => {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            let document = renderer.check_input(size, &pages).and_then(|_| {
+                renderer
+                    .load_document_from_file(path)
+                    .map_err(|e| load_error("Failed to load PDF from file", &e))
+            });
+            (document, flight_recorder::fingerprint_file(path))
+        }
+        DocumentSource::Bytes(bytes) => {
+            let document = renderer.check_input(bytes.len() as u64, &pages).and_then(|_| {
+                renderer
+                    .load_document(bytes)
+                    .map_err(|e| load_error("Failed to load PDF", &e))
+            });
+            (document, flight_recorder::fingerprint_bytes(bytes))
+        }
+    };
+    check.load_time = load_start.elapsed().as_millis() as u32;
+    let document = match document {
+        Ok((document, _)) => document,
+        Err(e) => {
+            check.error = Some(e);
+            return check;
+        }
+    };
+
+    // warmup: 前缀的来源不参与渲染缓存，每次都真正渲染
+    match renderer.render_document_pages(&document, &pages, &format!("warmup:{}", fingerprint)) {
+        Ok((num_pages, results)) => {
+            check.num_pages = num_pages;
+            check.pages = results;
+        }
+        Err(e) => check.error = Some(e),
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_names_come_first() {
+        let search = LibrarySearch {
+            names: vec!["libpdfium.so.1".to_string()],
+            dirs: vec!["/opt/a".to_string(), "/opt/b".to_string()],
+            include_defaults: false,
+            rebind_attempts: 0,
+            rebind_delay_ms: 0,
+        };
+        let paths: Vec<_> = candidates_for(&search).into_iter().map(|(_, p)| p.unwrap()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("/opt/a/libpdfium.so.1"), PathBuf::from("/opt/b/libpdfium.so.1")]
+        );
+
+        let search = LibrarySearch {
+            dirs: Vec::new(),
+            include_defaults: true,
+            ..search
+        };
+        let candidates = candidates_for(&search);
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].1, Some(PathBuf::from("libpdfium.so.1")));
+        assert_eq!(candidates[2].1, None);
+    }
+
+    #[test]
+    fn test_binding_health_transitions() {
+        let mut health = BindingHealth::new();
+        assert_eq!(health.state(), "unknown");
+        health.succeeded(false);
+        assert_eq!(health.state(), "available");
+
+        health.failed("dlopen failed".to_string(), 1000);
+        health.failed("dlopen failed".to_string(), 2000);
+        assert_eq!(health.state(), "unavailable");
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.unavailable_since, Some(1000));
+
+        health.succeeded(true);
+        assert_eq!(health.state(), "available");
+        assert_eq!((health.rebinds, health.unavailable_since), (1, None));
+        assert_eq!(health.last_error.as_deref(), Some("dlopen failed"));
+    }
+
+    #[test]
+    fn test_describe_load_error() {
+        let Err(error) = bind_candidate(Some(&PathBuf::from("/nonexistent/libpdfium.so"))) else {
+            panic!("binding a missing library should fail");
+        };
+        let message = describe_error(&error);
+        assert!(message.contains("/nonexistent/libpdfium.so"), "{}", message);
+        assert!(!message.contains('\n'), "{}", message);
+    }
+}
@@ -0,0 +1,121 @@
+//! 文档信息字典
+//!
+//! 读取标题、作者、创建日期等元数据和 PDF 版本号，供 getDocumentInfo 在不渲染、
+//! 不逐页解析内容的情况下返回。PDF 日期（D:YYYYMMDDHHmmSSOHH'mm'）转换为 ISO 8601，
+//! 无法识别的日期原样返回。
+
+use pdfium_render::prelude::*;
+
+/// 文档元数据
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+    /// PDF 版本（如 "1.7"），新建的文档为空
+    pub pdf_version: Option<String>,
+}
+
+pub fn read(document: &PdfDocument) -> Metadata {
+    let metadata = document.metadata();
+    // 空字符串视为未设置
+    let tag = |tag_type| {
+        metadata
+            .get(tag_type)
+            .map(|tag| tag.value().trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let date = |tag_type| tag(tag_type).map(|value: String| to_iso_date(&value).unwrap_or(value));
+    Metadata {
+        title: tag(PdfDocumentMetadataTagType::Title),
+        author: tag(PdfDocumentMetadataTagType::Author),
+        subject: tag(PdfDocumentMetadataTagType::Subject),
+        keywords: tag(PdfDocumentMetadataTagType::Keywords),
+        creator: tag(PdfDocumentMetadataTagType::Creator),
+        producer: tag(PdfDocumentMetadataTagType::Producer),
+        creation_date: date(PdfDocumentMetadataTagType::CreationDate),
+        modification_date: date(PdfDocumentMetadataTagType::ModificationDate),
+        pdf_version: version_string(document.version()),
+    }
+}
+
+fn version_string(version: PdfDocumentVersion) -> Option<String> {
+    let raw = match version {
+        PdfDocumentVersion::Unset => return None,
+        PdfDocumentVersion::Pdf1_0 => 10,
+        PdfDocumentVersion::Pdf1_1 => 11,
+        PdfDocumentVersion::Pdf1_2 => 12,
+        PdfDocumentVersion::Pdf1_3 => 13,
+        PdfDocumentVersion::Pdf1_4 => 14,
+        PdfDocumentVersion::Pdf1_5 => 15,
+        PdfDocumentVersion::Pdf1_6 => 16,
+        PdfDocumentVersion::Pdf1_7 => 17,
+        PdfDocumentVersion::Pdf2_0 => 20,
+        PdfDocumentVersion::Other(raw) => raw,
+    };
+    Some(format!("{}.{}", raw / 10, raw % 10))
+}
+
+/// 把 PDF 日期转换为 ISO 8601，如 "D:20240301120000+08'00'" → "2024-03-01T12:00:00+08:00"
+///
+/// 只有年份是必需的，缺少的月、日默认为 01，时间默认为 00；没有时区时不带时区后缀。
+pub fn to_iso_date(value: &str) -> Option<String> {
+    let value = value.strip_prefix("D:").unwrap_or(value);
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    if digits < 4 || digits % 2 != 0 || digits > 14 {
+        return None;
+    }
+    let (stamp, zone) = value.split_at(digits);
+    let field = |start: usize, default: &'static str| stamp.get(start..start + 2).unwrap_or(default);
+    let (month, day) = (field(4, "01"), field(6, "01"));
+    let (hour, minute, second) = (field(8, "00"), field(10, "00"), field(12, "00"));
+    let in_range = |v: &str, min: u32, max: u32| v.parse::<u32>().map(|n| (min..=max).contains(&n)).unwrap_or(false);
+    if !(in_range(month, 1, 12) && in_range(day, 1, 31) && in_range(hour, 0, 23) && in_range(minute, 0, 59) && in_range(second, 0, 59)) {
+        return None;
+    }
+
+    let offset = match zone.as_bytes().first() {
+        None => String::new(),
+        Some(b'Z') => "Z".to_string(),
+        Some(sign @ (b'+' | b'-')) => {
+            let parts: Vec<&str> = zone[1..].split('\'').filter(|part| !part.is_empty()).collect();
+            let hours = parts.first().filter(|h| h.len() == 2 && in_range(h, 0, 23))?;
+            let minutes = parts.get(1).copied().unwrap_or("00");
+            if minutes.len() != 2 || !in_range(minutes, 0, 59) {
+                return None;
+            }
+            format!("{}{}:{}", *sign as char, hours, minutes)
+        }
+        Some(_) => return None,
+    };
+    Some(format!("{}-{}-{}T{}:{}:{}{}", &stamp[..4], month, day, hour, minute, second, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_iso_date() {
+        assert_eq!(to_iso_date("D:20240301120000+08'00'").as_deref(), Some("2024-03-01T12:00:00+08:00"));
+        assert_eq!(to_iso_date("D:20240301120000-05'30").as_deref(), Some("2024-03-01T12:00:00-05:30"));
+        assert_eq!(to_iso_date("D:20240301120000Z").as_deref(), Some("2024-03-01T12:00:00Z"));
+        assert_eq!(to_iso_date("D:2024").as_deref(), Some("2024-01-01T00:00:00"));
+        assert_eq!(to_iso_date("20240301").as_deref(), Some("2024-03-01T00:00:00"));
+        assert_eq!(to_iso_date("D:20241301"), None);
+        assert_eq!(to_iso_date("D:202"), None);
+        assert_eq!(to_iso_date("Friday"), None);
+    }
+
+    #[test]
+    fn test_version_string() {
+        assert_eq!(version_string(PdfDocumentVersion::Pdf1_7).as_deref(), Some("1.7"));
+        assert_eq!(version_string(PdfDocumentVersion::Other(21)).as_deref(), Some("2.1"));
+        assert_eq!(version_string(PdfDocumentVersion::Unset), None);
+    }
+}
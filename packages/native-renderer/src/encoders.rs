@@ -0,0 +1,145 @@
+//! 编码器后端
+//!
+//! 同一输出格式可能有多个编译进来的编码实现（如 libwebp 有损编码和 image crate 的
+//! 纯 Rust 无损编码）。调用方通过 `encoderBackend` 按调用选择，`auto` 使用表中第一个。
+
+use crate::renderer::{OutputFormat, PixelFormat};
+
+/// 编码器后端
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    /// libwebp（有损，支持 quality / method）
+    Libwebp,
+    /// image crate（纯 Rust 实现）
+    Image,
+    /// 内置的 TIFF 写入器
+    Builtin,
+    /// ravif（纯 Rust 的 rav1e AV1 编码）
+    Ravif,
+}
+
+/// 已编译的后端，每种格式的第一项为默认值
+pub const BACKENDS: &[(OutputFormat, Backend, &str)] = &[
+    (OutputFormat::WebP, Backend::Libwebp, "lossy WebP via libwebp (quality, method)"),
+    (OutputFormat::WebP, Backend::Image, "lossless WebP via the image crate (pure Rust)"),
+    (OutputFormat::Png, Backend::Image, "PNG via the image crate"),
+    (OutputFormat::Jpg, Backend::Image, "baseline JPEG via the image crate"),
+    (OutputFormat::Tiff, Backend::Builtin, "uncompressed TIFF"),
+    (OutputFormat::Avif, Backend::Ravif, "AVIF via ravif (pure Rust, quality, speed)"),
+];
+
+impl Backend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Backend::Libwebp => "libwebp",
+            Backend::Image => "image",
+            Backend::Builtin => "builtin",
+            Backend::Ravif => "ravif",
+        }
+    }
+}
+
+/// 按名称选择格式的编码后端（`auto` 或空值使用默认后端）
+///
+/// 未编译进来的后端（如 mozjpeg）返回错误，而不是静默回退；没有内置后端的格式
+/// 只能由 registerEncoder 注册的编码器编码。
+pub fn resolve(format: OutputFormat, name: &str) -> Result<Backend, String> {
+    let name = name.trim().to_lowercase();
+    if !BACKENDS.iter().any(|(f, _, _)| *f == format) {
+        return Err(format!(
+            "No built-in {} encoder; register one with registerEncoder and select it via encoderBackend (got: {})",
+            format.as_str(),
+            if name.is_empty() { "auto" } else { &name }
+        ));
+    }
+    BACKENDS
+        .iter()
+        .filter(|(f, _, _)| *f == format)
+        .find(|(_, backend, _)| name.is_empty() || name == "auto" || backend.as_str() == name)
+        .map(|(_, backend, _)| *backend)
+        .ok_or_else(|| format!("Encoder backend not available for {}: {}", format.as_str(), name))
+}
+
+/// 用 ravif 把 4 通道像素编码为 AVIF
+///
+/// `quality` 为 0-100，`speed` 为 0-10（0 最慢但压缩最好）；ravif 不接受 0，按 1 处理。
+/// 完全不透明的图像不写入 alpha 通道。
+pub fn encode_avif(data: &[u8], width: u32, height: u32, quality: u8, speed: u8, pixel_format: PixelFormat) -> Result<Vec<u8>, String> {
+    let pixels: Vec<ravif::RGBA8> = data
+        .chunks_exact(4)
+        .map(|p| match pixel_format {
+            PixelFormat::Rgba => ravif::RGBA8::new(p[0], p[1], p[2], p[3]),
+            PixelFormat::Bgra => ravif::RGBA8::new(p[2], p[1], p[0], p[3]),
+        })
+        .collect();
+    if pixels.len() != width as usize * height as usize {
+        return Err(format!("AVIF encoding failed: expected {}x{} pixels, got {}", width, height, pixels.len()));
+    }
+    let quality = quality.clamp(1, 100) as f32;
+    let encoded = ravif::Encoder::new()
+        .with_quality(quality)
+        .with_alpha_quality(quality)
+        .with_speed(speed.clamp(1, 10))
+        .encode_rgba(ravif::Img::new(&pixels[..], width as usize, height as usize))
+        .map_err(|e| format!("AVIF encoding failed: {}", e))?;
+    Ok(encoded.avif_file)
+}
+
+/// webpMethodAuto：按像素数调整 WebP method，让每页的编码耗时大致不随页面尺寸变化
+///
+/// `base` 是常规页面（约 1-4 百万像素）使用的 method；缩略图提高压缩率，
+/// 超大位图降低 method，避免单页编码耗时成倍增长。
+pub fn auto_webp_method(base: i32, width: u32, height: u32) -> i32 {
+    let pixels = width as u64 * height as u64;
+    let shift = match pixels {
+        0..=500_000 => 2,
+        500_001..=1_000_000 => 1,
+        1_000_001..=4_000_000 => 0,
+        4_000_001..=12_000_000 => -2,
+        _ => -4,
+    };
+    (base + shift).clamp(0, 6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve(OutputFormat::WebP, "auto"), Ok(Backend::Libwebp));
+        assert_eq!(resolve(OutputFormat::WebP, "Image"), Ok(Backend::Image));
+        assert_eq!(resolve(OutputFormat::Png, ""), Ok(Backend::Image));
+        assert_eq!(
+            resolve(OutputFormat::Jpg, "mozjpeg"),
+            Err("Encoder backend not available for jpg: mozjpeg".to_string())
+        );
+        assert_eq!(resolve(OutputFormat::Avif, "auto"), Ok(Backend::Ravif));
+        assert_eq!(
+            resolve(OutputFormat::Avif, "libavif"),
+            Err("Encoder backend not available for avif: libavif".to_string())
+        );
+    }
+
+    #[test]
+    fn test_encode_avif() {
+        let mut pixels = Vec::new();
+        for y in 0..16u8 {
+            for x in 0..16u8 {
+                pixels.extend_from_slice(&[x * 16, y * 16, 128, 255]);
+            }
+        }
+        let avif = encode_avif(&pixels, 16, 16, 50, 10, PixelFormat::Bgra).unwrap();
+        assert_eq!(&avif[4..12], b"ftypavif");
+        assert!(encode_avif(&pixels, 16, 8, 50, 10, PixelFormat::Rgba).is_err());
+    }
+
+    #[test]
+    fn test_auto_webp_method() {
+        assert_eq!(auto_webp_method(4, 300, 400), 6);
+        assert_eq!(auto_webp_method(4, 1280, 1810), 4);
+        assert_eq!(auto_webp_method(4, 2480, 3508), 2);
+        assert_eq!(auto_webp_method(4, 9000, 12000), 0);
+        assert_eq!(auto_webp_method(1, 9000, 12000), 0);
+    }
+}
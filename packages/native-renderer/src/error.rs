@@ -2,6 +2,7 @@
 
 use thiserror::Error;
 
+#[allow(dead_code, clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum RenderError {
     #[error("Failed to load PDF: {0}")]
@@ -21,4 +22,71 @@ pub enum RenderError {
     
     #[error("PDFium library not available: {0}")]
     PdfiumNotAvailable(String),
+
+    #[error("Resource limit exceeded: {limit} (actual {actual}, max {max})")]
+    LimitExceeded {
+        limit: &'static str,
+        actual: u64,
+        max: u64,
+    },
+}
+
+/// 单页失败的类别（供批量调用方按类别决定是否重试）
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorCode {
+    /// 页码无效（小于 1）
+    InvalidPage,
+    /// 文档加载失败
+    LoadFailed,
+    /// PDFium 渲染失败
+    RenderFailed,
+    /// 图像编码失败
+    EncodeFailed,
+    /// 超出资源限制（maxPageObjects、maxFileSize 等）
+    LimitExceeded,
+    /// 选项无效（如画布超出尺寸上限）
+    InvalidOptions,
+    /// 单页处理超时
+    Timeout,
+    /// 单页渲染超出 pageTimeoutMs，已放弃该页
+    RenderTimeout,
+    /// 任务被取消（如模块正在关闭）
+    Cancelled,
+    /// 未尝试渲染（如超出总时间预算）
+    Skipped,
+    /// 页码大于文档页数（未尝试渲染，通常是调用方多传了页码）
+    OutOfRange,
+    /// PDFium 动态库无法加载（自动重试后仍失败，状态见 getDiagnostics）
+    PdfiumUnavailable,
+    /// 文档已加密，没有提供密码或密码都不对（调用方可以提示用户重新输入）
+    WrongPassword,
+    /// 渲染成功但写入文件失败（renderPagesToFiles；convert() 保存文件或上传失败时 JS 侧使用同一错误码）
+    OutputFailed,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidPage => "INVALID_PAGE",
+            ErrorCode::LoadFailed => "LOAD_FAILED",
+            ErrorCode::RenderFailed => "RENDER_FAILED",
+            ErrorCode::EncodeFailed => "ENCODE_FAILED",
+            ErrorCode::LimitExceeded => "LIMIT_EXCEEDED",
+            ErrorCode::InvalidOptions => "INVALID_OPTIONS",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::RenderTimeout => "RENDER_TIMEOUT",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::Skipped => "SKIPPED",
+            ErrorCode::OutOfRange => "OUT_OF_RANGE",
+            ErrorCode::PdfiumUnavailable => "PDFIUM_UNAVAILABLE",
+            ErrorCode::WrongPassword => "WRONG_PASSWORD",
+            ErrorCode::OutputFailed => "OUTPUT_FAILED",
+        }
+    }
+
+    /// 转换为结果中的 `errorCode` 字段
+    pub fn code(self) -> Option<String> {
+        Some(self.as_str().to_string())
+    }
 }
@@ -0,0 +1,552 @@
+//! 流式渲染前的取数计划（options.fetchPlan）
+//!
+//! 逐块按需读取时，PDFium 解析到一个对象才发现下一个需要的块，每次往返只换来一个缓存块。
+//! 这里在渲染前读一遍交叉引用表和页面树，按对象偏移算出所请求页面需要的字节范围
+//! （页面对象、内容流、资源和注释外观流），交给 JS fetcher 一次批量获取。
+//!
+//! 只解析传统的 xref 表（包括增量更新的 /Prev 链）。交叉引用流和对象流中的对象
+//! （PDF 1.5 压缩结构）没有可用的偏移，整个文件使用交叉引用流时放弃计划，渲染照常逐块读取。
+//! 计划只是预取提示，范围算多了只会多取数据，算少了由逐块读取补上，不影响渲染结果。
+//!
+//! 线性化（Web 优化）文件不需要解析 xref：文件开头的线性化字典给出了首页段落的结束位置（/E）、
+//! 提示表（/H）和主交叉引用表（/T），打开文档前按这些范围预取，渲染首页几乎不再需要逐块往返。
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+
+/// 读取文件末尾查找 startxref 的长度
+const TAIL_SIZE: u64 = 1024;
+/// 读取单个对象字典的初始窗口，字典更长时加倍直到上限
+const OBJECT_WINDOW: usize = 16 * 1024;
+const MAX_OBJECT_WINDOW: usize = 1024 * 1024;
+/// xref 表读取上限（约 20 万个对象）
+const MAX_XREF_SIZE: usize = 4 * 1024 * 1024;
+/// 最多跟随的增量更新层数
+const MAX_XREF_SECTIONS: usize = 32;
+/// 计划最多包含的对象数和页面树节点数（防止损坏文档中的循环引用）
+const MAX_PLAN_OBJECTS: usize = 4096;
+const MAX_TREE_NODES: usize = 10_000;
+
+/// 不跟随的键：指向父节点、其他页面或文档级结构，跟随后会把整个文档都纳入计划
+const SKIPPED_KEYS: &[&str] = &[
+    "Parent", "Kids", "P", "Pg", "Dest", "D", "A", "AA", "Next", "Prev", "First", "Last", "Popup", "IRT",
+    "Thumb", "Metadata", "PieceInfo", "B", "K",
+];
+
+/// 所请求页面需要的字节范围
+#[derive(Debug, Default, PartialEq)]
+pub struct FetchPlan {
+    /// 合并后的字节范围 [start, end)，按偏移排序
+    pub ranges: Vec<(u64, u64)>,
+    /// 纳入计划的对象数
+    pub objects: u32,
+}
+
+/// 为 `page_nums` 计算取数计划；`reader` 读取的数据会进入流式缓存，渲染时直接命中
+pub fn plan<R: Read + Seek>(reader: &mut R, file_size: u64, page_nums: &[u32]) -> Result<FetchPlan, String> {
+    let xref = read_xref(reader, file_size)?;
+    let root = xref.root.ok_or("Trailer has no /Root")?;
+    let catalog = read_object(reader, &xref, root)?;
+    let pages_root = reference(&catalog, "Pages").ok_or("Catalog has no /Pages")?;
+    let start = find_pages(reader, &xref, pages_root, page_nums)?;
+
+    // 从页面对象出发收集引用的对象（不向上回到页面树）
+    let mut seen: HashSet<u32> = start.iter().copied().collect();
+    let mut queue: Vec<u32> = start;
+    let mut objects = Vec::new();
+    while let Some(object) = queue.pop() {
+        if objects.len() >= MAX_PLAN_OBJECTS {
+            break;
+        }
+        let Ok(body) = read_object(reader, &xref, object) else { continue };
+        objects.push(object);
+        for child in references(&body) {
+            if seen.insert(child) {
+                queue.push(child);
+            }
+        }
+    }
+
+    let mut ranges: Vec<(u64, u64)> = objects
+        .iter()
+        .filter_map(|object| xref.object_range(*object, file_size))
+        .collect();
+    Ok(FetchPlan {
+        objects: objects.len() as u32,
+        ranges: merge_ranges(&mut ranges),
+    })
+}
+
+/// 读取线性化字典的长度（规范要求字典位于文件前 1024 字节内）
+const LINEARIZATION_WINDOW: usize = 1024;
+
+/// 文件开头的线性化字典
+#[derive(Debug, PartialEq)]
+pub struct Linearization {
+    /// 文档页数（/N）
+    pub pages: u32,
+    /// 首页段落中的页面（/P，从 1 开始）
+    pub first_page: u32,
+    /// 打开文档并渲染首页需要的字节范围 [start, end)：首页段落、提示表和文件末尾的主交叉引用表
+    pub ranges: Vec<(u64, u64)>,
+}
+
+/// 读取文件开头的线性化字典；文件没有线性化或之后经过增量更新（/L 与文件大小不符）时返回 None
+pub fn linearization<R: Read + Seek>(reader: &mut R, file_size: u64) -> Option<Linearization> {
+    let head = read_at(reader, 0, LINEARIZATION_WINDOW.min(file_size as usize)).ok()?;
+    let head = String::from_utf8_lossy(&head);
+    let start = head.find("obj")?;
+    let end = head[start..].find("endobj").map_or(head.len(), |i| start + i);
+    let dict = &head[start..end];
+    // /Linearized 的值是版本号（通常写作 1.0），只检查键是否存在
+    if !tokens(dict).contains(&Token::Name("Linearized")) || integer(dict, "L")? as u64 != file_size {
+        return None;
+    }
+
+    let first_page_end = (integer(dict, "E")? as u64).min(file_size);
+    let mut ranges = vec![(0, first_page_end)];
+    // 主提示流，以及可选的溢出提示流
+    for pair in integers(dict, "H")?.chunks_exact(2) {
+        let offset = pair[0].max(0) as u64;
+        ranges.push((offset, (offset + pair[1].max(0) as u64).min(file_size)));
+    }
+    // /T 指向主交叉引用表的第一个条目，往前留出 xref 关键字和小节头
+    if let Some(main_xref) = integer(dict, "T").map(|t| (t.max(0) as u64).saturating_sub(64)) {
+        if file_size - main_xref.min(file_size) <= MAX_XREF_SIZE as u64 {
+            ranges.push((main_xref, file_size));
+        }
+    }
+    Some(Linearization {
+        pages: integer(dict, "N")?.max(0) as u32,
+        first_page: integer(dict, "P").unwrap_or(0).max(0) as u32 + 1,
+        ranges: merge_ranges(&mut ranges),
+    })
+}
+
+/// 交叉引用表：对象号 -> 偏移
+struct Xref {
+    offsets: HashMap<u32, u64>,
+    /// 所有对象和 xref 表的起始偏移（升序），用于确定每个对象的结束位置
+    boundaries: Vec<u64>,
+    root: Option<u32>,
+}
+
+impl Xref {
+    /// 对象占用的字节范围：到下一个对象（或 xref 表）开始为止
+    fn object_range(&self, object: u32, file_size: u64) -> Option<(u64, u64)> {
+        let start = *self.offsets.get(&object)?;
+        let end = match self.boundaries.binary_search(&start) {
+            Ok(i) | Err(i) => self.boundaries[i..].iter().find(|&&b| b > start).copied(),
+        };
+        Some((start, end.unwrap_or(file_size).min(file_size)))
+    }
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> Result<Vec<u8>, String> {
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Seek failed: {}", e))?;
+    let mut data = Vec::with_capacity(len);
+    reader
+        .take(len as u64)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Read failed: {}", e))?;
+    Ok(data)
+}
+
+fn read_xref<R: Read + Seek>(reader: &mut R, file_size: u64) -> Result<Xref, String> {
+    let tail_start = file_size.saturating_sub(TAIL_SIZE);
+    let tail = read_at(reader, tail_start, (file_size - tail_start) as usize)?;
+    let tail = String::from_utf8_lossy(&tail);
+    let start = tail
+        .rfind("startxref")
+        .and_then(|i| tail[i + "startxref".len()..].split_whitespace().next()?.parse::<u64>().ok())
+        .ok_or("startxref not found")?;
+
+    let mut xref = Xref {
+        offsets: HashMap::new(),
+        boundaries: Vec::new(),
+        root: None,
+    };
+    let mut visited = HashSet::new();
+    let mut next = Some(start);
+    while let Some(offset) = next.take() {
+        if offset >= file_size || !visited.insert(offset) || visited.len() > MAX_XREF_SECTIONS {
+            break;
+        }
+        xref.boundaries.push(offset);
+        let section = read_xref_section(reader, offset, file_size)?;
+        let (entries, trailer) = parse_xref_section(&section)?;
+        // 新的增量更新先读到，优先于旧表中的同号对象
+        for (object, offset) in entries {
+            xref.offsets.entry(object).or_insert(offset);
+        }
+        if xref.root.is_none() {
+            xref.root = reference(trailer, "Root");
+        }
+        next = integer(trailer, "Prev").map(|prev| prev as u64);
+    }
+    xref.boundaries.extend(xref.offsets.values().copied());
+    xref.boundaries.sort_unstable();
+    xref.boundaries.dedup();
+    Ok(xref)
+}
+
+/// 读取一段 xref 表，直到 trailer 字典结束
+fn read_xref_section<R: Read + Seek>(reader: &mut R, offset: u64, file_size: u64) -> Result<String, String> {
+    let mut window = OBJECT_WINDOW;
+    loop {
+        let len = window.min((file_size - offset) as usize);
+        let text = String::from_utf8_lossy(&read_at(reader, offset, len)?).into_owned();
+        let complete = text.find("trailer").is_some_and(|i| text[i..].contains("startxref"));
+        if complete || len < window || window >= MAX_XREF_SIZE {
+            return Ok(text);
+        }
+        window *= 4;
+    }
+}
+
+/// xref 表中使用中的对象：(对象号, 偏移)
+type XrefEntries = Vec<(u32, u64)>;
+
+/// 解析一段 xref 表：使用中的对象和 trailer 字典文本
+fn parse_xref_section(text: &str) -> Result<(XrefEntries, &str), String> {
+    let body = text.trim_start();
+    let body = body
+        .strip_prefix("xref")
+        .ok_or("Cross-reference streams are not supported")?;
+    let trailer_at = body.find("trailer").ok_or("xref table has no trailer")?;
+    let mut tokens = body[..trailer_at].split_whitespace();
+    let mut entries = Vec::new();
+    while let (Some(first), Some(count)) = (tokens.next(), tokens.next()) {
+        let (Ok(first), Ok(count)) = (first.parse::<u32>(), count.parse::<u32>()) else {
+            return Err("Malformed xref subsection".to_string());
+        };
+        for i in 0..count {
+            let (Some(offset), Some(_generation), Some(kind)) = (tokens.next(), tokens.next(), tokens.next()) else {
+                return Err("Truncated xref table".to_string());
+            };
+            if kind == "n" {
+                if let Ok(offset) = offset.parse::<u64>() {
+                    entries.push((first + i, offset));
+                }
+            }
+        }
+    }
+    Ok((entries, &body[trailer_at..]))
+}
+
+/// 读取对象的字典部分（到 stream 或 endobj 为止）
+fn read_object<R: Read + Seek>(reader: &mut R, xref: &Xref, object: u32) -> Result<String, String> {
+    let offset = *xref
+        .offsets
+        .get(&object)
+        .ok_or_else(|| format!("Object {} is not in the xref table", object))?;
+    let mut window = OBJECT_WINDOW;
+    loop {
+        let data = read_at(reader, offset, window)?;
+        let text = String::from_utf8_lossy(&data);
+        let end = [text.find("endobj"), stream_keyword(&text)].into_iter().flatten().min();
+        match end {
+            Some(end) => return Ok(text[..end].to_string()),
+            None if data.len() < window || window >= MAX_OBJECT_WINDOW => return Ok(text.into_owned()),
+            None => window *= 4,
+        }
+    }
+}
+
+/// 数据流开始的 stream 关键字位置（跳过 endstream）
+fn stream_keyword(text: &str) -> Option<usize> {
+    text.match_indices("stream")
+        .map(|(i, _)| i)
+        .find(|&i| !text[..i].ends_with("end"))
+}
+
+/// 按页面树顺序找出 `page_nums` 对应的页面对象（页码从 1 开始）
+///
+/// 结果还包括途经的页面树节点直接引用的对象（可继承的 /Resources 等）。
+fn find_pages<R: Read + Seek>(reader: &mut R, xref: &Xref, root: u32, page_nums: &[u32]) -> Result<Vec<u32>, String> {
+    let wanted: HashSet<u32> = page_nums.iter().copied().collect();
+    let last = page_nums.iter().copied().max().unwrap_or(0);
+    let mut found = Vec::new();
+    let mut index = 0u32;
+    let mut stack = vec![root];
+    let mut visited = HashSet::new();
+    while let Some(node) = stack.pop() {
+        if index >= last || !visited.insert(node) || visited.len() > MAX_TREE_NODES {
+            break;
+        }
+        let dict = read_object(reader, xref, node)?;
+        match array_references(&dict, "Kids") {
+            Some(kids) => {
+                // 整棵子树都不包含请求的页面时按 /Count 跳过，不读取其中的节点
+                let count = integer(&dict, "Count").unwrap_or(-1);
+                if count >= 0 && !(index + 1..=index + count as u32).any(|p| wanted.contains(&p)) {
+                    index += count as u32;
+                    continue;
+                }
+                found.extend(references(&dict));
+                stack.extend(kids.into_iter().rev());
+            }
+            None => {
+                index += 1;
+                if wanted.contains(&index) {
+                    found.push(node);
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// 词法单元：名字（不含 /）、数字或关键字
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Name(&'a str),
+    Word(&'a str),
+    /// 数组或字典的开始，分隔前后两个值
+    Open,
+}
+
+/// 把对象文本切分为词法单元，跳过字符串、十六进制字符串和注释
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    let bytes = text.as_bytes();
+    let is_delimiter = |b: u8| b.is_ascii_whitespace() || b"()<>[]{}/%".contains(&b);
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => {
+                let mut depth = 0;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' => i += 1,
+                        b'(' => depth += 1,
+                        b')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'<' if bytes.get(i + 1) == Some(&b'<') => {
+                tokens.push(Token::Open);
+                i += 2;
+            }
+            b'<' => {
+                while i < bytes.len() && bytes[i] != b'>' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'[' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            b'%' => {
+                while i < bytes.len() && bytes[i] != b'\n' && bytes[i] != b'\r' {
+                    i += 1;
+                }
+            }
+            b'/' => {
+                let start = i + 1;
+                i = start;
+                while i < bytes.len() && !is_delimiter(bytes[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Name(&text[start..i]));
+            }
+            b if is_delimiter(b) => i += 1,
+            _ => {
+                let start = i;
+                while i < bytes.len() && !is_delimiter(bytes[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Word(&text[start..i]));
+            }
+        }
+    }
+    tokens
+}
+
+/// 对象中的间接引用（`N G R`），跳过 SKIPPED_KEYS 下的引用
+///
+/// 引用所属的键取它之前最近的名字：字典值和数组元素都归到外层的键名下。
+fn references(text: &str) -> Vec<u32> {
+    let tokens = tokens(text);
+    let mut key = "";
+    let mut result = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Name(name) => key = name,
+            Token::Word("R") if i >= 2 => {
+                if let (Token::Word(object), Token::Word(generation)) = (&tokens[i - 2], &tokens[i - 1]) {
+                    if generation.parse::<u32>().is_ok() && !SKIPPED_KEYS.contains(&key) {
+                        if let Ok(object) = object.parse::<u32>() {
+                            result.push(object);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// 字典中 `/key N G R` 引用的对象号
+fn reference(text: &str, key: &str) -> Option<u32> {
+    let tokens = tokens(text);
+    let at = tokens.iter().position(|t| *t == Token::Name(key))?;
+    match tokens.get(at + 1..at + 4)? {
+        [Token::Word(object), Token::Word(_), Token::Word("R")] => object.parse().ok(),
+        _ => None,
+    }
+}
+
+/// 字典中 `/key [N G R ...]` 数组的所有引用
+fn array_references(text: &str, key: &str) -> Option<Vec<u32>> {
+    let tokens = tokens(text);
+    let at = tokens.iter().position(|t| *t == Token::Name(key))?;
+    if tokens.get(at + 1) != Some(&Token::Open) {
+        return None;
+    }
+    let mut refs = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    for token in &tokens[at + 2..] {
+        match token {
+            Token::Word("R") if words.len() >= 2 => {
+                if let Ok(object) = words[words.len() - 2].parse() {
+                    refs.push(object);
+                }
+                words.clear();
+            }
+            Token::Word(word) => words.push(*word),
+            _ => break,
+        }
+    }
+    Some(refs)
+}
+
+/// 字典中 `/key N` 的整数值
+fn integer(text: &str, key: &str) -> Option<i64> {
+    let tokens = tokens(text);
+    let at = tokens.iter().position(|t| *t == Token::Name(key))?;
+    match tokens.get(at + 1)? {
+        Token::Word(value) => value.parse().ok(),
+        _ => None,
+    }
+}
+
+/// 字典中 `/key [N N ...]` 数组的整数
+fn integers(text: &str, key: &str) -> Option<Vec<i64>> {
+    let tokens = tokens(text);
+    let at = tokens.iter().position(|t| *t == Token::Name(key))?;
+    if tokens.get(at + 1) != Some(&Token::Open) {
+        return None;
+    }
+    let values = tokens[at + 2..]
+        .iter()
+        .map_while(|token| match token {
+            Token::Word(word) => word.parse().ok(),
+            _ => None,
+        })
+        .collect();
+    Some(values)
+}
+
+/// 排序并合并重叠或相邻的范围
+fn merge_ranges(ranges: &mut [(u64, u64)]) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for &(start, end) in ranges.iter() {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// 按顺序拼接对象并生成 xref 表
+    fn build_pdf(objects: &[&str]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, body).as_bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in &offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+        );
+        pdf
+    }
+
+    #[test]
+    fn test_plan_follows_page_resources() {
+        let pdf = build_pdf(&[
+            "<< /Type /Catalog /Pages 2 0 R >>",
+            "<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>",
+            "<< /Type /Page /Parent 2 0 R /Contents 5 0 R /Resources << /Font << /F1 7 0 R >> >> >>",
+            "<< /Type /Page /Parent 2 0 R /Contents 6 0 R /Annots [8 0 R] >>",
+            "<< /Length 10 >>\nstream\n0123456789\nendstream",
+            "<< /Length 3 >>\nstream\nabc\nendstream",
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Note (a 9 0 R) >>",
+            "<< /Type /Annot /P 3 0 R /Dest [3 0 R /Fit] /AP << /N 9 0 R >> >>",
+            "<< /Length 0 >>\nstream\n\nendstream",
+        ]);
+        let size = pdf.len() as u64;
+        let xref = read_xref(&mut Cursor::new(&pdf), size).unwrap();
+        let range = |object| xref.object_range(object, size).unwrap();
+
+        let first = plan(&mut Cursor::new(&pdf), size, &[1]).unwrap();
+        assert_eq!(first.objects, 3);
+        // 字符串中的 "9 0 R" 不是引用
+        assert_eq!(first.ranges, vec![range(3), range(5), range(7)]);
+
+        // 注释通过 /P 和 /Dest 指回第 1 页，这两个键不跟随
+        let second = plan(&mut Cursor::new(&pdf), size, &[2]).unwrap();
+        assert_eq!(second.objects, 4);
+        assert_eq!(second.ranges, vec![range(4), range(6), (range(8).0, range(9).1)]);
+    }
+
+    #[test]
+    fn test_linearization() {
+        let mut pdf = b"%PDF-1.5\n%\xe2\xe3\n1 0 obj\n<< /Linearized 1.0 /L 5000 /H [ 700 120 ] /O 3 /E 1200 /N 12 /T 4800 >>\nendobj\n"
+            .to_vec();
+        pdf.resize(5000, b' ');
+        let linearization = linearization(&mut Cursor::new(&pdf), 5000).unwrap();
+        assert_eq!(linearization.pages, 12);
+        assert_eq!(linearization.first_page, 1);
+        // 提示表落在首页段落内，与之合并
+        assert_eq!(linearization.ranges, vec![(0, 1200), (4736, 5000)]);
+
+        // 增量更新后 /L 与文件大小不符，线性化信息不再可靠
+        pdf.extend_from_slice(b"\n");
+        assert!(super::linearization(&mut Cursor::new(&pdf), 5001).is_none());
+        let plain = build_pdf(&["<< /Type /Catalog /Pages 2 0 R >>"]);
+        assert!(super::linearization(&mut Cursor::new(&plain), plain.len() as u64).is_none());
+    }
+
+    #[test]
+    fn test_xref_stream_not_supported() {
+        let text = "12 0 obj\n<< /Type /XRef /W [1 2 1] >>\nstream\n";
+        assert!(parse_xref_section(text).is_err());
+        assert_eq!(merge_ranges(&mut [(10, 20), (0, 5), (5, 8), (15, 30)]), vec![(0, 8), (10, 30)]);
+    }
+}
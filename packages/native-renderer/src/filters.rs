@@ -0,0 +1,392 @@
+//! 位图预处理滤镜
+//!
+//! 在编码之前对渲染结果做灰度化、二值化等处理。
+//! 所有函数都接受 4 通道像素数据（RGBA 或 BGRA），通道顺序由调用方指定。
+
+use crate::renderer::PixelFormat;
+
+/// 二值化方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BilevelMode {
+    /// 全局固定阈值（灰度低于阈值为黑）
+    Threshold(u8),
+    /// Sauvola 自适应阈值，适合光照不均的扫描件
+    Sauvola {
+        /// 局部窗口边长（像素）
+        window: u32,
+        /// 灵敏度系数，通常 0.2-0.5
+        k: f32,
+    },
+}
+
+impl BilevelMode {
+    /// 从选项构建二值化方式，未知的模式名返回 None
+    pub fn from_options(mode: &str, threshold: u8, window: u32, k: f32) -> Option<Self> {
+        match mode.to_lowercase().as_str() {
+            "threshold" => Some(BilevelMode::Threshold(threshold)),
+            "sauvola" | "adaptive" => Some(BilevelMode::Sauvola {
+                window: window.max(3),
+                k,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// 降噪方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DenoiseMode {
+    /// 中值滤波：去除椒盐噪声和 JPEG 块状伪影
+    Median { radius: u32 },
+    /// 双边滤波：平滑噪声的同时保留文字边缘
+    Bilateral { radius: u32 },
+}
+
+impl DenoiseMode {
+    /// 从选项构建降噪方式，未知的模式名返回 None
+    pub fn from_options(mode: &str, radius: u32) -> Option<Self> {
+        let radius = radius.clamp(1, 3);
+        match mode.to_lowercase().as_str() {
+            "median" => Some(DenoiseMode::Median { radius }),
+            "bilateral" => Some(DenoiseMode::Bilateral { radius }),
+            _ => None,
+        }
+    }
+}
+
+/// 对 4 通道像素做降噪（alpha 通道保持不变）
+pub fn denoise(data: &[u8], width: u32, height: u32, mode: DenoiseMode) -> Vec<u8> {
+    match mode {
+        DenoiseMode::Median { radius } => median(data, width as usize, height as usize, radius as usize),
+        DenoiseMode::Bilateral { radius } => bilateral(data, width as usize, height as usize, radius as usize),
+    }
+}
+
+fn median(data: &[u8], w: usize, h: usize, r: usize) -> Vec<u8> {
+    let mut out = data.to_vec();
+    let mut window = Vec::with_capacity((2 * r + 1) * (2 * r + 1));
+    for y in 0..h {
+        for x in 0..w {
+            for c in 0..3 {
+                window.clear();
+                for ny in y.saturating_sub(r)..(y + r + 1).min(h) {
+                    for nx in x.saturating_sub(r)..(x + r + 1).min(w) {
+                        window.push(data[(ny * w + nx) * 4 + c]);
+                    }
+                }
+                let mid = window.len() / 2;
+                window.select_nth_unstable(mid);
+                out[(y * w + x) * 4 + c] = window[mid];
+            }
+        }
+    }
+    out
+}
+
+fn bilateral(data: &[u8], w: usize, h: usize, r: usize) -> Vec<u8> {
+    // 空间权重 sigma = radius，值域权重 sigma = 30（8 位灰度）
+    let sigma_s = r.max(1) as f32;
+    let sigma_r = 30.0f32;
+    let range_lut: Vec<f32> = (0..256)
+        .map(|d| (-(d as f32 * d as f32) / (2.0 * sigma_r * sigma_r)).exp())
+        .collect();
+
+    let mut out = data.to_vec();
+    for y in 0..h {
+        for x in 0..w {
+            let center = &data[(y * w + x) * 4..(y * w + x) * 4 + 3];
+            let mut acc = [0f32; 3];
+            let mut weight_sum = 0f32;
+            for ny in y.saturating_sub(r)..(y + r + 1).min(h) {
+                for nx in x.saturating_sub(r)..(x + r + 1).min(w) {
+                    let px = &data[(ny * w + nx) * 4..(ny * w + nx) * 4 + 3];
+                    let dx = nx as f32 - x as f32;
+                    let dy = ny as f32 - y as f32;
+                    let spatial = (-(dx * dx + dy * dy) / (2.0 * sigma_s * sigma_s)).exp();
+                    let diff = px
+                        .iter()
+                        .zip(center)
+                        .map(|(&a, &b)| a.abs_diff(b) as usize)
+                        .max()
+                        .unwrap_or(0);
+                    let weight = spatial * range_lut[diff];
+                    for (a, &v) in acc.iter_mut().zip(px) {
+                        *a += v as f32 * weight;
+                    }
+                    weight_sum += weight;
+                }
+            }
+            for (c, a) in acc.iter().enumerate() {
+                out[(y * w + x) * 4 + c] = (a / weight_sum).round() as u8;
+            }
+        }
+    }
+    out
+}
+
+/// 将 4 通道像素转换为灰度（透明部分与白色背景混合）
+pub fn to_grayscale(data: &[u8], pixel_format: PixelFormat) -> Vec<u8> {
+    let (r_idx, b_idx) = match pixel_format {
+        PixelFormat::Rgba => (0, 2),
+        PixelFormat::Bgra => (2, 0),
+    };
+
+    data.chunks_exact(4)
+        .map(|px| {
+            let luma = 0.299 * px[r_idx] as f32 + 0.587 * px[1] as f32 + 0.114 * px[b_idx] as f32;
+            let a = px[3] as f32 / 255.0;
+            (luma * a + 255.0 * (1.0 - a)).round() as u8
+        })
+        .collect()
+}
+
+/// 对灰度图做二值化，返回每像素一个字节（1 为黑，0 为白）
+pub fn binarize(gray: &[u8], width: u32, height: u32, mode: BilevelMode) -> Vec<u8> {
+    match mode {
+        BilevelMode::Threshold(t) => gray.iter().map(|&v| (v < t) as u8).collect(),
+        BilevelMode::Sauvola { window, k } => sauvola(gray, width, height, window, k),
+    }
+}
+
+/// Sauvola 自适应二值化
+///
+/// 阈值 T = m * (1 + k * (s / R - 1))，m/s 为局部窗口的均值和标准差，R = 128。
+/// 使用积分图计算局部统计量，复杂度与窗口大小无关。
+fn sauvola(gray: &[u8], width: u32, height: u32, window: u32, k: f32) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let (sum, sq_sum) = integral_images(gray, w, h);
+    let half = (window / 2) as usize;
+
+    let mut out = vec![0u8; w * h];
+    for y in 0..h {
+        let y0 = y.saturating_sub(half);
+        let y1 = (y + half + 1).min(h);
+        for x in 0..w {
+            let x0 = x.saturating_sub(half);
+            let x1 = (x + half + 1).min(w);
+            let n = ((x1 - x0) * (y1 - y0)) as f64;
+
+            let s = rect_sum(&sum, w, x0, y0, x1, y1);
+            let sq = rect_sum(&sq_sum, w, x0, y0, x1, y1);
+            let mean = s / n;
+            let std = (sq / n - mean * mean).max(0.0).sqrt();
+            let threshold = mean * (1.0 + k as f64 * (std / 128.0 - 1.0));
+
+            out[y * w + x] = ((gray[y * w + x] as f64) < threshold) as u8;
+        }
+    }
+    out
+}
+
+/// 计算灰度图的积分图和平方积分图（尺寸为 (w+1) * (h+1)）
+pub(crate) fn integral_images(gray: &[u8], w: usize, h: usize) -> (Vec<f64>, Vec<f64>) {
+    let stride = w + 1;
+    let mut sum = vec![0f64; stride * (h + 1)];
+    let mut sq_sum = vec![0f64; stride * (h + 1)];
+    for y in 0..h {
+        let mut row_sum = 0f64;
+        let mut row_sq = 0f64;
+        for x in 0..w {
+            let v = gray[y * w + x] as f64;
+            row_sum += v;
+            row_sq += v * v;
+            sum[(y + 1) * stride + x + 1] = sum[y * stride + x + 1] + row_sum;
+            sq_sum[(y + 1) * stride + x + 1] = sq_sum[y * stride + x + 1] + row_sq;
+        }
+    }
+    (sum, sq_sum)
+}
+
+/// 从积分图中取矩形 [x0, x1) × [y0, y1) 的和
+pub(crate) fn rect_sum(integral: &[f64], w: usize, x0: usize, y0: usize, x1: usize, y1: usize) -> f64 {
+    let stride = w + 1;
+    integral[y1 * stride + x1] - integral[y0 * stride + x1] - integral[y1 * stride + x0]
+        + integral[y0 * stride + x0]
+}
+
+/// 背景归一化（光照校正）
+///
+/// 估计页面的背景亮度分布（按网格取局部最亮值，平滑后双线性插值），
+/// 再将每个像素按背景亮度拉伸到白色。用于消除扫描件的阴影和光照不均，
+/// 在编码或二值化之前使用可以明显提升低分辨率下的可读性和 OCR 准确率。
+///
+/// `window` 为背景估计的尺度（像素），应明显大于文字笔画宽度。
+pub fn normalize_background(data: &mut [u8], width: u32, height: u32, pixel_format: PixelFormat, window: u32) {
+    let w = width as usize;
+    let h = height as usize;
+    if w == 0 || h == 0 {
+        return;
+    }
+
+    let gray = to_grayscale(data, pixel_format);
+    let tile = (window as usize / 4).max(8);
+    let gw = w.div_ceil(tile);
+    let gh = h.div_ceil(tile);
+
+    // 1. 每个网格取最亮值作为背景的初始估计
+    let mut grid = vec![0u8; gw * gh];
+    for y in 0..h {
+        for x in 0..w {
+            let cell = &mut grid[(y / tile) * gw + x / tile];
+            *cell = (*cell).max(gray[y * w + x]);
+        }
+    }
+
+    // 2. 被大块深色内容覆盖的网格（明显暗于邻居）用邻居最亮值填充，再 3x3 均值平滑
+    let filled = grid_filter(&grid, gw, gh, |vals| {
+        let center = vals[vals.len() / 2] as f32;
+        let brightest = vals.iter().copied().max().unwrap_or(0) as f32;
+        if center < brightest * 0.75 {
+            brightest
+        } else {
+            center
+        }
+    });
+    let smoothed = grid_filter(&filled, gw, gh, |vals| {
+        vals.iter().map(|&v| v as f32).sum::<f32>() / vals.len() as f32
+    });
+
+    // 3. 双线性插值得到每个像素的背景亮度，并按比例拉伸
+    for y in 0..h {
+        let gy = ((y as f32 + 0.5) / tile as f32 - 0.5).clamp(0.0, (gh - 1) as f32);
+        let y0 = gy.floor() as usize;
+        let y1 = (y0 + 1).min(gh - 1);
+        let fy = gy - y0 as f32;
+        for x in 0..w {
+            let gx = ((x as f32 + 0.5) / tile as f32 - 0.5).clamp(0.0, (gw - 1) as f32);
+            let x0 = gx.floor() as usize;
+            let x1 = (x0 + 1).min(gw - 1);
+            let fx = gx - x0 as f32;
+
+            let top = smoothed[y0 * gw + x0] as f32 * (1.0 - fx) + smoothed[y0 * gw + x1] as f32 * fx;
+            let bottom = smoothed[y1 * gw + x0] as f32 * (1.0 - fx) + smoothed[y1 * gw + x1] as f32 * fx;
+            // 背景过暗时不做拉伸，避免放大噪声
+            let bg = (top * (1.0 - fy) + bottom * fy).max(32.0);
+            let gain = 255.0 / bg;
+
+            let px = &mut data[(y * w + x) * 4..(y * w + x) * 4 + 3];
+            for c in px.iter_mut() {
+                *c = (*c as f32 * gain).min(255.0) as u8;
+            }
+        }
+    }
+}
+
+/// 对网格做 3x3 邻域滤波（边界处复制边缘值，保证中心元素位于 `vals` 正中）
+fn grid_filter(grid: &[u8], gw: usize, gh: usize, f: impl Fn(&[u8]) -> f32) -> Vec<u8> {
+    let mut out = vec![0u8; grid.len()];
+    let mut vals = Vec::with_capacity(9);
+    for y in 0..gh {
+        for x in 0..gw {
+            vals.clear();
+            for dy in [-1isize, 0, 1] {
+                let ny = (y as isize + dy).clamp(0, gh as isize - 1) as usize;
+                for dx in [-1isize, 0, 1] {
+                    let nx = (x as isize + dx).clamp(0, gw as isize - 1) as usize;
+                    vals.push(grid[ny * gw + nx]);
+                }
+            }
+            out[y * gw + x] = f(&vals).round() as u8;
+        }
+    }
+    out
+}
+
+/// 将二值图展开为 4 通道像素（黑/白，不透明）
+pub fn bilevel_to_pixels(bits: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bits.len() * 4);
+    for &b in bits {
+        let v = if b != 0 { 0 } else { 255 };
+        out.extend_from_slice(&[v, v, v, 255]);
+    }
+    out
+}
+
+/// 将灰度图展开为 4 通道像素（R=G=B，不透明）
+pub fn gray_to_pixels(gray: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(gray.len() * 4);
+    for &v in gray {
+        out.extend_from_slice(&[v, v, v, 255]);
+    }
+    out
+}
+
+/// 位图是否近似灰度：通道差超过 `tolerance` 的彩色像素不超过千分之一
+///
+/// 容忍少量彩色像素（文字抗锯齿的色边、页眉的小徽标），它们转灰后几乎看不出区别。
+pub fn is_near_grayscale(data: &[u8], tolerance: u8) -> bool {
+    let max_colored = data.len() / 4 / 1000;
+    data.chunks_exact(4)
+        .filter(|px| px[0].max(px[1]).max(px[2]) - px[0].min(px[1]).min(px[2]) > tolerance)
+        .take(max_colored + 1)
+        .count()
+        <= max_colored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_near_grayscale() {
+        let mut data = gray_to_pixels(&vec![128u8; 2000]);
+        assert!(is_near_grayscale(&data, 16));
+        // 2000 像素最多容忍 2 个彩色像素
+        data[..8].copy_from_slice(&[255, 0, 0, 255, 0, 0, 255, 255]);
+        assert!(is_near_grayscale(&data, 16));
+        data[8..12].copy_from_slice(&[0, 255, 0, 255]);
+        assert!(!is_near_grayscale(&data, 16));
+    }
+
+    #[test]
+    fn test_threshold_binarize() {
+        let gray = [0, 127, 128, 255];
+        assert_eq!(binarize(&gray, 4, 1, BilevelMode::Threshold(128)), vec![1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_normalize_background_flattens_gradient() {
+        // 背景从左到右由 100 渐变到 250
+        let (w, h) = (64u32, 8u32);
+        let mut data = Vec::new();
+        for _ in 0..h {
+            for x in 0..w {
+                let v = (100 + x * 150 / (w - 1)) as u8;
+                data.extend_from_slice(&[v, v, v, 255]);
+            }
+        }
+        normalize_background(&mut data, w, h, PixelFormat::Rgba, 32);
+        let left = data[0];
+        let right = data[((w - 1) * 4) as usize];
+        assert!(left >= 200, "left = {}", left);
+        assert!(right >= 240, "right = {}", right);
+    }
+
+    #[test]
+    fn test_median_removes_isolated_speck() {
+        let mut data = vec![255u8; 3 * 3 * 4];
+        data[4 * 4] = 0;
+        data[4 * 4 + 1] = 0;
+        data[4 * 4 + 2] = 0;
+        let out = denoise(&data, 3, 3, DenoiseMode::Median { radius: 1 });
+        assert_eq!(&out[16..20], &[255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_sauvola_handles_uneven_background() {
+        // 左半边背景较暗（120），右半边较亮（220），两边各有一个更暗的“笔画”
+        let w = 20;
+        let mut gray = vec![0u8; w];
+        for (x, v) in gray.iter_mut().enumerate() {
+            *v = if x < 10 { 120 } else { 220 };
+        }
+        gray[5] = 40;
+        gray[15] = 120;
+        let bits = binarize(&gray, w as u32, 1, BilevelMode::Sauvola { window: 7, k: 0.2 });
+        assert_eq!(bits[5], 1);
+        assert_eq!(bits[15], 1);
+        assert_eq!(bits[1], 0);
+        assert_eq!(bits[18], 0);
+    }
+}
@@ -0,0 +1,346 @@
+//! 最近渲染记录（崩溃诊断）
+//!
+//! 用一个固定大小的环形缓冲区记录最近的渲染尝试：文档指纹、页码、尺寸和已到达的阶段。
+//! 进程崩溃后，最后一条停留在中间阶段的记录通常就是触发崩溃的文档和页面。
+//! 设置了转储路径时，Rust panic 会把当前快照写入文件。
+//! 每次尝试同时记下各阶段的开始时刻，供结果中的 spans（spanTimings 选项）使用。
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, Once, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// 环形缓冲区容量
+const CAPACITY: usize = 32;
+
+/// 计算文档指纹时从头部和尾部各取的字节数
+const FINGERPRINT_SAMPLE: usize = 64 * 1024;
+
+static RECORDS: Mutex<VecDeque<Attempt>> = Mutex::new(VecDeque::new());
+static NEXT_SEQ: AtomicU32 = AtomicU32::new(1);
+static DUMP_PATH: Mutex<Option<String>> = Mutex::new(None);
+static PANIC_HOOK: Once = Once::new();
+/// 单调时钟与墙上时钟的对应点，进程内第一次换算时确定
+static CLOCK_ANCHOR: OnceLock<(Instant, f64)> = OnceLock::new();
+
+/// 把单调时钟时刻换算为 Unix 毫秒（含小数部分）
+///
+/// 以进程内固定的对应点换算，同一进程内的时间戳不会因为系统时间调整而回退，
+/// 又能与调用方的 `Date.now()` / `performance.timeOrigin + performance.now()` 对齐。
+pub fn epoch_millis(instant: Instant) -> f64 {
+    let (anchor, anchor_millis) = *CLOCK_ANCHOR.get_or_init(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+        (Instant::now(), now)
+    });
+    match instant.checked_duration_since(anchor) {
+        Some(after) => anchor_millis + after.as_secs_f64() * 1000.0,
+        None => anchor_millis - anchor.duration_since(instant).as_secs_f64() * 1000.0,
+    }
+}
+
+/// 渲染阶段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    /// 加载页面
+    Loading,
+    /// PDFium 光栅化
+    Rendering,
+    /// 滤镜处理和编码
+    Encoding,
+    /// 成功完成
+    Done,
+    /// 失败（已正常返回错误）
+    Failed,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Loading => "loading",
+            Stage::Rendering => "rendering",
+            Stage::Encoding => "encoding",
+            Stage::Done => "done",
+            Stage::Failed => "failed",
+        }
+    }
+}
+
+/// 一次渲染尝试的快照
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub seq: u32,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: i64,
+    /// 文档指纹
+    pub source: String,
+    pub page_num: u32,
+    pub width: u32,
+    pub height: u32,
+    pub stage: Stage,
+    pub error: Option<String>,
+    /// 耗时（毫秒，完成时更新）
+    pub elapsed: u32,
+}
+
+/// 正在进行的渲染尝试，用于逐步更新缓冲区中的记录
+pub struct Recorder {
+    seq: u32,
+    start: Instant,
+    rendering_at: Cell<Option<Instant>>,
+    encoding_at: Cell<Option<Instant>>,
+}
+
+/// 一次尝试各阶段的开始时刻；没有到达的阶段为 None
+#[derive(Debug, Clone, Copy)]
+pub struct StageTimes {
+    pub start: Instant,
+    pub rendering: Option<Instant>,
+    pub encoding: Option<Instant>,
+    pub end: Instant,
+}
+
+/// 登记一次新的渲染尝试
+pub fn begin(source: &str, page_num: u32) -> Recorder {
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+
+    if let Ok(mut records) = RECORDS.lock() {
+        if records.len() >= CAPACITY {
+            records.pop_front();
+        }
+        records.push_back(Attempt {
+            seq,
+            started_at,
+            source: source.to_string(),
+            page_num,
+            width: 0,
+            height: 0,
+            stage: Stage::Loading,
+            error: None,
+            elapsed: 0,
+        });
+    }
+
+    Recorder {
+        seq,
+        start: Instant::now(),
+        rendering_at: Cell::new(None),
+        encoding_at: Cell::new(None),
+    }
+}
+
+impl Recorder {
+    fn update(&self, f: impl FnOnce(&mut Attempt)) {
+        if let Ok(mut records) = RECORDS.lock() {
+            if let Some(attempt) = records.iter_mut().rev().find(|a| a.seq == self.seq) {
+                f(attempt);
+                attempt.elapsed = self.start.elapsed().as_millis() as u32;
+            }
+        }
+    }
+
+    /// 进入新阶段
+    pub fn stage(&self, stage: Stage) {
+        self.mark(stage);
+        self.update(|a| a.stage = stage);
+    }
+
+    /// 记录渲染尺寸并进入光栅化阶段
+    pub fn rendering(&self, width: u32, height: u32) {
+        self.mark(Stage::Rendering);
+        self.update(|a| {
+            a.width = width;
+            a.height = height;
+            a.stage = Stage::Rendering;
+        });
+    }
+
+    fn mark(&self, stage: Stage) {
+        let slot = match stage {
+            Stage::Rendering => &self.rendering_at,
+            Stage::Encoding => &self.encoding_at,
+            _ => return,
+        };
+        if slot.get().is_none() {
+            slot.set(Some(Instant::now()));
+        }
+    }
+
+    /// 结束记录，返回各阶段的开始时刻
+    pub fn finish(self, error: Option<String>) -> StageTimes {
+        self.update(|a| {
+            a.stage = if error.is_some() { Stage::Failed } else { Stage::Done };
+            a.error = error;
+        });
+        StageTimes {
+            start: self.start,
+            rendering: self.rendering_at.get(),
+            encoding: self.encoding_at.get(),
+            end: Instant::now(),
+        }
+    }
+}
+
+/// 获取当前缓冲区快照（按时间从旧到新）
+pub fn snapshot() -> Vec<Attempt> {
+    RECORDS
+        .lock()
+        .map(|records| records.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 内存中文档的指纹：长度 + 首尾采样的 FNV-1a 哈希
+pub fn fingerprint_bytes(data: &[u8]) -> String {
+    let head = &data[..data.len().min(FINGERPRINT_SAMPLE)];
+    let tail = &data[data.len().saturating_sub(FINGERPRINT_SAMPLE)..];
+    format!("buffer:{}:{:016x}", data.len(), fnv1a(&[head, tail]))
+}
+
+/// 文件的指纹：路径、大小和首尾采样的哈希（读取失败时只保留路径）
+pub fn fingerprint_file(path: &str) -> String {
+    let sample = || -> std::io::Result<(u64, u64)> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let mut head = vec![0u8; (len as usize).min(FINGERPRINT_SAMPLE)];
+        file.read_exact(&mut head)?;
+        let tail_len = (len as usize).min(FINGERPRINT_SAMPLE);
+        let mut tail = vec![0u8; tail_len];
+        file.seek(SeekFrom::Start(len - tail_len as u64))?;
+        file.read_exact(&mut tail)?;
+        Ok((len, fnv1a(&[&head, &tail])))
+    };
+    match sample() {
+        Ok((len, hash)) => format!("file:{}:{}:{:016x}", path, len, hash),
+        Err(_) => format!("file:{}", path),
+    }
+}
+
+/// FNV-1a 64 位哈希（各部分依次输入）
+pub fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for &b in *part {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// 把快照序列化为 JSON
+pub fn to_json(attempts: &[Attempt]) -> String {
+    let items: Vec<String> = attempts
+        .iter()
+        .map(|a| {
+            format!(
+                r#"{{"seq":{},"startedAt":{},"source":{},"pageNum":{},"width":{},"height":{},"stage":"{}","error":{},"elapsed":{}}}"#,
+                a.seq,
+                a.started_at,
+                json_string(&a.source),
+                a.page_num,
+                a.width,
+                a.height,
+                a.stage.as_str(),
+                a.error.as_deref().map(json_string).unwrap_or_else(|| "null".to_string()),
+                a.elapsed,
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 把当前快照写入文件
+pub fn dump_to_file(path: &str) -> std::io::Result<()> {
+    std::fs::write(path, to_json(&snapshot()))
+}
+
+/// 设置（或清除）panic 时的转储路径
+pub fn set_dump_path(path: Option<String>) {
+    if let Ok(mut dump_path) = DUMP_PATH.lock() {
+        *dump_path = path;
+    }
+
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // panic 可能发生在持有锁期间，这里只尝试获取，避免死锁
+            if let Ok(Some(path)) = DUMP_PATH.try_lock().map(|p| p.clone()) {
+                if let Ok(records) = RECORDS.try_lock() {
+                    let attempts: Vec<Attempt> = records.iter().cloned().collect();
+                    let _ = std::fs::write(&path, to_json(&attempts));
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_tracks_stages() {
+        let recorder = begin("test:stages", 3);
+        recorder.rendering(100, 200);
+        let seq = recorder.seq;
+        let attempt = snapshot().into_iter().find(|a| a.seq == seq).unwrap();
+        assert_eq!(attempt.stage, Stage::Rendering);
+        assert_eq!((attempt.width, attempt.height), (100, 200));
+
+        let times = recorder.finish(Some("boom".to_string()));
+        let attempt = snapshot().into_iter().find(|a| a.seq == seq).unwrap();
+        assert_eq!(attempt.stage, Stage::Failed);
+        assert_eq!(attempt.error.as_deref(), Some("boom"));
+        // 没有进入编码阶段
+        assert!(times.rendering.is_some_and(|t| t >= times.start && t <= times.end));
+        assert!(times.encoding.is_none());
+    }
+
+    #[test]
+    fn test_epoch_millis_is_monotonic() {
+        let earlier = Instant::now();
+        let later = earlier + std::time::Duration::from_millis(5);
+        assert!((epoch_millis(later) - epoch_millis(earlier) - 5.0).abs() < 1e-6);
+        let wall = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as f64;
+        assert!((epoch_millis(Instant::now()) - wall).abs() < 1000.0);
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_content() {
+        assert_eq!(fingerprint_bytes(b"abc"), fingerprint_bytes(b"abc"));
+        assert_ne!(fingerprint_bytes(b"abc"), fingerprint_bytes(b"abd"));
+    }
+
+    #[test]
+    fn test_json_escaping() {
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+}
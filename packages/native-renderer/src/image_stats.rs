@@ -0,0 +1,111 @@
+//! 渲染结果的亮度统计
+//!
+//! 下游服务据此自动标记过暗、对比度过低的扫描件，送回重新扫描或增强处理。
+//! 统计基于输出位图的亮度（透明部分与白色背景混合，与灰度输出一致）。
+
+use crate::filters;
+use crate::renderer::PixelFormat;
+
+/// 亮度统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuminanceStats {
+    /// 256 级亮度直方图（像素数）
+    pub histogram: Vec<u32>,
+    /// 平均亮度（0-255）
+    pub mean: f64,
+    /// 亮度标准差，反映对比度
+    pub std_dev: f64,
+    /// 直方图的香农熵（位，0-8），内容越丰富越高
+    pub entropy: f64,
+    /// 1% 分位亮度（最暗的 1% 像素不低于该值）
+    pub low: u32,
+    /// 99% 分位亮度
+    pub high: u32,
+}
+
+/// 统计紧密排列的 4 通道位图的亮度
+pub fn luminance(data: &[u8], pixel_format: PixelFormat) -> LuminanceStats {
+    let mut histogram = vec![0u32; 256];
+    for value in filters::to_grayscale(data, pixel_format) {
+        histogram[value as usize] += 1;
+    }
+    from_histogram(histogram)
+}
+
+fn from_histogram(histogram: Vec<u32>) -> LuminanceStats {
+    let total: u64 = histogram.iter().map(|&c| c as u64).sum();
+    if total == 0 {
+        return LuminanceStats {
+            histogram,
+            mean: 0.0,
+            std_dev: 0.0,
+            entropy: 0.0,
+            low: 0,
+            high: 0,
+        };
+    }
+    let n = total as f64;
+    let mean = histogram.iter().enumerate().map(|(v, &c)| v as f64 * c as f64).sum::<f64>() / n;
+    let variance = histogram
+        .iter()
+        .enumerate()
+        .map(|(v, &c)| (v as f64 - mean).powi(2) * c as f64)
+        .sum::<f64>()
+        / n;
+    let entropy = histogram
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / n;
+            -p * p.log2()
+        })
+        .sum();
+
+    LuminanceStats {
+        low: percentile(&histogram, total, 0.01),
+        high: percentile(&histogram, total, 0.99),
+        histogram,
+        mean,
+        std_dev: variance.sqrt(),
+        entropy,
+    }
+}
+
+/// 累计像素数达到 `fraction` 的最小亮度
+fn percentile(histogram: &[u32], total: u64, fraction: f64) -> u32 {
+    let target = ((total as f64 * fraction).ceil() as u64).max(1);
+    let mut cumulative = 0u64;
+    for (value, &count) in histogram.iter().enumerate() {
+        cumulative += count as u64;
+        if cumulative >= target {
+            return value as u32;
+        }
+    }
+    255
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luminance_stats() {
+        // 一半黑一半白：均值 127.5，标准差 127.5，熵 1 位
+        let data: Vec<u8> = [[0, 0, 0, 255], [255, 255, 255, 255]].repeat(50).concat();
+        let stats = luminance(&data, PixelFormat::Rgba);
+        assert_eq!(stats.histogram[0], 50);
+        assert_eq!(stats.histogram[255], 50);
+        assert_eq!(stats.mean, 127.5);
+        assert_eq!(stats.std_dev, 127.5);
+        assert!((stats.entropy - 1.0).abs() < 1e-9);
+        assert_eq!((stats.low, stats.high), (0, 255));
+    }
+
+    #[test]
+    fn test_uniform_page_has_no_contrast() {
+        let stats = luminance(&[30, 30, 30, 255].repeat(10), PixelFormat::Bgra);
+        assert_eq!((stats.mean, stats.std_dev, stats.entropy), (30.0, 0.0, 0.0));
+        assert_eq!((stats.low, stats.high), (30, 30));
+        assert_eq!(luminance(&[], PixelFormat::Rgba).mean, 0.0);
+    }
+}
@@ -0,0 +1,138 @@
+//! JPEG 编码（渐进式 / 色度抽样）
+//!
+//! image 的 JPEG 编码器只支持基线模式和 4:4:4 抽样，渐进式或 4:2:0 输出交给 jpeg-encoder。
+//! 默认参数（基线 + 4:4:4）仍由 image 编码，输出保持不变。
+
+use jpeg_encoder::{ColorType, Encoder, SamplingFactor};
+
+/// 色度抽样方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChromaSubsampling {
+    /// 4:4:4，不抽样
+    #[default]
+    Full,
+    /// 4:2:0，色度水平和垂直各减半
+    Half,
+}
+
+impl ChromaSubsampling {
+    /// 从选项解析（"444" / "420"），未知的名称返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "444" | "4:4:4" => Some(Self::Full),
+            "420" | "4:2:0" => Some(Self::Half),
+            _ => None,
+        }
+    }
+
+    fn sampling_factor(self) -> SamplingFactor {
+        match self {
+            Self::Full => SamplingFactor::R_4_4_4,
+            Self::Half => SamplingFactor::R_4_2_0,
+        }
+    }
+}
+
+/// JPEG 编码参数
+#[derive(Debug, Clone, Copy)]
+pub struct JpegOptions {
+    /// 编码质量（1-100）
+    pub quality: u8,
+    /// 是否输出渐进式 JPEG
+    pub progressive: bool,
+    /// 色度抽样方式
+    pub subsampling: ChromaSubsampling,
+}
+
+/// 将 RGB 数据编码为 JPEG
+pub fn encode(rgb: &[u8], width: u32, height: u32, options: &JpegOptions) -> Result<Vec<u8>, String> {
+    if width == 0 || height == 0 || width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(format!("Invalid JPEG size: {}x{}", width, height));
+    }
+    if rgb.len() != width as usize * height as usize * 3 {
+        return Err(format!("Invalid RGB buffer length {} for {}x{}", rgb.len(), width, height));
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, options.quality.clamp(1, 100));
+    encoder.set_progressive(options.progressive);
+    encoder.set_sampling_factor(options.subsampling.sampling_factor());
+    encoder
+        .encode(rgb, width as u16, height as u16, ColorType::Rgb)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOF0: u8 = 0xC0;
+    const SOF2: u8 = 0xC2;
+
+    fn gradient(width: u32, height: u32) -> Vec<u8> {
+        let mut rgb = Vec::with_capacity((width * height * 3) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                rgb.extend_from_slice(&[(x * 255 / width) as u8, (y * 255 / height) as u8, 128]);
+            }
+        }
+        rgb
+    }
+
+    fn decode(data: &[u8]) -> image::RgbImage {
+        image::load_from_memory_with_format(data, image::ImageFormat::Jpeg)
+            .unwrap()
+            .to_rgb8()
+    }
+
+    fn mean_error(a: &[u8], b: &[u8]) -> f64 {
+        a.iter().zip(b).map(|(&x, &y)| (x as f64 - y as f64).abs()).sum::<f64>() / a.len() as f64
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        // 奇数尺寸，覆盖 MCU 边缘填充
+        let (width, height) = (37, 23);
+        let rgb = gradient(width, height);
+        for progressive in [false, true] {
+            for subsampling in [ChromaSubsampling::Full, ChromaSubsampling::Half] {
+                let options = JpegOptions { quality: 90, progressive, subsampling };
+                let data = encode(&rgb, width, height, &options).unwrap();
+                let sof = if progressive { SOF2 } else { SOF0 };
+                assert!(data.windows(2).any(|w| w == [0xFF, sof]));
+                let decoded = decode(&data);
+                assert_eq!(decoded.dimensions(), (width, height));
+                assert!(mean_error(decoded.as_raw(), &rgb) < 4.0, "{:?}", options);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subsampling_reduces_size() {
+        let (width, height) = (64, 64);
+        let mut rgb = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                rgb.extend_from_slice(&[(x * 37 % 256) as u8, (y * 53 % 256) as u8, ((x ^ y) * 11 % 256) as u8]);
+            }
+        }
+        let full = encode(&rgb, width, height, &JpegOptions { quality: 85, progressive: false, subsampling: ChromaSubsampling::Full }).unwrap();
+        let half = encode(&rgb, width, height, &JpegOptions { quality: 85, progressive: false, subsampling: ChromaSubsampling::Half }).unwrap();
+        assert!(half.len() < full.len());
+    }
+
+    #[test]
+    fn test_invalid_size() {
+        let options = JpegOptions { quality: 85, progressive: true, subsampling: ChromaSubsampling::Half };
+        assert!(encode(&[], 0, 0, &options).is_err());
+        assert!(encode(&[0; 5], 1, 1, &options).is_err());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(ChromaSubsampling::from_name("420"), Some(ChromaSubsampling::Half));
+        assert_eq!(ChromaSubsampling::from_name("444"), Some(ChromaSubsampling::Full));
+        assert_eq!(ChromaSubsampling::from_name("422"), None);
+    }
+}
@@ -4,25 +4,102 @@
 //! 通过 NAPI-RS 暴露给 Node.js 调用
 
 use napi::bindgen_prelude::*;
-use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction};
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi::{Env, JsFunction};
 use napi_derive::napi;
 
+mod analysis;
+mod attachments;
+mod batch;
+mod cancel;
+mod color;
+mod compare;
 mod config;
+mod content_hash;
+mod context;
+mod custom_encoders;
+mod diagnostics;
+mod doc_info;
+mod encoders;
 mod error;
+mod fetch_plan;
+mod filters;
+mod flight_recorder;
+mod image_stats;
+mod jpeg;
+mod lifecycle;
+mod memory;
+mod object_check;
+mod output_files;
+mod overlay;
+mod page_ranges;
+mod phash;
+mod pinned;
+mod profiles;
+mod quantize;
+mod reaper;
+mod raw_chunks;
+mod region;
+mod render_cache;
 mod renderer;
+mod scan_passthrough;
+mod scheduler;
+mod session;
+mod sprite;
+mod stamp;
+mod stream_limits;
 mod stream_reader;
+mod structure;
+mod svg;
+mod synthetic;
+mod tiff;
+mod tiles;
+mod usage;
+mod watchdog;
 
-use config::RenderConfig;
-use renderer::{PdfRenderer, OutputFormat};
-use stream_reader::{BlockRequest, JsFileStreamer};
+use error::ErrorCode;
+use color::{ColorManagement, IccProfile};
+use config::{Canvas, Highlight, PageScale, RenderConfig, ResourceLimits, ScanWidth};
+use std::collections::{BTreeMap, HashMap};
+use filters::{BilevelMode, DenoiseMode};
+use renderer::{annotation_type_from_name, AdvancedFlags, DeliveryOrder, FormMode, PageCallback, PdfRenderer, OutputFormat, OversizeStrategy, PixelFormat, SmartEncoding, TextGranularity};
+use page_ranges::PageSelection;
+use stamp::{Stamp, StampExclusion, StampOptions};
+use stream_reader::{BlockRequest, CacheTuning, FallbackPolicy, JsFileStreamer, SharedState};
 
 /// 创建 PDFium 实例
+///
+/// 先尝试 `configurePdfiumLibrary` 指定的库，再尝试当前工作目录下的库，最后回退到系统库；
+/// 都失败时返回最后一个错误，错误信息以 `PDFIUM_UNAVAILABLE` 开头
+/// （完整的探测过程和绑定状态见 `getDiagnostics`）。库曾经加载成功、之后又失败时先自动重试。
 fn create_pdfium() -> Result<pdfium_render::prelude::Pdfium> {
-    use pdfium_render::prelude::*;
-    Ok(Pdfium::default())
+    diagnostics::bind_with_retry()
+        .map(pdfium_render::prelude::Pdfium::new)
+        .map_err(|e| {
+            Error::from_reason(format!(
+                "{}: Failed to load PDFium library: {}",
+                ErrorCode::PdfiumUnavailable.as_str(),
+                e
+            ))
+        })
 }
 
+/// 用于预热和自检的最小单页 PDF
+const MINIMAL_PDF: &[u8] = b"%PDF-1.4
+1 0 obj<</Type/Catalog/Pages 2 0 R>>endobj
+2 0 obj<</Type/Pages/Kids[3 0 R]/Count 1>>endobj
+3 0 obj<</Type/Page/MediaBox[0 0 612 792]/Parent 2 0 R>>endobj
+xref
+0 4
+0000000000 65535 f 
+0000000009 00000 n 
+0000000052 00000 n 
+0000000101 00000 n 
+trailer<</Size 4/Root 1 0 R>>
+startxref
+170
+%%EOF";
+
 /// 单页渲染结果
 #[napi(object)]
 pub struct PageResult {
@@ -33,6 +110,9 @@ pub struct PageResult {
     /// 图像高度
     pub height: u32,
     /// 编码后的图像数据
+    ///
+    /// 直接引用原生层分配的内存（外部 Buffer，不复制），归 JS 所有，GC 回收时释放；
+    /// 可以长期持有或修改，不会被后续渲染覆盖。缓存命中时是缓存数据的副本。
     pub buffer: Buffer,
     /// 是否成功
     pub success: bool,
@@ -42,6 +122,321 @@ pub struct PageResult {
     pub render_time: u32,
     /// 编码耗时（毫秒）
     pub encode_time: u32,
+    /// 是否跳过（未尝试渲染，区别于渲染失败）：超出总时间预算（SKIPPED）、
+    /// 页码大于文档页数（OUT_OF_RANGE）或渲染被取消（CANCELLED）
+    pub skipped: bool,
+    /// 实际输出格式（webp/png/jpg/tiff），oversizeStrategy 为 fallbackPng 时超限页面为 png
+    pub format: String,
+    /// 分块结果（oversizeStrategy 为 tile 且页面超出尺寸上限时，此时 buffer 为空）
+    pub tiles: Option<PageTiles>,
+    /// 实际生效的渲染参数（渲染失败时为空），用于排查“为什么这页很模糊”
+    pub render_info: Option<RenderInfo>,
+    /// 失败类别（成功时为空），便于按类别制定重试策略：
+    /// INVALID_PAGE、LOAD_FAILED、RENDER_FAILED、ENCODE_FAILED、LIMIT_EXCEEDED、
+    /// INVALID_OPTIONS、TIMEOUT、RENDER_TIMEOUT、CANCELLED、SKIPPED、OUT_OF_RANGE、PDFIUM_UNAVAILABLE
+    pub error_code: Option<String>,
+    /// 由同一张位图额外编码的其他格式（options.alsoEncode），未指定或渲染失败时为空
+    pub alternates: Option<Vec<AlternateEncoding>>,
+    /// 是否直接取自渲染结果缓存（见 configureRenderCache），此时 renderTime 和 encodeTime 为 0
+    pub cached: bool,
+    /// 是否原样返回了扫描页面内嵌的图片（options.scanPassthrough），此时没有渲染和编码
+    pub passthrough: bool,
+    /// 是否识别为扫描件（决定是否使用 imageHeavyWidth 和降噪），未做识别（detectScan 关闭且没有 denoise）
+    /// 或渲染失败时为空；阈值见 scanMinImageCoverage / scanMaxTextObjects
+    pub detected_scan: Option<bool>,
+    /// 页面上的文字对象数（扫描件识别的依据之一），为空的情况同 detectedScan
+    pub text_object_count: Option<u32>,
+    /// 图片覆盖页面面积的比例（0-1，各图片外接矩形面积之和，超出 1 按 1 计），
+    /// 为空的情况同 detectedScan
+    pub image_coverage: Option<f64>,
+    /// 是否因超出内存预算（maxPixelsPerPage / maxTotalBytes）被缩小渲染，此时分辨率低于请求的分辨率
+    pub downscaled_due_to_memory: bool,
+    /// 输出图像的亮度统计（options.imageStats），未开启或渲染失败时为空
+    pub image_stats: Option<ImageStats>,
+    /// 编码前最终位图的内容哈希（options.contentHash，XXH64 的 16 位十六进制），
+    /// 未开启、渲染失败或原样返回（passthrough）时为空
+    pub content_hash: Option<String>,
+    /// 各阶段的起止时间（options.spanTimings），取自缓存或因超出时间预算跳过时为空
+    pub spans: Option<PageSpans>,
+    /// 输出尺寸是否因输出格式的单边像素上限（WebP 16383，其他格式 32767）被缩小，
+    /// 此时图像分辨率低于请求的分辨率
+    pub clamped: bool,
+    /// 被缩小前请求的输出尺寸（clamped 为 true 时）
+    pub requested_size: Option<RequestedSize>,
+}
+
+impl PageResult {
+    /// 失败的页面：没有图像数据，尺寸、耗时和各项统计为空；其他字段按需用结构体更新语法覆盖
+    pub(crate) fn failed(page_num: u32, format: &str, error: String, code: ErrorCode) -> Self {
+        Self {
+            page_num,
+            width: 0,
+            height: 0,
+            buffer: Buffer::from(vec![]),
+            success: false,
+            error: Some(error),
+            render_time: 0,
+            encode_time: 0,
+            skipped: false,
+            format: format.to_string(),
+            tiles: None,
+            render_info: None,
+            error_code: code.code(),
+            alternates: None,
+            cached: false,
+            passthrough: false,
+            detected_scan: None,
+            text_object_count: None,
+            image_coverage: None,
+            downscaled_due_to_memory: false,
+            image_stats: None,
+            content_hash: None,
+            spans: None,
+            clamped: false,
+            requested_size: None,
+        }
+    }
+}
+
+/// 按选项计算出的输出尺寸（像素）
+#[napi(object)]
+#[derive(Clone)]
+pub struct RequestedSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 单页各阶段的起止时间（Unix 毫秒，含小数部分）
+///
+/// 由单调时钟换算，同一进程内不会回退，可直接作为调用方追踪系统中子 span 的
+/// 开始和结束时间。没有到达的阶段为空（如渲染失败时没有编码阶段）。
+#[napi(object)]
+#[derive(Clone)]
+pub struct PageSpans {
+    /// 加载页面（取页面、扫描件识别、计算尺寸）
+    pub load_start: f64,
+    pub load_end: f64,
+    /// PDFium 光栅化
+    pub render_start: Option<f64>,
+    pub render_end: Option<f64>,
+    /// 滤镜处理和编码（原始位图结果只有整理像素数据）
+    pub encode_start: Option<f64>,
+    pub encode_end: Option<f64>,
+}
+
+/// 单次调用的资源占用（options.resourceUsage 时）
+///
+/// CPU 时间包含渲染线程和编码线程；内存只统计 Rust 侧的堆分配（像素缓冲、编码输出等），
+/// PDFium 内部的分配不计入，峰值是近似值。非 Linux 平台 CPU 时间为 0。
+/// 内存统计需要以 `alloc-usage` 特性构建原生模块（替换全局分配器），默认构建不返回。
+#[napi(object)]
+#[derive(Clone)]
+pub struct ResourceUsage {
+    /// 用户态 CPU 时间（毫秒）
+    pub cpu_user_ms: f64,
+    /// 内核态 CPU 时间（毫秒）
+    pub cpu_system_ms: f64,
+    /// 调用期间已分配未释放内存的峰值（字节）
+    pub peak_alloc_bytes: Option<i64>,
+    /// 调用期间累计分配的字节数
+    pub total_alloc_bytes: Option<i64>,
+}
+
+impl From<usage::Usage> for ResourceUsage {
+    fn from(usage: usage::Usage) -> Self {
+        Self {
+            cpu_user_ms: usage.user_micros as f64 / 1000.0,
+            cpu_system_ms: usage.system_micros as f64 / 1000.0,
+            peak_alloc_bytes: usage.peak_bytes.map(|bytes| bytes as i64),
+            total_alloc_bytes: usage.allocated_bytes.map(|bytes| bytes as i64),
+        }
+    }
+}
+
+/// 输出图像的亮度统计，用于自动标记过暗或对比度过低的扫描件
+#[napi(object)]
+#[derive(Clone)]
+pub struct ImageStats {
+    /// 256 级亮度直方图（每级的像素数）
+    pub histogram: Vec<u32>,
+    /// 平均亮度（0-255）
+    pub mean: f64,
+    /// 亮度标准差（对比度）
+    pub std_dev: f64,
+    /// 直方图的香农熵（0-8 位），空白或纯色页面接近 0
+    pub entropy: f64,
+    /// 1% 分位亮度
+    pub low: u32,
+    /// 99% 分位亮度；与 low 的差即有效动态范围
+    pub high: u32,
+}
+
+/// 同一页面的附加编码结果
+#[napi(object)]
+pub struct AlternateEncoding {
+    /// 输出格式（webp/png/jpg/tiff）
+    pub format: String,
+    /// 编码后的图像数据（编码失败时为空）
+    pub buffer: Buffer,
+    /// 编码耗时（毫秒）
+    pub encode_time: u32,
+    /// 错误信息（如果编码失败）
+    pub error: Option<String>,
+}
+
+/// 单页实际生效的渲染和编码参数
+#[napi(object)]
+#[derive(Clone)]
+pub struct RenderInfo {
+    /// 实际缩放比例（1.0 = 72 DPI）
+    pub scale: f64,
+    /// 决定尺寸的选项：targetWidth、dpi、imageHeavyWidth、imageNative（preferSharpness）、pageWidths、pageDpi、canvas、region（renderPageRegion）或 passthrough（scanPassthrough）
+    pub size_source: String,
+    /// 是否识别为扫描件
+    pub is_scan: bool,
+    /// 缩放比例是否被 maxScale 限制
+    pub max_scale_applied: bool,
+    /// 扫描件降级宽度是否被 maxScale 限制（小尺寸扫描页面），可用 preferSharpness 改按图片原始分辨率渲染
+    pub scale_clamped: bool,
+    /// 是否因输出格式的尺寸上限被缩小
+    pub dimension_clamped: bool,
+    /// 渲染后是否又做了一次缩放（PDFium 输出超出上限时）
+    pub resized: bool,
+    /// 实际使用的编码器后端
+    pub encoder_backend: String,
+    /// 编码质量（WebP/JPEG 为 quality，PNG 为压缩级别；无损编码时为空）
+    pub quality: Option<u32>,
+    /// WebP 编码方法（仅 WebP，webpMethodAuto 时为按页面尺寸调整后的值）
+    pub webp_method: Option<i32>,
+    /// 输出图像的有效分辨率（DPI，包含渲染后的缩放）
+    pub dpi: Option<f64>,
+    /// PDF 坐标（点）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`：
+    /// `px = a·x + c·y + e`，`py = b·x + d·y + f`
+    ///
+    /// 已包含页面旋转、裁剪框偏移、渲染后缩放和画布偏移，按点提取的文字框、
+    /// 链接、表单域可直接换算到图像上。
+    pub transform: Option<Vec<f64>>,
+    /// 是否按纯文字页面做了灰度编码（smartEncoding）
+    pub text_optimized: bool,
+    /// 是否因进程内存超出水位而降级渲染（缩小宽度、使用最快的编码参数）
+    pub memory_degraded: bool,
+    /// 编码超出 encodeBudgetMs，改用了最快的编码参数（webpMethod 0、quality 不超过 75）
+    pub encode_budget_exceeded: bool,
+    /// 经 ICC 变换转换到 sRGB 的图片数（forceSrgb）
+    pub color_converted_images: u32,
+    /// 输出是否保留了透明区域（preserveAlpha 且页面实际有透明像素）
+    pub has_alpha: bool,
+    /// 可能没有画出来的页面对象（options.objectDiagnostics），未开启时为空
+    pub object_errors: Option<Vec<ObjectError>>,
+}
+
+/// 可能绘制失败的页面对象
+///
+/// PDFium 跳过无法绘制的对象时不报错，只在页面上留下空白；
+/// 区域标出了空白可能出现的位置。
+#[napi(object)]
+#[derive(Clone)]
+pub struct ObjectError {
+    /// 页面顶层对象的下标（嵌套在表单 XObject 中的对象报告其所在的顶层对象）
+    pub object_index: u32,
+    /// 对象类型：image（图片无法解码）或 unsupported（PDFium 不支持的对象类型）
+    pub object_type: String,
+    /// 原因（英文）
+    pub reason: String,
+    /// 对象在输出图像中的区域（像素），对象没有外接矩形时为空
+    pub region: Option<ObjectRegion>,
+}
+
+/// 页面对象在输出图像中的外接矩形（像素）
+#[napi(object)]
+#[derive(Clone)]
+pub struct ObjectRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 超大页面的分块网格
+#[napi(object)]
+pub struct PageTiles {
+    /// 分块边长（像素），最后一行/列的分块可能更小
+    pub tile_size: u32,
+    /// 列数
+    pub columns: u32,
+    /// 行数
+    pub rows: u32,
+    /// 分块列表（行优先）
+    pub tiles: Vec<PageTile>,
+}
+
+/// 单个分块
+#[napi(object)]
+pub struct PageTile {
+    /// 行号（从 0 开始）
+    pub row: u32,
+    /// 列号（从 0 开始）
+    pub column: u32,
+    /// 分块在整页图像中的横坐标
+    pub x: u32,
+    /// 分块在整页图像中的纵坐标
+    pub y: u32,
+    /// 分块宽度
+    pub width: u32,
+    /// 分块高度
+    pub height: u32,
+    /// 编码后的图像数据
+    pub buffer: Buffer,
+}
+
+/// 深度缩放分块请求
+#[napi(object)]
+pub struct ZoomTileRequest {
+    /// 缩放级别：0 时整页缩放到一个分块以内，每级放大一倍
+    pub zoom: u32,
+    /// 分块边长（像素，64-4096），默认 256
+    pub tile_size: Option<u32>,
+    /// 只渲染指定列的分块（从 0 开始，与 y 同时给出）
+    pub x: Option<u32>,
+    /// 只渲染指定行的分块（从 0 开始，与 x 同时给出）
+    pub y: Option<u32>,
+}
+
+/// 深度缩放级别的分块渲染结果
+#[napi(object)]
+pub struct ZoomTiles {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 缩放级别
+    pub zoom: u32,
+    /// 最高缩放级别（缩放比例首次达到 maxScale 的级别）
+    pub max_zoom: u32,
+    /// 该级别的缩放比例（像素/点）
+    pub scale: f64,
+    /// 该级别下整页图像的宽度（像素）
+    pub width: u32,
+    /// 该级别下整页图像的高度（像素）
+    pub height: u32,
+    /// 分块边长（像素）
+    pub tile_size: u32,
+    /// 该级别的分块列数
+    pub columns: u32,
+    /// 该级别的分块行数
+    pub rows: u32,
+    /// 分块的图像格式
+    pub format: String,
+    /// 渲染的分块（行优先，指定 x/y 时只有一块）
+    pub tiles: Vec<PageTile>,
+    /// 渲染耗时（毫秒）
+    pub render_time: u32,
+    /// 编码耗时（毫秒）
+    pub encode_time: u32,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 错误码
+    pub error_code: Option<String>,
 }
 
 /// 原始位图结果（不编码）
@@ -55,12 +450,108 @@ pub struct RawBitmapResult {
     pub width: u32,
     /// 图像高度
     pub height: u32,
-    /// 通道数（固定为 4，RGBA）
+    /// 通道数（固定为 4，RGBA 或 BGRA）
     pub channels: u32,
-    /// 原始 RGBA 像素数据
+    /// 每行字节数
+    ///
+    /// 输出始终是紧密排列的（无行尾填充），即 `stride == width * channels`。
+    /// 将 buffer 交给 Sharp 等其他原生库时可直接使用此值。
+    pub stride: u32,
+    /// 像素通道顺序：rgba 或 bgra
+    pub pixel_format: String,
+    /// 原始像素数据（通道顺序见 pixel_format）
+    ///
+    /// 与 PageResult.buffer 相同，直接引用原生层分配的内存，归 JS 所有；大尺寸位图
+    /// （如 4000×3000 RGBA 约 48 MB）不会在 Node 堆中再复制一份。
     pub buffer: Buffer,
     /// 渲染耗时（毫秒）
     pub render_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+    /// 实际生效的渲染参数（encoderBackend 为 none，编码由调用方完成）
+    pub render_info: Option<RenderInfo>,
+    /// 失败类别（成功时为空），取值与 PageResult.errorCode 相同
+    pub error_code: Option<String>,
+    /// 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空）
+    pub password_index: Option<u32>,
+    /// 输出位图的亮度统计（options.imageStats 时）
+    pub image_stats: Option<ImageStats>,
+    /// 输出位图的内容哈希（options.contentHash 时），含义同 PageResult.contentHash
+    pub content_hash: Option<String>,
+    /// 各阶段的起止时间（options.spanTimings 时）
+    pub spans: Option<PageSpans>,
+    /// 本次调用的 CPU 时间和内存分配（options.resourceUsage 时）
+    pub resource_usage: Option<ResourceUsage>,
+    /// 扫描件识别结果，含义同 PageResult.detectedScan
+    pub detected_scan: Option<bool>,
+    /// 页面上的文字对象数，含义同 PageResult.textObjectCount
+    pub text_object_count: Option<u32>,
+    /// 图片覆盖页面面积的比例，含义同 PageResult.imageCoverage
+    pub image_coverage: Option<f64>,
+    /// 是否因超出内存预算被缩小渲染，含义同 PageResult.downscaledDueToMemory
+    pub downscaled_due_to_memory: bool,
+}
+
+/// renderPageToRawChunks 交给回调的一段位图
+#[napi(object)]
+pub struct RawBitmapChunk {
+    /// 分块任务 ID，处理完后调用 completeRawChunk(taskId) 确认
+    pub task_id: u32,
+    /// 分块序号（从 0 开始）
+    pub index: u32,
+    /// 本段第一行在整页中的行号
+    pub y: u32,
+    /// 本段行数
+    pub rows: u32,
+    /// 整页宽度
+    pub width: u32,
+    /// 整页高度
+    pub height: u32,
+    /// 每行字节数（紧密排列，等于 width * 4）
+    pub stride: u32,
+    /// 像素通道顺序：rgba 或 bgra
+    pub pixel_format: String,
+    /// 本段像素数据
+    pub buffer: Buffer,
+    /// 是否为最后一段
+    pub last: bool,
+}
+
+/// 原始位图分块渲染结果（像素已通过回调交出，这里只有元信息）
+#[napi(object)]
+pub struct RawChunkResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 失败类别（成功时为空），取值与 PageResult.errorCode 相同
+    pub error_code: Option<String>,
+    /// 整页宽度
+    pub width: u32,
+    /// 整页高度
+    pub height: u32,
+    /// 通道数（固定为 4）
+    pub channels: u32,
+    /// 每行字节数
+    pub stride: u32,
+    /// 像素通道顺序：rgba 或 bgra
+    pub pixel_format: String,
+    /// 已交出的分块数
+    pub chunks: u32,
+    /// 渲染耗时（毫秒，包含等待回调确认的时间）
+    pub render_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+    /// 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空）
+    pub password_index: Option<u32>,
 }
 
 /// 批量渲染结果
@@ -76,544 +567,7285 @@ pub struct RenderResult {
     pub pages: Vec<PageResult>,
     /// 总耗时（毫秒）
     pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+    /// 成功打开文档的密码在 options.passwords 中的下标（文档未加密时为空）
+    pub password_index: Option<u32>,
+    /// 渲染是否被取消（options.cancelToken），pages 中只有取消前完成的页面带有图像
+    pub cancelled: bool,
+    /// 本次调用的 CPU 时间和内存分配（options.resourceUsage 时）
+    pub resource_usage: Option<ResourceUsage>,
 }
 
-/// 渲染配置选项
+/// 页面上的矩形区域（点，原点在页面左上角，已考虑页面旋转）
 #[napi(object)]
-pub struct RenderOptions {
-    /// 目标渲染宽度（默认 1280）
-    pub target_width: Option<u32>,
-    /// 扫描件/图片页面的降级宽度（默认 1024）
-    pub image_heavy_width: Option<u32>,
-    /// 最大缩放比例（默认 4.0）
-    pub max_scale: Option<f64>,
-    /// 图片质量（1-100，用于 webp/jpg，已废弃，请使用 webp_quality/jpeg_quality）
-    pub quality: Option<u32>,
-    /// 是否启用扫描件检测（默认 true）
-    pub detect_scan: Option<bool>,
-    /// 输出格式：webp, png, jpg（默认 webp）
-    pub format: Option<String>,
-    /// WebP 编码质量（0-100，默认 80）
-    pub webp_quality: Option<u32>,
-    /// WebP 编码方法/速度（0-6，0最快，6最慢，默认 4）
-    pub webp_method: Option<i32>,
-    /// JPEG 编码质量（0-100，默认 85）
-    pub jpeg_quality: Option<u32>,
-    /// PNG 压缩级别（0-9，默认 6）
-    pub png_compression: Option<u32>,
+pub struct PageRegion {
+    /// 左上角 X 坐标（点）
+    pub x: f64,
+    /// 左上角 Y 坐标（点）
+    pub y: f64,
+    /// 区域宽度（点）
+    pub width: f64,
+    /// 区域高度（点）
+    pub height: f64,
+    /// 缩放比例（像素/点），省略时使用 options.maxScale（默认 4）
+    pub scale: Option<f64>,
 }
 
-impl Default for RenderOptions {
-    fn default() -> Self {
-        Self {
-            target_width: Some(1280),
-            image_heavy_width: Some(1024),
-            max_scale: Some(4.0),
-            quality: None,
-            detect_scan: Some(true),
-            format: Some("webp".to_string()),
-            webp_quality: Some(80),
-            webp_method: Some(4),
-            jpeg_quality: Some(85),
-            png_compression: Some(6),
-        }
-    }
+/// 版面区域
+#[napi(object)]
+pub struct LayoutRegion {
+    /// 区域类型：text（文本块）或 image（图片）
+    pub kind: String,
+    /// 左上角 X 坐标（渲染图像像素）
+    pub x: u32,
+    /// 左上角 Y 坐标（渲染图像像素）
+    pub y: u32,
+    /// 区域宽度（像素）
+    pub width: u32,
+    /// 区域高度（像素）
+    pub height: u32,
 }
 
-/// 从 RenderOptions 构建 RenderConfig
-fn build_config(opts: &RenderOptions) -> RenderConfig {
-    let format = OutputFormat::from_str(&opts.format.clone().unwrap_or_else(|| "webp".to_string()));
-    
-    // 兼容旧的 quality 参数
-    let legacy_quality = opts.quality.unwrap_or(80) as u8;
-    
-    RenderConfig {
-        target_width: opts.target_width.unwrap_or(1280),
-        image_heavy_width: opts.image_heavy_width.unwrap_or(1024),
-        max_scale: opts.max_scale.unwrap_or(4.0) as f32,
-        detect_scan: opts.detect_scan.unwrap_or(true),
-        format,
-        webp_quality: opts.webp_quality.map(|q| q as u8).unwrap_or(legacy_quality),
-        webp_method: opts.webp_method.unwrap_or(4),
-        jpeg_quality: opts.jpeg_quality.map(|q| q as u8).unwrap_or(legacy_quality),
-        png_compression: opts.png_compression.unwrap_or(6) as u8,
-    }
+/// 单页版面分析结果
+#[napi(object)]
+pub struct PageLayout {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 渲染图像宽度（与相同选项下 renderPages 的输出一致）
+    pub width: u32,
+    /// 渲染图像高度
+    pub height: u32,
+    /// 缩放比例（像素/点），可用于换算回 PDF 坐标
+    pub scale: f64,
+    /// 版面区域，按阅读顺序排列
+    pub regions: Vec<LayoutRegion>,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
 }
 
-/// 从 PDF Buffer 渲染指定页面
-///
-/// # Arguments
-/// * `pdf_buffer` - PDF 文件的二进制数据
-/// * `page_nums` - 要渲染的页码数组（从 1 开始）
-/// * `options` - 渲染配置选项
-///
-/// # Returns
-/// 包含所有页面渲染结果的对象
-#[napi]
-pub fn render_pages(
-    pdf_buffer: Buffer,
-    page_nums: Vec<u32>,
-    options: Option<RenderOptions>,
-) -> Result<RenderResult> {
-    let start_time = std::time::Instant::now();
-    let opts = options.unwrap_or_default();
-    let config = build_config(&opts);
-
-    let pdfium = match create_pdfium() {
-        Ok(p) => p,
-        Err(e) => {
-            return Ok(RenderResult {
-                success: false,
-                error: Some(e.to_string()),
-                num_pages: 0,
-                pages: vec![],
-                total_time: start_time.elapsed().as_millis() as u32,
-            });
-        }
-    };
+/// 结构树中的图形元素
+#[napi(object)]
+pub struct PageFigure {
+    /// 结构类型：Figure、Formula，或其他带替代文字的元素类型
+    pub kind: String,
+    /// 替代文字（/Alt）
+    pub alt_text: Option<String>,
+    /// 实际文字（/ActualText）
+    pub actual_text: Option<String>,
+    /// 标题（/T）
+    pub title: Option<String>,
+    /// 关联题注（Caption）的文字
+    pub caption: Option<String>,
+    /// 图形在渲染图像上的区域（找不到对应的页面对象时为空）
+    pub region: Option<LayoutRegion>,
+}
 
-    let renderer = PdfRenderer::new(&pdfium, config);
-    
-    match renderer.render_from_buffer(&pdf_buffer, &page_nums) {
-        Ok((num_pages, pages)) => Ok(RenderResult {
-            success: true,
-            error: None,
-            num_pages,
-            pages,
-            total_time: start_time.elapsed().as_millis() as u32,
-        }),
-        Err(e) => Ok(RenderResult {
-            success: false,
-            error: Some(e),
-            num_pages: 0,
-            pages: vec![],
-            total_time: start_time.elapsed().as_millis() as u32,
-        }),
-    }
+/// 单页图形与替代文字
+#[napi(object)]
+pub struct PageFigures {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面是否有结构树（未打标签的页面没有图形信息）
+    pub tagged: bool,
+    /// 渲染图像宽度（与相同选项下 renderPages 的输出一致）
+    pub width: u32,
+    /// 渲染图像高度
+    pub height: u32,
+    /// 图形元素，按结构树顺序排列
+    pub figures: Vec<PageFigure>,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
 }
 
-/// 从文件路径渲染 PDF 页面
-///
-/// 直接从文件系统读取 PDF，避免在 Node.js 堆中创建大 Buffer。
-/// 这是处理本地大文件的最高效方式。
-///
-/// # Arguments
-/// * `file_path` - PDF 文件的路径
-/// * `page_nums` - 要渲染的页码数组（从 1 开始）
-/// * `options` - 渲染配置选项
-///
-/// # Returns
-/// 包含所有页面渲染结果的对象
-#[napi]
-pub fn render_pages_from_file(
-    file_path: String,
-    page_nums: Vec<u32>,
-    options: Option<RenderOptions>,
-) -> Result<RenderResult> {
-    let start_time = std::time::Instant::now();
-    let opts = options.unwrap_or_default();
-    let config = build_config(&opts);
+/// 页面上的图片对象
+#[napi(object)]
+pub struct PageImage {
+    /// 在页面顶层对象中的序号（从 0 开始）
+    pub index: u32,
+    /// 图片本身的像素宽度
+    pub pixel_width: u32,
+    /// 图片本身的像素高度
+    pub pixel_height: u32,
+    /// 外接矩形左下角 X 坐标（PDF 坐标，单位点）
+    pub x: f64,
+    /// 外接矩形左下角 Y 坐标（PDF 坐标，原点在页面左下角）
+    pub y: f64,
+    /// 外接矩形宽度（点）
+    pub width: f64,
+    /// 外接矩形高度（点）
+    pub height: f64,
+    /// 图片水平方向的放置分辨率（DPI）
+    pub dpi_x: f64,
+    /// 图片垂直方向的放置分辨率（DPI）
+    pub dpi_y: f64,
+    /// 有效分辨率：两个方向中较低的一个
+    pub effective_dpi: f64,
+    /// 外接矩形占页面面积的比例（0-1）
+    pub coverage: f64,
+    /// 每像素位数（无法读取时为 0）
+    pub bits_per_pixel: u32,
+}
 
-    let pdfium = match create_pdfium() {
-        Ok(p) => p,
-        Err(e) => {
-            return Ok(RenderResult {
-                success: false,
-                error: Some(e.to_string()),
+/// 单页图片及其分辨率
+#[napi(object)]
+pub struct PageImages {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面顶层的图片对象（表单 XObject 内部的图片不列出）
+    pub images: Vec<PageImage>,
+    /// 覆盖面积最大的图片的有效分辨率（扫描页面即扫描分辨率），没有图片时为空
+    pub dominant_dpi: Option<f64>,
+    /// 所有图片中最低的有效分辨率，没有图片时为空
+    pub min_dpi: Option<f64>,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 单页文字统计
+#[napi(object)]
+pub struct PageTextStats {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 是否有可提取的文字（没有时通常需要 OCR）
+    pub has_text: bool,
+    /// 字符数（不含空白）
+    pub char_count: u32,
+    /// 词数（中日韩文字按字计）
+    pub word_count: u32,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 单页概况（不渲染）
+#[napi(object)]
+pub struct PageOverview {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面宽度（点，已考虑页面旋转）
+    pub width: f64,
+    /// 页面高度（点，已考虑页面旋转）
+    pub height: f64,
+    /// 页面旋转角度（0、90、180、270）
+    pub rotation: u32,
+    /// 页面标签（如 "iv"、"A-1"，文档未定义时为空）
+    pub label: Option<String>,
+    /// 是否有可提取的文字
+    pub has_text: bool,
+    /// 页面顶层是否有图片对象
+    pub has_images: bool,
+    /// 是否有表单控件以外的注释（noAnnotations 只影响这类注释）
+    pub has_annotations: bool,
+    /// 是否有表单控件（noFormData 只影响表单控件）
+    pub has_form_fields: bool,
+    /// 是否被识别为扫描件（有图片、没有文字对象，与渲染时的扫描件检测一致）
+    pub is_scan: bool,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 文档所有页面的概况
+#[napi(object)]
+pub struct DocumentLayout {
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页概况（按页码顺序）
+    pub pages: Vec<PageOverview>,
+    /// 被识别为扫描件的页数
+    pub scanned_pages: u32,
+    /// 扫描件页数占成功分析页数的比例（0-1），用于在 OCR 和文字提取之间做选择
+    pub scan_ratio: f64,
+    /// 文档级判定：至少一半页面是扫描件
+    pub is_scanned: bool,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+}
+
+/// 一个词或文字段及其位置
+#[napi(object)]
+pub struct TextRect {
+    /// 文字内容
+    pub text: String,
+    /// 左上角横坐标（点，原点在页面左上角，已考虑页面旋转）
+    pub x: f64,
+    /// 左上角纵坐标（点）
+    pub y: f64,
+    /// 宽度（点）
+    pub width: f64,
+    /// 高度（点）
+    pub height: f64,
+    /// 在输出图像中的左上角横坐标（像素，固定画布时包含画布偏移）
+    pub pixel_x: f64,
+    /// 在输出图像中的左上角纵坐标（像素）
+    pub pixel_y: f64,
+    /// 在输出图像中的宽度（像素）
+    pub pixel_width: f64,
+    /// 在输出图像中的高度（像素）
+    pub pixel_height: f64,
+}
+
+/// 单页文字位置（不渲染）
+#[napi(object)]
+pub struct PageTextRects {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 输出图像宽度（与相同选项下 renderPages 的输出一致）
+    pub width: u32,
+    /// 输出图像高度
+    pub height: u32,
+    /// 缩放比例（像素/点）
+    pub scale: f64,
+    /// PDF 坐标（点，原点在左下角）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`，与 renderInfo.transform 相同
+    pub transform: Vec<f64>,
+    /// 实际使用的粒度："word" 或 "run"
+    pub granularity: String,
+    /// 词或文字段，按页面文字流的顺序排列
+    pub rects: Vec<TextRect>,
+}
+
+/// 页面上的一个注释（链接、批注、表单控件等）
+#[napi(object)]
+pub struct PageAnnotation {
+    /// 注释类型，与 annotationTypes 的可选值相同（如 link、highlight、widget），其他类型为 unknown
+    pub kind: String,
+    /// 在输出图像中的左上角横坐标（像素，与相同选项下 renderPages 的输出一致）
+    pub x: f64,
+    /// 在输出图像中的左上角纵坐标（像素）
+    pub y: f64,
+    /// 宽度（像素）
+    pub width: f64,
+    /// 高度（像素）
+    pub height: f64,
+    /// 链接的外部地址（URI 动作）
+    pub uri: Option<String>,
+    /// 链接指向的本文档页码（从 1 开始）
+    pub dest_page_num: Option<u32>,
+    /// 注释的文字内容（批注正文等）
+    pub contents: Option<String>,
+}
+
+/// 单页注释（不渲染）
+#[napi(object)]
+pub struct PageAnnotations {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 输出图像宽度（与相同选项下 renderPages 的输出一致）
+    pub width: u32,
+    /// 输出图像高度
+    pub height: u32,
+    /// 缩放比例（像素/点）
+    pub scale: f64,
+    /// PDF 坐标（点）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`，与 renderInfo.transform 相同
+    pub transform: Vec<f64>,
+    /// 注释，按页面中的顺序排列
+    pub annotations: Vec<PageAnnotation>,
+}
+
+/// 表单字段（getFormFields 返回，每个控件一项）
+#[napi(object)]
+pub struct FormField {
+    /// 完整字段名（如 `applicant.name`）
+    pub name: Option<String>,
+    /// 字段类型：pushButton、checkbox、radioButton、comboBox、listBox、text、signature 或 unknown
+    pub kind: String,
+    /// 当前值：文本框的文字、组合框和列表框选中的选项、复选框和单选按钮组的值
+    pub value: Option<String>,
+    /// 复选框和单选按钮是否选中
+    pub checked: Option<bool>,
+    /// 是否只读
+    pub read_only: bool,
+    /// 是否必填
+    pub required: bool,
+    /// 所在页码（从 1 开始）
+    pub page_num: u32,
+    /// 控件区域左上角 x（像素，与相同选项下 renderPages 的输出一致）
+    pub x: f64,
+    /// 控件区域左上角 y（像素）
+    pub y: f64,
+    /// 控件区域宽度（像素）
+    pub width: f64,
+    /// 控件区域高度（像素）
+    pub height: f64,
+}
+
+/// 单页尺寸信息（不渲染）
+#[napi(object)]
+pub struct PageInfo {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面宽度（点，已考虑页面旋转）
+    pub width: f64,
+    /// 页面高度（点，已考虑页面旋转）
+    pub height: f64,
+    /// 页面旋转角度（0、90、180、270）
+    pub rotation: u32,
+    /// 页面标签（如 "iv"、"A-1"，文档未定义时为空）
+    pub label: Option<String>,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 文档元数据和所有页面的尺寸（不渲染）
+#[napi(object)]
+pub struct DocumentInfo {
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 标题
+    pub title: Option<String>,
+    /// 作者
+    pub author: Option<String>,
+    /// 主题
+    pub subject: Option<String>,
+    /// 关键词
+    pub keywords: Option<String>,
+    /// 创建文档的应用程序
+    pub creator: Option<String>,
+    /// 生成 PDF 的应用程序
+    pub producer: Option<String>,
+    /// 创建日期（ISO 8601，无法识别时为 PDF 中的原始值）
+    pub creation_date: Option<String>,
+    /// 修改日期（ISO 8601，无法识别时为 PDF 中的原始值）
+    pub modification_date: Option<String>,
+    /// PDF 版本（如 "1.7"）
+    pub pdf_version: Option<String>,
+    /// 每页尺寸（按页码顺序）
+    pub pages: Vec<PageInfo>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+}
+
+/// 流式渲染中文档加载完成时的信息（onDocument 回调，早于任何页面完成）
+#[napi(object)]
+pub struct StreamDocumentInfo {
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 标题
+    pub title: Option<String>,
+    /// 作者
+    pub author: Option<String>,
+    /// 主题
+    pub subject: Option<String>,
+    /// 关键词
+    pub keywords: Option<String>,
+    /// 创建文档的应用程序
+    pub creator: Option<String>,
+    /// 生成 PDF 的应用程序
+    pub producer: Option<String>,
+    /// 创建日期（ISO 8601，无法识别时为 PDF 中的原始值）
+    pub creation_date: Option<String>,
+    /// 修改日期（ISO 8601，无法识别时为 PDF 中的原始值）
+    pub modification_date: Option<String>,
+    /// PDF 版本（如 "1.7"）
+    pub pdf_version: Option<String>,
+    /// 文件总大小（字节，长度未知时为 fetcher 报告的长度）
+    pub file_size: i64,
+    /// 调用开始到文档加载完成的耗时（毫秒，含排队时间）
+    pub load_time: u32,
+}
+
+/// 文档附件（嵌入文件）信息
+#[napi(object)]
+pub struct AttachmentInfo {
+    /// 序号（从 0 开始，传给 getAttachment）
+    pub index: u32,
+    /// 文件名
+    pub name: String,
+    /// 解压后的字节数
+    pub size: i64,
+    /// MIME 类型（如 "text/xml"，文档未指定时为空）
+    pub mime_type: Option<String>,
+    /// 创建日期（ISO 8601，无法识别的格式原样返回）
+    pub creation_date: Option<String>,
+    /// 修改日期
+    pub modification_date: Option<String>,
+}
+
+/// 书签条目及其目标位置
+#[napi(object)]
+pub struct OutlineAnchor {
+    /// 书签标题
+    pub title: String,
+    /// 层级（顶层为 0）
+    pub level: u32,
+    /// 目标页码（从 1 开始，书签不指向本文档页面时为空）
+    pub page_num: Option<u32>,
+    /// 目标位置在渲染图像中的横坐标（像素，未指定时为空）
+    pub x: Option<f64>,
+    /// 目标位置在渲染图像中的纵坐标（像素，从图像顶部算起，未指定时为空）
+    pub y: Option<f64>,
+    /// 目标缩放比例（未指定时为空）
+    pub zoom: Option<f64>,
+    /// 目标页渲染图像宽度（与相同选项下 renderPages 的输出一致）
+    pub width: u32,
+    /// 目标页渲染图像高度
+    pub height: u32,
+}
+
+/// 书签树中的一个条目
+#[napi(object)]
+pub struct OutlineItem {
+    /// 书签标题
+    pub title: String,
+    /// 目标页码（从 1 开始，书签不指向本文档页面时为空）
+    pub page_num: Option<u32>,
+    /// 子书签
+    pub children: Vec<OutlineItem>,
+}
+
+/// 跨页（对开页）渲染结果
+#[napi(object)]
+pub struct SpreadResult {
+    /// 包含的页码（从左到右，封面或末尾单页时只有一页）
+    pub pages: Vec<u32>,
+    /// 图像宽度
+    pub width: u32,
+    /// 图像高度
+    pub height: u32,
+    /// 编码后的图像数据
+    pub buffer: Buffer,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 渲染耗时（毫秒）
+    pub render_time: u32,
+    /// 编码耗时（毫秒）
+    pub encode_time: u32,
+}
+
+/// 跨页批量渲染结果
+#[napi(object)]
+pub struct SpreadRenderResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每个跨页的渲染结果
+    pub spreads: Vec<SpreadResult>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 拼图中单页所在的区域
+#[napi(object)]
+pub struct SpriteCell {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面图像在拼图中的横坐标（已在单元格内居中）
+    pub x: u32,
+    /// 页面图像在拼图中的纵坐标
+    pub y: u32,
+    /// 页面图像宽度（失败时为 0）
+    pub width: u32,
+    /// 页面图像高度
+    pub height: u32,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 缩略图拼图结果
+#[napi(object)]
+pub struct ThumbnailStripResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 编码后的拼图
+    pub buffer: Buffer,
+    /// 拼图宽度
+    pub width: u32,
+    /// 拼图高度
+    pub height: u32,
+    /// 列数
+    pub columns: u32,
+    /// 行数
+    pub rows: u32,
+    /// 单元格边长（像素）
+    pub cell_size: u32,
+    /// 每页所在的区域，顺序与请求的页码一致
+    pub cells: Vec<SpriteCell>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 页面内嵌缩略图
+#[napi(object)]
+pub struct EmbeddedThumbnail {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 页面是否带有内嵌缩略图
+    pub found: bool,
+    /// 缩略图宽度
+    pub width: u32,
+    /// 缩略图高度
+    pub height: u32,
+    /// 缩略图数据（没有缩略图或失败时为空）
+    pub buffer: Buffer,
+    /// 数据格式：重新编码时为 webp/png/jpg/tiff，原样返回时为 jpg 或 jp2
+    pub format: Option<String>,
+    /// 是否为 PDF 中的原始数据（未解码重新编码）
+    pub passthrough: bool,
+    /// 页面没有内嵌缩略图，按 thumbnailFallbackWidth 渲染得到（此时 found 为 false）
+    pub rendered: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 内嵌缩略图提取结果
+#[napi(object)]
+pub struct ThumbnailResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页的缩略图
+    pub thumbnails: Vec<EmbeddedThumbnail>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 合成渲染的一个来源文档
+#[napi(object)]
+pub struct ComposeSource {
+    /// PDF 文件路径或 Buffer
+    #[napi(ts_type = "string | Buffer")]
+    pub input: Either<String, Buffer>,
+    /// 要取用的页码（从 1 开始，按给出的顺序），为空时取全部页面
+    pub pages: Option<Vec<u32>>,
+}
+
+/// 合成文档中一页的来源
+#[napi(object)]
+pub struct ComposedPageSource {
+    /// 来源在 sources 中的下标
+    pub source_index: u32,
+    /// 来源文档中的页码（从 1 开始）
+    pub page_num: u32,
+}
+
+/// 合成渲染结果
+#[napi(object)]
+pub struct ComposedRenderResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// 合成文档的总页数
+    pub num_pages: u32,
+    /// 各页渲染结果，pageNum 为合成文档中的页码
+    pub pages: Vec<PageResult>,
+    /// 合成文档每页的来源，第 i 项对应 pageNum 为 i + 1 的页面
+    pub page_sources: Vec<ComposedPageSource>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 导出为单页 PDF 的页面
+#[napi(object)]
+pub struct ExtractedPage {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 只包含该页的 PDF 数据（失败时为空）
+    pub buffer: Buffer,
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 错误码（如果失败）：INVALID_PAGE、OUT_OF_RANGE、RENDER_FAILED
+    pub error_code: Option<String>,
+}
+
+/// 单页 PDF 导出结果
+#[napi(object)]
+pub struct ExtractPagesResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页导出的 PDF
+    pub pages: Vec<ExtractedPage>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 选中页面导出为新 PDF 的结果
+#[napi(object)]
+pub struct ExtractDocumentResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 原 PDF 总页数
+    pub num_pages: u32,
+    /// 导出的页码（从 1 开始），与新 PDF 中的页面顺序一致
+    pub page_nums: Vec<u32>,
+    /// 只包含选中页面的 PDF 数据（失败时为空）
+    pub buffer: Buffer,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 两个文档中同一页码的布局比较
+#[napi(object)]
+pub struct PageComparison {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 文档 A 中的页面宽度（点，已考虑页面旋转，页面不存在时为空）
+    pub width_a: Option<f64>,
+    /// 文档 A 中的页面高度
+    pub height_a: Option<f64>,
+    /// 文档 A 中的页面旋转角度
+    pub rotation_a: Option<u32>,
+    /// 文档 B 中的页面宽度
+    pub width_b: Option<f64>,
+    /// 文档 B 中的页面高度
+    pub height_b: Option<f64>,
+    /// 文档 B 中的页面旋转角度
+    pub rotation_b: Option<u32>,
+    /// 页面尺寸不同（容差 0.5 点）
+    pub size_changed: bool,
+    /// 旋转角度不同
+    pub rotation_changed: bool,
+    /// 文档 A 没有这一页
+    pub missing_in_a: bool,
+    /// 文档 B 没有这一页
+    pub missing_in_b: bool,
+    /// 是否有任何差异（页面读取失败也算作有差异）
+    pub changed: bool,
+    /// 页面读取失败的原因
+    pub error: Option<String>,
+}
+
+/// 两个文档的布局比较结果
+#[napi(object)]
+pub struct DocumentComparison {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// 文档 A 的总页数
+    pub num_pages_a: u32,
+    /// 文档 B 的总页数
+    pub num_pages_b: u32,
+    /// 页数是否不同
+    pub page_count_changed: bool,
+    /// 页数相同且每页的尺寸和旋转都一致
+    pub identical_layout: bool,
+    /// 有差异的页码
+    pub changed_pages: Vec<u32>,
+    /// 逐页比较结果（按页码对齐，长度为两个文档页数的较大值）
+    pub pages: Vec<PageComparison>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 单页的感知哈希
+#[napi(object)]
+pub struct PageHash {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 64 位感知哈希（16 位十六进制字符串），失败时为空
+    pub hash: Option<String>,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+}
+
+/// 一组内容相同或相近的页面
+#[napi(object)]
+pub struct DuplicateCluster {
+    /// 页码（升序）
+    pub pages: Vec<u32>,
+    /// 组内两页之间的最大哈希距离（0 表示完全相同）
+    pub max_distance: u32,
+}
+
+/// 重复页检测结果
+#[napi(object)]
+pub struct DuplicatePagesResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页的哈希
+    pub hashes: Vec<PageHash>,
+    /// 重复页分组（只包含两页以上的组）
+    pub clusters: Vec<DuplicateCluster>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 渲染配置选项
+#[napi(object)]
+#[derive(Clone)]
+pub struct RenderOptions {
+    /// 目标渲染宽度（默认 1280）
+    pub target_width: Option<u32>,
+    /// 渲染分辨率（DPI，如 150、300），按页面物理尺寸计算渲染尺寸
+    ///
+    /// 混合 A4、Letter、幻灯片等不同尺寸的文档时，各页的物理分辨率保持一致。
+    /// 与 targetWidth 互斥，同时指定时每页以 INVALID_OPTIONS 失败；
+    /// 未指定 maxScale 时上限放宽到该 DPI（否则仍受 maxScale 约束，默认 4.0 即 288 DPI）。
+    /// 扫描件降级宽度、pageWidths、pageDpi 和 canvas 仍然优先。
+    pub dpi: Option<f64>,
+    /// 扫描件/图片页面的降级宽度（默认 1024）
+    pub image_heavy_width: Option<u32>,
+    /// 最大缩放比例（默认 4.0）
+    pub max_scale: Option<f64>,
+    /// 扫描件降级宽度超出 maxScale 时按内嵌图片的原始分辨率渲染（默认 false）
+    ///
+    /// 小尺寸扫描页面（如收据）按 imageHeavyWidth 计算的缩放比例常被 maxScale 限制，
+    /// 输出比扫描图片本身还模糊。开启后改用图片的原始分辨率（不超过 imageHeavyWidth），
+    /// renderInfo.sizeSource 为 imageNative。
+    pub prefer_sharpness: Option<bool>,
+    /// 图片质量（1-100，用于 webp/jpg，已废弃，请使用 webp_quality/jpeg_quality）
+    pub quality: Option<u32>,
+    /// 是否启用扫描件检测（默认 true）
+    pub detect_scan: Option<bool>,
+    /// 识别为扫描件所需的最小图片覆盖比例（0-1，默认 0：至少有一张图片即可）
+    ///
+    /// 设为 0.5 等值可避免带小插图或 logo 的页面被当作扫描件降低宽度。
+    pub scan_min_image_coverage: Option<f64>,
+    /// 识别为扫描件时允许的最多文字对象数（默认 0）
+    ///
+    /// 带 OCR 文字层或页眉页码的扫描件有少量文字对象，可以适当放宽。
+    pub scan_max_text_objects: Option<u32>,
+    /// 输出格式：webp, png, jpg, tiff, avif（默认 webp）
+    ///
+    /// avif 使用内置的 ravif 编码（纯 Rust，速度明显慢于 WebP），也可以用 registerEncoder 注册其他编码器
+    pub format: Option<String>,
+    /// WebP 编码质量（0-100，默认 80）
+    pub webp_quality: Option<u32>,
+    /// WebP 编码方法/速度（0-6，0最快，6最慢，默认 4）
+    pub webp_method: Option<i32>,
+    /// 按页面像素数自动调整 WebP 编码方法（默认 false）：缩略图提高 method 以获得更好的压缩，
+    /// 超大页面降低 method，单页编码耗时大致恒定；webpMethod 作为常规尺寸页面的基准
+    pub webp_method_auto: Option<bool>,
+    /// JPEG 编码质量（0-100，默认 85）
+    pub jpeg_quality: Option<u32>,
+    /// 输出渐进式 JPEG（默认 false）
+    ///
+    /// 大尺寸扫描页面在网页中可以先显示模糊的整页，再逐步变清晰。
+    pub jpeg_progressive: Option<bool>,
+    /// JPEG 色度抽样："444"（默认，不抽样）或 "420"
+    ///
+    /// 420 将色度分辨率减半，照片类页面体积约减少 30%，彩色细字边缘会略有发虚。
+    pub jpeg_chroma_subsampling: Option<String>,
+    /// AVIF 编码质量（0-100，默认 50）
+    pub avif_quality: Option<u32>,
+    /// AVIF 编码速度（0-10，0 最慢但压缩最好，默认 6）
+    pub avif_speed: Option<u32>,
+    /// PNG 压缩级别（0-9，默认 6）
+    pub png_compression: Option<u32>,
+    /// 位图像素格式：rgba 或 bgra（默认 rgba）
+    ///
+    /// bgra 保持 PDFium 原生通道顺序，省去每帧的通道转换。
+    /// 原始位图接口直接返回 BGRA 数据；WebP 编码通过 libwebp 的 BGRA 导入直接编码。
+    pub pixel_format: Option<String>,
+    /// 调色板颜色数（2-256，默认不量化）
+    ///
+    /// 设置后渲染结果会被量化为最多 N 种颜色；PNG 输出为调色板（索引色）PNG，
+    /// 适合线条图和超小尺寸预览。
+    pub colors: Option<u32>,
+    /// 二值化（1 位）输出：threshold（固定阈值）或 sauvola（自适应），默认关闭
+    ///
+    /// 开启后 PNG 输出 1 位灰度 PNG，TIFF 输出 CCITT G4 压缩，适合传真/归档场景。
+    pub bilevel: Option<String>,
+    /// 固定阈值二值化的阈值（0-255，默认 128）
+    pub bilevel_threshold: Option<u32>,
+    /// Sauvola 二值化的局部窗口大小（像素，默认 25）
+    pub sauvola_window: Option<u32>,
+    /// Sauvola 二值化的灵敏度系数 k（默认 0.2）
+    pub sauvola_k: Option<f64>,
+    /// renderMultipageTiff 的颜色模式：bilevel（CCITT G4）、gray 或 color（LZW）
+    ///
+    /// 默认：设置了 bilevel 时为 bilevel，否则为 color。
+    pub tiff_color: Option<String>,
+    /// 是否启用背景归一化（光照校正，默认 false）
+    ///
+    /// 在编码/二值化之前消除扫描件的阴影和光照不均，提升可读性和 OCR 准确率。
+    pub normalize_background: Option<bool>,
+    /// 背景归一化的估计窗口（像素，默认 64，应明显大于文字笔画宽度）
+    pub background_window: Option<u32>,
+    /// 扫描件页面的降噪方式：median（中值）或 bilateral（双边），默认关闭
+    ///
+    /// 只作用于被识别为扫描件的页面，可去除源扫描件中的 JPEG 伪影，显著减小 WebP 体积。
+    pub denoise: Option<String>,
+    /// 降噪半径（1-3，默认 1）
+    pub denoise_radius: Option<u32>,
+    /// PDFium 底层渲染标志（高级选项，未识别的标志会被忽略）
+    ///
+    /// 可选值：noAnnotations、noFormData、lcdText、noNativeText、grayscale、
+    /// limitImageCache、forceHalftone、printMode、noTextSmoothing、noImageSmoothing、
+    /// noPathSmoothing、fillsAsStrokes、reverseByteOrder（等价于 pixelFormat: 'rgba'）、
+    /// nativeByteOrder（等价于 pixelFormat: 'bgra'）。
+    ///
+    /// PDFium 默认会缓存已解码的图片以加速重复绘制，长时间运行的 worker 处理大量图片型文档时
+    /// 缓存可能累积到数百 MB。limitImageCache 让 PDFium 在绘制完成后尽快释放解码结果，
+    /// 以少量重复解码为代价换取稳定的内存占用。
+    pub advanced_flags: Option<Vec<String>>,
+    /// 只渲染这些类型的注释（如 ['highlight', 'ink']），其余注释隐藏；未识别的类型名会被忽略
+    ///
+    /// 可选值：text、link、freeText、line、square、circle、polygon、polyline、highlight、
+    /// underline、squiggly、strikeOut、stamp、caret、ink、popup、fileAttachment、sound、
+    /// movie、widget（表单控件）、screen、printerMark、trapNet、watermark、threeD、
+    /// richMedia、redact。advancedFlags 含 noAnnotations 时所有注释都不渲染。
+    pub annotation_types: Option<Vec<String>>,
+    /// 表单控件的渲染方式（默认 "render"）
+    ///
+    /// - "render"：通过 PDFium 表单环境绘制控件和当前填写的值
+    /// - "flatten"：渲染前把表单控件和注释按打印效果合并进页面内容（不打印的注释不再显示）
+    /// - "omit"：不绘制表单控件，只保留页面内容和其他注释
+    pub form_mode: Option<String>,
+    /// 渲染注释（默认 true）
+    ///
+    /// 为 false 时不绘制任何注释，等价于 advancedFlags 中的 noAnnotations，
+    /// 适合存档渲染和需要稳定输出的比对工具。
+    pub render_annotations: Option<bool>,
+    /// 渲染表单控件（默认 true）
+    ///
+    /// 为 false 时等价于 formMode: "omit"，优先于 formMode 的其他取值。
+    pub render_forms: Option<bool>,
+    /// 关闭文字抗锯齿（默认 false，等价于 advancedFlags 中的 noTextSmoothing）
+    pub disable_text_antialiasing: Option<bool>,
+    /// 关闭图片抗锯齿（默认 false，等价于 advancedFlags 中的 noImageSmoothing）
+    pub disable_image_antialiasing: Option<bool>,
+    /// 针对 LCD 屏幕优化文字渲染（默认 false，等价于 advancedFlags 中的 lcdText）
+    pub optimize_for_lcd: Option<bool>,
+    /// 请求关联 ID（可选）
+    ///
+    /// 原样回显在渲染结果中，便于把 JS 层和原生层的日志、监控事件串联到同一条链路。
+    pub correlation_id: Option<String>,
+    /// 单次调用最多处理的页数（默认不限制）
+    pub max_pages: Option<u32>,
+    /// 分析页面时单页最多检查的对象数（默认不限制）
+    ///
+    /// 扫描件识别和版面分析需要遍历页面对象，恶意构造的页面可能包含数百万个对象。
+    pub max_page_objects: Option<u32>,
+    /// 输入文档的最大字节数（默认不限制）
+    pub max_file_size: Option<f64>,
+    /// 单页位图的最大像素数（默认不限制）
+    ///
+    /// 尺寸上限只限制单边像素，5 米见方的页面在 32767×32767 以内仍需要 4 GB 位图。
+    /// 超出时按 memoryBudgetAction 缩小渲染或让该页失败，结果的 downscaledDueToMemory 为 true。
+    pub max_pixels_per_page: Option<f64>,
+    /// 同时渲染的页面位图（RGBA，每像素 4 字节）总字节数上限（默认不限制）
+    ///
+    /// 按 concurrency 平分到每页，与 maxPixelsPerPage 取较严格的一个。
+    pub max_total_bytes: Option<f64>,
+    /// 页面超出内存预算（maxPixelsPerPage / maxTotalBytes）时的处理方式（默认 "downscale"）
+    ///
+    /// - "downscale": 等比缩小到预算以内再渲染
+    /// - "fail": 该页失败（errorCode 为 LIMIT_EXCEEDED），其余页面正常渲染
+    pub memory_budget_action: Option<String>,
+    /// 单次调用的总时间预算（毫秒，默认不限制）
+    ///
+    /// 超出后不再渲染剩余页面，这些页面标记为 skipped 并立即返回已完成的结果，
+    /// 适合交互式预览：宁可先拿到前几页，也不要等全部页面渲染完。
+    pub total_time_budget_ms: Option<u32>,
+    /// 单页编码时间预算（毫秒），与渲染时间分开计算
+    ///
+    /// libwebp 有损编码超出预算时放弃，改用 method 0、quality 不超过 75 重新编码
+    /// （renderInfo.encodeBudgetExceeded）；其他格式和编码器不能中途放弃，不受影响。
+    pub encode_budget_ms: Option<u32>,
+    /// 单页渲染超时（毫秒，默认不限制）
+    ///
+    /// 损坏的 PDF 可能让 PDFium 在一页上运行几分钟。超出后放弃该页（errorCode 为 RENDER_TIMEOUT），
+    /// 继续渲染其余页面。超时在 PDFium 绘制页面对象的间隙检查，单个对象内部（如解码巨大的图片）无法中断。
+    pub page_timeout_ms: Option<u32>,
+    /// 流式渲染时允许同时活动的流式任务数上限（仅对 renderPagesFromStream 生效）
+    ///
+    /// 活动任务数达到该值时本任务排队等待；与 `configureStreamLimits` 的全局上限取较小值。
+    pub max_concurrent_streams: Option<u32>,
+    /// 流式渲染的并行请求数（1-8，默认 1，仅对 renderPagesFromStream 生效）
+    ///
+    /// 大于 1 时，检测到 PDFium 顺序读取后会同时请求后续的若干块，
+    /// 适合高延迟的对象存储；随机访问时仍逐块请求。
+    pub fetch_lanes: Option<u32>,
+    /// 流式渲染的合并读取块数（0-32，默认 0，仅对 renderPagesFromStream 生效）
+    ///
+    /// PDFium 以大量小的顺序读取访问文件，每次缓存未命中都是一次 JS 往返。大于 0 时，
+    /// 检测到顺序读取后的缓存未命中会在同一个请求中连同后续的若干块一起获取；
+    /// 可与 fetchLanes 同时使用。预取的效果见 streamStats.prefetchHits / prefetchedBlocks。
+    pub stream_readahead: Option<u32>,
+    /// 流式渲染前先计算所请求页面需要的字节范围，一次批量交给 fetcher（默认 false，仅对 renderPagesFromStream 生效）
+    ///
+    /// 打开文档后读取 xref 表和页面树，找出页面对象、内容流、资源和注释外观流所在的块，
+    /// 以一个带 ranges 的请求发给 fetcher，减少逐块往返。只支持传统 xref 表，
+    /// 使用交叉引用流的文件放弃计划（streamStats.fetchPlanError），渲染照常逐块读取。
+    pub fetch_plan: Option<bool>,
+    /// 线性化（Web 优化）文件的快速路径（默认 true，仅对 renderPagesFromStream 生效）
+    ///
+    /// 所请求的页面包含首页段落中的页面时，打开文档前按文件开头的线性化字典
+    /// 并发获取首页段落、提示表和主交叉引用表，首页不必再等 PDFium 逐块往返。
+    /// 是否使用见 streamStats.linearizedFastPath。
+    pub linearized_fast_path: Option<bool>,
+    /// 已获取的字节数达到文件大小的该比例时，改为一次获取整个文件（0-1，默认不回退，仅对 renderPagesFromStream 生效）
+    ///
+    /// 达到条件后的下一次缓存未命中时，以一个带 `full: true` 的请求向 fetcher 要整个文件，
+    /// 之后的读取全部从内存返回；获取失败时继续逐块读取（streamStats.fallbackError）。
+    pub stream_fallback_fraction: Option<f64>,
+    /// 已发出的请求数达到该值时改为一次获取整个文件（默认不回退，仅对 renderPagesFromStream 生效）
+    ///
+    /// 与 streamFallbackFraction 任一满足即回退。
+    pub stream_fallback_requests: Option<u32>,
+    /// 流式渲染的缓存块大小（字节，16KB-16MB，默认 256KB，仅对 renderPagesFromStream 生效）
+    ///
+    /// 也是每次向 fetcher 请求的大小。按请求计费或延迟较高的对象存储可以调大以减少请求数，
+    /// 代价是每次缓存未命中多读一些数据。
+    pub stream_block_size: Option<u32>,
+    /// 流式渲染最多缓存的块数（2-4096，默认 64，仅对 renderPagesFromStream 生效）
+    ///
+    /// 缓存满后按 LRU 淘汰；streamStats 的 REFETCHED_BLOCKS 建议出现时可以调大。
+    pub stream_max_cache_blocks: Option<u32>,
+    /// 流式渲染等待 fetcher 返回一个块的超时时间（毫秒，默认 30000，仅对 renderPagesFromStream 生效）
+    pub stream_fetch_timeout_ms: Option<u32>,
+    /// 常驻文档句柄空闲超过该时间（毫秒）后清空流式块缓存（默认不清空，仅对 PdfDocumentHandle 的打开方法生效）
+    ///
+    /// 调用方忘记 `close()` 时块缓存会一直占用内存。超时后句柄仍然可用，
+    /// 之后的渲染按需重新通过 fetcher 获取数据。
+    pub document_idle_timeout_ms: Option<u32>,
+    /// 内嵌缩略图是 JPEG/JPEG 2000 时原样返回（默认 false，仅对 getEmbeddedThumbnails 生效）
+    ///
+    /// 原样返回时不解码也不重新编码，输出格式以结果中的 `format` 为准；
+    /// 其他编码方式的缩略图仍按 `format` 选项重新编码。
+    pub thumbnail_passthrough: Option<bool>,
+    /// 没有内嵌缩略图的页面改为按该宽度渲染（像素，默认不回退，仅对 getEmbeddedThumbnails 生效）
+    ///
+    /// 网格视图可以一次调用填满：有缩略图的页面直接读取，其余页面渲染一张小图，
+    /// 结果的 `rendered` 为 true。渲染使用 `format` 等其他选项，只把宽度换成该值。
+    pub thumbnail_fallback_width: Option<u32>,
+    /// 两遍渲染：每页先按该宽度渲染一张预览图交给 onPreview，再按正式尺寸渲染（默认 320）
+    ///
+    /// 只在传入 onPreview 回调时生效，预览与正式渲染使用同一个已加载的文档。
+    /// 预览宽度不小于 targetWidth 时不生成预览；命中渲染缓存的页面直接返回最终结果。
+    pub preview_width: Option<u32>,
+    /// 扫描页面只有一张铺满页面的 JPEG/JPEG 2000 图片时原样返回图片数据（默认 false）
+    ///
+    /// 不渲染也不重新编码，输出为图片的原始分辨率，格式以结果中的 `format` 为准，
+    /// 结果的 `passthrough` 为 true。页面有注释、旋转，图片经过变换或使用 CMYK 等色彩空间，
+    /// 或开启了 canvas、highlights、水印、二值化、调色板、滤镜、alsoEncode、imageStats 等需要像素的选项时照常渲染。
+    /// 需要 detectScan（默认开启）。
+    pub scan_passthrough: Option<bool>,
+    /// 按页码指定目标宽度，如 `{ "1": 2560 }`（其余页面仍使用 targetWidth）
+    ///
+    /// 一次调用中让不同页面使用不同尺寸（例如首页作为大图），只加载一次文档。
+    /// 同时受 maxScale 和输出格式的尺寸上限约束。
+    pub page_widths: Option<HashMap<String, u32>>,
+    /// 按页码指定渲染分辨率（DPI，72 为原始尺寸），如 `{ "1": 300 }`
+    ///
+    /// 同一页同时出现在 pageWidths 中时以 pageWidths 为准。
+    pub page_dpi: Option<HashMap<String, f64>>,
+    /// 按页码覆盖扫描件降级宽度，如 `{ "3": "full" }`
+    ///
+    /// - "full": 即使识别为扫描件也使用 targetWidth（版式接近扫描件、但需要完整宽度的画册页面）
+    /// - "scan": 即使未识别为扫描件也使用 imageHeavyWidth
+    /// - "auto": 按扫描件检测结果（默认）
+    ///
+    /// 不受 detectScan 开关影响；pageWidths / pageDpi 指定的页面以它们为准。
+    pub page_scan_mode: Option<HashMap<String, String>>,
+    /// 按页码覆盖输出格式，如 `{ "2": "png" }`（其余页面仍使用 format）
+    ///
+    /// 同一批次中透明或线稿页面输出 PNG、其余页面输出 WebP。渲染流程相同，只有编码器不同；
+    /// encoderBackend 不支持该页格式时改用该格式的默认后端。
+    pub page_formats: Option<HashMap<String, String>>,
+    /// 缩略图拼图的列数（默认排成一行，超出尺寸上限后换行，仅对 renderThumbnailStrip 生效）
+    pub sprite_columns: Option<u32>,
+    /// 跨页渲染时第一页（封面）是否单独成页（默认 true，仅对 renderSpreads 生效）
+    ///
+    /// 为 true 时按 [1]、[2,3]、[4,5]… 组合，与印刷书籍的左右页一致；
+    /// 为 false 时按 [1,2]、[3,4]… 组合。
+    pub spread_cover: Option<bool>,
+    /// 文字位置的粒度："word"（默认，按词）或 "run"（PDFium 的文字段），仅对 getPageTextRects 生效
+    pub text_granularity: Option<String>,
+    /// 租户标记（仅对 scheduleRender 生效），调度器按租户分别执行并发和 CPU 时间限额
+    pub tenant: Option<String>,
+    /// 固定输出宽度（需同时指定 canvasHeight）
+    ///
+    /// 页面等比缩放到 canvasWidth × canvasHeight 以内，空白部分用 padColor 填充，
+    /// 不同比例的页面得到相同尺寸的图片，适合固定大小的缩略图格子。优先于 targetWidth 等尺寸设置。
+    pub canvas_width: Option<u32>,
+    /// 固定输出高度（需同时指定 canvasWidth）
+    pub canvas_height: Option<u32>,
+    /// 画布填充色：`#rgb`、`#rrggbb`、`#rrggbbaa` 或 `transparent`（默认白色）
+    ///
+    /// JPEG 不支持透明度，透明填充在 JPEG 中显示为 jpegBackground（默认白色）。
+    pub pad_color: Option<String>,
+    /// 页面在画布中的对齐方式：center（默认）、top、bottom、left、right 或 top-left 等组合
+    pub pad_align: Option<String>,
+    /// 页面尺寸超出 WebP 上限（单边 16383）时的处理方式（默认 "downscale"）
+    ///
+    /// - "downscale": 等比缩小到上限以内
+    /// - "fallbackPng": 保持计算出的尺寸，该页改用 PNG 输出（结果中的 `format` 为 png）
+    /// - "tile": 保持计算出的尺寸，切成 tileSize 大小的网格分别编码（结果中的 `tiles`）
+    pub oversize_strategy: Option<String>,
+    /// 分块边长（像素，默认 4096，不超过输出格式的尺寸上限）
+    pub tile_size: Option<u32>,
+    /// 判定为重复页的最大哈希距离（0-64，默认 4，仅对 findDuplicatePages 生效）
+    ///
+    /// 0 只匹配渲染结果几乎一致的页面；扫描件的重复上传通常在 10 以内。
+    pub duplicate_threshold: Option<u32>,
+    /// 编码器后端（默认 "auto"，可选值见 getEncoderCapabilities）
+    ///
+    /// - "libwebp": WebP 有损编码（默认），受 webpQuality / webpMethod 控制
+    /// - "image": image crate 的纯 Rust 编码器；WebP 为无损编码
+    /// - "ravif": AVIF 编码（avif 的默认后端），受 avifQuality / avifSpeed 控制
+    /// - registerEncoder 注册的名称：交给 JS 自定义编码器（仅异步接口）
+    ///
+    /// 指定的后端未编译进来或不支持当前格式时，该页编码失败并返回错误。
+    pub encoder_backend: Option<String>,
+    /// 合成到指定页面上的高亮区域（如评论系统中的用户高亮）
+    ///
+    /// 以正片叠底方式合成，文字保持清晰。坐标无效的条目会被忽略。
+    pub highlights: Option<Vec<HighlightOption>>,
+    /// 文字水印，合成到每页渲染结果上（编码之前，缩放和填充画布之前）
+    ///
+    /// 使用 PDF 标准字体 Helvetica 绘制，只支持拉丁字符；其他文字请使用 stampImage。
+    pub stamp_text: Option<String>,
+    /// 图片水印：紧密排列的 RGBA 像素（非预乘 alpha），需同时指定 stampImageWidth / stampImageHeight
+    ///
+    /// 与 stampText 同时指定时使用图片。数据长度与宽高不符时每页以 INVALID_OPTIONS 失败。
+    pub stamp_image: Option<Buffer>,
+    /// 图片水印的宽度（像素）
+    pub stamp_image_width: Option<u32>,
+    /// 图片水印的高度（像素）
+    pub stamp_image_height: Option<u32>,
+    /// 文字水印的字号（像素，默认 48，范围 4-1000）
+    pub stamp_font_size: Option<f64>,
+    /// 文字水印的颜色：`#rgb`、`#rrggbb` 或 `#rrggbbaa`（默认 #808080）
+    pub stamp_color: Option<String>,
+    /// 水印的不透明度（0-1，默认 0.3），与图片自身的透明度相乘
+    pub stamp_opacity: Option<f64>,
+    /// 水印位置：center（默认）、top、bottom、left、right 或 top-left 等组合，tile 表示铺满整页
+    pub stamp_position: Option<String>,
+    /// 水印到图像边缘的距离（像素，默认 24），tile 时为水印之间的间距
+    pub stamp_margin: Option<u32>,
+    /// 不合成水印的区域（如签名、印章所在的位置），坐标无效的条目会被忽略
+    pub stamp_exclusions: Option<Vec<StampExclusionOption>>,
+    /// 输出 JPEG（及 TIFF）时半透明像素混合的背景色：`#rgb` 或 `#rrggbb`，默认白色
+    ///
+    /// 页面本身通常不透明，主要影响透明的画布填充（padColor: 'transparent'）。
+    pub jpeg_background: Option<String>,
+    /// 渲染结果超出尺寸上限的容差（像素，默认 2）
+    ///
+    /// PDFium 输出的位图偶尔因取整比预先计算的尺寸大 1～2 像素。超出量在容差以内时
+    /// 直接裁掉多余的边缘，省去一次 Lanczos 缩放；超出更多时仍然等比缩小。设为 0 总是缩放。
+    pub resize_tolerance: Option<u32>,
+    /// 页码大于文档页数时的处理方式（默认 "skip"）
+    ///
+    /// - "skip": 该页标记为跳过（skipped 为 true，errorCode 为 OUT_OF_RANGE），其余页面正常渲染
+    /// - "fail": 整个调用失败，不渲染任何页面
+    pub out_of_range_pages: Option<String>,
+    /// 纯文字页面的编码方式（仅 WebP 输出，默认 "off"）
+    ///
+    /// 页面只有文字对象、没有图片且渲染结果近似灰度时：
+    /// - "grayscale": 转为灰度，用 method 6 有损编码
+    /// - "lossless": 转为灰度后无损编码，文字边缘更锐利
+    ///
+    /// 扫描件、彩色页面及指定了 bilevel/colors 的调用不受影响；
+    /// 转换后的页面 renderInfo.textOptimized 为 true。
+    pub smart_encoding: Option<String>,
+    /// 文档加密时依次尝试的候选密码
+    ///
+    /// 先不带密码打开，遇到密码错误再按顺序尝试，结果的 passwordIndex 为成功的下标。
+    /// 适合批量处理只用几个已知密码之一加密的归档文档。
+    pub passwords: Option<Vec<String>>,
+    /// 文档密码（用户输入的单个密码）
+    ///
+    /// 与 passwords 同时指定时先尝试这个密码（passwordIndex 为 0，passwords 的下标顺延）。
+    /// 密码错误或需要密码但没有提供时，错误信息以 `WRONG_PASSWORD:` 开头，
+    /// 单页结果的 errorCode 为 WRONG_PASSWORD，调用方可以据此提示用户重新输入。
+    pub password: Option<String>,
+    /// 额外输出的格式列表（如 ["jpg"]），默认不输出
+    ///
+    /// 每页渲染一次，用同一张位图再编码为这些格式，结果放在 PageResult.alternates 中，
+    /// 例如主格式为 WebP、同时为旧客户端准备 JPEG，无需整份文档渲染两遍。
+    /// 与主格式相同的条目会被忽略；分块输出的超大页面不生成附加编码。
+    pub also_encode: Option<Vec<String>>,
+    /// 渲染前把 CMYK 和 ICCBased 图片经 ICC 变换转换到 sRGB（默认 false）
+    ///
+    /// PDFium 按近似公式转换 DeviceCMYK，品牌色在印刷素材上容易偏色。开启后 ICCBased 图片
+    /// 使用内嵌的配置文件，DeviceCMYK 图片使用 cmykProfile；只处理页面顶层、8 位深度、
+    /// 未旋转的图片，矢量图形和文字的颜色不变。结果的 renderInfo.colorConvertedImages 为转换的图片数。
+    pub force_srgb: Option<bool>,
+    /// DeviceCMYK 图片使用的 CMYK ICC 配置文件（如 FOGRA39、SWOP），仅在 forceSrgb 时生效
+    ///
+    /// 未提供时 DeviceCMYK 图片仍由 PDFium 转换。
+    pub cmyk_profile: Option<Buffer>,
+    /// 在结果中返回每页输出图像的亮度统计（直方图、均值、对比度、熵），默认 false
+    ///
+    /// 统计基于最终输出的位图（包含滤镜和画布填充），分块输出的超大页面同样统计整页。
+    pub image_stats: Option<bool>,
+    /// 在结果中返回每页输出位图的内容哈希 contentHash（默认 false）
+    ///
+    /// 对编码前的最终位图计算 XXH64，与输出格式和编码参数无关，可以作为 CDN 缓存的键。
+    /// 与 deterministic 一起使用时，哈希相同的页面输出的图像也逐字节相同。
+    pub content_hash: Option<bool>,
+    /// 检查页面对象能否正常绘制，结果见 renderInfo.objectErrors（默认 false）
+    ///
+    /// 图片数据损坏、使用 PDFium 不支持的过滤器或对象类型未知时，PDFium 直接跳过该对象，
+    /// 页面上只留下空白。开启后每张图片会额外完整解码一次，用于排查显示不全的问题。
+    pub object_diagnostics: Option<bool>,
+    /// 保留页面透明区域（默认 false）
+    ///
+    /// 默认 PDFium 在白色背景上渲染，输出总是不透明。开启后以透明背景渲染，页面实际
+    /// 有透明区域时 WebP/PNG 输出保留 alpha 通道（renderInfo.hasAlpha 为 true）；
+    /// JPEG/TIFF 仍与 jpegBackground 混合。有透明区域的页面不做 smartEncoding 灰度编码。
+    pub preserve_alpha: Option<bool>,
+    /// 渲染会话（startRenderSession）中最先渲染并交付的页码（从 1 开始）
+    ///
+    /// 该页在要渲染的页面中时移到最前，其余页面保持原来的顺序，界面可以尽快显示首屏。
+    /// 不指定时按 pageNums 的顺序（全部页面时从第 1 页开始）。
+    pub hero_page: Option<u32>,
+    /// 在结果中返回每页加载、渲染、编码阶段的起止时间（spans，默认 false）
+    ///
+    /// 时间戳是 Unix 毫秒，调用方可以据此在追踪系统（如 OpenTelemetry）中还原
+    /// 跨越原生边界的真实 span，而不只是耗时。
+    pub span_timings: Option<bool>,
+    /// 在结果中返回本次调用的 CPU 时间和内存分配（resourceUsage，默认 false）
+    ///
+    /// 多租户服务可以据此按客户文档核算成本。支持 renderPages、renderPagesAsync、
+    /// renderPagesFromFile、renderPageRegion、renderPageToRawBitmap、renderPreloadedPages 和文档句柄的 renderPages。
+    pub resource_usage: Option<bool>,
+    /// renderPageToRawChunks 每个分块的行数（默认按约 4 MB 一块计算）
+    pub chunk_rows: Option<u32>,
+    /// 多页渲染时并行编码的线程数（默认 1，最大 16）
+    ///
+    /// PDFium 不支持多线程，页面位图仍依次渲染；编码（WebP/PNG/JPEG、滤镜、切片）
+    /// 交给工作线程并行执行，编码耗时占主要部分的批量渲染可以明显缩短总耗时。
+    /// 同时等待编码的位图不超过该数量，内存占用随之增加。
+    ///
+    /// 流式渲染（renderPagesFromStream、流式打开的文档）即使为 1 也在后台线程编码，
+    /// PDFium 线程同时开始下一页并请求它的数据块，网络读取和编码互相重叠。
+    pub concurrency: Option<u32>,
+    /// 并行编码时 onPage 的回调顺序（默认 "completion"）
+    ///
+    /// - "completion": 哪页先编码完成先回调哪页，延迟最低
+    /// - "input": 按请求的页码顺序回调，先完成的页面等待前面的页面（期间保留其图像数据）
+    ///
+    /// concurrency 为 1 且不是流式渲染时页面本来就按顺序完成，两者没有区别。
+    pub delivery_order: Option<String>,
+    /// 取消令牌（createCancelToken 返回的 ID）
+    ///
+    /// 调用 cancelRender 后，尚未开始的页面和等待编码的页面不再处理，
+    /// 以 CANCELLED 错误码返回（skipped 为 true），结果的 cancelled 为 true。
+    pub cancel_token: Option<u32>,
+    /// 确定性输出（默认 false）：相同的 PDF 和选项总是得到逐字节相同的图像
+    ///
+    /// 关闭随运行环境变化的自适应行为：内存超出水位时的降级渲染、encodeBudgetMs 超时后改用的快速编码；
+    /// 并行编码时 onPage 按页码顺序回调。输出图像本身不含时间戳等元数据。
+    /// 未嵌入的字体仍由系统字体替代，不同机器上的字体不同时输出可能不同；自定义编码器的输出不受约束。
+    pub deterministic: Option<bool>,
+    /// 使用 loadProfiles 注册的命名配置
+    ///
+    /// 本次调用中设置的选项优先，未设置的选项取自该配置；名称未注册时抛出异常。
+    pub profile: Option<String>,
+}
+
+/// 高亮区域
+#[napi(object)]
+#[derive(Clone)]
+pub struct HighlightOption {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 左下角 X 坐标（PDF 坐标，单位点，与注释的 /Rect 相同）
+    pub x: f64,
+    /// 左下角 Y 坐标（PDF 坐标，原点在页面左下角）
+    pub y: f64,
+    /// 宽度（点）
+    pub width: f64,
+    /// 高度（点）
+    pub height: f64,
+    /// 颜色：`#rgb`、`#rrggbb` 或 `#rrggbbaa`（alpha 控制强度），默认黄色
+    pub color: Option<String>,
+}
+
+/// 水印排除区域
+#[napi(object)]
+#[derive(Clone)]
+pub struct StampExclusionOption {
+    /// 页码（从 1 开始），不指定时对每一页生效
+    pub page_num: Option<u32>,
+    /// 左下角 X 坐标（PDF 坐标，单位点）
+    pub x: f64,
+    /// 左下角 Y 坐标（PDF 坐标，原点在页面左下角）
+    pub y: f64,
+    /// 宽度（点）
+    pub width: f64,
+    /// 高度（点）
+    pub height: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            target_width: Some(1280),
+            dpi: None,
+            image_heavy_width: Some(1024),
+            max_scale: Some(4.0),
+            prefer_sharpness: Some(false),
+            quality: None,
+            detect_scan: Some(true),
+            scan_min_image_coverage: Some(0.0),
+            scan_max_text_objects: Some(0),
+            format: Some("webp".to_string()),
+            webp_quality: Some(80),
+            webp_method: Some(4),
+            webp_method_auto: Some(false),
+            jpeg_quality: Some(85),
+            jpeg_progressive: Some(false),
+            jpeg_chroma_subsampling: None,
+            avif_quality: Some(50),
+            avif_speed: Some(6),
+            png_compression: Some(6),
+            pixel_format: Some("rgba".to_string()),
+            colors: None,
+            bilevel: None,
+            bilevel_threshold: Some(128),
+            sauvola_window: Some(25),
+            sauvola_k: Some(0.2),
+            tiff_color: None,
+            normalize_background: Some(false),
+            background_window: Some(64),
+            denoise: None,
+            denoise_radius: Some(1),
+            advanced_flags: None,
+            annotation_types: None,
+            form_mode: Some("render".to_string()),
+            render_annotations: Some(true),
+            render_forms: Some(true),
+            disable_text_antialiasing: Some(false),
+            disable_image_antialiasing: Some(false),
+            optimize_for_lcd: Some(false),
+            correlation_id: None,
+            max_pages: None,
+            max_page_objects: None,
+            max_file_size: None,
+            max_pixels_per_page: None,
+            max_total_bytes: None,
+            memory_budget_action: Some("downscale".to_string()),
+            total_time_budget_ms: None,
+            encode_budget_ms: None,
+            page_timeout_ms: None,
+            max_concurrent_streams: None,
+            fetch_lanes: Some(1),
+            stream_readahead: None,
+            fetch_plan: None,
+            linearized_fast_path: None,
+            stream_fallback_fraction: None,
+            stream_fallback_requests: None,
+            stream_block_size: None,
+            stream_max_cache_blocks: None,
+            stream_fetch_timeout_ms: None,
+            document_idle_timeout_ms: None,
+            thumbnail_passthrough: Some(false),
+            thumbnail_fallback_width: None,
+            preview_width: None,
+            scan_passthrough: Some(false),
+            page_widths: None,
+            page_dpi: None,
+            page_scan_mode: None,
+            page_formats: None,
+            sprite_columns: None,
+            spread_cover: Some(true),
+            text_granularity: Some("word".to_string()),
+            tenant: None,
+            canvas_width: None,
+            canvas_height: None,
+            pad_color: None,
+            pad_align: None,
+            oversize_strategy: Some("downscale".to_string()),
+            tile_size: Some(4096),
+            duplicate_threshold: Some(4),
+            encoder_backend: Some("auto".to_string()),
+            highlights: None,
+            stamp_text: None,
+            stamp_image: None,
+            stamp_image_width: None,
+            stamp_image_height: None,
+            stamp_font_size: None,
+            stamp_color: None,
+            stamp_opacity: None,
+            stamp_position: None,
+            stamp_margin: None,
+            stamp_exclusions: None,
+            jpeg_background: None,
+            resize_tolerance: Some(2),
+            out_of_range_pages: Some("skip".to_string()),
+            smart_encoding: Some("off".to_string()),
+            passwords: None,
+            password: None,
+            also_encode: None,
+            force_srgb: None,
+            cmyk_profile: None,
+            image_stats: None,
+            content_hash: None,
+            object_diagnostics: None,
+            preserve_alpha: None,
+            hero_page: None,
+            span_timings: None,
+            resource_usage: None,
+            chunk_rows: None,
+            concurrency: None,
+            delivery_order: Some("completion".to_string()),
+            cancel_token: None,
+            deterministic: Some(false),
+            profile: None,
+        }
+    }
+}
+
+/// 从 RenderOptions 构建 RenderConfig
+fn build_config(opts: &RenderOptions) -> RenderConfig {
+    let format = OutputFormat::from_str(&opts.format.clone().unwrap_or_else(|| "webp".to_string()));
+    
+    let deterministic = opts.deterministic.unwrap_or(false);
+
+    // 兼容旧的 quality 参数
+    let legacy_quality = opts.quality.unwrap_or(80) as u8;
+
+    // 字节序标志会覆盖 pixel_format，其余标志交给 PDFium 渲染配置
+    let flag_names = opts.advanced_flags.as_deref().unwrap_or(&[]);
+    let pixel_format = if flag_names.iter().any(|f| f == "nativeByteOrder") {
+        PixelFormat::Bgra
+    } else if flag_names.iter().any(|f| f == "reverseByteOrder") {
+        PixelFormat::Rgba
+    } else {
+        PixelFormat::from_str(opts.pixel_format.as_deref().unwrap_or("rgba"))
+    };
+    
+    let form_mode = if opts.render_forms == Some(false) {
+        FormMode::Omit
+    } else {
+        FormMode::from_str(opts.form_mode.as_deref().unwrap_or("render"))
+    };
+
+    let dpi = opts.dpi.filter(|dpi| dpi.is_finite() && *dpi > 0.0).map(|dpi| dpi as f32);
+    let size_error = (dpi.is_some() && opts.target_width.is_some())
+        .then(|| "dpi and targetWidth are mutually exclusive".to_string());
+
+    RenderConfig {
+        target_width: opts.target_width.unwrap_or(1280),
+        dpi,
+        size_error,
+        image_heavy_width: opts.image_heavy_width.unwrap_or(1024),
+        max_scale: opts.max_scale.unwrap_or_else(|| dpi.map_or(4.0, |dpi| (dpi as f64 / 72.0).max(4.0))) as f32,
+        prefer_sharpness: opts.prefer_sharpness.unwrap_or(false),
+        detect_scan: opts.detect_scan.unwrap_or(true),
+        scan_min_image_coverage: opts.scan_min_image_coverage.unwrap_or(0.0).clamp(0.0, 1.0),
+        scan_max_text_objects: opts.scan_max_text_objects.unwrap_or(0),
+        format,
+        webp_quality: opts.webp_quality.map(|q| q as u8).unwrap_or(legacy_quality),
+        webp_method: opts.webp_method.unwrap_or(4),
+        webp_method_auto: opts.webp_method_auto.unwrap_or(false),
+        jpeg_quality: opts.jpeg_quality.map(|q| q as u8).unwrap_or(legacy_quality),
+        jpeg_progressive: opts.jpeg_progressive.unwrap_or(false),
+        jpeg_chroma_subsampling: opts
+            .jpeg_chroma_subsampling
+            .as_deref()
+            .and_then(jpeg::ChromaSubsampling::from_name)
+            .unwrap_or_default(),
+        avif_quality: opts.avif_quality.or(opts.quality).unwrap_or(50).min(100) as u8,
+        avif_speed: opts.avif_speed.unwrap_or(6).min(10) as u8,
+        png_compression: opts.png_compression.unwrap_or(6) as u8,
+        pixel_format,
+        colors: opts.colors.map(|c| c.clamp(2, 256) as u16),
+        bilevel: opts.bilevel.as_deref().and_then(|mode| {
+            BilevelMode::from_options(
+                mode,
+                opts.bilevel_threshold.unwrap_or(128).min(255) as u8,
+                opts.sauvola_window.unwrap_or(25),
+                opts.sauvola_k.unwrap_or(0.2) as f32,
+            )
+        }),
+        normalize_background: opts
+            .normalize_background
+            .unwrap_or(false)
+            .then(|| opts.background_window.unwrap_or(64)),
+        denoise: opts
+            .denoise
+            .as_deref()
+            .and_then(|mode| DenoiseMode::from_options(mode, opts.denoise_radius.unwrap_or(1))),
+        advanced_flags: {
+            let mut flags = AdvancedFlags::from_names(flag_names);
+            flags.no_annotations |= !opts.render_annotations.unwrap_or(true);
+            flags.no_text_smoothing |= opts.disable_text_antialiasing.unwrap_or(false);
+            flags.no_image_smoothing |= opts.disable_image_antialiasing.unwrap_or(false);
+            flags.lcd_text |= opts.optimize_for_lcd.unwrap_or(false);
+            flags.no_form_data |= form_mode == FormMode::Omit;
+            flags
+        },
+        annotation_types: opts
+            .annotation_types
+            .as_ref()
+            .map(|names| names.iter().filter_map(|name| annotation_type_from_name(name)).collect()),
+        form_mode,
+        limits: ResourceLimits {
+            max_pages: opts.max_pages,
+            max_page_objects: opts.max_page_objects,
+            max_file_size: opts.max_file_size.map(|size| size as u64),
+            max_pixels_per_page: opts.max_pixels_per_page.map(|pixels| pixels.max(1.0) as u64),
+            max_total_bytes: opts.max_total_bytes.map(|bytes| bytes.max(4.0) as u64),
+        },
+        total_time_budget_ms: opts.total_time_budget_ms,
+        // 编码预算按耗时改变编码参数，确定性输出时不生效
+        encode_budget_ms: opts.encode_budget_ms.filter(|&ms| ms > 0 && !deterministic),
+        page_timeout_ms: opts.page_timeout_ms.filter(|&ms| ms > 0),
+        thumbnail_passthrough: opts.thumbnail_passthrough.unwrap_or(false),
+        thumbnail_fallback_width: opts.thumbnail_fallback_width.filter(|&width| width > 0),
+        preview_width: opts.preview_width.filter(|&width| width > 0),
+        scan_passthrough: opts.scan_passthrough.unwrap_or(false),
+        page_scales: page_scales(opts),
+        scan_widths: scan_widths(opts),
+        page_formats: page_formats(opts),
+        canvas: Canvas::from_options(
+            opts.canvas_width,
+            opts.canvas_height,
+            opts.pad_color.as_deref(),
+            opts.pad_align.as_deref(),
+        ),
+        oversize_strategy: OversizeStrategy::from_str(opts.oversize_strategy.as_deref().unwrap_or("downscale")),
+        tile_size: opts.tile_size.unwrap_or(4096).max(256),
+        encoder_backend: opts.encoder_backend.clone().unwrap_or_else(|| "auto".to_string()),
+        highlights: highlights(opts),
+        stamp: Stamp::from_options(StampOptions {
+            text: opts.stamp_text.as_deref(),
+            image: opts
+                .stamp_image
+                .as_ref()
+                .map(|image| (&image[..], opts.stamp_image_width, opts.stamp_image_height)),
+            font_size: opts.stamp_font_size,
+            color: opts.stamp_color.as_deref(),
+            opacity: opts.stamp_opacity,
+            position: opts.stamp_position.as_deref(),
+            margin: opts.stamp_margin,
+            exclusions: stamp_exclusions(opts),
+        }),
+        jpeg_background: opts
+            .jpeg_background
+            .as_deref()
+            .and_then(config::parse_color)
+            .map(|[r, g, b, _]| [r, g, b])
+            .unwrap_or([255, 255, 255]),
+        resize_tolerance: opts.resize_tolerance.unwrap_or(2),
+        fail_out_of_range: opts.out_of_range_pages.as_deref() == Some("fail"),
+        fail_over_memory_budget: opts.memory_budget_action.as_deref() == Some("fail"),
+        smart_encoding: SmartEncoding::from_str(opts.smart_encoding.as_deref().unwrap_or("off")),
+        passwords: opts.password.iter().chain(opts.passwords.iter().flatten()).cloned().collect(),
+        also_encode: opts.also_encode.iter().flatten().map(|f| OutputFormat::from_str(f)).collect(),
+        image_stats: opts.image_stats.unwrap_or(false),
+        content_hash: opts.content_hash.unwrap_or(false),
+        object_diagnostics: opts.object_diagnostics.unwrap_or(false),
+        preserve_alpha: opts.preserve_alpha.unwrap_or(false),
+        hero_page: opts.hero_page,
+        span_timings: opts.span_timings.unwrap_or(false),
+        chunk_rows: opts.chunk_rows,
+        concurrency: opts.concurrency.unwrap_or(1).clamp(1, 16),
+        delivery_order: if deterministic {
+            DeliveryOrder::Input
+        } else {
+            DeliveryOrder::from_str(opts.delivery_order.as_deref().unwrap_or("completion"))
+        },
+        overlap_fetch: false,
+        cancel_token: opts.cancel_token,
+        deterministic,
+        color_management: opts.force_srgb.unwrap_or(false).then(|| ColorManagement {
+            cmyk_profile: opts.cmyk_profile.as_ref().map(|profile| IccProfile::new(profile.to_vec())),
+        }),
+    }
+}
+
+/// 结果中是否有因取消而未完成的页面
+/// options.resourceUsage 时为本次调用创建资源统计
+/// 取调用选项（未传时使用默认值），并展开其中的 profile
+fn resolve_options(options: Option<RenderOptions>) -> Result<RenderOptions> {
+    profiles::resolve(options.unwrap_or_default()).map_err(Error::from_reason)
+}
+
+fn usage_meter(opts: &RenderOptions) -> Option<std::sync::Arc<usage::UsageMeter>> {
+    opts.resource_usage.unwrap_or(false).then(usage::UsageMeter::new)
+}
+
+/// 结果中回显的完整配置和配置哈希
+fn config_echo(config: &RenderConfig) -> (Option<String>, Option<String>) {
+    (Some(config.echo()), Some(config.output_hash()))
+}
+
+fn was_cancelled(pages: &[PageResult]) -> bool {
+    let code = ErrorCode::Cancelled.code();
+    pages.iter().any(|page| page.error_code == code)
+}
+
+/// 按页码分组高亮区域
+fn highlights(opts: &RenderOptions) -> BTreeMap<u32, Vec<Highlight>> {
+    let mut highlights: BTreeMap<u32, Vec<Highlight>> = BTreeMap::new();
+    for option in opts.highlights.iter().flatten() {
+        if let Some(highlight) = Highlight::from_options(option.x, option.y, option.width, option.height, option.color.as_deref()) {
+            highlights.entry(option.page_num).or_default().push(highlight);
+        }
+    }
+    highlights
+}
+
+/// 水印排除区域（坐标无效的条目会被忽略）
+fn stamp_exclusions(opts: &RenderOptions) -> Vec<StampExclusion> {
+    opts.stamp_exclusions
+        .iter()
+        .flatten()
+        .filter_map(|option| StampExclusion::from_options(option.page_num, option.x, option.y, option.width, option.height))
+        .collect()
+}
+
+/// 合并按页码指定的宽度和 DPI（页码无法解析或值无效的条目会被忽略）
+fn page_scales(opts: &RenderOptions) -> BTreeMap<u32, PageScale> {
+    fn by_page<T: Copy>(map: &Option<HashMap<String, T>>) -> impl Iterator<Item = (u32, T)> + '_ {
+        map.iter()
+            .flatten()
+            .filter_map(|(page, value)| Some((page.trim().parse().ok()?, *value)))
+    }
+
+    let mut scales = BTreeMap::new();
+    for (page_num, dpi) in by_page(&opts.page_dpi).filter(|(_, dpi)| dpi.is_finite() && *dpi > 0.0) {
+        scales.insert(page_num, PageScale::Dpi(dpi as f32));
+    }
+    for (page_num, width) in by_page(&opts.page_widths).filter(|(_, width)| *width > 0) {
+        scales.insert(page_num, PageScale::Width(width));
+    }
+    scales
+}
+
+/// 按页码的扫描件宽度覆盖（页码无法解析或值为 auto 的条目会被忽略）
+fn scan_widths(opts: &RenderOptions) -> BTreeMap<u32, ScanWidth> {
+    opts.page_scan_mode
+        .iter()
+        .flatten()
+        .filter_map(|(page, mode)| Some((page.trim().parse().ok()?, ScanWidth::from_str(mode)?)))
+        .collect()
+}
+
+/// 按页码指定的输出格式（页码无法解析的条目会被忽略）
+fn page_formats(opts: &RenderOptions) -> BTreeMap<u32, OutputFormat> {
+    opts.page_formats
+        .iter()
+        .flatten()
+        .filter_map(|(page, format)| Some((page.trim().parse().ok()?, OutputFormat::from_str(format))))
+        .collect()
+}
+
+/// 从 PDF Buffer 渲染指定页面
+///
+/// 在调用线程上同步渲染，渲染期间事件循环被阻塞；大批量渲染请使用 `renderPagesAsync`。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串（`"1-5,8,10-"`、`"all"`）、`{ from, to }`
+/// * `options` - 渲染配置选项
+///
+/// # Returns
+/// 包含所有页面渲染结果的对象
+#[napi(ts_args_type = "pdfBuffer: Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null")]
+pub fn render_pages(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: PagesArg,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+
+    let _task = match context::instance(&env)?.lifecycle.begin_task() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(RenderResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                total_time: start_time.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                password_index: None,
+                cancelled: false,
+                resource_usage: None,
+            });
+        }
+    };
+
+    let pdfium = match create_pdfium() {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(RenderResult {
+                success: false,
+                error: Some(e.to_string()),
+                num_pages: 0,
+                pages: vec![],
+                total_time: start_time.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                password_index: None,
+                cancelled: false,
+                resource_usage: None,
+            });
+        }
+    };
+
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let meter = usage_meter(&opts);
+    let attached = meter.as_ref().map(usage::attach);
+    let rendered = renderer.render_from_buffer(&pdf_buffer, &pages);
+    drop(attached);
+    let resource_usage = meter.map(|meter| meter.usage().into());
+
+    match rendered {
+        Ok((num_pages, pages, password_index)) => Ok(RenderResult {
+            success: true,
+            error: None,
+            num_pages,
+            cancelled: was_cancelled(&pages),
+            pages,
+            total_time: start_time.elapsed().as_millis() as u32,
+            correlation_id: opts.correlation_id.clone(),
+            resolved_config: resolved_config.clone(),
+            config_hash: config_hash.clone(),
+            password_index,
+            resource_usage,
+        }),
+        Err(e) => Ok(RenderResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            pages: vec![],
+            total_time: start_time.elapsed().as_millis() as u32,
+            correlation_id: opts.correlation_id.clone(),
+            resolved_config: resolved_config.clone(),
+            config_hash: config_hash.clone(),
+            password_index: None,
+            cancelled: false,
+            resource_usage,
+        }),
+    }
+}
+
+/// 从 PDF Buffer 渲染指定页面（异步版本）
+///
+/// 与 `renderPages` 相同，但渲染在后台线程中进行，返回 Promise，
+/// 大批量渲染时不阻塞事件循环。Buffer 数据会先复制一份，调用返回后即可释放或修改。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+/// * `options` - 渲染配置选项
+/// * `on_page` - 每页完成后立即以 `(err, page)` 调用（见 `page_callback`）
+/// * `on_preview` - 两遍渲染：每页先以 `(err, page)` 交付 previewWidth 宽的预览图，再渲染最终结果
+///
+/// # Returns
+/// Promise<RenderResult>
+#[napi(
+    ts_args_type = "pdfBuffer: Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null, onPage?: (err: Error | null, page: PageResult) => void, onPreview?: (err: Error | null, page: PageResult) => void",
+    ts_return_type = "Promise<RenderResult>"
+)]
+pub fn render_pages_async(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: PagesArg,
+    options: Option<RenderOptions>,
+    on_page: Option<JsFunction>,
+    on_preview: Option<JsFunction>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let meter = usage_meter(&opts);
+    let thread_meter = meter.clone();
+    let correlation_id = opts.correlation_id;
+    let task = context::instance(&env)?.lifecycle.begin_task();
+    let on_page = page_callback(on_page)?;
+    let on_preview = page_callback(on_preview)?;
+    let data = pdf_buffer.to_vec();
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            let _usage = thread_meter.as_ref().map(usage::attach);
+            let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+            let renderer = PdfRenderer::new(&pdfium, config)
+                .with_page_callback(on_page)
+                .with_preview_callback(on_preview);
+            renderer.render_from_buffer(&data, &pages)
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        let resource_usage = meter.map(|meter| meter.usage().into());
+        Ok(match result {
+            Ok((num_pages, pages, password_index)) => RenderResult {
+                success: true,
+                error: None,
+                num_pages,
+                cancelled: was_cancelled(&pages),
+                pages,
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index,
+                resource_usage,
+            },
+            Err(e) => RenderResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index: None,
+                cancelled: false,
+                resource_usage,
+            },
+        })
+    })
+}
+
+/// 从文件路径渲染 PDF 页面
+///
+/// 直接从文件系统读取 PDF，避免在 Node.js 堆中创建大 Buffer。
+/// 这是处理本地大文件的最高效方式。
+///
+/// 文件读取和渲染都在后台线程中进行，返回 Promise，
+/// 慢速存储（如 NFS 挂载）上的文件不会阻塞事件循环。
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+/// * `options` - 渲染配置选项
+/// * `on_page` - 每页完成后立即以 `(err, page)` 调用（见 `page_callback`）
+/// * `on_preview` - 两遍渲染：每页先以 `(err, page)` 交付 previewWidth 宽的预览图，再渲染最终结果
+///
+/// # Returns
+/// 包含所有页面渲染结果的对象
+#[napi(
+    ts_args_type = "filePath: string, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null, onPage?: (err: Error | null, page: PageResult) => void, onPreview?: (err: Error | null, page: PageResult) => void",
+    ts_return_type = "Promise<RenderResult>"
+)]
+pub fn render_pages_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: PagesArg,
+    options: Option<RenderOptions>,
+    on_page: Option<JsFunction>,
+    on_preview: Option<JsFunction>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let meter = usage_meter(&opts);
+    let thread_meter = meter.clone();
+    let correlation_id = opts.correlation_id;
+    let task = context::instance(&env)?.lifecycle.begin_task();
+    let on_page = page_callback(on_page)?;
+    let on_preview = page_callback(on_preview)?;
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            let _usage = thread_meter.as_ref().map(usage::attach);
+            let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+            let renderer = PdfRenderer::new(&pdfium, config)
+                .with_page_callback(on_page)
+                .with_preview_callback(on_preview);
+            renderer.render_from_file(&file_path, &pages)
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        let resource_usage = meter.map(|meter| meter.usage().into());
+        Ok(match result {
+            Ok((num_pages, pages, password_index)) => RenderResult {
+                success: true,
+                error: None,
+                num_pages,
+                cancelled: was_cancelled(&pages),
+                pages,
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index,
+                resource_usage,
+            },
+            Err(e) => RenderResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index: None,
+                cancelled: false,
+                resource_usage,
+            },
+        })
+    })
+}
+
+/// 批量渲染中的一个任务
+#[napi(object)]
+pub struct BatchRenderJob {
+    /// PDF 文件路径或 Buffer
+    #[napi(ts_type = "string | Buffer")]
+    pub source: Either<String, Buffer>,
+    /// 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+    #[napi(ts_type = "Array<number> | string | PageRange")]
+    pub page_nums: PagesArg,
+    /// 渲染配置选项（每个任务独立）
+    pub options: Option<RenderOptions>,
+}
+
+/// 批量渲染选项
+#[napi(object)]
+pub struct BatchRenderOptions {
+    /// 读取线程数，也是最多提前读入内存的任务数（默认为 CPU 核数，最多 4）
+    pub concurrency: Option<u32>,
+}
+
+/// 批量渲染的结果
+#[napi(object)]
+pub struct BatchRenderResult {
+    /// 各任务的渲染结果，与 jobs 顺序一致；单个任务失败不影响其他任务
+    pub results: Vec<RenderResult>,
+    /// 成功的任务数
+    pub succeeded: u32,
+    /// 失败的任务数（选项无效、读取或加载失败等）
+    pub failed: u32,
+    /// 成功渲染的页数（所有任务之和）
+    pub rendered_pages: u32,
+    /// 读取文件耗时之和（毫秒；读取与渲染重叠进行，不计入 totalTime 的关键路径）
+    pub load_time: u32,
+    /// 各页渲染耗时之和（毫秒）
+    pub render_time: u32,
+    /// 各页编码耗时之和（毫秒）
+    pub encode_time: u32,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+}
+
+/// 在一次调用中渲染多个文档
+///
+/// 适合一次处理大量小 PDF（如缩略图服务）：所有任务在原生侧处理，PDFium 只初始化一次，
+/// 固定数量的读取线程提前读取文件，省去逐个调用的调度和 JS↔Rust 往返开销。
+/// 结果与 jobs 顺序一致，单个任务的选项无效或渲染失败只体现在该任务的结果中。
+/// Buffer 数据会先复制一份，调用返回后即可释放或修改。
+#[napi(
+    ts_args_type = "jobs: Array<BatchRenderJob>, options?: BatchRenderOptions | undefined | null",
+    ts_return_type = "Promise<BatchRenderResult>"
+)]
+pub fn render_batch(env: Env, jobs: Vec<BatchRenderJob>, options: Option<BatchRenderOptions>) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let default_concurrency = std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(1).min(4);
+    let concurrency = options.and_then(|o| o.concurrency).unwrap_or(default_concurrency).clamp(1, 64);
+
+    // 选项或页码无效的任务不进入批量渲染，直接以失败返回
+    struct Prepared {
+        job: std::result::Result<batch::BatchJob, String>,
+        correlation_id: Option<String>,
+        resolved_config: Option<String>,
+        config_hash: Option<String>,
+    }
+    let prepared: Vec<Prepared> = jobs
+        .into_iter()
+        .map(|job| {
+            let opts = match resolve_options(job.options) {
+                Ok(opts) => opts,
+                Err(e) => {
+                    return Prepared { job: Err(e.reason), correlation_id: None, resolved_config: None, config_hash: None };
+                }
+            };
+            let config = build_config(&opts);
+            let (resolved_config, config_hash) = config_echo(&config);
+            let source = match job.source {
+                Either::A(path) => scheduler::DocumentSource::File(path),
+                Either::B(buffer) => scheduler::DocumentSource::Bytes(buffer.to_vec()),
+            };
+            let job = page_selection(job.page_nums).map_err(|e| e.reason).map(|pages| batch::BatchJob {
+                source,
+                pages,
+                config,
+                meter: usage_meter(&opts),
+            });
+            Prepared { job, correlation_id: opts.correlation_id, resolved_config, config_hash }
+        })
+        .collect();
+    let task = context::instance(&env)?.lifecycle.begin_task();
+
+    env.spawn_future(async move {
+        let (jobs, prepared): (Vec<_>, Vec<_>) = prepared
+            .into_iter()
+            .map(|p| match p.job {
+                Ok(job) => (Some(job), (None, p.correlation_id, p.resolved_config, p.config_hash)),
+                Err(e) => (None, (Some(e), p.correlation_id, p.resolved_config, p.config_hash)),
+            })
+            .unzip();
+        let meters: Vec<_> = jobs.iter().map(|job| job.as_ref().and_then(|job| job.meter.clone())).collect();
+        let job_count = jobs.iter().flatten().count();
+        let outcomes = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            batch::run(jobs.into_iter().flatten().collect(), concurrency as usize)
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let mut outcomes = match outcomes {
+            Ok(outcomes) => outcomes.into_iter().map(Ok).collect::<Vec<_>>().into_iter(),
+            // PDFium 不可用或正在关闭：所有任务以同一原因失败
+            Err(e) => std::iter::repeat_n(e, job_count).map(Err).collect::<Vec<_>>().into_iter(),
+        };
+        let mut batch_result = BatchRenderResult {
+            results: Vec::with_capacity(prepared.len()),
+            succeeded: 0,
+            failed: 0,
+            rendered_pages: 0,
+            load_time: 0,
+            render_time: 0,
+            encode_time: 0,
+            total_time: 0,
+        };
+        for ((error, correlation_id, resolved_config, config_hash), meter) in prepared.into_iter().zip(meters) {
+            let (result, job_time) = match error {
+                Some(e) => (Err(e), 0),
+                None => match outcomes.next() {
+                    Some(Ok(outcome)) => {
+                        batch_result.load_time += outcome.load_time.as_millis() as u32;
+                        (outcome.result, (outcome.load_time + outcome.render_time).as_millis() as u32)
+                    }
+                    Some(Err(e)) => (Err(e), 0),
+                    None => (Err("No result".to_string()), 0),
+                },
+            };
+            let resource_usage = meter.map(|meter| meter.usage().into());
+            let result = match result {
+                Ok((num_pages, pages, password_index)) => {
+                    batch_result.succeeded += 1;
+                    for page in pages.iter().filter(|page| page.success) {
+                        batch_result.rendered_pages += 1;
+                        batch_result.render_time += page.render_time;
+                        batch_result.encode_time += page.encode_time;
+                    }
+                    RenderResult {
+                        success: true,
+                        error: None,
+                        num_pages,
+                        cancelled: was_cancelled(&pages),
+                        pages,
+                        total_time: job_time,
+                        correlation_id,
+                        resolved_config,
+                        config_hash,
+                        password_index,
+                        resource_usage,
+                    }
+                }
+                Err(e) => {
+                    batch_result.failed += 1;
+                    RenderResult {
+                        success: false,
+                        error: Some(e),
+                        num_pages: 0,
+                        pages: vec![],
+                        total_time: job_time,
+                        correlation_id,
+                        resolved_config,
+                        config_hash,
+                        password_index: None,
+                        cancelled: false,
+                        resource_usage,
+                    }
+                }
+            };
+            batch_result.results.push(result);
+        }
+        batch_result.total_time = start_time.elapsed().as_millis() as u32;
+        Ok(batch_result)
+    })
+}
+
+/// 写入文件的单页结果
+#[napi(object)]
+pub struct PageFileResult {
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 图像文件路径（渲染或写入失败、分块页面时为空）
+    pub path: Option<String>,
+    /// 分块文件路径（oversizeStrategy 为 tile 且页面超出尺寸上限时，行优先）
+    pub tile_paths: Option<Vec<String>>,
+    /// 写入的字节数（分块页面为所有分块之和）
+    pub size: i64,
+    /// 图像宽度
+    pub width: u32,
+    /// 图像高度
+    pub height: u32,
+    /// 实际输出格式，也是文件扩展名
+    pub format: String,
+    /// 是否成功（渲染、编码和写入都成功）
+    pub success: bool,
+    /// 错误信息（如果失败）
+    pub error: Option<String>,
+    /// 失败类别，写入文件失败为 OUTPUT_FAILED，其余同 PageResult.errorCode
+    pub error_code: Option<String>,
+    /// 是否跳过（见 PageResult.skipped）
+    pub skipped: bool,
+    /// 渲染耗时（毫秒）
+    pub render_time: u32,
+    /// 编码耗时（毫秒）
+    pub encode_time: u32,
+    /// 写入文件耗时（毫秒）
+    pub write_time: u32,
+}
+
+/// 渲染到文件的结果
+#[napi(object)]
+pub struct FileRenderResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页的写入结果
+    pub pages: Vec<PageFileResult>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+    /// 渲染是否被取消（options.cancelToken），取消前完成的页面已写入文件
+    pub cancelled: bool,
+}
+
+/// 把 PDF 页面直接渲染到输出目录，不经过 Node Buffer
+///
+/// 每页编码完成后立即在后台线程写入文件，图像数据不会返回 JS，
+/// 适合结果最终都要落盘的批量转换任务。输出目录不存在时自动创建，同名文件会被覆盖。
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）
+/// * `output_dir` - 输出目录
+/// * `filename_pattern` - 文件名模板（默认 `page-%d`），`%d` 替换为页码，
+///   `%0Nd` 补零到 N 位；扩展名按实际输出格式追加
+/// * `options` - 渲染配置选项
+///
+/// # Returns
+/// 每页的文件路径、大小和耗时
+#[napi(
+    ts_args_type = "filePath: string, pageNums: Array<number> | string | PageRange, outputDir: string, filenamePattern?: string | undefined | null, options?: RenderOptions | undefined | null",
+    ts_return_type = "Promise<FileRenderResult>"
+)]
+pub fn render_pages_to_files(
+    env: Env,
+    file_path: String,
+    page_nums: PagesArg,
+    output_dir: String,
+    filename_pattern: Option<String>,
+    options: Option<RenderOptions>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let correlation_id = opts.correlation_id;
+    let task = context::instance(&env)?.lifecycle.begin_task();
+    let pattern = filename_pattern.unwrap_or_else(|| output_files::DEFAULT_PATTERN.to_string());
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            output_files::validate_pattern(&pattern)?;
+            let dir = std::path::PathBuf::from(&output_dir);
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create output directory {}: {}", output_dir, e))?;
+
+            let written = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+            let sink = std::sync::Arc::clone(&written);
+            let on_page: PageCallback = std::sync::Arc::new(move |page: PageResult| {
+                let outcome = output_files::write_page(&dir, &pattern, &page);
+                sink.lock().unwrap().insert(page.page_num, outcome);
+            });
+
+            let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+            let renderer = PdfRenderer::new(&pdfium, config).with_page_callback(Some(on_page));
+            let (num_pages, pages, _) = renderer.render_from_file(&file_path, &pages)?;
+            drop(renderer);
+            let cancelled = was_cancelled(&pages);
+
+            let mut written = std::mem::take(&mut *written.lock().unwrap());
+            let pages: Vec<PageFileResult> = pages
+                .into_iter()
+                .map(|page| {
+                    let outcome = written.remove(&page.page_num).unwrap_or_default();
+                    let write_failed = outcome.error.is_some();
+                    PageFileResult {
+                        page_num: page.page_num,
+                        path: outcome.path,
+                        tile_paths: outcome.tile_paths,
+                        size: outcome.bytes as i64,
+                        width: page.width,
+                        height: page.height,
+                        format: page.format,
+                        success: page.success && !write_failed,
+                        error: outcome.error.or(page.error),
+                        error_code: if write_failed { ErrorCode::OutputFailed.code() } else { page.error_code },
+                        skipped: page.skipped,
+                        render_time: page.render_time,
+                        encode_time: page.encode_time,
+                        write_time: outcome.write_time,
+                    }
+                })
+                .collect();
+            Ok::<_, String>((num_pages, pages, cancelled))
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        Ok(match result {
+            Ok((num_pages, pages, cancelled)) => FileRenderResult {
+                success: true,
+                error: None,
+                num_pages,
+                cancelled,
+                pages,
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            },
+            Err(e) => FileRenderResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                cancelled: false,
+            },
+        })
+    })
+}
+
+/// 多页 TIFF 中单页的结果
+#[napi(object)]
+pub struct TiffPageResult {
+    pub page_num: u32,
+    /// 是否写入了 TIFF（失败的页面不写入）
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// 渲染耗时（毫秒）
+    pub render_time: u32,
+    /// 压缩并写入 TIFF 的耗时（毫秒）
+    pub encode_time: u32,
+}
+
+/// 多页 TIFF 渲染结果
+#[napi(object)]
+pub struct MultiPageTiffResult {
+    pub success: bool,
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// TIFF 数据（指定了 outputPath 时为空）
+    pub buffer: Option<Buffer>,
+    /// 写入的文件路径（指定了 outputPath 时）
+    pub output_path: Option<String>,
+    /// TIFF 字节数
+    pub size: i64,
+    /// 每页的结果（按请求顺序，也是 TIFF 中的页面顺序）
+    pub pages: Vec<TiffPageResult>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 把多个页面渲染进同一个多页 TIFF（传真、归档）
+///
+/// 每页按正常的尺寸选项渲染，`tiffColor` 决定压缩方式：`bilevel` 使用 CCITT G4
+/// （二值化方式沿用 `bilevel` 选项），`gray` / `color` 使用 LZW。未指定时设置了
+/// `bilevel` 选项为 `bilevel`，否则为 `color`。渲染失败的页面不写入 TIFF，
+/// 没有任何页面成功时整体失败。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项
+/// * `output_path` - 写入的文件路径；不传时在结果的 buffer 中返回
+#[napi(
+    ts_args_type = "input: string | Buffer, pageNums: Array<number>, options?: RenderOptions | undefined | null, outputPath?: string | undefined | null",
+    ts_return_type = "Promise<MultiPageTiffResult>"
+)]
+pub fn render_multipage_tiff(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+    output_path: Option<String>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let color = match opts.tiff_color.as_deref() {
+        Some(name) => tiff::TiffColor::from_name(name)
+            .ok_or_else(|| Error::from_reason(format!("Invalid tiffColor: {} (expected bilevel, gray or color)", name)))?,
+        None if opts.bilevel.is_some() => tiff::TiffColor::Bilevel,
+        None => tiff::TiffColor::Color,
+    };
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let correlation_id = opts.correlation_id;
+    let task = context::instance(&env)?.lifecycle.begin_task();
+    let source = match input {
+        Either::A(path) => scheduler::DocumentSource::File(path),
+        Either::B(buffer) => scheduler::DocumentSource::Bytes(buffer.to_vec()),
+    };
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+            let renderer = PdfRenderer::new(&pdfium, config);
+            let (document, fingerprint) = match &source {
+                scheduler::DocumentSource::File(path) => {
+                    let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                    renderer.check_input(file_size, &page_nums)?;
+                    let (document, _) = renderer
+                        .load_document_from_file(path)
+                        .map_err(|e| renderer::load_error("Failed to load PDF from file", &e))?;
+                    (document, flight_recorder::fingerprint_file(path))
+                }
+                scheduler::DocumentSource::Bytes(bytes) => {
+                    renderer.check_input(bytes.len() as u64, &page_nums)?;
+                    let (document, _) = renderer
+                        .load_document(bytes)
+                        .map_err(|e| renderer::load_error("Failed to load PDF", &e))?;
+                    (document, flight_recorder::fingerprint_bytes(bytes))
+                }
+            };
+            let (num_pages, data, pages) = renderer.render_multipage_tiff(&document, &page_nums, color, &fingerprint)?;
+            let size = data.len() as i64;
+            let buffer = match &output_path {
+                Some(path) => {
+                    std::fs::write(path, &data).map_err(|e| format!("Failed to write TIFF to {}: {}", path, e))?;
+                    None
+                }
+                None => Some(renderer::into_js_buffer(data)),
+            };
+            Ok::<_, String>((num_pages, buffer, size, pages, output_path))
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        Ok(match result {
+            Ok((num_pages, buffer, size, pages, output_path)) => MultiPageTiffResult {
+                success: true,
+                error: None,
+                num_pages,
+                buffer,
+                output_path,
+                size,
+                pages,
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            },
+            Err(e) => MultiPageTiffResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                buffer: None,
+                output_path: None,
+                size: 0,
+                pages: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            },
+        })
+    })
+}
+
+/// 把 JS 的 onPage 回调包装成逐页交付结果的回调
+///
+/// 每页渲染、编码完成后立即交给 JS（开启 concurrency 时按完成顺序，不一定按页码），
+/// 界面不必等整批完成就能显示第一页。此时 Promise 的结果仍包含每页的统计，
+/// 但 buffer、tiles、alternates 为空，图像数据只通过回调交付一次。
+fn page_callback(on_page: Option<JsFunction>) -> Result<Option<PageCallback>> {
+    let Some(on_page) = on_page else { return Ok(None) };
+    let tsfn: ThreadsafeFunction<PageResult, ErrorStrategy::CalleeHandled> =
+        on_page.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<PageResult>| Ok(vec![ctx.value]))?;
+    Ok(Some(std::sync::Arc::new(move |page: PageResult| {
+        tsfn.call(Ok(page), ThreadsafeFunctionCallMode::NonBlocking);
+    })))
+}
+
+/// 把 JS 的 onDocument 回调包装成线程安全函数，文档加载完成后在渲染线程上调用
+fn document_callback(
+    on_document: Option<JsFunction>,
+) -> Result<Option<ThreadsafeFunction<StreamDocumentInfo, ErrorStrategy::CalleeHandled>>> {
+    on_document
+        .map(|on_document| {
+            on_document.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<StreamDocumentInfo>| Ok(vec![ctx.value]))
+        })
+        .transpose()
+}
+
+/// 没有 options 参数的接口（页数、文字统计、SVG）接受单个密码或候选密码数组
+fn password_list(passwords: Option<Either<String, Vec<String>>>) -> Vec<String> {
+    match passwords {
+        None => Vec::new(),
+        Some(Either::A(password)) => vec![password],
+        Some(Either::B(passwords)) => passwords,
+    }
+}
+
+/// `{ from, to }` 形式的页码范围
+#[napi(object)]
+pub struct PageRange {
+    /// 起始页（从 1 开始，默认 1）
+    pub from: Option<u32>,
+    /// 结束页（包含），为空表示到最后一页
+    pub to: Option<u32>,
+}
+
+/// 渲染接口的页码参数：页码数组、范围字符串（`"1-5,8,10-"`、`"all"`）或 `{ from, to }`
+type PagesArg = Either3<Vec<u32>, String, PageRange>;
+
+fn page_selection(pages: PagesArg) -> Result<PageSelection> {
+    match pages {
+        Either3::A(pages) => Ok(PageSelection::List(pages)),
+        Either3::B(spec) => PageSelection::parse(&spec),
+        Either3::C(range) => PageSelection::range(range.from, range.to),
+    }
+    .map_err(|e| Error::from_reason(format!("{}: {}", ErrorCode::InvalidOptions.as_str(), e)))
+}
+
+/// 按文档页数展开页码范围（与渲染接口的页码参数规则相同）
+///
+/// 先取页数再计算页码的调用方（如按页分发到工作线程）用它得到与原生渲染一致的页码数组。
+///
+/// # Arguments
+/// * `pages` - 页码数组、范围字符串（`"1-5,8,10-"`、`"all"`）或 `{ from, to }`
+/// * `num_pages` - 文档页数
+///
+/// # Returns
+/// 页码数组（范围中重复的页码只保留一次）；范围格式错误或超出页数时抛出 INVALID_OPTIONS 错误
+#[napi(ts_args_type = "pages: Array<number> | string | PageRange, numPages: number")]
+pub fn resolve_page_ranges(pages: PagesArg, num_pages: u32) -> Result<Vec<u32>> {
+    page_selection(pages)?
+        .resolve(num_pages)
+        .map_err(|e| Error::from_reason(format!("{}: {}", ErrorCode::InvalidOptions.as_str(), e)))
+}
+
+/// 从文件路径获取 PDF 页数（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// PDF 的总页数
+#[napi]
+pub fn get_page_count_from_file(file_path: String, passwords: Option<Either<String, Vec<String>>>) -> Result<u32> {
+    let pdfium = create_pdfium()?;
+    let passwords = password_list(passwords);
+    
+    let (document, _) = renderer::try_passwords(&passwords, |password| {
+        pdfium.load_pdf_from_file(&file_path, password)
+    })
+    .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+    
+    Ok(document.pages().len() as u32)
+}
+
+/// 获取 PDF 页数（不渲染）
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// PDF 的总页数
+#[napi]
+pub fn get_page_count(pdf_buffer: Buffer, passwords: Option<Either<String, Vec<String>>>) -> Result<u32> {
+    let pdfium = create_pdfium()?;
+    let passwords = password_list(passwords);
+    
+    let (document, _) = renderer::try_passwords(&passwords, |password| {
+        pdfium.load_pdf_from_byte_slice(&pdf_buffer, password)
+    })
+    .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+    
+    Ok(document.pages().len() as u32)
+}
+
+/// 获取页面的粗粒度版面信息（不渲染）
+///
+/// 基于 PDFium 的文字和对象几何信息，返回每页的文本块和图片区域。
+/// 坐标与相同选项下 `renderPages` 输出的图像一致，可直接用于裁剪。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 要分析的页码数组（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 每页的版面分析结果
+#[napi]
+pub fn get_page_layout(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageLayout>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_nums
+        .iter()
+        .map(|&page_num| renderer.analyze_page_layout(&document, page_num))
+        .collect())
+}
+
+/// 从文件路径获取页面的粗粒度版面信息（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 要分析的页码数组（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 每页的版面分析结果
+#[napi]
+pub fn get_page_layout_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageLayout>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_nums
+        .iter()
+        .map(|&page_num| renderer.analyze_page_layout(&document, page_num))
+        .collect())
+}
+
+/// 获取页面中带标签的图形、替代文字和题注（不渲染）
+///
+/// 读取标签 PDF 的结构树，返回 Figure 等图形元素的替代文字，以及与之关联的题注文字，
+/// 供无障碍流程给生成的页面图像自动附加替代文字。区域坐标与相同选项下 `renderPages`
+/// 输出的图像一致。只读取页面结构树，不解析文档级 XMP 元数据。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 要读取的页码数组（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 每页的图形信息
+#[napi]
+pub fn get_page_figures(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageFigures>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_nums
+        .iter()
+        .map(|&page_num| renderer.page_figures(&document, page_num))
+        .collect())
+}
+
+/// 从文件路径获取页面中带标签的图形、替代文字和题注（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 要读取的页码数组（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 每页的图形信息
+#[napi]
+pub fn get_page_figures_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageFigures>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_nums
+        .iter()
+        .map(|&page_num| renderer.page_figures(&document, page_num))
+        .collect())
+}
+
+/// 一次获取所有页面的尺寸、旋转、标签以及是否有文字和图片（不渲染）
+///
+/// 供查看器在打开文档时一次性取得整个文档的页面信息，再按需渲染；
+/// 结果同时汇总扫描件页数比例，可据此在 OCR 和文字提取之间选择处理流程。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `options` - 渲染配置选项（只使用资源限制）
+///
+/// # Returns
+/// 文档页面概况
+#[napi]
+pub fn get_document_layout(env: Env, pdf_buffer: Buffer, options: Option<RenderOptions>) -> Result<DocumentLayout> {
+    let start_time = std::time::Instant::now();
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(document_layout(&renderer, &document, start_time))
+}
+
+/// 从文件路径一次获取所有页面的概况（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `options` - 渲染配置选项（只使用资源限制）
+///
+/// # Returns
+/// 文档页面概况
+#[napi]
+pub fn get_document_layout_from_file(env: Env, file_path: String, options: Option<RenderOptions>) -> Result<DocumentLayout> {
+    let start_time = std::time::Instant::now();
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(document_layout(&renderer, &document, start_time))
+}
+
+fn document_layout(
+    renderer: &PdfRenderer,
+    document: &pdfium_render::prelude::PdfDocument,
+    start_time: std::time::Instant,
+) -> DocumentLayout {
+    let num_pages = document.pages().len() as u32;
+    let pages: Vec<PageOverview> = (1..=num_pages).map(|page_num| renderer.page_overview(document, page_num)).collect();
+    let analyzed = pages.iter().filter(|page| page.success).count() as u32;
+    let scanned_pages = pages.iter().filter(|page| page.is_scan).count() as u32;
+    let scan_ratio = if analyzed == 0 { 0.0 } else { scanned_pages as f64 / analyzed as f64 };
+    DocumentLayout {
+        num_pages,
+        pages,
+        scanned_pages,
+        scan_ratio,
+        is_scanned: analyzed > 0 && scan_ratio >= 0.5,
+        total_time: start_time.elapsed().as_millis() as u32,
+    }
+}
+
+/// 获取文档元数据（标题、作者、日期等）和每页的尺寸、旋转、标签（不渲染）
+///
+/// 查看器在渲染任何页面之前据此计算布局。与 getDocumentLayout 不同，
+/// 不检查页面内容（文字、图片、注释），大文档也能很快返回。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 文档信息
+#[napi]
+pub fn get_document_info(env: Env, pdf_buffer: Buffer, options: Option<RenderOptions>) -> Result<DocumentInfo> {
+    let start_time = std::time::Instant::now();
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(document_info(&renderer, &document, start_time))
+}
+
+/// 从文件路径获取文档元数据和每页的尺寸（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 文档信息
+#[napi]
+pub fn get_document_info_from_file(env: Env, file_path: String, options: Option<RenderOptions>) -> Result<DocumentInfo> {
+    let start_time = std::time::Instant::now();
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(document_info(&renderer, &document, start_time))
+}
+
+/// 列出文档中的附件（不渲染，不读取附件内容）
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 附件列表，没有附件时为空
+#[napi]
+pub fn get_attachments(env: Env, pdf_buffer: Buffer, options: Option<RenderOptions>) -> Result<Vec<AttachmentInfo>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(attachment_infos(&document))
+}
+
+/// 从文件路径列出文档中的附件（不渲染，不读取附件内容）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 附件列表，没有附件时为空
+#[napi]
+pub fn get_attachments_from_file(env: Env, file_path: String, options: Option<RenderOptions>) -> Result<Vec<AttachmentInfo>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(attachment_infos(&document))
+}
+
+/// 取出附件内容
+///
+/// 附件解压后的大小同样受 maxFileSize 限制。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `index` - 附件序号（getAttachments 返回的 index）
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 附件内容，序号无效时报错
+#[napi]
+pub fn get_attachment(env: Env, pdf_buffer: Buffer, index: u32, options: Option<RenderOptions>) -> Result<Buffer> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    attachments::data(&document, index, &renderer.config().limits)
+        .map(Buffer::from)
+        .map_err(Error::from_reason)
+}
+
+/// 从文件路径取出附件内容
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `index` - 附件序号（getAttachments 返回的 index）
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 附件内容，序号无效时报错
+#[napi]
+pub fn get_attachment_from_file(env: Env, file_path: String, index: u32, options: Option<RenderOptions>) -> Result<Buffer> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    attachments::data(&document, index, &renderer.config().limits)
+        .map(Buffer::from)
+        .map_err(Error::from_reason)
+}
+
+fn attachment_infos(document: &pdfium_render::prelude::PdfDocument) -> Vec<AttachmentInfo> {
+    attachments::list(document)
+        .into_iter()
+        .map(|attachment| AttachmentInfo {
+            index: attachment.index,
+            name: attachment.name,
+            size: attachment.size as i64,
+            mime_type: attachment.mime_type,
+            creation_date: attachment.creation_date,
+            modification_date: attachment.modification_date,
+        })
+        .collect()
+}
+
+/// 获取单页的尺寸、旋转和标签（不渲染）
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 页面信息，页码无效时报错
+#[napi]
+pub fn get_page_info(env: Env, pdf_buffer: Buffer, page_num: u32, options: Option<RenderOptions>) -> Result<PageInfo> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    single_page_info(&renderer, &document, page_num)
+}
+
+/// 从文件路径获取单页的尺寸、旋转和标签（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 页面信息，页码无效时报错
+#[napi]
+pub fn get_page_info_from_file(env: Env, file_path: String, page_num: u32, options: Option<RenderOptions>) -> Result<PageInfo> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    single_page_info(&renderer, &document, page_num)
+}
+
+/// 获取单页文字（词或文字段）及其位置（不渲染）
+///
+/// 每个词同时给出页面坐标（点）和输出图像坐标（像素），像素坐标与相同选项下 `renderPages`
+/// 输出的图像一致，可直接用于在渲染结果上高亮搜索命中或叠加可选中的文字层。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸，`textGranularity` 控制粒度）
+///
+/// # Returns
+/// 页面文字位置，页码无效时报错
+#[napi]
+pub fn get_page_text_rects(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<PageTextRects> {
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[page_num])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .page_text_rects(&document, page_num, text_granularity(&opts))
+        .map_err(Error::from_reason)
+}
+
+/// 从文件路径获取单页文字（词或文字段）及其位置（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸，`textGranularity` 控制粒度）
+///
+/// # Returns
+/// 页面文字位置，页码无效时报错
+#[napi]
+pub fn get_page_text_rects_from_file(
+    env: Env,
+    file_path: String,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<PageTextRects> {
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[page_num])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .page_text_rects(&document, page_num, text_granularity(&opts))
+        .map_err(Error::from_reason)
+}
+
+/// 获取单页的链接和其他注释及其位置（不渲染）
+///
+/// 链接返回外部地址或本文档内的目标页码，区域坐标与相同选项下 `renderPages` 输出的图像一致，
+/// 网页查看器可以直接在渲染图上放置可点击区域。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 页面注释，页码无效时报错
+#[napi]
+pub fn get_page_annotations(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<PageAnnotations> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[page_num])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .page_annotations(&document, page_num)
+        .map_err(Error::from_reason)
+}
+
+/// 从文件路径获取单页的链接和其他注释及其位置（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 页面注释，页码无效时报错
+#[napi]
+pub fn get_page_annotations_from_file(
+    env: Env,
+    file_path: String,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<PageAnnotations> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[page_num])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .page_annotations(&document, page_num)
+        .map_err(Error::from_reason)
+}
+
+/// 获取表单字段的名称、类型、当前值和位置（不渲染）
+///
+/// 区域坐标与相同选项下 `renderPages` 输出的图像一致。渲染时表单的处理方式由 formMode 控制。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_num` - 页码（从 1 开始），为空时列出所有页的字段
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 表单字段，按页码和页面中的顺序排列；页码无效时报错
+#[napi]
+pub fn get_form_fields(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_num: Option<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<FormField>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .form_fields(&document, page_num)
+        .map_err(Error::from_reason)
+}
+
+/// 从文件路径获取表单字段的名称、类型、当前值和位置（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_num` - 页码（从 1 开始），为空时列出所有页的字段
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 表单字段，按页码和页面中的顺序排列；页码无效时报错
+#[napi]
+pub fn get_form_fields_from_file(
+    env: Env,
+    file_path: String,
+    page_num: Option<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<FormField>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    renderer
+        .form_fields(&document, page_num)
+        .map_err(Error::from_reason)
+}
+
+fn text_granularity(opts: &RenderOptions) -> TextGranularity {
+    TextGranularity::from_str(opts.text_granularity.as_deref().unwrap_or("word"))
+}
+
+fn document_info(
+    renderer: &PdfRenderer,
+    document: &pdfium_render::prelude::PdfDocument,
+    start_time: std::time::Instant,
+) -> DocumentInfo {
+    let num_pages = document.pages().len() as u32;
+    let metadata = doc_info::read(document);
+    DocumentInfo {
+        num_pages,
+        title: metadata.title,
+        author: metadata.author,
+        subject: metadata.subject,
+        keywords: metadata.keywords,
+        creator: metadata.creator,
+        producer: metadata.producer,
+        creation_date: metadata.creation_date,
+        modification_date: metadata.modification_date,
+        pdf_version: metadata.pdf_version,
+        pages: (1..=num_pages).map(|page_num| renderer.page_info(document, page_num)).collect(),
+        total_time: start_time.elapsed().as_millis() as u32,
+    }
+}
+
+fn single_page_info(
+    renderer: &PdfRenderer,
+    document: &pdfium_render::prelude::PdfDocument,
+    page_num: u32,
+) -> Result<PageInfo> {
+    let info = renderer.page_info(document, page_num);
+    match info.error {
+        Some(error) => Err(Error::from_reason(error)),
+        None => Ok(info),
+    }
+}
+
+/// 列出页面上的图片及其像素尺寸和放置分辨率（不渲染、不解码图片）
+///
+/// 上传质量检查可以在渲染前拒绝扫描分辨率过低（如低于 150 DPI）的文档，
+/// 不必先按大尺寸渲染再判断。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 每页的图片信息
+#[napi]
+pub fn get_page_images(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageImages>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_images(&renderer, &document, page_nums))
+}
+
+/// 从文件路径列出页面上的图片及其放置分辨率（不渲染、不解码图片）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 每页的图片信息
+#[napi]
+pub fn get_page_images_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<Vec<PageImages>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(page_images(&renderer, &document, page_nums))
+}
+
+fn page_images(
+    renderer: &PdfRenderer,
+    document: &pdfium_render::prelude::PdfDocument,
+    page_nums: Vec<u32>,
+) -> Vec<PageImages> {
+    let page_nums = if page_nums.is_empty() {
+        (1..=document.pages().len() as u32).collect()
+    } else {
+        page_nums
+    };
+    page_nums
+        .into_iter()
+        .map(|page_num| renderer.page_images(document, page_num))
+        .collect()
+}
+
+/// 统计页面文字（不渲染、不返回文字内容）
+///
+/// 返回每页的字符数、词数以及是否有可提取的文字，用于在索引和 OCR 之间做选择。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// 每页的文字统计
+#[napi]
+pub fn get_text_stats(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    passwords: Option<Either<String, Vec<String>>>,
+) -> Result<Vec<PageTextStats>> {
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let config = RenderConfig {
+        passwords: password_list(passwords),
+        ..RenderConfig::default()
+    };
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(text_stats(&renderer, &document, page_nums))
+}
+
+/// 从文件路径统计页面文字（不渲染、不返回文字内容）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// 每页的文字统计
+#[napi]
+pub fn get_text_stats_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    passwords: Option<Either<String, Vec<String>>>,
+) -> Result<Vec<PageTextStats>> {
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let config = RenderConfig {
+        passwords: password_list(passwords),
+        ..RenderConfig::default()
+    };
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &page_nums)
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(text_stats(&renderer, &document, page_nums))
+}
+
+fn text_stats(
+    renderer: &PdfRenderer,
+    document: &pdfium_render::prelude::PdfDocument,
+    page_nums: Vec<u32>,
+) -> Vec<PageTextStats> {
+    let page_nums = if page_nums.is_empty() {
+        (1..=document.pages().len() as u32).collect()
+    } else {
+        page_nums
+    };
+    page_nums
+        .iter()
+        .map(|&page_num| renderer.page_text_stats(document, page_num))
+        .collect()
+}
+
+/// 渲染跨页（对开页）
+///
+/// 把相邻的两页按书籍左右页拼成一张图片，整体宽度约为 `targetWidth`，
+/// 两页缩放到相同高度。拼接在原生层完成，避免在 JS 中解码再重新编码。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），返回包含这些页面的跨页；空数组表示全部页面
+/// * `options` - 渲染配置选项（`spreadCover` 控制封面是否单独成页）
+///
+/// # Returns
+/// 每个跨页的渲染结果
+#[napi]
+pub fn render_spreads(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<SpreadRenderResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+
+    let result = renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document(&pdf_buffer)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .and_then(|document| {
+            renderer.render_spreads(&document, &page_nums, opts.spread_cover.unwrap_or(true))
+        });
+
+    Ok(spread_render_result(result, start_time, opts.correlation_id, renderer.config()))
+}
+
+/// 从文件路径渲染跨页（对开页）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），返回包含这些页面的跨页；空数组表示全部页面
+/// * `options` - 渲染配置选项（`spreadCover` 控制封面是否单独成页）
+///
+/// # Returns
+/// 每个跨页的渲染结果
+#[napi]
+pub fn render_spreads_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<SpreadRenderResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+
+    let result = renderer
+        .check_input(file_size, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document_from_file(&file_path)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF from file", &e))
+        })
+        .and_then(|document| {
+            renderer.render_spreads(&document, &page_nums, opts.spread_cover.unwrap_or(true))
+        });
+
+    Ok(spread_render_result(result, start_time, opts.correlation_id, renderer.config()))
+}
+
+fn spread_render_result(
+    result: std::result::Result<(u32, Vec<SpreadResult>), String>,
+    start_time: std::time::Instant,
+    correlation_id: Option<String>,
+    config: &RenderConfig,
+) -> SpreadRenderResult {
+    let (resolved_config, config_hash) = config_echo(config);
+    let total_time = start_time.elapsed().as_millis() as u32;
+    match result {
+        Ok((num_pages, spreads)) => SpreadRenderResult {
+            success: true,
+            error: None,
+            num_pages,
+            spreads,
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => SpreadRenderResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            spreads: vec![],
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    }
+}
+
+/// 渲染缩略图拼图（sprite sheet）
+///
+/// 每页在边长为 `cell_size` 的正方形单元格内等比缩放并居中，所有单元格拼成一张图片，
+/// 按 `format` 选项编码。查看器的进度条预览只需加载一张图片，比逐页生成小图便宜得多。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `cell_size` - 单元格边长（像素）
+/// * `options` - 渲染配置选项（输出格式、`spriteColumns` 等）
+///
+/// # Returns
+/// 拼图和每页所在的区域
+#[napi]
+pub fn render_thumbnail_strip(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    cell_size: u32,
+    options: Option<RenderOptions>,
+) -> Result<ThumbnailStripResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+
+    let result = renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document(&pdf_buffer)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .and_then(|document| {
+            let num_pages = document.pages().len() as u32;
+            renderer
+                .render_sprite_sheet(&document, &page_nums, cell_size, opts.sprite_columns)
+                .map(|sheet| (num_pages, sheet))
+        });
+
+    Ok(thumbnail_strip_result(result, cell_size, start_time, opts.correlation_id, renderer.config()))
+}
+
+/// 从文件路径渲染缩略图拼图（sprite sheet）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `cell_size` - 单元格边长（像素）
+/// * `options` - 渲染配置选项（输出格式、`spriteColumns` 等）
+///
+/// # Returns
+/// 拼图和每页所在的区域
+#[napi]
+pub fn render_thumbnail_strip_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    cell_size: u32,
+    options: Option<RenderOptions>,
+) -> Result<ThumbnailStripResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+
+    let result = renderer
+        .check_input(file_size, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document_from_file(&file_path)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF from file", &e))
+        })
+        .and_then(|document| {
+            let num_pages = document.pages().len() as u32;
+            renderer
+                .render_sprite_sheet(&document, &page_nums, cell_size, opts.sprite_columns)
+                .map(|sheet| (num_pages, sheet))
+        });
+
+    Ok(thumbnail_strip_result(result, cell_size, start_time, opts.correlation_id, renderer.config()))
+}
+
+type SpriteSheet = (Vec<u8>, sprite::SpriteLayout, Vec<SpriteCell>);
+
+fn thumbnail_strip_result(
+    result: std::result::Result<(u32, SpriteSheet), String>,
+    cell_size: u32,
+    start_time: std::time::Instant,
+    correlation_id: Option<String>,
+    config: &RenderConfig,
+) -> ThumbnailStripResult {
+    let (resolved_config, config_hash) = config_echo(config);
+    let total_time = start_time.elapsed().as_millis() as u32;
+    match result {
+        Ok((num_pages, (buffer, layout, cells))) => ThumbnailStripResult {
+            success: true,
+            error: None,
+            num_pages,
+            buffer: renderer::into_js_buffer(buffer),
+            width: layout.width(),
+            height: layout.height(),
+            columns: layout.columns,
+            rows: layout.rows,
+            cell_size,
+            cells,
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => ThumbnailStripResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            buffer: Buffer::from(vec![]),
+            width: 0,
+            height: 0,
+            columns: 0,
+            rows: 0,
+            cell_size,
+            cells: vec![],
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    }
+}
+
+/// 提取页面内嵌的缩略图（不渲染）
+///
+/// 部分 PDF 生成器会为每页写入预生成的缩略图，读取它们比渲染便宜得多，
+/// 适合列表视图。没有缩略图的页面返回 `found: false`，调用方可以回退到渲染；
+/// 设置 `thumbnailFallbackWidth` 时这些页面直接按该宽度渲染（`rendered: true`）。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（用于输出格式和 `thumbnailPassthrough`）
+///
+/// # Returns
+/// 每页的缩略图
+#[napi]
+pub fn get_embedded_thumbnails(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<ThumbnailResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+
+    let result = renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document(&pdf_buffer)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .map(|document| renderer.extract_thumbnails(&document, &page_nums));
+
+    Ok(thumbnail_result(result, start_time, correlation_id, renderer.config()))
+}
+
+/// 从文件路径提取页面内嵌的缩略图（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（用于输出格式和 `thumbnailPassthrough`）
+///
+/// # Returns
+/// 每页的缩略图
+#[napi]
+pub fn get_embedded_thumbnails_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<ThumbnailResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+
+    let result = renderer
+        .check_input(file_size, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document_from_file(&file_path)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF from file", &e))
+        })
+        .map(|document| renderer.extract_thumbnails(&document, &page_nums));
+
+    Ok(thumbnail_result(result, start_time, correlation_id, renderer.config()))
+}
+
+fn thumbnail_result(
+    result: std::result::Result<(u32, Vec<EmbeddedThumbnail>), String>,
+    start_time: std::time::Instant,
+    correlation_id: Option<String>,
+    config: &RenderConfig,
+) -> ThumbnailResult {
+    let (resolved_config, config_hash) = config_echo(config);
+    let total_time = start_time.elapsed().as_millis() as u32;
+    match result {
+        Ok((num_pages, thumbnails)) => ThumbnailResult {
+            success: true,
+            error: None,
+            num_pages,
+            thumbnails,
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => ThumbnailResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            thumbnails: vec![],
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    }
+}
+
+/// 查找内容相同或相近的页面
+///
+/// 每页渲染成小图并计算感知哈希，哈希距离不超过 `duplicateThreshold` 的页面归为一组，
+/// 用于找出扫描件中重复上传的页面。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（`duplicateThreshold`）
+///
+/// # Returns
+/// 每页的哈希和重复页分组
+#[napi]
+pub fn find_duplicate_pages(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<DuplicatePagesResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let threshold = opts.duplicate_threshold.unwrap_or(4).min(64);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+
+    let result = renderer
+        .check_input(pdf_buffer.len() as u64, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document(&pdf_buffer)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .and_then(|document| renderer.find_duplicates(&document, &page_nums, threshold));
+
+    Ok(duplicate_pages_result(result, start_time, correlation_id, renderer.config()))
+}
+
+/// 从文件路径查找内容相同或相近的页面
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（`duplicateThreshold`）
+///
+/// # Returns
+/// 每页的哈希和重复页分组
+#[napi]
+pub fn find_duplicate_pages_from_file(
+    env: Env,
+    file_path: String,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<DuplicatePagesResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let threshold = opts.duplicate_threshold.unwrap_or(4).min(64);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+
+    let result = renderer
+        .check_input(file_size, &page_nums)
+        .and_then(|_| {
+            renderer
+                .load_document_from_file(&file_path)
+                .map(|(document, _)| document)
+                .map_err(|e| renderer::load_error("Failed to load PDF from file", &e))
+        })
+        .and_then(|document| renderer.find_duplicates(&document, &page_nums, threshold));
+
+    Ok(duplicate_pages_result(result, start_time, correlation_id, renderer.config()))
+}
+
+fn duplicate_pages_result(
+    result: std::result::Result<(u32, Vec<PageHash>, Vec<DuplicateCluster>), String>,
+    start_time: std::time::Instant,
+    correlation_id: Option<String>,
+    config: &RenderConfig,
+) -> DuplicatePagesResult {
+    let (resolved_config, config_hash) = config_echo(config);
+    let total_time = start_time.elapsed().as_millis() as u32;
+    match result {
+        Ok((num_pages, hashes, clusters)) => DuplicatePagesResult {
+            success: true,
+            error: None,
+            num_pages,
+            hashes,
+            clusters,
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => DuplicatePagesResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            hashes: vec![],
+            clusters: vec![],
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    }
+}
+
+/// 把多个 PDF 的页面拼成一个虚拟文档并渲染（异步）
+///
+/// 例如模板中的封面加上上传文件的第 1-3 页：按 `sources` 的顺序把各来源的页面
+/// 复制到一个内存中的新文档，再一次性渲染全部页面，不需要先单独合并成文件。
+/// 所有来源共用 `options` 中的密码；任一来源打开失败或页码无效时整体失败。
+///
+/// # Arguments
+/// * `sources` - 来源文档及各自取用的页码
+/// * `options` - 渲染配置选项
+///
+/// # Returns
+/// Promise<ComposedRenderResult>
+#[napi(ts_return_type = "Promise<ComposedRenderResult>")]
+pub fn render_composed_pages(env: Env, sources: Vec<ComposeSource>, options: Option<RenderOptions>) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let task = context::instance(&env)?.lifecycle.begin_task();
+
+    // Buffer 不能带到后台线程，先复制
+    let sources: Vec<(scheduler::DocumentSource, Vec<u32>)> = sources
+        .into_iter()
+        .map(|source| {
+            let input = match source.input {
+                Either::A(path) => scheduler::DocumentSource::File(path),
+                Either::B(buffer) => scheduler::DocumentSource::Bytes(buffer.to_vec()),
+            };
+            (input, source.pages.unwrap_or_default())
+        })
+        .collect();
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            for (source, _) in &sources {
+                let size = match source {
+                    scheduler::DocumentSource::File(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+                    scheduler::DocumentSource::Bytes(bytes) => bytes.len() as u64,
+                };
+                config.limits.check_file_size(size).map_err(|e| e.to_string())?;
+            }
+            let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+            let renderer = PdfRenderer::new(&pdfium, config);
+            let (document, page_sources) = renderer.compose_document(&sources)?;
+            let page_nums: Vec<u32> = (1..=page_sources.len() as u32).collect();
+            let (num_pages, pages) =
+                renderer.render_document_pages(&document, &page_nums, &format!("compose:{}", sources.len()))?;
+            Ok::<_, String>((num_pages, pages, page_sources))
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        Ok(match result {
+            Ok((num_pages, pages, page_sources)) => ComposedRenderResult {
+                success: true,
+                error: None,
+                num_pages,
+                pages,
+                page_sources: page_sources
+                    .into_iter()
+                    .map(|(source_index, page_num)| ComposedPageSource { source_index, page_num })
+                    .collect(),
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            },
+            Err(e) => ComposedRenderResult {
+                success: false,
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                page_sources: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            },
+        })
+    })
+}
+
+/// 把指定页面分别导出为单页 PDF
+///
+/// 通过 PDFium 的页面导入接口复制页面（文字、矢量图形和字体都保持原样），
+/// 适合在图片预览旁提供"只发送这一页"的 PDF 下载。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_nums` - 页码数组（从 1 开始），空数组表示全部页面
+/// * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+///
+/// # Returns
+/// 每页的单页 PDF
+#[napi(ts_args_type = "input: string | Buffer, pageNums: number[], options?: RenderOptions | undefined | null")]
+pub fn extract_pages(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<ExtractPagesResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let (resolved_config, config_hash) = config_echo(renderer.config());
+
+    let file_size = match &input {
+        Either::A(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        Either::B(buffer) => buffer.len() as u64,
+    };
+    let result = renderer
+        .check_input(file_size, &page_nums)
+        .and_then(|_| {
+            match &input {
+                Either::A(path) => renderer.load_document_from_file(path),
+                Either::B(buffer) => renderer.load_document(buffer),
+            }
+            .map(|(document, _)| document)
+            .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .map(|document| renderer.extract_pages(&document, &page_nums));
+
+    let total_time = start_time.elapsed().as_millis() as u32;
+    Ok(match result {
+        Ok((num_pages, pages)) => ExtractPagesResult {
+            success: true,
+            error: None,
+            num_pages,
+            pages,
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => ExtractPagesResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            pages: vec![],
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    })
+}
+
+/// 把选中的页面导出为一个新的 PDF
+///
+/// 通过 PDFium 的页面导入接口一次复制所有选中的页面（文字、矢量图形和字体都保持原样，
+/// 页面之间共享的资源只复制一份），新文档中的页面顺序与页码参数一致。
+/// 适合渲染前先拆分文档，不必再调用外部工具。书签、表单和文档信息不会复制。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_nums` - 页码数组（从 1 开始，空数组表示全部页面），或范围字符串、`{ from, to }`（同 `renderPages`）
+/// * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+///
+/// # Returns
+/// 新 PDF 的数据；任一页码无效时整体失败
+#[napi(
+    ts_args_type = "input: string | Buffer, pageNums: Array<number> | string | PageRange, options?: RenderOptions | undefined | null"
+)]
+pub fn extract_pages_as_pdf(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_nums: PagesArg,
+    options: Option<RenderOptions>,
+) -> Result<ExtractDocumentResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let correlation_id = opts.correlation_id.clone();
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let (resolved_config, config_hash) = config_echo(renderer.config());
+
+    let file_size = match &input {
+        Either::A(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        Either::B(buffer) => buffer.len() as u64,
+    };
+    let result = renderer
+        .check_input(file_size, pages.known_pages())
+        .and_then(|_| {
+            match &input {
+                Either::A(path) => renderer.load_document_from_file(path),
+                Either::B(buffer) => renderer.load_document(buffer),
+            }
+            .map(|(document, _)| document)
+            .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+        })
+        .and_then(|document| renderer.extract_document(&document, &pages));
+
+    let total_time = start_time.elapsed().as_millis() as u32;
+    Ok(match result {
+        Ok((num_pages, page_nums, data)) => ExtractDocumentResult {
+            success: true,
+            error: None,
+            num_pages,
+            page_nums,
+            buffer: data.into(),
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+        Err(e) => ExtractDocumentResult {
+            success: false,
+            error: Some(e),
+            num_pages: 0,
+            page_nums: vec![],
+            buffer: Buffer::from(vec![]),
+            total_time,
+            correlation_id,
+            resolved_config,
+            config_hash,
+        },
+    })
+}
+
+/// 只渲染页面的一个矩形区域
+///
+/// 截取签名框、插图等小区域时，按区域尺寸分配位图并通过变换矩阵只绘制区域内的内容，
+/// 不需要先以高倍率渲染整页再裁剪。区域超出页面的部分被裁掉；
+/// 输出格式、编码和滤镜选项与 `renderPages` 相同，尺寸类选项（targetWidth、dpi、canvas 等）不生效。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_num` - 页码（从 1 开始）
+/// * `region` - 页面上的区域（点）和缩放比例
+/// * `options` - 渲染配置选项
+///
+/// # Returns
+/// 只包含该区域图像的渲染结果（pages 中只有一页）
+#[napi(ts_args_type = "input: string | Buffer, pageNum: number, region: PageRegion, options?: RenderOptions | undefined | null")]
+pub fn render_page_region(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_num: u32,
+    region: PageRegion,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let mut config = build_config(&opts);
+    // 区域的输出尺寸由区域和缩放比例决定，不填充到固定画布
+    config.canvas = None;
+    let (resolved_config, config_hash) = config_echo(&config);
+    let scale = region.scale.unwrap_or(config.max_scale as f64);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+
+    let file_size = match &input {
+        Either::A(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        Either::B(buffer) => buffer.len() as u64,
+    };
+    let loaded = renderer.check_input(file_size, &[page_num]).and_then(|_| {
+        match &input {
+            Either::A(path) => renderer.load_document_from_file(path),
+            Either::B(buffer) => renderer.load_document(buffer),
+        }
+        .map_err(|e| renderer::load_error("Failed to load PDF", &e))
+    });
+    let (document, password_index) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return Ok(RenderResult {
+                success: false,
+                error: Some(e),
                 num_pages: 0,
                 pages: vec![],
                 total_time: start_time.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id,
+                resolved_config,
+                config_hash,
+                password_index: None,
+                cancelled: false,
+                resource_usage: None,
+            })
+        }
+    };
+
+    let source = match &input {
+        Either::A(path) => flight_recorder::fingerprint_file(path),
+        Either::B(buffer) => flight_recorder::fingerprint_bytes(buffer),
+    };
+    let region = region::Region {
+        x: region.x,
+        y: region.y,
+        width: region.width,
+        height: region.height,
+    };
+    let meter = usage_meter(&opts);
+    let attached = meter.as_ref().map(usage::attach);
+    let page = renderer.render_page_region(&document, page_num, region, scale, &source);
+    drop(attached);
+    Ok(RenderResult {
+        success: true,
+        error: None,
+        num_pages: document.pages().len() as u32,
+        pages: vec![page],
+        total_time: start_time.elapsed().as_millis() as u32,
+        correlation_id: opts.correlation_id,
+        resolved_config,
+        config_hash,
+        password_index,
+        cancelled: false,
+        resource_usage: meter.map(|meter| meter.usage().into()),
+    })
+}
+
+/// 渲染深度缩放查看器（OpenSeadragon、Leaflet 等）的页面分块
+///
+/// 级别 0 时整页缩放到一个分块以内，之后每级放大一倍，最高级别的缩放比例不小于
+/// `options.maxScale`。每个分块通过变换矩阵单独渲染，超大幅面的高倍率级别也不需要渲染整页。
+/// 指定 `x`/`y` 时只渲染这一块；不指定时渲染该级别的全部分块（最多 256 块）。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_num` - 页码（从 1 开始）
+/// * `request` - 缩放级别、分块大小和分块坐标
+/// * `options` - 渲染配置选项（使用输出格式、编码和渲染标志，尺寸类选项不生效）
+///
+/// # Returns
+/// 该级别的网格信息和编码后的分块
+#[napi(ts_args_type = "input: string | Buffer, pageNum: number, request: ZoomTileRequest, options?: RenderOptions | undefined | null")]
+pub fn render_page_tiles(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_num: u32,
+    request: ZoomTileRequest,
+    options: Option<RenderOptions>,
+) -> Result<ZoomTiles> {
+    let tile = match (request.x, request.y) {
+        (Some(x), Some(y)) => Some((x, y)),
+        (None, None) => None,
+        _ => {
+            return Err(Error::from_reason(format!(
+                "{}: x and y must be given together",
+                ErrorCode::InvalidOptions.as_str()
+            )))
+        }
+    };
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let document = load_input(&renderer, &input, "Failed to load PDF").map_err(Error::from_reason)?;
+
+    Ok(renderer.render_zoom_tiles(
+        &document,
+        page_num,
+        request.zoom,
+        request.tile_size.unwrap_or(renderer::DEFAULT_ZOOM_TILE_SIZE),
+        tile,
+    ))
+}
+
+/// 比较两个文档的页数和每页的尺寸、旋转（不渲染）
+///
+/// 按页码对齐两个文档的页面，只读取页面尺寸和旋转，不渲染也不遍历页面对象，
+/// 可以在生成逐页视觉差异之前快速判断两个版本的版式是否发生了变化。
+///
+/// # Arguments
+/// * `input_a` - 文档 A 的文件路径或 Buffer
+/// * `input_b` - 文档 B 的文件路径或 Buffer
+/// * `options` - 渲染配置选项（只使用文件大小限制、密码和 correlationId）
+///
+/// # Returns
+/// 逐页的布局差异
+#[napi(ts_args_type = "inputA: string | Buffer, inputB: string | Buffer, options?: RenderOptions | undefined | null")]
+pub fn compare_documents(
+    env: Env,
+    input_a: Either<String, Buffer>,
+    input_b: Either<String, Buffer>,
+    options: Option<RenderOptions>,
+) -> Result<DocumentComparison> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, build_config(&opts));
+    let (resolved_config, config_hash) = config_echo(renderer.config());
+
+    let result = load_input(&renderer, &input_a, "Failed to load PDF A").and_then(|a| load_input(&renderer, &input_b, "Failed to load PDF B").map(|b| (a, b)));
+
+    let (document_a, document_b) = match result {
+        Ok(documents) => documents,
+        Err(e) => {
+            return Ok(DocumentComparison {
+                success: false,
+                error: Some(e),
+                num_pages_a: 0,
+                num_pages_b: 0,
+                page_count_changed: false,
+                identical_layout: false,
+                changed_pages: vec![],
+                pages: vec![],
+                total_time: start_time.elapsed().as_millis() as u32,
+                correlation_id,
+                resolved_config,
+                config_hash,
+            })
+        }
+    };
+
+    let num_pages_a = document_a.pages().len() as u32;
+    let num_pages_b = document_b.pages().len() as u32;
+    let geometry = |document: &pdfium_render::prelude::PdfDocument, num_pages: u32, page_num: u32| {
+        (page_num <= num_pages).then(|| renderer.page_geometry(document, page_num))
+    };
+    let pages: Vec<PageComparison> = (1..=num_pages_a.max(num_pages_b))
+        .map(|page_num| {
+            let a = geometry(&document_a, num_pages_a, page_num);
+            let b = geometry(&document_b, num_pages_b, page_num);
+            let error = [&a, &b]
+                .iter()
+                .filter_map(|side| side.as_ref().and_then(|g| g.as_ref().err()))
+                .next()
+                .cloned();
+            let a = a.and_then(|g| g.ok());
+            let b = b.and_then(|g| g.ok());
+            let diff = if error.is_some() {
+                compare::LayoutDiff::default()
+            } else {
+                compare::diff_page(a.as_ref(), b.as_ref())
+            };
+            PageComparison {
+                page_num,
+                width_a: a.map(|g| g.width),
+                height_a: a.map(|g| g.height),
+                rotation_a: a.map(|g| g.rotation),
+                width_b: b.map(|g| g.width),
+                height_b: b.map(|g| g.height),
+                rotation_b: b.map(|g| g.rotation),
+                changed: diff.changed() || error.is_some(),
+                size_changed: diff.size_changed,
+                rotation_changed: diff.rotation_changed,
+                missing_in_a: diff.missing_in_a,
+                missing_in_b: diff.missing_in_b,
+                error,
+            }
+        })
+        .collect();
+
+    let changed_pages: Vec<u32> = pages.iter().filter(|page| page.changed).map(|page| page.page_num).collect();
+    Ok(DocumentComparison {
+        success: true,
+        error: None,
+        num_pages_a,
+        num_pages_b,
+        page_count_changed: num_pages_a != num_pages_b,
+        identical_layout: changed_pages.is_empty(),
+        changed_pages,
+        pages,
+        total_time: start_time.elapsed().as_millis() as u32,
+        correlation_id,
+        resolved_config,
+        config_hash,
+    })
+}
+
+/// 加载路径或 Buffer 形式的输入文档，`context` 为加载失败时错误信息的前缀
+fn load_input<'a>(
+    renderer: &'a PdfRenderer,
+    input: &'a Either<String, Buffer>,
+    context: &str,
+) -> std::result::Result<pdfium_render::prelude::PdfDocument<'a>, String> {
+    let file_size = match input {
+        Either::A(path) => std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        Either::B(buffer) => buffer.len() as u64,
+    };
+    renderer.check_input(file_size, &[])?;
+    match input {
+        Either::A(path) => renderer.load_document_from_file(path),
+        Either::B(buffer) => renderer.load_document(buffer),
+    }
+    .map(|(document, _)| document)
+    .map_err(|e| renderer::load_error(context, &e))
+}
+
+/// 提取书签（目录），附带目标位置在渲染图像中的坐标
+///
+/// 按文档顺序深度优先展开，`level` 表示层级。坐标与相同选项下
+/// `renderPages` 输出的图像一致，点击目录条目时可以直接滚动到对应位置。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 书签条目列表
+#[napi]
+pub fn get_outline_anchors(
+    env: Env,
+    pdf_buffer: Buffer,
+    options: Option<RenderOptions>,
+) -> Result<Vec<OutlineAnchor>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(renderer.outline_anchors(&document))
+}
+
+/// 从文件路径提取书签（目录），附带目标位置在渲染图像中的坐标
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `options` - 渲染配置选项（用于确定输出图像尺寸）
+///
+/// # Returns
+/// 书签条目列表
+#[napi]
+pub fn get_outline_anchors_from_file(
+    env: Env,
+    file_path: String,
+    options: Option<RenderOptions>,
+) -> Result<Vec<OutlineAnchor>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(renderer.outline_anchors(&document))
+}
+
+/// 提取书签（目录）树（不渲染）
+///
+/// 按 PDF 中的嵌套关系返回，只解析目标页码，适合直接生成目录。
+/// 需要目标位置的像素坐标时使用 `get_outline_anchors`。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 顶层书签列表，没有书签时为空
+#[napi]
+pub fn get_outline(env: Env, pdf_buffer: Buffer, options: Option<RenderOptions>) -> Result<Vec<OutlineItem>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    renderer
+        .check_input(pdf_buffer.len() as u64, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(renderer.outline_tree(&document))
+}
+
+/// 从文件路径提取书签（目录）树（不渲染）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `options` - 渲染配置选项（只使用密码和资源限制）
+///
+/// # Returns
+/// 顶层书签列表，没有书签时为空
+#[napi]
+pub fn get_outline_from_file(env: Env, file_path: String, options: Option<RenderOptions>) -> Result<Vec<OutlineItem>> {
+    let config = build_config(&resolve_options(options)?);
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    renderer
+        .check_input(file_size, &[])
+        .map_err(Error::from_reason)?;
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    Ok(renderer.outline_tree(&document))
+}
+
+/// 将单页导出为矢量 SVG（实验性）
+///
+/// 把页面中的路径、文字和图片转换为 SVG 元素，适合需要无限缩放清晰度
+/// 而非像素级还原的场景。裁剪路径、着色对象和嵌入字体不会被保留。
+///
+/// # Arguments
+/// * `pdf_buffer` - PDF 文件的二进制数据
+/// * `page_num` - 页码（从 1 开始）
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// SVG 文本
+#[napi]
+pub fn render_page_to_svg(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_num: u32,
+    passwords: Option<Either<String, Vec<String>>>,
+) -> Result<String> {
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let config = RenderConfig {
+        passwords: password_list(passwords),
+        ..RenderConfig::default()
+    };
+    let renderer = PdfRenderer::new(&pdfium, config);
+
+    let document = renderer
+        .load_document(&pdf_buffer)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    page_document_to_svg(&document, page_num)
+}
+
+/// 从文件路径将单页导出为矢量 SVG（实验性）
+///
+/// # Arguments
+/// * `file_path` - PDF 文件的路径
+/// * `page_num` - 页码（从 1 开始）
+/// * `passwords` - 文档的密码，或加密时依次尝试的候选密码
+///
+/// # Returns
+/// SVG 文本
+#[napi]
+pub fn render_page_to_svg_from_file(
+    env: Env,
+    file_path: String,
+    page_num: u32,
+    passwords: Option<Either<String, Vec<String>>>,
+) -> Result<String> {
+    let _task = context::instance(&env)?
+        .lifecycle
+        .begin_task()
+        .map_err(Error::from_reason)?;
+    let pdfium = create_pdfium()?;
+    let config = RenderConfig {
+        passwords: password_list(passwords),
+        ..RenderConfig::default()
+    };
+    let renderer = PdfRenderer::new(&pdfium, config);
+
+    let document = renderer
+        .load_document_from_file(&file_path)
+        .map(|(document, _)| document)
+        .map_err(|e| Error::from_reason(renderer::load_error("Failed to load PDF", &e)))?;
+
+    page_document_to_svg(&document, page_num)
+}
+
+fn page_document_to_svg(document: &pdfium_render::prelude::PdfDocument, page_num: u32) -> Result<String> {
+    let num_pages = document.pages().len() as u32;
+    if page_num < 1 || page_num > num_pages {
+        return Err(Error::from_reason(format!(
+            "Invalid page number: {} (total: {})",
+            page_num, num_pages
+        )));
+    }
+
+    let page = document
+        .pages()
+        .get((page_num - 1) as u16)
+        .map_err(|e| Error::from_reason(format!("Failed to get page: {}", e)))?;
+
+    svg::page_to_svg(document, &page).map_err(Error::from_reason)
+}
+
+/// 渲染单页到原始位图（不编码）
+///
+/// 这个函数只进行 PDFium 渲染，跳过图像编码步骤，
+/// 返回原始 RGBA 像素数据。编码工作可以交给 Sharp 等更高效的库。
+///
+/// # Arguments
+/// * `file_path` - PDF 文件路径
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染选项
+///
+/// # Returns
+/// 包含原始位图数据和元信息的结果
+#[napi]
+pub fn render_page_to_raw_bitmap(
+    env: Env,
+    file_path: String,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<RawBitmapResult> {
+    let render_start = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+
+    let _task = match context::instance(&env)?.lifecycle.begin_task() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::Cancelled.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+        }
+    };
+
+    let pdfium = match create_pdfium() {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e.to_string()),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::PdfiumUnavailable.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+        }
+    };
+
+    let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+    if let Err(e) = config.limits.check_file_size(file_size) {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e.to_string()),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::LimitExceeded.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+    }
+
+    let pixel_format = config.pixel_format;
+    let renderer = renderer::PdfRenderer::new(&pdfium, config);
+    let (document, password_index) = match renderer.load_document_from_file(&file_path) {
+        Ok(d) => d,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(renderer::load_error("Failed to load PDF", &e)),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: renderer::load_error_code(&e).code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+        }
+    };
+
+    let meter = usage_meter(&opts);
+    let attached = meter.as_ref().map(usage::attach);
+    let mut result = renderer.render_page_to_raw_bitmap(&document, page_num, &flight_recorder::fingerprint_file(&file_path));
+    drop(attached);
+    result.correlation_id = opts.correlation_id;
+    result.resolved_config = resolved_config;
+    result.config_hash = config_hash;
+    result.password_index = password_index;
+    result.resource_usage = meter.map(|meter| meter.usage().into());
+
+    Ok(result)
+}
+
+/// 从 Buffer 渲染单页到原始位图（不编码）
+#[napi]
+pub fn render_page_to_raw_bitmap_from_buffer(
+    env: Env,
+    pdf_buffer: Buffer,
+    page_num: u32,
+    options: Option<RenderOptions>,
+) -> Result<RawBitmapResult> {
+    let render_start = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+
+    let _task = match context::instance(&env)?.lifecycle.begin_task() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::Cancelled.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+        }
+    };
+
+    let pdfium = match create_pdfium() {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e.to_string()),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::PdfiumUnavailable.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+        }
+    };
+
+    if let Err(e) = config.limits.check_file_size(pdf_buffer.len() as u64) {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(e.to_string()),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: ErrorCode::LimitExceeded.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+            });
+    }
+
+    let pixel_format = config.pixel_format;
+    let renderer = renderer::PdfRenderer::new(&pdfium, config);
+    let (document, password_index) = match renderer.load_document(&pdf_buffer) {
+        Ok(d) => d,
+        Err(e) => {
+            return Ok(RawBitmapResult {
+                success: false,
+                error: Some(renderer::load_error("Failed to load PDF", &e)),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: opts.correlation_id.clone(),
+                resolved_config: resolved_config.clone(),
+                config_hash: config_hash.clone(),
+                render_info: None,
+                error_code: renderer::load_error_code(&e).code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
             });
         }
     };
 
-    let renderer = PdfRenderer::new(&pdfium, config);
-    
-    match renderer.render_from_file(&file_path, &page_nums) {
-        Ok((num_pages, pages)) => Ok(RenderResult {
-            success: true,
-            error: None,
-            num_pages,
-            pages,
-            total_time: start_time.elapsed().as_millis() as u32,
-        }),
-        Err(e) => Ok(RenderResult {
-            success: false,
-            error: Some(e),
-            num_pages: 0,
-            pages: vec![],
-            total_time: start_time.elapsed().as_millis() as u32,
-        }),
+    let meter = usage_meter(&opts);
+    let attached = meter.as_ref().map(usage::attach);
+    let mut result = renderer.render_page_to_raw_bitmap(&document, page_num, &flight_recorder::fingerprint_bytes(&pdf_buffer));
+    drop(attached);
+    result.correlation_id = opts.correlation_id;
+    result.resolved_config = resolved_config;
+    result.config_hash = config_hash;
+    result.password_index = password_index;
+    result.resource_usage = meter.map(|meter| meter.usage().into());
+
+    Ok(result)
+}
+
+/// 按条带渲染单页原始位图，分块交给回调（不编码，不持有整页像素）
+///
+/// 适合把 600 DPI 等超大位图直接接入流式编码器或上传：每段渲染完通过 `onChunk`
+/// 交给 JS，JS 处理完后调用 `completeRawChunk(taskId)` 确认，同时在途的分块最多两个，
+/// 消费慢时渲染线程等待。确认时传入错误信息会中止渲染。
+///
+/// 条带直接由 PDFium 渲染，canvas、滤镜和高亮叠加不生效，表单控件按外观流绘制。
+///
+/// # Arguments
+/// * `input` - PDF 文件路径或 Buffer
+/// * `page_num` - 页码（从 1 开始）
+/// * `options` - 渲染选项（chunkRows 指定每块行数）
+/// * `on_chunk` - 接收 RawBitmapChunk 的回调
+///
+/// # Returns
+/// Promise<RawChunkResult>，所有分块都确认后完成
+#[napi(
+    ts_args_type = "input: string | Buffer, pageNum: number, options: RenderOptions | null | undefined, onChunk: (err: Error | null, chunk: RawBitmapChunk) => void",
+    ts_return_type = "Promise<RawChunkResult>"
+)]
+pub fn render_page_to_raw_chunks(
+    env: Env,
+    input: Either<String, Buffer>,
+    page_num: u32,
+    options: Option<RenderOptions>,
+    on_chunk: JsFunction,
+) -> Result<napi::JsObject> {
+    let opts = resolve_options(options)?;
+    let config = build_config(&opts);
+    let correlation_id = opts.correlation_id.clone();
+    let (resolved_config, config_hash) = config_echo(&config);
+
+    let module_state = context::instance(&env)?;
+    let task_guard = module_state.lifecycle.begin_task();
+    let task_id = module_state.next_task_id();
+    let window = std::sync::Arc::new(raw_chunks::ChunkWindow::default());
+    module_state.register_chunk_window(task_id, window.clone());
+
+    let tsfn: ThreadsafeFunction<RawBitmapChunk, ErrorStrategy::CalleeHandled> =
+        on_chunk.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<RawBitmapChunk>| Ok(vec![ctx.value]))?;
+    let input = match input {
+        Either::A(path) => Either::A(path),
+        Either::B(buffer) => Either::B(buffer.to_vec()),
+    };
+    let guard_error = task_guard.as_ref().err().cloned();
+
+    env.execute_tokio_future(
+        async move {
+            tokio::task::spawn_blocking(move || match guard_error {
+                Some(e) => raw_chunk_failure(config.pixel_format, e, ErrorCode::Cancelled),
+                None => render_raw_chunks_blocking(config, input, page_num, task_id, &window, tsfn),
+            })
+            .await
+            .map_err(|e| napi::Error::from_reason(format!("Task join error: {}", e)))
+        },
+        move |_env: &mut Env, mut result: RawChunkResult| {
+            module_state.unregister_chunk_window(task_id);
+            drop(task_guard);
+            result.correlation_id = correlation_id;
+            result.resolved_config = resolved_config;
+            result.config_hash = config_hash;
+            Ok(result)
+        },
+    )
+}
+
+fn raw_chunk_failure(pixel_format: PixelFormat, error: String, code: ErrorCode) -> RawChunkResult {
+    RawChunkResult {
+        success: false,
+        error: Some(error),
+        error_code: code.code(),
+        width: 0,
+        height: 0,
+        channels: 4,
+        stride: 0,
+        pixel_format: pixel_format.as_str().to_string(),
+        chunks: 0,
+        render_time: 0,
+        correlation_id: None,
+        resolved_config: None,
+        config_hash: None,
+        password_index: None,
+    }
+}
+
+fn render_raw_chunks_blocking(
+    config: RenderConfig,
+    input: Either<String, Vec<u8>>,
+    page_num: u32,
+    task_id: u32,
+    window: &raw_chunks::ChunkWindow,
+    tsfn: ThreadsafeFunction<RawBitmapChunk, ErrorStrategy::CalleeHandled>,
+) -> RawChunkResult {
+    let pixel_format = config.pixel_format;
+    let pdfium = match create_pdfium() {
+        Ok(p) => p,
+        Err(e) => return raw_chunk_failure(pixel_format, e.to_string(), ErrorCode::PdfiumUnavailable),
+    };
+    let (file_size, source) = match &input {
+        Either::A(path) => (
+            std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            flight_recorder::fingerprint_file(path),
+        ),
+        Either::B(data) => (data.len() as u64, flight_recorder::fingerprint_bytes(data)),
+    };
+    if let Err(e) = config.limits.check_file_size(file_size) {
+        return raw_chunk_failure(pixel_format, e.to_string(), ErrorCode::LimitExceeded);
+    }
+
+    let renderer = PdfRenderer::new(&pdfium, config);
+    let loaded = match &input {
+        Either::A(path) => renderer.load_document_from_file(path),
+        Either::B(data) => renderer.load_document(data),
+    };
+    let (document, password_index) = match loaded {
+        Ok(d) => d,
+        Err(e) => {
+            return raw_chunk_failure(pixel_format, renderer::load_error("Failed to load PDF", &e), renderer::load_error_code(&e))
+        }
+    };
+
+    let mut result = renderer.render_page_to_raw_chunks(&document, page_num, &source, |chunk| {
+        window.acquire(raw_chunks::MAX_IN_FLIGHT, raw_chunks::ACK_TIMEOUT)?;
+        let status = tsfn.call(
+            Ok(RawBitmapChunk {
+                task_id,
+                index: chunk.index,
+                y: chunk.y,
+                rows: chunk.rows,
+                width: chunk.width,
+                height: chunk.height,
+                stride: chunk.width * 4,
+                pixel_format: pixel_format.as_str().to_string(),
+                buffer: renderer::into_js_buffer(chunk.pixels),
+                last: chunk.last,
+            }),
+            ThreadsafeFunctionCallMode::NonBlocking,
+        );
+        if status != Status::Ok {
+            window.complete(None);
+            return Err(format!("Failed to deliver chunk: {}", status));
+        }
+        Ok(())
+    });
+    if result.success {
+        if let Err(e) = window.drain(raw_chunks::ACK_TIMEOUT) {
+            result.success = false;
+            result.error = Some(e);
+            result.error_code = ErrorCode::Cancelled.code();
+        }
+    }
+    result.password_index = password_index;
+    result
+}
+
+/// 确认 renderPageToRawChunks 交出的一个分块
+///
+/// # Arguments
+/// * `task_id` - 分块中的 taskId
+/// * `error` - 回调处理失败时的错误信息，渲染随之中止
+#[napi]
+pub fn complete_raw_chunk(env: Env, task_id: u32, error: Option<String>) -> Result<()> {
+    if let Some(window) = context::instance(&env)?.chunk_window(task_id) {
+        window.complete(error);
+    }
+    Ok(())
+}
+
+/// 单个候选库路径的探测结果
+#[napi(object)]
+pub struct LibraryProbe {
+    /// 候选路径（系统库为库名，由动态链接器按搜索路径查找）
+    pub path: String,
+    /// 是否加载成功
+    pub loaded: bool,
+    /// 加载失败原因
+    pub error: Option<String>,
+    /// 加载耗时（毫秒）
+    pub load_time: u32,
+}
+
+/// 诊断结果
+#[napi(object)]
+pub struct Diagnostics {
+    /// PDFium 是否可用（有库加载成功且测试渲染通过）
+    pub available: bool,
+    /// 实际使用的库路径
+    pub loaded_from: Option<String>,
+    /// 按顺序探测的候选库
+    pub probes: Vec<LibraryProbe>,
+    /// 绑定所针对的 PDFium API 版本（库加载失败时为空）
+    pub pdfium_api_version: Option<String>,
+    /// 原生模块版本
+    pub module_version: String,
+    /// 运行平台（os-arch）
+    pub platform: String,
+    /// 当前工作目录（相对路径的库在此目录下查找）
+    pub cwd: String,
+    /// 测试渲染是否成功
+    pub test_render_ok: bool,
+    /// 测试渲染失败原因
+    pub test_render_error: Option<String>,
+    /// 测试渲染耗时（毫秒）
+    pub test_render_time: u32,
+    /// 诊断总耗时（毫秒）
+    pub total_time: u32,
+    /// 渲染调用的绑定状态：available（最近一次绑定成功）、unavailable（最近一次绑定失败）、
+    /// unknown（尚未渲染过）；与上面的探测结果不同，反映的是实际渲染调用的情况
+    pub binding_state: String,
+    /// 绑定状态为 unavailable 时为 PDFIUM_UNAVAILABLE，与渲染结果的 errorCode 一致
+    pub error_code: Option<String>,
+    /// 连续绑定失败次数（自动重试后仍失败才计数）
+    pub bind_failures: u32,
+    /// 最近一次绑定失败的原因
+    pub last_bind_error: Option<String>,
+    /// 进入不可用状态的时间（Unix 毫秒）
+    pub unavailable_since: Option<i64>,
+    /// 库曾经加载成功、之后经自动重试才重新绑定成功的次数
+    pub rebinds: u32,
+}
+
+/// 获取诊断信息
+///
+/// 返回探测过的库路径、实际加载的库、PDFium API 版本、一次最小测试渲染的结果和耗时。
+/// 同时返回渲染调用的绑定状态（bindingState），库在运行中丢失时为 unavailable。
+/// 该函数不会抛出异常，所有失败都记录在返回结果中。
+#[napi]
+pub fn get_diagnostics() -> Diagnostics {
+    let start_time = std::time::Instant::now();
+
+    let mut probes = Vec::new();
+    let mut bindings = None;
+    let mut loaded_from = None;
+
+    for (label, path) in diagnostics::library_candidates() {
+        let load_start = std::time::Instant::now();
+        let result = diagnostics::bind_candidate(path.as_ref());
+        let load_time = load_start.elapsed().as_millis() as u32;
+
+        match result {
+            Ok(b) => {
+                probes.push(LibraryProbe {
+                    path: label.clone(),
+                    loaded: true,
+                    error: None,
+                    load_time,
+                });
+                if bindings.is_none() {
+                    bindings = Some(b);
+                    loaded_from = Some(label);
+                }
+            }
+            Err(e) => probes.push(LibraryProbe {
+                path: label,
+                loaded: false,
+                error: Some(diagnostics::describe_error(&e)),
+                load_time,
+            }),
+        }
+    }
+
+    let pdfium_api_version = bindings.as_ref().map(|b| format!("{:?}", b.version()));
+
+    let render_start = std::time::Instant::now();
+    let test_render = match bindings {
+        Some(b) => diagnostics::test_render(&pdfium_render::prelude::Pdfium::new(b)),
+        None => Err("No PDFium library could be loaded".to_string()),
+    };
+    let test_render_time = render_start.elapsed().as_millis() as u32;
+    let health = diagnostics::binding_health();
+
+    Diagnostics {
+        available: test_render.is_ok(),
+        loaded_from,
+        probes,
+        pdfium_api_version,
+        module_version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        cwd: std::env::current_dir()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        test_render_ok: test_render.is_ok(),
+        test_render_error: test_render.err(),
+        test_render_time,
+        total_time: start_time.elapsed().as_millis() as u32,
+        binding_state: health.state().to_string(),
+        error_code: (health.consecutive_failures > 0).then(|| ErrorCode::PdfiumUnavailable.as_str().to_string()),
+        bind_failures: health.consecutive_failures,
+        last_bind_error: health.last_error,
+        unavailable_since: health.unavailable_since,
+        rebinds: health.rebinds,
+    }
+}
+
+/// 最近一次渲染尝试的记录
+#[napi(object)]
+pub struct RenderAttempt {
+    /// 递增序号
+    pub seq: u32,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: i64,
+    /// 文档指纹（buffer:长度:哈希 / file:路径:大小:哈希 / stream:大小:任务ID）
+    pub source: String,
+    /// 页码（从 1 开始）
+    pub page_num: u32,
+    /// 渲染宽度（尚未计算出尺寸时为 0）
+    pub width: u32,
+    /// 渲染高度
+    pub height: u32,
+    /// 已到达的阶段：loading / rendering / encoding / done / failed
+    pub stage: String,
+    /// 失败原因
+    pub error: Option<String>,
+    /// 耗时（毫秒）
+    pub elapsed: u32,
+}
+
+/// 获取最近的渲染记录（最多 32 条，从旧到新）
+///
+/// 停留在 loading / rendering / encoding 阶段的记录表示该页尚未完成，
+/// 进程崩溃后可据此定位触发崩溃的文档和页面。
+#[napi]
+pub fn get_recent_renders() -> Vec<RenderAttempt> {
+    flight_recorder::snapshot()
+        .into_iter()
+        .map(|a| RenderAttempt {
+            seq: a.seq,
+            started_at: a.started_at,
+            source: a.source,
+            page_num: a.page_num,
+            width: a.width,
+            height: a.height,
+            stage: a.stage.as_str().to_string(),
+            error: a.error,
+            elapsed: a.elapsed,
+        })
+        .collect()
+}
+
+/// 把最近的渲染记录以 JSON 写入文件
+///
+/// 适合在 worker 的 error / exit 事件或 uncaughtException 处理中调用。
+#[napi]
+pub fn dump_recent_renders(path: String) -> Result<()> {
+    flight_recorder::dump_to_file(&path)
+        .map_err(|e| Error::from_reason(format!("Failed to write render history: {}", e)))
+}
+
+/// 设置崩溃转储路径
+///
+/// 设置后，原生模块内发生 panic 时会先把最近的渲染记录写入该文件。
+/// 传入 null 取消转储。
+#[napi]
+pub fn set_crash_dump_path(path: Option<String>) {
+    flight_recorder::set_dump_path(path);
+}
+
+/// 检查 PDFium 库是否可用
+///
+/// 只返回布尔值；需要排查原因时使用 `getDiagnostics`。
+#[napi]
+pub fn is_pdfium_available() -> bool {
+    create_pdfium().is_ok()
+}
+
+/// 预热 PDFium 库
+/// 
+/// 在服务启动时调用，提前加载 PDFium 动态库并初始化，
+/// 避免首次请求时的冷启动延迟（约 1-2 秒）
+/// 
+/// # Returns
+/// 预热耗时（毫秒）
+#[napi]
+pub fn warmup() -> Result<u32> {
+    let start_time = std::time::Instant::now();
+    
+    let pdfium = create_pdfium()?;
+
+    let _ = pdfium.load_pdf_from_byte_slice(MINIMAL_PDF, None);
+    
+    Ok(start_time.elapsed().as_millis() as u32)
+}
+
+/// 样本文档中单页的检查结果
+#[napi(object)]
+pub struct SamplePageReport {
+    pub page_num: u32,
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// 编码后的字节数
+    pub size: u32,
+    /// 渲染耗时（毫秒）
+    pub render_time: u32,
+    /// 编码耗时（毫秒）
+    pub encode_time: u32,
+}
+
+/// 样本文档预热 / 就绪检查的结果
+#[napi(object)]
+pub struct WarmupReport {
+    /// 文档加载成功且所有页面都渲染成功
+    pub ok: bool,
+    /// 绑定或加载失败的原因
+    pub error: Option<String>,
+    /// 绑定 PDFium 的耗时（毫秒）
+    pub bind_time: u32,
+    /// 打开样本文档的耗时（毫秒）
+    pub load_time: u32,
+    /// 样本文档总页数（加载失败时为 0）
+    pub num_pages: u32,
+    pub pages: Vec<SamplePageReport>,
+    /// 所有页面编码后的总字节数
+    pub output_bytes: u32,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+}
+
+/// 用调用方提供的样本文档预热并检查就绪状态
+///
+/// 与 `warmup` 只加载内置的最小 PDF 不同，这里对部署实际处理的文档走一遍绑定、加载、
+/// 渲染、编码流程，报告各阶段耗时和输出大小，适合作为就绪探针。
+/// 最多检查 8 页（默认第 1 页），未指定 totalTimeBudgetMs 时预算为 30 秒；
+/// 样本渲染不经过渲染缓存。失败不会抛出异常，原因在结果的 error 和各页的 error 中。
+///
+/// # Arguments
+/// * `sample` - 样本 PDF 的文件路径或 Buffer
+/// * `page_nums` - 要检查的页码（默认 [1]）
+/// * `options` - 渲染配置选项（与正式渲染使用相同的配置，检查结果才有代表性）
+#[napi(
+    ts_args_type = "sample: string | Buffer, pageNums?: Array<number> | undefined | null, options?: RenderOptions | undefined | null",
+    ts_return_type = "Promise<WarmupReport>"
+)]
+pub fn warmup_with_sample(
+    env: Env,
+    sample: Either<String, Buffer>,
+    page_nums: Option<Vec<u32>>,
+    options: Option<RenderOptions>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let config = build_config(&resolve_options(options)?);
+    let page_nums = page_nums.filter(|p| !p.is_empty()).unwrap_or_else(|| vec![1]);
+    let source = match sample {
+        Either::A(path) => scheduler::DocumentSource::File(path),
+        Either::B(buffer) => scheduler::DocumentSource::Bytes(buffer.to_vec()),
+    };
+    let task = context::instance(&env)?.lifecycle.begin_task();
+
+    env.spawn_future(async move {
+        let check = tokio::task::spawn_blocking(move || match task {
+            Ok(_task) => diagnostics::sample_check(&source, &page_nums, config),
+            Err(e) => diagnostics::SampleCheck::failed(e),
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        let pages: Vec<SamplePageReport> = check
+            .pages
+            .into_iter()
+            .map(|page| SamplePageReport {
+                page_num: page.page_num,
+                success: page.success,
+                error: page.error,
+                error_code: page.error_code,
+                width: page.width,
+                height: page.height,
+                size: page.buffer.len() as u32,
+                render_time: page.render_time,
+                encode_time: page.encode_time,
+            })
+            .collect();
+        Ok(WarmupReport {
+            ok: check.error.is_none() && !pages.is_empty() && pages.iter().all(|p| p.success),
+            error: check.error,
+            bind_time: check.bind_time,
+            load_time: check.load_time,
+            num_pages: check.num_pages,
+            output_bytes: pages.iter().map(|p| p.size).sum(),
+            pages,
+            total_time,
+        })
+    })
+}
+
+/// 获取版本信息
+#[napi]
+pub fn get_version() -> String {
+    format!("pdf-renderer v{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// 合成测试 PDF 的参数
+#[napi(object)]
+pub struct TestPdfOptions {
+    /// 页面内容：text（纯文字）、image（整页灰度 JPEG，识别为扫描件）、
+    /// huge（14400 点见方的超大页面）、mixed（按页循环以上三种），默认 text
+    pub kind: Option<String>,
+    /// 页数（1-1000，默认 3）
+    pub pages: Option<u32>,
+    /// 设置后以该密码加密（RC4 40 位），打开时需要传入 password
+    pub password: Option<String>,
+}
+
+/// 生成合成测试 PDF
+///
+/// 给下游服务的集成测试使用，覆盖纯文字、扫描件、超大页面和加密文档等情况，
+/// 不需要在仓库中保存二进制样本。不依赖 PDFium，相同参数生成的字节完全相同。
+#[napi]
+pub fn generate_test_pdf(options: Option<TestPdfOptions>) -> Result<Buffer> {
+    let options = options.unwrap_or(TestPdfOptions {
+        kind: None,
+        pages: None,
+        password: None,
+    });
+    let kind = options.kind.as_deref().unwrap_or("text");
+    let spec = synthetic::SyntheticSpec {
+        kind: synthetic::SyntheticKind::from_str(kind).ok_or_else(|| {
+            Error::from_reason(format!(
+                "{}: Invalid kind: {} (expected text, image, huge or mixed)",
+                ErrorCode::InvalidOptions.as_str(),
+                kind
+            ))
+        })?,
+        pages: options.pages.unwrap_or(3),
+        password: options.password,
+    };
+    synthetic::generate(&spec)
+        .map(Buffer::from)
+        .map_err(|e| Error::from_reason(format!("{}: {}", ErrorCode::InvalidOptions.as_str(), e)))
+}
+
+/// 已编译的编码器后端
+#[napi(object)]
+pub struct EncoderCapability {
+    /// 输出格式（webp/png/jpg/tiff）
+    pub format: String,
+    /// 后端名称（可作为 encoderBackend 选项的值）
+    pub backend: String,
+    /// 是否为该格式的默认后端（encoderBackend 为 auto 时使用）
+    pub is_default: bool,
+    /// 说明
+    pub description: String,
+}
+
+/// 交给自定义编码器的位图
+#[napi(object)]
+pub struct EncodeRequest {
+    /// 请求 ID，编码完成后调用 completeEncodeRequest(requestId, data, error)
+    pub request_id: u32,
+    /// 编码器名称
+    pub encoder: String,
+    /// format 选项对应的输出格式（webp/png/jpg/tiff/avif）
+    pub format: String,
+    /// 质量参数（供编码器参考）：jpg 为 jpegQuality，avif 为 avifQuality，其他格式为 webpQuality
+    pub quality: u32,
+    /// 编码速度（仅 avif，avifSpeed：0 最慢但压缩最好，10 最快）
+    pub speed: Option<u32>,
+    pub width: u32,
+    pub height: u32,
+    /// 每行字节数（紧密排列，等于 width * 4）
+    pub stride: u32,
+    /// 像素通道顺序：rgba 或 bgra
+    pub pixel_format: String,
+    /// 像素数据
+    pub buffer: Buffer,
+}
+
+/// 注册自定义编码器
+///
+/// 注册后可以通过 `encoderBackend: name` 选用：每页渲染完成后，位图以 EncodeRequest
+/// 交给 `callback`，JS 编码完成后调用 completeEncodeRequest 交回结果，渲染线程在此期间等待。
+/// 同名编码器会被替换。只能用于异步渲染接口，同步接口选用时该页编码失败。
+///
+/// # Arguments
+/// * `name` - 编码器名称（不能是 auto/libwebp/image/builtin/none）
+/// * `callback` - 接收 EncodeRequest 的回调
+/// * `format` - 输出格式名（如 avif），写入 PageResult.format；不传时沿用 format 选项
+#[napi(ts_args_type = "name: string, callback: (err: Error | null, request: EncodeRequest) => void, format?: string | null")]
+pub fn register_encoder(name: String, callback: JsFunction, format: Option<String>) -> Result<()> {
+    let tsfn: ThreadsafeFunction<EncodeRequest, ErrorStrategy::CalleeHandled> =
+        callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<EncodeRequest>| Ok(vec![ctx.value]))?;
+    custom_encoders::register(&name, format, tsfn).map_err(Error::from_reason)?;
+    // 同名编码器换了实现，之前缓存的编码结果不再可信
+    render_cache::clear();
+    Ok(())
+}
+
+/// 注销自定义编码器，返回是否存在
+#[napi]
+pub fn unregister_encoder(name: String) -> bool {
+    custom_encoders::unregister(&name)
+}
+
+/// 交回自定义编码器的编码结果
+///
+/// # Arguments
+/// * `request_id` - EncodeRequest.requestId
+/// * `data` - 编码后的数据
+/// * `error` - 错误信息（编码失败时），该页随之编码失败
+#[napi]
+pub fn complete_encode_request(request_id: u32, data: Option<Buffer>, error: Option<String>) {
+    let result = match (data, error) {
+        (_, Some(err)) => Err(err),
+        (Some(buffer), None) => Ok(buffer.to_vec()),
+        (None, None) => Err("No data or error provided".to_string()),
+    };
+    custom_encoders::complete(request_id, result);
+}
+
+/// 查询已编译的编码器后端
+///
+/// 不同构建可能包含不同的编码器，部署时据此选择速度或压缩率优先的后端。
+/// registerEncoder 注册的编码器排在最后，format 为其输出格式名（未指定时为 custom）。
+#[napi]
+pub fn get_encoder_capabilities() -> Vec<EncoderCapability> {
+    let builtin = encoders::BACKENDS
+        .iter()
+        .enumerate()
+        .map(|(i, (format, backend, description))| EncoderCapability {
+            format: format.as_str().to_string(),
+            backend: backend.as_str().to_string(),
+            is_default: !encoders::BACKENDS[..i].iter().any(|(f, _, _)| f == format),
+            description: description.to_string(),
+        });
+    let custom = custom_encoders::registered().into_iter().map(|(name, format)| EncoderCapability {
+        format: format.unwrap_or_else(|| "custom".to_string()),
+        backend: name,
+        is_default: false,
+        description: "custom encoder registered via registerEncoder".to_string(),
+    });
+    builtin.chain(custom).collect()
+}
+
+/// 流式渲染结果（包含额外的统计信息）
+#[napi(object)]
+pub struct StreamRenderResult {
+    /// 是否成功
+    pub success: bool,
+    /// 错误信息（如果整体失败）
+    pub error: Option<String>,
+    /// PDF 总页数
+    pub num_pages: u32,
+    /// 每页的渲染结果
+    pub pages: Vec<PageResult>,
+    /// 总耗时（毫秒）
+    pub total_time: u32,
+    /// 流式加载统计
+    pub stream_stats: Option<StreamStats>,
+    /// 请求关联 ID（原样回显 options.correlationId）
+    pub correlation_id: Option<String>,
+    /// 合并默认值、命名配置和旧字段（如 quality）后的完整渲染配置，文字形式，供日志和排查使用（密码只保留个数）
+    pub resolved_config: Option<String>,
+    /// 影响输出的渲染配置的哈希，与文档指纹组合即可作为 JS 侧缓存的键
+    pub config_hash: Option<String>,
+}
+
+/// 流式加载统计信息
+#[napi(object)]
+pub struct StreamStats {
+    /// 总请求次数
+    pub total_requests: u32,
+    /// 缓存命中次数
+    pub cache_hits: u32,
+    /// 缓存未命中次数
+    pub cache_misses: u32,
+    /// 总下载字节数
+    pub total_bytes_fetched: i64,
+    /// 因并发限制排队等待的时间（毫秒）
+    pub queue_time: u32,
+    /// 由预取（fetchLanes 并行预取、streamReadahead 合并读取）提供、随后被读取的块数
+    pub prefetch_hits: u32,
+    /// 预取请求的块数；prefetchHits 与它的比值即预取的有效程度，过低时出现 WASTED_PREFETCH 建议
+    pub prefetched_blocks: u32,
+    /// 由 seedBlocks 预先写入缓存的块数
+    pub seeded_blocks: u32,
+    /// 按取数计划（options.fetchPlan）批量获取的块数
+    pub planned_blocks: u32,
+    /// 取数计划失败的原因（渲染照常逐块读取）
+    pub fetch_plan_error: Option<String>,
+    /// 改为获取整个文件的原因（options.streamFallbackFraction / streamFallbackRequests，未回退时为空）
+    pub fallback_reason: Option<String>,
+    /// 获取整个文件失败的原因（渲染照常逐块读取）
+    pub fallback_error: Option<String>,
+    /// 被多个页面读取过的块（按偏移排序）
+    pub reused_blocks: Vec<ReusedBlock>,
+    /// 缓存命中率（缓存命中与预取命中占全部读取的比例，目标 0.8）
+    pub hit_ratio: f64,
+    /// 文件是否线性化（按文件开头的 /Linearized 字典判断），未读到文件开头时为空
+    pub linearized: Option<bool>,
+    /// 是否使用了线性化文件的快速路径（options.linearizedFastPath）
+    pub linearized_fast_path: bool,
+    /// 根据本次读取模式给出的调优建议
+    pub hints: Vec<StreamHint>,
+    /// 等待超时或任务结束时仍未得到响应的请求数
+    pub orphaned_requests: u32,
+}
+
+/// 流式读取的调优建议
+#[napi(object)]
+pub struct StreamHint {
+    /// 建议类别
+    ///
+    /// - FULL_DOWNLOAD：读取量接近文件大小，整体下载更合适
+    /// - NOT_LINEARIZED：文档未线性化，页面数据分散在整个文件中
+    /// - ENABLE_FETCH_LANES：大部分块按顺序读取，建议设置 fetchLanes 或 streamReadahead
+    /// - WASTED_PREFETCH：预取的块大多没有被读取，建议调小 fetchLanes 或 streamReadahead
+    /// - REFETCHED_BLOCKS：块被缓存淘汰后重新获取
+    /// - LOW_HIT_RATIO：缓存命中率低于目标
+    pub code: String,
+    /// 说明（英文）
+    pub message: String,
+}
+
+/// 本次任务中被多个页面读取过的缓存块
+#[napi(object)]
+pub struct ReusedBlock {
+    /// 块在文件中的偏移
+    pub offset: i64,
+    /// 块大小（字节）
+    pub size: u32,
+    /// 读取过该块的页码（升序），0 表示打开文档阶段
+    pub pages: Vec<u32>,
+    /// 通过 fetcher 获取的次数，大于 1 说明块被淘汰后又重新获取
+    pub fetches: u32,
+    /// 是否由 seedBlocks 提供
+    pub seeded: bool,
+}
+
+/// 调用方已有的文件数据，用于预先填充流式缓存
+#[napi(object)]
+pub struct SeedBlock {
+    /// 数据在文件中的偏移
+    pub offset: i64,
+    /// 文件数据
+    pub data: Buffer,
+}
+
+/// 创建流式读取器：fetcher 包装成线程安全函数，seedBlocks 预先写入块缓存
+fn create_streamer(
+    fetcher: JsFunction,
+    known_size: Option<u64>,
+    task_id: u32,
+    opts: &RenderOptions,
+    config: &RenderConfig,
+    seed_blocks: Option<Vec<SeedBlock>>,
+) -> Result<JsFileStreamer> {
+    let fetch_correlation_id = opts.correlation_id.clone();
+    let tsfn: ThreadsafeFunction<BlockRequest, ErrorStrategy::CalleeHandled> = fetcher
+        .create_threadsafe_function(0, move |ctx: ThreadSafeCallContext<BlockRequest>| {
+            let mut obj = ctx.env.create_object()?;
+            obj.set("offset", ctx.value.offset as f64)?;
+            obj.set("size", ctx.value.size)?;
+            obj.set("requestId", ctx.value.request_id as i64)?;
+            if ctx.value.full {
+                obj.set("full", true)?;
+            }
+            if !ctx.value.ranges.is_empty() {
+                let ranges = ctx
+                    .value
+                    .ranges
+                    .iter()
+                    .map(|&(offset, size)| {
+                        let mut range = ctx.env.create_object()?;
+                        range.set("offset", offset as f64)?;
+                        range.set("size", size)?;
+                        Ok(range)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                obj.set("ranges", ranges)?;
+            }
+            obj.set("correlationId", fetch_correlation_id.clone())?;
+            Ok(vec![obj])
+        })?;
+
+    let tuning = CacheTuning::new(opts.stream_block_size, opts.stream_max_cache_blocks, opts.stream_fetch_timeout_ms);
+    let streamer = JsFileStreamer::new(known_size, tsfn, task_id, tuning)
+        .with_max_size(config.limits.max_file_size)
+        .with_fetch_lanes(opts.fetch_lanes.unwrap_or(1))
+        .with_readahead(opts.stream_readahead.unwrap_or(0))
+        .with_fallback(FallbackPolicy {
+            fetched_fraction: opts.stream_fallback_fraction,
+            requests: opts.stream_fallback_requests,
+        });
+    for seed in seed_blocks.iter().flatten() {
+        streamer.seed(seed.offset.max(0) as u64, &seed.data);
+    }
+    Ok(streamer)
+}
+
+/// 流式渲染任务在后台线程完成后交回主线程的数据
+type StreamTaskOutput = (
+    std::result::Result<(u32, Vec<PageResult>), String>,
+    std::sync::Arc<SharedState>,
+    std::time::Instant,
+    u32,
+    u32,
+);
+
+/// 从流式数据源渲染 PDF 页面（异步版本）
+///
+/// 这个函数在独立线程中运行 PDFium 渲染，返回 Promise。
+/// 主线程保持事件循环运行，可以处理 JS 回调。
+///
+/// # Arguments
+/// * `env` - NAPI 环境
+/// * `pdf_size` - PDF 文件的总大小（字节）；传入 0 或负数表示长度未知（适合边上传边渲染），
+///   此时第一个请求的响应需要通过 `completeStreamRequest` 的 `totalSize` 报告总长度
+/// * `page_nums` - 要渲染的页码数组（从 1 开始），或范围字符串、`{ from, to }`（同 `renderPages`）；
+///   范围在文档加载、页数已知后展开，不需要先单独获取页数
+/// * `options` - 渲染配置选项
+/// * `fetcher` - JavaScript 回调函数，用于获取指定范围的数据
+/// * `seed_blocks` - 调用方已有的文件数据（例如上传时已经读到的前 1 MB），
+///   预先写入缓存，覆盖的块不再通过 fetcher 获取
+/// * `on_document` - 文档头和页面树解析完成后、任何页面渲染之前以 `(err, info)` 调用一次，
+///   界面可以先按页数画出翻页骨架；文档加载失败时不调用
+///
+/// # Returns
+/// Promise<StreamRenderResult>
+#[napi(
+    ts_args_type = "pdfSize: number, pageNums: number[] | string | PageRange, options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number, correlationId?: string) => void, seedBlocks?: SeedBlock[] | null, onDocument?: ((err: Error | null, info: StreamDocumentInfo) => void) | null"
+)]
+pub fn render_pages_from_stream(
+    env: Env,
+    pdf_size: f64,
+    page_nums: PagesArg,
+    options: Option<RenderOptions>,
+    fetcher: JsFunction,
+    seed_blocks: Option<Vec<SeedBlock>>,
+    on_document: Option<JsFunction>,
+) -> napi::Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let pages = page_selection(page_nums)?;
+    let known_size = (pdf_size > 0.0).then_some(pdf_size as u64);
+
+    let mut config = build_config(&opts);
+    config.overlap_fetch = true;
+
+    let module_state = context::instance(&env)?;
+    let task_id = module_state.next_task_id();
+    let correlation_id = opts.correlation_id.clone();
+    let (resolved_config, config_hash) = config_echo(&config);
+    let max_concurrent_streams = opts.max_concurrent_streams;
+    let fetch_lanes = opts.fetch_lanes.unwrap_or(1);
+    let fetch_plan = opts.fetch_plan.unwrap_or(false);
+    let linearized_fast_path = opts.linearized_fast_path.unwrap_or(true);
+
+    let streamer = create_streamer(fetcher, known_size, task_id, &opts, &config, seed_blocks)?;
+    let shared_state = streamer.get_shared_state();
+    let page_state = shared_state.clone();
+    let on_document = document_callback(on_document)?;
+
+    module_state.register_stream(task_id, shared_state.clone());
+
+    // 守卫随结果一起交回主线程，Promise 完成时才结束计数
+    let task_guard = module_state.lifecycle.begin_task();
+    let guard_error = task_guard.as_ref().err().cloned();
+
+    env.execute_tokio_future(
+        async move {
+            // 并发名额在进入阻塞线程之前获取，排队时不占用线程
+            let queue_start = std::time::Instant::now();
+            let permit = match guard_error {
+                Some(e) => Err(e),
+                None => stream_limits::acquire(max_concurrent_streams).await,
+            };
+            let queue_time = queue_start.elapsed().as_millis() as u32;
+
+            let result = tokio::task::spawn_blocking(move || {
+                let _permit = permit?;
+                let pdfium = create_pdfium().map_err(|e| e.to_string())?;
+                let passwords = config.passwords.clone();
+                let renderer = PdfRenderer::new(&pdfium, config);
+                renderer.check_input(known_size.unwrap_or(0), pages.known_pages())?;
+                // 长度未知时先等 fetcher 报告总长度（同时检查大小限制），PDFium 打开文档前需要知道总长度
+                let mut streamer = streamer;
+                let file_size = streamer
+                    .total_size()
+                    .map_err(|e| format!("Failed to read PDF stream: {}", e))?;
+                // 线性化文件：打开文档前并发预取首页段落、提示表和主交叉引用表（失败时照常逐块读取）
+                if linearized_fast_path {
+                    let linearization = fetch_plan::linearization(&mut streamer.reopen(), file_size)
+                        .filter(|l| pages.resolve(l.pages).is_ok_and(|p| p.contains(&l.first_page)));
+                    if linearization.is_some_and(|l| streamer.prefetch_ranges(&l.ranges).is_ok()) {
+                        page_state.record_fast_path();
+                    }
+                }
+                // 换密码重试时复用同一份块缓存，不会重新请求已经读到的数据
+                let (document, _) = renderer::try_passwords(&passwords, |password| {
+                    pdfium.load_pdf_from_reader(streamer.reopen(), password)
+                })
+                .map_err(|e| renderer::load_error("Failed to load PDF from stream", &e))?;
+                if let Some(on_document) = on_document {
+                    let metadata = doc_info::read(&document);
+                    let info = StreamDocumentInfo {
+                        num_pages: document.pages().len() as u32,
+                        title: metadata.title,
+                        author: metadata.author,
+                        subject: metadata.subject,
+                        keywords: metadata.keywords,
+                        creator: metadata.creator,
+                        producer: metadata.producer,
+                        creation_date: metadata.creation_date,
+                        modification_date: metadata.modification_date,
+                        pdf_version: metadata.pdf_version,
+                        file_size: file_size as i64,
+                        load_time: start_time.elapsed().as_millis() as u32,
+                    };
+                    on_document.call(Ok(info), ThreadsafeFunctionCallMode::NonBlocking);
+                }
+                let page_nums = pages.resolve(document.pages().len() as u32)?;
+                if fetch_plan {
+                    let planned = fetch_plan::plan(&mut streamer.reopen(), file_size, &page_nums)
+                        .and_then(|plan| streamer.fetch_planned(&plan.ranges).map_err(|e| e.to_string()));
+                    if let Err(e) = planned {
+                        page_state.record_plan_error(e);
+                    }
+                }
+                let source = match known_size {
+                    Some(size) => format!("stream:{}:{}", size, task_id),
+                    None => format!("stream:unknown:{}", task_id),
+                };
+                renderer.render_document_pages_with(&document, &page_nums, &source, |page_num| {
+                    page_state.set_current_page(page_num)
+                })
+            })
+            .await
+            .map_err(|e| napi::Error::from_reason(format!("Task join error: {}", e)))?;
+
+            Ok((result, shared_state, start_time, task_id, queue_time))
+        },
+        move |env: &mut Env, (result, shared_state, start_time, task_id, queue_time): StreamTaskOutput| {
+            module_state.unregister_stream(task_id);
+            drop(task_guard);
+
+            let hints = shared_state
+                .tuning_hints(fetch_lanes)
+                .into_iter()
+                .map(|hint| StreamHint {
+                    code: hint.code.to_string(),
+                    message: hint.message,
+                })
+                .collect();
+            let hit_ratio = shared_state.hit_ratio();
+            let fallback = shared_state.fallback();
+            let stats = shared_state.stats.lock().unwrap();
+            let stream_stats = StreamStats {
+                total_requests: stats.total_requests,
+                cache_hits: stats.cache_hits,
+                cache_misses: stats.cache_misses,
+                total_bytes_fetched: stats.total_bytes_fetched as i64,
+                queue_time,
+                prefetch_hits: stats.prefetch_hits,
+                prefetched_blocks: stats.prefetched_blocks,
+                seeded_blocks: stats.seeded_blocks,
+                planned_blocks: stats.planned_blocks,
+                fetch_plan_error: shared_state.plan_error(),
+                fallback_reason: fallback.as_ref().map(|fallback| fallback.reason.clone()),
+                fallback_error: fallback.and_then(|fallback| fallback.error),
+                reused_blocks: shared_state
+                    .reused_blocks()
+                    .into_iter()
+                    .map(|(offset, usage)| ReusedBlock {
+                        offset: offset as i64,
+                        size: usage.size,
+                        pages: usage.pages,
+                        fetches: usage.fetches,
+                        seeded: usage.seeded,
+                    })
+                    .collect(),
+                hit_ratio,
+                linearized: shared_state.linearized(),
+                linearized_fast_path: shared_state.fast_path(),
+                hints,
+                orphaned_requests: shared_state.orphaned_requests(),
+            };
+
+            match result {
+                Ok((num_pages, pages)) => {
+                    let mut obj = env.create_object()?;
+                    obj.set("success", true)?;
+                    obj.set("error", env.get_null()?)?;
+                    obj.set("numPages", num_pages)?;
+                    obj.set("pages", pages)?;
+                    obj.set("totalTime", start_time.elapsed().as_millis() as u32)?;
+                    obj.set("streamStats", stream_stats)?;
+                    obj.set("correlationId", correlation_id)?;
+                    obj.set("resolvedConfig", resolved_config)?;
+                    obj.set("configHash", config_hash)?;
+                    Ok(obj)
+                }
+                Err(e) => {
+                    let mut obj = env.create_object()?;
+                    obj.set("success", false)?;
+                    obj.set("error", e)?;
+                    obj.set("numPages", 0u32)?;
+                    obj.set("pages", Vec::<PageResult>::new())?;
+                    obj.set("totalTime", start_time.elapsed().as_millis() as u32)?;
+                    obj.set("streamStats", stream_stats)?;
+                    obj.set("correlationId", correlation_id)?;
+                    obj.set("resolvedConfig", resolved_config)?;
+                    obj.set("configHash", config_hash)?;
+                    Ok(obj)
+                }
+            }
+        },
+    )
+}
+
+/// 完成流式请求
+///
+/// 当 JS 端获取到数据后，调用这个函数将数据发送给 Rust 端。
+/// Buffer 不会被复制，直接作为块缓存的数据，调用后不要再修改它。
+///
+/// # Arguments
+/// * `request_id` - 请求 ID
+/// * `data` - 获取到的数据
+/// * `error` - 错误信息（如果获取失败）
+/// * `total_size` - 文件总长度（`pdfSize` 未知时必须在第一个请求的响应中提供，例如 HTTP 响应
+///   Content-Range 中的总长度；返回的数据短于请求的大小不会被当作文件结束）
+///
+/// 返回请求是否仍在等待；请求已超时或任务已结束时返回 false，计入迟到响应。
+#[napi]
+pub fn complete_stream_request(
+    env: Env,
+    request_id: i64,
+    data: Option<Buffer>,
+    error: Option<String>,
+    total_size: Option<f64>,
+) -> Result<bool> {
+    Ok(complete_request(&*context::instance(&env)?, request_id, data, error, total_size))
+}
+
+/// 一个流式请求的响应（见 completeStreamRequests）
+#[napi(object)]
+pub struct StreamResponse {
+    /// 请求 ID
+    pub request_id: i64,
+    /// 获取到的数据
+    pub data: Option<Buffer>,
+    /// 错误信息（如果获取失败）
+    pub error: Option<String>,
+    /// 文件总长度（见 completeStreamRequest 的 totalSize）
+    pub total_size: Option<f64>,
+}
+
+/// 一次完成多个流式请求
+///
+/// 与逐个调用 `completeStreamRequest` 相同，但只需一次 JS→Rust 调用；适合 fetcher 把同一时刻
+/// 到达的多个块（例如一次 HTTP multipart 响应）一起交回。返回仍在等待的请求数，其余计入迟到响应。
+#[napi]
+pub fn complete_stream_requests(env: Env, responses: Vec<StreamResponse>) -> Result<u32> {
+    let module_state = context::instance(&env)?;
+    Ok(responses
+        .into_iter()
+        .map(|r| complete_request(&module_state, r.request_id, r.data, r.error, r.total_size))
+        .filter(|&pending| pending)
+        .count() as u32)
+}
+
+fn complete_request(
+    module_state: &context::ModuleState,
+    request_id: i64,
+    data: Option<Buffer>,
+    error: Option<String>,
+    total_size: Option<f64>,
+) -> bool {
+    // request_id 由 task_id 和 16 位请求序号组成（见 SharedState::next_id）
+    let request_id = request_id as u64;
+    let Some(shared_state) = u32::try_from(request_id >> 16).ok().and_then(|task_id| module_state.stream(task_id)) else {
+        stream_reader::record_late_response();
+        return false;
+    };
+    let result = match (data, error) {
+        (Some(buffer), _) => Ok(buffer.into()),
+        (None, Some(err)) => Err(err),
+        (None, None) => Err("No data or error provided".to_string()),
+    };
+    if let Some(size) = total_size.filter(|&size| size >= 0.0) {
+        shared_state.report_file_size(size as u64);
+    }
+    shared_state.complete_request(request_id, result)
+}
+
+/// 流式请求状态，用于发现 fetcher 丢失的请求
+#[napi(object)]
+pub struct StreamRequestStats {
+    /// 本实例进行中的流式任务数
+    pub active_tasks: u32,
+    /// 本实例正在等待 JS 响应的请求数
+    pub pending_requests: u32,
+    /// 单个任务最多同时等待的请求数，超出时读取失败
+    pub max_pending_per_task: u32,
+    /// 进程内累计的孤立请求数（等待超时或任务结束时仍未响应）
+    pub orphaned_requests: i64,
+    /// 进程内累计的迟到响应数（请求已超时或任务已结束后才调用 completeStreamRequest）
+    pub late_responses: i64,
+}
+
+/// 获取流式请求状态
+///
+/// `orphanedRequests` 持续增长说明 fetcher 丢弃了请求而没有回调
+/// `completeStreamRequest`；`lateResponses` 增长说明 fetcher 响应太慢。
+#[napi]
+pub fn get_stream_request_stats(env: Env) -> Result<StreamRequestStats> {
+    let (active_tasks, pending_requests) = context::instance(&env)?.stream_request_counts();
+    let (orphaned, late) = stream_reader::orphan_counts();
+    Ok(StreamRequestStats {
+        active_tasks,
+        pending_requests,
+        max_pending_per_task: stream_reader::MAX_PENDING_REQUESTS as u32,
+        orphaned_requests: orphaned as i64,
+        late_responses: late as i64,
+    })
+}
+
+/// 流式任务全局并发限制
+#[napi(object)]
+pub struct StreamLimitOptions {
+    /// 最多同时活动的流式任务数（不传表示不限制）
+    pub max_active_streams: Option<u32>,
+    /// 最多排队的流式任务数，排满后新任务直接失败（不传表示不限制）
+    pub max_queued_streams: Option<u32>,
+}
+
+/// 流式任务并发状态
+#[napi(object)]
+pub struct StreamLimitStats {
+    /// 活动任务数
+    pub active: u32,
+    /// 排队任务数
+    pub queued: u32,
+    /// 当前的最大活动任务数
+    pub max_active_streams: Option<u32>,
+    /// 当前的最大排队任务数
+    pub max_queued_streams: Option<u32>,
+}
+
+/// 设置流式任务的全局并发限制
+///
+/// 每个活动的流式任务会占用最多 16 MB 的块缓存和一个阻塞线程。
+/// 超出 `maxActiveStreams` 的任务异步排队，直到有任务结束。
+#[napi]
+pub fn configure_stream_limits(options: StreamLimitOptions) {
+    stream_limits::configure(options.max_active_streams, options.max_queued_streams);
+}
+
+/// 获取流式任务的并发状态
+#[napi]
+pub fn get_stream_limit_stats() -> StreamLimitStats {
+    let snapshot = stream_limits::snapshot();
+    StreamLimitStats {
+        active: snapshot.active,
+        queued: snapshot.queued,
+        max_active_streams: snapshot.max_active,
+        max_queued_streams: snapshot.max_queued,
     }
 }
 
-/// 从文件路径获取 PDF 页数（不渲染）
+/// 渲染结果缓存容量
+#[napi(object)]
+pub struct RenderCacheOptions {
+    /// 最多缓存的页数（默认 256）
+    pub max_entries: Option<u32>,
+    /// 缓存数据的总字节数上限，传 0 关闭缓存
+    pub max_bytes: f64,
+}
+
+/// 渲染结果缓存统计
+#[napi(object)]
+pub struct RenderCacheStats {
+    /// 是否已启用
+    pub enabled: bool,
+    /// 当前缓存的页数
+    pub entries: u32,
+    /// 当前缓存数据的总字节数
+    pub bytes: f64,
+    /// 最多缓存的页数
+    pub max_entries: u32,
+    /// 总字节数上限
+    pub max_bytes: f64,
+    /// 命中次数
+    pub hits: f64,
+    /// 未命中次数
+    pub misses: f64,
+    /// 因容量不足被淘汰的页数
+    pub evictions: f64,
+}
+
+/// 启用或调整进程级的渲染结果缓存（LRU），传 null 关闭并清空
 ///
-/// # Arguments
-/// * `file_path` - PDF 文件的路径
+/// 同一文档（Buffer 按长度和首尾内容识别，文件按路径、大小和修改时间识别）、同一页、
+/// 同样配置的渲染结果直接从缓存返回，结果的 cached 为 true。适合查看器在页面之间来回跳转。
+/// 流式渲染和原始位图不经过缓存；因内存水位降级渲染的页面不会写入缓存。
+#[napi]
+pub fn configure_render_cache(options: Option<RenderCacheOptions>) {
+    match options {
+        Some(o) => render_cache::configure(o.max_entries.unwrap_or(256), o.max_bytes.max(0.0) as u64),
+        None => render_cache::configure(0, 0),
+    }
+}
+
+/// 获取渲染结果缓存的统计信息
+#[napi]
+pub fn get_render_cache_stats() -> RenderCacheStats {
+    let stats = render_cache::stats();
+    RenderCacheStats {
+        enabled: render_cache::enabled(),
+        entries: stats.entries,
+        bytes: stats.bytes as f64,
+        max_entries: stats.max_entries,
+        max_bytes: stats.max_bytes as f64,
+        hits: stats.hits as f64,
+        misses: stats.misses as f64,
+        evictions: stats.evictions as f64,
+    }
+}
+
+/// 清空渲染结果缓存（保留容量设置），返回清除的页数
+#[napi]
+pub fn clear_render_cache() -> u32 {
+    render_cache::clear()
+}
+
+/// 软内存水位设置
+#[napi(object)]
+pub struct MemoryWatermarkOptions {
+    /// 常驻内存（RSS）上限（字节）
+    pub rss_bytes: f64,
+    /// 超出水位后渲染宽度乘以该系数（0.1-1，默认 0.5）
+    pub width_factor: Option<f64>,
+    /// 降级后的最小渲染宽度（像素，默认 640）
+    pub min_width: Option<u32>,
+}
+
+/// 内存水位状态
+#[napi(object)]
+pub struct MemoryStatus {
+    /// 当前进程的常驻内存（字节），当前平台读取不到时为空
+    pub resident_bytes: Option<f64>,
+    /// 当前水位（字节），未设置时为空
+    pub watermark_bytes: Option<f64>,
+    /// 是否超出水位（新开始的页面会降级渲染）
+    pub over_watermark: bool,
+    /// 进程启动以来降级渲染的页数
+    pub degraded_pages: f64,
+    /// 空闲回收的 TTL（毫秒），未启用时为空
+    pub idle_ttl_ms: Option<u32>,
+    /// 进行中的任务数（所有模块实例）
+    pub active_tasks: u32,
+    /// 进程内没有进行中任务的时长（毫秒）
+    pub idle_ms: f64,
+    /// 空闲回收和 trimMemory 清除的渲染缓存页数（进程启动以来）
+    pub reaped_cache_entries: f64,
+    /// 空闲回收和 trimMemory 清除的渲染缓存字节数
+    pub reaped_cache_bytes: f64,
+    /// 把空闲堆内存归还给操作系统的次数
+    pub allocator_trims: f64,
+}
+
+/// 空闲回收设置
+#[napi(object)]
+pub struct IdleReaperOptions {
+    /// 空闲时间（毫秒）：渲染缓存中超过该时间未使用的页面被清除，进程内没有进行中的任务
+    /// 超过该时间后把空闲堆内存归还给操作系统
+    pub idle_ttl_ms: u32,
+}
+
+/// trimMemory 的结果
+#[napi(object)]
+pub struct TrimMemoryResult {
+    /// 清除的渲染缓存页数
+    pub cache_entries: u32,
+    /// 清除的渲染缓存字节数
+    pub cache_bytes: f64,
+    /// 是否把空闲堆内存归还给了操作系统（仅 glibc 上执行）
+    pub allocator_trimmed: bool,
+    /// 回收前后的常驻内存（字节），当前平台读取不到时为空
+    pub resident_before: Option<f64>,
+    pub resident_after: Option<f64>,
+}
+
+/// 设置进程级的软内存水位，传 null 取消
 ///
-/// # Returns
-/// PDF 的总页数
+/// 常驻内存超出水位后，之后开始的页面不会失败，而是按 `widthFactor` 缩小渲染宽度、
+/// 改用最快的编码参数（WebP method 0、PNG 压缩级别 1），结果的 renderInfo.memoryDegraded 为 true。
+/// 内存回落到水位以下后自动恢复。常驻内存读取自 /proc，其他平台不会降级。
 #[napi]
-pub fn get_page_count_from_file(file_path: String) -> Result<u32> {
-    let pdfium = create_pdfium()?;
-    
-    let document = pdfium
-        .load_pdf_from_file(&file_path, None)
-        .map_err(|e| Error::from_reason(format!("Failed to load PDF: {}", e)))?;
-    
-    Ok(document.pages().len() as u32)
+pub fn set_memory_watermark(options: Option<MemoryWatermarkOptions>) {
+    memory::configure(options.map(|o| memory::Watermark {
+        rss_bytes: o.rss_bytes.max(0.0) as u64,
+        width_factor: o.width_factor.unwrap_or(memory::Watermark::DEFAULT_WIDTH_FACTOR as f64).clamp(0.1, 1.0) as f32,
+        min_width: o.min_width.unwrap_or(memory::Watermark::DEFAULT_MIN_WIDTH),
+    }));
 }
 
-/// 获取 PDF 页数（不渲染）
+/// 获取当前常驻内存和水位状态
+#[napi]
+pub fn get_memory_status() -> MemoryStatus {
+    let resident = memory::resident_bytes();
+    let watermark = memory::watermark();
+    let reaper = reaper::stats();
+    MemoryStatus {
+        resident_bytes: resident.map(|b| b as f64),
+        watermark_bytes: watermark.map(|w| w.rss_bytes as f64),
+        over_watermark: matches!((resident, watermark), (Some(rss), Some(w)) if rss > w.rss_bytes),
+        degraded_pages: memory::degraded_pages() as f64,
+        idle_ttl_ms: reaper.idle_ttl.map(|ttl| ttl.as_millis() as u32),
+        active_tasks: reaper.active_tasks as u32,
+        idle_ms: reaper.idle_for.as_millis() as f64,
+        reaped_cache_entries: reaper.reaped_entries as f64,
+        reaped_cache_bytes: reaper.reaped_bytes as f64,
+        allocator_trims: reaper.allocator_trims as f64,
+    }
+}
+
+/// 设置空闲回收，传 null 或 idleTtlMs 为 0 时关闭
 ///
-/// # Arguments
-/// * `pdf_buffer` - PDF 文件的二进制数据
+/// 常驻的 worker 在流量高峰之间空闲时，后台线程定期清除超过 TTL 未使用的渲染缓存，
+/// 进程内空闲超过 TTL 后把空闲堆内存归还给操作系统。预加载的文档（preloadDocument）
+/// 由调用方管理，不会被回收。
+/// 设置是进程级的，所有模块实例共享。
+#[napi]
+pub fn configure_idle_reaper(options: Option<IdleReaperOptions>) {
+    reaper::configure(options.map(|o| std::time::Duration::from_millis(o.idle_ttl_ms as u64)));
+}
+
+/// 立即回收空闲资源
 ///
-/// # Returns
-/// PDF 的总页数
+/// 清空渲染缓存（保留容量设置），并把空闲堆内存归还给操作系统。适合在流量高峰结束、或收到内存告警时调用。
 #[napi]
-pub fn get_page_count(pdf_buffer: Buffer) -> Result<u32> {
-    let pdfium = create_pdfium()?;
-    
-    let document = pdfium
-        .load_pdf_from_byte_slice(&pdf_buffer, None)
-        .map_err(|e| Error::from_reason(format!("Failed to load PDF: {}", e)))?;
-    
-    Ok(document.pages().len() as u32)
+pub fn trim_memory() -> TrimMemoryResult {
+    let resident_before = memory::resident_bytes();
+    let reaped = reaper::trim_now();
+    TrimMemoryResult {
+        cache_entries: reaped.cache_entries,
+        cache_bytes: reaped.cache_bytes as f64,
+        allocator_trimmed: reaped.allocator_trimmed,
+        resident_before: resident_before.map(|b| b as f64),
+        resident_after: memory::resident_bytes().map(|b| b as f64),
+    }
 }
 
-/// 渲染单页到原始位图（不编码）
+/// PDFium 动态库搜索设置
+#[napi(object)]
+pub struct PdfiumLibraryOptions {
+    /// 依次尝试的库文件名（如 `libpdfium.so.1`），也可以是完整路径
+    pub names: Option<Vec<String>>,
+    /// 在这些目录中查找 `names`（按顺序）；不传时按文件名交给系统加载器搜索
+    pub dirs: Option<Vec<String>>,
+    /// 自定义候选都失败后是否继续尝试默认位置（当前目录和系统库，默认 true）
+    pub include_defaults: Option<bool>,
+    /// 库曾经加载成功、之后绑定失败（如容器层变化导致库文件丢失）时的自动重试次数（默认 2，0 表示不重试）
+    pub rebind_attempts: Option<u32>,
+    /// 重试间隔（毫秒，第 n 次重试等待 n 倍，默认 100）
+    pub rebind_delay_ms: Option<u32>,
+}
+
+/// 设置 PDFium 动态库的文件名和搜索顺序
 ///
-/// 这个函数只进行 PDFium 渲染，跳过图像编码步骤，
-/// 返回原始 RGBA 像素数据。编码工作可以交给 Sharp 等更高效的库。
+/// 默认只查找平台标准库名（如 `libpdfium.so`），无法加载发行版自带的
+/// `libpdfium.so.1` 或改过名的库。设置后对之后新建的 PDFium 实例生效（已常驻的库被放弃、
+/// 按新的顺序重新探测），可以用 `getDiagnostics` 查看实际的探测顺序和结果。
+#[napi]
+pub fn configure_pdfium_library(options: PdfiumLibraryOptions) {
+    diagnostics::set_library_search(diagnostics::LibrarySearch {
+        names: options.names.unwrap_or_default(),
+        dirs: options.dirs.unwrap_or_default(),
+        include_defaults: options.include_defaults.unwrap_or(true),
+        rebind_attempts: options.rebind_attempts.unwrap_or(2),
+        rebind_delay_ms: options.rebind_delay_ms.unwrap_or(100),
+    });
+}
+
+/// 注册命名的渲染配置，替换之前注册的全部配置
 ///
-/// # Arguments
-/// * `file_path` - PDF 文件路径
-/// * `page_num` - 页码（从 1 开始）
-/// * `options` - 渲染选项
+/// `json` 是以配置名为键、RenderOptions 为值的 JSON 对象，如
+/// `{"preview":{"targetWidth":640,"format":"jpg"},"archive":{"dpi":300,"format":"png"}}`。
+/// 之后的调用通过 `profile` 选项引用，调用中设置的选项覆盖配置中的值。
+/// 配置在进程内共享；JSON 无效、选项类型不对或包含 correlationId、cancelToken 时抛出异常，
+/// 已注册的配置保持不变。返回注册的配置名（按名称排序）。
+#[napi]
+pub fn load_profiles(env: Env, json: String) -> Result<Vec<String>> {
+    let global_json: napi::JsObject = env.get_global()?.get_named_property("JSON")?;
+    let parse: JsFunction = global_json.get_named_property("parse")?;
+    let value = parse.call(Some(&global_json), &[env.create_string(&json)?])?;
+    let object = plain_object(value).ok_or_else(|| Error::from_reason("Profiles must be a JSON object keyed by profile name"))?;
+
+    let names = object.get_property_names()?;
+    let mut profiles = HashMap::new();
+    for i in 0..names.get_array_length()? {
+        let name: String = names.get_element::<napi::JsString>(i)?.into_utf8()?.into_owned()?;
+        let options = plain_object(object.get_named_property::<napi::JsUnknown>(&name)?)
+            .ok_or_else(|| Error::from_reason(format!("Profile {} must be an object", name)))?;
+        // SAFETY: options 是当前环境中刚取出的 JS 对象
+        let options = unsafe { RenderOptions::from_napi_value(env.raw(), napi::NapiRaw::raw(&options)) }
+            .map_err(|e| Error::from_reason(format!("Invalid options in profile {}: {}", name, e.reason)))?;
+        profiles.insert(name, options);
+    }
+    profiles::load(profiles).map_err(Error::from_reason)
+}
+
+/// 非数组的 JS 对象
+fn plain_object(value: napi::JsUnknown) -> Option<napi::JsObject> {
+    if value.get_type().ok()? != napi::ValueType::Object || value.is_array().ok()? {
+        return None;
+    }
+    // SAFETY: 已确认是对象
+    Some(unsafe { value.cast() })
+}
+
+/// initPdfium 的结果
+#[napi(object)]
+pub struct PdfiumInitInfo {
+    /// 实际加载的库路径
+    pub loaded_from: Option<String>,
+    /// 绑定耗时（毫秒）
+    pub bind_time: u32,
+    /// 测试渲染耗时（毫秒）
+    pub test_render_time: u32,
+}
+
+/// 立即加载 PDFium 库并常驻进程
 ///
-/// # Returns
-/// 包含原始位图数据和元信息的结果
+/// 传入 `libPath` 时只使用该路径的库（不再回退到当前目录和系统库），适合 Electron、
+/// pkg 等把库打包在自定义位置的应用；不传时按 `configurePdfiumLibrary` 的搜索顺序加载。
+/// 加载成功后执行一次测试渲染，之后的调用直接绑定到这个库，不再探测和重新加载。
+/// 加载或测试渲染失败时抛出 `PDFIUM_UNAVAILABLE` 错误。
 #[napi]
-pub fn render_page_to_raw_bitmap(
-    file_path: String,
-    page_num: u32,
-    options: Option<RenderOptions>,
-) -> Result<RawBitmapResult> {
+pub fn init_pdfium(lib_path: Option<String>) -> Result<PdfiumInitInfo> {
+    if let Some(path) = lib_path {
+        let search = diagnostics::library_search();
+        diagnostics::set_library_search(diagnostics::LibrarySearch {
+            names: vec![path],
+            dirs: Vec::new(),
+            include_defaults: false,
+            ..search
+        });
+    }
+
+    let bind_start = std::time::Instant::now();
+    let pdfium = create_pdfium()?;
+    let bind_time = bind_start.elapsed().as_millis() as u32;
+
     let render_start = std::time::Instant::now();
-    let opts = options.unwrap_or_default();
+    diagnostics::test_render(&pdfium).map_err(|e| {
+        Error::from_reason(format!("{}: {}", ErrorCode::PdfiumUnavailable.as_str(), e))
+    })?;
+
+    Ok(PdfiumInitInfo {
+        loaded_from: diagnostics::resident_library(),
+        bind_time,
+        test_render_time: render_start.elapsed().as_millis() as u32,
+    })
+}
+
+/// 预加载文档并常驻内存，返回文档 ID
+///
+/// 文档数据（文件路径或内存数据）和打开时成功的密码在 `releaseDocument` 之前一直保留在原生内存中，
+/// 之后的渲染不再依次尝试候选密码。常驻的不是解析后的文档：每次 `renderPreloadedPages`
+/// 都绑定 PDFium 并重新解析文档，请求之间不占用 PDFium，其他渲染调用照常进行。
+/// 适合查看器会话按需渲染任意页面和缩放级别。`options` 只用于检查文件大小限制和提供候选密码（passwords）。
+#[napi(ts_args_type = "input: string | Buffer, options?: RenderOptions")]
+pub fn preload_document(env: Env, input: Either<String, Buffer>, options: Option<RenderOptions>) -> Result<u32> {
+    let module_state = context::instance(&env)?;
+    let _task = module_state.lifecycle.begin_task().map_err(Error::from_reason)?;
+    let config = build_config(&resolve_options(options)?);
+    let limits = config.limits;
+
+    let source = match input {
+        Either::A(path) => {
+            let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            limits.check_file_size(file_size).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::File(path)
+        }
+        Either::B(buffer) => {
+            limits.check_file_size(buffer.len() as u64).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::Bytes(buffer.to_vec())
+        }
+    };
+
+    let document = pinned::PinnedDocument::open(source, config.passwords, None).map_err(Error::from_reason)?;
+    Ok(module_state.pin_document(document))
+}
+
+/// 渲染预加载文档的页面
+///
+/// 在后台线程中渲染，返回 Promise。同一文档的请求按提交顺序依次处理，
+/// 不同文档之间互不阻塞。
+#[napi(ts_return_type = "Promise<RenderResult>")]
+pub fn render_preloaded_pages(
+    env: Env,
+    document_id: u32,
+    page_nums: Vec<u32>,
+    options: Option<RenderOptions>,
+) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let opts = resolve_options(options)?;
+    let correlation_id = opts.correlation_id.clone();
     let config = build_config(&opts);
+    let (resolved_config, config_hash) = config_echo(&config);
+    let meter = usage_meter(&opts);
+    let thread_meter = meter.clone();
 
-    let pdfium = match create_pdfium() {
-        Ok(p) => p,
-        Err(e) => {
-            return Ok(RawBitmapResult {
+    let module_state = context::instance(&env)?;
+    let task = module_state.lifecycle.begin_task();
+    let document = module_state.pinned_document(document_id);
+
+    env.spawn_future(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let _task = task?;
+            let _usage = thread_meter.as_ref().map(usage::attach);
+            let document = document.ok_or_else(|| format!("Unknown document: {}", document_id))?;
+            document.render(page_nums, config)
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+        let total_time = start_time.elapsed().as_millis() as u32;
+        let resource_usage = meter.map(|meter| meter.usage().into());
+        Ok(match result {
+            Ok((num_pages, pages)) => RenderResult {
+                success: true,
+                error: None,
+                num_pages,
+                cancelled: was_cancelled(&pages),
+                pages,
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index: None,
+                resource_usage,
+            },
+            Err(e) => RenderResult {
                 success: false,
-                error: Some(e.to_string()),
-                width: 0,
-                height: 0,
-                channels: 4,
-                buffer: Buffer::from(vec![]),
-                render_time: render_start.elapsed().as_millis() as u32,
-            });
+                error: Some(e),
+                num_pages: 0,
+                pages: vec![],
+                total_time,
+                correlation_id,
+                resolved_config,
+                config_hash,
+                password_index: None,
+                cancelled: false,
+                resource_usage,
+            },
+        })
+    })
+}
+
+/// 释放预加载的文档，返回文档是否存在
+///
+/// 进行中的渲染会正常完成，之后文档才被销毁。
+#[napi]
+pub fn release_document(env: Env, document_id: u32) -> Result<bool> {
+    Ok(context::instance(&env)?.release_document(document_id))
+}
+
+/// 常驻文档句柄
+///
+/// 与 `preloadDocument` 相同，文档来源和打开结果（页数、成功的密码）由专属线程持有，
+/// 之后的渲染和查询不再尝试候选密码，流式来源已获取的块不再重新下载，适合查看器
+/// 从同一个大文件逐页渲染。句柄被垃圾回收或调用 `close()` 后释放文档，进行中的请求会正常完成。
+///
+/// 句柄不保留解析后的文档：文档线程只在处理请求时绑定 PDFium 并重新解析文档（使用
+/// 打开时成功的密码），请求之间不占用 PDFium，句柄打开期间其他渲染调用照常进行。
+/// 打开方法需要先解析一遍文档，都返回 Promise，不在 JS 线程上等待。打开选项中的
+/// `documentIdleTimeoutMs` 让空闲的句柄清空流式块缓存。
+#[napi]
+pub struct PdfDocumentHandle {
+    document: Option<std::sync::Arc<pinned::PinnedDocument>>,
+    module_state: std::sync::Arc<context::ModuleState>,
+    /// 流式来源的任务 ID（用于 completeStreamRequest 查找块缓存）
+    stream_task_id: Option<u32>,
+}
+
+#[napi]
+impl PdfDocumentHandle {
+    /// 打开 PDF 文件，返回 Promise（`options` 只用于检查文件大小限制和提供密码）
+    #[napi(ts_return_type = "Promise<PdfDocumentHandle>")]
+    pub fn open_from_file(env: Env, file_path: String, options: Option<RenderOptions>) -> Result<napi::JsObject> {
+        let opts = resolve_options(options)?;
+        let config = build_config(&opts);
+        let file_size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+        config.limits.check_file_size(file_size).map_err(|e| Error::from_reason(e.to_string()))?;
+        let source = scheduler::DocumentSource::File(file_path).into();
+        Self::open(env, context::instance(&env)?, source, config.passwords, idle_timeout(&opts), None)
+    }
+
+    /// 打开内存中的 PDF，返回 Promise（数据会复制一份，之后可以释放传入的 Buffer）
+    #[napi(ts_return_type = "Promise<PdfDocumentHandle>")]
+    pub fn open_from_buffer(env: Env, pdf_buffer: Buffer, options: Option<RenderOptions>) -> Result<napi::JsObject> {
+        let opts = resolve_options(options)?;
+        let config = build_config(&opts);
+        config.limits.check_file_size(pdf_buffer.len() as u64).map_err(|e| Error::from_reason(e.to_string()))?;
+        let source = scheduler::DocumentSource::Bytes(pdf_buffer.to_vec()).into();
+        Self::open(env, context::instance(&env)?, source, config.passwords, idle_timeout(&opts), None)
+    }
+
+    /// 从流式数据源打开 PDF，返回 Promise
+    ///
+    /// `pdfSize`、`fetcher`、`seedBlocks` 与 `renderPagesFromStream` 相同。文档存活期间
+    /// 渲染用到的数据仍按需通过 fetcher 获取（已获取的块会缓存），fetcher 取到数据后
+    /// 调用 `completeStreamRequest`。fetcher 不会阻止进程退出。
+    ///
+    /// 块缓存在多次 `renderPages` 之间共享：每次渲染仍重新解析文档，但 xref 表和页面树
+    /// 所在的块已经缓存，不再重新下载；`streamBlockSize` / `streamMaxCacheBlocks` 等在打开时生效。
+    #[napi(
+        ts_args_type = "pdfSize: number, options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number, correlationId?: string) => void, seedBlocks?: SeedBlock[] | null",
+        ts_return_type = "Promise<PdfDocumentHandle>"
+    )]
+    pub fn open_from_stream(
+        env: Env,
+        pdf_size: f64,
+        options: Option<RenderOptions>,
+        fetcher: JsFunction,
+        seed_blocks: Option<Vec<SeedBlock>>,
+    ) -> Result<napi::JsObject> {
+        let opts = resolve_options(options)?;
+        let known_size = (pdf_size > 0.0).then_some(pdf_size as u64);
+        let config = build_config(&opts);
+        config
+            .limits
+            .check_file_size(known_size.unwrap_or(0))
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+
+        let module_state = context::instance(&env)?;
+        let task_id = module_state.next_task_id();
+        let mut streamer = create_streamer(fetcher, known_size, task_id, &opts, &config, seed_blocks)?;
+        streamer.unref(&env)?;
+        module_state.register_stream(task_id, streamer.get_shared_state());
+
+        let source = match known_size {
+            Some(size) => format!("stream:{}:{}", size, task_id),
+            None => format!("stream:unknown:{}", task_id),
+        };
+        let source = pinned::PinnedSource::Stream { streamer, source };
+        Self::open(env, module_state, source, config.passwords, idle_timeout(&opts), Some(task_id))
+    }
+
+    /// 在后台线程打开文档；失败时注销流式任务
+    fn open(
+        env: Env,
+        module_state: std::sync::Arc<context::ModuleState>,
+        source: pinned::PinnedSource,
+        passwords: Vec<String>,
+        idle_timeout: Option<std::time::Duration>,
+        stream_task_id: Option<u32>,
+    ) -> Result<napi::JsObject> {
+        let task = module_state.lifecycle.begin_task();
+        env.execute_tokio_future(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let _task = task?;
+                    pinned::PinnedDocument::open(source, passwords, idle_timeout)
+                })
+                .await
+                .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))
+            },
+            move |env: &mut Env, opened| match opened {
+                Ok(document) => Ok(PdfDocumentHandle {
+                    document: Some(std::sync::Arc::new(document)),
+                    module_state,
+                    stream_task_id,
+                }
+                .into_instance(*env)?
+                .as_object(*env)),
+                Err(e) => {
+                    if let Some(task_id) = stream_task_id {
+                        module_state.unregister_stream(task_id);
+                    }
+                    Err(Error::from_reason(e))
+                }
+            },
+        )
+    }
+
+    fn document(&self) -> Result<std::sync::Arc<pinned::PinnedDocument>> {
+        self.document
+            .clone()
+            .ok_or_else(|| Error::from_reason("Document handle is closed"))
+    }
+
+    /// 文档总页数
+    #[napi]
+    pub fn page_count(&self) -> Result<u32> {
+        Ok(self.document()?.page_count)
+    }
+
+    /// 成功打开文档的密码在 passwords 中的下标（文档未加密时为空）
+    #[napi(getter)]
+    pub fn password_index(&self) -> Option<u32> {
+        self.document.as_ref().and_then(|d| d.password_index)
+    }
+
+    /// 句柄是否已关闭
+    #[napi(getter)]
+    pub fn closed(&self) -> bool {
+        self.document.is_none()
+    }
+
+    /// 渲染页面，返回 Promise；同一文档的请求按提交顺序依次处理
+    #[napi(ts_return_type = "Promise<RenderResult>")]
+    pub fn render_pages(&self, env: Env, page_nums: Vec<u32>, options: Option<RenderOptions>) -> Result<napi::JsObject> {
+        let start_time = std::time::Instant::now();
+        let document = self.document()?;
+        let opts = resolve_options(options)?;
+        let correlation_id = opts.correlation_id.clone();
+        let mut config = build_config(&opts);
+        config.overlap_fetch = self.stream_task_id.is_some();
+        let (resolved_config, config_hash) = config_echo(&config);
+        let task = self.module_state.lifecycle.begin_task();
+        let password_index = document.password_index;
+        let meter = usage_meter(&opts);
+        let thread_meter = meter.clone();
+
+        env.spawn_future(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let _task = task?;
+                let _usage = thread_meter.as_ref().map(usage::attach);
+                document.render(page_nums, config)
+            })
+            .await
+            .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?;
+
+            let total_time = start_time.elapsed().as_millis() as u32;
+        let resource_usage = meter.map(|meter| meter.usage().into());
+            Ok(match result {
+                Ok((num_pages, pages)) => RenderResult {
+                    success: true,
+                    error: None,
+                    num_pages,
+                    cancelled: was_cancelled(&pages),
+                    pages,
+                    total_time,
+                    correlation_id,
+                    resolved_config,
+                    config_hash,
+                    password_index,
+                    resource_usage,
+                },
+                Err(e) => RenderResult {
+                    success: false,
+                    error: Some(e),
+                    num_pages: 0,
+                    pages: vec![],
+                    total_time,
+                    correlation_id,
+                    resolved_config,
+                    config_hash,
+                    password_index: None,
+                    cancelled: false,
+                    resource_usage,
+                },
+            })
+        })
+    }
+
+    /// 单页概况（尺寸、旋转、标签、是否有文字和图片），返回 Promise
+    #[napi(ts_return_type = "Promise<PageOverview>")]
+    pub fn get_page_info(&self, env: Env, page_num: u32) -> Result<napi::JsObject> {
+        let document = self.document()?;
+        env.spawn_future(async move {
+            tokio::task::spawn_blocking(move || document.page_info(page_num))
+                .await
+                .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))?
+                .map_err(Error::from_reason)
+        })
+    }
+
+    /// 释放文档，返回句柄此前是否打开；之后调用其他方法会报错
+    #[napi]
+    pub fn close(&mut self) -> bool {
+        if let Some(task_id) = self.stream_task_id.take() {
+            self.module_state.unregister_stream(task_id);
         }
-    };
+        self.document.take().is_some()
+    }
+}
+
+/// 常驻文档清空流式块缓存前的空闲时间，0 表示不清空
+fn idle_timeout(opts: &RenderOptions) -> Option<std::time::Duration> {
+    opts.document_idle_timeout_ms
+        .filter(|&ms| ms > 0)
+        .map(|ms| std::time::Duration::from_millis(ms as u64))
+}
+
+impl Drop for PdfDocumentHandle {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// 渲染调度器选项
+#[napi(object)]
+pub struct SchedulerOptions {
+    /// 每个租户同时渲染的最大页数（默认不限制）
+    pub tenant_concurrency: Option<u32>,
+    /// 每个租户在统计窗口内的 CPU 时间预算（毫秒，默认不限制）
+    ///
+    /// 超出预算的租户不会被拒绝，只在其他租户都没有可运行的任务时才调度。
+    pub tenant_cpu_time_ms: Option<u32>,
+    /// CPU 时间的统计窗口（毫秒，默认 60000）
+    pub tenant_window_ms: Option<u32>,
+    /// 按租户覆盖 tenantConcurrency / tenantCpuTimeMs
+    pub tenant_quotas: Option<Vec<TenantQuota>>,
+}
+
+/// 单个租户的限额
+#[napi(object)]
+pub struct TenantQuota {
+    /// 租户标记（与 RenderOptions.tenant 一致）
+    pub tenant: String,
+    /// 同时渲染的最大页数
+    pub concurrency: Option<u32>,
+    /// 统计窗口内的 CPU 时间预算（毫秒）
+    pub cpu_time_ms: Option<u32>,
+}
+
+/// 调度任务的渲染结果（通过回调逐页返回）
+#[napi(object)]
+pub struct ScheduledPageResult {
+    /// scheduleRender 返回的任务 ID
+    pub job_id: u32,
+    /// 同一次 scheduleRender 调用的页面共享同一个文档 ID
+    pub document_id: u32,
+    /// 任务优先级
+    pub priority: i32,
+    /// 租户标记
+    pub tenant: Option<String>,
+    /// 入队到开始渲染的等待时间（毫秒）
+    pub queue_time: u32,
+    /// 页面结果
+    pub page: PageResult,
+}
+
+/// 渲染调度器统计
+#[napi(object)]
+pub struct SchedulerStats {
+    /// 排队中的任务数
+    pub queued: u32,
+    /// 正在渲染的任务数
+    pub running: u32,
+    /// 已完成的任务数
+    pub completed: f64,
+    /// 有排队、运行中任务或预算用量的租户（按租户标记排序，未标记的任务在最前）
+    pub tenants: Vec<TenantStats>,
+}
+
+/// 单个租户的调度统计
+#[napi(object)]
+pub struct TenantStats {
+    /// 租户标记（未标记的任务为空）
+    pub tenant: Option<String>,
+    pub queued: u32,
+    pub running: u32,
+    /// 当前统计窗口内已用的 CPU 时间（毫秒）
+    pub cpu_time_ms: f64,
+    /// 是否超出 CPU 时间预算
+    pub over_budget: bool,
+}
 
-    let document = match pdfium.load_pdf_from_file(&file_path, None) {
-        Ok(d) => d,
-        Err(e) => {
-            return Ok(RawBitmapResult {
-                success: false,
-                error: Some(format!("Failed to load PDF: {}", e)),
-                width: 0,
-                height: 0,
-                channels: 4,
-                buffer: Buffer::from(vec![]),
-                render_time: render_start.elapsed().as_millis() as u32,
-            });
-        }
+/// 关闭调度器选项
+#[napi(object)]
+pub struct CloseSchedulerOptions {
+    /// 是否取消排队中的任务（以 CANCELLED 错误码回调，默认 false：处理完队列后关闭）
+    pub cancel_pending: Option<bool>,
+}
+
+/// 创建跨文档的优先级渲染调度器
+///
+/// 多个文档的页面可以放入同一个调度器，工作线程按优先级（数值大的先渲染，
+/// 相同优先级按入队顺序）取任务，每页完成后调用 `callback(err, result)`。
+/// 多个租户共用调度器时，可以用 tenantConcurrency / tenantCpuTimeMs 限制单个租户
+/// 同时渲染的页数和 CPU 时间，避免大批量任务拖慢其他租户。
+/// 调度器持有回调，不再使用时需要调用 `closeRenderScheduler`，否则进程不会退出。
+#[napi(ts_args_type = "options: SchedulerOptions | undefined | null, callback: (err: Error | null, result: ScheduledPageResult) => void")]
+pub fn create_render_scheduler(env: Env, options: Option<SchedulerOptions>, callback: JsFunction) -> Result<u32> {
+    let module_state = context::instance(&env)?;
+    // 只检查当前是否在关闭，任务计数由每个排队任务自己持有
+    drop(module_state.lifecycle.begin_task().map_err(Error::from_reason)?);
+
+    let options = options.unwrap_or(SchedulerOptions {
+        tenant_concurrency: None,
+        tenant_cpu_time_ms: None,
+        tenant_window_ms: None,
+        tenant_quotas: None,
+    });
+    let quota = |concurrency: Option<u32>, cpu_time_ms: Option<u32>| scheduler::TenantQuota {
+        concurrency: concurrency.map(|n| n.max(1)),
+        cpu_time: cpu_time_ms.map(|ms| std::time::Duration::from_millis(ms as u64)),
+    };
+    let limits = scheduler::TenantLimits {
+        default: quota(options.tenant_concurrency, options.tenant_cpu_time_ms),
+        overrides: options
+            .tenant_quotas
+            .unwrap_or_default()
+            .into_iter()
+            .map(|q| (q.tenant, quota(q.concurrency, q.cpu_time_ms)))
+            .collect(),
+        window: std::time::Duration::from_millis(options.tenant_window_ms.unwrap_or(60_000).max(1) as u64),
     };
 
-    let renderer = renderer::PdfRenderer::new(&pdfium, config);
-    let result = renderer.render_page_to_raw_bitmap(&document, page_num);
-    
-    Ok(result)
+    let tsfn: ThreadsafeFunction<ScheduledPageResult, ErrorStrategy::CalleeHandled> = callback
+        .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<ScheduledPageResult>| Ok(vec![ctx.value]))?;
+
+    Ok(module_state.register_scheduler(scheduler::Scheduler::start(limits, tsfn)))
 }
 
-/// 从 Buffer 渲染单页到原始位图（不编码）
-#[napi]
-pub fn render_page_to_raw_bitmap_from_buffer(
-    pdf_buffer: Buffer,
-    page_num: u32,
+/// 把一个文档的若干页放入调度器，返回各页的任务 ID（与 pageNums 顺序一致）
+///
+/// `input` 为文件路径或 PDF Buffer。同一次调用的页面共享文档和渲染选项，
+/// 工作线程会复用最近打开的文档。`options.tenant` 标记任务所属的租户。
+#[napi(ts_args_type = "schedulerId: number, input: string | Buffer, pageNums: number[], priority?: number, options?: RenderOptions")]
+pub fn schedule_render(
+    env: Env,
+    scheduler_id: u32,
+    input: Either<String, Buffer>,
+    page_nums: Vec<u32>,
+    priority: Option<i32>,
     options: Option<RenderOptions>,
-) -> Result<RawBitmapResult> {
-    let render_start = std::time::Instant::now();
-    let opts = options.unwrap_or_default();
+) -> Result<Vec<u32>> {
+    let module_state = context::instance(&env)?;
+    let scheduler = module_state
+        .scheduler(scheduler_id)
+        .ok_or_else(|| Error::from_reason(format!("Unknown scheduler: {}", scheduler_id)))?;
+    let opts = resolve_options(options)?;
     let config = build_config(&opts);
 
-    let pdfium = match create_pdfium() {
-        Ok(p) => p,
-        Err(e) => {
-            return Ok(RawBitmapResult {
-                success: false,
-                error: Some(e.to_string()),
-                width: 0,
-                height: 0,
-                channels: 4,
-                buffer: Buffer::from(vec![]),
-                render_time: render_start.elapsed().as_millis() as u32,
-            });
+    let source = match input {
+        Either::A(path) => {
+            let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            config.limits.check_file_size(file_size).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::File(path)
         }
-    };
-
-    let document = match pdfium.load_pdf_from_byte_slice(&pdf_buffer, None) {
-        Ok(d) => d,
-        Err(e) => {
-            return Ok(RawBitmapResult {
-                success: false,
-                error: Some(format!("Failed to load PDF: {}", e)),
-                width: 0,
-                height: 0,
-                channels: 4,
-                buffer: Buffer::from(vec![]),
-                render_time: render_start.elapsed().as_millis() as u32,
-            });
+        Either::B(buffer) => {
+            config.limits.check_file_size(buffer.len() as u64).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::Bytes(buffer.to_vec())
         }
     };
+    config.limits.check_pages(page_nums.len()).map_err(|e| Error::from_reason(e.to_string()))?;
 
-    let renderer = renderer::PdfRenderer::new(&pdfium, config);
-    let result = renderer.render_page_to_raw_bitmap(&document, page_num);
-    
-    Ok(result)
-}
+    let mut pages = Vec::with_capacity(page_nums.len());
+    for page_num in page_nums {
+        let guard = module_state.lifecycle.begin_task().map_err(Error::from_reason)?;
+        pages.push((module_state.next_job_id(), page_num, guard));
+    }
+    let job_ids = pages.iter().map(|(id, _, _)| *id).collect();
 
-/// 检查 PDFium 库是否可用
-#[napi]
-pub fn is_pdfium_available() -> bool {
-    create_pdfium().is_ok()
+    let document = std::sync::Arc::new(scheduler::ScheduledDocument {
+        id: module_state.next_document_id(),
+        source,
+        config,
+        tenant: opts.tenant,
+    });
+    scheduler
+        .enqueue(&document, pages, priority.unwrap_or(0))
+        .map_err(Error::from_reason)?;
+    Ok(job_ids)
 }
 
-/// 预热 PDFium 库
-/// 
-/// 在服务启动时调用，提前加载 PDFium 动态库并初始化，
-/// 避免首次请求时的冷启动延迟（约 1-2 秒）
-/// 
-/// # Returns
-/// 预热耗时（毫秒）
+/// 查询调度器的队列状态
 #[napi]
-pub fn warmup() -> Result<u32> {
-    let start_time = std::time::Instant::now();
-    
-    let pdfium = create_pdfium()?;
-    
-    let minimal_pdf = b"%PDF-1.4
-1 0 obj<</Type/Catalog/Pages 2 0 R>>endobj
-2 0 obj<</Type/Pages/Kids[3 0 R]/Count 1>>endobj
-3 0 obj<</Type/Page/MediaBox[0 0 612 792]/Parent 2 0 R>>endobj
-xref
-0 4
-0000000000 65535 f 
-0000000009 00000 n 
-0000000052 00000 n 
-0000000101 00000 n 
-trailer<</Size 4/Root 1 0 R>>
-startxref
-170
-%%EOF";
-    
-    let _ = pdfium.load_pdf_from_byte_slice(minimal_pdf, None);
-    
-    Ok(start_time.elapsed().as_millis() as u32)
+pub fn get_render_scheduler_stats(env: Env, scheduler_id: u32) -> Result<SchedulerStats> {
+    let scheduler = context::instance(&env)?
+        .scheduler(scheduler_id)
+        .ok_or_else(|| Error::from_reason(format!("Unknown scheduler: {}", scheduler_id)))?;
+    let counts = scheduler.counts();
+    Ok(SchedulerStats {
+        queued: counts.queued,
+        running: counts.running,
+        completed: counts.completed as f64,
+        tenants: counts
+            .tenants
+            .into_iter()
+            .map(|t| TenantStats {
+                tenant: t.tenant,
+                queued: t.queued,
+                running: t.running,
+                cpu_time_ms: t.cpu_time.as_secs_f64() * 1000.0,
+                over_budget: t.over_budget,
+            })
+            .collect(),
+    })
 }
 
-/// 获取版本信息
+/// 关闭调度器，返回被取消的排队任务数
+///
+/// 关闭后不再接收新任务。正在渲染的页面会正常完成并回调；排队中的任务
+/// 默认继续处理，`cancelPending` 为 true 时立即以 CANCELLED 错误码回调。
 #[napi]
-pub fn get_version() -> String {
-    format!("pdf-renderer v{}", env!("CARGO_PKG_VERSION"))
+pub fn close_render_scheduler(env: Env, scheduler_id: u32, options: Option<CloseSchedulerOptions>) -> Result<u32> {
+    let scheduler = context::instance(&env)?
+        .remove_scheduler(scheduler_id)
+        .ok_or_else(|| Error::from_reason(format!("Unknown scheduler: {}", scheduler_id)))?;
+    let cancel_pending = options.and_then(|o| o.cancel_pending).unwrap_or(false);
+    Ok(scheduler.close(cancel_pending, "Scheduler closed"))
 }
 
-/// 流式渲染结果（包含额外的统计信息）
+/// 批量渲染会话状态
 #[napi(object)]
-pub struct StreamRenderResult {
-    /// 是否成功
-    pub success: bool,
-    /// 错误信息（如果整体失败）
-    pub error: Option<String>,
-    /// PDF 总页数
+pub struct RenderSessionStatus {
+    /// running、paused、completed、cancelled 或 failed
+    pub status: String,
+    /// 本次会话要渲染的页数（页码为空时在文档加载后确定）
+    pub total: u32,
+    /// 已完成的页数（包括失败的页面）
+    pub completed: u32,
+    /// 尚未开始渲染的页数
+    pub pending: u32,
+    /// 被取消的页数
+    pub cancelled: u32,
+    /// 已完成、尚未被 pollRenderSession 取走的页数
+    pub ready: u32,
+    /// PDF 总页数（文档加载前为 0）
     pub num_pages: u32,
-    /// 每页的渲染结果
-    pub pages: Vec<PageResult>,
-    /// 总耗时（毫秒）
-    pub total_time: u32,
-    /// 流式加载统计
-    pub stream_stats: Option<StreamStats>,
+    /// 文档加载失败的原因
+    pub error: Option<String>,
+    /// 渲染已结束且所有结果都已取走，之后不会再有新结果
+    pub done: bool,
 }
 
-/// 流式加载统计信息
+/// 一次轮询取到的页面
 #[napi(object)]
-pub struct StreamStats {
-    /// 总请求次数
-    pub total_requests: u32,
-    /// 缓存命中次数
-    pub cache_hits: u32,
-    /// 缓存未命中次数
-    pub cache_misses: u32,
-    /// 总下载字节数
-    pub total_bytes_fetched: i64,
+pub struct RenderSessionPoll {
+    /// 按完成顺序排列的页面结果
+    pub pages: Vec<PageResult>,
+    /// 轮询后的会话状态
+    pub status: RenderSessionStatus,
 }
 
-/// 从流式数据源渲染 PDF 页面（异步版本）
-///
-/// 这个函数在独立线程中运行 PDFium 渲染，返回 Promise。
-/// 主线程保持事件循环运行，可以处理 JS 回调。
-///
-/// # Arguments
-/// * `env` - NAPI 环境
-/// * `pdf_size` - PDF 文件的总大小（字节）
-/// * `page_nums` - 要渲染的页码数组（从 1 开始）
-/// * `options` - 渲染配置选项
-/// * `fetcher` - JavaScript 回调函数，用于获取指定范围的数据
+impl From<session::SessionSnapshot> for RenderSessionStatus {
+    fn from(snapshot: session::SessionSnapshot) -> Self {
+        Self {
+            status: snapshot.status.as_str().to_string(),
+            total: snapshot.total,
+            completed: snapshot.completed,
+            pending: snapshot.pending,
+            cancelled: snapshot.cancelled,
+            ready: snapshot.ready,
+            num_pages: snapshot.num_pages,
+            error: snapshot.error,
+            done: snapshot.done,
+        }
+    }
+}
+
+fn render_session(env: &Env, session_id: u32) -> Result<std::sync::Arc<session::RenderSession>> {
+    context::instance(env)?
+        .session(session_id)
+        .ok_or_else(|| Error::from_reason(format!("Unknown session: {}", session_id)))
+}
+
+/// 开始批量渲染会话，返回会话 ID
 ///
-/// # Returns
-/// Promise<StreamRenderResult>
-#[napi(
-    ts_args_type = "pdfSize: number, pageNums: number[], options: RenderOptions | null | undefined, fetcher: (offset: number, size: number, requestId: number) => void"
-)]
-pub fn render_pages_from_stream(
+/// 在后台线程中按顺序逐页渲染 `pageNums`（空数组表示全部页面），会话状态保存在原生侧。
+/// 不传 `callback` 时结果暂存，由 `pollRenderSession` 取走；传入时每页完成后调用
+/// `callback(err, page)`，不再暂存。渲染过程中可以暂停、恢复或取消剩余页面，
+/// 适合用户随时可能放弃的整份文档渲染任务。
+#[napi(ts_args_type = "input: string | Buffer, pageNums: number[], options?: RenderOptions | undefined | null, callback?: (err: Error | null, page: PageResult) => void")]
+pub fn start_render_session(
     env: Env,
-    pdf_size: f64,
+    input: Either<String, Buffer>,
     page_nums: Vec<u32>,
     options: Option<RenderOptions>,
-    fetcher: JsFunction,
-) -> napi::Result<napi::JsObject> {
-    let start_time = std::time::Instant::now();
-    let opts = options.unwrap_or_default();
-    let pdf_size_u64 = pdf_size as u64;
-
-    let config = build_config(&opts);
-
-    let task_id = next_task_id();
+    callback: Option<JsFunction>,
+) -> Result<u32> {
+    let module_state = context::instance(&env)?;
+    let guard = module_state.lifecycle.begin_task().map_err(Error::from_reason)?;
+    let config = build_config(&resolve_options(options)?);
 
-    let tsfn: ThreadsafeFunction<BlockRequest, ErrorStrategy::CalleeHandled> = fetcher
-        .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<BlockRequest>| {
-            let mut obj = ctx.env.create_object()?;
-            obj.set("offset", ctx.value.offset as f64)?;
-            obj.set("size", ctx.value.size)?;
-            obj.set("requestId", ctx.value.request_id)?;
-            Ok(vec![obj])
-        })?;
+    let source = match input {
+        Either::A(path) => {
+            let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            config.limits.check_file_size(file_size).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::File(path)
+        }
+        Either::B(buffer) => {
+            config.limits.check_file_size(buffer.len() as u64).map_err(|e| Error::from_reason(e.to_string()))?;
+            scheduler::DocumentSource::Bytes(buffer.to_vec())
+        }
+    };
+    config.limits.check_pages(page_nums.len()).map_err(|e| Error::from_reason(e.to_string()))?;
 
-    let streamer = JsFileStreamer::new(pdf_size_u64, tsfn, task_id);
-    let shared_state = streamer.get_shared_state();
+    let tsfn = callback
+        .map(|callback| {
+            callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<PageResult>| Ok(vec![ctx.value]))
+        })
+        .transpose()?;
 
-    register_stream_state(task_id, shared_state.clone());
+    let session = session::RenderSession::start(source, page_nums, config, tsfn, guard);
+    Ok(module_state.register_session(session))
+}
 
-    env.execute_tokio_future(
-        async move {
-            let result = tokio::task::spawn_blocking(move || {
-                let pdfium = create_pdfium().map_err(|e| e.to_string())?;
-                let document = pdfium
-                    .load_pdf_from_reader(streamer, None)
-                    .map_err(|e| format!("Failed to load PDF from stream: {}", e))?;
-                let renderer = PdfRenderer::new(&pdfium, config);
-                renderer.render_document_pages(&document, &page_nums)
-            })
-            .await
-            .map_err(|e| napi::Error::from_reason(format!("Task join error: {}", e)))?;
+/// 取走会话中已完成的页面（最多 `maxPages` 个，默认全部）
+///
+/// 返回的 `status.done` 为 true 时会话已结束且没有剩余结果，会话随之释放，
+/// 之后再访问该 ID 会报错。
+#[napi]
+pub fn poll_render_session(env: Env, session_id: u32, max_pages: Option<u32>) -> Result<RenderSessionPoll> {
+    let session = render_session(&env, session_id)?;
+    let (pages, snapshot) = session.poll(max_pages.map_or(usize::MAX, |n| n as usize));
+    if snapshot.done {
+        context::instance(&env)?.remove_session(session_id);
+    }
+    Ok(RenderSessionPoll {
+        pages,
+        status: snapshot.into(),
+    })
+}
 
-            Ok((result, shared_state, start_time, task_id))
-        },
-        |env: &mut Env, (result, shared_state, start_time, task_id): (std::result::Result<(u32, Vec<PageResult>), String>, std::sync::Arc<SharedState>, std::time::Instant, u32)| {
-            unregister_stream_state(task_id);
+/// 查询会话状态（不取走结果）
+#[napi]
+pub fn get_render_session_status(env: Env, session_id: u32) -> Result<RenderSessionStatus> {
+    Ok(render_session(&env, session_id)?.snapshot().into())
+}
 
-            let stats = shared_state.stats.lock().unwrap();
-            let stream_stats = StreamStats {
-                total_requests: stats.total_requests,
-                cache_hits: stats.cache_hits,
-                cache_misses: stats.cache_misses,
-                total_bytes_fetched: stats.total_bytes_fetched as i64,
-            };
+/// 暂停会话：正在渲染的页面完成后不再开始新页面。返回状态是否改变
+///
+/// 暂停期间会话释放 PDFium 和文档，不影响其他渲染调用；恢复后重新打开文档继续渲染。
+#[napi]
+pub fn pause_render_session(env: Env, session_id: u32) -> Result<bool> {
+    Ok(render_session(&env, session_id)?.pause())
+}
 
-            match result {
-                Ok((num_pages, pages)) => {
-                    let mut obj = env.create_object()?;
-                    obj.set("success", true)?;
-                    obj.set("error", env.get_null()?)?;
-                    obj.set("numPages", num_pages)?;
-                    obj.set("pages", pages)?;
-                    obj.set("totalTime", start_time.elapsed().as_millis() as u32)?;
-                    obj.set("streamStats", stream_stats)?;
-                    Ok(obj)
-                }
-                Err(e) => {
-                    let mut obj = env.create_object()?;
-                    obj.set("success", false)?;
-                    obj.set("error", e)?;
-                    obj.set("numPages", 0u32)?;
-                    obj.set("pages", Vec::<PageResult>::new())?;
-                    obj.set("totalTime", start_time.elapsed().as_millis() as u32)?;
-                    obj.set("streamStats", stream_stats)?;
-                    Ok(obj)
-                }
-            }
-        },
-    )
+/// 恢复暂停的会话，返回状态是否改变
+#[napi]
+pub fn resume_render_session(env: Env, session_id: u32) -> Result<bool> {
+    Ok(render_session(&env, session_id)?.resume())
 }
 
-/// 完成流式请求
+/// 取消会话的剩余页面，返回取消的页数
 ///
-/// 当 JS 端获取到数据后，调用这个函数将数据发送给 Rust 端。
+/// 正在渲染的一页仍会完成；已完成的页面保留，仍可通过 `pollRenderSession` 取走。
+#[napi]
+pub fn cancel_render_session(env: Env, session_id: u32) -> Result<u32> {
+    Ok(render_session(&env, session_id)?.cancel())
+}
+
+/// 创建取消令牌，放进 options.cancelToken 后可以用 `cancelRender` 中止渲染
 ///
-/// # Arguments
-/// * `request_id` - 请求 ID
-/// * `data` - 获取到的数据
-/// * `error` - 错误信息（如果获取失败）
+/// 令牌用完后调用 `releaseCancelToken` 释放。
 #[napi]
-pub fn complete_stream_request(
-    request_id: u32,
-    data: Option<Buffer>,
-    error: Option<String>,
-) -> Result<()> {
-    let task_id = request_id >> 16;
-    
-    let states = GLOBAL_STREAM_STATES
-        .lock()
-        .map_err(|e| Error::from_reason(format!("Failed to lock global states: {}", e)))?;
-    
-    if let Some(shared_state) = states.get(&task_id) {
-        let result = match (data, error) {
-            (Some(buffer), _) => Ok(buffer.to_vec()),
-            (None, Some(err)) => Err(err),
-            (None, None) => Err("No data or error provided".to_string()),
-        };
-        shared_state.complete_request(request_id, result);
-    }
-    
-    Ok(())
+pub fn create_cancel_token() -> u32 {
+    cancel::create()
 }
 
-use std::sync::Mutex as StdMutex;
-use std::collections::HashMap;
-use once_cell::sync::Lazy;
-use stream_reader::SharedState;
+/// 取消使用该令牌的渲染，返回令牌是否存在
+///
+/// 正在渲染的一页仍会完成；尚未开始和等待编码的页面以 CANCELLED 错误码返回。
+/// 令牌被取消后，之后使用它的渲染也会立即结束。
+#[napi]
+pub fn cancel_render(token: u32) -> bool {
+    cancel::cancel(token)
+}
 
-static GLOBAL_STREAM_STATES: Lazy<StdMutex<HashMap<u32, std::sync::Arc<SharedState>>>> =
-    Lazy::new(|| StdMutex::new(HashMap::new()));
+/// 令牌是否已被取消（令牌不存在时为 false）
+///
+/// 用于不经过多页渲染的调用方（如逐页渲染原始位图的工作线程）在页与页之间检查。
+#[napi]
+pub fn is_render_cancelled(token: u32) -> bool {
+    cancel::flag(token).is_some_and(|flag| flag.is_cancelled())
+}
 
-static GLOBAL_TASK_ID: Lazy<StdMutex<u32>> = Lazy::new(|| StdMutex::new(0));
+/// 释放取消令牌，返回令牌是否存在
+#[napi]
+pub fn release_cancel_token(token: u32) -> bool {
+    cancel::release(token)
+}
 
-fn next_task_id() -> u32 {
-    let mut id = GLOBAL_TASK_ID.lock().unwrap();
-    let current = *id & 0xFFFF;
-    *id = id.wrapping_add(1);
-    current
+/// 关闭选项
+#[napi(object)]
+pub struct ShutdownOptions {
+    /// 等待进行中任务完成的最长时间（毫秒，默认 30000）
+    pub drain_timeout_ms: Option<u32>,
 }
 
-fn register_stream_state(task_id: u32, state: std::sync::Arc<SharedState>) {
-    GLOBAL_STREAM_STATES.lock().unwrap().insert(task_id, state);
+/// 关闭结果
+#[napi(object)]
+pub struct ShutdownResult {
+    /// 是否所有进行中的任务都已完成
+    pub drained: bool,
+    /// 关闭结束时仍未完成的任务数
+    pub remaining: u32,
+    /// 因超时被取消的流式任务数
+    pub cancelled_streams: u32,
+    /// 因超时被取消的调度任务数（排队中、尚未开始渲染的页面）
+    pub cancelled_jobs: u32,
+    /// 因超时被取消的会话页面数（包括暂停中的会话）
+    pub cancelled_session_pages: u32,
+    /// 关闭耗时（毫秒）
+    pub elapsed: u32,
 }
 
-fn unregister_stream_state(task_id: u32) {
-    GLOBAL_STREAM_STATES.lock().unwrap().remove(&task_id);
+/// 超时取消流式任务后，等待其退出的最长时间
+const CANCEL_GRACE_MS: u64 = 1000;
+
+/// 优雅关闭渲染器
+///
+/// 立即停止接收新任务（新调用会返回 "Renderer is shutting down" 错误），
+/// 然后等待进行中的渲染、流式任务和调度器中已排队的任务完成。超过 `drainTimeoutMs` 后，
+/// 仍在等待数据的流式任务和调度器中尚未开始的任务会被取消。PDFium 实例随每个任务创建和释放，
+/// 任务全部结束后不再持有任何 PDFium 资源。
+///
+/// 关闭是单向的，用于滚动发布时进程退出前的清理。只作用于调用所在的
+/// JS 环境（主线程或某个 worker），其他 worker 中加载的模块实例不受影响。
+#[napi(ts_return_type = "Promise<ShutdownResult>")]
+pub fn shutdown(env: Env, options: Option<ShutdownOptions>) -> Result<napi::JsObject> {
+    let start_time = std::time::Instant::now();
+    let drain_timeout = std::time::Duration::from_millis(
+        options.and_then(|o| o.drain_timeout_ms).unwrap_or(30000) as u64,
+    );
+
+    let module_state = context::instance(&env)?;
+    module_state.lifecycle.begin_shutdown();
+    // 常驻文档随进行中的渲染结束而销毁
+    module_state.release_documents();
+    // 调度器不再接收新任务，已排队的任务继续处理
+    let schedulers = module_state.take_schedulers();
+    for scheduler in &schedulers {
+        scheduler.close(false, lifecycle::SHUTDOWN_ERROR);
+    }
+
+    env.spawn_future(async move {
+        tokio::task::spawn_blocking(move || {
+            let lifecycle = &module_state.lifecycle;
+            let poll_interval = std::time::Duration::from_millis(10);
+
+            while lifecycle.in_flight() > 0 && start_time.elapsed() < drain_timeout {
+                std::thread::sleep(poll_interval);
+            }
+
+            // 超时：取消所有仍在进行的流式任务，让它们尽快失败退出
+            let mut cancelled_streams = 0u32;
+            let mut cancelled_jobs = 0u32;
+            let mut cancelled_session_pages = 0u32;
+            if lifecycle.in_flight() > 0 {
+                cancelled_streams = module_state.cancel_streams(lifecycle::SHUTDOWN_ERROR);
+                cancelled_jobs = schedulers.iter().map(|s| s.close(true, lifecycle::SHUTDOWN_ERROR)).sum();
+                cancelled_session_pages = module_state.cancel_sessions();
+
+                let grace_deadline = std::time::Instant::now() + std::time::Duration::from_millis(CANCEL_GRACE_MS);
+                while lifecycle.in_flight() > 0 && std::time::Instant::now() < grace_deadline {
+                    std::thread::sleep(poll_interval);
+                }
+            }
+
+            let remaining = lifecycle.in_flight() as u32;
+            ShutdownResult {
+                drained: remaining == 0,
+                remaining,
+                cancelled_streams,
+                cancelled_jobs,
+                cancelled_session_pages,
+                elapsed: start_time.elapsed().as_millis() as u32,
+            }
+        })
+        .await
+        .map_err(|e| Error::from_reason(format!("Task join error: {}", e)))
+    })
 }
@@ -0,0 +1,74 @@
+//! 渲染器生命周期管理
+//!
+//! 跟踪进行中的渲染任务数量，并在关闭（shutdown）后拒绝新任务，
+//! 用于滚动发布时的优雅下线：先停止接收任务，再等待进行中的任务完成。
+//! 每个模块实例（主线程、worker_threads、Electron 渲染进程）各自持有一份状态；
+//! 任务的开始和结束同时通知进程级的空闲回收（`reaper`）。
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 关闭后拒绝新任务时返回的错误信息
+pub const SHUTDOWN_ERROR: &str = "Renderer is shutting down";
+
+/// 单个模块实例的生命周期状态
+#[derive(Default)]
+pub struct Lifecycle {
+    /// 是否正在关闭（关闭后拒绝新任务）
+    shutting_down: AtomicBool,
+    /// 进行中的任务数量
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// 进行中任务的守卫，析构时自动减少计数
+pub struct TaskGuard(Arc<AtomicUsize>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+        crate::reaper::task_finished();
+    }
+}
+
+impl Lifecycle {
+    /// 登记一个新任务；正在关闭时返回错误
+    pub fn begin_task(&self) -> Result<TaskGuard, String> {
+        // 先计数再检查标志，保证 shutdown 看到的计数不会漏掉刚开始的任务
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        crate::reaper::task_started();
+        let guard = TaskGuard(Arc::clone(&self.in_flight));
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Err(SHUTDOWN_ERROR.to_string());
+        }
+        Ok(guard)
+    }
+
+    /// 进入关闭状态，此后 `begin_task` 都会失败
+    pub fn begin_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// 当前进行中的任务数量
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_guard_counts_and_shutdown_rejects() {
+        let lifecycle = Lifecycle::default();
+        {
+            let _guard = lifecycle.begin_task().unwrap();
+            assert_eq!(lifecycle.in_flight(), 1);
+        }
+        assert_eq!(lifecycle.in_flight(), 0);
+
+        lifecycle.begin_shutdown();
+        assert!(lifecycle.begin_task().is_err());
+        assert_eq!(lifecycle.in_flight(), 0);
+    }
+}
@@ -0,0 +1,112 @@
+//! 软内存水位
+//!
+//! 进程常驻内存超过水位后，后续页面自动降低渲染宽度、改用更快的编码参数，
+//! 而不是继续按原尺寸渲染直到被 OOM 杀掉。水位是进程级的，所有模块实例共享。
+//! 常驻内存读取自 `/proc/self/status`，其他平台读取不到时不会降级。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// 内存水位与降级方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Watermark {
+    /// 常驻内存（RSS）上限（字节）
+    pub rss_bytes: u64,
+    /// 超出水位后渲染宽度乘以该系数
+    pub width_factor: f32,
+    /// 降级后的最小渲染宽度（像素）
+    pub min_width: u32,
+}
+
+impl Watermark {
+    pub const DEFAULT_WIDTH_FACTOR: f32 = 0.5;
+    pub const DEFAULT_MIN_WIDTH: u32 = 640;
+
+    /// 降级后的宽度：按系数缩小，但不低于最小宽度，也不超过原宽度
+    pub fn degrade_width(&self, width: u32) -> u32 {
+        ((width as f32 * self.width_factor) as u32).max(self.min_width).min(width)
+    }
+}
+
+static WATERMARK: Mutex<Option<Watermark>> = Mutex::new(None);
+
+/// 因超出水位而降级渲染的页数（进程启动以来）
+static DEGRADED_PAGES: AtomicU64 = AtomicU64::new(0);
+
+/// 设置水位，None 表示取消
+pub fn configure(watermark: Option<Watermark>) {
+    *WATERMARK.lock().unwrap() = watermark;
+}
+
+pub fn watermark() -> Option<Watermark> {
+    *WATERMARK.lock().unwrap()
+}
+
+/// 当前进程的常驻内存（字节），读取不到时为 None
+pub fn resident_bytes() -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// 常驻内存超出水位时返回水位设置
+pub fn pressure() -> Option<Watermark> {
+    let watermark = watermark()?;
+    resident_bytes()
+        .is_some_and(|rss| rss > watermark.rss_bytes)
+        .then_some(watermark)
+}
+
+pub fn record_degraded() {
+    DEGRADED_PAGES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn degraded_pages() -> u64 {
+    DEGRADED_PAGES.load(Ordering::Relaxed)
+}
+
+/// 把分配器缓存的空闲内存归还给操作系统，返回是否执行
+///
+/// glibc 在 free 后通常保留堆内存供之后复用，大位图释放后常驻内存不会回落；
+/// malloc_trim 释放这部分内存。其他平台的分配器没有对应接口，不执行。
+pub fn trim_allocator() -> bool {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    {
+        // SAFETY: malloc_trim 只整理分配器内部的空闲块，可以在任意线程调用
+        unsafe { libc::malloc_trim(0) };
+        true
+    }
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    {
+        false
+    }
+}
+
+/// 从 `/proc/self/status` 中解析 `VmRSS:  123456 kB`
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tnode\nVmPeak:\t  900000 kB\nVmRSS:\t  123456 kB\nThreads:\t11\n";
+        assert_eq!(parse_vm_rss(status), Some(123456 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tnode\n"), None);
+    }
+
+    #[test]
+    fn test_degrade_width() {
+        let watermark = Watermark {
+            rss_bytes: 0,
+            width_factor: 0.5,
+            min_width: 640,
+        };
+        assert_eq!(watermark.degrade_width(2560), 1280);
+        assert_eq!(watermark.degrade_width(1000), 640);
+        assert_eq!(watermark.degrade_width(480), 480);
+    }
+}
@@ -0,0 +1,135 @@
+//! 页面对象绘制问题检查
+//!
+//! PDFium 遇到无法绘制的对象（图片数据流损坏、不支持的过滤器、未知对象类型）时
+//! 直接跳过，页面上只留下一块空白，渲染调用本身仍然成功。这里逐个检查页面对象，
+//! 找出可能画不出来的对象及其位置，便于排查“图片显示不全”一类的问题。
+//!
+//! 图片需要完整解码一次，耗时与页面上的图片大小相当，默认不开启（options.objectDiagnostics）。
+
+use pdfium_render::prelude::*;
+use std::ptr;
+
+/// 表单 XObject 的最大嵌套检查深度
+const MAX_FORM_DEPTH: usize = 8;
+
+/// 通用的数据流过滤器，解码后即为像素数据，可以按图片尺寸校验长度
+const STREAM_FILTERS: &[&str] = &[
+    "ASCIIHexDecode",
+    "AHx",
+    "ASCII85Decode",
+    "A85",
+    "LZWDecode",
+    "LZW",
+    "FlateDecode",
+    "Fl",
+    "RunLengthDecode",
+    "RL",
+];
+
+/// 图片专用的过滤器，由 PDFium 在绘制时解码
+const IMAGE_FILTERS: &[&str] = &[
+    "CCITTFaxDecode",
+    "CCF",
+    "DCTDecode",
+    "DCT",
+    "JBIG2Decode",
+    "JPXDecode",
+];
+
+/// 一个可能绘制失败的对象
+#[derive(Debug)]
+pub struct ObjectIssue {
+    /// 页面顶层对象的下标（嵌套在表单 XObject 中的对象报告其所在的顶层对象）
+    pub index: usize,
+    /// 出问题的对象类型：image、unsupported
+    pub kind: &'static str,
+    pub reason: String,
+    /// 对象在页面上的外接矩形（点）
+    pub bounds: Option<PdfRect>,
+}
+
+/// 检查页面的所有对象
+pub fn check_page_objects(page: &PdfPage) -> Vec<ObjectIssue> {
+    let bindings = page.bindings();
+    let mut issues = Vec::new();
+    for (index, object) in page.objects().iter().enumerate() {
+        let bounds = object.bounds().ok().map(|quad| quad.to_rect());
+        let mut report = |kind, reason| {
+            issues.push(ObjectIssue { index, kind, reason, bounds });
+        };
+        check_object(bindings, &object, 0, &mut report);
+    }
+    issues
+}
+
+fn check_object(
+    bindings: &dyn PdfiumLibraryBindings,
+    object: &PdfPageObject,
+    depth: usize,
+    report: &mut impl FnMut(&'static str, String),
+) {
+    match object.object_type() {
+        PdfPageObjectType::Unsupported => report("unsupported", "Unsupported page object type".to_string()),
+        PdfPageObjectType::Image => {
+            if let Some(image) = object.as_image_object() {
+                if let Some(reason) = image_problem(bindings, object, image) {
+                    report("image", reason);
+                }
+            }
+        }
+        PdfPageObjectType::XObjectForm if depth < MAX_FORM_DEPTH => {
+            if let Some(form) = object.as_x_object_form_object() {
+                for child in (0..form.len()).filter_map(|i| form.get(i).ok()) {
+                    check_object(bindings, &child, depth + 1, report);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 图片无法解码的原因，能正常解码时返回 None
+fn image_problem(bindings: &dyn PdfiumLibraryBindings, object: &PdfPageObject, image: &PdfPageImageObject) -> Option<String> {
+    let filters: Vec<String> = image.filters().iter().map(|filter| filter.name().to_string()).collect();
+    if let Some(filter) = filters.iter().find(|name| !is_supported_filter(name)) {
+        return Some(format!("Unsupported image filter: {}", filter));
+    }
+    // PDFium 解码损坏的压缩数据时不报错，只返回截断的数据，绘制出的图片缺少下半部分
+    if filters.iter().all(|name| STREAM_FILTERS.contains(&name.as_str())) {
+        let handle = bindings.get_handle_from_object(object);
+        let decoded = bindings.FPDFImageObj_GetImageDataDecoded(handle, ptr::null_mut(), 0) as u64;
+        if let Some(expected) = expected_data_len(image).filter(|expected| decoded < *expected) {
+            return Some(format!("Image data is truncated or corrupt ({} of {} bytes)", decoded, expected));
+        }
+    }
+    match image.get_raw_bitmap() {
+        Ok(bitmap) if bitmap.width() == 0 || bitmap.height() == 0 => Some("Image has no pixel data".to_string()),
+        Ok(_) => None,
+        Err(e) => Some(format!("Image failed to decode: {}", e)),
+    }
+}
+
+/// 按尺寸和位深计算解码后的数据长度，元数据不可用时返回 None
+fn expected_data_len(image: &PdfPageImageObject) -> Option<u64> {
+    let width = image.width().ok().filter(|w| *w > 0)? as u64;
+    let height = image.height().ok().filter(|h| *h > 0)? as u64;
+    let bits = image.bits_per_pixel().ok().filter(|b| *b > 0)? as u64;
+    Some((width * bits).div_ceil(8) * height)
+}
+
+fn is_supported_filter(name: &str) -> bool {
+    STREAM_FILTERS.contains(&name) || IMAGE_FILTERS.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_names() {
+        assert!(is_supported_filter("FlateDecode"));
+        assert!(is_supported_filter("DCT"));
+        assert!(!is_supported_filter("Crypt"));
+        assert!(!is_supported_filter("flatedecode"));
+    }
+}
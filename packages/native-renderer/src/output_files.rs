@@ -0,0 +1,133 @@
+//! 渲染结果直接写入文件
+//!
+//! 批量转换时编码后的图像最终都要落盘，经过 Node Buffer 中转只会多占一份内存。
+//! `renderPagesToFiles` 在逐页交付回调中把图像写到输出目录，结果只保留路径和统计。
+//!
+//! 文件名模板中的 `%d` 替换为页码，`%0Nd` 替换为补零到 N 位的页码，
+//! 扩展名按页面的实际输出格式追加（webp/png/jpg/tiff）。分块页面每个分块写一个文件，
+//! 文件名追加 `-r{行}-c{列}`。
+
+use crate::PageResult;
+use std::path::Path;
+use std::time::Instant;
+
+/// 默认文件名模板
+pub const DEFAULT_PATTERN: &str = "page-%d";
+
+/// 单页的写入结果
+#[derive(Debug, Default)]
+pub struct WrittenPage {
+    /// 整页图像的路径（分块页面为空）
+    pub path: Option<String>,
+    /// 分块文件的路径（行优先）
+    pub tile_paths: Option<Vec<String>>,
+    /// 写入的总字节数
+    pub bytes: u64,
+    /// 写入耗时（毫秒）
+    pub write_time: u32,
+    /// 写入失败的原因
+    pub error: Option<String>,
+}
+
+/// 检查文件名模板：必须包含一个页码占位符，且不能包含路径分隔符
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    if pattern.contains(['/', '\\']) || pattern == ".." {
+        return Err(format!("Filename pattern must not contain path separators: {}", pattern));
+    }
+    if placeholder(pattern).is_none() {
+        return Err(format!("Filename pattern must contain a page placeholder (%d or %0Nd): {}", pattern));
+    }
+    Ok(())
+}
+
+/// 找到模板中的页码占位符，返回其字节范围和补零宽度
+fn placeholder(pattern: &str) -> Option<(std::ops::Range<usize>, usize)> {
+    let bytes = pattern.as_bytes();
+    let mut start = 0;
+    while let Some(offset) = pattern[start..].find('%') {
+        let at = start + offset;
+        let mut end = at + 1;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+        }
+        if end < bytes.len() && bytes[end] == b'd' {
+            let digits = &pattern[at + 1..end];
+            if digits.is_empty() || digits.starts_with('0') {
+                let width = digits.parse().unwrap_or(0).min(10);
+                return Some((at..end + 1, width));
+            }
+        }
+        start = at + 1;
+    }
+    None
+}
+
+/// 按模板生成文件名（不含扩展名）
+pub fn file_stem(pattern: &str, page_num: u32) -> String {
+    match placeholder(pattern) {
+        Some((range, width)) => format!(
+            "{}{:0width$}{}",
+            &pattern[..range.start],
+            page_num,
+            &pattern[range.end..],
+            width = width
+        ),
+        None => format!("{}{}", pattern, page_num),
+    }
+}
+
+/// 把一页的图像写入输出目录；渲染失败或跳过的页面不写文件
+pub fn write_page(dir: &Path, pattern: &str, page: &PageResult) -> WrittenPage {
+    if !page.success {
+        return WrittenPage::default();
+    }
+    let start = Instant::now();
+    let stem = file_stem(pattern, page.page_num);
+    let mut written = WrittenPage::default();
+    let outcome = match &page.tiles {
+        Some(tiles) => {
+            let mut paths = Vec::with_capacity(tiles.tiles.len());
+            let result = tiles.tiles.iter().try_for_each(|tile| {
+                let path = dir.join(format!("{}-r{}-c{}.{}", stem, tile.row, tile.column, page.format));
+                write_file(&path, &tile.buffer, &mut written.bytes)?;
+                paths.push(path.to_string_lossy().into_owned());
+                Ok(())
+            });
+            written.tile_paths = Some(paths);
+            result
+        }
+        None => {
+            let path = dir.join(format!("{}.{}", stem, page.format));
+            let result = write_file(&path, &page.buffer, &mut written.bytes);
+            if result.is_ok() {
+                written.path = Some(path.to_string_lossy().into_owned());
+            }
+            result
+        }
+    };
+    written.error = outcome.err();
+    written.write_time = start.elapsed().as_millis() as u32;
+    written
+}
+
+fn write_file(path: &Path, data: &[u8], bytes: &mut u64) -> Result<(), String> {
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    *bytes += data.len() as u64;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_placeholders() {
+        assert_eq!(file_stem("page-%d", 7), "page-7");
+        assert_eq!(file_stem("scan_%04d_out", 7), "scan_0007_out");
+        assert_eq!(file_stem("100%-%d", 3), "100%-3");
+        assert!(validate_pattern("page-%d").is_ok());
+        assert!(validate_pattern("page").is_err());
+        assert!(validate_pattern("%5d").is_err());
+        assert!(validate_pattern("../page-%d").is_err());
+    }
+}
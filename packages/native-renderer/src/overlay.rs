@@ -0,0 +1,56 @@
+//! 渲染结果上的叠加层
+//!
+//! 把外部数据（如评论系统中的高亮区域）合成到页面图像上，
+//! 使分享出去的预览图与用户在查看器中看到的一致。
+
+use crate::analysis::Rect;
+use crate::renderer::PixelFormat;
+
+/// 以正片叠底方式把高亮矩形合成到紧密排列的 4 通道图像上
+///
+/// 正片叠底只会让底色变暗，文字仍然清晰，效果接近荧光笔；
+/// 颜色的 alpha 控制高亮强度。矩形超出图像的部分会被裁掉。
+pub fn burn_highlights(image: &mut [u8], width: u32, height: u32, highlights: &[(Rect, [u8; 4])], pixel_format: PixelFormat) {
+    for (rect, [r, g, b, a]) in highlights {
+        let rect = rect.clamp(width as f32, height as f32);
+        let (left, top) = (rect.left.floor() as u32, rect.top.floor() as u32);
+        let (right, bottom) = (rect.right.ceil() as u32, rect.bottom.ceil() as u32);
+        if right <= left || bottom <= top || *a == 0 {
+            continue;
+        }
+
+        let color = match pixel_format {
+            PixelFormat::Rgba => [*r, *g, *b],
+            PixelFormat::Bgra => [*b, *g, *r],
+        };
+        let alpha = *a as u32;
+        for y in top..bottom {
+            let row = (y * width) as usize * 4;
+            for x in left..right {
+                let pixel = &mut image[row + x as usize * 4..][..3];
+                for (channel, c) in pixel.iter_mut().zip(color) {
+                    let multiplied = *channel as u32 * c as u32 / 255;
+                    *channel = ((*channel as u32 * (255 - alpha) + multiplied * alpha) / 255) as u8;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_highlights() {
+        // 2x1 白色图像，只高亮左边一个像素
+        let mut image = vec![255u8; 8];
+        let highlight = (Rect::from_corners(0.0, 0.0, 1.0, 1.0), [255, 200, 0, 255]);
+        burn_highlights(&mut image, 2, 1, &[highlight], PixelFormat::Rgba);
+        assert_eq!(image, vec![255, 200, 0, 255, 255, 255, 255, 255]);
+
+        let mut image = vec![255u8; 8];
+        burn_highlights(&mut image, 2, 1, &[(highlight.0, [255, 200, 0, 128])], PixelFormat::Bgra);
+        assert_eq!(&image[..4], &[127, 227, 255, 255]);
+    }
+}
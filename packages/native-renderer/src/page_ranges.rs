@@ -0,0 +1,146 @@
+//! 页码范围表达式
+//!
+//! 渲染接口除了页码数组，还接受范围字符串（`"1-5,8,10-"`）、`{ from, to }` 对象和 `"all"`，
+//! 调用方不必先取页数再拼出 `[1..500]` 这样的数组。范围在文档打开、页数已知后展开，
+//! 超出页数的段落报错并指出是哪一段；页码数组保持原来的行为（超出页数的页码按 outOfRangePages 处理）。
+
+/// 要渲染的页面
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageSelection {
+    /// 显式的页码数组，原样使用
+    List(Vec<u32>),
+    /// 全部页面
+    All,
+    /// 范围列表：(起始页, 结束页)，结束页为空表示到最后一页
+    Ranges(Vec<(u32, Option<u32>)>),
+}
+
+impl PageSelection {
+    /// 解析范围字符串：逗号分隔的 `N`、`N-M`、`N-`（到最后一页）、`-M`（从第 1 页），或 `all`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec.eq_ignore_ascii_case("all") {
+            return Ok(PageSelection::All);
+        }
+        if spec.is_empty() {
+            return Err("Page range is empty (use \"all\" for all pages)".to_string());
+        }
+
+        let mut ranges = Vec::new();
+        for segment in spec.split(',') {
+            let segment = segment.trim();
+            let range = match segment.split_once('-') {
+                None => {
+                    let page = parse_page(segment, segment)?;
+                    (page, Some(page))
+                }
+                Some((from, to)) => {
+                    let from = if from.trim().is_empty() { 1 } else { parse_page(from, segment)? };
+                    let to = if to.trim().is_empty() { None } else { Some(parse_page(to, segment)?) };
+                    (from, to)
+                }
+            };
+            ranges.push(validate(range, segment)?);
+        }
+        Ok(PageSelection::Ranges(ranges))
+    }
+
+    /// `{ from, to }` 形式的范围，from 默认为 1，to 为空表示到最后一页
+    pub fn range(from: Option<u32>, to: Option<u32>) -> Result<Self, String> {
+        let from = from.unwrap_or(1);
+        let label = format!("{}-{}", from, to.map(|to| to.to_string()).unwrap_or_default());
+        Ok(PageSelection::Ranges(vec![validate((from, to), &label)?]))
+    }
+
+    /// 打开文档前已知的页码（用于提前检查页数限制），范围和全部页面为空
+    pub fn known_pages(&self) -> &[u32] {
+        match self {
+            PageSelection::List(pages) => pages,
+            _ => &[],
+        }
+    }
+
+    /// 按文档页数展开为页码数组；范围中重复的页码只保留第一次出现的位置
+    pub fn resolve(&self, num_pages: u32) -> Result<Vec<u32>, String> {
+        match self {
+            PageSelection::List(pages) => Ok(pages.clone()),
+            PageSelection::All => Ok((1..=num_pages).collect()),
+            PageSelection::Ranges(ranges) => {
+                let mut seen = vec![false; num_pages as usize + 1];
+                let mut pages = Vec::new();
+                for &(from, to) in ranges {
+                    let end = to.unwrap_or(num_pages);
+                    if from > num_pages || end > num_pages {
+                        return Err(format!(
+                            "Page range {} is out of range (total: {})",
+                            describe(from, to),
+                            num_pages
+                        ));
+                    }
+                    for page in from..=end {
+                        if !std::mem::replace(&mut seen[page as usize], true) {
+                            pages.push(page);
+                        }
+                    }
+                }
+                Ok(pages)
+            }
+        }
+    }
+}
+
+fn parse_page(text: &str, segment: &str) -> Result<u32, String> {
+    text.trim()
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid page range segment: \"{}\"", segment))
+}
+
+fn validate((from, to): (u32, Option<u32>), segment: &str) -> Result<(u32, Option<u32>), String> {
+    if from == 0 || to == Some(0) {
+        return Err(format!("Invalid page range segment: \"{}\" (pages start at 1)", segment));
+    }
+    if to.is_some_and(|to| to < from) {
+        return Err(format!("Invalid page range segment: \"{}\" (start is after end)", segment));
+    }
+    Ok((from, to))
+}
+
+fn describe(from: u32, to: Option<u32>) -> String {
+    match to {
+        Some(to) if to == from => from.to_string(),
+        Some(to) => format!("{}-{}", from, to),
+        None => format!("{}-", from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let selection = PageSelection::parse(" 1-3, 8,10- ,2").unwrap();
+        assert_eq!(selection.resolve(12).unwrap(), vec![1, 2, 3, 8, 10, 11, 12]);
+        assert_eq!(PageSelection::parse("-2").unwrap().resolve(5).unwrap(), vec![1, 2]);
+        assert_eq!(PageSelection::parse("ALL").unwrap().resolve(3).unwrap(), vec![1, 2, 3]);
+        assert_eq!(PageSelection::range(Some(4), None).unwrap().resolve(5).unwrap(), vec![4, 5]);
+        assert_eq!(PageSelection::range(None, Some(2)).unwrap().resolve(5).unwrap(), vec![1, 2]);
+        // 页码数组原样使用，超出页数的页码留给 outOfRangePages 处理
+        assert_eq!(PageSelection::List(vec![9, 1]).resolve(3).unwrap(), vec![9, 1]);
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(PageSelection::parse("").is_err());
+        assert!(PageSelection::parse("1,,2").is_err());
+        assert!(PageSelection::parse("a-3").is_err());
+        assert!(PageSelection::parse("0-3").unwrap_err().contains("pages start at 1"));
+        assert!(PageSelection::parse("5-3").unwrap_err().contains("start is after end"));
+        assert!(PageSelection::range(Some(3), Some(1)).is_err());
+
+        let error = PageSelection::parse("1-2,10-12").unwrap().resolve(8).unwrap_err();
+        assert_eq!(error, "Page range 10-12 is out of range (total: 8)");
+        assert!(PageSelection::parse("9-").unwrap().resolve(8).is_err());
+        assert!(PageSelection::parse("8-").unwrap().resolve(8).is_ok());
+    }
+}
@@ -0,0 +1,116 @@
+//! 感知哈希（pHash）与重复页聚类
+//!
+//! 页面缩小为 32×32 灰度图后做二维 DCT，取左上角 8×8 低频系数（不含直流分量）
+//! 与中位数比较得到 64 位哈希。内容相同或仅有压缩噪声、轻微偏移的页面哈希距离很小，
+//! 适合找出扫描件中重复上传的页面。
+
+/// 哈希输入图像的边长
+pub const HASH_INPUT_SIZE: usize = 32;
+const LOW_FREQ_SIZE: usize = 8;
+
+/// 计算 32×32 灰度图的感知哈希
+pub fn phash(gray: &[u8]) -> u64 {
+    debug_assert_eq!(gray.len(), HASH_INPUT_SIZE * HASH_INPUT_SIZE);
+    let n = HASH_INPUT_SIZE;
+
+    // 余弦表：cos[(2x + 1) u π / 2N]
+    let cos: Vec<f64> = (0..LOW_FREQ_SIZE)
+        .flat_map(|u| {
+            (0..n).map(move |x| ((2 * x + 1) as f64 * u as f64 * std::f64::consts::PI / (2 * n) as f64).cos())
+        })
+        .collect();
+
+    // 只需要低频部分：先对行做 DCT，再对列做 DCT
+    let mut rows = vec![0.0f64; n * LOW_FREQ_SIZE];
+    for y in 0..n {
+        for u in 0..LOW_FREQ_SIZE {
+            rows[y * LOW_FREQ_SIZE + u] = (0..n).map(|x| gray[y * n + x] as f64 * cos[u * n + x]).sum();
+        }
+    }
+    let mut coefficients = Vec::with_capacity(LOW_FREQ_SIZE * LOW_FREQ_SIZE);
+    for v in 0..LOW_FREQ_SIZE {
+        for u in 0..LOW_FREQ_SIZE {
+            coefficients.push((0..n).map(|y| rows[y * LOW_FREQ_SIZE + u] * cos[v * n + y]).sum::<f64>());
+        }
+    }
+
+    // 直流分量只反映整体亮度，不参与比较
+    let mut sorted: Vec<f64> = coefficients[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+
+    coefficients
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| *i > 0 && **c > median)
+        .fold(0u64, |hash, (i, _)| hash | (1 << i))
+}
+
+/// 两个哈希的汉明距离
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 把距离不超过 `threshold` 的页面合并为一组（传递闭包），只返回包含两页以上的组
+pub fn clusters(hashes: &[(u32, u64)], threshold: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            if distance(hashes[i].1, hashes[j].1) <= threshold {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root = std::collections::HashMap::new();
+    for i in 0..hashes.len() {
+        let root = find(&mut parent, i);
+        let index = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(i);
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 伪随机纹理，亮度范围 [offset, offset + 200)
+    fn texture(seed: u32, offset: u8) -> Vec<u8> {
+        let mut state = seed;
+        (0..HASH_INPUT_SIZE * HASH_INPUT_SIZE)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) % 200) as u8 + offset
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_phash_ignores_brightness() {
+        let a = phash(&texture(1, 0));
+        assert_eq!(distance(a, phash(&texture(1, 40))), 0);
+        assert!(distance(a, phash(&texture(2, 0))) > 10);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let hashes = [(1, 0b0000u64), (2, 0xffff_0000), (3, 0b0011), (4, 0b1111)];
+        assert_eq!(clusters(&hashes, 2), vec![vec![0, 2, 3]]);
+        assert!(clusters(&hashes, 0).is_empty());
+    }
+}
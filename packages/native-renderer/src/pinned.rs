@@ -0,0 +1,281 @@
+//! 常驻文档
+//!
+//! 查看器会话在整个生命周期内反复渲染同一文档的不同页面和缩放级别。
+//! 预加载后文档来源（文件路径、内存数据或流式数据源）和打开结果（页数、成功的密码）
+//! 由一个专属线程持有，渲染请求通过通道发给该线程，按提交顺序依次处理。
+//!
+//! thread_safe 模式下 PDFium 实例在存活期间持有进程级的锁，文档不能在请求之间保持打开：
+//! 每个请求在文档线程上绑定 PDFium、重新打开文档，处理完后一并释放，请求之间
+//! 其他渲染调用照常进行。重新打开时直接使用上次成功的密码；流式来源的块缓存
+//! 在请求之间保留，不需要再次下载 xref 表和页面树。
+//! 释放后通道关闭，线程退出。指定空闲时间时，超过该时间没有收到请求的
+//! 文档清空流式块缓存，避免被遗忘的句柄一直占用内存；之后的请求按需重新获取数据。
+
+use crate::config::RenderConfig;
+use crate::flight_recorder;
+use crate::renderer::{load_error, try_passwords, PdfRenderer};
+use crate::scheduler::DocumentSource;
+use crate::stream_reader::JsFileStreamer;
+use crate::usage::{self, UsageMeter};
+use crate::{PageOverview, PageResult};
+use pdfium_render::prelude::*;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type RenderReply = Result<(u32, Vec<PageResult>), String>;
+
+/// 常驻文档的来源
+pub enum PinnedSource {
+    Document(DocumentSource),
+    /// 流式数据源，文档存活期间按需通过 JS fetcher 读取数据；`source` 用于渲染记录
+    Stream { streamer: JsFileStreamer, source: String },
+}
+
+impl From<DocumentSource> for PinnedSource {
+    fn from(source: DocumentSource) -> Self {
+        PinnedSource::Document(source)
+    }
+}
+
+/// 可以常驻的文档来源：每个请求都要能重新打开
+pub trait Reopen: Send + 'static {
+    /// 打开文档；流式来源每次打开都共享同一份块缓存
+    fn load<'a>(&'a self, pdfium: &'a Pdfium, password: Option<&'a str>) -> Result<PdfDocument<'a>, PdfiumError>;
+
+    /// 打开失败时错误信息的前缀
+    fn load_context(&self) -> &'static str;
+
+    /// 渲染记录中的文档标识
+    fn fingerprint(&self) -> String;
+
+    /// 首次打开前的准备（流式来源探测长度）
+    fn prepare(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 空闲超时后释放来源占用的缓存
+    fn release_cache(&self) {}
+}
+
+impl Reopen for DocumentSource {
+    fn load<'a>(&'a self, pdfium: &'a Pdfium, password: Option<&'a str>) -> Result<PdfDocument<'a>, PdfiumError> {
+        match self {
+            DocumentSource::File(path) => pdfium.load_pdf_from_file(path, password),
+            DocumentSource::Bytes(bytes) => pdfium.load_pdf_from_byte_slice(bytes, password),
+        }
+    }
+
+    fn load_context(&self) -> &'static str {
+        match self {
+            DocumentSource::File(_) => "Failed to load PDF from file",
+            DocumentSource::Bytes(_) => "Failed to load PDF",
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        match self {
+            DocumentSource::File(path) => flight_recorder::fingerprint_file(path),
+            DocumentSource::Bytes(bytes) => flight_recorder::fingerprint_bytes(bytes),
+        }
+    }
+}
+
+impl Reopen for PinnedSource {
+    fn load<'a>(&'a self, pdfium: &'a Pdfium, password: Option<&'a str>) -> Result<PdfDocument<'a>, PdfiumError> {
+        match self {
+            PinnedSource::Document(source) => source.load(pdfium, password),
+            PinnedSource::Stream { streamer, .. } => pdfium.load_pdf_from_reader(streamer.reopen(), password),
+        }
+    }
+
+    fn load_context(&self) -> &'static str {
+        match self {
+            PinnedSource::Document(source) => source.load_context(),
+            PinnedSource::Stream { .. } => "Failed to load PDF from stream",
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        match self {
+            PinnedSource::Document(source) => source.fingerprint(),
+            PinnedSource::Stream { source, .. } => source.clone(),
+        }
+    }
+
+    fn prepare(&self) -> Result<(), String> {
+        match self {
+            PinnedSource::Document(_) => Ok(()),
+            // 长度未知时先等 fetcher 报告总长度
+            PinnedSource::Stream { streamer, .. } => streamer
+                .reopen()
+                .total_size()
+                .map(|_| ())
+                .map_err(|e| format!("Failed to read PDF stream: {}", e)),
+        }
+    }
+
+    fn release_cache(&self) {
+        if let PinnedSource::Stream { streamer, .. } = self {
+            streamer.get_shared_state().clear_cache();
+        }
+    }
+}
+
+/// 绑定 PDFium 并重新打开文档，处理完后一并释放，不在请求之间占用 PDFium
+fn with_document<R>(
+    source: &impl Reopen,
+    password: Option<&str>,
+    f: impl FnOnce(&Pdfium, &PdfDocument) -> R,
+) -> Result<R, String> {
+    let pdfium = crate::create_pdfium().map_err(|e| e.reason)?;
+    let document = source
+        .load(&pdfium, password)
+        .map_err(|e| load_error(source.load_context(), &e))?;
+    Ok(f(&pdfium, &document))
+}
+
+enum Request {
+    Render {
+        page_nums: Vec<u32>,
+        config: Box<RenderConfig>,
+        /// 调用方线程登记的资源统计，渲染期间文档线程登记到同一统计
+        meter: Option<Arc<UsageMeter>>,
+        reply: mpsc::Sender<RenderReply>,
+    },
+    PageInfo {
+        page_num: u32,
+        reply: mpsc::Sender<Result<PageOverview, String>>,
+    },
+}
+
+/// 打开成功后交回的文档信息
+struct Loaded {
+    page_count: u32,
+    password_index: Option<u32>,
+}
+
+/// 常驻文档句柄，析构时释放文档
+pub struct PinnedDocument {
+    requests: Mutex<mpsc::Sender<Request>>,
+    /// 文档总页数
+    pub page_count: u32,
+    /// 成功打开文档的密码在 passwords 中的下标
+    pub password_index: Option<u32>,
+}
+
+impl PinnedDocument {
+    /// 在专属线程中打开文档（需要密码时依次尝试 `passwords`），加载完成（或失败）后返回
+    ///
+    /// 流式来源的打开过程需要 JS 提供数据，不能在 JS 线程上调用。
+    /// `idle_timeout` 为 None 时流式块缓存一直保留到句柄释放。
+    pub fn open(
+        source: impl Reopen,
+        passwords: Vec<String>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<Self, String> {
+        let (requests, receiver) = mpsc::channel::<Request>();
+        let (loaded_sender, loaded_receiver) = mpsc::channel::<Result<Loaded, String>>();
+
+        std::thread::spawn(move || {
+            // 首次打开：依次尝试候选密码，确认文档可以打开并取得页数，随后释放 PDFium
+            let opened = crate::create_pdfium().map_err(|e| e.reason).and_then(|pdfium| {
+                source.prepare()?;
+                let (document, password_index) = try_passwords(&passwords, |password| source.load(&pdfium, password))
+                    .map_err(|e| load_error(source.load_context(), &e))?;
+                Ok(Loaded {
+                    page_count: document.pages().len() as u32,
+                    password_index,
+                })
+            });
+            let password = match opened {
+                Ok(loaded) => {
+                    let password = loaded.password_index.map(|index| passwords[index as usize].clone());
+                    let _ = loaded_sender.send(Ok(loaded));
+                    password
+                }
+                Err(e) => {
+                    let _ = loaded_sender.send(Err(e));
+                    return;
+                }
+            };
+            let fingerprint = source.fingerprint();
+
+            // 句柄释放后 recv 返回错误，线程退出；空闲超时后清空缓存，继续等待下一个请求
+            let mut cache_released = false;
+            loop {
+                let request = match idle_timeout.filter(|_| !cache_released) {
+                    Some(timeout) => match receiver.recv_timeout(timeout) {
+                        Ok(request) => request,
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            source.release_cache();
+                            cache_released = true;
+                            continue;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match receiver.recv() {
+                        Ok(request) => request,
+                        Err(_) => break,
+                    },
+                };
+                cache_released = false;
+                match request {
+                    Request::Render { page_nums, config, meter, reply } => {
+                        let _usage = meter.as_ref().map(usage::attach);
+                        let result = with_document(&source, password.as_deref(), |pdfium, document| {
+                            PdfRenderer::new(pdfium, *config).render_document_pages(document, &page_nums, &fingerprint)
+                        });
+                        let _ = reply.send(result.and_then(|result| result));
+                    }
+                    Request::PageInfo { page_num, reply } => {
+                        let _ = reply.send(with_document(&source, password.as_deref(), |pdfium, document| {
+                            PdfRenderer::new(pdfium, RenderConfig::default()).page_overview(document, page_num)
+                        }));
+                    }
+                }
+            }
+        });
+
+        let Loaded { page_count, password_index } = loaded_receiver
+            .recv()
+            .map_err(|_| "Document thread exited".to_string())??;
+        Ok(Self {
+            requests: Mutex::new(requests),
+            page_count,
+            password_index,
+        })
+    }
+
+    /// 渲染页面，阻塞到结果返回；同一文档的请求按提交顺序依次处理
+    pub fn render(&self, page_nums: Vec<u32>, config: RenderConfig) -> RenderReply {
+        let (reply, receiver) = mpsc::channel();
+        self.send(Request::Render {
+            page_nums,
+            config: Box::new(config),
+            meter: usage::current(),
+            reply,
+        })?;
+        receiver.recv().map_err(|_| self.exited())?
+    }
+
+    /// 单页概况（尺寸、旋转、标签等），阻塞到结果返回
+    pub fn page_info(&self, page_num: u32) -> Result<PageOverview, String> {
+        let (reply, receiver) = mpsc::channel();
+        self.send(Request::PageInfo { page_num, reply })?;
+        receiver.recv().map_err(|_| self.exited())?
+    }
+
+    fn send(&self, request: Request) -> Result<(), String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .send(request)
+            .map_err(|_| self.exited())
+    }
+
+    fn exited(&self) -> String {
+        "Document thread exited".to_string()
+    }
+}
+
@@ -0,0 +1,167 @@
+//! 命名的渲染配置
+//!
+//! 服务启动时从配置中心拿到一组命名的选项（如 `archive`、`preview-b`），通过 `loadProfiles`
+//! 注册后，请求只需传 `profile` 名称，不必在每次调用中重复构造整套选项；切换配置做 A/B
+//! 测试时也只需重新加载。配置是进程级的，所有模块实例（worker_threads）共享。
+
+use crate::RenderOptions;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+static PROFILES: Mutex<Option<HashMap<String, RenderOptions>>> = Mutex::new(None);
+
+/// 替换全部已注册的配置，返回注册的名称（按名称排序）
+///
+/// 配置中不能再引用其他配置，也不能包含只对单次调用有意义的 correlationId、cancelToken。
+pub fn load(profiles: HashMap<String, RenderOptions>) -> Result<Vec<String>, String> {
+    for (name, options) in &profiles {
+        if name.trim().is_empty() {
+            return Err("Profile name must not be empty".to_string());
+        }
+        let per_call = [
+            ("profile", options.profile.is_some()),
+            ("correlationId", options.correlation_id.is_some()),
+            ("cancelToken", options.cancel_token.is_some()),
+        ];
+        if let Some((field, _)) = per_call.iter().find(|(_, set)| *set) {
+            return Err(format!("Profile {} must not set {}", name, field));
+        }
+    }
+
+    let mut names: Vec<String> = profiles.keys().cloned().collect();
+    names.sort();
+    *PROFILES.lock().unwrap() = Some(profiles);
+    Ok(names)
+}
+
+/// 展开调用选项中的 `profile`：调用中设置的选项优先，其余取自配置
+pub fn resolve(options: RenderOptions) -> Result<RenderOptions, String> {
+    let Some(name) = options.profile.clone() else {
+        return Ok(options);
+    };
+    let profile = PROFILES
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|profiles| profiles.get(&name).cloned())
+        .ok_or_else(|| format!("Unknown render profile: {}", name))?;
+    Ok(overlay(options, profile))
+}
+
+fn overlay(options: RenderOptions, profile: RenderOptions) -> RenderOptions {
+    RenderOptions {
+        target_width: options.target_width.or(profile.target_width),
+        dpi: options.dpi.or(profile.dpi),
+        image_heavy_width: options.image_heavy_width.or(profile.image_heavy_width),
+        max_scale: options.max_scale.or(profile.max_scale),
+        prefer_sharpness: options.prefer_sharpness.or(profile.prefer_sharpness),
+        quality: options.quality.or(profile.quality),
+        detect_scan: options.detect_scan.or(profile.detect_scan),
+        scan_min_image_coverage: options.scan_min_image_coverage.or(profile.scan_min_image_coverage),
+        scan_max_text_objects: options.scan_max_text_objects.or(profile.scan_max_text_objects),
+        format: options.format.or(profile.format),
+        webp_quality: options.webp_quality.or(profile.webp_quality),
+        webp_method: options.webp_method.or(profile.webp_method),
+        webp_method_auto: options.webp_method_auto.or(profile.webp_method_auto),
+        jpeg_quality: options.jpeg_quality.or(profile.jpeg_quality),
+        jpeg_progressive: options.jpeg_progressive.or(profile.jpeg_progressive),
+        jpeg_chroma_subsampling: options.jpeg_chroma_subsampling.or(profile.jpeg_chroma_subsampling),
+        avif_quality: options.avif_quality.or(profile.avif_quality),
+        avif_speed: options.avif_speed.or(profile.avif_speed),
+        png_compression: options.png_compression.or(profile.png_compression),
+        pixel_format: options.pixel_format.or(profile.pixel_format),
+        colors: options.colors.or(profile.colors),
+        bilevel: options.bilevel.or(profile.bilevel),
+        bilevel_threshold: options.bilevel_threshold.or(profile.bilevel_threshold),
+        sauvola_window: options.sauvola_window.or(profile.sauvola_window),
+        sauvola_k: options.sauvola_k.or(profile.sauvola_k),
+        tiff_color: options.tiff_color.or(profile.tiff_color),
+        normalize_background: options.normalize_background.or(profile.normalize_background),
+        background_window: options.background_window.or(profile.background_window),
+        denoise: options.denoise.or(profile.denoise),
+        denoise_radius: options.denoise_radius.or(profile.denoise_radius),
+        advanced_flags: options.advanced_flags.or(profile.advanced_flags),
+        annotation_types: options.annotation_types.or(profile.annotation_types),
+        form_mode: options.form_mode.or(profile.form_mode),
+        render_annotations: options.render_annotations.or(profile.render_annotations),
+        render_forms: options.render_forms.or(profile.render_forms),
+        disable_text_antialiasing: options.disable_text_antialiasing.or(profile.disable_text_antialiasing),
+        disable_image_antialiasing: options.disable_image_antialiasing.or(profile.disable_image_antialiasing),
+        optimize_for_lcd: options.optimize_for_lcd.or(profile.optimize_for_lcd),
+        correlation_id: options.correlation_id.or(profile.correlation_id),
+        max_pages: options.max_pages.or(profile.max_pages),
+        max_page_objects: options.max_page_objects.or(profile.max_page_objects),
+        max_file_size: options.max_file_size.or(profile.max_file_size),
+        max_pixels_per_page: options.max_pixels_per_page.or(profile.max_pixels_per_page),
+        max_total_bytes: options.max_total_bytes.or(profile.max_total_bytes),
+        memory_budget_action: options.memory_budget_action.or(profile.memory_budget_action),
+        total_time_budget_ms: options.total_time_budget_ms.or(profile.total_time_budget_ms),
+        encode_budget_ms: options.encode_budget_ms.or(profile.encode_budget_ms),
+        page_timeout_ms: options.page_timeout_ms.or(profile.page_timeout_ms),
+        max_concurrent_streams: options.max_concurrent_streams.or(profile.max_concurrent_streams),
+        fetch_lanes: options.fetch_lanes.or(profile.fetch_lanes),
+        stream_readahead: options.stream_readahead.or(profile.stream_readahead),
+        fetch_plan: options.fetch_plan.or(profile.fetch_plan),
+        linearized_fast_path: options.linearized_fast_path.or(profile.linearized_fast_path),
+        stream_fallback_fraction: options.stream_fallback_fraction.or(profile.stream_fallback_fraction),
+        stream_fallback_requests: options.stream_fallback_requests.or(profile.stream_fallback_requests),
+        stream_block_size: options.stream_block_size.or(profile.stream_block_size),
+        stream_max_cache_blocks: options.stream_max_cache_blocks.or(profile.stream_max_cache_blocks),
+        stream_fetch_timeout_ms: options.stream_fetch_timeout_ms.or(profile.stream_fetch_timeout_ms),
+        document_idle_timeout_ms: options.document_idle_timeout_ms.or(profile.document_idle_timeout_ms),
+        thumbnail_passthrough: options.thumbnail_passthrough.or(profile.thumbnail_passthrough),
+        thumbnail_fallback_width: options.thumbnail_fallback_width.or(profile.thumbnail_fallback_width),
+        preview_width: options.preview_width.or(profile.preview_width),
+        scan_passthrough: options.scan_passthrough.or(profile.scan_passthrough),
+        page_widths: options.page_widths.or(profile.page_widths),
+        page_dpi: options.page_dpi.or(profile.page_dpi),
+        page_scan_mode: options.page_scan_mode.or(profile.page_scan_mode),
+        page_formats: options.page_formats.or(profile.page_formats),
+        sprite_columns: options.sprite_columns.or(profile.sprite_columns),
+        spread_cover: options.spread_cover.or(profile.spread_cover),
+        text_granularity: options.text_granularity.or(profile.text_granularity),
+        tenant: options.tenant.or(profile.tenant),
+        canvas_width: options.canvas_width.or(profile.canvas_width),
+        canvas_height: options.canvas_height.or(profile.canvas_height),
+        pad_color: options.pad_color.or(profile.pad_color),
+        pad_align: options.pad_align.or(profile.pad_align),
+        oversize_strategy: options.oversize_strategy.or(profile.oversize_strategy),
+        tile_size: options.tile_size.or(profile.tile_size),
+        duplicate_threshold: options.duplicate_threshold.or(profile.duplicate_threshold),
+        encoder_backend: options.encoder_backend.or(profile.encoder_backend),
+        highlights: options.highlights.or(profile.highlights),
+        stamp_text: options.stamp_text.or(profile.stamp_text),
+        stamp_image: options.stamp_image.or(profile.stamp_image),
+        stamp_image_width: options.stamp_image_width.or(profile.stamp_image_width),
+        stamp_image_height: options.stamp_image_height.or(profile.stamp_image_height),
+        stamp_font_size: options.stamp_font_size.or(profile.stamp_font_size),
+        stamp_color: options.stamp_color.or(profile.stamp_color),
+        stamp_opacity: options.stamp_opacity.or(profile.stamp_opacity),
+        stamp_position: options.stamp_position.or(profile.stamp_position),
+        stamp_margin: options.stamp_margin.or(profile.stamp_margin),
+        stamp_exclusions: options.stamp_exclusions.or(profile.stamp_exclusions),
+        jpeg_background: options.jpeg_background.or(profile.jpeg_background),
+        resize_tolerance: options.resize_tolerance.or(profile.resize_tolerance),
+        out_of_range_pages: options.out_of_range_pages.or(profile.out_of_range_pages),
+        smart_encoding: options.smart_encoding.or(profile.smart_encoding),
+        passwords: options.passwords.or(profile.passwords),
+        password: options.password.or(profile.password),
+        also_encode: options.also_encode.or(profile.also_encode),
+        force_srgb: options.force_srgb.or(profile.force_srgb),
+        cmyk_profile: options.cmyk_profile.or(profile.cmyk_profile),
+        image_stats: options.image_stats.or(profile.image_stats),
+        content_hash: options.content_hash.or(profile.content_hash),
+        object_diagnostics: options.object_diagnostics.or(profile.object_diagnostics),
+        preserve_alpha: options.preserve_alpha.or(profile.preserve_alpha),
+        hero_page: options.hero_page.or(profile.hero_page),
+        span_timings: options.span_timings.or(profile.span_timings),
+        resource_usage: options.resource_usage.or(profile.resource_usage),
+        chunk_rows: options.chunk_rows.or(profile.chunk_rows),
+        concurrency: options.concurrency.or(profile.concurrency),
+        delivery_order: options.delivery_order.or(profile.delivery_order),
+        cancel_token: options.cancel_token.or(profile.cancel_token),
+        deterministic: options.deterministic.or(profile.deterministic),
+        profile: options.profile,
+    }
+}
+
@@ -0,0 +1,187 @@
+//! 调色板量化（中位切分）
+//!
+//! 将渲染结果压缩到最多 N 种颜色，用于极小尺寸的预览图和线条图。
+//! 量化与通道顺序无关，RGBA 和 BGRA 数据都可以直接处理。
+
+use std::collections::HashMap;
+
+/// 直方图统计时每个通道保留的位数（降低精度以控制颜色桶数量）
+const HISTOGRAM_BITS: u32 = 5;
+
+/// 量化结果
+pub struct Quantized {
+    /// 调色板（与输入数据相同的通道顺序）
+    pub palette: Vec<[u8; 4]>,
+    /// 每个像素对应的调色板索引
+    pub indices: Vec<u8>,
+}
+
+impl Quantized {
+    /// 将索引展开回 4 通道像素数据
+    pub fn expand(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.indices.len() * 4);
+        for &idx in &self.indices {
+            out.extend_from_slice(&self.palette[idx as usize]);
+        }
+        out
+    }
+}
+
+/// 直方图中的一个颜色桶
+#[derive(Clone, Copy)]
+struct Bucket {
+    key: u32,
+    count: u64,
+    sum: [u64; 4],
+}
+
+impl Bucket {
+    fn mean(&self) -> [u8; 4] {
+        let mut c = [0u8; 4];
+        for (i, v) in c.iter_mut().enumerate() {
+            *v = (self.sum[i] / self.count.max(1)) as u8;
+        }
+        c
+    }
+}
+
+fn reduce_key(px: &[u8]) -> u32 {
+    let shift = 8 - HISTOGRAM_BITS;
+    ((px[0] as u32 >> shift) << (HISTOGRAM_BITS * 3))
+        | ((px[1] as u32 >> shift) << (HISTOGRAM_BITS * 2))
+        | ((px[2] as u32 >> shift) << HISTOGRAM_BITS)
+        | (px[3] as u32 >> shift)
+}
+
+/// 使用中位切分算法把 4 通道像素数据量化到最多 `max_colors` 种颜色
+///
+/// `max_colors` 会被限制在 2-256 之间。
+pub fn quantize(data: &[u8], max_colors: usize) -> Quantized {
+    let max_colors = max_colors.clamp(2, 256);
+
+    // 1. 统计降精度后的颜色直方图
+    let mut histogram: HashMap<u32, Bucket> = HashMap::new();
+    for px in data.chunks_exact(4) {
+        let key = reduce_key(px);
+        let bucket = histogram.entry(key).or_insert(Bucket {
+            key,
+            count: 0,
+            sum: [0; 4],
+        });
+        bucket.count += 1;
+        for (sum, &v) in bucket.sum.iter_mut().zip(px) {
+            *sum += v as u64;
+        }
+    }
+
+    // 2. 中位切分：反复拆分跨度最大的盒子
+    let mut boxes: Vec<Vec<Bucket>> = vec![histogram.into_values().collect()];
+    while boxes.len() < max_colors {
+        let candidate = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, widest_channel(b)))
+            .max_by_key(|(_, (_, range))| *range);
+
+        let Some((index, (channel, range))) = candidate else {
+            break;
+        };
+        if range == 0 {
+            break;
+        }
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|b| b.mean()[channel]);
+
+        // 按像素数找到中位点
+        let total: u64 = colors.iter().map(|b| b.count).sum();
+        let mut acc = 0;
+        let mut split = 1;
+        for (i, b) in colors.iter().enumerate() {
+            acc += b.count;
+            if acc * 2 >= total {
+                split = (i + 1).clamp(1, colors.len() - 1);
+                break;
+            }
+        }
+
+        let upper = colors.split_off(split);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    // 3. 每个盒子的加权平均色作为调色板项
+    let mut palette = Vec::with_capacity(boxes.len());
+    let mut key_to_index: HashMap<u32, u8> = HashMap::new();
+    for (i, colors) in boxes.iter().enumerate() {
+        let mut merged = Bucket {
+            key: 0,
+            count: 0,
+            sum: [0; 4],
+        };
+        for b in colors {
+            merged.count += b.count;
+            for (sum, &v) in merged.sum.iter_mut().zip(&b.sum) {
+                *sum += v;
+            }
+            key_to_index.insert(b.key, i as u8);
+        }
+        palette.push(merged.mean());
+    }
+
+    if palette.is_empty() {
+        palette.push([255, 255, 255, 255]);
+    }
+
+    // 4. 映射像素到调色板索引
+    let indices = data
+        .chunks_exact(4)
+        .map(|px| key_to_index.get(&reduce_key(px)).copied().unwrap_or(0))
+        .collect();
+
+    Quantized { palette, indices }
+}
+
+/// 返回盒子中跨度最大的通道及其跨度
+fn widest_channel(colors: &[Bucket]) -> (usize, u32) {
+    let mut min = [u8::MAX; 4];
+    let mut max = [0u8; 4];
+    for b in colors {
+        let m = b.mean();
+        for c in 0..4 {
+            min[c] = min[c].min(m[c]);
+            max[c] = max[c].max(m[c]);
+        }
+    }
+    (0..4)
+        .map(|c| (c, (max[c] - min[c]) as u32))
+        .max_by_key(|(_, range)| *range)
+        .unwrap_or((0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize_limits_palette_size() {
+        let mut data = Vec::new();
+        for i in 0..=255u8 {
+            data.extend_from_slice(&[i, 255 - i, i / 2, 255]);
+        }
+        let q = quantize(&data, 4);
+        assert!(q.palette.len() <= 4);
+        assert_eq!(q.indices.len(), 256);
+        assert_eq!(q.expand().len(), data.len());
+    }
+
+    #[test]
+    fn test_quantize_keeps_two_colors() {
+        let data = [0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 255];
+        let q = quantize(&data, 16);
+        assert_eq!(q.palette.len(), 2);
+        assert_eq!(q.indices[0], q.indices[2]);
+        assert_ne!(q.indices[0], q.indices[1]);
+    }
+}
@@ -0,0 +1,233 @@
+//! 原始位图分块输出（renderPageToRawChunks）
+//!
+//! 高 DPI 的大幅面页面整页位图可能有几百 MB。这里按水平条带逐段让 PDFium 渲染到
+//! 小位图中（以负的起始偏移把页面对应的部分画进条带），每段交给 JS 回调后再渲染下一段，
+//! 同时在途的分块数有上限，JS 端处理完（例如写入编码器或上传流）确认后才继续，
+//! 内存占用只与条带大小有关。
+//!
+//! pdfium-render 没有公开表单句柄，条带渲染不经过 FPDF_FFLDraw：
+//! 表单控件按其外观流随注释一起绘制。
+
+use crate::renderer::AdvancedFlags;
+use pdfium_render::prelude::*;
+use std::os::raw::c_int;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+// fpdfview.h 中的渲染标志
+const FPDF_ANNOT: c_int = 0x01;
+const FPDF_LCD_TEXT: c_int = 0x02;
+const FPDF_NO_NATIVETEXT: c_int = 0x04;
+const FPDF_GRAYSCALE: c_int = 0x08;
+const FPDF_REVERSE_BYTE_ORDER: c_int = 0x10;
+const FPDF_CONVERT_FILL_TO_STROKE: c_int = 0x20;
+const FPDF_RENDER_LIMITEDIMAGECACHE: c_int = 0x200;
+const FPDF_RENDER_FORCEHALFTONE: c_int = 0x400;
+const FPDF_PRINTING: c_int = 0x800;
+const FPDF_RENDER_NO_SMOOTHTEXT: c_int = 0x1000;
+const FPDF_RENDER_NO_SMOOTHIMAGE: c_int = 0x2000;
+const FPDF_RENDER_NO_SMOOTHPATH: c_int = 0x4000;
+const FPDF_BITMAP_BGRA: c_int = 4;
+
+/// 未指定 chunkRows 时每个分块的目标大小
+const DEFAULT_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// 同时交给 JS 但尚未确认的分块数上限
+pub const MAX_IN_FLIGHT: u32 = 2;
+
+/// 等待 JS 确认分块的最长时间，超时视为调用方已放弃
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 渲染好的一段条带
+pub struct RawChunk {
+    pub index: u32,
+    /// 本段第一行在整页中的行号
+    pub y: u32,
+    pub rows: u32,
+    /// 整页尺寸
+    pub width: u32,
+    pub height: u32,
+    pub last: bool,
+    /// 紧密排列的 4 通道像素
+    pub pixels: Vec<u8>,
+}
+
+/// 每个分块的行数：指定了 chunkRows 时使用指定值，否则按约 4 MB 一块计算
+pub fn chunk_rows(width: u32, height: u32, requested: Option<u32>) -> u32 {
+    let rows = requested.unwrap_or_else(|| (DEFAULT_CHUNK_BYTES / (width.max(1) as usize * 4)) as u32);
+    rows.clamp(1, height.max(1))
+}
+
+/// 把整页高度切成 (起始行, 行数) 条带
+pub fn bands(height: u32, rows: u32) -> impl Iterator<Item = (u32, u32)> {
+    let rows = rows.max(1);
+    (0..height).step_by(rows as usize).map(move |y| (y, rows.min(height - y)))
+}
+
+/// 把 advancedFlags 换算成 FPDF_RenderPageBitmap 的 flags 参数
+pub fn render_flags(flags: &AdvancedFlags, reverse_byte_order: bool) -> c_int {
+    [
+        (!flags.no_annotations, FPDF_ANNOT),
+        (flags.lcd_text, FPDF_LCD_TEXT),
+        (flags.no_native_text, FPDF_NO_NATIVETEXT),
+        (flags.grayscale, FPDF_GRAYSCALE),
+        (reverse_byte_order, FPDF_REVERSE_BYTE_ORDER),
+        (flags.fills_as_strokes, FPDF_CONVERT_FILL_TO_STROKE),
+        (flags.limit_image_cache, FPDF_RENDER_LIMITEDIMAGECACHE),
+        (flags.force_halftone, FPDF_RENDER_FORCEHALFTONE),
+        (flags.print_mode, FPDF_PRINTING),
+        (flags.no_text_smoothing, FPDF_RENDER_NO_SMOOTHTEXT),
+        (flags.no_image_smoothing, FPDF_RENDER_NO_SMOOTHIMAGE),
+        (flags.no_path_smoothing, FPDF_RENDER_NO_SMOOTHPATH),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .fold(0, |acc, (_, flag)| acc | flag)
+}
+
+/// 渲染整页（width x height）中从第 y 行开始的 rows 行，返回紧密排列的 4 通道像素
+///
+/// `clear_color` 为 ARGB，透明输出时传 alpha 为 0 的背景色。
+pub fn render_band(
+    page: &PdfPage,
+    (width, height): (u32, u32),
+    (y, rows): (u32, u32),
+    flags: c_int,
+    clear_color: FPDF_DWORD,
+) -> Result<Vec<u8>, String> {
+    let bindings = page.bindings();
+    let bitmap = create_bitmap(bindings, width, rows, clear_color)?;
+    bindings.FPDF_RenderPageBitmap(
+        bitmap,
+        bindings.get_handle_from_page(page),
+        0,
+        -(y as c_int),
+        width as c_int,
+        height as c_int,
+        0,
+        flags,
+    );
+    take_pixels(bindings, bitmap, width, rows)
+}
+
+/// 创建 width x rows 的 BGRA 位图并以 `clear_color` 填充
+pub fn create_bitmap(
+    bindings: &dyn PdfiumLibraryBindings,
+    width: u32,
+    rows: u32,
+    clear_color: FPDF_DWORD,
+) -> Result<FPDF_BITMAP, String> {
+    let bitmap = bindings.FPDFBitmap_CreateEx(width as c_int, rows as c_int, FPDF_BITMAP_BGRA, std::ptr::null_mut(), 0);
+    if bitmap.is_null() {
+        return Err(format!("Failed to allocate {}x{} bitmap", width, rows));
+    }
+    bindings.FPDFBitmap_FillRect(bitmap, 0, 0, width as c_int, rows as c_int, clear_color);
+    Ok(bitmap)
+}
+
+/// 取出位图中紧密排列的 4 通道像素并释放位图
+pub fn take_pixels(
+    bindings: &dyn PdfiumLibraryBindings,
+    bitmap: FPDF_BITMAP,
+    width: u32,
+    rows: u32,
+) -> Result<Vec<u8>, String> {
+    let stride = bindings.FPDFBitmap_GetStride(bitmap).max(0) as usize;
+    let row_bytes = width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_bytes * rows as usize);
+    #[allow(deprecated)]
+    let buffer = bindings.FPDFBitmap_GetBuffer(bitmap) as *const u8;
+    if !buffer.is_null() {
+        // SAFETY: 缓冲区大小为 stride * rows，在 FPDFBitmap_Destroy 之前有效
+        let data = unsafe { std::slice::from_raw_parts(buffer, stride * rows as usize) };
+        for row in data.chunks(stride.max(1)).take(rows as usize) {
+            pixels.extend_from_slice(&row[..row_bytes]);
+        }
+    }
+    bindings.FPDFBitmap_Destroy(bitmap);
+
+    if pixels.len() == row_bytes * rows as usize {
+        Ok(pixels)
+    } else {
+        Err("Failed to read rendered bitmap".to_string())
+    }
+}
+
+/// 在途分块窗口：渲染线程发送前占用名额，JS 确认后归还
+#[derive(Default)]
+pub struct ChunkWindow {
+    state: Mutex<WindowState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct WindowState {
+    in_flight: u32,
+    error: Option<String>,
+}
+
+impl ChunkWindow {
+    /// 等到在途分块少于 `limit` 后占用一个名额；JS 报告了错误或等待超时时返回错误
+    pub fn acquire(&self, limit: u32, timeout: Duration) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        let (mut state, result) = self
+            .changed
+            .wait_timeout_while(state, timeout, |s| s.error.is_none() && s.in_flight >= limit)
+            .unwrap();
+        if let Some(error) = &state.error {
+            return Err(error.clone());
+        }
+        if result.timed_out() {
+            return Err("Timed out waiting for chunk consumer".to_string());
+        }
+        state.in_flight += 1;
+        Ok(())
+    }
+
+    /// 等待所有在途分块被确认
+    pub fn drain(&self, timeout: Duration) -> Result<(), String> {
+        self.acquire(1, timeout).map(|_| self.complete(None))
+    }
+
+    /// JS 确认一个分块；`error` 表示回调处理失败，渲染随之中止
+    pub fn complete(&self, error: Option<String>) {
+        let mut state = self.state.lock().unwrap();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        if let Some(error) = error {
+            state.error.get_or_insert(error);
+        }
+        self.changed.notify_all();
+    }
+
+    /// 中止渲染（关闭模块时使用）
+    pub fn cancel(&self, reason: &str) {
+        self.complete(Some(reason.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bands_cover_the_page() {
+        let bands: Vec<_> = bands(10, 4).collect();
+        assert_eq!(bands, vec![(0, 4), (4, 4), (8, 2)]);
+        assert_eq!(chunk_rows(1024, 100_000, None), 1024);
+        assert_eq!(chunk_rows(1024, 10, Some(64)), 10);
+        assert_eq!(chunk_rows(1024, 10, Some(0)), 1);
+    }
+
+    #[test]
+    fn window_blocks_until_acknowledged() {
+        let window = ChunkWindow::default();
+        window.acquire(2, Duration::from_millis(10)).unwrap();
+        window.acquire(2, Duration::from_millis(10)).unwrap();
+        assert!(window.acquire(2, Duration::from_millis(10)).is_err());
+        window.complete(None);
+        window.acquire(2, Duration::from_millis(10)).unwrap();
+
+        window.complete(Some("upload failed".to_string()));
+        assert_eq!(window.acquire(3, Duration::from_millis(10)), Err("upload failed".to_string()));
+    }
+}
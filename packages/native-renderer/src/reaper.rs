@@ -0,0 +1,158 @@
+//! 空闲资源回收
+//!
+//! 常驻的 worker 在流量高峰之间长时间空闲，缓存的渲染结果和分配器保留的空闲堆内存
+//! 都不会自行释放。设置空闲时间（idle TTL）后，后台线程定期清除超过 TTL 未使用的
+//! 渲染缓存；进程内没有进行中的任务超过 TTL 时，再把空闲内存归还给操作系统。
+//! `trimMemory` 立即执行同样的回收。调度器的工作线程在没有任务时自行释放 PDFium 和文档，
+//! 不需要回收。
+//! 回收设置是进程级的，所有模块实例共享。
+
+use crate::{memory, render_cache};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 检查间隔的上下限：TTL 的四分之一，限制在 1-30 秒之间
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 空闲时间，None 表示关闭空闲回收
+static IDLE_TTL: Mutex<Option<Duration>> = Mutex::new(None);
+static WAKE: Condvar = Condvar::new();
+static REAPER_THREAD: OnceLock<()> = OnceLock::new();
+
+/// 进行中的任务数（所有模块实例）
+static ACTIVE_TASKS: AtomicUsize = AtomicUsize::new(0);
+/// 最近一次任务开始或结束的时间（相对进程内首次调用，毫秒）
+static LAST_ACTIVITY_MS: AtomicU64 = AtomicU64::new(0);
+/// 本次空闲期间是否已归还过内存（有新任务后重置）
+static TRIMMED: AtomicBool = AtomicBool::new(false);
+
+static REAPED_ENTRIES: AtomicU64 = AtomicU64::new(0);
+static REAPED_BYTES: AtomicU64 = AtomicU64::new(0);
+static ALLOCATOR_TRIMS: AtomicU64 = AtomicU64::new(0);
+
+/// 单次回收的结果
+pub struct Reaped {
+    pub cache_entries: u32,
+    pub cache_bytes: u64,
+    pub allocator_trimmed: bool,
+}
+
+/// 回收统计（进程启动以来）
+pub struct ReaperStats {
+    pub idle_ttl: Option<Duration>,
+    pub active_tasks: usize,
+    pub idle_for: Duration,
+    pub reaped_entries: u64,
+    pub reaped_bytes: u64,
+    pub allocator_trims: u64,
+}
+
+fn now_ms() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// 任务开始（由生命周期守卫调用）
+pub fn task_started() {
+    ACTIVE_TASKS.fetch_add(1, Ordering::SeqCst);
+    LAST_ACTIVITY_MS.store(now_ms(), Ordering::Relaxed);
+    TRIMMED.store(false, Ordering::Relaxed);
+}
+
+/// 任务结束（由生命周期守卫调用）
+pub fn task_finished() {
+    LAST_ACTIVITY_MS.store(now_ms(), Ordering::Relaxed);
+    ACTIVE_TASKS.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// 设置空闲时间，None 表示关闭空闲回收；首次启用时启动后台线程
+pub fn configure(idle_ttl: Option<Duration>) {
+    *IDLE_TTL.lock().unwrap() = idle_ttl.filter(|ttl| !ttl.is_zero());
+    if idle_ttl.is_some() {
+        REAPER_THREAD.get_or_init(|| {
+            let _ = std::thread::Builder::new()
+                .name("pdf-idle-reaper".to_string())
+                .spawn(reaper_loop);
+        });
+    }
+    WAKE.notify_all();
+}
+
+pub fn idle_ttl() -> Option<Duration> {
+    *IDLE_TTL.lock().unwrap()
+}
+
+/// 立即清空渲染缓存，并把空闲内存归还给操作系统
+pub fn trim_now() -> Reaped {
+    let cache_bytes = render_cache::stats().bytes;
+    let cache_entries = render_cache::clear();
+    let allocator_trimmed = memory::trim_allocator();
+    record(cache_entries, cache_bytes, allocator_trimmed);
+    Reaped {
+        cache_entries,
+        cache_bytes,
+        allocator_trimmed,
+    }
+}
+
+pub fn stats() -> ReaperStats {
+    let idle_for = if ACTIVE_TASKS.load(Ordering::SeqCst) > 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_millis(now_ms().saturating_sub(LAST_ACTIVITY_MS.load(Ordering::Relaxed)))
+    };
+    ReaperStats {
+        idle_ttl: idle_ttl(),
+        active_tasks: ACTIVE_TASKS.load(Ordering::SeqCst),
+        idle_for,
+        reaped_entries: REAPED_ENTRIES.load(Ordering::Relaxed),
+        reaped_bytes: REAPED_BYTES.load(Ordering::Relaxed),
+        allocator_trims: ALLOCATOR_TRIMS.load(Ordering::Relaxed),
+    }
+}
+
+fn record(entries: u32, bytes: u64, allocator_trimmed: bool) {
+    REAPED_ENTRIES.fetch_add(entries as u64, Ordering::Relaxed);
+    REAPED_BYTES.fetch_add(bytes, Ordering::Relaxed);
+    ALLOCATOR_TRIMS.fetch_add(allocator_trimmed as u64, Ordering::Relaxed);
+}
+
+/// 检查间隔
+fn interval(ttl: Duration) -> Duration {
+    (ttl / 4).clamp(MIN_INTERVAL, MAX_INTERVAL)
+}
+
+fn reaper_loop() {
+    loop {
+        // 等待期间持有设置锁，检查和回收时不持有，不阻塞 configure
+        let ttl = {
+            let guard = IDLE_TTL.lock().unwrap();
+            let guard = match *guard {
+                Some(ttl) => WAKE.wait_timeout(guard, interval(ttl)).unwrap().0,
+                None => WAKE.wait(guard).unwrap(),
+            };
+            *guard
+        };
+        let Some(ttl) = ttl else { continue };
+
+        let (entries, bytes) = render_cache::evict_idle(ttl);
+        // 空闲期间只归还一次；之后又清除了缓存时再归还一次
+        let idle = stats().idle_for >= ttl;
+        let allocator_trimmed = idle && (!TRIMMED.swap(true, Ordering::Relaxed) || entries > 0) && memory::trim_allocator();
+        record(entries, bytes, allocator_trimmed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(interval(Duration::from_secs(2)), MIN_INTERVAL);
+        assert_eq!(interval(Duration::from_secs(60)), Duration::from_secs(15));
+        assert_eq!(interval(Duration::from_secs(600)), MAX_INTERVAL);
+    }
+}
@@ -0,0 +1,68 @@
+//! 页面局部区域的渲染尺寸计算
+//!
+//! 区域以点为单位，坐标原点在页面左上角（已考虑页面旋转，与 scale 为 1 时的渲染图像一致）。
+//! 渲染时用变换矩阵把区域左上角平移到位图原点，PDFium 只绘制位图范围内的内容，
+//! 高倍率截取签名框或插图时不必先渲染整页再裁剪。
+
+/// 页面上的矩形区域（点）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Region {
+    /// 把区域限制在页面范围内；区域无效或与页面没有交集时返回错误
+    pub fn clip_to_page(&self, page_width: f64, page_height: f64) -> Result<Region, String> {
+        let values = [self.x, self.y, self.width, self.height];
+        if values.iter().any(|v| !v.is_finite()) || self.width <= 0.0 || self.height <= 0.0 {
+            return Err(format!(
+                "Invalid region: {}x{} at ({}, {})",
+                self.width, self.height, self.x, self.y
+            ));
+        }
+        let left = self.x.max(0.0);
+        let top = self.y.max(0.0);
+        let right = (self.x + self.width).min(page_width);
+        let bottom = (self.y + self.height).min(page_height);
+        if right <= left || bottom <= top {
+            return Err(format!(
+                "Region {}x{} at ({}, {}) is outside the page ({}x{})",
+                self.width, self.height, self.x, self.y, page_width, page_height
+            ));
+        }
+        Ok(Region {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+
+    /// 按缩放比例计算的像素尺寸（至少 1 像素）
+    pub fn pixel_size(&self, scale: f64) -> (u32, u32) {
+        (
+            ((self.width * scale).round() as u32).max(1),
+            ((self.height * scale).round() as u32).max(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_to_page() {
+        let region = Region { x: -10.0, y: 700.0, width: 100.0, height: 200.0 };
+        let clipped = region.clip_to_page(595.0, 842.0).unwrap();
+        assert_eq!(clipped, Region { x: 0.0, y: 700.0, width: 90.0, height: 142.0 });
+        assert_eq!(clipped.pixel_size(4.0), (360, 568));
+
+        assert!(Region { x: 600.0, y: 0.0, width: 10.0, height: 10.0 }.clip_to_page(595.0, 842.0).is_err());
+        assert!(Region { x: 0.0, y: 0.0, width: 0.0, height: 10.0 }.clip_to_page(595.0, 842.0).is_err());
+        assert!(Region { x: f64::NAN, y: 0.0, width: 1.0, height: 1.0 }.clip_to_page(595.0, 842.0).is_err());
+    }
+}
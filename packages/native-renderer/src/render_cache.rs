@@ -0,0 +1,387 @@
+//! 页面渲染结果缓存
+//!
+//! 查看器在页面之间来回跳转时，同一文档、同一页、同样配置的渲染结果完全相同。
+//! 缓存按 (文档指纹, 页码, 配置) 保存编码后的页面，命中时直接返回，不再渲染和编码。
+//! 缓存占用的是进程内存，所有模块实例共享；默认关闭，需要通过 `configure` 设置容量。
+
+use crate::config::RenderConfig;
+use crate::{ImageStats, RenderInfo, RequestedSize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 缓存键
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    document: String,
+    page_num: u32,
+    config: u64,
+}
+
+impl CacheKey {
+    pub fn new(document: &str, page_num: u32, config: &RenderConfig) -> Self {
+        Self {
+            document: document.to_string(),
+            page_num,
+            config: config_hash(config, page_num),
+        }
+    }
+}
+
+/// 缓存的页面输出
+#[derive(Clone)]
+pub struct CachedPage {
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+    pub data: Vec<u8>,
+    pub render_info: Option<RenderInfo>,
+    /// 附加编码：(格式, 数据)
+    pub alternates: Option<Vec<(String, Vec<u8>)>>,
+    pub image_stats: Option<ImageStats>,
+    pub content_hash: Option<String>,
+    pub clamped: bool,
+    pub requested_size: Option<RequestedSize>,
+    pub passthrough: bool,
+    pub detected_scan: Option<bool>,
+    pub text_object_count: Option<u32>,
+    pub image_coverage: Option<f64>,
+    pub downscaled_due_to_memory: bool,
+}
+
+impl CachedPage {
+    fn size(&self) -> u64 {
+        let alternates: usize = self.alternates.iter().flatten().map(|(_, data)| data.len()).sum();
+        (self.data.len() + alternates) as u64
+    }
+}
+
+struct Entry {
+    page: CachedPage,
+    last_used: u64,
+    /// 最近一次写入或命中的时间（空闲回收按此判断）
+    last_used_at: Instant,
+}
+
+struct Cache {
+    entries: HashMap<CacheKey, Entry>,
+    /// 最多缓存的页数，0 表示关闭
+    max_entries: u32,
+    /// 缓存数据的总字节数上限，0 表示关闭
+    max_bytes: u64,
+    bytes: u64,
+    access_counter: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+/// 缓存统计
+pub struct CacheStats {
+    pub entries: u32,
+    pub bytes: u64,
+    pub max_entries: u32,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// 设置缓存容量；任一上限为 0 时关闭缓存并清空
+pub fn configure(max_entries: u32, max_bytes: u64) {
+    let mut cache = CACHE.lock().unwrap();
+    if max_entries == 0 || max_bytes == 0 {
+        *cache = None;
+        return;
+    }
+    let cache = cache.get_or_insert_with(|| Cache {
+        entries: HashMap::new(),
+        max_entries,
+        max_bytes,
+        bytes: 0,
+        access_counter: 0,
+        hits: 0,
+        misses: 0,
+        evictions: 0,
+    });
+    cache.max_entries = max_entries;
+    cache.max_bytes = max_bytes;
+    cache.evict(0);
+}
+
+pub fn enabled() -> bool {
+    CACHE.lock().unwrap().is_some()
+}
+
+pub fn get(key: &CacheKey) -> Option<CachedPage> {
+    CACHE.lock().unwrap().as_mut()?.get(key)
+}
+
+pub fn put(key: CacheKey, page: CachedPage) {
+    if let Some(cache) = CACHE.lock().unwrap().as_mut() {
+        cache.put(key, page);
+    }
+}
+
+/// 清空缓存（保留容量设置），返回清除的页数
+pub fn clear() -> u32 {
+    match CACHE.lock().unwrap().as_mut() {
+        Some(cache) => {
+            let count = cache.entries.len() as u32;
+            cache.entries.clear();
+            cache.bytes = 0;
+            count
+        }
+        None => 0,
+    }
+}
+
+/// 清除超过 `idle` 未被使用的页面（保留容量设置），返回清除的页数和字节数
+pub fn evict_idle(idle: Duration) -> (u32, u64) {
+    match CACHE.lock().unwrap().as_mut() {
+        Some(cache) => cache.evict_idle(idle),
+        None => (0, 0),
+    }
+}
+
+pub fn stats() -> CacheStats {
+    match CACHE.lock().unwrap().as_ref() {
+        Some(cache) => CacheStats {
+            entries: cache.entries.len() as u32,
+            bytes: cache.bytes,
+            max_entries: cache.max_entries,
+            max_bytes: cache.max_bytes,
+            hits: cache.hits,
+            misses: cache.misses,
+            evictions: cache.evictions,
+        },
+        None => CacheStats {
+            entries: 0,
+            bytes: 0,
+            max_entries: 0,
+            max_bytes: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        },
+    }
+}
+
+impl Cache {
+    fn get(&mut self, key: &CacheKey) -> Option<CachedPage> {
+        self.access_counter += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = self.access_counter;
+                entry.last_used_at = Instant::now();
+                self.hits += 1;
+                Some(entry.page.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn put(&mut self, key: CacheKey, page: CachedPage) {
+        let size = page.size();
+        // 单页超过总容量时不缓存，避免把其他页面全部挤掉
+        if size > self.max_bytes {
+            return;
+        }
+        if let Some(old) = self.entries.remove(&key) {
+            self.bytes -= old.page.size();
+        }
+        self.evict(size);
+        self.access_counter += 1;
+        self.bytes += size;
+        self.entries.insert(
+            key,
+            Entry {
+                page,
+                last_used: self.access_counter,
+                last_used_at: Instant::now(),
+            },
+        );
+    }
+
+    fn evict_idle(&mut self, idle: Duration) -> (u32, u64) {
+        let before = (self.entries.len(), self.bytes);
+        self.entries.retain(|_, entry| entry.last_used_at.elapsed() < idle);
+        self.bytes = self.entries.values().map(|entry| entry.page.size()).sum();
+        ((before.0 - self.entries.len()) as u32, before.1 - self.bytes)
+    }
+
+    /// 淘汰最久未使用的页面，直到能再放入 `incoming` 字节的一页
+    fn evict(&mut self, incoming: u64) {
+        let reserve = (incoming > 0) as usize;
+        while !self.entries.is_empty()
+            && (self.entries.len() + reserve > self.max_entries as usize || self.bytes + incoming > self.max_bytes)
+        {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(key) = oldest {
+                if let Some(entry) = self.entries.remove(&key) {
+                    self.bytes -= entry.page.size();
+                    self.evictions += 1;
+                }
+            }
+        }
+    }
+}
+
+/// 可以作为缓存键的文档标识
+///
+/// `source` 是最近渲染记录使用的文档指纹。内存文档的指纹包含长度和首尾采样哈希；
+/// 文件再加上修改时间，文件被原地改写后不会命中旧结果。流式文档的指纹只在单次任务内有效，不缓存。
+pub fn document_key(source: &str) -> Option<String> {
+    if source.starts_with("buffer:") {
+        return Some(source.to_string());
+    }
+    let rest = source.strip_prefix("file:")?;
+    // file:{path}:{len}:{hash}，路径本身可能包含冒号
+    let mut parts = rest.rsplitn(3, ':');
+    let (_hash, _len, path) = (parts.next()?, parts.next()?, parts.next()?);
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let nanos = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_nanos();
+    Some(format!("{}:{}", source, nanos))
+}
+
+/// 影响该页输出的配置的哈希
+///
+/// 按页码区分的设置只取本页的条目，其他页面的覆盖不影响本页的缓存；
+/// 密码、时间预算和资源限制不影响输出，不参与计算（见 `RenderConfig::output_settings`）。
+fn config_hash(config: &RenderConfig, page_num: u32) -> u64 {
+    let mut normalized = config.output_settings();
+    normalized.page_scales.clear();
+    normalized.scan_widths.clear();
+    normalized.page_formats.clear();
+    normalized.highlights.clear();
+
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", normalized).hash(&mut hasher);
+    format!("{:?}", config.page_scales.get(&page_num)).hash(&mut hasher);
+    format!("{:?}", config.scan_widths.get(&page_num)).hash(&mut hasher);
+    format!("{:?}", config.page_formats.get(&page_num)).hash(&mut hasher);
+    format!("{:?}", config.highlights.get(&page_num)).hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PageScale;
+    use crate::renderer::{DeliveryOrder, OutputFormat};
+
+    fn page(size: usize) -> CachedPage {
+        CachedPage {
+            width: 1,
+            height: 1,
+            format: "webp".to_string(),
+            data: vec![0; size],
+            render_info: None,
+            alternates: None,
+            image_stats: None,
+            content_hash: None,
+            clamped: false,
+            requested_size: None,
+            passthrough: false,
+            detected_scan: None,
+            text_object_count: None,
+            image_coverage: None,
+            downscaled_due_to_memory: false,
+        }
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = Cache {
+            entries: HashMap::new(),
+            max_entries: 2,
+            max_bytes: 100,
+            bytes: 0,
+            access_counter: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        };
+        let config = RenderConfig::default();
+        let key = |page_num| CacheKey::new("buffer:1:0", page_num, &config);
+
+        cache.put(key(1), page(10));
+        cache.put(key(2), page(10));
+        assert!(cache.get(&key(1)).is_some());
+        // 超出页数上限：淘汰最久未使用的第 2 页
+        cache.put(key(3), page(10));
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(1)).is_some());
+        // 超出字节上限：需要腾出空间
+        cache.put(key(4), page(95));
+        assert_eq!(cache.entries.len(), 1);
+        assert_eq!(cache.bytes, 95);
+        // 单页超出总容量：不缓存
+        cache.put(key(5), page(200));
+        assert!(cache.get(&key(5)).is_none());
+        assert_eq!((cache.hits, cache.misses, cache.evictions), (2, 2, 3));
+    }
+
+    #[test]
+    fn test_evict_idle() {
+        let mut cache = Cache {
+            entries: HashMap::new(),
+            max_entries: 4,
+            max_bytes: 100,
+            bytes: 0,
+            access_counter: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        };
+        let config = RenderConfig::default();
+        cache.put(CacheKey::new("buffer:1:0", 1, &config), page(10));
+        cache.put(CacheKey::new("buffer:1:0", 2, &config), page(20));
+        cache.entries.values_mut().next().unwrap().last_used_at -= Duration::from_secs(60);
+        let (entries, bytes) = cache.evict_idle(Duration::from_secs(30));
+        assert_eq!(entries, 1);
+        assert_eq!(cache.entries.len(), 1);
+        assert_eq!(cache.bytes + bytes, 30);
+        assert_eq!(cache.evict_idle(Duration::from_secs(30)), (0, 0));
+    }
+
+    #[test]
+    fn test_config_hash_uses_only_page_entries() {
+        let mut config = RenderConfig::default();
+        let base = config_hash(&config, 1);
+        config.page_scales.insert(2, PageScale::Width(800));
+        config.passwords.push("secret".to_string());
+        assert_eq!(config_hash(&config, 1), base);
+        assert_ne!(config_hash(&config, 2), base);
+        config.page_formats.insert(3, OutputFormat::Png);
+        assert_eq!(config_hash(&config, 1), base);
+        assert_ne!(config_hash(&config, 3), config_hash(&RenderConfig::default(), 3));
+        config.delivery_order = DeliveryOrder::Input;
+        assert_eq!(config_hash(&config, 1), base);
+        config.target_width = 640;
+        assert_ne!(config_hash(&config, 1), base);
+    }
+
+    #[test]
+    fn test_document_key() {
+        assert_eq!(document_key("buffer:10:00ff").as_deref(), Some("buffer:10:00ff"));
+        assert!(document_key("stream:10:3").is_none());
+        assert!(document_key("file:/no/such/file.pdf:10:00ff").is_none());
+        let path = std::env::temp_dir().join("render_cache_key.pdf");
+        std::fs::write(&path, b"%PDF").unwrap();
+        let source = format!("file:{}:4:00ff", path.display());
+        assert!(document_key(&source).unwrap().starts_with(&source));
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -1,24 +1,93 @@
 //! PDF 渲染核心实现
 
-use crate::config::RenderConfig;
-use crate::{PageResult, RawBitmapResult};
-use image::{ImageBuffer, Rgba, ImageEncoder};
+use crate::analysis::{self, Rect};
+use crate::cancel::{self, CancelFlag};
+use crate::color;
+use crate::compare::PageGeometry;
+use crate::custom_encoders::{self, EncodeInput};
+use crate::config::{PageScale, RenderConfig, ScanWidth};
+use crate::encoders::{self, Backend};
+use crate::error::ErrorCode;
+use crate::filters;
+use crate::flight_recorder;
+use crate::content_hash;
+use crate::image_stats;
+use crate::jpeg::{self, ChromaSubsampling};
+use crate::memory::{self, Watermark};
+use crate::object_check;
+use crate::overlay;
+use crate::page_ranges::PageSelection;
+use crate::phash;
+use crate::quantize::{self, Quantized};
+use crate::raw_chunks::{self, RawChunk};
+use crate::region::Region;
+use crate::render_cache::{self, CacheKey, CachedPage};
+use crate::scan_passthrough;
+use crate::stamp;
+use crate::scheduler::DocumentSource;
+use crate::sprite::{self, SpriteLayout};
+use crate::structure::{self, StructNode};
+use crate::tiff::{TiffColor, TiffWriter};
+use crate::tiles::{self, TileGrid, TileRect};
+use crate::usage;
+use crate::watchdog;
+use crate::{
+    AlternateEncoding, DuplicateCluster, EmbeddedThumbnail, ExtractedPage, FormField, ImageStats, LayoutRegion, ObjectError, ObjectRegion, PageSpans, OutlineAnchor, OutlineItem, PageFigure, PageFigures, PageHash, PageImage, PageImages, PageAnnotation, PageAnnotations,
+    PageInfo, PageLayout, PageOverview, PageResult, PageTextRects, PageTextStats, PageTile, PageTiles, RawBitmapResult, RawChunkResult, RenderInfo, RequestedSize, SpreadResult, SpriteCell,
+    TextRect, TiffPageResult, ZoomTiles,
+};
+use image::{ImageBuffer, ImageEncoder, Luma, Rgba};
 use image::codecs::png::{CompressionType, FilterType, PngEncoder};
 use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
 use napi::bindgen_prelude::*;
 use pdfium_render::prelude::*;
-use webp::{Encoder as WebpEncoder, WebPConfig};
+use webp::WebPConfig;
+use std::collections::BTreeMap;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 /// WebP 格式限制
 const WEBP_MAX_DIMENSION: u32 = 16383;
 
+/// AVIF 单边尺寸上限（libavif / 浏览器解码器默认拒绝更大的图像）
+const AVIF_MAX_DIMENSION: u32 = 16384;
+
+/// PNG/JPG/原始位图的尺寸上限（理论上支持更大尺寸，但为了性能和内存限制在 32767）
+const RAW_MAX_DIMENSION: u32 = 32767;
+
+/// 编码超出 encodeBudgetMs 后改用的 WebP 质量上限（method 同时降为 0）
+const BUDGET_FALLBACK_QUALITY: f32 = 75.0;
+
+/// 放置分辨率低于此值（DPI）的图片不参与统计：通常是退化的变换矩阵，而不是真实的图片
+const MIN_PLACED_DPI: f64 = 1.0;
+
+/// 交给 JS 的 Buffer 空闲容量超过此值（且超过数据长度的 1/8）时先收缩
+const BUFFER_SHRINK_SLACK: usize = 64 * 1024;
+
+/// 两遍渲染未指定 previewWidth 时的预览宽度
+const DEFAULT_PREVIEW_WIDTH: u32 = 320;
+
+/// 书签最多展开的条目数和层级（防止损坏文档中的循环引用）
+const MAX_OUTLINE_ENTRIES: usize = 10_000;
+const MAX_OUTLINE_DEPTH: u32 = 64;
+
+/// 深度缩放分块的边长范围，以及不指定分块时一次最多渲染的分块数
+pub const DEFAULT_ZOOM_TILE_SIZE: u32 = 256;
+const MIN_ZOOM_TILE_SIZE: u32 = 64;
+const MAX_ZOOM_TILE_SIZE: u32 = 4096;
+const MAX_ZOOM_TILES: usize = 256;
+
 /// 输出格式
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OutputFormat {
     WebP,
     Png,
     Jpg,
+    Tiff,
+    /// 本模块不内置 AVIF 编码器，需要 registerEncoder 注册的编码器完成编码
+    Avif,
 }
 
 impl OutputFormat {
@@ -26,36 +95,627 @@ impl OutputFormat {
         match s.to_lowercase().as_str() {
             "png" => OutputFormat::Png,
             "jpg" | "jpeg" => OutputFormat::Jpg,
+            "tif" | "tiff" => OutputFormat::Tiff,
+            "avif" => OutputFormat::Avif,
             _ => OutputFormat::WebP,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::WebP => "webp",
+            OutputFormat::Png => "png",
+            OutputFormat::Jpg => "jpg",
+            OutputFormat::Tiff => "tiff",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    /// 格式本身的单边像素上限（WebP、AVIF），没有上限的格式返回 None
+    pub fn max_dimension(&self) -> Option<u32> {
+        match self {
+            OutputFormat::WebP => Some(WEBP_MAX_DIMENSION),
+            OutputFormat::Avif => Some(AVIF_MAX_DIMENSION),
+            _ => None,
+        }
+    }
+}
+
+/// 页面尺寸超出输出格式上限（WebP 单边 16383）时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OversizeStrategy {
+    /// 等比缩小到上限以内（默认）
+    Downscale,
+    /// 保持原尺寸，改用 PNG 输出
+    FallbackPng,
+    /// 保持原尺寸，切成多块分别编码
+    Tile,
+}
+
+impl OversizeStrategy {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "fallbackpng" | "fallback_png" | "fallback-png" => OversizeStrategy::FallbackPng,
+            "tile" | "tiles" => OversizeStrategy::Tile,
+            _ => OversizeStrategy::Downscale,
+        }
+    }
+}
+
+/// 并行编码时 onPage 回调的顺序
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeliveryOrder {
+    /// 哪页先编码完成先回调哪页（默认）
+    Completion,
+    /// 按请求的页码顺序回调，先完成的页面等待前面的页面
+    Input,
+}
+
+impl DeliveryOrder {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "input" => DeliveryOrder::Input,
+            _ => DeliveryOrder::Completion,
+        }
+    }
+}
+
+/// getPageTextRects 返回的文字位置粒度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextGranularity {
+    /// 按词合并逐字的外接矩形（默认）
+    Word,
+    /// PDFium 的文字段（同一行上字体和样式相同的连续文字）
+    Run,
+}
+
+impl TextGranularity {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "run" => TextGranularity::Run,
+            _ => TextGranularity::Word,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextGranularity::Word => "word",
+            TextGranularity::Run => "run",
+        }
+    }
+}
+
+/// 纯文字页面的编码方式（仅 WebP 输出）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmartEncoding {
+    /// 所有页面使用相同的编码参数（默认）
+    Off,
+    /// 转为灰度，用最慢但压缩最好的有损方法（method 6）编码
+    Grayscale,
+    /// 转为灰度后无损编码，文字边缘没有有损压缩的振铃
+    Lossless,
+}
+
+impl SmartEncoding {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "grayscale" | "gray" | "auto" => SmartEncoding::Grayscale,
+            "lossless" => SmartEncoding::Lossless,
+            _ => SmartEncoding::Off,
+        }
+    }
+}
+
+/// 渲染尺寸的计算结果
+struct RenderPlan {
+    width: u32,
+    height: u32,
+    scale: f32,
+    /// 决定尺寸的选项
+    size_source: &'static str,
+    /// 缩放比例是否被 max_scale 限制
+    max_scale_applied: bool,
+    /// 扫描件降级宽度是否被 max_scale 限制
+    scale_clamped: bool,
+    /// 是否因输出格式的尺寸上限被缩小
+    clamped: bool,
+    /// 被尺寸上限缩小前的尺寸
+    requested_width: u32,
+    requested_height: u32,
+    /// 是否因超出内存预算被缩小
+    memory_downscaled: bool,
+}
+
+/// 不渲染时按与渲染相同的尺寸计算出的输出图像尺寸和坐标换算
+pub struct OutputMapping {
+    /// 输出图像宽度（固定画布时为画布宽度）
+    pub width: u32,
+    /// 输出图像高度
+    pub height: u32,
+    /// 缩放比例（像素/点）
+    pub scale: f32,
+    /// PDF 坐标（点）到输出图像像素的仿射变换，与 renderInfo.transform 相同
+    pub transform: [f64; 6],
+    /// 页面在画布中的偏移（像素，没有画布时为 0）
+    offset: (f32, f32),
+}
+
+impl OutputMapping {
+    /// PDF 矩形（点）在输出图像中的外接矩形（像素）
+    pub fn to_pixels(&self, rect: &PdfRect) -> Rect {
+        let [a, b, c, d, e, f] = self.transform;
+        let corners = [
+            (rect.left().value, rect.bottom().value),
+            (rect.right().value, rect.bottom().value),
+            (rect.left().value, rect.top().value),
+            (rect.right().value, rect.top().value),
+        ]
+        .map(|(x, y)| ((a * x as f64 + c * y as f64 + e) as f32, (b * x as f64 + d * y as f64 + f) as f32));
+        corners[1..].iter().fold(
+            Rect::from_corners(corners[0].0, corners[0].1, corners[0].0, corners[0].1),
+            |r, &(x, y)| r.union(&Rect::from_corners(x, y, x, y)),
+        )
+    }
+
+    /// 输出图像中的矩形（像素）换算为页面坐标（点，原点在左上角，已考虑页面旋转）
+    pub fn to_page_points(&self, rect: &Rect) -> Rect {
+        let scale = self.scale.max(f32::EPSILON);
+        let (dx, dy) = self.offset;
+        Rect::from_corners(
+            (rect.left - dx) / scale,
+            (rect.top - dy) / scale,
+            (rect.right - dx) / scale,
+            (rect.bottom - dy) / scale,
+        )
+    }
+}
+
+/// 渲染阶段的产物：紧密排列的位图及编码阶段需要的页面信息
+struct RenderedPage {
+    page_num: u32,
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    max_dimension: u32,
+    /// 只超出尺寸上限几个像素，编码前裁掉边缘
+    cropped: bool,
+    /// 超出尺寸上限较多，编码前缩小
+    resized: bool,
+    transform: Option<[f64; 6]>,
+    is_scan: bool,
+    /// 扫描件识别结果（未做识别时为空）
+    scan: Option<ScanDecision>,
+    text_only: bool,
+    plan: RenderPlan,
+    color_converted: u32,
+    /// 可能没有画出来的页面对象（objectDiagnostics）
+    object_errors: Option<Vec<ObjectError>>,
+    render_time: u32,
+}
+
+/// 扫描件识别结果
+#[derive(Debug, Clone, Copy)]
+struct ScanDecision {
+    is_scan: bool,
+    text_objects: u32,
+    /// 图片外接矩形面积之和占页面面积的比例（不超过 1）
+    image_coverage: f64,
+}
+
+/// 渲染完成、等待编码的页面
+struct PendingEncode {
+    rendered: RenderedPage,
+    recorder: flight_recorder::Recorder,
+    cache_key: Option<CacheKey>,
+    /// 渲染时超出的内存水位，编码参数随之降级
+    watermark: Option<Watermark>,
+}
+
+/// 单页渲染阶段的结果
+enum PageStep {
+    /// 已经有结果（跳过、命中缓存或渲染失败）
+    Done(Box<PageResult>),
+    Encode(Box<PendingEncode>),
+}
+
+/// 并行编码时收集各页结果，并按 deliveryOrder 交给 onPage
+struct Delivery {
+    on_page: Option<PageCallback>,
+    order: DeliveryOrder,
+    slots: Vec<Mutex<Option<PageResult>>>,
+    /// 按输入顺序交付时：下一个要交付的下标，以及已完成、等待前面页面的结果
+    waiting: Mutex<(usize, BTreeMap<usize, PageResult>)>,
+}
+
+impl Delivery {
+    fn new(on_page: Option<PageCallback>, order: DeliveryOrder, len: usize) -> Self {
+        Self {
+            on_page,
+            order,
+            slots: (0..len).map(|_| Mutex::new(None)).collect(),
+            waiting: Mutex::new((0, BTreeMap::new())),
+        }
+    }
+
+    /// 第 `index` 页完成
+    fn complete(&self, index: usize, page: PageResult) {
+        let Some(on_page) = &self.on_page else {
+            *self.slots[index].lock().unwrap() = Some(page);
+            return;
+        };
+        if self.order == DeliveryOrder::Completion {
+            *self.slots[index].lock().unwrap() = Some(deliver_to(on_page, page));
+            return;
+        }
+        // 持锁回调，保证回调顺序与页码顺序一致
+        let mut waiting = self.waiting.lock().unwrap();
+        waiting.1.insert(index, page);
+        loop {
+            let next = waiting.0;
+            let Some(page) = waiting.1.remove(&next) else { break };
+            *self.slots[next].lock().unwrap() = Some(deliver_to(on_page, page));
+            waiting.0 += 1;
+        }
+    }
+
+    /// 按页码顺序返回结果；编码线程异常退出时，排在缺失页面之后的结果仍依次交付
+    fn into_results(self) -> Vec<Option<PageResult>> {
+        let (_, waiting) = self.waiting.into_inner().unwrap();
+        let mut slots: Vec<Option<PageResult>> = self.slots.into_iter().map(|slot| slot.into_inner().unwrap()).collect();
+        if let Some(on_page) = &self.on_page {
+            for (index, page) in waiting {
+                slots[index] = Some(deliver_to(on_page, page));
+            }
+        }
+        slots
+    }
+}
+
+/// 把完成的页面交给回调，返回不含图像数据的摘要
+fn deliver_to(on_page: &PageCallback, page: PageResult) -> PageResult {
+    let summary = PageResult {
+        page_num: page.page_num,
+        width: page.width,
+        height: page.height,
+        buffer: Buffer::from(vec![]),
+        success: page.success,
+        error: page.error.clone(),
+        render_time: page.render_time,
+        encode_time: page.encode_time,
+        skipped: page.skipped,
+        format: page.format.clone(),
+        tiles: None,
+        render_info: page.render_info.clone(),
+        error_code: page.error_code.clone(),
+        alternates: None,
+        cached: page.cached,
+        passthrough: page.passthrough,
+        detected_scan: page.detected_scan,
+        text_object_count: page.text_object_count,
+        image_coverage: page.image_coverage,
+        downscaled_due_to_memory: page.downscaled_due_to_memory,
+        image_stats: page.image_stats.clone(),
+        content_hash: page.content_hash.clone(),
+        spans: page.spans.clone(),
+        clamped: page.clamped,
+        requested_size: page.requested_size.clone(),
+    };
+    on_page(page);
+    summary
+}
+
+/// 位图像素通道顺序
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelFormat {
+    /// R, G, B, A（默认）
+    Rgba,
+    /// B, G, R, A（PDFium 原生格式，无需通道转换）
+    Bgra,
+}
+
+impl PixelFormat {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "bgra" => PixelFormat::Bgra,
+            _ => PixelFormat::Rgba,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PixelFormat::Rgba => "rgba",
+            PixelFormat::Bgra => "bgra",
+        }
+    }
+}
+
+/// PDFium 底层渲染标志（`advancedFlags` 选项）
+///
+/// 直接映射到 PdfRenderConfig 的对应开关，未识别的标志名会被忽略。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdvancedFlags {
+    /// 不渲染注释（noAnnotations）
+    pub no_annotations: bool,
+    /// 不渲染表单控件和表单数据（noFormData）
+    pub no_form_data: bool,
+    /// 针对 LCD 优化文字渲染（lcdText）
+    pub lcd_text: bool,
+    /// 禁用平台原生文字渲染（noNativeText）
+    pub no_native_text: bool,
+    /// 灰度渲染（grayscale）
+    pub grayscale: bool,
+    /// 限制 PDFium 图片缓存大小（limitImageCache）
+    pub limit_image_cache: bool,
+    /// 强制半色调（forceHalftone）
+    pub force_halftone: bool,
+    /// 打印质量渲染（printMode）
+    pub print_mode: bool,
+    /// 关闭文字抗锯齿（noTextSmoothing）
+    pub no_text_smoothing: bool,
+    /// 关闭图片抗锯齿（noImageSmoothing）
+    pub no_image_smoothing: bool,
+    /// 关闭路径抗锯齿（noPathSmoothing）
+    pub no_path_smoothing: bool,
+    /// 将填充渲染为描边（fillsAsStrokes）
+    pub fills_as_strokes: bool,
+}
+
+impl AdvancedFlags {
+    pub fn from_names(names: &[String]) -> Self {
+        let mut flags = Self::default();
+        for name in names {
+            match name.as_str() {
+                "noAnnotations" => flags.no_annotations = true,
+                "noFormData" => flags.no_form_data = true,
+                "lcdText" => flags.lcd_text = true,
+                "noNativeText" => flags.no_native_text = true,
+                "grayscale" => flags.grayscale = true,
+                "limitImageCache" => flags.limit_image_cache = true,
+                "forceHalftone" => flags.force_halftone = true,
+                "printMode" => flags.print_mode = true,
+                "noTextSmoothing" => flags.no_text_smoothing = true,
+                "noImageSmoothing" => flags.no_image_smoothing = true,
+                "noPathSmoothing" => flags.no_path_smoothing = true,
+                "fillsAsStrokes" => flags.fills_as_strokes = true,
+                _ => {}
+            }
+        }
+        flags
+    }
+
+    /// 将标志应用到 PDFium 渲染配置
+    fn apply(&self, config: PdfRenderConfig) -> PdfRenderConfig {
+        config
+            .render_annotations(!self.no_annotations)
+            .render_form_data(!self.no_form_data)
+            .use_lcd_text_rendering(self.lcd_text)
+            .disable_native_text_rendering(self.no_native_text)
+            .use_grayscale_rendering(self.grayscale)
+            .limit_render_image_cache_size(self.limit_image_cache)
+            .force_half_tone(self.force_halftone)
+            .use_print_quality(self.print_mode)
+            .set_text_smoothing(!self.no_text_smoothing)
+            .set_image_smoothing(!self.no_image_smoothing)
+            .set_path_smoothing(!self.no_path_smoothing)
+            .render_fills_as_strokes(self.fills_as_strokes)
+    }
+}
+
+/// 表单控件的渲染方式（`formMode` 选项）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FormMode {
+    /// 通过 PDFium 表单环境绘制控件和当前值（默认）
+    #[default]
+    Render,
+    /// 渲染前把表单控件和注释按打印效果合并进页面内容
+    Flatten,
+    /// 不绘制表单控件
+    Omit,
+}
+
+impl FormMode {
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "flatten" => FormMode::Flatten,
+            "omit" => FormMode::Omit,
+            _ => FormMode::Render,
+        }
+    }
+}
+
+/// 表单字段类型名（getFormFields 的 kind）
+fn form_field_type_name(field_type: PdfFormFieldType) -> &'static str {
+    match field_type {
+        PdfFormFieldType::PushButton => "pushButton",
+        PdfFormFieldType::Checkbox => "checkbox",
+        PdfFormFieldType::RadioButton => "radioButton",
+        PdfFormFieldType::ComboBox => "comboBox",
+        PdfFormFieldType::ListBox => "listBox",
+        PdfFormFieldType::Text => "text",
+        PdfFormFieldType::Signature => "signature",
+        PdfFormFieldType::Unknown => "unknown",
+    }
+}
+
+/// 注释类型名（`annotationTypes` 选项和 getPageAnnotations 的 kind）与 PDFium 注释类型的对应关系
+const ANNOTATION_TYPES: [(&str, PdfPageAnnotationType); 27] = [
+    ("text", PdfPageAnnotationType::Text),
+    ("link", PdfPageAnnotationType::Link),
+    ("freeText", PdfPageAnnotationType::FreeText),
+    ("line", PdfPageAnnotationType::Line),
+    ("square", PdfPageAnnotationType::Square),
+    ("circle", PdfPageAnnotationType::Circle),
+    ("polygon", PdfPageAnnotationType::Polygon),
+    ("polyline", PdfPageAnnotationType::Polyline),
+    ("highlight", PdfPageAnnotationType::Highlight),
+    ("underline", PdfPageAnnotationType::Underline),
+    ("squiggly", PdfPageAnnotationType::Squiggly),
+    ("strikeOut", PdfPageAnnotationType::Strikeout),
+    ("stamp", PdfPageAnnotationType::Stamp),
+    ("caret", PdfPageAnnotationType::Caret),
+    ("ink", PdfPageAnnotationType::Ink),
+    ("popup", PdfPageAnnotationType::Popup),
+    ("fileAttachment", PdfPageAnnotationType::FileAttachment),
+    ("sound", PdfPageAnnotationType::Sound),
+    ("movie", PdfPageAnnotationType::Movie),
+    ("widget", PdfPageAnnotationType::Widget),
+    ("screen", PdfPageAnnotationType::Screen),
+    ("printerMark", PdfPageAnnotationType::PrinterMark),
+    ("trapNet", PdfPageAnnotationType::TrapNet),
+    ("watermark", PdfPageAnnotationType::Watermark),
+    ("threeD", PdfPageAnnotationType::ThreeD),
+    ("richMedia", PdfPageAnnotationType::RichMedia),
+    ("redact", PdfPageAnnotationType::Redacted),
+];
+
+/// 注释类型名（`annotationTypes` 选项）对应的 PDFium 注释类型，未识别的名称返回 None
+pub fn annotation_type_from_name(name: &str) -> Option<PdfPageAnnotationType> {
+    ANNOTATION_TYPES.iter().find(|(n, _)| *n == name).map(|(_, t)| *t)
+}
+
+/// PDFium 注释类型对应的类型名，不在 `annotationTypes` 可选值中的类型返回 "unknown"
+pub fn annotation_type_name(annotation_type: PdfPageAnnotationType) -> &'static str {
+    ANNOTATION_TYPES
+        .iter()
+        .find(|(_, t)| *t == annotation_type)
+        .map_or("unknown", |(n, _)| n)
+}
+
+/// 用书签的目标调用 `f`：书签可以直接带目标，也可以通过跳转动作指定，都没有时返回 None
+fn with_bookmark_destination<R>(bookmark: &PdfBookmark, f: impl FnOnce(&PdfDestination) -> R) -> Option<R> {
+    if let Some(destination) = bookmark.destination() {
+        return Some(f(&destination));
+    }
+    let action = bookmark.action()?;
+    let destination = action.as_local_destination_action()?.destination().ok()?;
+    Some(f(&destination))
+}
+
+fn build_outline_tree(mut bookmark: Option<PdfBookmark>, level: u32, num_pages: u32, remaining: &mut usize) -> Vec<OutlineItem> {
+    let mut items = Vec::new();
+    while let Some(current) = bookmark {
+        if *remaining == 0 {
+            break;
+        }
+        *remaining -= 1;
+        let page_num = with_bookmark_destination(&current, |destination| destination.page_index().ok())
+            .flatten()
+            .map(|index| index as u32 + 1)
+            .filter(|&n| n <= num_pages);
+        let children = if level < MAX_OUTLINE_DEPTH {
+            build_outline_tree(current.first_child(), level + 1, num_pages, remaining)
+        } else {
+            vec![]
+        };
+        items.push(OutlineItem {
+            title: current.title().unwrap_or_default(),
+            page_num,
+            children,
+        });
+        bookmark = current.next_sibling();
+    }
+    items
 }
 
 /// PDF 渲染器
 pub struct PdfRenderer<'a> {
     pdfium: &'a Pdfium,
+    /// 配置和编码阶段的状态，方法和字段通过 Deref 直接使用
+    encoder: PageEncoder,
+    /// 每页完成后调用（onPage），结果中只保留不含图像数据的摘要
+    on_page: Option<PageCallback>,
+    /// 两遍渲染时接收每页预览图的回调（onPreview）
+    on_preview: Option<PageCallback>,
+}
+
+/// 编码阶段用到的状态：渲染配置、取消标志和时间预算，不含 PDFium 绑定
+///
+/// 位图的后处理和编码（`finish_encode`）都是它的方法，从不调用 PDFium。
+/// 并行编码时每个编码线程持有自己的一份，只接收渲染阶段交来的位图。
+pub struct PageEncoder {
     config: RenderConfig,
+    /// 创建时间，总时间预算从这里开始计算（包含文档加载）
+    created_at: std::time::Instant,
+    /// options.cancelToken 对应的取消标志
+    cancel: Option<CancelFlag>,
+    /// 当前页的编码时间预算（只在 with_encode_budget 创建的副本上设置）
+    encode_budget: Option<EncodeBudget>,
 }
 
+impl std::ops::Deref for PdfRenderer<'_> {
+    type Target = PageEncoder;
+
+    fn deref(&self) -> &PageEncoder {
+        &self.encoder
+    }
+}
+
+/// 单页编码时间预算（encodeBudgetMs）
+struct EncodeBudget {
+    deadline: std::time::Instant,
+    /// 是否已超出预算；超出后同一页剩余的编码（分块、附加格式）直接使用快速参数
+    exceeded: AtomicBool,
+}
+
+/// 逐页交付结果的回调，可能在编码线程上调用
+pub type PageCallback = std::sync::Arc<dyn Fn(PageResult) + Send + Sync>;
+
 impl<'a> PdfRenderer<'a> {
     /// 创建新的渲染器实例
     pub fn new(pdfium: &'a Pdfium, config: RenderConfig) -> Self {
-        Self { pdfium, config }
+        let cancel = config.cancel_token.and_then(cancel::flag);
+        Self {
+            pdfium,
+            encoder: PageEncoder {
+                config,
+                created_at: std::time::Instant::now(),
+                cancel,
+                encode_budget: None,
+            },
+            on_page: None,
+            on_preview: None,
+        }
+    }
+
+    /// 渲染配置
+    pub fn config(&self) -> &RenderConfig {
+        &self.config
+    }
+
+    /// 设置逐页交付结果的回调
+    ///
+    /// 多页渲染时每页完成后立即把结果交给回调，返回的结果中该页只保留摘要
+    /// （buffer 为空，tiles、alternates 为空），避免图像数据保留两份。
+    pub fn with_page_callback(mut self, on_page: Option<PageCallback>) -> Self {
+        self.on_page = on_page;
+        self
+    }
+
+    /// 设置接收预览图的回调
+    ///
+    /// 配置了 previewWidth 时，每页先按预览宽度渲染一张小图交给回调，
+    /// 再按正式尺寸渲染、编码，作为该页的最终结果。
+    pub fn with_preview_callback(mut self, on_preview: Option<PageCallback>) -> Self {
+        self.on_preview = on_preview;
+        self
     }
 
     /// 从 Buffer 渲染 PDF 页面
     pub fn render_from_buffer(
         &self,
         pdf_data: &[u8],
-        page_nums: &[u32],
-    ) -> std::result::Result<(u32, Vec<PageResult>), String> {
+        pages: &PageSelection,
+    ) -> std::result::Result<(u32, Vec<PageResult>, Option<u32>), String> {
+        self.check_input(pdf_data.len() as u64, pages.known_pages())?;
+
         // 加载 PDF 文档
-        let document = self
-            .pdfium
-            .load_pdf_from_byte_slice(pdf_data, None)
-            .map_err(|e| format!("Failed to load PDF: {}", e))?;
+        let (document, password_index) = self
+            .load_document(pdf_data)
+            .map_err(|e| load_error("Failed to load PDF", &e))?;
 
-        self.render_document_pages(&document, page_nums)
+        let page_nums = pages.resolve(document.pages().len() as u32)?;
+        let (num_pages, pages) = self.render_document_pages(&document, &page_nums, &flight_recorder::fingerprint_bytes(pdf_data))?;
+        Ok((num_pages, pages, password_index))
     }
 
     /// 从文件路径渲染 PDF 页面
@@ -64,106 +724,2514 @@ impl<'a> PdfRenderer<'a> {
     pub fn render_from_file(
         &self,
         file_path: &str,
-        page_nums: &[u32],
-    ) -> std::result::Result<(u32, Vec<PageResult>), String> {
+        pages: &PageSelection,
+    ) -> std::result::Result<(u32, Vec<PageResult>, Option<u32>), String> {
+        if let Ok(metadata) = std::fs::metadata(file_path) {
+            self.check_input(metadata.len(), pages.known_pages())?;
+        }
+
         // 直接从文件加载 PDF 文档
-        let document = self
+        let (document, password_index) = self
+            .load_document_from_file(file_path)
+            .map_err(|e| load_error("Failed to load PDF from file", &e))?;
+
+        let page_nums = pages.resolve(document.pages().len() as u32)?;
+        let (num_pages, pages) = self.render_document_pages(&document, &page_nums, &flight_recorder::fingerprint_file(file_path))?;
+        Ok((num_pages, pages, password_index))
+    }
+
+    /// 加载文档，需要密码时依次尝试配置的候选密码
+    ///
+    /// 返回文档和成功打开文档的密码在 `passwords` 中的下标（未加密时为 None）。
+    pub fn load_document<'b>(&'b self, pdf_data: &'b [u8]) -> std::result::Result<(PdfDocument<'b>, Option<u32>), PdfiumError> {
+        try_passwords(&self.config.passwords, |password| self.pdfium.load_pdf_from_byte_slice(pdf_data, password))
+    }
+
+    /// 从文件加载文档，需要密码时依次尝试配置的候选密码
+    pub fn load_document_from_file<'b>(&'b self, file_path: &str) -> std::result::Result<(PdfDocument<'b>, Option<u32>), PdfiumError> {
+        try_passwords(&self.config.passwords, |password| self.pdfium.load_pdf_from_file(file_path, password))
+    }
+
+    /// 把多个来源文档的页面依次复制到一个新文档中（复制页面对象，不渲染）
+    ///
+    /// 每个来源的页码为空时复制全部页面。返回合成的文档，以及其中每页的来源（来源下标, 来源页码）。
+    pub fn compose_document(
+        &self,
+        sources: &[(DocumentSource, Vec<u32>)],
+    ) -> std::result::Result<(PdfDocument<'a>, Vec<(u32, u32)>), String> {
+        let mut composed = self
             .pdfium
-            .load_pdf_from_file(file_path, None)
-            .map_err(|e| format!("Failed to load PDF from file: {}", e))?;
+            .create_new_pdf()
+            .map_err(|e| format!("Failed to create PDF: {}", e))?;
+        let mut page_sources = Vec::new();
+
+        for (index, (source, page_nums)) in sources.iter().enumerate() {
+            let (document, _) = match source {
+                DocumentSource::File(path) => self.load_document_from_file(path),
+                DocumentSource::Bytes(bytes) => self.load_document(bytes),
+            }
+            .map_err(|e| load_error(&format!("Failed to load source {}", index), &e))?;
+            let num_pages = document.pages().len() as u32;
+            let page_nums: Vec<u32> = if page_nums.is_empty() {
+                (1..=num_pages).collect()
+            } else {
+                page_nums.clone()
+            };
 
-        self.render_document_pages(&document, page_nums)
+            for page_num in page_nums {
+                if page_num < 1 || page_num > num_pages {
+                    return Err(format!(
+                        "Invalid page number in source {}: {} (total: {})",
+                        index, page_num, num_pages
+                    ));
+                }
+                let page_index = (page_num - 1) as PdfPageIndex;
+                let destination = composed.pages().len();
+                composed
+                    .pages_mut()
+                    .copy_page_from_document(&document, page_index, destination)
+                    .map_err(|e| format!("Failed to copy page {} of source {}: {}", page_num, index, e))?;
+                page_sources.push((index as u32, page_num));
+            }
+        }
+        Ok((composed, page_sources))
     }
 
     /// 从已加载的 PdfDocument 渲染指定页面
     ///
     /// 这个方法允许外部代码先加载文档（例如通过流式加载），
-    /// 然后调用此方法进行渲染。
+    /// 然后调用此方法进行渲染。`source` 是文档指纹，写入最近渲染记录。
     pub fn render_document_pages(
         &self,
         document: &PdfDocument,
         page_nums: &[u32],
+        source: &str,
+    ) -> std::result::Result<(u32, Vec<PageResult>), String> {
+        self.render_document_pages_with(document, page_nums, source, |_| {})
+    }
+
+    /// 同 `render_document_pages`，每页开始渲染前调用 `before_page`（例如按页统计流式读取）
+    pub fn render_document_pages_with(
+        &self,
+        document: &PdfDocument,
+        page_nums: &[u32],
+        source: &str,
+        mut before_page: impl FnMut(u32),
     ) -> std::result::Result<(u32, Vec<PageResult>), String> {
+        self.config.limits.check_pages(page_nums.len()).map_err(|e| e.to_string())?;
+
+        let num_pages = document.pages().len() as u32;
+        if self.config.fail_out_of_range {
+            if let Some(page_num) = page_nums.iter().find(|&&p| p > num_pages) {
+                return Err(format!("Page out of range: {} (total: {})", page_num, num_pages));
+            }
+        }
+        let document_key = if render_cache::enabled() { render_cache::document_key(source) } else { None };
+        let concurrency = (self.config.concurrency as usize).min(page_nums.len());
+        // 流式文档的块请求会阻塞 PDFium 线程：编码放到后台后，下一页的网络读取与当前页的编码重叠
+        let overlap = self.config.overlap_fetch && page_nums.len() > 1;
+        if concurrency <= 1 && !overlap {
+            let results = page_nums
+                .iter()
+                .map(|&page_num| match self.begin_page(document, page_num, num_pages, source, document_key.as_deref(), &mut before_page) {
+                    PageStep::Done(result) => self.deliver(*result),
+                    PageStep::Encode(pending) => self.deliver(self.finish_encode(*pending)),
+                })
+                .collect();
+            return Ok((num_pages, results));
+        }
+
+        // PDFium 只能在当前线程串行使用：这里依次渲染位图，编码交给工作线程并行执行。
+        // 通道容量限制了等待编码的位图数量，避免整批位图同时驻留内存。
+        let concurrency = concurrency.max(1);
+        let delivery = Delivery::new(self.on_page.clone(), self.config.delivery_order, page_nums.len());
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<(usize, Box<PendingEncode>)>(concurrency);
+        let receiver = Mutex::new(receiver);
+        // 编码线程的 CPU 时间和分配计入调用方登记的资源统计
+        let meter = usage::current();
+        std::thread::scope(|scope| {
+            for _ in 0..concurrency {
+                // 编码线程持有自己的编码状态，只接收渲染阶段交来的位图，不引用 PDFium 绑定
+                let encoder = self.encoder.with_config(self.config.clone());
+                let (receiver, delivery, meter) = (&receiver, &delivery, &meter);
+                scope.spawn(move || {
+                    let _usage = meter.as_ref().map(usage::attach);
+                    loop {
+                        let job = receiver.lock().unwrap().recv();
+                        let Ok((index, pending)) = job else { break };
+                        delivery.complete(index, encoder.finish_encode(*pending));
+                    }
+                });
+            }
+            for (index, &page_num) in page_nums.iter().enumerate() {
+                match self.begin_page(document, page_num, num_pages, source, document_key.as_deref(), &mut before_page) {
+                    PageStep::Done(result) => delivery.complete(index, *result),
+                    PageStep::Encode(pending) => {
+                        let _ = sender.send((index, pending));
+                    }
+                }
+            }
+            // 关闭通道，编码线程处理完剩余页面后退出
+            drop(sender);
+        });
+
+        let results = delivery
+            .into_results()
+            .into_iter()
+            .zip(page_nums)
+            .map(|(slot, &page_num)| {
+                slot.unwrap_or_else(|| {
+                    PageResult::failed(page_num, self.config.format.as_str(), "Encoder thread exited".to_string(), ErrorCode::EncodeFailed)
+                })
+            })
+            .collect();
+        Ok((num_pages, results))
+    }
+
+    /// 单页的渲染阶段：预算和缓存检查、PDFium 渲染；需要编码时返回待编码的位图
+    fn begin_page(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        num_pages: u32,
+        source: &str,
+        document_key: Option<&str>,
+        before_page: &mut impl FnMut(u32),
+    ) -> PageStep {
+        if let Some(renderer) = self.with_page_format(page_num) {
+            return renderer.begin_page(document, page_num, num_pages, source, document_key, before_page);
+        }
+        if self.budget_exceeded() {
+            return PageStep::Done(Box::new(PageResult {
+                skipped: true,
+                ..PageResult::failed(
+                    page_num,
+                    self.config.format.as_str(),
+                    "Skipped: total time budget exceeded".to_string(),
+                    ErrorCode::Skipped,
+                )
+            }));
+        }
+
+        if self.cancelled() {
+            return PageStep::Done(Box::new(cancelled_page(page_num, self.config.format)));
+        }
+
+        before_page(page_num);
+        let cache_key = document_key.map(|document| CacheKey::new(document, page_num, &self.config));
+        if let Some(page) = cache_key.as_ref().and_then(render_cache::get) {
+            return PageStep::Done(Box::new(cached_result(page_num, page)));
+        }
+
+        self.deliver_preview(document, page_num, num_pages, source);
+        let recorder = flight_recorder::begin(source, page_num);
+        if let Some(result) = self.scan_passthrough(document, page_num, num_pages) {
+            return PageStep::Done(Box::new(self.finish_page(result, recorder, cache_key, false)));
+        }
+        let watermark = self.memory_pressure();
+        let rendered = match watermark {
+            Some(watermark) => self.degraded(&watermark).render_page_bitmap(document, page_num, num_pages, &recorder),
+            None => self.render_page_bitmap(document, page_num, num_pages, &recorder),
+        };
+        match rendered {
+            Ok(rendered) => PageStep::Encode(Box::new(PendingEncode {
+                rendered,
+                recorder,
+                cache_key,
+                watermark,
+            })),
+            Err(result) => PageStep::Done(Box::new(self.finish_page(*result, recorder, cache_key, watermark.is_some()))),
+        }
+    }
+
+    /// 两遍渲染的第一遍：用已加载的文档按 previewWidth 渲染预览图并交给 onPreview
+    ///
+    /// 预览失败时不调用回调，正式渲染照常进行；预览宽度不小于目标宽度时没有意义，直接跳过。
+    fn deliver_preview(&self, document: &PdfDocument, page_num: u32, num_pages: u32, source: &str) {
+        let Some(on_preview) = &self.on_preview else { return };
+        let width = self.config.preview_width.unwrap_or(DEFAULT_PREVIEW_WIDTH);
+        if width >= self.config.target_width {
+            return;
+        }
+        let mut config = self.config.clone();
+        config.target_width = width;
+        config.page_scales = BTreeMap::new();
+        config.dpi = None;
+        config.also_encode = Vec::new();
+        config.scan_passthrough = false;
+        config.preview_width = None;
+        let renderer = PdfRenderer {
+            pdfium: self.pdfium,
+            encoder: self.encoder.with_config(config),
+            on_page: None,
+            on_preview: None,
+        };
+        let preview = match renderer.begin_page(document, page_num, num_pages, source, None, &mut |_| {}) {
+            PageStep::Done(page) => *page,
+            PageStep::Encode(pending) => renderer.finish_encode(*pending),
+        };
+        if preview.success {
+            on_preview(preview);
+        }
+    }
+
+    /// 有 onPage 回调时把完成的页面交给回调，返回不含图像数据的摘要
+    fn deliver(&self, page: PageResult) -> PageResult {
+        match &self.on_page {
+            Some(on_page) => deliver_to(on_page, page),
+            None => page,
+        }
+    }
+}
+
+impl PageEncoder {
+    /// 单页的编码阶段（不访问 PDFium，可以在其他线程执行）
+    fn finish_encode(&self, pending: PendingEncode) -> PageResult {
+        if let Some(renderer) = self.with_page_format(pending.rendered.page_num) {
+            return renderer.finish_encode(pending);
+        }
+        let PendingEncode {
+            rendered,
+            recorder,
+            cache_key,
+            watermark,
+        } = pending;
+        // 排队等待编码期间被取消时丢弃位图，不再编码
+        if self.cancelled() {
+            return self.finish_page(cancelled_page(rendered.page_num, self.config.format), recorder, None, false);
+        }
+        let result = match watermark {
+            Some(watermark) => self.degraded(&watermark).encode_rendered_page(rendered, &recorder),
+            None => self.encode_rendered_page(rendered, &recorder),
+        };
+        self.finish_page(result, recorder, cache_key, watermark.is_some())
+    }
+
+    /// 记录耗时、写入缓存
+    fn finish_page(
+        &self,
+        mut result: PageResult,
+        recorder: flight_recorder::Recorder,
+        cache_key: Option<CacheKey>,
+        degraded: bool,
+    ) -> PageResult {
+        if degraded {
+            mark_degraded(&mut result.render_info);
+        }
+        let times = recorder.finish(result.error.clone());
+        result.spans = self.spans(times);
+        // 降级渲染的结果不缓存，内存回落后应重新按原尺寸渲染
+        if let Some(key) = cache_key.filter(|_| !degraded && is_cacheable(&result)) {
+            render_cache::put(key, cached_page(&result));
+        }
+        result
+    }
+
+    /// 是否已取消（options.cancelToken）
+    fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelFlag::is_cancelled)
+    }
+
+    /// 是否已超出总时间预算
+    fn budget_exceeded(&self) -> bool {
+        self.config
+            .total_time_budget_ms
+            .is_some_and(|budget| self.created_at.elapsed().as_millis() >= budget as u128)
+    }
+}
+
+impl<'a> PdfRenderer<'a> {
+    /// 渲染包含 `page_nums` 的跨页，`page_nums` 为空时渲染全部跨页
+    pub fn render_spreads(
+        &self,
+        document: &PdfDocument,
+        page_nums: &[u32],
+        cover: bool,
+    ) -> std::result::Result<(u32, Vec<SpreadResult>), String> {
         let num_pages = document.pages().len() as u32;
-        let mut results = Vec::with_capacity(page_nums.len());
+        let groups = spread_groups(num_pages, cover, page_nums);
+        self.config.limits.check_pages(groups.iter().map(Vec::len).sum()).map_err(|e| e.to_string())?;
+
+        let spreads = groups
+            .into_iter()
+            .map(|pages| {
+                let render_start = std::time::Instant::now();
+                let mut result = SpreadResult {
+                    pages: pages.clone(),
+                    width: 0,
+                    height: 0,
+                    buffer: Buffer::from(vec![]),
+                    success: false,
+                    error: None,
+                    render_time: 0,
+                    encode_time: 0,
+                };
+
+                let (pixels, width, height) = match self.render_spread_canvas(document, &pages) {
+                    Ok(canvas) => canvas,
+                    Err(e) => {
+                        result.error = Some(e);
+                        return result;
+                    }
+                };
+                result.width = width;
+                result.height = height;
+                result.render_time = render_start.elapsed().as_millis() as u32;
+
+                let encode_start = std::time::Instant::now();
+                match self.encode_image(&pixels, width, height) {
+                    Ok(encoded) => {
+                        result.buffer = encoded.into();
+                        result.success = true;
+                    }
+                    Err(e) => result.error = Some(e),
+                }
+                result.encode_time = encode_start.elapsed().as_millis() as u32;
+                result
+            })
+            .collect();
+
+        Ok((num_pages, spreads))
+    }
+
+    /// 把一组页面缩放到相同高度后左右拼接，整体宽度约为 `target_width`
+    fn render_spread_canvas(
+        &self,
+        document: &PdfDocument,
+        pages: &[u32],
+    ) -> std::result::Result<(Vec<u8>, u32, u32), String> {
+        let mut pages: Vec<PdfPage> = pages
+            .iter()
+            .map(|&page_num| {
+                document
+                    .pages()
+                    .get((page_num - 1) as u16)
+                    .map_err(|e| format!("Failed to get page {}: {}", page_num, e))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+
+        // 统一高度（点）：以最高的页面为准，其余页面等比放大到相同高度
+        let height_points = pages.iter().map(|p| p.height().value).fold(0.0f32, f32::max);
+        let widths_points: Vec<f32> = pages
+            .iter()
+            .map(|p| p.width().value * height_points / p.height().value)
+            .collect();
+        let total_width_points: f32 = widths_points.iter().sum();
+
+        // 单页跨页（封面）按半宽渲染，与对开页中的单页大小一致
+        let target_width = if pages.len() == 1 {
+            self.config.target_width as f32 / 2.0
+        } else {
+            self.config.target_width as f32
+        };
+        let max_dimension = self.max_dimension() as f32;
+        let scale = (target_width / total_width_points)
+            .min(self.config.max_scale)
+            .min(max_dimension / total_width_points)
+            .min(max_dimension / height_points);
+
+        let height = (height_points * scale).round().max(1.0) as u32;
+        let widths: Vec<u32> = widths_points.iter().map(|w| (w * scale).round().max(1.0) as u32).collect();
+        let width: u32 = widths.iter().sum();
+
+        let mut canvas = vec![255u8; (width * height * 4) as usize];
+        let mut x = 0;
+        for (page, &page_width) in pages.iter_mut().zip(&widths) {
+            self.filter_annotations(page);
+            let bitmap = page
+                .render_with_config(&self.page_render_config(page_width, height))
+                .map_err(|e| format!("Failed to render page: {}", e))?;
+            let (bitmap_width, bitmap_height) = (bitmap.width() as u32, bitmap.height() as u32);
+            let pixels = pack_rows(bitmap.as_raw_bytes(), bitmap_width, bitmap_height, 4);
+            sprite::blit(&mut canvas, width, &pixels, bitmap_width.min(width - x), bitmap_height, x, 0);
+            x += page_width;
+        }
+
+        Ok((self.apply_filters(canvas, width, height, false), width, height))
+    }
+
+    /// 把多页缩略图拼成一张图片，返回编码后的图片、拼图布局和每页所在的单元格
+    ///
+    /// `page_nums` 为空时包含全部页面；渲染失败的页面保留空白单元格。
+    pub fn render_sprite_sheet(
+        &self,
+        document: &PdfDocument,
+        page_nums: &[u32],
+        cell_size: u32,
+        columns: Option<u32>,
+    ) -> std::result::Result<(Vec<u8>, SpriteLayout, Vec<SpriteCell>), String> {
+        let num_pages = document.pages().len() as u32;
+        let page_nums: Vec<u32> = if page_nums.is_empty() {
+            (1..=num_pages).collect()
+        } else {
+            page_nums.to_vec()
+        };
+        self.config.limits.check_pages(page_nums.len()).map_err(|e| e.to_string())?;
+
+        let layout = SpriteLayout::new(page_nums.len() as u32, cell_size, columns, self.max_dimension())?;
+        let mut canvas = vec![255u8; (layout.width() * layout.height() * 4) as usize];
+
+        let cells = page_nums
+            .iter()
+            .enumerate()
+            .map(|(index, &page_num)| {
+                let (cell_x, cell_y) = layout.cell_origin(index as u32);
+                let mut cell = SpriteCell {
+                    page_num,
+                    x: cell_x,
+                    y: cell_y,
+                    width: 0,
+                    height: 0,
+                    success: false,
+                    error: None,
+                };
+                match self.render_sprite_cell(document, page_num, num_pages, cell_size) {
+                    Ok((pixels, width, height)) => {
+                        // 在单元格内居中
+                        cell.x += (cell_size - width) / 2;
+                        cell.y += (cell_size - height) / 2;
+                        cell.width = width;
+                        cell.height = height;
+                        cell.success = true;
+                        sprite::blit(&mut canvas, layout.width(), &pixels, width, height, cell.x, cell.y);
+                    }
+                    Err(e) => cell.error = Some(e),
+                }
+                cell
+            })
+            .collect();
+
+        let encoded = self.encode_image(&canvas, layout.width(), layout.height())?;
+        Ok((encoded, layout, cells))
+    }
+
+    fn render_sprite_cell(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        num_pages: u32,
+        cell_size: u32,
+    ) -> std::result::Result<(Vec<u8>, u32, u32), String> {
+        if page_num < 1 || page_num > num_pages {
+            return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        let mut page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+
+        let (width, height) = sprite::fit_in_cell(page.width().value, page.height().value, cell_size);
+        self.filter_annotations(&mut page);
+        let bitmap = page
+            .render_with_config(&self.page_render_config(width, height))
+            .map_err(|e| format!("Failed to render page: {}", e))?;
+
+        // PDFium 可能因取整多出一个像素，按单元格裁剪
+        let width = (bitmap.width() as u32).min(cell_size);
+        let height = (bitmap.height() as u32).min(cell_size);
+        let stride_width = bitmap.width() as u32;
+        let pixels = pack_rows(bitmap.as_raw_bytes(), stride_width, bitmap.height() as u32, 4);
+        let pixels = if stride_width == width {
+            pixels
+        } else {
+            pixels
+                .chunks_exact((stride_width * 4) as usize)
+                .take(height as usize)
+                .flat_map(|row| &row[..(width * 4) as usize])
+                .copied()
+                .collect()
+        };
+        Ok((pixels, width, height))
+    }
+
+    /// 把多页渲染进同一个多页 TIFF（传真、归档）
+    ///
+    /// 二值页面使用 CCITT G4 压缩（二值化方式沿用 bilevel 选项，未指定时按灰度阈值 128），
+    /// 灰度和彩色页面使用 LZW 压缩。渲染失败的页面不写入 TIFF，在页面结果中报告。
+    pub fn render_multipage_tiff(
+        &self,
+        document: &PdfDocument,
+        page_nums: &[u32],
+        color: TiffColor,
+        source: &str,
+    ) -> std::result::Result<(u32, Vec<u8>, Vec<TiffPageResult>), String> {
+        let num_pages = document.pages().len() as u32;
+        let page_nums: Vec<u32> = if page_nums.is_empty() {
+            (1..=num_pages).collect()
+        } else {
+            page_nums.to_vec()
+        };
+        self.config.limits.check_pages(page_nums.len()).map_err(|e| e.to_string())?;
+
+        let mut writer = TiffWriter::new();
+        let pages: Vec<TiffPageResult> = page_nums
+            .iter()
+            .map(|&page_num| {
+                let raw = self.render_page_to_raw_bitmap(document, page_num, source);
+                let mut page = TiffPageResult {
+                    page_num,
+                    success: raw.success,
+                    error: raw.error,
+                    error_code: raw.error_code,
+                    width: raw.width,
+                    height: raw.height,
+                    render_time: raw.render_time,
+                    encode_time: 0,
+                };
+                if !raw.success {
+                    return page;
+                }
+
+                let encode_start = std::time::Instant::now();
+                let dpi = raw.render_info.and_then(|info| info.dpi).unwrap_or(72.0) as f32;
+                let (width, height) = (raw.width, raw.height);
+                match color {
+                    TiffColor::Bilevel => {
+                        let gray = filters::to_grayscale(&raw.buffer, self.config.pixel_format);
+                        let mode = self.config.bilevel.unwrap_or(filters::BilevelMode::Threshold(128));
+                        writer.add_bilevel_page(&filters::binarize(&gray, width, height, mode), width, height, dpi);
+                    }
+                    TiffColor::Gray => {
+                        let gray = filters::to_grayscale(&raw.buffer, self.config.pixel_format);
+                        writer.add_lzw_gray_page(&gray, width, height, dpi);
+                    }
+                    TiffColor::Color => {
+                        let rgb = rgba_to_rgb(&raw.buffer, self.config.pixel_format, self.config.jpeg_background);
+                        writer.add_lzw_rgb_page(&rgb, width, height, dpi);
+                    }
+                }
+                page.encode_time = encode_start.elapsed().as_millis() as u32;
+                page
+            })
+            .collect();
+
+        if !pages.iter().any(|p| p.success) {
+            let reason = pages.iter().find_map(|p| p.error.as_deref()).unwrap_or("no pages requested");
+            return Err(format!("No pages were rendered: {}", reason));
+        }
+        Ok((num_pages, writer.finish(), pages))
+    }
+
+    /// 计算页面的感知哈希，并把内容相同或相近的页面聚类
+    ///
+    /// 页面先按 128 像素渲染成小图再缩到 32×32，聚类使用传递闭包（A≈B、B≈C 时三页同组）。
+    pub fn find_duplicates(
+        &self,
+        document: &PdfDocument,
+        page_nums: &[u32],
+        threshold: u32,
+    ) -> std::result::Result<(u32, Vec<PageHash>, Vec<DuplicateCluster>), String> {
+        let num_pages = document.pages().len() as u32;
+        let page_nums: Vec<u32> = if page_nums.is_empty() {
+            (1..=num_pages).collect()
+        } else {
+            page_nums.to_vec()
+        };
+        self.config.limits.check_pages(page_nums.len()).map_err(|e| e.to_string())?;
+
+        let mut hashes = Vec::with_capacity(page_nums.len());
+        let mut hashed = Vec::new();
+        for page_num in page_nums {
+            match self.page_hash(document, page_num, num_pages) {
+                Ok(hash) => {
+                    hashed.push((page_num, hash));
+                    hashes.push(PageHash {
+                        page_num,
+                        hash: Some(format!("{:016x}", hash)),
+                        error: None,
+                    });
+                }
+                Err(e) => hashes.push(PageHash {
+                    page_num,
+                    hash: None,
+                    error: Some(e),
+                }),
+            }
+        }
+
+        let clusters = phash::clusters(&hashed, threshold)
+            .into_iter()
+            .map(|group| {
+                let hashed = &hashed;
+                let max_distance = group
+                    .iter()
+                    .flat_map(|&i| group.iter().map(move |&j| phash::distance(hashed[i].1, hashed[j].1)))
+                    .max()
+                    .unwrap_or(0);
+                DuplicateCluster {
+                    pages: group.iter().map(|&i| hashed[i].0).collect(),
+                    max_distance,
+                }
+            })
+            .collect();
+
+        Ok((num_pages, hashes, clusters))
+    }
+
+    fn page_hash(&self, document: &PdfDocument, page_num: u32, num_pages: u32) -> std::result::Result<u64, String> {
+        let (pixels, width, height) = self.render_sprite_cell(document, page_num, num_pages, 128)?;
+        let gray = filters::to_grayscale(&pixels, self.config.pixel_format);
+        let image: ImageBuffer<Luma<u8>, _> = ImageBuffer::from_raw(width, height, gray)
+            .ok_or_else(|| "Failed to create image buffer".to_string())?;
+        let size = phash::HASH_INPUT_SIZE as u32;
+        let small = image::imageops::resize(&image, size, size, image::imageops::FilterType::Triangle);
+        Ok(phash::phash(small.as_raw()))
+    }
+
+    /// 按文档顺序展开书签，并把目标位置换算为渲染图像坐标
+    pub fn outline_anchors(&self, document: &PdfDocument) -> Vec<OutlineAnchor> {
+        let mut anchors = Vec::new();
+        let mut page_sizes = std::collections::HashMap::new();
+        if let Some(root) = document.bookmarks().root() {
+            self.collect_outline(document, Some(root), 0, &mut page_sizes, &mut anchors);
+        }
+        anchors
+    }
+
+    /// 书签树（只解析目标页码，不计算渲染尺寸）
+    ///
+    /// 与 `outline_anchors` 使用相同的条目数和层级上限，超出的条目不返回。
+    pub fn outline_tree(&self, document: &PdfDocument) -> Vec<OutlineItem> {
+        let num_pages = document.pages().len() as u32;
+        let mut remaining = MAX_OUTLINE_ENTRIES;
+        build_outline_tree(document.bookmarks().root(), 0, num_pages, &mut remaining)
+    }
+
+    fn collect_outline(
+        &self,
+        document: &PdfDocument,
+        mut bookmark: Option<PdfBookmark>,
+        level: u32,
+        page_sizes: &mut std::collections::HashMap<u16, (u32, u32)>,
+        anchors: &mut Vec<OutlineAnchor>,
+    ) {
+        while let Some(current) = bookmark {
+            if anchors.len() >= MAX_OUTLINE_ENTRIES {
+                return;
+            }
+
+            let mut anchor = OutlineAnchor {
+                title: current.title().unwrap_or_default(),
+                level,
+                page_num: None,
+                x: None,
+                y: None,
+                zoom: None,
+                width: 0,
+                height: 0,
+            };
+            with_bookmark_destination(&current, |destination| {
+                self.resolve_anchor(document, destination, page_sizes, &mut anchor)
+            });
+            anchors.push(anchor);
+
+            if level < MAX_OUTLINE_DEPTH {
+                self.collect_outline(document, current.first_child(), level + 1, page_sizes, anchors);
+            }
+            bookmark = current.next_sibling();
+        }
+    }
+
+    fn resolve_anchor(
+        &self,
+        document: &PdfDocument,
+        destination: &PdfDestination,
+        page_sizes: &mut std::collections::HashMap<u16, (u32, u32)>,
+        anchor: &mut OutlineAnchor,
+    ) {
+        let Ok(page_index) = destination.page_index() else {
+            return;
+        };
+        let Ok(page) = document.pages().get(page_index) else {
+            return;
+        };
+        anchor.page_num = Some(page_index as u32 + 1);
+
+        let (width, height) = *page_sizes.entry(page_index).or_insert_with(|| {
+            let is_scan = self.config.detect_scan && self.is_likely_scan(&page).unwrap_or(false);
+            self.render_size(&page, page_index as u32 + 1, is_scan, self.page_max_dimension())
+        });
+        anchor.width = width;
+        anchor.height = height;
+
+        // 只有指定了坐标的目标类型才有位置，其余（整页适配等）只定位到页
+        let (x, y, zoom) = match destination.view_settings() {
+            Ok(PdfDestinationViewSettings::SpecificCoordinatesAndZoom(x, y, zoom)) => (x, y, zoom),
+            Ok(PdfDestinationViewSettings::FitPageHorizontallyToWindow(y))
+            | Ok(PdfDestinationViewSettings::FitBoundsHorizontallyToWindow(y)) => (None, y, None),
+            Ok(PdfDestinationViewSettings::FitPageVerticallyToWindow(x))
+            | Ok(PdfDestinationViewSettings::FitBoundsVerticallyToWindow(x)) => (x, None, None),
+            Ok(PdfDestinationViewSettings::FitPageToRectangle(rect)) => (Some(rect.left()), Some(rect.top()), None),
+            _ => (None, None, None),
+        };
+        anchor.zoom = zoom.filter(|z| *z > 0.0).map(|z| z as f64);
+        if x.is_none() && y.is_none() {
+            return;
+        }
+
+        // 缺失的坐标用页面边界代替，转换后只保留指定了的那一维
+        let render_config = self.page_render_config(width, height);
+        let point_x = x.unwrap_or(PdfPoints::ZERO);
+        let point_y = y.unwrap_or(page.height());
+        if let Ok((px, py)) = page.points_to_pixels(point_x, point_y, &render_config) {
+            anchor.x = x.map(|_| (px as f64).clamp(0.0, width as f64));
+            anchor.y = y.map(|_| (py as f64).clamp(0.0, height as f64));
+        }
+    }
+
+    /// 提取内嵌缩略图，`page_nums` 为空时提取全部页面
+    /// 把每个页面分别导出为单页 PDF（通过 PDFium 的页面导入接口复制页面对象，不经过渲染）
+    pub fn extract_pages(&self, document: &PdfDocument, page_nums: &[u32]) -> (u32, Vec<ExtractedPage>) {
+        let num_pages = document.pages().len() as u32;
+        let page_nums: Vec<u32> = if page_nums.is_empty() {
+            (1..=num_pages).collect()
+        } else {
+            page_nums.to_vec()
+        };
+
+        let pages = page_nums
+            .into_iter()
+            .map(|page_num| {
+                let result = if page_num < 1 || page_num > num_pages {
+                    let code = if page_num > num_pages { ErrorCode::OutOfRange } else { ErrorCode::InvalidPage };
+                    Err((format!("Invalid page number: {} (total: {})", page_num, num_pages), code))
+                } else {
+                    self.extract_page(document, page_num).map_err(|e| (e, ErrorCode::RenderFailed))
+                };
+                match result {
+                    Ok(data) => ExtractedPage {
+                        page_num,
+                        buffer: into_js_buffer(data),
+                        success: true,
+                        error: None,
+                        error_code: None,
+                    },
+                    Err((error, code)) => ExtractedPage {
+                        page_num,
+                        buffer: Buffer::from(vec![]),
+                        success: false,
+                        error: Some(error),
+                        error_code: code.code(),
+                    },
+                }
+            })
+            .collect();
+        (num_pages, pages)
+    }
+
+    fn extract_page(&self, document: &PdfDocument, page_num: u32) -> std::result::Result<Vec<u8>, String> {
+        let mut single = self
+            .pdfium
+            .create_new_pdf()
+            .map_err(|e| format!("Failed to create PDF: {}", e))?;
+        let page_index = (page_num - 1) as PdfPageIndex;
+        single
+            .pages_mut()
+            .copy_page_from_document(document, page_index, 0)
+            .map_err(|e| format!("Failed to copy page {}: {}", page_num, e))?;
+        single
+            .save_to_bytes()
+            .map_err(|e| format!("Failed to save page {}: {}", page_num, e))
+    }
+
+    /// 把选中的页面按给定顺序导出为一个新的 PDF，返回 (总页数, 导出的页码, PDF 数据)
+    ///
+    /// 所有页面在一次导入中复制，页面之间共享的字体、图片等资源只复制一份。
+    /// 页码数组为空时导出全部页面；任一页码无效时整体失败。
+    pub fn extract_document(
+        &self,
+        document: &PdfDocument,
+        pages: &PageSelection,
+    ) -> std::result::Result<(u32, Vec<u32>, Vec<u8>), String> {
+        let num_pages = document.pages().len() as u32;
+        let page_nums = match pages {
+            PageSelection::List(page_nums) if page_nums.is_empty() => (1..=num_pages).collect(),
+            _ => pages.resolve(num_pages)?,
+        };
+        if let Some(&page_num) = page_nums.iter().find(|&&page_num| page_num < 1 || page_num > num_pages) {
+            return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        if page_nums.is_empty() {
+            return Err("No pages to extract".to_string());
+        }
+
+        let mut extracted = self
+            .pdfium
+            .create_new_pdf()
+            .map_err(|e| format!("Failed to create PDF: {}", e))?;
+        extracted
+            .pages_mut()
+            .copy_pages_from_document(document, &import_range(&page_nums), 0)
+            .map_err(|e| format!("Failed to copy pages: {}", e))?;
+        let data = extracted
+            .save_to_bytes()
+            .map_err(|e| format!("Failed to save PDF: {}", e))?;
+        Ok((num_pages, page_nums, data))
+    }
+
+    pub fn extract_thumbnails(&self, document: &PdfDocument, page_nums: &[u32]) -> (u32, Vec<EmbeddedThumbnail>) {
+        let num_pages = document.pages().len() as u32;
+        let page_nums: Vec<u32> = if page_nums.is_empty() {
+            (1..=num_pages).collect()
+        } else {
+            page_nums.to_vec()
+        };
+
+        let mut thumbnails: Vec<EmbeddedThumbnail> = page_nums
+            .into_iter()
+            .map(|page_num| {
+                let mut thumbnail = EmbeddedThumbnail {
+                    page_num,
+                    found: false,
+                    width: 0,
+                    height: 0,
+                    buffer: Buffer::from(vec![]),
+                    format: None,
+                    passthrough: false,
+                    rendered: false,
+                    error: None,
+                };
+                if let Err(e) = self.extract_thumbnail(document, page_num, num_pages, &mut thumbnail) {
+                    thumbnail.error = Some(e);
+                }
+                thumbnail
+            })
+            .collect();
+
+        if let Some(width) = self.config.thumbnail_fallback_width {
+            self.render_missing_thumbnails(document, &mut thumbnails, width);
+        }
+        (num_pages, thumbnails)
+    }
+
+    /// 没有内嵌缩略图的页面按 `width` 一起渲染（其余选项不变），填入对应的结果
+    fn render_missing_thumbnails(&self, document: &PdfDocument, thumbnails: &mut [EmbeddedThumbnail], width: u32) {
+        let missing: Vec<u32> = thumbnails
+            .iter()
+            .filter(|t| !t.found && t.error.is_none())
+            .map(|t| t.page_num)
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let config = RenderConfig {
+            target_width: width,
+            page_scales: BTreeMap::new(),
+            ..self.config.clone()
+        };
+        let renderer = PdfRenderer::new(self.pdfium, config);
+        let pages = match renderer.render_document_pages(document, &missing, "thumbnail-fallback") {
+            Ok((_, pages)) => pages,
+            Err(e) => {
+                for thumbnail in thumbnails.iter_mut().filter(|t| missing.contains(&t.page_num)) {
+                    thumbnail.error = Some(e.clone());
+                }
+                return;
+            }
+        };
+        for page in pages {
+            let Some(thumbnail) = thumbnails.iter_mut().find(|t| t.page_num == page.page_num && !t.found && !t.rendered) else {
+                continue;
+            };
+            if page.success {
+                thumbnail.width = page.width;
+                thumbnail.height = page.height;
+                thumbnail.format = Some(page.format);
+                thumbnail.buffer = page.buffer;
+                thumbnail.rendered = true;
+            } else {
+                thumbnail.error = page.error;
+            }
+        }
+    }
+
+    fn extract_thumbnail(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        num_pages: u32,
+        thumbnail: &mut EmbeddedThumbnail,
+    ) -> std::result::Result<(), String> {
+        if page_num < 1 || page_num > num_pages {
+            return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+
+        let bitmap = match page.embedded_thumbnail() {
+            Ok(bitmap) => bitmap,
+            Err(PdfiumError::PageMissingEmbeddedThumbnail) => return Ok(()),
+            Err(e) => return Err(format!("Failed to read thumbnail: {}", e)),
+        };
+        thumbnail.found = true;
+        thumbnail.width = bitmap.width() as u32;
+        thumbnail.height = bitmap.height() as u32;
+
+        if self.config.thumbnail_passthrough {
+            let raw = raw_thumbnail_data(&page);
+            if let Some(format) = passthrough_format(&raw) {
+                thumbnail.buffer = raw.into();
+                thumbnail.format = Some(format.to_string());
+                thumbnail.passthrough = true;
+                return Ok(());
+            }
+        }
+
+        // 缩略图位图的格式不固定（BGR/BGRA/灰度），统一转换后按配置的通道顺序编码
+        let mut pixels = bitmap.as_rgba_bytes();
+        if self.config.pixel_format == PixelFormat::Bgra {
+            pixels = swap_red_blue(&pixels);
+        }
+        let encoded = self.encode_image(&pixels, thumbnail.width, thumbnail.height)?;
+        thumbnail.buffer = encoded.into();
+        thumbnail.format = Some(self.config.format.as_str().to_string());
+        Ok(())
+    }
+
+    /// 加载文档前检查资源限制（文件大小、页数），超出时不再解析文档
+    pub fn check_input(&self, file_size: u64, page_nums: &[u32]) -> std::result::Result<(), String> {
+        let limits = &self.config.limits;
+        limits
+            .check_file_size(file_size)
+            .and_then(|_| limits.check_pages(page_nums.len()))
+            .map_err(|e| e.to_string())
+    }
+
+    /// 渲染阶段：用 PDFium 把页面画成位图，并收集编码阶段需要的页面信息
+    ///
+    /// 编码阶段不再访问 PDFium，可以放到其他线程执行（options.concurrency）。
+    fn render_page_bitmap(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        num_pages: u32,
+        recorder: &flight_recorder::Recorder,
+    ) -> std::result::Result<RenderedPage, Box<PageResult>> {
+        let render_start = std::time::Instant::now();
+
+        // 检查页码有效性；超出页数的页面视为跳过（outOfRangePages 为 "fail" 时整个调用已在前面失败）
+        if page_num < 1 || page_num > num_pages {
+            let out_of_range = page_num > num_pages;
+            return Err(Box::new(PageResult {
+                skipped: out_of_range,
+                ..PageResult::failed(
+                    page_num,
+                    self.config.format.as_str(),
+                    if out_of_range {
+                        format!("Skipped: page {} out of range (total: {})", page_num, num_pages)
+                    } else {
+                        format!("Invalid page number: {} (total: {})", page_num, num_pages)
+                    },
+                    if out_of_range { ErrorCode::OutOfRange } else { ErrorCode::InvalidPage },
+                )
+            }));
+        }
+
+        // PDFium 页码从 0 开始
+        let page_index = (page_num - 1) as u16;
+        
+        let mut page = match document.pages().get(page_index) {
+            Ok(p) => p,
+            Err(e) => {
+                return Err(Box::new(PageResult::failed(
+                    page_num,
+                    self.config.format.as_str(),
+                    format!("Failed to get page: {}", e),
+                    ErrorCode::RenderFailed,
+                )));
+            }
+        };
+
+        // 扫描件识别（用于降级宽度和降噪）
+        let scan = if self.config.detect_scan || self.config.denoise.is_some() {
+            match self.scan_decision(&page) {
+                Ok(scan) => Some(scan),
+                Err(e) => {
+                    return Err(Box::new(PageResult {
+                        render_time: render_start.elapsed().as_millis() as u32,
+                        ..PageResult::failed(page_num, self.config.format.as_str(), e, ErrorCode::LimitExceeded)
+                    }));
+                }
+            }
+        } else {
+            None
+        };
+        let is_scan = scan.is_some_and(|scan| scan.is_scan);
+
+        // WebP 尺寸限制检查（单边不能超过 16383）
+        // 注意：PNG 和 JPG 没有这个限制，但为了一致性和内存考虑，仍然应用此限制
+        // oversizeStrategy 为 fallbackPng 时放宽到原始位图上限，超出 WebP 上限的页面改用 PNG
+        let max_dimension = self.page_max_dimension();
+        if let Err(e) = self.check_size_options(max_dimension) {
+            return Err(Box::new(PageResult {
+                render_time: render_start.elapsed().as_millis() as u32,
+                ..PageResult::failed(page_num, self.config.format.as_str(), e, ErrorCode::InvalidOptions)
+            }));
+        }
+        let plan = self.render_plan(&page, page_num, is_scan, max_dimension);
+        if let Err(e) = self.check_memory_budget(&plan) {
+            return Err(Box::new(PageResult {
+                render_time: render_start.elapsed().as_millis() as u32,
+                ..PageResult::failed(page_num, self.config.format.as_str(), e, ErrorCode::LimitExceeded)
+            }));
+        }
+        let (render_width, render_height) = (plan.width, plan.height);
+        recorder.rendering(render_width, render_height);
+        self.filter_annotations(&mut page);
+        let color_converted = self.convert_colors(document, &page);
+
+        // 渲染页面为 RGBA 位图（通道顺序已由渲染配置决定，无需再转换）
+        let (rgba_data, actual_width, actual_height) =
+            match self.render_page_overlaid(&page, page_num, (render_width, render_height), render_start) {
+                Ok(rendered) => rendered,
+                Err((code, e)) => {
+                    return Err(Box::new(PageResult {
+                        render_time: render_start.elapsed().as_millis() as u32,
+                        ..PageResult::failed(page_num, self.config.format.as_str(), e, code)
+                    }));
+                }
+            };
+
+        let render_time = render_start.elapsed().as_millis() as u32;
+
+        // 最终尺寸检查：只超出几个像素时（预先限制尺寸后的取整误差）裁掉多余的边缘，
+        // 不再做一次代价很高的 Lanczos 缩放
+        let oversize = actual_width.max(actual_height).saturating_sub(max_dimension);
+        let cropped = oversize > 0 && oversize <= self.config.resize_tolerance;
+        let resized = oversize > self.config.resize_tolerance;
+        let scaled_size = if resized {
+            scaled_to_fit(actual_width, actual_height, max_dimension)
+        } else {
+            (actual_width, actual_height)
+        };
+        let transform = self.point_transform(&page, (actual_width, actual_height), scaled_size);
+        let object_errors = self.object_errors(&page, transform);
+
+        Ok(RenderedPage {
+            page_num,
+            pixels: rgba_data,
+            width: actual_width,
+            height: actual_height,
+            max_dimension,
+            cropped,
+            resized,
+            transform,
+            is_scan,
+            scan,
+            text_only: self.is_text_only(&page, is_scan),
+            plan,
+            color_converted,
+            object_errors,
+            render_time,
+        })
+    }
+
+    /// 渲染页面的局部区域并编码
+    ///
+    /// `region` 为页面坐标（点，左上角原点），`scale` 为像素/点，不受 `max_scale` 限制；
+    /// 区域的像素尺寸超出输出格式上限时按比例缩小。
+    pub fn render_page_region(&self, document: &PdfDocument, page_num: u32, region: Region, scale: f64, source: &str) -> PageResult {
+        let recorder = flight_recorder::begin(source, page_num);
+        let result = match self.render_region_bitmap(document, page_num, region, scale, &recorder) {
+            Ok(rendered) => self.encode_rendered_page(rendered, &recorder),
+            Err(result) => *result,
+        };
+        self.finish_page(result, recorder, None, false)
+    }
+
+    /// 区域渲染的渲染阶段：用变换矩阵把区域左上角平移到位图原点，只绘制区域内的内容
+    fn render_region_bitmap(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        region: Region,
+        scale: f64,
+        recorder: &flight_recorder::Recorder,
+    ) -> std::result::Result<RenderedPage, Box<PageResult>> {
+        let render_start = std::time::Instant::now();
+        let fail = |error: String, code: ErrorCode| {
+            Box::new(PageResult {
+                render_time: render_start.elapsed().as_millis() as u32,
+                ..PageResult::failed(page_num, self.config.format.as_str(), error, code)
+            })
+        };
+
+        let num_pages = document.pages().len() as u32;
+        if page_num < 1 || page_num > num_pages {
+            return Err(fail(
+                format!("Invalid page number: {} (total: {})", page_num, num_pages),
+                ErrorCode::InvalidPage,
+            ));
+        }
+        let mut page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| fail(format!("Failed to get page: {}", e), ErrorCode::RenderFailed))?;
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(fail(format!("Invalid region scale: {}", scale), ErrorCode::InvalidOptions));
+        }
+        let region = region
+            .clip_to_page(page.width().value as f64, page.height().value as f64)
+            .map_err(|e| fail(e, ErrorCode::InvalidOptions))?;
+        let scan = if self.config.detect_scan || self.config.denoise.is_some() {
+            Some(self.scan_decision(&page).map_err(|e| fail(e, ErrorCode::LimitExceeded))?)
+        } else {
+            None
+        };
+        let is_scan = scan.is_some_and(|scan| scan.is_scan);
+
+        let max_dimension = self.page_max_dimension();
+        let (requested_width, requested_height) = region.pixel_size(scale);
+        let longest = requested_width.max(requested_height);
+        let clamped = longest > max_dimension;
+        let scale = if clamped { scale * max_dimension as f64 / longest as f64 } else { scale };
+        let (width, height) = region.pixel_size(scale);
+        let pixels = width.min(max_dimension) as u64 * height.min(max_dimension) as u64;
+        let budget = self.config.limits.page_pixel_budget(self.config.concurrency);
+        let memory_downscaled = !self.config.fail_over_memory_budget && budget.is_some_and(|budget| pixels > budget);
+        let scale = match (memory_downscaled, budget) {
+            (true, Some(budget)) => scale * (budget as f64 / pixels as f64).sqrt(),
+            _ => scale,
+        };
+        let (width, height) = if memory_downscaled { region.pixel_size(scale) } else { (width, height) };
+        let (width, height) = (width.min(max_dimension), height.min(max_dimension));
+        let plan = RenderPlan {
+            width,
+            height,
+            scale: scale as f32,
+            size_source: "region",
+            max_scale_applied: false,
+            scale_clamped: false,
+            clamped,
+            requested_width,
+            requested_height,
+            memory_downscaled,
+        };
+        self.check_memory_budget(&plan).map_err(|e| fail(e, ErrorCode::LimitExceeded))?;
+        recorder.rendering(width, height);
+        self.filter_annotations(&mut page);
+        let color_converted = self.convert_colors(document, &page);
+
+        let (dx, dy) = (-region.x * scale, -region.y * scale);
+        let pixels = self
+            .render_clip(&page, scale, (dx, dy), (width, height))
+            .map_err(|e| fail(format!("Failed to render page: {}", e), ErrorCode::RenderFailed))?;
+        let render_time = render_start.elapsed().as_millis() as u32;
+
+        // 整页按相同比例渲染时的坐标变换，再平移到区域原点
+        let full_size = (
+            (page.width().value as f64 * scale).round() as u32,
+            (page.height().value as f64 * scale).round() as u32,
+        );
+        let transform = self
+            .point_transform(&page, full_size, full_size)
+            .map(|[a, b, c, d, e, f]| [a, b, c, d, e + dx, f + dy]);
+        let object_errors = self.object_errors(&page, transform);
+
+        Ok(RenderedPage {
+            page_num,
+            pixels,
+            width,
+            height,
+            max_dimension,
+            cropped: false,
+            resized: false,
+            transform,
+            is_scan,
+            scan,
+            text_only: self.is_text_only(&page, is_scan),
+            plan,
+            color_converted,
+            object_errors,
+            render_time,
+        })
+    }
+
+    /// 渲染深度缩放级别 `zoom` 的分块，`tile` 为 `(列, 行)`，为空时渲染该级别的全部分块
+    ///
+    /// 每个分块单独渲染，内存占用只与分块大小有关；不指定分块时一次最多渲染
+    /// `MAX_ZOOM_TILES` 块，更高的级别需要逐块请求。
+    pub fn render_zoom_tiles(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        zoom: u32,
+        tile_size: u32,
+        tile: Option<(u32, u32)>,
+    ) -> ZoomTiles {
+        let render_start = std::time::Instant::now();
+        let mut result = ZoomTiles {
+            page_num,
+            zoom,
+            max_zoom: 0,
+            scale: 0.0,
+            width: 0,
+            height: 0,
+            tile_size,
+            columns: 0,
+            rows: 0,
+            format: self.format_name(self.config.format),
+            tiles: vec![],
+            render_time: 0,
+            encode_time: 0,
+            success: false,
+            error: None,
+            error_code: None,
+        };
+        let fail = |mut result: ZoomTiles, error: String, code: ErrorCode| {
+            result.error = Some(error);
+            result.error_code = code.code();
+            result.render_time = render_start.elapsed().as_millis() as u32;
+            result
+        };
+
+        let num_pages = document.pages().len() as u32;
+        if page_num < 1 || page_num > num_pages {
+            let error = format!("Invalid page number: {} (total: {})", page_num, num_pages);
+            return fail(result, error, ErrorCode::InvalidPage);
+        }
+        if !(MIN_ZOOM_TILE_SIZE..=MAX_ZOOM_TILE_SIZE).contains(&tile_size) {
+            let error = format!(
+                "Invalid tile size: {} (must be {}-{})",
+                tile_size, MIN_ZOOM_TILE_SIZE, MAX_ZOOM_TILE_SIZE
+            );
+            return fail(result, error, ErrorCode::InvalidOptions);
+        }
+        let mut page = match document.pages().get((page_num - 1) as u16) {
+            Ok(page) => page,
+            Err(e) => return fail(result, format!("Failed to get page: {}", e), ErrorCode::RenderFailed),
+        };
+
+        let (page_width, page_height) = (page.width().value as f64, page.height().value as f64);
+        let longest = page_width.max(page_height).max(1.0);
+        result.max_zoom = tiles::max_zoom(tile_size, longest, self.config.max_scale as f64);
+        if zoom > result.max_zoom {
+            let error = format!("Zoom level {} exceeds the maximum {} (raise maxScale for deeper zoom)", zoom, result.max_zoom);
+            return fail(result, error, ErrorCode::InvalidOptions);
+        }
+        let scale = tiles::zoom_scale(tile_size, longest, zoom);
+        let grid = TileGrid::new(
+            ((page_width * scale).round() as u32).max(1),
+            ((page_height * scale).round() as u32).max(1),
+            tile_size,
+        );
+        result.scale = scale;
+        (result.width, result.height, result.columns, result.rows) = (grid.width, grid.height, grid.columns, grid.rows);
+
+        let rects: Vec<TileRect> = match tile {
+            Some((column, row)) => match grid.tile(column, row) {
+                Some(rect) => vec![rect],
+                None => {
+                    let error = format!(
+                        "Tile {},{} is outside the {}x{} grid at zoom {}",
+                        column, row, grid.columns, grid.rows, zoom
+                    );
+                    return fail(result, error, ErrorCode::InvalidOptions);
+                }
+            },
+            None if (grid.columns * grid.rows) as usize > MAX_ZOOM_TILES => {
+                let error = format!(
+                    "Zoom level {} has {} tiles (max {} per call); request tiles by x/y",
+                    zoom,
+                    grid.columns * grid.rows,
+                    MAX_ZOOM_TILES
+                );
+                return fail(result, error, ErrorCode::InvalidOptions);
+            }
+            None => grid.tiles().collect(),
+        };
+
+        self.filter_annotations(&mut page);
+        self.convert_colors(document, &page);
+        let mut encode_time = std::time::Duration::ZERO;
+        for rect in rects {
+            if self.cancelled() {
+                return fail(result, "Cancelled".to_string(), ErrorCode::Cancelled);
+            }
+            let offset = (-(rect.x as f64), -(rect.y as f64));
+            let pixels = match self.render_clip(&page, scale, offset, (rect.width, rect.height)) {
+                Ok(pixels) => pixels,
+                Err(e) => return fail(result, format!("Failed to render tile: {}", e), ErrorCode::RenderFailed),
+            };
+            let encode_start = std::time::Instant::now();
+            let encoded = self.encode_image(&pixels, rect.width, rect.height);
+            encode_time += encode_start.elapsed();
+            match encoded {
+                Ok(buffer) => result.tiles.push(PageTile {
+                    row: rect.row,
+                    column: rect.column,
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                    buffer: into_js_buffer(buffer),
+                }),
+                Err(e) => return fail(result, e, ErrorCode::EncodeFailed),
+            }
+        }
+
+        result.encode_time = encode_time.as_millis() as u32;
+        result.render_time = (render_start.elapsed() - encode_time).as_millis() as u32;
+        result.success = true;
+        result
+    }
+
+    /// 以 `scale` 渲染页面并平移 `offset` 像素，只绘制 `size` 大小的位图（紧密排列）
+    ///
+    /// PDFium 使用变换矩阵时不绘制交互表单层，表单控件按其外观流随注释绘制。
+    fn render_clip(&self, page: &PdfPage, scale: f64, offset: (f64, f64), size: (u32, u32)) -> std::result::Result<Vec<u8>, PdfiumError> {
+        let (width, height) = size;
+        let bitmap = self
+            .page_render_config(width, height)
+            .set_fixed_size(width as i32, height as i32)
+            .transform(scale as f32, 0.0, 0.0, scale as f32, offset.0 as f32, offset.1 as f32)
+            .and_then(|config| page.render_with_config(&config))?;
+        Ok(pack_rows(bitmap.as_raw_bytes(), width, height, 4))
+    }
+}
+
+impl PageEncoder {
+    /// 编码阶段：缩放、填充画布、滤镜和编码，只处理像素，不访问 PDFium
+    fn encode_rendered_page(&self, rendered: RenderedPage, recorder: &flight_recorder::Recorder) -> PageResult {
+        let RenderedPage {
+            page_num,
+            pixels: rgba_data,
+            width: actual_width,
+            height: actual_height,
+            max_dimension,
+            cropped,
+            resized,
+            transform,
+            is_scan,
+            scan,
+            text_only,
+            plan,
+            color_converted,
+            object_errors,
+            render_time,
+        } = rendered;
+        let encode_start = std::time::Instant::now();
+        recorder.stage(flight_recorder::Stage::Encoding);
+        // 渲染尺寸被上限缩小，或渲染后又缩小到上限以内
+        let requested_size = (plan.clamped || resized).then_some(RequestedSize {
+            width: plan.requested_width,
+            height: plan.requested_height,
+        });
+
+        let (final_width, final_height, final_rgba) = if cropped {
+            let rect = tiles::TileRect {
+                row: 0,
+                column: 0,
+                x: 0,
+                y: 0,
+                width: actual_width.min(max_dimension),
+                height: actual_height.min(max_dimension),
+            };
+            (rect.width, rect.height, tiles::crop(&rgba_data, actual_width, &rect))
+        } else if resized {
+            let (new_width, new_height) = scaled_to_fit(actual_width, actual_height, max_dimension);
+
+            // 缩放与通道顺序无关，BGRA 数据同样可以按 Rgba 像素处理
+            let img: ImageBuffer<Rgba<u8>, _> = match ImageBuffer::from_raw(actual_width, actual_height, rgba_data) {
+                Some(img) => img,
+                None => {
+                    return PageResult {
+                        width: actual_width,
+                        height: actual_height,
+                        render_time,
+                        ..PageResult::failed(
+                            page_num,
+                            self.config.format.as_str(),
+                            "Failed to create image buffer for resize".to_string(),
+                            ErrorCode::RenderFailed,
+                        )
+                    };
+                }
+            };
+            
+            let resized = image::imageops::resize(&img, new_width, new_height, image::imageops::FilterType::Lanczos3);
+            (new_width, new_height, resized.into_raw())
+        } else {
+            // 尺寸不变时直接使用渲染阶段的位图，不再复制
+            (actual_width, actual_height, rgba_data)
+        };
+
+        // 填充到固定尺寸画布
+        let (final_width, final_height, final_rgba) = match self.config.canvas {
+            Some(canvas) => (
+                canvas.width,
+                canvas.height,
+                sprite::letterbox(&final_rgba, final_width, final_height, &canvas, self.config.pixel_format),
+            ),
+            None => (final_width, final_height, final_rgba),
+        };
+
+        let final_rgba = self.apply_filters(final_rgba, final_width, final_height, is_scan);
+        let image_stats = self.image_stats(&final_rgba);
+        // 高亮和水印已在渲染阶段合成（render_page_overlaid），哈希的就是交给编码器的位图
+        let content_hash = self
+            .config
+            .content_hash
+            .then(|| content_hash::bitmap_hash(&final_rgba, final_width, final_height));
+        let has_alpha = self.config.preserve_alpha && has_transparency(&final_rgba);
+
+        // 超出输出格式上限的页面按网格分块编码
+        let oversize = final_width > self.max_dimension() || final_height > self.max_dimension();
+        if self.config.oversize_strategy == OversizeStrategy::Tile && oversize {
+            let budgeted = self.with_encode_budget();
+            let encoder = budgeted.as_ref().unwrap_or(self);
+            let tiles = encoder.encode_tiles(&final_rgba, final_width, final_height);
+            let encode_time = encode_start.elapsed().as_millis() as u32;
+            let error_code = tiles.as_ref().err().and(ErrorCode::EncodeFailed.code());
+            return PageResult {
+                page_num,
+                width: final_width,
+                height: final_height,
+                buffer: Buffer::from(vec![]),
+                success: tiles.is_ok(),
+                error: tiles.as_ref().err().cloned(),
+                render_time,
+                encode_time,
+                skipped: false,
+                format: self.format_name(self.config.format),
+                tiles: tiles.ok(),
+                render_info: Some(encoder.budget_info(RenderInfo {
+                    color_converted_images: color_converted,
+                    has_alpha: has_alpha && self.keeps_alpha(self.config.format),
+                    object_errors,
+                    ..self.render_info(
+                        &plan,
+                        is_scan,
+                        resized,
+                        self.config.format,
+                        transform,
+                        (final_width.min(self.config.tile_size), final_height.min(self.config.tile_size)),
+                    )
+                })),
+                error_code,
+                alternates: None,
+                cached: false,
+                passthrough: false,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
+                image_stats,
+                content_hash,
+                spans: None,
+                clamped: requested_size.is_some(),
+                requested_size,
+            };
+        }
+
+        // 根据配置的格式进行编码；纯文字页面可切换到灰度编码
+        let format = self.page_format(final_width, final_height);
+        let is_text_page = text_only && format == OutputFormat::WebP && filters::is_near_grayscale(&final_rgba, 16);
+        let text_encoder = (is_text_page && !has_alpha).then(|| self.text_page_encoder());
+        let final_rgba = match text_encoder {
+            Some(_) => filters::gray_to_pixels(&filters::to_grayscale(&final_rgba, self.config.pixel_format)),
+            None => final_rgba,
+        };
+        let encoder = text_encoder.as_ref().unwrap_or(self);
+        let budgeted = encoder.with_encode_budget();
+        let encoder = budgeted.as_ref().unwrap_or(encoder);
+        let info = RenderInfo {
+            text_optimized: text_encoder.is_some(),
+            color_converted_images: color_converted,
+            has_alpha: has_alpha && self.keeps_alpha(format),
+            object_errors,
+            ..encoder.render_info(&plan, is_scan, resized, format, transform, (final_width, final_height))
+        };
+        let encoded = encoder.encode_image_as(format, &final_rgba, final_width, final_height);
+        let info = encoder.budget_info(info);
+        let encoded_buffer = match encoded {
+            Ok(buf) => buf,
+            Err(e) => {
+                return PageResult {
+                    width: final_width,
+                    height: final_height,
+                    render_time,
+                    render_info: Some(info),
+                    clamped: requested_size.is_some(),
+                    requested_size,
+                    ..PageResult::failed(page_num, format.as_str(), e, ErrorCode::EncodeFailed)
+                };
+            }
+        };
+
+        let encode_time = encode_start.elapsed().as_millis() as u32;
+        let alternates = encoder.encode_alternates(format, &final_rgba, final_width, final_height);
+
+        PageResult {
+            page_num,
+            width: final_width,
+            height: final_height,
+            buffer: into_js_buffer(encoded_buffer),
+            success: true,
+            error: None,
+            render_time,
+            encode_time,
+            skipped: false,
+            format: encoder.format_name(format),
+            tiles: None,
+            render_info: Some(info),
+            error_code: None,
+            alternates,
+            cached: false,
+            passthrough: false,
+            detected_scan: scan.map(|scan| scan.is_scan),
+            text_object_count: scan.map(|scan| scan.text_objects),
+            image_coverage: scan.map(|scan| scan.image_coverage),
+            downscaled_due_to_memory: plan.memory_downscaled,
+            image_stats,
+            content_hash,
+            spans: None,
+            clamped: requested_size.is_some(),
+            requested_size,
+        }
+    }
+}
+
+impl<'a> PdfRenderer<'a> {
+    /// annotationTypes / formMode：渲染前把不在列表中的注释（formMode 为 omit 时还有表单控件）
+    /// 标记为隐藏，formMode 为 flatten 时再把剩余的控件和注释合并进页面内容
+    ///
+    /// 文档每次调用都重新打开，修改的注释标志不会影响其他渲染。
+    fn filter_annotations(&self, page: &mut PdfPage) {
+        let types = &self.config.annotation_types;
+        let omit_forms = self.config.form_mode == FormMode::Omit;
+        if types.is_some() || omit_forms {
+            let annotations = page.annotations();
+            for index in 0..annotations.len() {
+                if let Ok(mut annotation) = annotations.get(index) {
+                    let kind = annotation.annotation_type();
+                    let is_form = matches!(kind, PdfPageAnnotationType::Widget | PdfPageAnnotationType::XfaWidget);
+                    let keep = types.as_ref().is_none_or(|types| types.contains(&kind)) && !(omit_forms && is_form);
+                    if !keep && !annotation.is_hidden() {
+                        let _ = annotation.set_is_hidden(true);
+                    }
+                }
+            }
+        }
+        if self.config.form_mode == FormMode::Flatten {
+            // 合并失败时按默认方式渲染
+            let _ = page.flatten();
+        }
+    }
+
+    /// forceSrgb：渲染前把页面上的 CMYK/ICC 图片经 ICC 变换转换到 sRGB，返回转换的图片数
+    fn convert_colors(&self, document: &PdfDocument, page: &PdfPage) -> u32 {
+        match &self.config.color_management {
+            Some(settings) => color::convert_page_images(document, page, settings),
+            None => 0,
+        }
+    }
+}
+
+impl PageEncoder {
+    /// 编码结果是否带 alpha 通道：JPEG/TIFF 与背景色混合，二值化输出只有黑白两色
+    fn keeps_alpha(&self, format: OutputFormat) -> bool {
+        matches!(format, OutputFormat::WebP | OutputFormat::Png | OutputFormat::Avif) && self.config.bilevel.is_none()
+    }
+
+    /// options.spanTimings：各阶段的起止时间
+    fn spans(&self, times: flight_recorder::StageTimes) -> Option<PageSpans> {
+        if !self.config.span_timings {
+            return None;
+        }
+        let millis = |instant: Option<std::time::Instant>| instant.map(flight_recorder::epoch_millis);
+        Some(PageSpans {
+            load_start: flight_recorder::epoch_millis(times.start),
+            load_end: flight_recorder::epoch_millis(times.rendering.unwrap_or(times.end)),
+            render_start: millis(times.rendering),
+            render_end: millis(times.rendering.map(|_| times.encoding.unwrap_or(times.end))),
+            encode_start: millis(times.encoding),
+            encode_end: millis(times.encoding.map(|_| times.end)),
+        })
+    }
+
+    /// options.imageStats：输出图像的亮度统计
+    fn image_stats(&self, data: &[u8]) -> Option<ImageStats> {
+        self.config.image_stats.then(|| {
+            let stats = image_stats::luminance(data, self.config.pixel_format);
+            ImageStats {
+                histogram: stats.histogram,
+                mean: stats.mean,
+                std_dev: stats.std_dev,
+                entropy: stats.entropy,
+                low: stats.low,
+                high: stats.high,
+            }
+        })
+    }
+
+    /// 汇总单页实际生效的渲染和编码参数
+    ///
+    /// `size` 为单次编码的图像尺寸（分块页面为分块大小），用于确定 webpMethodAuto 选择的 method。
+    fn render_info(
+        &self,
+        plan: &RenderPlan,
+        is_scan: bool,
+        resized: bool,
+        format: OutputFormat,
+        transform: Option<[f64; 6]>,
+        size: (u32, u32),
+    ) -> RenderInfo {
+        let (encoder_backend, quality) = self.encoder_settings(format);
+        RenderInfo {
+            encoder_backend,
+            quality,
+            webp_method: (format == OutputFormat::WebP).then(|| self.webp_method(size.0, size.1)),
+            ..self.plan_info(plan, is_scan, resized, transform)
+        }
+    }
+
+    /// 编码指定尺寸的图像实际使用的 WebP method
+    fn webp_method(&self, width: u32, height: u32) -> i32 {
+        if self.config.webp_method_auto {
+            encoders::auto_webp_method(self.config.webp_method, width, height)
+        } else {
+            self.config.webp_method
+        }
+    }
+
+    /// 只包含渲染参数（原始位图不编码，编码器为 none）
+    fn plan_info(&self, plan: &RenderPlan, is_scan: bool, resized: bool, transform: Option<[f64; 6]>) -> RenderInfo {
+        RenderInfo {
+            scale: plan.scale as f64,
+            size_source: plan.size_source.to_string(),
+            is_scan,
+            max_scale_applied: plan.max_scale_applied,
+            scale_clamped: plan.scale_clamped,
+            dimension_clamped: plan.clamped,
+            resized,
+            encoder_backend: "none".to_string(),
+            quality: None,
+            webp_method: None,
+            text_optimized: false,
+            memory_degraded: false,
+            encode_budget_exceeded: false,
+            color_converted_images: 0,
+            has_alpha: false,
+            object_errors: None,
+            // 旋转 90° 时 x 方向的缩放体现在 b 上
+            dpi: transform.map(|[a, b, ..]| a.hypot(b) * 72.0),
+            transform: transform.map(|t| t.to_vec()),
+        }
+    }
+}
+
+impl<'a> PdfRenderer<'a> {
+    /// 检查可能没有画出来的页面对象，区域按 `transform` 换算到输出图像（未开启 objectDiagnostics 时为空）
+    fn object_errors(&self, page: &PdfPage, transform: Option<[f64; 6]>) -> Option<Vec<ObjectError>> {
+        if !self.config.object_diagnostics {
+            return None;
+        }
+        let to_region = |bounds: &PdfRect| {
+            let [a, b, c, d, e, f] = transform?;
+            let corners = [
+                (bounds.left().value, bounds.bottom().value),
+                (bounds.right().value, bounds.bottom().value),
+                (bounds.left().value, bounds.top().value),
+                (bounds.right().value, bounds.top().value),
+            ]
+            .map(|(x, y)| (a * x as f64 + c * y as f64 + e, b * x as f64 + d * y as f64 + f));
+            let (left, right) = corners.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+            let (top, bottom) = corners.iter().fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+            Some(ObjectRegion {
+                x: left.floor() as i32,
+                y: top.floor() as i32,
+                width: (right.ceil() - left.floor()) as u32,
+                height: (bottom.ceil() - top.floor()) as u32,
+            })
+        };
+        let errors = object_check::check_page_objects(page)
+            .into_iter()
+            .map(|issue| ObjectError {
+                object_index: issue.index as u32,
+                object_type: issue.kind.to_string(),
+                reason: issue.reason,
+                region: issue.bounds.as_ref().and_then(to_region),
+            })
+            .collect();
+        Some(errors)
+    }
+
+    /// PDF 坐标（点）到输出图像像素的仿射变换 `[a, b, c, d, e, f]`
+    ///
+    /// 用与渲染相同的配置换算三个参考点得到，再叠加渲染后的缩放（`rendered` → `output`）
+    /// 和画布偏移。参考点相距 1000 点，像素取整带来的误差可以忽略。
+    fn point_transform(&self, page: &PdfPage, rendered: (u32, u32), output: (u32, u32)) -> Option<[f64; 6]> {
+        const SPAN: f32 = 1000.0;
+        let render_config = self.page_render_config(rendered.0, rendered.1);
+        let to_pixels = |x: f32, y: f32| {
+            page.points_to_pixels(PdfPoints::new(x), PdfPoints::new(y), &render_config)
+                .ok()
+                .map(|(px, py)| (px as f64, py as f64))
+        };
+        let (e, f) = to_pixels(0.0, 0.0)?;
+        let (x1, y1) = to_pixels(SPAN, 0.0)?;
+        let (x2, y2) = to_pixels(0.0, SPAN)?;
+        let span = SPAN as f64;
+        let (a, b, c, d) = ((x1 - e) / span, (y1 - f) / span, (x2 - e) / span, (y2 - f) / span);
+
+        let sx = output.0 as f64 / rendered.0.max(1) as f64;
+        let sy = output.1 as f64 / rendered.1.max(1) as f64;
+        let (ox, oy) = match self.config.canvas {
+            Some(canvas) => canvas.offset(output.0, output.1),
+            None => (0, 0),
+        };
+        Some([a * sx, b * sy, c * sx, d * sy, e * sx + ox as f64, f * sy + oy as f64])
+    }
+
+    /// 不渲染，按与 `render_page_bitmap` 相同的扫描件判断、尺寸计算和画布计算输出图像的尺寸和坐标换算
+    ///
+    /// 版面分析、图形区域和文字位置共用，保证返回的像素坐标与实际渲染的图像一致。
+    pub fn output_mapping(&self, page: &PdfPage, page_num: u32) -> Option<OutputMapping> {
+        let is_scan = self.config.detect_scan && self.is_likely_scan(page).unwrap_or(false);
+        let plan = self.render_plan(page, page_num, is_scan, self.page_max_dimension());
+        let transform = self.point_transform(page, (plan.width, plan.height), (plan.width, plan.height))?;
+        let (width, height, offset) = match self.config.canvas {
+            Some(canvas) => {
+                let (ox, oy) = canvas.offset(plan.width, plan.height);
+                (canvas.width, canvas.height, (ox as f32, oy as f32))
+            }
+            None => (plan.width, plan.height, (0.0, 0.0)),
+        };
+        Some(OutputMapping { width, height, scale: plan.scale, transform, offset })
+    }
+
+    /// 分析单页的版面（文本块和图片区域）
+    ///
+    /// 文本块由 PDFium 的文字分段聚合而成，图片区域取页面顶层图片对象的外接矩形。
+    /// 坐标换算使用与实际渲染相同的尺寸和配置，因此与渲染结果逐像素对齐。
+    pub fn analyze_page_layout(&self, document: &PdfDocument, page_num: u32) -> PageLayout {
+        let num_pages = document.pages().len() as u32;
+        let failed = |error: String| PageLayout {
+            page_num,
+            width: 0,
+            height: 0,
+            scale: 0.0,
+            regions: vec![],
+            success: false,
+            error: Some(error),
+        };
+
+        // 检查页码有效性
+        if page_num < 1 || page_num > num_pages {
+            return failed(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+
+        // 版面分析总会检查页面对象，先做对象数限制
+        if let Err(e) = self.config.limits.check_page_objects(page.objects().len()) {
+            return failed(e.to_string());
+        }
+        let Some(mapping) = self.output_mapping(&page, page_num) else {
+            return failed("Failed to compute page transform".to_string());
+        };
+        // PDF 坐标（点）转换为输出图像坐标（像素），自动处理页面旋转、裁剪框偏移和画布
+        let to_pixels = |rect: &PdfRect| -> Option<Rect> {
+            Some(mapping.to_pixels(rect).clamp(mapping.width as f32, mapping.height as f32))
+        };
+
+        let text_rects: Vec<Rect> = match page.text() {
+            Ok(text) => text
+                .segments()
+                .iter()
+                .filter(|segment| !segment.text().trim().is_empty())
+                .filter_map(|segment| to_pixels(&segment.bounds()))
+                .filter(|r| r.width() > 0.0 && r.height() > 0.0)
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let mut image_rects: Vec<Rect> = page
+            .objects()
+            .iter()
+            .filter(|obj| matches!(obj.object_type(), PdfPageObjectType::Image))
+            .filter_map(|obj| obj.bounds().ok())
+            .filter_map(|bounds| to_pixels(&bounds.to_rect()))
+            .filter(|r| r.width() >= 1.0 && r.height() >= 1.0)
+            .collect();
+        analysis::sort_reading_order(&mut image_rects);
+
+        let to_region = |kind: &str, r: &Rect| LayoutRegion {
+            kind: kind.to_string(),
+            x: r.left.floor() as u32,
+            y: r.top.floor() as u32,
+            width: (r.right.ceil() - r.left.floor()) as u32,
+            height: (r.bottom.ceil() - r.top.floor()) as u32,
+        };
+
+        let regions = analysis::cluster_text_blocks(&text_rects)
+            .iter()
+            .map(|r| to_region("text", r))
+            .chain(image_rects.iter().map(|r| to_region("image", r)))
+            .collect();
+
+        PageLayout {
+            page_num,
+            width: mapping.width,
+            height: mapping.height,
+            scale: mapping.scale as f64,
+            regions,
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 读取页面结构树中的图形、替代文字和题注
+    ///
+    /// 区域坐标与相同选项下 `render_document_pages` 输出的图像一致。
+    pub fn page_figures(&self, document: &PdfDocument, page_num: u32) -> PageFigures {
+        let num_pages = document.pages().len() as u32;
+        let failed = |error: String| PageFigures {
+            page_num,
+            tagged: false,
+            width: 0,
+            height: 0,
+            figures: vec![],
+            success: false,
+            error: Some(error),
+        };
+
+        if page_num < 1 || page_num > num_pages {
+            return failed(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+        if let Err(e) = self.config.limits.check_page_objects(page.objects().len()) {
+            return failed(e.to_string());
+        }
+
+        let Some(mapping) = self.output_mapping(&page, page_num) else {
+            return failed("Failed to compute page transform".to_string());
+        };
+
+        // 按标记内容 ID 收集页面对象的外接矩形（像素）和文字
+        let mut objects = std::collections::HashMap::<i32, Vec<(Option<Rect>, Option<String>)>>::new();
+        for object in page.objects().iter() {
+            let mcid = structure::marked_content_id(page.bindings(), &object);
+            if mcid < 0 {
+                continue;
+            }
+            let rect = object
+                .bounds()
+                .ok()
+                .map(|bounds| mapping.to_pixels(&bounds.to_rect()).clamp(mapping.width as f32, mapping.height as f32));
+            let text = object.as_text_object().map(|t| t.text());
+            objects.entry(mcid).or_default().push((rect, text));
+        }
+        let region = |node: &StructNode| {
+            let rects: Vec<Rect> = node
+                .all_mcids()
+                .iter()
+                .filter_map(|id| objects.get(id))
+                .flatten()
+                .filter_map(|(rect, _)| *rect)
+                .filter(|r| r.width() > 0.0 && r.height() > 0.0)
+                .collect();
+            let bounds = rects.iter().skip(1).fold(*rects.first()?, |a, b| a.union(b));
+            Some(LayoutRegion {
+                kind: "figure".to_string(),
+                x: bounds.left.floor() as u32,
+                y: bounds.top.floor() as u32,
+                width: (bounds.right.ceil() - bounds.left.floor()) as u32,
+                height: (bounds.bottom.ceil() - bounds.top.floor()) as u32,
+            })
+        };
+        // 题注优先使用结构元素自带的文字，否则拼接其标记内容中的文字
+        let caption_text = |node: &StructNode| {
+            node.actual_text.clone().or_else(|| node.alt_text.clone()).or_else(|| {
+                let parts: Vec<&str> = node
+                    .all_mcids()
+                    .iter()
+                    .filter_map(|id| objects.get(id))
+                    .flatten()
+                    .filter_map(|(_, text)| text.as_deref())
+                    .collect();
+                let text = parts.join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+                (!text.is_empty()).then_some(text)
+            })
+        };
+
+        let tree = structure::page_structure(&page);
+        let figures = structure::collect_figures(&tree)
+            .into_iter()
+            .map(|figure| PageFigure {
+                kind: figure.node.kind.clone(),
+                alt_text: figure.node.alt_text.clone(),
+                actual_text: figure.node.actual_text.clone(),
+                title: figure.node.title.clone(),
+                caption: figure.caption.and_then(caption_text),
+                region: region(figure.node),
+            })
+            .collect();
+
+        PageFigures {
+            page_num,
+            tagged: !tree.is_empty(),
+            width: mapping.width,
+            height: mapping.height,
+            figures,
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 单页概况：尺寸、旋转、标签、是否有文字和图片（不渲染）
+    pub fn page_overview(&self, document: &PdfDocument, page_num: u32) -> PageOverview {
+        let failed = |error: String| PageOverview {
+            page_num,
+            width: 0.0,
+            height: 0.0,
+            rotation: 0,
+            label: None,
+            has_text: false,
+            has_images: false,
+            has_annotations: false,
+            has_form_fields: false,
+            is_scan: false,
+            success: false,
+            error: Some(error),
+        };
+
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+        // 判断是否有图片需要遍历页面对象，先做对象数限制
+        if let Err(e) = self.config.limits.check_page_objects(page.objects().len()) {
+            return failed(e.to_string());
+        }
+
+        let rotation = rotation_degrees(&page);
+        let (mut has_annotations, mut has_form_fields) = (false, false);
+        for annotation in page.annotations().iter() {
+            match annotation.annotation_type() {
+                PdfPageAnnotationType::Widget | PdfPageAnnotationType::XfaWidget => has_form_fields = true,
+                _ => has_annotations = true,
+            }
+        }
+        PageOverview {
+            page_num,
+            width: page.width().value as f64,
+            height: page.height().value as f64,
+            rotation,
+            label: page.label().map(str::to_string),
+            has_text: page.text().map(|text| !text.is_empty()).unwrap_or(false),
+            has_images: page
+                .objects()
+                .iter()
+                .any(|obj| matches!(obj.object_type(), PdfPageObjectType::Image)),
+            has_annotations,
+            has_form_fields,
+            is_scan: self.is_likely_scan(&page).unwrap_or(false),
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 单页顶层图片对象的像素尺寸、放置位置和有效分辨率（不渲染、不解码图片）
+    pub fn page_images(&self, document: &PdfDocument, page_num: u32) -> PageImages {
+        let num_pages = document.pages().len() as u32;
+        let failed = |error: String| PageImages {
+            page_num,
+            images: vec![],
+            dominant_dpi: None,
+            min_dpi: None,
+            success: false,
+            error: Some(error),
+        };
+
+        if page_num < 1 || page_num > num_pages {
+            return failed(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+        if let Err(e) = self.config.limits.check_page_objects(page.objects().len()) {
+            return failed(e.to_string());
+        }
+
+        let images = page_image_list(&page);
+        PageImages {
+            page_num,
+            dominant_dpi: dominant_image(&images).map(|image| image.effective_dpi),
+            min_dpi: images.iter().map(|image| image.effective_dpi).min_by(f64::total_cmp),
+            images,
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 提取单页的文字及其位置（不渲染）
+    ///
+    /// 同时返回页面坐标（点）和输出图像坐标（像素），像素坐标与相同选项下 `render_document_pages`
+    /// 输出的图像一致。按词合并时使用逐字的宽松外接矩形，相邻的词不会因字形差异留下缝隙。
+    pub fn page_text_rects(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        granularity: TextGranularity,
+    ) -> std::result::Result<PageTextRects, String> {
+        let num_pages = document.pages().len() as u32;
+        if page_num < 1 || page_num > num_pages {
+            return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+        self.config.limits.check_page_objects(page.objects().len()).map_err(|e| e.to_string())?;
+        let mapping = self
+            .output_mapping(&page, page_num)
+            .ok_or_else(|| "Failed to compute page transform".to_string())?;
+        let text = page.text().map_err(|e| format!("Failed to extract text: {}", e))?;
+
+        // 统一在输出图像坐标中合并，再换算回页面坐标
+        let runs: Vec<(String, Rect)> = match granularity {
+            TextGranularity::Word => analysis::group_words(text.chars().iter().filter_map(|ch| {
+                let bounds = ch.loose_bounds().ok()?;
+                Some((ch.unicode_char().unwrap_or(' '), mapping.to_pixels(&bounds)))
+            })),
+            TextGranularity::Run => text
+                .segments()
+                .iter()
+                .filter_map(|segment| {
+                    let content = segment.text().split_whitespace().collect::<Vec<_>>().join(" ");
+                    (!content.is_empty()).then(|| (content, mapping.to_pixels(&segment.bounds())))
+                })
+                .collect(),
+        };
+        let rects = runs
+            .into_iter()
+            .map(|(text, pixels)| {
+                let points = mapping.to_page_points(&pixels);
+                TextRect {
+                    text,
+                    x: points.left as f64,
+                    y: points.top as f64,
+                    width: points.width() as f64,
+                    height: points.height() as f64,
+                    pixel_x: pixels.left as f64,
+                    pixel_y: pixels.top as f64,
+                    pixel_width: pixels.width() as f64,
+                    pixel_height: pixels.height() as f64,
+                }
+            })
+            .collect();
+
+        Ok(PageTextRects {
+            page_num,
+            width: mapping.width,
+            height: mapping.height,
+            scale: mapping.scale as f64,
+            transform: mapping.transform.to_vec(),
+            granularity: granularity.as_str().to_string(),
+            rects,
+        })
+    }
+
+    /// 列出单页的链接和其他注释及其在输出图像中的位置（不渲染）
+    ///
+    /// 链接解析为外部 URI 或本文档内的目标页码，其他注释返回类型和文字内容。
+    /// 隐藏的注释（包括默认不显示的弹出框）不返回；表单控件也作为注释返回（类型为 widget）。
+    pub fn page_annotations(&self, document: &PdfDocument, page_num: u32) -> std::result::Result<PageAnnotations, String> {
+        let num_pages = document.pages().len() as u32;
+        if page_num < 1 || page_num > num_pages {
+            return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+        let mapping = self
+            .output_mapping(&page, page_num)
+            .ok_or_else(|| "Failed to compute page transform".to_string())?;
+
+        let annotations = page
+            .annotations()
+            .iter()
+            .filter(|annotation| !annotation.is_hidden())
+            .filter_map(|annotation| {
+                let bounds = annotation.bounds().ok()?;
+                let rect = mapping.to_pixels(&bounds).clamp(mapping.width as f32, mapping.height as f32);
+                if rect.width() <= 0.0 || rect.height() <= 0.0 {
+                    return None;
+                }
+                let link = annotation.as_link_annotation().and_then(|link| link.link().ok());
+                let action = link.as_ref().and_then(|link| link.action());
+                // 链接可以直接带目标，也可以通过跳转动作指定
+                let destination = link.as_ref().and_then(|link| link.destination()).or_else(|| {
+                    action
+                        .as_ref()
+                        .and_then(|a| a.as_local_destination_action())
+                        .and_then(|a| a.destination().ok())
+                });
+                Some(PageAnnotation {
+                    kind: annotation_type_name(annotation.annotation_type()).to_string(),
+                    x: rect.left as f64,
+                    y: rect.top as f64,
+                    width: rect.width() as f64,
+                    height: rect.height() as f64,
+                    uri: action
+                        .as_ref()
+                        .and_then(|a| a.as_uri_action())
+                        .and_then(|a| a.uri().ok())
+                        .filter(|uri| !uri.is_empty()),
+                    dest_page_num: destination
+                        .and_then(|d| d.page_index().ok())
+                        .map(|index| index as u32 + 1)
+                        .filter(|&n| n <= num_pages),
+                    contents: annotation.contents().filter(|c| !c.trim().is_empty()),
+                })
+            })
+            .collect();
+
+        Ok(PageAnnotations {
+            page_num,
+            width: mapping.width,
+            height: mapping.height,
+            scale: mapping.scale as f64,
+            transform: mapping.transform.to_vec(),
+            annotations,
+        })
+    }
+
+    /// 列出表单字段及其在输出图像中的位置（不渲染）
+    ///
+    /// 每个表单控件返回一项，同一单选按钮组的各个按钮名称相同；`page_num` 为空时列出所有页。
+    pub fn form_fields(&self, document: &PdfDocument, page_num: Option<u32>) -> std::result::Result<Vec<FormField>, String> {
+        let num_pages = document.pages().len() as u32;
+        let page_nums = match page_num {
+            Some(page_num) if page_num < 1 || page_num > num_pages => {
+                return Err(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+            }
+            Some(page_num) => page_num..=page_num,
+            None => 1..=num_pages,
+        };
+        // 没有表单的文档不必逐页加载
+        if document.form().is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut fields = Vec::new();
+        for page_num in page_nums {
+            let page = document
+                .pages()
+                .get((page_num - 1) as u16)
+                .map_err(|e| format!("Failed to get page: {}", e))?;
+            let mapping = self
+                .output_mapping(&page, page_num)
+                .ok_or_else(|| "Failed to compute page transform".to_string())?;
+            for annotation in page.annotations().iter() {
+                let (Some(field), Ok(bounds)) = (annotation.as_form_field(), annotation.bounds()) else {
+                    continue;
+                };
+                let rect = mapping.to_pixels(&bounds).clamp(mapping.width as f32, mapping.height as f32);
+                let value = match field {
+                    PdfFormField::Text(text) => text.value(),
+                    PdfFormField::ComboBox(combo) => combo.value(),
+                    PdfFormField::ListBox(list) => list.value(),
+                    PdfFormField::Checkbox(checkbox) => checkbox.group_value(),
+                    PdfFormField::RadioButton(radio) => radio.group_value(),
+                    _ => None,
+                };
+                let checked = match field {
+                    PdfFormField::Checkbox(checkbox) => checkbox.is_checked().ok(),
+                    PdfFormField::RadioButton(radio) => radio.is_checked().ok(),
+                    _ => None,
+                };
+                fields.push(FormField {
+                    name: field.name().filter(|name| !name.is_empty()),
+                    kind: form_field_type_name(field.field_type()).to_string(),
+                    value,
+                    checked,
+                    read_only: field.is_read_only(),
+                    required: field.is_required(),
+                    page_num,
+                    x: rect.left as f64,
+                    y: rect.top as f64,
+                    width: rect.width().max(0.0) as f64,
+                    height: rect.height().max(0.0) as f64,
+                });
+            }
+        }
+        Ok(fields)
+    }
+
+    /// 单页的尺寸、旋转和标签（不遍历页面对象，不提取文字）
+    pub fn page_info(&self, document: &PdfDocument, page_num: u32) -> PageInfo {
+        let num_pages = document.pages().len() as u32;
+        let failed = |error: String| PageInfo {
+            page_num,
+            width: 0.0,
+            height: 0.0,
+            rotation: 0,
+            label: None,
+            success: false,
+            error: Some(error),
+        };
+        if page_num < 1 || page_num > num_pages {
+            return failed(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+        PageInfo {
+            page_num,
+            width: page.width().value as f64,
+            height: page.height().value as f64,
+            rotation: rotation_degrees(&page),
+            label: page.label().map(str::to_string),
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 单页的尺寸和旋转，用于文档间的布局比较（不遍历页面对象）
+    pub fn page_geometry(&self, document: &PdfDocument, page_num: u32) -> std::result::Result<PageGeometry, String> {
+        let page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+        Ok(PageGeometry {
+            width: page.width().value as f64,
+            height: page.height().value as f64,
+            rotation: rotation_degrees(&page),
+        })
+    }
+
+    /// 统计页面可提取文字的字符数和词数（不渲染，不返回文字内容）
+    pub fn page_text_stats(&self, document: &PdfDocument, page_num: u32) -> PageTextStats {
+        let num_pages = document.pages().len() as u32;
+        let failed = |error: String| PageTextStats {
+            page_num,
+            has_text: false,
+            char_count: 0,
+            word_count: 0,
+            success: false,
+            error: Some(error),
+        };
+
+        if page_num < 1 || page_num > num_pages {
+            return failed(format!("Invalid page number: {} (total: {})", page_num, num_pages));
+        }
+
+        let page = match document.pages().get((page_num - 1) as u16) {
+            Ok(p) => p,
+            Err(e) => return failed(format!("Failed to get page: {}", e)),
+        };
+        let text = match page.text() {
+            Ok(text) => text.all(),
+            Err(e) => return failed(format!("Failed to load page text: {}", e)),
+        };
+
+        let (char_count, word_count) = analysis::count_text(&text);
+        PageTextStats {
+            page_num,
+            has_text: char_count > 0,
+            char_count,
+            word_count,
+            success: true,
+            error: None,
+        }
+    }
+
+    /// 把该页的高亮区域合成到刚渲染出的位图上（缩放和填充画布之前）
+    fn burn_highlights(&self, page: &PdfPage, page_num: u32, image: &mut [u8], width: u32, height: u32) {
+        let Some(highlights) = self.config.highlights.get(&page_num) else {
+            return;
+        };
+        // PDF 坐标转换为像素坐标，自动处理页面旋转和裁剪框偏移
+        let render_config = self.page_render_config(width, height);
+        let rects: Vec<(Rect, [u8; 4])> = highlights
+            .iter()
+            .filter_map(|h| {
+                let (x1, y1) = page.points_to_pixels(PdfPoints::new(h.left), PdfPoints::new(h.top), &render_config).ok()?;
+                let (x2, y2) = page.points_to_pixels(PdfPoints::new(h.right), PdfPoints::new(h.bottom), &render_config).ok()?;
+                Some((Rect::from_corners(x1 as f32, y1 as f32, x2 as f32, y2 as f32), h.color))
+            })
+            .collect();
+        overlay::burn_highlights(image, width, height, &rects, self.config.pixel_format);
+    }
 
-        for &page_num in page_nums {
-            let result = self.render_single_page(document, page_num, num_pages);
-            results.push(result);
-        }
+    /// options.stampText / stampImage：把水印合成到位图上（在高亮之上，跳过 stampExclusions）
+    fn burn_stamp(&self, page: &PdfPage, page_num: u32, image: &mut [u8], width: u32, height: u32) -> std::result::Result<(), String> {
+        let Some(Ok(stamp)) = &self.config.stamp else {
+            return Ok(());
+        };
+        let mark = stamp.image(self.pdfium)?;
+        let render_config = self.page_render_config(width, height);
+        let exclusions: Vec<Rect> = stamp
+            .exclusions
+            .iter()
+            .filter(|e| e.applies_to(page_num))
+            .filter_map(|e| {
+                let (x1, y1) = page.points_to_pixels(PdfPoints::new(e.left), PdfPoints::new(e.top), &render_config).ok()?;
+                let (x2, y2) = page.points_to_pixels(PdfPoints::new(e.right), PdfPoints::new(e.bottom), &render_config).ok()?;
+                Some(Rect::from_corners(x1 as f32, y1 as f32, x2 as f32, y2 as f32))
+            })
+            .collect();
+        stamp::composite(image, width, height, stamp, &mark, &exclusions, self.config.pixel_format);
+        Ok(())
+    }
+}
 
-        Ok((num_pages, results))
+impl PageEncoder {
+    /// 当前输出格式允许的最大单边像素数
+    fn max_dimension(&self) -> u32 {
+        self.config.format.max_dimension().unwrap_or(RAW_MAX_DIMENSION)
     }
 
-    /// 渲染单个页面
-    fn render_single_page(
-        &self,
-        document: &PdfDocument,
-        page_num: u32,
-        num_pages: u32,
-    ) -> PageResult {
-        let render_start = std::time::Instant::now();
+    /// 单页渲染允许的最大单边像素数（超出 WebP 上限时可改用 PNG 的情况下不受 WebP 限制）
+    fn page_max_dimension(&self) -> u32 {
+        match self.config.oversize_strategy {
+            OversizeStrategy::FallbackPng | OversizeStrategy::Tile => RAW_MAX_DIMENSION,
+            OversizeStrategy::Downscale => self.max_dimension(),
+        }
+    }
 
-        // 检查页码有效性
-        if page_num < 1 || page_num > num_pages {
-            return PageResult {
-                page_num,
-                width: 0,
-                height: 0,
-                buffer: Buffer::from(vec![]),
-                success: false,
-                error: Some(format!("Invalid page number: {} (total: {})", page_num, num_pages)),
-                render_time: 0,
-                encode_time: 0,
-            };
+    /// 把整页图像按网格切块并逐块编码
+    fn encode_tiles(&self, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<PageTiles, String> {
+        let grid = TileGrid::new(width, height, self.config.tile_size.min(self.max_dimension()));
+        let mut tiles = Vec::with_capacity((grid.columns * grid.rows) as usize);
+        for rect in grid.tiles() {
+            let pixels = tiles::crop(rgba_data, width, &rect);
+            let encoded = self.encode_image(&pixels, rect.width, rect.height)?;
+            tiles.push(PageTile {
+                row: rect.row,
+                column: rect.column,
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+                buffer: into_js_buffer(encoded),
+            });
         }
+        Ok(PageTiles {
+            tile_size: grid.tile_size,
+            columns: grid.columns,
+            rows: grid.rows,
+            tiles,
+        })
+    }
 
-        // PDFium 页码从 0 开始
-        let page_index = (page_num - 1) as u16;
-        
-        let page = match document.pages().get(page_index) {
-            Ok(p) => p,
-            Err(e) => {
-                return PageResult {
-                    page_num,
-                    width: 0,
-                    height: 0,
-                    buffer: Buffer::from(vec![]),
-                    success: false,
-                    error: Some(format!("Failed to get page: {}", e)),
-                    render_time: 0,
-                    encode_time: 0,
-                };
-            }
+    /// 给定尺寸的页面实际使用的输出格式
+    fn page_format(&self, width: u32, height: u32) -> OutputFormat {
+        let limit = self.config.format.max_dimension().unwrap_or(u32::MAX);
+        if width > limit || height > limit {
+            OutputFormat::Png
+        } else {
+            self.config.format
+        }
+    }
+
+    /// 实际使用的编码参数
+    fn encoder_settings(&self, format: OutputFormat) -> (String, Option<u32>) {
+        let backend_name = if format == self.config.format { self.config.encoder_backend.as_str() } else { "auto" };
+        let backend = encoders::resolve(format, backend_name)
+            .map(|b| b.as_str().to_string())
+            .unwrap_or_else(|_| backend_name.to_string());
+        let quality = match format {
+            OutputFormat::WebP if backend == "libwebp" => Some(self.config.webp_quality as u32),
+            OutputFormat::Jpg => Some(self.config.jpeg_quality as u32),
+            OutputFormat::Png => Some(self.config.png_compression as u32),
+            OutputFormat::Avif => Some(self.config.avif_quality as u32),
+            _ => None,
         };
+        (backend, quality)
+    }
+}
+
+impl<'a> PdfRenderer<'a> {
+    /// 尺寸选项不能互相冲突，固定画布不能超出输出格式的尺寸上限，水印选项必须有效
+    fn check_size_options(&self, max_dimension: u32) -> std::result::Result<(), String> {
+        if let Some(e) = &self.config.size_error {
+            return Err(e.clone());
+        }
+        if let Some(Err(e)) = &self.config.stamp {
+            return Err(e.clone());
+        }
+        match self.config.canvas {
+            Some(canvas) if canvas.width > max_dimension || canvas.height > max_dimension => Err(format!(
+                "Canvas too large: {}x{} (max {})",
+                canvas.width, canvas.height, max_dimension
+            )),
+            _ => Ok(()),
+        }
+    }
 
+    /// 计算页面的渲染像素尺寸
+    ///
+    /// 按目标宽度或 DPI（扫描件使用 `image_heavy_width`）和 `max_scale` 计算缩放比例，
+    /// 并保证单边不超过 `max_dimension`。
+    fn render_size(&self, page: &PdfPage, page_num: u32, is_scan: bool, max_dimension: u32) -> (u32, u32) {
+        let plan = self.render_plan(page, page_num, is_scan, max_dimension);
+        (plan.width, plan.height)
+    }
+
+    /// 计算渲染尺寸，并记录尺寸来源和触发的限制
+    fn render_plan(&self, page: &PdfPage, page_num: u32, is_scan: bool, max_dimension: u32) -> RenderPlan {
         // 获取页面原始尺寸（点，72 DPI）
-        let original_width = page.width().value as f32;
-        let original_height = page.height().value as f32;
+        let original_width = page.width().value;
+        let original_height = page.height().value;
 
-        // 计算缩放比例
-        let target_width = if self.config.detect_scan && self.is_likely_scan(&page) {
-            self.config.image_heavy_width as f32
-        } else {
-            self.config.target_width as f32
+        // 单页的扫描件宽度覆盖优先于检测结果，也不受 detect_scan 开关影响
+        let image_heavy = match self.config.scan_widths.get(&page_num) {
+            Some(ScanWidth::Full) => false,
+            Some(ScanWidth::ImageHeavy) => true,
+            None => self.config.detect_scan && is_scan,
         };
 
-        let mut scale = target_width / original_width;
-        scale = scale.min(self.config.max_scale);
+        // 计算缩放比例：固定画布优先，其次是单页覆盖和扫描件降级宽度
+        let (requested_scale, size_source) = match (self.config.canvas, self.config.page_scales.get(&page_num)) {
+            (Some(canvas), _) => (
+                (canvas.width as f32 / original_width).min(canvas.height as f32 / original_height),
+                "canvas",
+            ),
+            (None, Some(PageScale::Width(width))) => (*width as f32 / original_width, "pageWidths"),
+            (None, Some(PageScale::Dpi(dpi))) => (dpi / 72.0, "pageDpi"),
+            (None, None) if image_heavy => {
+                (self.config.image_heavy_width as f32 / original_width, "imageHeavyWidth")
+            }
+            (None, None) => match self.config.dpi {
+                Some(dpi) => (dpi / 72.0, "dpi"),
+                None => (self.config.target_width as f32 / original_width, "targetWidth"),
+            },
+        };
+        let max_scale_applied = requested_scale > self.config.max_scale;
+        // 小尺寸扫描页面：降级宽度被 max_scale 限制，preferSharpness 时改按内嵌图片的原始分辨率
+        let scale_clamped = max_scale_applied && size_source == "imageHeavyWidth";
+        let native = (scale_clamped && self.config.prefer_sharpness)
+            .then(|| Self::native_image_scale(page))
+            .flatten()
+            .filter(|&native| native > self.config.max_scale);
+        let (mut scale, size_source) = match native {
+            Some(native) => (native.min(requested_scale), "imageNative"),
+            None => (requested_scale.min(self.config.max_scale), size_source),
+        };
 
         let mut render_width = (original_width * scale).round() as u32;
         let mut render_height = (original_height * scale).round() as u32;
+        let (requested_width, requested_height) = (render_width, render_height);
+        let clamped = render_width > max_dimension || render_height > max_dimension;
 
-        // WebP 尺寸限制检查（单边不能超过 16383）
-        // 注意：PNG 和 JPG 没有这个限制，但为了一致性和内存考虑，仍然应用此限制
-        let max_dimension = if self.config.format == OutputFormat::WebP {
-            WEBP_MAX_DIMENSION
-        } else {
-            // PNG/JPG 理论上支持更大尺寸，但为了性能和内存，限制在 32767
-            32767
-        };
-
-        if render_width > max_dimension || render_height > max_dimension {
+        if clamped {
             let width_factor = if render_width > max_dimension {
                 max_dimension as f32 / render_width as f32
             } else {
@@ -175,157 +3243,554 @@ impl<'a> PdfRenderer<'a> {
                 1.0
             };
             let limit_factor = width_factor.min(height_factor);
-            
+
             scale *= limit_factor;
             render_width = (original_width * scale).round() as u32;
             render_height = (original_height * scale).round() as u32;
         }
 
-        // 渲染页面为 RGBA 位图
-        let bitmap = match page.render_with_config(
-            &PdfRenderConfig::new()
-                .set_target_width(render_width as i32)
-                .set_target_height(render_height as i32)
-                .render_form_data(true)
-                .render_annotations(true)
-        ) {
-            Ok(b) => b,
-            Err(e) => {
-                return PageResult {
-                    page_num,
-                    width: 0,
-                    height: 0,
-                    buffer: Buffer::from(vec![]),
-                    success: false,
-                    error: Some(format!("Failed to render page: {}", e)),
-                    render_time: render_start.elapsed().as_millis() as u32,
-                    encode_time: 0,
-                };
-            }
-        };
+        // 四舍五入不能让页面超出画布
+        if let Some(canvas) = self.config.canvas {
+            render_width = render_width.clamp(1, canvas.width);
+            render_height = render_height.clamp(1, canvas.height);
+        }
 
-        let render_time = render_start.elapsed().as_millis() as u32;
-        let encode_start = std::time::Instant::now();
+        // 位图超出内存预算时等比缩小（memoryBudgetAction 为 fail 时保持原尺寸，由调用方让该页失败）
+        let budget = self.config.limits.page_pixel_budget(self.config.concurrency);
+        let pixels = render_width as u64 * render_height as u64;
+        let memory_downscaled = !self.config.fail_over_memory_budget && budget.is_some_and(|budget| pixels > budget);
+        if let (true, Some(budget)) = (memory_downscaled, budget) {
+            let factor = (budget as f64 / pixels as f64).sqrt();
+            scale *= factor as f32;
+            render_width = ((render_width as f64 * factor).floor() as u32).max(1);
+            render_height = ((render_height as f64 * factor).floor() as u32).max(1);
+        }
 
-        // 转换为 image crate 的格式
-        let actual_width = bitmap.width() as u32;
-        let actual_height = bitmap.height() as u32;
-        
-        // 获取 RGBA 像素数据
-        let rgba_data = bitmap.as_rgba_bytes();
+        RenderPlan {
+            width: render_width,
+            height: render_height,
+            scale,
+            size_source,
+            max_scale_applied,
+            scale_clamped,
+            clamped,
+            requested_width,
+            requested_height,
+            memory_downscaled,
+        }
+    }
 
-        // 最终尺寸检查
-        let (final_width, final_height, final_rgba) = if actual_width > max_dimension || actual_height > max_dimension {
-            let width_factor = if actual_width > max_dimension {
-                max_dimension as f32 / actual_width as f32
-            } else {
-                1.0
-            };
-            let height_factor = if actual_height > max_dimension {
-                max_dimension as f32 / actual_height as f32
-            } else {
-                1.0
-            };
-            let limit_factor = width_factor.min(height_factor);
-            
-            let new_width = ((actual_width as f32) * limit_factor).round() as u32;
-            let new_height = ((actual_height as f32) * limit_factor).round() as u32;
-            
-            let img: ImageBuffer<Rgba<u8>, _> = match ImageBuffer::from_raw(actual_width, actual_height, rgba_data.to_vec()) {
-                Some(img) => img,
-                None => {
-                    return PageResult {
-                        page_num,
-                        width: actual_width,
-                        height: actual_height,
-                        buffer: Buffer::from(vec![]),
-                        success: false,
-                        error: Some("Failed to create image buffer for resize".to_string()),
-                        render_time,
-                        encode_time: 0,
-                    };
-                }
-            };
-            
-            let resized = image::imageops::resize(&img, new_width, new_height, image::imageops::FilterType::Lanczos3);
-            (new_width, new_height, resized.into_raw())
+    /// memoryBudgetAction 为 fail 时检查页面位图是否超出内存预算
+    fn check_memory_budget(&self, plan: &RenderPlan) -> std::result::Result<(), String> {
+        if !self.config.fail_over_memory_budget {
+            return Ok(());
+        }
+        self.config
+            .limits
+            .check_page_pixels(plan.width, plan.height, self.config.concurrency)
+            .map_err(|e| e.to_string())
+    }
+
+    /// 构造 PDFium 渲染配置
+    fn page_render_config(&self, render_width: u32, render_height: u32) -> PdfRenderConfig {
+        let config = PdfRenderConfig::new()
+            .set_target_width(render_width as i32)
+            .set_target_height(render_height as i32)
+            // RGBA 时让 PDFium 在渲染阶段直接交换 R/B 通道；BGRA 保持原生顺序
+            .set_reverse_byte_order(self.config.pixel_format == PixelFormat::Rgba);
+        // preserveAlpha：以透明背景渲染，页面没有绘制的区域保持透明
+        let config = if self.config.preserve_alpha {
+            config.set_clear_color(PdfColor::new(255, 255, 255, 0))
         } else {
-            (actual_width, actual_height, rgba_data.to_vec())
+            config
         };
+        self.config.advanced_flags.apply(config)
+    }
 
-        // 根据配置的格式进行编码
-        let encoded_buffer = match self.encode_image(&final_rgba, final_width, final_height) {
-            Ok(buf) => buf,
-            Err(e) => {
-                return PageResult {
-                    page_num,
-                    width: final_width,
-                    height: final_height,
-                    buffer: Buffer::from(vec![]),
-                    success: false,
-                    error: Some(e),
-                    render_time,
-                    encode_time: 0,
-                };
-            }
+    /// 渲染整页位图并合成高亮和水印
+    fn render_page_overlaid(
+        &self,
+        page: &PdfPage,
+        page_num: u32,
+        size: (u32, u32),
+        started: std::time::Instant,
+    ) -> std::result::Result<(Vec<u8>, u32, u32), (ErrorCode, String)> {
+        let (mut pixels, width, height) = self.render_page_pixels(page, size, started)?;
+        self.burn_highlights(page, page_num, &mut pixels, width, height);
+        self.burn_stamp(page, page_num, &mut pixels, width, height).map_err(|e| (ErrorCode::RenderFailed, e))?;
+        Ok((pixels, width, height))
+    }
+
+    /// 渲染整页位图，返回紧密排列的 4 通道像素和实际尺寸
+    ///
+    /// 指定了 page_timeout_ms 时改用可中断的渐进式渲染（见 watchdog），从 `started` 起超出时限返回 RenderTimeout。
+    fn render_page_pixels(
+        &self,
+        page: &PdfPage,
+        (width, height): (u32, u32),
+        started: std::time::Instant,
+    ) -> std::result::Result<(Vec<u8>, u32, u32), (ErrorCode, String)> {
+        let Some(timeout_ms) = self.config.page_timeout_ms else {
+            let bitmap = page
+                .render_with_config(&self.page_render_config(width, height))
+                .map_err(|e| (ErrorCode::RenderFailed, format!("Failed to render page: {}", e)))?;
+            let (actual_width, actual_height) = (bitmap.width() as u32, bitmap.height() as u32);
+            return Ok((pack_rows(bitmap.as_raw_bytes(), actual_width, actual_height, 4), actual_width, actual_height));
         };
 
-        let encode_time = encode_start.elapsed().as_millis() as u32;
+        let flags = raw_chunks::render_flags(&self.config.advanced_flags, self.config.pixel_format == PixelFormat::Rgba);
+        let clear_color = if self.config.preserve_alpha { 0x00FF_FFFF } else { 0xFFFF_FFFF };
+        let deadline = started + std::time::Duration::from_millis(timeout_ms as u64);
+        match watchdog::render_page(page, (width, height), flags, clear_color, deadline) {
+            Ok(Some(pixels)) => Ok((pixels, width, height)),
+            Ok(None) => Err((ErrorCode::RenderTimeout, format!("Page render timed out after {}ms", timeout_ms))),
+            Err(e) => Err((ErrorCode::RenderFailed, format!("Failed to render page: {}", e))),
+        }
+    }
 
-        PageResult {
-            page_num,
-            width: final_width,
-            height: final_height,
-            buffer: Buffer::from(encoded_buffer),
+    /// 检测页面是否可能是扫描件（启发式判断）
+    ///
+    /// 页面对象数超过 `max_page_objects` 时直接返回错误，不再逐个检查。
+    fn is_likely_scan(&self, page: &PdfPage) -> std::result::Result<bool, String> {
+        self.scan_decision(page).map(|scan| scan.is_scan)
+    }
+
+    /// 扫描件识别：至少有一张图片、图片覆盖比例不低于 scan_min_image_coverage、
+    /// 文字对象不超过 scan_max_text_objects
+    fn scan_decision(&self, page: &PdfPage) -> std::result::Result<ScanDecision, String> {
+        self.config
+            .limits
+            .check_page_objects(page.objects().len())
+            .map_err(|e| e.to_string())?;
+
+        let page_rect = page
+            .boundaries()
+            .crop()
+            .or_else(|_| page.boundaries().media())
+            .map(|boundary| boundary.bounds)
+            .ok();
+        let mut text_objects = 0u32;
+        let mut image_objects = 0u32;
+        let mut image_area = 0.0f64;
+        for object in page.objects().iter() {
+            match object.object_type() {
+                PdfPageObjectType::Text => text_objects += 1,
+                PdfPageObjectType::Image => {
+                    image_objects += 1;
+                    if let (Some(page_rect), Ok(bounds)) = (page_rect, object.bounds()) {
+                        image_area += overlap_area(&page_rect, &bounds.to_rect());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let page_area = page_rect.map_or(0.0, |rect| rect.width().value as f64 * rect.height().value as f64);
+        let image_coverage = if page_area > 0.0 { (image_area / page_area).min(1.0) } else { 0.0 };
+        Ok(ScanDecision {
+            is_scan: image_objects > 0
+                && image_coverage >= self.config.scan_min_image_coverage
+                && text_objects <= self.config.scan_max_text_objects,
+            text_objects,
+            image_coverage,
+        })
+    }
+
+    /// scanPassthrough：扫描页面只有一张整页 JPEG/JPEG 2000 图片时原样返回图片数据
+    ///
+    /// 需要修改像素的选项开启时，或页面不满足条件时返回 None，由调用方照常渲染。
+    fn scan_passthrough(&self, document: &PdfDocument, page_num: u32, num_pages: u32) -> Option<PageResult> {
+        let config = &self.config;
+        if !config.scan_passthrough
+            || !config.detect_scan
+            || config.size_error.is_some()
+            || config.canvas.is_some()
+            || config.bilevel.is_some()
+            || config.colors.is_some()
+            || config.normalize_background.is_some()
+            || config.denoise.is_some()
+            || config.color_management.is_some()
+            || config.image_stats
+            || config.object_diagnostics
+            || !config.also_encode.is_empty()
+            || config.highlights.contains_key(&page_num)
+            || config.stamp.is_some()
+            || config.page_formats.contains_key(&page_num)
+            || page_num < 1
+            || page_num > num_pages
+        {
+            return None;
+        }
+        let start = std::time::Instant::now();
+        let page = document.pages().get((page_num - 1) as u16).ok()?;
+        let scan = self.scan_decision(&page).ok().filter(|scan| scan.is_scan)?;
+        let image = scan_passthrough::extract(&page)?;
+        let [a, ..] = image.transform;
+        Some(PageResult {
+            width: image.width,
+            height: image.height,
+            buffer: into_js_buffer(image.data),
             success: true,
             error: None,
-            render_time,
-            encode_time,
+            error_code: None,
+            render_time: start.elapsed().as_millis() as u32,
+            format: image.format.to_string(),
+            render_info: Some(RenderInfo {
+                scale: a,
+                size_source: "passthrough".to_string(),
+                is_scan: true,
+                max_scale_applied: false,
+                scale_clamped: false,
+                dimension_clamped: false,
+                resized: false,
+                encoder_backend: "passthrough".to_string(),
+                quality: None,
+                webp_method: None,
+                dpi: Some(a * 72.0),
+                transform: Some(image.transform.to_vec()),
+                text_optimized: false,
+                memory_degraded: false,
+                encode_budget_exceeded: false,
+                color_converted_images: 0,
+                has_alpha: false,
+                object_errors: None,
+            }),
+            passthrough: true,
+            detected_scan: Some(true),
+            text_object_count: Some(scan.text_objects),
+            image_coverage: Some(scan.image_coverage),
+            downscaled_due_to_memory: false,
+            ..PageResult::failed(page_num, "", String::new(), ErrorCode::RenderFailed)
+        })
+    }
+
+    /// 页面上显示面积最大的图片的原始分辨率（缩放比例，1.0 = 72 DPI）
+    ///
+    /// 没有图片或无法读取尺寸时返回 None。
+    fn native_image_scale(page: &PdfPage) -> Option<f32> {
+        dominant_image(&page_image_list(page)).map(|image| (image.effective_dpi / 72.0) as f32)
+    }
+
+    /// 页面是否可以按纯文字页面编码：开启了 smart_encoding、未指定二值化/调色板，页面只有文字没有图片
+    ///
+    /// 编码阶段还要求输出 WebP 且渲染结果近似灰度（彩色图表、彩色文字的页面不转换）。
+    fn is_text_only(&self, page: &PdfPage, is_scan: bool) -> bool {
+        if self.config.smart_encoding == SmartEncoding::Off
+            || is_scan
+            || self.config.bilevel.is_some()
+            || self.config.colors.is_some()
+            || self.config.limits.check_page_objects(page.objects().len()).is_err()
+        {
+            return false;
+        }
+
+        let mut has_text = false;
+        for object in page.objects().iter() {
+            match object.object_type() {
+                PdfPageObjectType::Image => return false,
+                PdfPageObjectType::Text => has_text = true,
+                _ => {}
+            }
         }
+        has_text
     }
 
-    /// 检测页面是否可能是扫描件（启发式判断）
-    fn is_likely_scan(&self, page: &PdfPage) -> bool {
-        let text_objects = page.objects().iter()
-            .filter(|obj| matches!(obj.object_type(), PdfPageObjectType::Text))
-            .count();
-        
-        let image_objects = page.objects().iter()
-            .filter(|obj| matches!(obj.object_type(), PdfPageObjectType::Image))
-            .count();
-        
-        text_objects == 0 && image_objects > 0
+    /// 常驻内存超出水位时返回水位设置（确定性输出时不降级，总是返回 None）
+    fn memory_pressure(&self) -> Option<Watermark> {
+        if self.config.deterministic {
+            None
+        } else {
+            memory::pressure()
+        }
+    }
+
+    /// 内存超出水位时使用的渲染器（见 `PageEncoder::degraded`）
+    fn degraded(&self, watermark: &Watermark) -> PdfRenderer<'a> {
+        self.with_encoder(self.encoder.degraded(watermark))
+    }
+
+    /// pageFormats 为该页指定了其他格式时返回输出该格式的副本（见 `PageEncoder::with_page_format`）
+    fn with_page_format(&self, page_num: u32) -> Option<PdfRenderer<'a>> {
+        self.encoder.with_page_format(page_num).map(|encoder| self.with_encoder(encoder))
+    }
+
+    /// 换用另一份编码状态的副本，PDFium 绑定和回调不变
+    fn with_encoder(&self, encoder: PageEncoder) -> PdfRenderer<'a> {
+        PdfRenderer {
+            pdfium: self.pdfium,
+            encoder,
+            on_page: self.on_page.clone(),
+            on_preview: self.on_preview.clone(),
+        }
+    }
+}
+
+impl PageEncoder {
+    /// 换用另一份配置的副本（不带编码时间预算）
+    fn with_config(&self, config: RenderConfig) -> PageEncoder {
+        PageEncoder {
+            config,
+            created_at: self.created_at,
+            cancel: self.cancel.clone(),
+            encode_budget: None,
+        }
+    }
+
+    /// 内存超出水位时使用的编码状态：缩小渲染宽度，编码改用最快的参数
+    ///
+    /// 固定尺寸画布（canvas）是调用方要求的输出尺寸，不受影响。
+    fn degraded(&self, watermark: &Watermark) -> PageEncoder {
+        let mut config = self.config.clone();
+        config.target_width = watermark.degrade_width(config.target_width);
+        config.dpi = config.dpi.map(|dpi| dpi * watermark.width_factor);
+        config.image_heavy_width = watermark.degrade_width(config.image_heavy_width);
+        for scale in config.page_scales.values_mut() {
+            *scale = match *scale {
+                PageScale::Width(width) => PageScale::Width(watermark.degrade_width(width)),
+                PageScale::Dpi(dpi) => PageScale::Dpi(dpi * watermark.width_factor),
+            };
+        }
+        config.webp_method = 0;
+        config.webp_method_auto = false;
+        config.png_compression = config.png_compression.min(1);
+        // 纯文字页面的灰度编码使用 method 6，内存紧张时不再额外优化
+        config.smart_encoding = SmartEncoding::Off;
+        self.with_config(config)
+    }
+
+    /// pageFormats 为该页指定了其他格式时返回输出该格式的副本
+    ///
+    /// 为配置格式指定的内置编码后端不支持该格式时改用默认后端；自定义编码器收到该页的格式，仍然沿用。
+    fn with_page_format(&self, page_num: u32) -> Option<PageEncoder> {
+        let format = *self.config.page_formats.get(&page_num)?;
+        if format == self.config.format {
+            return None;
+        }
+        let mut config = self.config.clone();
+        config.format = format;
+        if custom_encoders::get(&config.encoder_backend).is_none() && encoders::resolve(format, &config.encoder_backend).is_err() {
+            config.encoder_backend = "auto".to_string();
+        }
+        Some(self.with_config(config))
+    }
+
+    /// 设置了 encodeBudgetMs 时返回带本页编码截止时间的副本
+    fn with_encode_budget(&self) -> Option<PageEncoder> {
+        let budget_ms = self.config.encode_budget_ms?;
+        Some(PageEncoder {
+            encode_budget: Some(EncodeBudget {
+                deadline: std::time::Instant::now() + std::time::Duration::from_millis(budget_ms as u64),
+                exceeded: AtomicBool::new(false),
+            }),
+            ..self.with_config(self.config.clone())
+        })
+    }
+
+    /// 本页编码超出预算时，在渲染信息中标记并改为实际使用的编码参数
+    fn budget_info(&self, info: RenderInfo) -> RenderInfo {
+        match &self.encode_budget {
+            Some(budget) if budget.exceeded.load(Ordering::Relaxed) => RenderInfo {
+                encode_budget_exceeded: true,
+                webp_method: info.webp_method.map(|_| 0),
+                quality: info.quality.map(|q| q.min(BUDGET_FALLBACK_QUALITY as u32)),
+                ..info
+            },
+            _ => info,
+        }
+    }
+
+    /// 纯文字页面使用的编码器：灰度有损用 method 6，无损改用 image 后端
+    fn text_page_encoder(&self) -> PageEncoder {
+        let mut config = self.config.clone();
+        match config.smart_encoding {
+            SmartEncoding::Lossless => config.encoder_backend = Backend::Image.as_str().to_string(),
+            _ => {
+                config.encoder_backend = Backend::Libwebp.as_str().to_string();
+                config.webp_method = 6;
+                config.webp_method_auto = false;
+            }
+        }
+        self.with_config(config)
+    }
+
+    /// 编码前的位图预处理（降噪、光照校正等）
+    fn apply_filters(&self, mut data: Vec<u8>, width: u32, height: u32, is_scan: bool) -> Vec<u8> {
+        if let (Some(mode), true) = (self.config.denoise, is_scan) {
+            data = filters::denoise(&data, width, height, mode);
+        }
+        if let Some(window) = self.config.normalize_background {
+            filters::normalize_background(&mut data, width, height, self.config.pixel_format, window);
+        }
+        data
+    }
+
+    /// 结果中的格式名：自定义编码器声明了输出格式时使用声明的格式
+    fn format_name(&self, format: OutputFormat) -> String {
+        match custom_encoders::get(&self.config.encoder_backend) {
+            Some(custom) if format == self.config.format => custom.format.clone().unwrap_or_else(|| format.as_str().to_string()),
+            _ => format.as_str().to_string(),
+        }
     }
 
     /// 根据配置的格式编码图像
     fn encode_image(&self, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
-        match self.config.format {
+        self.encode_image_as(self.config.format, rgba_data, width, height)
+    }
+
+    /// 按指定格式编码（格式可能因尺寸超限与配置不同）
+    fn encode_image_as(
+        &self,
+        format: OutputFormat,
+        rgba_data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> std::result::Result<Vec<u8>, String> {
+        // 二值化：PNG/TIFF 输出真正的 1 位图像，其他格式输出黑白像素
+        if let Some(mode) = self.config.bilevel {
+            let gray = filters::to_grayscale(rgba_data, self.config.pixel_format);
+            let bits = filters::binarize(&gray, width, height, mode);
+            return match format {
+                OutputFormat::Png => self.encode_png_bilevel(&bits, width, height),
+                OutputFormat::Tiff => {
+                    let mut writer = TiffWriter::new();
+                    writer.add_bilevel_page(&bits, width, height, 72.0);
+                    Ok(writer.finish())
+                }
+                _ => self.encode_by_format(format, &filters::bilevel_to_pixels(&bits), width, height),
+            };
+        }
+
+        // 调色板量化：PNG 直接输出索引色，其他格式使用量化后的像素
+        if let Some(colors) = self.config.colors {
+            let quantized = quantize::quantize(rgba_data, colors as usize);
+            if format == OutputFormat::Png {
+                return self.encode_png_indexed(&quantized, width, height);
+            }
+            return self.encode_by_format(format, &quantized.expand(), width, height);
+        }
+
+        self.encode_by_format(format, rgba_data, width, height)
+    }
+
+    /// 把同一张位图额外编码为 also_encode 中的格式（跳过主格式和重复项）
+    fn encode_alternates(&self, primary: OutputFormat, rgba_data: &[u8], width: u32, height: u32) -> Option<Vec<AlternateEncoding>> {
+        if self.config.also_encode.is_empty() {
+            return None;
+        }
+        let mut formats: Vec<OutputFormat> = Vec::new();
+        for format in &self.config.also_encode {
+            if *format != primary && !formats.contains(format) {
+                formats.push(*format);
+            }
+        }
+        let alternates = formats
+            .into_iter()
+            .map(|format| {
+                let start = std::time::Instant::now();
+                let encoded = self.encode_image_as(format, rgba_data, width, height);
+                let encode_time = start.elapsed().as_millis() as u32;
+                let (buffer, error) = match encoded {
+                    Ok(data) => (data, None),
+                    Err(e) => (Vec::new(), Some(e)),
+                };
+                AlternateEncoding {
+                    format: format.as_str().to_string(),
+                    buffer: into_js_buffer(buffer),
+                    encode_time,
+                    error,
+                }
+            })
+            .collect();
+        Some(alternates)
+    }
+
+    /// 按输出格式分发到具体编码器
+    fn encode_by_format(&self, format: OutputFormat, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
+        // 尺寸超限改用的格式（如 PNG 兜底）不沿用为配置格式指定的后端
+        let backend_name = if format == self.config.format { self.config.encoder_backend.as_str() } else { "auto" };
+        if let Some(custom) = custom_encoders::get(backend_name) {
+            return custom.encode(
+                backend_name,
+                EncodeInput {
+                    format,
+                    quality: match format {
+                        OutputFormat::Jpg => self.config.jpeg_quality,
+                        OutputFormat::Avif => self.config.avif_quality,
+                        _ => self.config.webp_quality,
+                    } as u32,
+                    speed: (format == OutputFormat::Avif).then_some(self.config.avif_speed as u32),
+                    pixel_format: self.config.pixel_format.as_str(),
+                    data: rgba_data,
+                    width,
+                    height,
+                },
+            );
+        }
+        let backend = encoders::resolve(format, backend_name)?;
+        match format {
+            OutputFormat::WebP if backend == Backend::Image => self.encode_webp_lossless(rgba_data, width, height),
             OutputFormat::WebP => self.encode_webp(rgba_data, width, height),
             OutputFormat::Png => self.encode_png(rgba_data, width, height),
             OutputFormat::Jpg => self.encode_jpg(rgba_data, width, height),
+            OutputFormat::Tiff => {
+                let mut writer = TiffWriter::new();
+                writer.add_rgb_page(&rgba_to_rgb(rgba_data, self.config.pixel_format, self.config.jpeg_background), width, height, 72.0);
+                Ok(writer.finish())
+            }
+            OutputFormat::Avif => encoders::encode_avif(
+                rgba_data,
+                width,
+                height,
+                self.config.avif_quality,
+                self.config.avif_speed,
+                self.config.pixel_format,
+            ),
         }
     }
 
-    /// 将 RGBA 数据编码为 WebP
+    /// 将 RGBA/BGRA 数据编码为 WebP
     fn encode_webp(&self, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
-        let img: ImageBuffer<Rgba<u8>, _> = ImageBuffer::from_raw(width, height, rgba_data.to_vec())
-            .ok_or_else(|| "Failed to create image buffer".to_string())?;
-
-        let encoder = WebpEncoder::from_rgba(img.as_raw(), width, height);
-        
         // 使用 WebPConfig 来控制编码速度和质量
         let mut config = WebPConfig::new()
             .map_err(|_| "Failed to create WebPConfig".to_string())?;
         
         // method: 0-6, 0 最快, 6 最慢但压缩最好
         // 默认值 4 是速度和压缩率的最佳平衡点
-        config.method = self.config.webp_method;
+        config.method = self.webp_method(width, height);
         config.quality = self.config.webp_quality as f32;
-        
-        let webp_data = encoder.encode_advanced(&config)
-            .map_err(|_| "WebP encoding failed".to_string())?;
 
-        Ok(webp_data.to_vec())
+        // 编码预算：超时后 libwebp 在进度回调中放弃，改用 method 0 重新编码
+        if let Some(budget) = &self.encode_budget {
+            if !budget.exceeded.load(Ordering::Relaxed) {
+                if let Some(data) = encode_webp_raw(rgba_data, width, height, &config, self.config.pixel_format, Some(budget.deadline))? {
+                    return Ok(data);
+                }
+                budget.exceeded.store(true, Ordering::Relaxed);
+            }
+            config.method = 0;
+            config.quality = config.quality.min(BUDGET_FALLBACK_QUALITY);
+        }
+
+        // 直接从位图导入（RGBA/BGRA 均可），输出写入 Vec，不经过中间副本
+        encode_webp_raw(rgba_data, width, height, &config, self.config.pixel_format, None)?
+            .ok_or_else(|| "WebP encoding failed".to_string())
+    }
+
+    /// 使用 image crate 编码无损 WebP（纯 Rust 实现，不受 quality / method 影响）
+    fn encode_webp_lossless(&self, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
+        let swapped;
+        let rgba_data = if self.config.pixel_format == PixelFormat::Bgra {
+            swapped = swap_red_blue(rgba_data);
+            &swapped[..]
+        } else {
+            rgba_data
+        };
+
+        let mut buffer = Vec::new();
+        WebPEncoder::new_lossless(&mut buffer)
+            .write_image(rgba_data, width, height, image::ExtendedColorType::Rgba8)
+            .map_err(|e| format!("WebP encoding failed: {}", e))?;
+        Ok(buffer)
     }
 
     /// 将 RGBA 数据编码为 PNG
@@ -341,6 +3806,15 @@ impl<'a> PdfRenderer<'a> {
         };
         
         let encoder = PngEncoder::new_with_quality(&mut buffer, compression, FilterType::Adaptive);
+
+        // PNG 只接受 RGBA 顺序，BGRA 输入需要交换 R/B 通道
+        let swapped;
+        let rgba_data = if self.config.pixel_format == PixelFormat::Bgra {
+            swapped = swap_red_blue(rgba_data);
+            &swapped[..]
+        } else {
+            rgba_data
+        };
         
         encoder.write_image(
             rgba_data,
@@ -352,11 +3826,99 @@ impl<'a> PdfRenderer<'a> {
         Ok(buffer)
     }
 
+    /// 将量化结果编码为调色板 PNG
+    fn encode_png_indexed(&self, quantized: &Quantized, width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
+        let mut buffer = Vec::new();
+
+        let mut palette = Vec::with_capacity(quantized.palette.len() * 3);
+        let mut trns = Vec::with_capacity(quantized.palette.len());
+        for c in &quantized.palette {
+            let (r, b) = match self.config.pixel_format {
+                PixelFormat::Rgba => (c[0], c[2]),
+                PixelFormat::Bgra => (c[2], c[0]),
+            };
+            palette.extend_from_slice(&[r, c[1], b]);
+            trns.push(c[3]);
+        }
+
+        // 调色板项数决定位深，颜色越少每像素占用越少
+        let depth = match quantized.palette.len() {
+            0..=2 => png::BitDepth::One,
+            3..=4 => png::BitDepth::Two,
+            5..=16 => png::BitDepth::Four,
+            _ => png::BitDepth::Eight,
+        };
+        let data = pack_indices(&quantized.indices, width, depth as u8);
+
+        let mut encoder = png::Encoder::new(&mut buffer, width, height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(depth);
+        encoder.set_palette(palette);
+        if trns.iter().any(|&a| a != 255) {
+            encoder.set_trns(trns);
+        }
+        encoder.set_compression(match self.config.png_compression {
+            0..=3 => png::Compression::Fast,
+            4..=6 => png::Compression::Balanced,
+            _ => png::Compression::High,
+        });
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+        writer
+            .write_image_data(&data)
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+        writer
+            .finish()
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+
+        Ok(buffer)
+    }
+
+    /// 将二值图编码为 1 位灰度 PNG
+    fn encode_png_bilevel(&self, bits: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
+        let mut buffer = Vec::new();
+
+        // PNG 灰度中 1 为白，与二值图（1 为黑）相反
+        let inverted: Vec<u8> = bits.iter().map(|&b| (b == 0) as u8).collect();
+        let data = pack_indices(&inverted, width, 1);
+
+        let mut encoder = png::Encoder::new(&mut buffer, width, height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+        encoder.set_compression(png::Compression::High);
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+        writer
+            .write_image_data(&data)
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+        writer
+            .finish()
+            .map_err(|e| format!("PNG encoding failed: {}", e))?;
+
+        Ok(buffer)
+    }
+
     /// 将 RGBA 数据编码为 JPG
+    ///
+    /// 渐进式或 4:2:0 抽样由 jpeg-encoder 处理，默认参数仍使用 image 的编码器。
     fn encode_jpg(&self, rgba_data: &[u8], width: u32, height: u32) -> std::result::Result<Vec<u8>, String> {
         // JPG 不支持 alpha 通道，需要转换为 RGB
-        let rgb_data = self.rgba_to_rgb(rgba_data);
-        
+        let rgb_data = rgba_to_rgb(rgba_data, self.config.pixel_format, self.config.jpeg_background);
+
+        if self.config.jpeg_progressive || self.config.jpeg_chroma_subsampling != ChromaSubsampling::Full {
+            let options = jpeg::JpegOptions {
+                quality: self.config.jpeg_quality,
+                progressive: self.config.jpeg_progressive,
+                subsampling: self.config.jpeg_chroma_subsampling,
+            };
+            return jpeg::encode(&rgb_data, width, height, &options)
+                .map_err(|e| format!("JPG encoding failed: {}", e));
+        }
+
         let mut buffer = Cursor::new(Vec::new());
         let mut encoder = JpegEncoder::new_with_quality(&mut buffer, self.config.jpeg_quality);
         
@@ -369,28 +3931,9 @@ impl<'a> PdfRenderer<'a> {
 
         Ok(buffer.into_inner())
     }
+}
 
-    /// 将 RGBA 数据转换为 RGB（移除 alpha 通道，与白色背景混合）
-    fn rgba_to_rgb(&self, rgba_data: &[u8]) -> Vec<u8> {
-        let pixel_count = rgba_data.len() / 4;
-        let mut rgb_data = Vec::with_capacity(pixel_count * 3);
-
-        for i in 0..pixel_count {
-            let r = rgba_data[i * 4] as f32;
-            let g = rgba_data[i * 4 + 1] as f32;
-            let b = rgba_data[i * 4 + 2] as f32;
-            let a = rgba_data[i * 4 + 3] as f32 / 255.0;
-
-            // 与白色背景混合
-            let bg = 255.0;
-            rgb_data.push((r * a + bg * (1.0 - a)) as u8);
-            rgb_data.push((g * a + bg * (1.0 - a)) as u8);
-            rgb_data.push((b * a + bg * (1.0 - a)) as u8);
-        }
-
-        rgb_data
-    }
-
+impl<'a> PdfRenderer<'a> {
     /// 渲染单页到原始位图（不进行编码）
     /// 
     /// 这个方法跳过编码步骤，直接返回 RGBA 像素数据。
@@ -399,6 +3942,27 @@ impl<'a> PdfRenderer<'a> {
         &self,
         document: &PdfDocument,
         page_num: u32,
+        source: &str,
+    ) -> RawBitmapResult {
+        let recorder = flight_recorder::begin(source, page_num);
+        let degraded = self.memory_pressure().map(|watermark| self.degraded(&watermark));
+        let mut result = degraded
+            .as_ref()
+            .unwrap_or(self)
+            .render_raw_bitmap_stages(document, page_num, &recorder);
+        if degraded.is_some() {
+            mark_degraded(&mut result.render_info);
+        }
+        let times = recorder.finish(result.error.clone());
+        result.spans = self.spans(times);
+        result
+    }
+
+    fn render_raw_bitmap_stages(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        recorder: &flight_recorder::Recorder,
     ) -> RawBitmapResult {
         let render_start = std::time::Instant::now();
         let num_pages = document.pages().len() as u32;
@@ -411,15 +3975,31 @@ impl<'a> PdfRenderer<'a> {
                 width: 0,
                 height: 0,
                 channels: 4,
+                stride: 0,
+                pixel_format: self.config.pixel_format.as_str().to_string(),
                 buffer: Buffer::from(vec![]),
                 render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: None,
+                resolved_config: None,
+                config_hash: None,
+                render_info: None,
+                error_code: if page_num > num_pages { ErrorCode::OutOfRange } else { ErrorCode::InvalidPage }.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
             };
         }
 
         // PDFium 页码从 0 开始
         let page_index = (page_num - 1) as u16;
         
-        let page = match document.pages().get(page_index) {
+        let mut page = match document.pages().get(page_index) {
             Ok(p) => p,
             Err(e) => {
                 return RawBitmapResult {
@@ -428,77 +4008,178 @@ impl<'a> PdfRenderer<'a> {
                     width: 0,
                     height: 0,
                     channels: 4,
+                    stride: 0,
+                    pixel_format: self.config.pixel_format.as_str().to_string(),
                     buffer: Buffer::from(vec![]),
                     render_time: render_start.elapsed().as_millis() as u32,
+                    correlation_id: None,
+                    resolved_config: None,
+                    config_hash: None,
+                    render_info: None,
+                    error_code: ErrorCode::RenderFailed.code(),
+                    password_index: None,
+                    image_stats: None,
+                    content_hash: None,
+                    spans: None,
+                    resource_usage: None,
+                    detected_scan: None,
+                    text_object_count: None,
+                    image_coverage: None,
+                    downscaled_due_to_memory: false,
                 };
             }
         };
 
-        // 获取页面原始尺寸（点，72 DPI）
-        let original_width = page.width().value as f32;
-        let original_height = page.height().value as f32;
-
-        // 计算缩放比例
-        let target_width = if self.config.detect_scan && self.is_likely_scan(&page) {
-            self.config.image_heavy_width as f32
+        // 扫描件识别（用于降级宽度和降噪）
+        let scan = if self.config.detect_scan || self.config.denoise.is_some() {
+            match self.scan_decision(&page) {
+                Ok(scan) => Some(scan),
+                Err(e) => {
+                    return RawBitmapResult {
+                        success: false,
+                        error: Some(e),
+                        width: 0,
+                        height: 0,
+                        channels: 4,
+                        stride: 0,
+                        pixel_format: self.config.pixel_format.as_str().to_string(),
+                        buffer: Buffer::from(vec![]),
+                        render_time: render_start.elapsed().as_millis() as u32,
+                        correlation_id: None,
+                        resolved_config: None,
+                        config_hash: None,
+                        render_info: None,
+                        error_code: ErrorCode::LimitExceeded.code(),
+                        password_index: None,
+                        image_stats: None,
+                        content_hash: None,
+                        spans: None,
+                        resource_usage: None,
+                        detected_scan: None,
+                        text_object_count: None,
+                        image_coverage: None,
+                        downscaled_due_to_memory: false,
+                    };
+                }
+            }
         } else {
-            self.config.target_width as f32
+            None
         };
-
-        let mut scale = target_width / original_width;
-        scale = scale.min(self.config.max_scale);
-
-        let mut render_width = (original_width * scale).round() as u32;
-        let mut render_height = (original_height * scale).round() as u32;
+        let is_scan = scan.is_some_and(|scan| scan.is_scan);
 
         // 尺寸限制检查（为了内存安全）
-        let max_dimension: u32 = 32767;
-
-        if render_width > max_dimension || render_height > max_dimension {
-            let width_factor = if render_width > max_dimension {
-                max_dimension as f32 / render_width as f32
-            } else {
-                1.0
+        if let Err(e) = self.check_size_options(RAW_MAX_DIMENSION) {
+            return RawBitmapResult {
+                success: false,
+                error: Some(e),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: self.config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: None,
+                resolved_config: None,
+                config_hash: None,
+                render_info: None,
+                error_code: ErrorCode::InvalidOptions.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: None,
+                text_object_count: None,
+                image_coverage: None,
+                downscaled_due_to_memory: false,
             };
-            let height_factor = if render_height > max_dimension {
-                max_dimension as f32 / render_height as f32
-            } else {
-                1.0
+        }
+        let plan = self.render_plan(&page, page_num, is_scan, RAW_MAX_DIMENSION);
+        if let Err(e) = self.check_memory_budget(&plan) {
+            return RawBitmapResult {
+                success: false,
+                error: Some(e),
+                width: 0,
+                height: 0,
+                channels: 4,
+                stride: 0,
+                pixel_format: self.config.pixel_format.as_str().to_string(),
+                buffer: Buffer::from(vec![]),
+                render_time: render_start.elapsed().as_millis() as u32,
+                correlation_id: None,
+                resolved_config: None,
+                config_hash: None,
+                render_info: None,
+                error_code: ErrorCode::LimitExceeded.code(),
+                password_index: None,
+                image_stats: None,
+                content_hash: None,
+                spans: None,
+                resource_usage: None,
+                detected_scan: scan.map(|scan| scan.is_scan),
+                text_object_count: scan.map(|scan| scan.text_objects),
+                image_coverage: scan.map(|scan| scan.image_coverage),
+                downscaled_due_to_memory: false,
             };
-            let limit_factor = width_factor.min(height_factor);
-            
-            scale *= limit_factor;
-            render_width = (original_width * scale).round() as u32;
-            render_height = (original_height * scale).round() as u32;
         }
+        let (render_width, render_height) = (plan.width, plan.height);
+        recorder.rendering(render_width, render_height);
+        self.filter_annotations(&mut page);
+        let color_converted = self.convert_colors(document, &page);
 
         // 渲染页面为 RGBA 位图
-        let bitmap = match page.render_with_config(
-            &PdfRenderConfig::new()
-                .set_target_width(render_width as i32)
-                .set_target_height(render_height as i32)
-                .render_form_data(true)
-                .render_annotations(true)
-        ) {
-            Ok(b) => b,
-            Err(e) => {
-                return RawBitmapResult {
-                    success: false,
-                    error: Some(format!("Failed to render page: {}", e)),
-                    width: 0,
-                    height: 0,
-                    channels: 4,
-                    buffer: Buffer::from(vec![]),
-                    render_time: render_start.elapsed().as_millis() as u32,
-                };
-            }
-        };
+        let (rgba_data, actual_width, actual_height) =
+            match self.render_page_overlaid(&page, page_num, (render_width, render_height), render_start) {
+                Ok(rendered) => rendered,
+                Err((code, e)) => {
+                    return RawBitmapResult {
+                        success: false,
+                        error: Some(e),
+                        width: 0,
+                        height: 0,
+                        channels: 4,
+                        stride: 0,
+                        pixel_format: self.config.pixel_format.as_str().to_string(),
+                        buffer: Buffer::from(vec![]),
+                        render_time: render_start.elapsed().as_millis() as u32,
+                        correlation_id: None,
+                        resolved_config: None,
+                        config_hash: None,
+                        render_info: None,
+                        error_code: code.code(),
+                        password_index: None,
+                        image_stats: None,
+                        content_hash: None,
+                        spans: None,
+                        resource_usage: None,
+                        detected_scan: None,
+                        text_object_count: None,
+                        image_coverage: None,
+                        downscaled_due_to_memory: false,
+                    };
+                }
+            };
 
-        let actual_width = bitmap.width() as u32;
-        let actual_height = bitmap.height() as u32;
-        
-        // 获取 RGBA 像素数据
-        let rgba_data = bitmap.as_rgba_bytes().to_vec();
+        recorder.stage(flight_recorder::Stage::Encoding);
+        let transform = self.point_transform(&page, (actual_width, actual_height), (actual_width, actual_height));
+        let object_errors = self.object_errors(&page, transform);
+        let (actual_width, actual_height, rgba_data) = match self.config.canvas {
+            Some(canvas) => (
+                canvas.width,
+                canvas.height,
+                sprite::letterbox(&rgba_data, actual_width, actual_height, &canvas, self.config.pixel_format),
+            ),
+            None => (actual_width, actual_height, rgba_data),
+        };
+        let rgba_data = self.apply_filters(rgba_data, actual_width, actual_height, is_scan);
+        let image_stats = self.image_stats(&rgba_data);
+        // 与编码路径相同，位图已包含高亮和水印
+        let content_hash = self
+            .config
+            .content_hash
+            .then(|| content_hash::bitmap_hash(&rgba_data, actual_width, actual_height));
+        let has_alpha = self.config.preserve_alpha && has_transparency(&rgba_data);
 
         RawBitmapResult {
             success: true,
@@ -506,8 +4187,704 @@ impl<'a> PdfRenderer<'a> {
             width: actual_width,
             height: actual_height,
             channels: 4,
-            buffer: Buffer::from(rgba_data),
+            stride: actual_width * 4,
+            pixel_format: self.config.pixel_format.as_str().to_string(),
+            buffer: into_js_buffer(rgba_data),
             render_time: render_start.elapsed().as_millis() as u32,
+            correlation_id: None,
+            resolved_config: None,
+            config_hash: None,
+            render_info: Some(RenderInfo {
+                color_converted_images: color_converted,
+                has_alpha,
+                object_errors,
+                ..self.plan_info(&plan, is_scan, false, transform)
+            }),
+            error_code: None,
+            password_index: None,
+            image_stats,
+            content_hash,
+            spans: None,
+            resource_usage: None,
+            detected_scan: scan.map(|scan| scan.is_scan),
+            text_object_count: scan.map(|scan| scan.text_objects),
+            image_coverage: scan.map(|scan| scan.image_coverage),
+            downscaled_due_to_memory: plan.memory_downscaled,
+        }
+    }
+
+    /// 按水平条带渲染单页原始位图，每段渲染完立即交给 `emit`，不持有整页像素
+    ///
+    /// 条带直接由 PDFium 渲染，canvas、滤镜和高亮叠加等需要整页像素的处理不生效。
+    /// `emit` 返回错误时停止渲染。
+    pub fn render_page_to_raw_chunks(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        source: &str,
+        mut emit: impl FnMut(RawChunk) -> std::result::Result<(), String>,
+    ) -> RawChunkResult {
+        let render_start = std::time::Instant::now();
+        let recorder = flight_recorder::begin(source, page_num);
+        let mut result = RawChunkResult {
+            success: false,
+            error: None,
+            error_code: None,
+            width: 0,
+            height: 0,
+            channels: 4,
+            stride: 0,
+            pixel_format: self.config.pixel_format.as_str().to_string(),
+            chunks: 0,
+            render_time: 0,
+            correlation_id: None,
+            resolved_config: None,
+            config_hash: None,
+            password_index: None,
+        };
+
+        let outcome = self.render_raw_chunk_bands(document, page_num, &recorder, &mut result, &mut emit);
+        if let Err((error, code)) = outcome {
+            result.error = Some(error);
+            result.error_code = code.code();
+        }
+        result.success = result.error.is_none();
+        result.render_time = render_start.elapsed().as_millis() as u32;
+        recorder.finish(result.error.clone());
+        result
+    }
+
+    fn render_raw_chunk_bands(
+        &self,
+        document: &PdfDocument,
+        page_num: u32,
+        recorder: &flight_recorder::Recorder,
+        result: &mut RawChunkResult,
+        emit: &mut impl FnMut(RawChunk) -> std::result::Result<(), String>,
+    ) -> std::result::Result<(), (String, ErrorCode)> {
+        let num_pages = document.pages().len() as u32;
+        if page_num < 1 || page_num > num_pages {
+            let code = if page_num > num_pages { ErrorCode::OutOfRange } else { ErrorCode::InvalidPage };
+            return Err((format!("Invalid page number: {} (total: {})", page_num, num_pages), code));
+        }
+        let mut page = document
+            .pages()
+            .get((page_num - 1) as u16)
+            .map_err(|e| (format!("Failed to get page: {}", e), ErrorCode::RenderFailed))?;
+
+        let is_scan = if self.config.detect_scan {
+            self.is_likely_scan(&page).map_err(|e| (e, ErrorCode::LimitExceeded))?
+        } else {
+            false
+        };
+        self.check_size_options(RAW_MAX_DIMENSION).map_err(|e| (e, ErrorCode::InvalidOptions))?;
+        let plan = self.render_plan(&page, page_num, is_scan, RAW_MAX_DIMENSION);
+        let (width, height) = (plan.width, plan.height);
+        result.width = width;
+        result.height = height;
+        result.stride = width * 4;
+
+        recorder.rendering(width, height);
+        self.filter_annotations(&mut page);
+        self.convert_colors(document, &page);
+
+        let flags = raw_chunks::render_flags(&self.config.advanced_flags, self.config.pixel_format == PixelFormat::Rgba);
+        let clear_color = if self.config.preserve_alpha { 0x00FF_FFFF } else { 0xFFFF_FFFF };
+        let rows = raw_chunks::chunk_rows(width, height, self.config.chunk_rows);
+        for (index, (y, band_rows)) in raw_chunks::bands(height, rows).enumerate() {
+            let pixels = raw_chunks::render_band(&page, (width, height), (y, band_rows), flags, clear_color)
+                .map_err(|e| (format!("Failed to render page: {}", e), ErrorCode::RenderFailed))?;
+            emit(RawChunk {
+                index: index as u32,
+                y,
+                rows: band_rows,
+                width,
+                height,
+                last: y + band_rows >= height,
+                pixels,
+            })
+            .map_err(|e| (e, ErrorCode::Cancelled))?;
+            result.chunks += 1;
+        }
+        Ok(())
+    }
+}
+
+/// 按顺序尝试打开文档：先不带密码，遇到密码错误再依次尝试候选密码
+///
+/// 返回文档和成功的密码下标；非密码错误立即返回，候选密码都不对时返回密码错误。
+pub fn try_passwords<'p, T>(
+    passwords: &'p [String],
+    mut load: impl FnMut(Option<&'p str>) -> std::result::Result<T, PdfiumError>,
+) -> std::result::Result<(T, Option<u32>), PdfiumError> {
+    let mut last_error = match load(None) {
+        Err(e) if is_password_error(&e) => e,
+        other => return other.map(|document| (document, None)),
+    };
+    for (index, password) in passwords.iter().enumerate() {
+        match load(Some(password)) {
+            Err(e) if is_password_error(&e) => last_error = e,
+            other => return other.map(|document| (document, Some(index as u32))),
+        }
+    }
+    Err(last_error)
+}
+
+/// 文档打开失败的错误信息
+///
+/// 密码错误（或需要密码但没有提供）以 `WRONG_PASSWORD:` 开头，其他错误为 `{context}: {error}`。
+pub fn load_error(context: &str, error: &PdfiumError) -> String {
+    if is_password_error(error) {
+        format!("{}: Incorrect password or password required", ErrorCode::WrongPassword.as_str())
+    } else {
+        format!("{}: {}", context, error)
+    }
+}
+
+/// 文档打开失败的类别
+pub fn load_error_code(error: &PdfiumError) -> ErrorCode {
+    if is_password_error(error) {
+        ErrorCode::WrongPassword
+    } else {
+        ErrorCode::LoadFailed
+    }
+}
+
+fn is_password_error(error: &PdfiumError) -> bool {
+    matches!(error, PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError))
+}
+
+/// 直接通过 libwebp 编码（BGRA 使用 BGRA 导入接口，跳过 RGBA 转换）
+///
+/// 编码输出直接追加到返回的 Vec，不经过 libwebp 的内存写入器再复制一次。
+/// 指定 `deadline` 时通过进度回调在超时后放弃编码，返回 `Ok(None)`。
+fn encode_webp_raw(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    config: &WebPConfig,
+    pixel_format: PixelFormat,
+    deadline: Option<std::time::Instant>,
+) -> std::result::Result<Option<Vec<u8>>, String> {
+    use libwebp_sys::*;
+
+    unsafe extern "C" fn before_deadline(_percent: std::ffi::c_int, picture: *const WebPPicture) -> std::ffi::c_int {
+        let deadline = &*((*picture).user_data as *const std::time::Instant);
+        (std::time::Instant::now() < *deadline) as std::ffi::c_int
+    }
+
+    unsafe extern "C" fn write_to_vec(data: *const u8, size: usize, picture: *const WebPPicture) -> std::ffi::c_int {
+        let output = &mut *((*picture).custom_ptr as *mut Vec<u8>);
+        if size > 0 {
+            output.extend_from_slice(std::slice::from_raw_parts(data, size));
+        }
+        1
+    }
+
+    let expected_len = (width as usize) * (height as usize) * 4;
+    if width == 0 || height == 0 || data.len() < expected_len {
+        return Err("Failed to create image buffer".to_string());
+    }
+
+    unsafe {
+        if WebPValidateConfig(config) == 0 {
+            return Err("WebP encoding failed: invalid config".to_string());
+        }
+
+        let mut picture = WebPPicture::new().map_err(|_| "Failed to create WebPPicture".to_string())?;
+        picture.use_argb = 1;
+        picture.width = width as i32;
+        picture.height = height as i32;
+
+        let imported = match pixel_format {
+            PixelFormat::Bgra => WebPPictureImportBGRA(&mut picture, data.as_ptr(), width as i32 * 4),
+            PixelFormat::Rgba => WebPPictureImportRGBA(&mut picture, data.as_ptr(), width as i32 * 4),
+        };
+        if imported == 0 {
+            WebPPictureFree(&mut picture);
+            return Err(format!("WebP encoding failed: {} import error", pixel_format.as_str()));
+        }
+        if let Some(deadline) = &deadline {
+            picture.progress_hook = Some(before_deadline);
+            picture.user_data = deadline as *const std::time::Instant as *mut std::ffi::c_void;
+        }
+
+        let mut output: Vec<u8> = Vec::new();
+        picture.writer = Some(write_to_vec);
+        picture.custom_ptr = &mut output as *mut Vec<u8> as *mut std::ffi::c_void;
+
+        let ok = WebPEncode(config, &mut picture);
+        let aborted = picture.error_code == WebPEncodingError::VP8_ENC_ERROR_USER_ABORT;
+        WebPPictureFree(&mut picture);
+
+        if ok != 0 {
+            Ok(Some(output))
+        } else if aborted {
+            Ok(None)
+        } else {
+            Err("WebP encoding failed".to_string())
+        }
+    }
+}
+
+/// PDFium 页面导入使用的范围字符串（从 1 开始，按给定顺序），连续递增的页码合并为 `a-b`
+fn import_range(page_nums: &[u32]) -> String {
+    let mut segments: Vec<(u32, u32)> = Vec::new();
+    for &page_num in page_nums {
+        match segments.last_mut() {
+            Some((_, end)) if *end + 1 == page_num => *end = page_num,
+            _ => segments.push((page_num, page_num)),
+        }
+    }
+    segments
+        .iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 成功、整页输出且附加编码都成功的结果才缓存
+fn is_cacheable(result: &PageResult) -> bool {
+    result.success && result.tiles.is_none() && result.alternates.iter().flatten().all(|a| a.error.is_none())
+}
+
+/// 保存到渲染结果缓存的页面输出
+fn cached_page(result: &PageResult) -> CachedPage {
+    CachedPage {
+        width: result.width,
+        height: result.height,
+        format: result.format.clone(),
+        data: result.buffer.to_vec(),
+        render_info: result.render_info.clone(),
+        alternates: result
+            .alternates
+            .as_ref()
+            .map(|alternates| alternates.iter().map(|a| (a.format.clone(), a.buffer.to_vec())).collect()),
+        image_stats: result.image_stats.clone(),
+        content_hash: result.content_hash.clone(),
+        clamped: result.clamped,
+        requested_size: result.requested_size.clone(),
+        passthrough: result.passthrough,
+        detected_scan: result.detected_scan,
+        text_object_count: result.text_object_count,
+        image_coverage: result.image_coverage,
+        downscaled_due_to_memory: result.downscaled_due_to_memory,
+    }
+}
+
+/// 由缓存的页面输出构造结果
+fn cached_result(page_num: u32, page: CachedPage) -> PageResult {
+    PageResult {
+        page_num,
+        width: page.width,
+        height: page.height,
+        buffer: into_js_buffer(page.data),
+        success: true,
+        error: None,
+        render_time: 0,
+        encode_time: 0,
+        skipped: false,
+        format: page.format,
+        tiles: None,
+        render_info: page.render_info,
+        error_code: None,
+        alternates: page.alternates.map(|alternates| {
+            alternates
+                .into_iter()
+                .map(|(format, data)| AlternateEncoding {
+                    format,
+                    buffer: into_js_buffer(data),
+                    encode_time: 0,
+                    error: None,
+                })
+                .collect()
+        }),
+        cached: true,
+        passthrough: page.passthrough,
+        detected_scan: page.detected_scan,
+        text_object_count: page.text_object_count,
+        image_coverage: page.image_coverage,
+        downscaled_due_to_memory: page.downscaled_due_to_memory,
+        image_stats: page.image_stats,
+        content_hash: page.content_hash,
+        spans: None,
+        clamped: page.clamped,
+        requested_size: page.requested_size,
+    }
+}
+
+/// 因取消而未完成的页面
+fn cancelled_page(page_num: u32, format: OutputFormat) -> PageResult {
+    PageResult {
+        skipped: true,
+        ..PageResult::failed(page_num, format.as_str(), "Cancelled".to_string(), ErrorCode::Cancelled)
+    }
+}
+
+/// 标记页面因内存水位降级渲染
+fn mark_degraded(info: &mut Option<RenderInfo>) {
+    memory::record_degraded();
+    if let Some(info) = info {
+        info.memory_degraded = true;
+    }
+}
+
+/// 位图中是否有不完全不透明的像素
+fn has_transparency(rgba_data: &[u8]) -> bool {
+    rgba_data.chunks_exact(4).any(|p| p[3] != 255)
+}
+
+/// 将 RGBA 数据转换为 RGB（移除 alpha 通道，与背景色混合）
+///
+/// BGRA 输入在这一步顺带完成通道交换，不需要额外的转换。PDFium 渲染结果
+/// 绝大多数完全不透明，此时直接丢弃 alpha 通道，不做混合计算。
+fn rgba_to_rgb(rgba_data: &[u8], pixel_format: PixelFormat, background: [u8; 3]) -> Vec<u8> {
+    let (r_idx, b_idx) = match pixel_format {
+        PixelFormat::Rgba => (0, 2),
+        PixelFormat::Bgra => (2, 0),
+    };
+    let pixels = rgba_data.chunks_exact(4);
+
+    if !has_transparency(rgba_data) {
+        return pixels.flat_map(|p| [p[r_idx], p[1], p[b_idx]]).collect();
+    }
+
+    let blend = |c: u8, a: u32, bg: u8| ((c as u32 * a + bg as u32 * (255 - a) + 127) / 255) as u8;
+    let [bg_r, bg_g, bg_b] = background;
+    pixels
+        .flat_map(|p| {
+            let a = p[3] as u32;
+            [blend(p[r_idx], a, bg_r), blend(p[1], a, bg_g), blend(p[b_idx], a, bg_b)]
+        })
+        .collect()
+}
+
+/// 把调色板索引（或 1 位灰度值）按位深打包成 PNG 扫描行（每行按字节对齐）
+fn pack_indices(indices: &[u8], width: u32, depth: u8) -> Vec<u8> {
+    if depth == 8 {
+        return indices.to_vec();
+    }
+
+    let per_byte = (8 / depth) as usize;
+    let row_bytes = (width as usize).div_ceil(per_byte);
+    let mut out = Vec::with_capacity(row_bytes * indices.len() / (width as usize).max(1));
+    for row in indices.chunks(width as usize) {
+        for chunk in row.chunks(per_byte) {
+            let mut byte = 0u8;
+            for (i, &idx) in chunk.iter().enumerate() {
+                byte |= idx << (8 - depth as usize * (i + 1));
+            }
+            out.push(byte);
         }
     }
+    out
+}
+
+/// 交换 R/B 通道（BGRA <-> RGBA）
+/// 按书籍左右页分组，返回包含 `page_nums` 中任一页的跨页（`page_nums` 为空时返回全部）
+///
+/// `cover` 为 true 时第一页单独成组：[1]、[2,3]、[4,5]…；否则 [1,2]、[3,4]…。
+/// 超出范围的页码被忽略。
+fn spread_groups(num_pages: u32, cover: bool, page_nums: &[u32]) -> Vec<Vec<u32>> {
+    let mut groups = Vec::new();
+    let mut page = 1;
+    if cover && num_pages > 0 {
+        groups.push(vec![1]);
+        page = 2;
+    }
+    while page <= num_pages {
+        groups.push((page..=(page + 1).min(num_pages)).collect());
+        page += 2;
+    }
+
+    if page_nums.is_empty() {
+        return groups;
+    }
+    groups
+        .into_iter()
+        .filter(|group| group.iter().any(|p| page_nums.contains(p)))
+        .collect()
+}
+
+/// 读取页面缩略图未解码的原始流数据（pdfium-render 只提供解码后的位图）
+/// 页面的 /Rotate 角度（0、90、180、270）
+fn rotation_degrees(page: &PdfPage) -> u32 {
+    match page.rotation() {
+        Ok(PdfPageRenderRotation::Degrees90) => 90,
+        Ok(PdfPageRenderRotation::Degrees180) => 180,
+        Ok(PdfPageRenderRotation::Degrees270) => 270,
+        _ => 0,
+    }
+}
+
+fn raw_thumbnail_data(page: &PdfPage) -> Vec<u8> {
+    let bindings = page.bindings();
+    let handle = bindings.get_handle_from_page(page);
+    let len = bindings.FPDFPage_GetRawThumbnailData(handle, std::ptr::null_mut(), 0);
+    let mut data = vec![0u8; len as usize];
+    if len > 0 {
+        bindings.FPDFPage_GetRawThumbnailData(handle, data.as_mut_ptr() as *mut _, len);
+    }
+    data
+}
+
+/// 可以原样返回的缩略图格式（其他编码方式的原始数据无法直接使用）
+pub(crate) fn passthrough_format(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.starts_with(&[0x00, 0x00, 0x00, 0x0C, b'j', b'P', b' ', b' '])
+        || data.starts_with(&[0xFF, 0x4F, 0xFF, 0x51])
+    {
+        Some("jp2")
+    } else {
+        None
+    }
+}
+
+fn swap_red_blue(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    for px in out.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+    out
+}
+
+/// 等比缩小到单边不超过 `max_dimension` 后的尺寸
+fn scaled_to_fit(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let factor = |size: u32| if size > max_dimension { max_dimension as f32 / size as f32 } else { 1.0 };
+    let limit_factor = factor(width).min(factor(height));
+    (
+        ((width as f32) * limit_factor).round() as u32,
+        ((height as f32) * limit_factor).round() as u32,
+    )
+}
+
+/// 两个矩形重叠部分的面积（平方点）
+fn overlap_area(a: &PdfRect, b: &PdfRect) -> f64 {
+    let width = a.right().value.min(b.right().value) - a.left().value.max(b.left().value);
+    let height = a.top().value.min(b.top().value) - a.bottom().value.max(b.bottom().value);
+    (width.max(0.0) as f64) * (height.max(0.0) as f64)
+}
+
+/// 页面顶层的图片对象及其放置分辨率
+///
+/// 分辨率取自 PDFium 的图片元数据：像素数除以变换矩阵在该方向上的长度（英寸），
+/// 旋转、倾斜放置的图片同样准确。表单 XObject 内部的图片不在顶层，不会列出。
+fn page_image_list(page: &PdfPage) -> Vec<PageImage> {
+    let page_area = page.width().value as f64 * page.height().value as f64;
+    page.objects()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, object)| {
+            let image = object.as_image_object()?;
+            let (pixel_width, pixel_height) = (image.width().ok()?, image.height().ok()?);
+            let (dpi_x, dpi_y) = (image.horizontal_dpi().ok()? as f64, image.vertical_dpi().ok()? as f64);
+            if pixel_width <= 0 || pixel_height <= 0 || !(dpi_x.is_finite() && dpi_y.is_finite()) {
+                return None;
+            }
+            if dpi_x.min(dpi_y) < MIN_PLACED_DPI {
+                return None;
+            }
+            let bounds = object.bounds().ok()?.to_rect();
+            let (width, height) = (bounds.width().value as f64, bounds.height().value as f64);
+            Some(PageImage {
+                index: index as u32,
+                pixel_width: pixel_width as u32,
+                pixel_height: pixel_height as u32,
+                x: bounds.left().value as f64,
+                y: bounds.bottom().value as f64,
+                width,
+                height,
+                dpi_x,
+                dpi_y,
+                effective_dpi: dpi_x.min(dpi_y),
+                coverage: if page_area > 0.0 { (width * height / page_area).min(1.0) } else { 0.0 },
+                bits_per_pixel: image.bits_per_pixel().map(u32::from).unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// 覆盖页面面积最大的图片（扫描页面即扫描图像本身）
+fn dominant_image(images: &[PageImage]) -> Option<&PageImage> {
+    images.iter().max_by(|a, b| a.coverage.total_cmp(&b.coverage))
+}
+
+/// 把编码结果或位图作为 Buffer 交给 JS，不复制数据
+///
+/// `Buffer::from(Vec<u8>)` 创建的是外部 Buffer：JS 直接引用 Vec 的内存，GC 回收 Buffer 时
+/// 才释放，在此之前整个容量（不只是数据长度）都被占用。编码器的输出 Vec 按倍数增长，
+/// 空闲容量较大时先收缩（分配器通常原地完成）。不允许外部 Buffer 的运行时（如开启
+/// V8 内存沙箱的 Electron）由 napi 退回到复制。
+pub(crate) fn into_js_buffer(mut data: Vec<u8>) -> Buffer {
+    if data.capacity() - data.len() > BUFFER_SHRINK_SLACK.max(data.len() / 8) {
+        data.shrink_to_fit();
+    }
+    Buffer::from(data)
+}
+
+/// 去掉位图每行末尾的对齐填充，返回紧密排列的像素数据
+///
+/// PDFium 的位图按 4 字节对齐分配行缓冲，行宽（stride）可能大于
+/// `width * channels`。如果已经是紧密排列的则直接返回原数据，不做拷贝。
+pub(crate) fn pack_rows(data: Vec<u8>, width: u32, height: u32, channels: u32) -> Vec<u8> {
+    let row_bytes = (width * channels) as usize;
+    if height == 0 || row_bytes == 0 {
+        return data;
+    }
+
+    let stride = data.len() / height as usize;
+    if stride <= row_bytes {
+        return data;
+    }
+
+    let mut packed = Vec::with_capacity(row_bytes * height as usize);
+    for row in data.chunks_exact(stride).take(height as usize) {
+        packed.extend_from_slice(&row[..row_bytes]);
+    }
+    packed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_rows_removes_padding() {
+        // 2x2 RGB，每行填充 2 字节到 stride = 8
+        let data = vec![1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0];
+        assert_eq!(
+            pack_rows(data, 2, 2, 3),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+    }
+
+    #[test]
+    fn test_import_range() {
+        assert_eq!(import_range(&[1, 2, 3, 5, 7, 8]), "1-3,5,7-8");
+        // 保持给定顺序，递减的页码不合并
+        assert_eq!(import_range(&[4, 3, 2, 9]), "4,3,2,9");
+        assert_eq!(import_range(&[2, 2]), "2,2");
+    }
+
+    #[test]
+    fn test_overlap_area() {
+        let rect = |left: f32, bottom: f32, right: f32, top: f32| {
+            PdfRect::new_from_values(bottom, left, top, right)
+        };
+        let page = rect(0.0, 0.0, 612.0, 792.0);
+        assert_eq!(overlap_area(&page, &page), 612.0 * 792.0);
+        // 超出页面的部分不计
+        assert_eq!(overlap_area(&page, &rect(306.0, -100.0, 700.0, 100.0)), 306.0 * 100.0);
+        assert_eq!(overlap_area(&page, &rect(700.0, 0.0, 800.0, 100.0)), 0.0);
+    }
+
+    #[test]
+    fn test_try_passwords() {
+        let passwords = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let password_error = || PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError);
+
+        // 未加密的文档不尝试候选密码
+        assert_eq!(try_passwords(&passwords, |_| Ok(1)).unwrap(), (1, None));
+
+        let mut tried = Vec::new();
+        let result = try_passwords(&passwords, |password| {
+            tried.push(password.map(str::to_string));
+            match password {
+                Some("b") => Ok(2),
+                _ => Err(password_error()),
+            }
+        });
+        assert_eq!(result.unwrap(), (2, Some(1)));
+        assert_eq!(tried, vec![None, Some("a".to_string()), Some("b".to_string())]);
+
+        assert!(matches!(try_passwords(&passwords, |_| Err::<u32, _>(password_error())), Err(e) if is_password_error(&e)));
+        // 非密码错误立即返回
+        let mut calls = 0;
+        let result = try_passwords(&passwords, |_| {
+            calls += 1;
+            Err::<u32, _>(PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::FormatError))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_pack_indices_two_bit() {
+        // 宽度 5，2 位深：每行 2 字节，末尾补零
+        let indices = [1, 2, 3, 0, 1, 3, 3, 3, 3, 3];
+        assert_eq!(
+            pack_indices(&indices, 5, 2),
+            vec![0b0110_1100, 0b0100_0000, 0b1111_1111, 0b1100_0000]
+        );
+    }
+
+    #[test]
+    fn test_spread_groups() {
+        assert_eq!(spread_groups(5, true, &[]), vec![vec![1], vec![2, 3], vec![4, 5]]);
+        assert_eq!(spread_groups(5, false, &[]), vec![vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(spread_groups(6, true, &[3, 4, 9]), vec![vec![2, 3], vec![4, 5]]);
+        assert!(spread_groups(0, true, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_passthrough_format() {
+        assert_eq!(passthrough_format(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("jpg"));
+        assert_eq!(passthrough_format(&[0xFF, 0x4F, 0xFF, 0x51, 0x00]), Some("jp2"));
+        assert_eq!(passthrough_format(&[0x78, 0x9C, 0x01]), None);
+        assert_eq!(passthrough_format(&[]), None);
+    }
+
+    #[test]
+    fn test_swap_red_blue() {
+        assert_eq!(swap_red_blue(&[1, 2, 3, 4, 5, 6, 7, 8]), vec![3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn test_advanced_flags_from_names() {
+        let flags = AdvancedFlags::from_names(&[
+            "noAnnotations".to_string(),
+            "grayscale".to_string(),
+            "unknownFlag".to_string(),
+        ]);
+        assert!(flags.no_annotations);
+        assert!(flags.grayscale);
+        assert!(!flags.no_form_data);
+        assert_eq!(AdvancedFlags::from_names(&[]), AdvancedFlags::default());
+    }
+
+    #[test]
+    fn test_form_mode_from_str() {
+        assert_eq!(FormMode::from_str("flatten"), FormMode::Flatten);
+        assert_eq!(FormMode::from_str("Omit"), FormMode::Omit);
+        assert_eq!(FormMode::from_str("render"), FormMode::Render);
+        assert_eq!(FormMode::from_str("unknown"), FormMode::Render);
+    }
+
+    #[test]
+    fn test_annotation_type_from_name() {
+        assert_eq!(annotation_type_from_name("highlight"), Some(PdfPageAnnotationType::Highlight));
+        assert_eq!(annotation_type_from_name("strikeOut"), Some(PdfPageAnnotationType::Strikeout));
+        assert_eq!(annotation_type_from_name("widget"), Some(PdfPageAnnotationType::Widget));
+        assert_eq!(annotation_type_from_name("Highlight"), None);
+        assert_eq!(annotation_type_name(PdfPageAnnotationType::Strikeout), "strikeOut");
+        assert_eq!(annotation_type_name(PdfPageAnnotationType::XfaWidget), "unknown");
+    }
+
+    #[test]
+    fn test_pack_rows_keeps_tight_data() {
+        let data = vec![0u8; 16];
+        assert_eq!(pack_rows(data.clone(), 2, 2, 4), data);
+    }
+
+    #[test]
+    fn test_rgba_to_rgb() {
+        // 不透明：只去掉 alpha（BGRA 顺带交换通道）
+        assert_eq!(rgba_to_rgb(&[1, 2, 3, 255], PixelFormat::Bgra, [0, 0, 0]), vec![3, 2, 1]);
+        // 透明像素与背景色混合
+        let data = [0, 0, 0, 0, 200, 100, 0, 255];
+        assert_eq!(rgba_to_rgb(&data, PixelFormat::Rgba, [10, 20, 30]), vec![10, 20, 30, 200, 100, 0]);
+        assert!(has_transparency(&data));
+        assert!(!has_transparency(&[1, 2, 3, 255]));
+    }
 }
@@ -0,0 +1,125 @@
+//! 扫描页面原图直出（options.scanPassthrough）
+//!
+//! 扫描件的每页通常只有一张铺满页面的 JPEG，重新栅格化再编码既慢又会二次损失画质。
+//! 页面只有这一张图片、图片没有旋转或翻转、数据以 DCT/JPX 单一过滤器存储时，
+//! 直接返回图片在 PDF 中存储的原始数据。任何一项不满足都返回 None，由调用方照常渲染。
+
+use pdfium_render::prelude::*;
+
+/// 图片外接矩形的每条边与页面（裁剪框）对应边的最大偏差，占页面宽/高的比例
+const EDGE_TOLERANCE: f32 = 0.02;
+
+/// 可以原样返回的图片编码（原始数据即完整的 JPEG / JPEG 2000 文件）
+const PASSTHROUGH_FILTERS: &[&str] = &["DCTDecode", "DCT", "JPXDecode"];
+
+/// 原样取出的页面图片
+pub struct ExtractedImage {
+    /// 图片在 PDF 中存储的原始数据
+    pub data: Vec<u8>,
+    /// 输出格式（jpg 或 jp2）
+    pub format: &'static str,
+    /// 图片像素尺寸
+    pub width: u32,
+    pub height: u32,
+    /// PDF 坐标（点）到图片像素的仿射变换 `[a, b, c, d, e, f]`
+    pub transform: [f64; 6],
+}
+
+/// 页面只有一张铺满页面的 JPEG / JPEG 2000 图片时取出其原始数据
+pub fn extract(page: &PdfPage) -> Option<ExtractedImage> {
+    if page.rotation().ok()? != PdfPageRenderRotation::None || !page.annotations().is_empty() {
+        return None;
+    }
+    let objects = page.objects();
+    if objects.len() != 1 {
+        return None;
+    }
+    let object = objects.get(0).ok()?;
+    let image = object.as_image_object()?;
+
+    // 旋转、倾斜或翻转的图片与页面方向不一致
+    let matrix = object.matrix().ok()?;
+    if matrix.b() != 0.0 || matrix.c() != 0.0 || matrix.a() <= 0.0 || matrix.d() <= 0.0 {
+        return None;
+    }
+
+    let page_bounds = page
+        .boundaries()
+        .crop()
+        .or_else(|_| page.boundaries().media())
+        .ok()?
+        .bounds;
+    let image_bounds = object.bounds().ok()?.to_rect();
+    if !covers_page(edges(&page_bounds), edges(&image_bounds)) {
+        return None;
+    }
+
+    // 多个过滤器时原始数据不是图片文件本身；CMYK、专色等色彩空间的 JPEG 由浏览器解码时颜色不对
+    let filters: Vec<String> = image.filters().iter().map(|filter| filter.name().to_string()).collect();
+    if filters.len() != 1 || !PASSTHROUGH_FILTERS.contains(&filters[0].as_str()) {
+        return None;
+    }
+    if !matches!(
+        image.color_space().ok()?,
+        PdfColorSpace::Unknown
+            | PdfColorSpace::DeviceGray
+            | PdfColorSpace::DeviceRGB
+            | PdfColorSpace::CalibratedCIEGray
+            | PdfColorSpace::CalibratedCIERGB
+            | PdfColorSpace::CalibratedICCProfile
+    ) {
+        return None;
+    }
+
+    let data = image.get_raw_image_data().ok()?;
+    let format = crate::renderer::passthrough_format(&data)?;
+    let (width, height) = (image.width().ok()? as u32, image.height().ok()? as u32);
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let (left, _, _, top) = edges(&image_bounds);
+    let sx = width as f64 / image_bounds.width().value as f64;
+    let sy = height as f64 / image_bounds.height().value as f64;
+    Some(ExtractedImage {
+        data,
+        format,
+        width,
+        height,
+        transform: [sx, 0.0, 0.0, -sy, -(left as f64) * sx, top as f64 * sy],
+    })
+}
+
+/// 矩形的 (左, 下, 右, 上)
+fn edges(rect: &PdfRect) -> (f32, f32, f32, f32) {
+    (rect.left().value, rect.bottom().value, rect.right().value, rect.top().value)
+}
+
+/// 图片的四条边是否都与页面对应的边对齐（允许 EDGE_TOLERANCE 的偏差）
+fn covers_page(page: (f32, f32, f32, f32), image: (f32, f32, f32, f32)) -> bool {
+    let (width, height) = (page.2 - page.0, page.3 - page.1);
+    if width <= 0.0 || height <= 0.0 {
+        return false;
+    }
+    let (dx, dy) = (width * EDGE_TOLERANCE, height * EDGE_TOLERANCE);
+    (page.0 - image.0).abs() <= dx
+        && (page.2 - image.2).abs() <= dx
+        && (page.1 - image.1).abs() <= dy
+        && (page.3 - image.3).abs() <= dy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covers_page() {
+        let page = (0.0, 0.0, 612.0, 792.0);
+        assert!(covers_page(page, page));
+        assert!(covers_page(page, (-2.0, 1.0, 614.0, 790.0)));
+        // 只占半页，或明显超出裁剪框
+        assert!(!covers_page(page, (0.0, 396.0, 612.0, 792.0)));
+        assert!(!covers_page(page, (-100.0, 0.0, 712.0, 792.0)));
+        assert!(!covers_page((0.0, 0.0, 0.0, 792.0), page));
+    }
+}
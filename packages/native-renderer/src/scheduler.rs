@@ -0,0 +1,569 @@
+//! 跨文档的优先级渲染调度
+//!
+//! 调用方把（文档、页码、优先级）任务放入同一个队列，工作线程按优先级（相同优先级按
+//! 入队顺序）取任务渲染，结果通过回调逐页返回。PDFium 实例持有进程级的锁，页面的渲染和
+//! 编码都在持有期间完成，多个工作线程只会轮流等锁，因此只有一个工作线程。
+//! 每次绑定 PDFium 最多连续处理 `JOBS_PER_BINDING` 个任务，期间保留最近打开的文档，
+//! 同一文档的连续任务不需要重新加载；之后连同 PDFium 一起释放，队列很长时其他渲染调用
+//! 也能在两批任务之间拿到 PDFium。
+//!
+//! 任务可以用 tenant 选项标记租户，每个租户一个队列。取任务时先按租户限额筛选：
+//! 达到并发上限的租户暂不调度；统计窗口内 CPU 时间超出预算的租户只在其他租户都没有
+//! 可运行任务时才调度（工作线程不会因此空闲）。其余租户之间按优先级、正在渲染的页数、
+//! 窗口内已用 CPU 时间、入队顺序依次比较，一个租户大量入队不会让其他租户一直等待。
+
+use crate::config::RenderConfig;
+use crate::error::ErrorCode;
+use crate::lifecycle::TaskGuard;
+use crate::usage;
+use crate::renderer::{load_error, load_error_code, try_passwords, PdfRenderer};
+use crate::{PageResult, ScheduledPageResult};
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use pdfium_render::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// 每次绑定 PDFium 最多连续渲染的任务数
+const JOBS_PER_BINDING: usize = 8;
+
+/// 任务的文档来源（同一次入队的页面共享）
+pub enum DocumentSource {
+    File(String),
+    Bytes(Vec<u8>),
+}
+
+/// 一次入队的文档及其渲染配置
+pub struct ScheduledDocument {
+    pub id: u32,
+    pub source: DocumentSource,
+    pub config: RenderConfig,
+    /// 租户标记（未指定时所有任务属于同一个默认租户）
+    pub tenant: Option<String>,
+}
+
+/// 单个租户的限额
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TenantQuota {
+    /// 同时渲染的最大页数
+    pub concurrency: Option<u32>,
+    /// 统计窗口内可用的 CPU 时间
+    pub cpu_time: Option<Duration>,
+}
+
+/// 调度器的租户限额配置
+#[derive(Debug, Clone)]
+pub struct TenantLimits {
+    /// 所有租户的默认限额
+    pub default: TenantQuota,
+    /// 按租户覆盖，未设置的字段使用默认限额
+    pub overrides: HashMap<String, TenantQuota>,
+    /// CPU 时间的统计窗口
+    pub window: Duration,
+}
+
+impl Default for TenantLimits {
+    fn default() -> Self {
+        Self {
+            default: TenantQuota::default(),
+            overrides: HashMap::new(),
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+impl TenantLimits {
+    fn quota(&self, tenant: Option<&str>) -> TenantQuota {
+        match tenant.and_then(|tenant| self.overrides.get(tenant)) {
+            Some(quota) => TenantQuota {
+                concurrency: quota.concurrency.or(self.default.concurrency),
+                cpu_time: quota.cpu_time.or(self.default.cpu_time),
+            },
+            None => self.default,
+        }
+    }
+}
+
+/// 单页任务
+pub struct Job {
+    pub id: u32,
+    pub page_num: u32,
+    pub priority: i32,
+    pub document: Arc<ScheduledDocument>,
+    seq: u64,
+    enqueued_at: Instant,
+    /// 任务结束（渲染完成或取消）前计入模块生命周期，shutdown 会等待它们
+    _guard: TaskGuard,
+}
+
+impl PartialEq for Job {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Job {}
+
+impl PartialOrd for Job {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Job {
+    /// 优先级高的在前，相同优先级先入队的在前
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// 单个租户的队列和用量
+struct TenantQueue {
+    jobs: BinaryHeap<Job>,
+    running: u32,
+    /// 当前统计窗口的开始时间和窗口内已用的 CPU 时间
+    window_start: Instant,
+    cpu_used: Duration,
+}
+
+impl TenantQueue {
+    fn new(now: Instant) -> Self {
+        Self {
+            jobs: BinaryHeap::new(),
+            running: 0,
+            window_start: now,
+            cpu_used: Duration::ZERO,
+        }
+    }
+
+    /// 当前窗口内已用的 CPU 时间（窗口已过期时为 0）
+    fn cpu_used(&self, now: Instant, window: Duration) -> Duration {
+        if now.duration_since(self.window_start) >= window {
+            Duration::ZERO
+        } else {
+            self.cpu_used
+        }
+    }
+
+    fn charge(&mut self, cpu_time: Duration, now: Instant, window: Duration) {
+        if now.duration_since(self.window_start) >= window {
+            self.window_start = now;
+            self.cpu_used = Duration::ZERO;
+        }
+        self.cpu_used += cpu_time;
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    tenants: HashMap<Option<String>, TenantQueue>,
+    next_seq: u64,
+    closed: bool,
+    running: u32,
+    completed: u64,
+}
+
+impl QueueState {
+    fn queued(&self) -> usize {
+        self.tenants.values().map(|queue| queue.jobs.len()).sum()
+    }
+
+    /// 按租户限额选出下一个任务所属的租户
+    fn select(&self, limits: &TenantLimits, now: Instant) -> Option<&Option<String>> {
+        self.tenants
+            .iter()
+            .filter_map(|(tenant, queue)| {
+                let head = queue.jobs.peek()?;
+                let quota = limits.quota(tenant.as_deref());
+                if quota.concurrency.is_some_and(|limit| queue.running >= limit) {
+                    return None;
+                }
+                let cpu_used = queue.cpu_used(now, limits.window);
+                let within_budget = quota.cpu_time.is_none_or(|budget| cpu_used < budget);
+                let rank = (within_budget, head.priority, Reverse(queue.running), Reverse(cpu_used), Reverse(head.seq));
+                Some((rank, tenant))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, tenant)| tenant)
+    }
+
+    /// 取出下一个任务，并计入该租户正在渲染的页数
+    fn pop_next(&mut self, limits: &TenantLimits, now: Instant) -> Option<Job> {
+        let tenant = self.select(limits, now)?.clone();
+        let queue = self.tenants.get_mut(&tenant)?;
+        queue.running += 1;
+        self.running += 1;
+        queue.jobs.pop()
+    }
+
+    /// 任务结束：记入租户用量；没有排队和运行中的任务、且用量已不影响调度的租户被移除
+    fn finish(&mut self, tenant: &Option<String>, cpu_time: Duration, limits: &TenantLimits, now: Instant) {
+        self.running -= 1;
+        self.completed += 1;
+        if let Some(queue) = self.tenants.get_mut(tenant) {
+            queue.running -= 1;
+            queue.charge(cpu_time, now, limits.window);
+            let budgeted = limits.quota(tenant.as_deref()).cpu_time.is_some();
+            if queue.jobs.is_empty() && queue.running == 0 && !budgeted {
+                self.tenants.remove(tenant);
+            }
+        }
+    }
+
+    /// 窗口已过期的空闲租户不再需要保留
+    fn prune(&mut self, now: Instant, window: Duration) {
+        self.tenants.retain(|_, queue| {
+            !queue.jobs.is_empty() || queue.running > 0 || queue.cpu_used(now, window) > Duration::ZERO
+        });
+    }
+}
+
+/// 单个租户的统计
+pub struct TenantCounts {
+    pub tenant: Option<String>,
+    pub queued: u32,
+    pub running: u32,
+    /// 当前统计窗口内已用的 CPU 时间
+    pub cpu_time: Duration,
+    /// 是否超出 CPU 时间预算
+    pub over_budget: bool,
+}
+
+/// 调度器统计
+pub struct SchedulerCounts {
+    pub queued: u32,
+    pub running: u32,
+    pub completed: u64,
+    pub tenants: Vec<TenantCounts>,
+}
+
+pub struct Scheduler {
+    state: Mutex<QueueState>,
+    ready: Condvar,
+    callback: ThreadsafeFunction<ScheduledPageResult, ErrorStrategy::CalleeHandled>,
+    limits: TenantLimits,
+}
+
+impl Scheduler {
+    /// 创建调度器并启动工作线程
+    pub fn start(
+        limits: TenantLimits,
+        callback: ThreadsafeFunction<ScheduledPageResult, ErrorStrategy::CalleeHandled>,
+    ) -> Arc<Self> {
+        let scheduler = Arc::new(Self {
+            state: Mutex::new(QueueState::default()),
+            ready: Condvar::new(),
+            callback,
+            limits,
+        });
+        let worker = Arc::clone(&scheduler);
+        std::thread::spawn(move || worker.worker_loop());
+        scheduler
+    }
+
+    /// 入队；调度器已关闭时返回错误
+    pub fn enqueue(
+        &self,
+        document: &Arc<ScheduledDocument>,
+        pages: Vec<(u32, u32, TaskGuard)>,
+        priority: i32,
+    ) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err("Scheduler is closed".to_string());
+        }
+        let enqueued_at = Instant::now();
+        state.prune(enqueued_at, self.limits.window);
+        for (id, page_num, guard) in pages {
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            let queue = state
+                .tenants
+                .entry(document.tenant.clone())
+                .or_insert_with(|| TenantQueue::new(enqueued_at));
+            queue.jobs.push(Job {
+                id,
+                page_num,
+                priority,
+                document: Arc::clone(document),
+                seq,
+                enqueued_at,
+                _guard: guard,
+            });
+        }
+        drop(state);
+        self.ready.notify_all();
+        Ok(())
+    }
+
+    /// 停止接收新任务；`cancel_pending` 为 true 时排队中的任务立即以 CANCELLED 返回，
+    /// 否则继续处理完队列。返回取消的任务数
+    pub fn close(&self, cancel_pending: bool, reason: &str) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        let cancelled: Vec<Job> = if cancel_pending {
+            state
+                .tenants
+                .values_mut()
+                .flat_map(|queue| std::mem::take(&mut queue.jobs).into_vec())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        drop(state);
+        self.ready.notify_all();
+
+        let count = cancelled.len() as u32;
+        for job in cancelled {
+            let page = failed_page(&job, reason.to_string(), ErrorCode::Cancelled);
+            self.deliver(&job, page);
+        }
+        count
+    }
+
+    pub fn counts(&self) -> SchedulerCounts {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let mut tenants: Vec<TenantCounts> = state
+            .tenants
+            .iter()
+            .map(|(tenant, queue)| {
+                let cpu_time = queue.cpu_used(now, self.limits.window);
+                let budget = self.limits.quota(tenant.as_deref()).cpu_time;
+                TenantCounts {
+                    tenant: tenant.clone(),
+                    queued: queue.jobs.len() as u32,
+                    running: queue.running,
+                    cpu_time,
+                    over_budget: budget.is_some_and(|budget| cpu_time >= budget),
+                }
+            })
+            .collect();
+        tenants.sort_by(|a, b| a.tenant.cmp(&b.tenant));
+        SchedulerCounts {
+            queued: state.queued() as u32,
+            running: state.running,
+            completed: state.completed,
+            tenants,
+        }
+    }
+
+    /// 取下一个可运行的任务，不等待
+    fn try_next_job(&self) -> Option<Job> {
+        self.state.lock().unwrap().pop_next(&self.limits, Instant::now())
+    }
+
+    /// 等到有可运行的任务（不取出）；已关闭且队列为空时返回 false
+    fn wait_for_job(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.select(&self.limits, Instant::now()).is_some() {
+                return true;
+            }
+            if state.closed && state.queued() == 0 {
+                return false;
+            }
+            state = self.ready.wait(state).unwrap();
+        }
+    }
+
+    fn worker_loop(&self) {
+        // PDFium 实例持有进程级的锁：有可运行的任务时才绑定，处理完一批任务后连同打开的
+        // 文档一起释放，等待新任务期间不占用 PDFium。
+        // 绑定成功后才取任务：等锁期间不占用任务，任务在真正开始渲染时才按优先级和租户限额选出。
+        // PDFium 无法加载时当前任务失败，下一个任务前重新绑定，库恢复后无需重建调度器
+        while self.wait_for_job() {
+            match crate::create_pdfium() {
+                Ok(pdfium) => {
+                    self.render_ready_jobs(&pdfium);
+                    drop(pdfium);
+                    // 让等锁的其他渲染调用先拿到 PDFium，再绑定下一批
+                    std::thread::yield_now();
+                }
+                Err(e) => {
+                    if let Some(job) = self.try_next_job() {
+                        self.finish_job(&job, failed_page(&job, e.reason, ErrorCode::PdfiumUnavailable), Duration::ZERO);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 渲染已就绪的任务，最多 `JOBS_PER_BINDING` 个，没有可运行的任务时提前返回
+    /// （调用方随后释放 PDFium）
+    fn render_ready_jobs(&self, pdfium: &Pdfium) {
+        // 最近打开的文档（文档 ID，文档）
+        let mut cached: Option<(u32, PdfDocument)> = None;
+        for _ in 0..JOBS_PER_BINDING {
+            let Some(job) = self.try_next_job() else { break };
+            let started = (Instant::now(), usage::thread_cpu_micros());
+            let page = render_job(pdfium, &mut cached, &job);
+            self.finish_job(&job, page, cpu_time_since(started));
+        }
+    }
+
+    fn finish_job(&self, job: &Job, page: PageResult, cpu_time: Duration) {
+        self.deliver(job, page);
+        let mut state = self.state.lock().unwrap();
+        state.finish(&job.document.tenant, cpu_time, &self.limits, Instant::now());
+        drop(state);
+        // 达到并发上限的租户空出名额，唤醒等待中的工作线程
+        self.ready.notify_one();
+    }
+
+    fn deliver(&self, job: &Job, page: PageResult) {
+        let result = ScheduledPageResult {
+            job_id: job.id,
+            document_id: job.document.id,
+            priority: job.priority,
+            tenant: job.document.tenant.clone(),
+            queue_time: (job.enqueued_at.elapsed().as_millis() as u32)
+                .saturating_sub(page.render_time + page.encode_time),
+            page,
+        };
+        self.callback.call(Ok(result), ThreadsafeFunctionCallMode::NonBlocking);
+    }
+}
+
+/// 任务消耗的 CPU 时间；无法读取线程 CPU 时间的平台按耗时计
+fn cpu_time_since((started, cpu_start): (Instant, Option<(u64, u64)>)) -> Duration {
+    match (cpu_start, usage::thread_cpu_micros()) {
+        (Some((user_start, system_start)), Some((user, system))) => {
+            Duration::from_micros(user.saturating_sub(user_start) + system.saturating_sub(system_start))
+        }
+        _ => started.elapsed(),
+    }
+}
+
+fn render_job<'a>(pdfium: &'a Pdfium, cached: &mut Option<(u32, PdfDocument<'a>)>, job: &Job) -> PageResult {
+    let document = &job.document;
+    if cached.as_ref().map(|(id, _)| *id) != Some(document.id) {
+        *cached = None;
+        let passwords = &document.config.passwords;
+        let loaded = match &document.source {
+            // 从文件加载时密码须与文档同生命周期，带密码的尝试改为读入内存后加载
+            DocumentSource::File(path) => try_passwords(passwords, |password| match password {
+                None => pdfium.load_pdf_from_file(path, None),
+                Some(password) => {
+                    let bytes = std::fs::read(path).map_err(PdfiumError::IoError)?;
+                    pdfium.load_pdf_from_byte_vec(bytes, Some(password))
+                }
+            }),
+            DocumentSource::Bytes(bytes) => {
+                try_passwords(passwords, |password| pdfium.load_pdf_from_byte_vec(bytes.clone(), password))
+            }
+        };
+        match loaded {
+            Ok((loaded, _)) => *cached = Some((document.id, loaded)),
+            Err(e) => return failed_page(job, load_error("Failed to load PDF", &e), load_error_code(&e)),
+        }
+    }
+    let (_, pdf) = cached.as_ref().unwrap();
+
+    let renderer = PdfRenderer::new(pdfium, document.config.clone());
+    let source = format!("scheduler:{}", document.id);
+    match renderer.render_document_pages(pdf, &[job.page_num], &source) {
+        Ok((_, mut pages)) => pages.pop().unwrap_or_else(|| {
+            failed_page(job, "No result".to_string(), ErrorCode::RenderFailed)
+        }),
+        // 整个调用级别的错误：页数限制，或 outOfRangePages 为 fail 时的页码越界
+        Err(e) => {
+            let code = if e.starts_with("Page out of range") { ErrorCode::OutOfRange } else { ErrorCode::LimitExceeded };
+            failed_page(job, e, code)
+        }
+    }
+}
+
+fn failed_page(job: &Job, error: String, code: ErrorCode) -> PageResult {
+    PageResult::failed(job.page_num, job.document.config.format.as_str(), error, code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifecycle::Lifecycle;
+
+    #[test]
+    fn test_job_order() {
+        let lifecycle = Lifecycle::default();
+        let document = Arc::new(ScheduledDocument {
+            id: 1,
+            source: DocumentSource::Bytes(vec![]),
+            config: RenderConfig::default(),
+            tenant: None,
+        });
+        let mut jobs = BinaryHeap::new();
+        for (seq, priority) in [0, 5, 0, 5].into_iter().enumerate() {
+            jobs.push(Job {
+                id: seq as u32,
+                page_num: 1,
+                priority,
+                document: Arc::clone(&document),
+                seq: seq as u64,
+                enqueued_at: Instant::now(),
+                _guard: lifecycle.begin_task().unwrap(),
+            });
+        }
+        let order: Vec<u32> = std::iter::from_fn(|| jobs.pop().map(|job| job.id)).collect();
+        assert_eq!(order, vec![1, 3, 0, 2]);
+        assert_eq!(lifecycle.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_tenant_selection() {
+        let lifecycle = Lifecycle::default();
+        let now = Instant::now();
+        let mut state = QueueState::default();
+        let push = |state: &mut QueueState, tenant: &str, priority: i32| {
+            let document = Arc::new(ScheduledDocument {
+                id: 1,
+                source: DocumentSource::Bytes(vec![]),
+                config: RenderConfig::default(),
+                tenant: Some(tenant.to_string()),
+            });
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            let queue = state.tenants.entry(document.tenant.clone()).or_insert_with(|| TenantQueue::new(now));
+            queue.jobs.push(Job {
+                id: seq as u32,
+                page_num: 1,
+                priority,
+                document,
+                seq,
+                enqueued_at: now,
+                _guard: lifecycle.begin_task().unwrap(),
+            });
+        };
+        for _ in 0..3 {
+            push(&mut state, "bulk", 0);
+        }
+        push(&mut state, "small", 0);
+
+        let mut limits = TenantLimits::default();
+        limits.default.concurrency = Some(1);
+        let first = state.pop_next(&limits, now).unwrap();
+        assert_eq!(first.id, 0);
+        // bulk 已达并发上限，轮到 small
+        let second = state.pop_next(&limits, now).unwrap();
+        assert_eq!(second.id, 3);
+        assert!(state.pop_next(&limits, now).is_none());
+        state.finish(&second.document.tenant, Duration::ZERO, &limits, now);
+
+        // bulk 超出 CPU 预算后，即使优先级更高也排在其他租户之后
+        limits.overrides.insert(
+            "bulk".to_string(),
+            TenantQuota { concurrency: Some(2), cpu_time: Some(Duration::from_millis(10)) },
+        );
+        state.finish(&first.document.tenant, Duration::from_millis(20), &limits, now);
+        push(&mut state, "small", -1);
+        assert_eq!(state.pop_next(&limits, now).unwrap().id, 4);
+        // 没有其他可运行的任务时照常调度
+        assert_eq!(state.pop_next(&limits, now).unwrap().id, 1);
+        // 窗口过期后预算恢复
+        let later = now + limits.window;
+        assert_eq!(state.tenants[&Some("bulk".to_string())].cpu_used(later, limits.window), Duration::ZERO);
+    }
+}
@@ -0,0 +1,339 @@
+//! 可暂停、可取消的批量渲染会话
+//!
+//! 用户发起的"渲染整份文档"任务经常中途放弃。会话在专属线程中按顺序逐页渲染，
+//! 结果暂存在原生侧，由调用方轮询取走（或通过回调逐页接收）；渲染过程中可以
+//! 随时暂停、恢复，或取消剩余页面，不必等整批完成或丢弃已经渲染好的页面。
+//! 暂停期间会话线程释放 PDFium（实例存活期间持有进程级的锁）和文档，恢复后重新打开。
+
+use crate::config::RenderConfig;
+use crate::error::ErrorCode;
+use crate::lifecycle::TaskGuard;
+use crate::pinned::Reopen;
+use crate::renderer::{load_error, try_passwords, PdfRenderer};
+use crate::scheduler::DocumentSource;
+use crate::PageResult;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// 会话状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionStatus {
+    /// 正在渲染（包括加载文档阶段）
+    Running,
+    /// 已暂停，当前页完成后不再开始新页面，并释放 PDFium 和文档直到恢复
+    Paused,
+    /// 所有页面已渲染完成
+    Completed,
+    /// 剩余页面已取消
+    Cancelled,
+    /// 文档加载失败
+    Failed,
+}
+
+impl SessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Running => "running",
+            SessionStatus::Paused => "paused",
+            SessionStatus::Completed => "completed",
+            SessionStatus::Cancelled => "cancelled",
+            SessionStatus::Failed => "failed",
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, SessionStatus::Completed | SessionStatus::Cancelled | SessionStatus::Failed)
+    }
+}
+
+struct SessionState {
+    status: SessionStatus,
+    /// 尚未开始渲染的页码；页码列表为空时在文档加载后填入全部页面
+    pending: VecDeque<u32>,
+    /// 已完成、尚未被轮询取走的页面（使用回调时不暂存）
+    ready: VecDeque<PageResult>,
+    /// 本次会话要渲染的页数
+    total: u32,
+    completed: u32,
+    cancelled: u32,
+    num_pages: u32,
+    error: Option<String>,
+    /// 渲染线程是否已退出（取消后正在渲染的一页仍会完成）
+    finished: bool,
+}
+
+/// 会话状态快照
+pub struct SessionSnapshot {
+    pub status: SessionStatus,
+    pub total: u32,
+    pub completed: u32,
+    pub pending: u32,
+    pub cancelled: u32,
+    pub ready: u32,
+    pub num_pages: u32,
+    pub error: Option<String>,
+    /// 渲染线程已退出且所有结果都已取走，之后不会再有新结果
+    pub done: bool,
+}
+
+pub struct RenderSession {
+    state: Mutex<SessionState>,
+    resumed: Condvar,
+    /// 逐页回调；渲染线程退出时释放，避免阻止进程退出
+    callback: Mutex<Option<ThreadsafeFunction<PageResult, ErrorStrategy::CalleeHandled>>>,
+}
+
+impl RenderSession {
+    /// 创建会话并启动渲染线程；`page_nums` 为空表示全部页面
+    pub fn start(
+        source: DocumentSource,
+        page_nums: Vec<u32>,
+        config: RenderConfig,
+        callback: Option<ThreadsafeFunction<PageResult, ErrorStrategy::CalleeHandled>>,
+        guard: TaskGuard,
+    ) -> Arc<Self> {
+        let session = Arc::new(Self::new(page_nums, callback));
+        let worker = Arc::clone(&session);
+        std::thread::spawn(move || {
+            worker.run(source, config);
+            worker.finish();
+            drop(guard);
+        });
+        session
+    }
+
+    fn new(page_nums: Vec<u32>, callback: Option<ThreadsafeFunction<PageResult, ErrorStrategy::CalleeHandled>>) -> Self {
+        Self {
+            state: Mutex::new(SessionState::new(page_nums)),
+            resumed: Condvar::new(),
+            callback: Mutex::new(callback),
+        }
+    }
+
+    /// 暂停；只有正在渲染的会话可以暂停，返回状态是否改变
+    pub fn pause(&self) -> bool {
+        self.transition(SessionStatus::Running, SessionStatus::Paused)
+    }
+
+    /// 恢复暂停的会话，返回状态是否改变
+    pub fn resume(&self) -> bool {
+        self.transition(SessionStatus::Paused, SessionStatus::Running)
+    }
+
+    /// 取消剩余页面，返回取消的页数；正在渲染的一页仍会完成并返回
+    pub fn cancel(&self) -> u32 {
+        let count = self.state.lock().unwrap().cancel();
+        self.resumed.notify_all();
+        count
+    }
+
+    /// 取走最多 `max` 个已完成的页面
+    pub fn poll(&self, max: usize) -> (Vec<PageResult>, SessionSnapshot) {
+        let mut state = self.state.lock().unwrap();
+        let count = max.min(state.ready.len());
+        let pages = state.ready.drain(..count).collect();
+        (pages, state.snapshot())
+    }
+
+    pub fn snapshot(&self) -> SessionSnapshot {
+        self.state.lock().unwrap().snapshot()
+    }
+
+    fn transition(&self, from: SessionStatus, to: SessionStatus) -> bool {
+        let changed = self.state.lock().unwrap().transition(from, to);
+        self.resumed.notify_all();
+        changed
+    }
+
+    /// 取下一页；暂停、取消或全部完成时返回 None
+    fn next_page(&self) -> Option<u32> {
+        self.state.lock().unwrap().next_page()
+    }
+
+    /// 暂停时等到恢复或取消；恢复后还有页面要渲染时返回 true
+    fn wait_resumed(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while state.status == SessionStatus::Paused {
+            state = self.resumed.wait(state).unwrap();
+        }
+        state.status == SessionStatus::Running && !state.pending.is_empty()
+    }
+
+    fn run(&self, source: DocumentSource, config: RenderConfig) {
+        let fingerprint = source.fingerprint();
+        // 首次打开时依次尝试候选密码，之后直接使用成功的密码
+        let mut password = None;
+        loop {
+            if let Err(e) = self.render_until_paused(&source, &config, &mut password, &fingerprint) {
+                return self.fail(e);
+            }
+            // 暂停期间不持有 PDFium 和文档，恢复后重新打开
+            if !self.wait_resumed() {
+                return;
+            }
+        }
+    }
+
+    /// 绑定 PDFium、打开文档并逐页渲染，直到暂停、取消或全部完成
+    fn render_until_paused(
+        &self,
+        source: &DocumentSource,
+        config: &RenderConfig,
+        password: &mut Option<Option<String>>,
+        fingerprint: &str,
+    ) -> Result<(), String> {
+        let pdfium = crate::create_pdfium().map_err(|e| e.reason)?;
+        let document = match password {
+            Some(password) => source
+                .load(&pdfium, password.as_deref())
+                .map_err(|e| load_error(source.load_context(), &e))?,
+            None => {
+                let (document, password_index) = try_passwords(&config.passwords, |password| source.load(&pdfium, password))
+                    .map_err(|e| load_error(source.load_context(), &e))?;
+                *password = Some(password_index.map(|index| config.passwords[index as usize].clone()));
+                self.loaded(document.pages().len() as u32, config.hero_page);
+                document
+            }
+        };
+
+        let format = config.format;
+        let renderer = PdfRenderer::new(&pdfium, config.clone());
+        while let Some(page_num) = self.next_page() {
+            let page = match renderer.render_document_pages(&document, &[page_num], fingerprint) {
+                Ok((_, mut pages)) => pages.pop(),
+                Err(e) => {
+                    let code = if e.starts_with("Page out of range") { ErrorCode::OutOfRange } else { ErrorCode::LimitExceeded };
+                    Some(PageResult::failed(page_num, format.as_str(), e, code))
+                }
+            };
+            if let Some(page) = page {
+                self.deliver(page);
+            }
+        }
+        Ok(())
+    }
+
+    /// 文档首次打开后记录页数；页码列表为空时填入全部页面
+    fn loaded(&self, num_pages: u32, hero_page: Option<u32>) {
+        let mut state = self.state.lock().unwrap();
+        state.num_pages = num_pages;
+        if state.total == 0 && state.status != SessionStatus::Cancelled {
+            state.pending = (1..=num_pages).collect();
+            state.total = num_pages;
+        }
+        if let Some(hero_page) = hero_page {
+            prioritize(&mut state.pending, hero_page);
+        }
+    }
+
+    fn deliver(&self, page: PageResult) {
+        let callback = self.callback.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+        state.completed += 1;
+        match callback.as_ref() {
+            Some(callback) => {
+                callback.call(Ok(page), ThreadsafeFunctionCallMode::NonBlocking);
+            }
+            None => state.ready.push_back(page),
+        }
+    }
+
+    fn fail(&self, error: String) {
+        let mut state = self.state.lock().unwrap();
+        state.cancel();
+        state.status = SessionStatus::Failed;
+        state.error = Some(error);
+    }
+
+    fn finish(&self) {
+        self.state.lock().unwrap().finish();
+        self.callback.lock().unwrap().take();
+    }
+}
+
+impl SessionState {
+    fn new(page_nums: Vec<u32>) -> Self {
+        Self {
+            status: SessionStatus::Running,
+            total: page_nums.len() as u32,
+            pending: page_nums.into(),
+            ready: VecDeque::new(),
+            completed: 0,
+            cancelled: 0,
+            num_pages: 0,
+            error: None,
+            finished: false,
+        }
+    }
+
+    fn transition(&mut self, from: SessionStatus, to: SessionStatus) -> bool {
+        if self.status != from {
+            return false;
+        }
+        self.status = to;
+        true
+    }
+
+    fn cancel(&mut self) -> u32 {
+        if self.status.is_terminal() {
+            return 0;
+        }
+        let count = self.pending.len() as u32;
+        self.pending.clear();
+        self.cancelled += count;
+        self.status = SessionStatus::Cancelled;
+        count
+    }
+
+    /// 正在渲染时取下一页（暂停后由调用方释放文档并等待）
+    fn next_page(&mut self) -> Option<u32> {
+        match self.status {
+            SessionStatus::Running => self.pending.pop_front(),
+            _ => None,
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.status == SessionStatus::Running {
+            self.status = SessionStatus::Completed;
+        }
+        self.finished = true;
+    }
+
+    fn snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            status: self.status,
+            total: self.total,
+            completed: self.completed,
+            pending: self.pending.len() as u32,
+            cancelled: self.cancelled,
+            ready: self.ready.len() as u32,
+            num_pages: self.num_pages,
+            error: self.error.clone(),
+            done: self.finished && self.ready.is_empty(),
+        }
+    }
+}
+
+/// 把 `page_num` 移到待渲染队列最前（不在队列中时不变），其余页面保持原顺序
+fn prioritize(pending: &mut VecDeque<u32>, page_num: u32) {
+    if let Some(index) = pending.iter().position(|&p| p == page_num) {
+        pending.remove(index);
+        pending.push_front(page_num);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prioritize_hero_page() {
+        let mut pending: VecDeque<u32> = (1..=5).collect();
+        prioritize(&mut pending, 4);
+        assert_eq!(pending, [4, 1, 2, 3, 5]);
+        prioritize(&mut pending, 9);
+        assert_eq!(pending, [4, 1, 2, 3, 5]);
+    }
+}
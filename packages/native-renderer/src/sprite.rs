@@ -0,0 +1,124 @@
+//! 缩略图拼图（sprite sheet）
+//!
+//! 把多页缩略图按网格拼到一张图片中，每页占一个正方形单元格，
+//! 页面在单元格内等比缩放并居中。查看器的进度条预览只需加载一张图片。
+//! 固定尺寸画布（letterbox）复用同样的拼接逻辑。
+
+use crate::config::Canvas;
+use crate::renderer::PixelFormat;
+
+/// 拼图网格布局
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteLayout {
+    pub columns: u32,
+    pub rows: u32,
+    pub cell_size: u32,
+}
+
+impl SpriteLayout {
+    /// 计算布局
+    ///
+    /// `columns` 未指定时尽量排成一行，超出 `max_dimension` 后换行；
+    /// 拼图高度仍超出限制时返回错误。
+    pub fn new(count: u32, cell_size: u32, columns: Option<u32>, max_dimension: u32) -> Result<Self, String> {
+        if cell_size == 0 || cell_size > max_dimension {
+            return Err(format!("Invalid cell size: {} (max {})", cell_size, max_dimension));
+        }
+        let max_columns = max_dimension / cell_size;
+        let columns = columns
+            .unwrap_or(count)
+            .clamp(1, max_columns)
+            .min(count.max(1));
+        let rows = count.div_ceil(columns).max(1);
+        if rows * cell_size > max_dimension {
+            return Err(format!(
+                "Sprite sheet too large: {} pages of {}px exceed {}px",
+                count, cell_size, max_dimension
+            ));
+        }
+        Ok(Self { columns, rows, cell_size })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.columns * self.cell_size
+    }
+
+    pub fn height(&self) -> u32 {
+        self.rows * self.cell_size
+    }
+
+    /// 第 `index` 个单元格的左上角坐标
+    pub fn cell_origin(&self, index: u32) -> (u32, u32) {
+        (
+            (index % self.columns) * self.cell_size,
+            (index / self.columns) * self.cell_size,
+        )
+    }
+}
+
+/// 页面在单元格内等比缩放后的尺寸
+pub fn fit_in_cell(page_width: f32, page_height: f32, cell_size: u32) -> (u32, u32) {
+    let scale = (cell_size as f32 / page_width).min(cell_size as f32 / page_height);
+    (
+        ((page_width * scale).round() as u32).clamp(1, cell_size),
+        ((page_height * scale).round() as u32).clamp(1, cell_size),
+    )
+}
+
+/// 把紧密排列的 4 通道图像复制到画布的 (x, y) 位置（超出画布的部分被裁掉）
+pub fn blit(canvas: &mut [u8], canvas_width: u32, image: &[u8], width: u32, height: u32, x: u32, y: u32) {
+    let canvas_height = canvas.len() as u32 / (canvas_width * 4).max(1);
+    let copy_width = width.min(canvas_width.saturating_sub(x)) as usize * 4;
+    for row in 0..height.min(canvas_height.saturating_sub(y)) {
+        let src = (row * width * 4) as usize;
+        let dst = (((y + row) * canvas_width + x) * 4) as usize;
+        canvas[dst..dst + copy_width].copy_from_slice(&image[src..src + copy_width]);
+    }
+}
+
+/// 把页面图像按画布的对齐方式放到填充色背景上，返回画布像素
+pub fn letterbox(image: &[u8], width: u32, height: u32, canvas: &Canvas, pixel_format: PixelFormat) -> Vec<u8> {
+    let [r, g, b, a] = canvas.color;
+    let pixel = match pixel_format {
+        PixelFormat::Rgba => [r, g, b, a],
+        PixelFormat::Bgra => [b, g, r, a],
+    };
+    let mut pixels = pixel.repeat((canvas.width * canvas.height) as usize);
+    let (x, y) = canvas.offset(width, height);
+    blit(&mut pixels, canvas.width, image, width, height, x, y);
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_wraps_at_max_dimension() {
+        let layout = SpriteLayout::new(10, 100, None, 450).unwrap();
+        assert_eq!((layout.columns, layout.rows), (4, 3));
+        assert_eq!(layout.cell_origin(5), (100, 100));
+        assert_eq!((layout.width(), layout.height()), (400, 300));
+
+        assert!(SpriteLayout::new(100, 100, None, 450).is_err());
+        assert_eq!(SpriteLayout::new(3, 100, Some(8), 1000).unwrap().columns, 3);
+    }
+
+    #[test]
+    fn test_fit_and_blit() {
+        assert_eq!(fit_in_cell(612.0, 792.0, 100), (77, 100));
+
+        let mut canvas = vec![0u8; 3 * 2 * 4];
+        blit(&mut canvas, 3, &[9u8; 2 * 2 * 4], 2, 2, 2, 1);
+        assert_eq!(&canvas[(3 + 2) * 4..(3 + 3) * 4], &[9, 9, 9, 9]);
+        assert_eq!(&canvas[..4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_letterbox_bottom_right() {
+        let canvas = Canvas::from_options(Some(3), Some(2), Some("#102030"), Some("bottom-right")).unwrap();
+        let pixels = letterbox(&[9u8; 4], 1, 1, &canvas, PixelFormat::Bgra);
+        assert_eq!(&pixels[..4], &[0x30, 0x20, 0x10, 255]);
+        assert_eq!(&pixels[(3 + 2) * 4..], &[9, 9, 9, 9]);
+    }
+}
@@ -0,0 +1,462 @@
+//! 水印（stampText / stampImage）
+//!
+//! 分享出去的预览图常常需要打上"内部资料"之类的水印。在原生侧直接合成到渲染出的位图上，
+//! 不必在 Sharp 中再解码、合成、编码一遍。文字水印借助 PDFium 绘制：把文字放到一个临时的
+//! 单页 PDF 上渲染，得到抗锯齿的字形蒙版，再按颜色和不透明度合成。
+//!
+//! 文字使用 PDF 标准字体 Helvetica，只支持拉丁字符；其他文字请使用图片水印。
+//!
+//! 排除区域（如签名、印章所在的位置）内不合成水印，法务场景中水印不能遮挡签名。
+
+use crate::analysis::Rect;
+use crate::config::{parse_align, parse_color};
+use crate::content_hash;
+use crate::renderer::PixelFormat;
+use once_cell::sync::Lazy;
+use pdfium_render::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// 文字水印的默认字号（像素）
+const DEFAULT_FONT_SIZE: f32 = 48.0;
+/// 字号范围（像素）
+const MIN_FONT_SIZE: f32 = 4.0;
+const MAX_FONT_SIZE: f32 = 1000.0;
+/// 默认颜色：中灰
+const DEFAULT_COLOR: [u8; 4] = [128, 128, 128, 255];
+/// 默认不透明度
+const DEFAULT_OPACITY: f32 = 0.3;
+/// 默认边距（像素），平铺时也是水印之间的间距
+const DEFAULT_MARGIN: u32 = 24;
+/// 最多缓存的文字水印蒙版数
+const TEXT_CACHE_SIZE: usize = 8;
+
+/// 水印内容
+#[derive(Debug, Clone, PartialEq)]
+pub enum StampContent {
+    Text(String),
+    Image(StampImage),
+}
+
+/// 水印的摆放方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StampPosition {
+    /// 按对齐方式放一个（0 左/上，0.5 居中，1 右/下）
+    Align(f32, f32),
+    /// 铺满整页
+    Tile,
+}
+
+/// 水印设置
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamp {
+    pub content: StampContent,
+    /// 文字水印的字号（像素）
+    pub font_size: f32,
+    /// 文字颜色（RGBA）
+    pub color: [u8; 4],
+    /// 不透明度（0-1），与图片自身的 alpha 相乘
+    pub opacity: f32,
+    pub position: StampPosition,
+    /// 到图像边缘的距离（像素），平铺时为水印之间的间距
+    pub margin: u32,
+    /// 不合成水印的区域
+    pub exclusions: Vec<StampExclusion>,
+}
+
+/// 水印排除区域（PDF 坐标，单位点，原点在页面左下角）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StampExclusion {
+    /// 只对该页生效，None 表示每一页
+    pub page_num: Option<u32>,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl StampExclusion {
+    /// 从左下角坐标和宽高构建；坐标或宽高无效时返回 None
+    pub fn from_options(page_num: Option<u32>, x: f64, y: f64, width: f64, height: f64) -> Option<Self> {
+        if !(width.is_finite() && height.is_finite() && x.is_finite() && y.is_finite()) || width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            page_num,
+            left: x as f32,
+            bottom: y as f32,
+            right: (x + width) as f32,
+            top: (y + height) as f32,
+        })
+    }
+
+    pub fn applies_to(&self, page_num: u32) -> bool {
+        self.page_num.is_none_or(|n| n == page_num)
+    }
+}
+
+/// 紧密排列的 RGBA 图像（非预乘 alpha）；Debug 只输出尺寸和哈希，避免把像素写进日志和缓存键
+#[derive(Clone, PartialEq)]
+pub struct StampImage {
+    pub width: u32,
+    pub height: u32,
+    pixels: Arc<Vec<u8>>,
+    hash: u64,
+}
+
+impl StampImage {
+    /// 像素数据长度与宽高不符时返回错误
+    pub fn new(pixels: Vec<u8>, width: u32, height: u32) -> Result<Self, String> {
+        if width == 0 || height == 0 || pixels.len() != width as usize * height as usize * 4 {
+            return Err(format!(
+                "stampImage must be {}x{} RGBA ({} bytes), got {} bytes",
+                width,
+                height,
+                width as usize * height as usize * 4,
+                pixels.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            hash: content_hash::xxh64(&pixels, 0),
+            pixels: Arc::new(pixels),
+        })
+    }
+}
+
+impl std::fmt::Debug for StampImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StampImage({}x{}, {:016x})", self.width, self.height, self.hash)
+    }
+}
+
+/// 水印选项
+pub struct StampOptions<'a> {
+    pub text: Option<&'a str>,
+    pub image: Option<(&'a [u8], Option<u32>, Option<u32>)>,
+    pub font_size: Option<f64>,
+    pub color: Option<&'a str>,
+    pub opacity: Option<f64>,
+    pub position: Option<&'a str>,
+    pub margin: Option<u32>,
+    pub exclusions: Vec<StampExclusion>,
+}
+
+impl Stamp {
+    /// 从选项构建；没有文字也没有图片时返回 None，图片数据与宽高不符时返回错误
+    ///
+    /// 同时指定时使用图片。无法识别的颜色和位置使用默认值（中灰、居中）。
+    pub fn from_options(options: StampOptions) -> Option<Result<Self, String>> {
+        let content = match (options.image, options.text) {
+            (Some((data, width, height)), _) => {
+                match StampImage::new(data.to_vec(), width.unwrap_or(0), height.unwrap_or(0)) {
+                    Ok(image) => StampContent::Image(image),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            (None, Some(text)) if !text.trim().is_empty() => StampContent::Text(text.to_string()),
+            _ => return None,
+        };
+        let position = match options.position.map(str::trim) {
+            Some(position) if position.eq_ignore_ascii_case("tile") => StampPosition::Tile,
+            Some(position) => {
+                let (x, y) = parse_align(position);
+                StampPosition::Align(x, y)
+            }
+            None => StampPosition::Align(0.5, 0.5),
+        };
+        Some(Ok(Self {
+            content,
+            font_size: options
+                .font_size
+                .filter(|size| size.is_finite())
+                .map_or(DEFAULT_FONT_SIZE, |size| (size as f32).clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)),
+            color: options.color.and_then(parse_color).unwrap_or(DEFAULT_COLOR),
+            opacity: options
+                .opacity
+                .filter(|opacity| opacity.is_finite())
+                .map_or(DEFAULT_OPACITY, |opacity| opacity.clamp(0.0, 1.0) as f32),
+            position,
+            margin: options.margin.unwrap_or(DEFAULT_MARGIN),
+            exclusions: options.exclusions,
+        }))
+    }
+
+    /// 要合成的图像：图片水印直接使用，文字水印由 PDFium 绘制（结果按文字、字号和颜色缓存）
+    pub fn image(&self, pdfium: &Pdfium) -> Result<StampImage, String> {
+        let text = match &self.content {
+            StampContent::Image(image) => return Ok(image.clone()),
+            StampContent::Text(text) => text,
+        };
+        type Key = (String, u32, [u8; 4]);
+        static CACHE: Lazy<Mutex<Vec<(Key, StampImage)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+        let key = (text.clone(), self.font_size.to_bits(), self.color);
+        if let Some((_, image)) = CACHE.lock().unwrap().iter().find(|(k, _)| *k == key) {
+            return Ok(image.clone());
+        }
+        let image = render_text(pdfium, text, self.font_size, self.color)?;
+        let mut cache = CACHE.lock().unwrap();
+        if cache.len() >= TEXT_CACHE_SIZE {
+            cache.remove(0);
+        }
+        cache.push((key, image.clone()));
+        Ok(image)
+    }
+}
+
+/// 在临时的单页 PDF 上以 1 点 = 1 像素绘制黑色文字，把灰度转为蒙版并裁掉四周的空白
+fn render_text(pdfium: &Pdfium, text: &str, font_size: f32, color: [u8; 4]) -> Result<StampImage, String> {
+    let chars = text.chars().count() as f32;
+    let (width, height) = (((chars + 1.0) * font_size).ceil(), (font_size * 2.0).ceil());
+    let failed = |e: PdfiumError| format!("Failed to render stamp text: {}", e);
+
+    let mut document = pdfium.create_new_pdf().map_err(failed)?;
+    let font = document.fonts_mut().helvetica();
+    let mut page = document
+        .pages_mut()
+        .create_page_at_end(PdfPagePaperSize::Custom(PdfPoints::new(width), PdfPoints::new(height)))
+        .map_err(failed)?;
+    page.objects_mut()
+        .create_text_object(
+            PdfPoints::new(font_size / 2.0),
+            PdfPoints::new(font_size / 2.0),
+            text,
+            font,
+            PdfPoints::new(font_size),
+        )
+        .map_err(failed)?;
+    let bitmap = page
+        .render_with_config(&PdfRenderConfig::new().set_target_size(width as i32, height as i32))
+        .map_err(failed)?;
+    let (bitmap_width, bitmap_height) = (bitmap.width() as usize, bitmap.height() as usize);
+    let data = bitmap.as_raw_bytes();
+    let stride = data.len() / bitmap_height.max(1);
+    // 白底黑字，各通道相同，取任一通道的反相作为覆盖度
+    let coverage = |x: usize, y: usize| 255 - data[y * stride + x * 4];
+
+    let (mut left, mut top, mut right, mut bottom) = (bitmap_width, bitmap_height, 0, 0);
+    for y in 0..bitmap_height {
+        for x in 0..bitmap_width {
+            if coverage(x, y) > 0 {
+                (left, top) = (left.min(x), top.min(y));
+                (right, bottom) = (right.max(x + 1), bottom.max(y + 1));
+            }
+        }
+    }
+    if right <= left || bottom <= top {
+        return Err("Stamp text has no visible glyphs (only Latin characters are supported)".to_string());
+    }
+
+    let mut pixels = Vec::with_capacity((right - left) * (bottom - top) * 4);
+    for y in top..bottom {
+        for x in left..right {
+            let alpha = coverage(x, y) as u32 * color[3] as u32 / 255;
+            pixels.extend_from_slice(&[color[0], color[1], color[2], alpha as u8]);
+        }
+    }
+    StampImage::new(pixels, (right - left) as u32, (bottom - top) as u32)
+}
+
+/// 把水印合成到紧密排列的 4 通道图像上（源在上，按 alpha × opacity 混合）
+///
+/// 水印超出图像的部分和落在 `exclusions`（像素坐标）内的部分会被裁掉；
+/// 图像本身带透明度时，结果的 alpha 同样按"源在上"计算。
+pub fn composite(
+    image: &mut [u8],
+    width: u32,
+    height: u32,
+    stamp: &Stamp,
+    mark: &StampImage,
+    exclusions: &[Rect],
+    pixel_format: PixelFormat,
+) {
+    // 排除区域向外取整，边缘像素宁可不合成也不遮挡
+    let exclusions: Vec<(i64, i64, i64, i64)> = exclusions
+        .iter()
+        .map(|rect| rect.clamp(width as f32, height as f32))
+        .map(|rect| (rect.left.floor() as i64, rect.top.floor() as i64, rect.right.ceil() as i64, rect.bottom.ceil() as i64))
+        .filter(|(left, top, right, bottom)| right > left && bottom > top)
+        .collect();
+    let excluded = |x: i64, y: i64| {
+        exclusions
+            .iter()
+            .any(|&(left, top, right, bottom)| x >= left && x < right && y >= top && y < bottom)
+    };
+    let margin = stamp.margin as i64;
+    let (mark_width, mark_height) = (mark.width as i64, mark.height as i64);
+    let origins: Vec<(i64, i64)> = match stamp.position {
+        StampPosition::Align(align_x, align_y) => {
+            let place = |size: u32, mark: i64, align: f32| {
+                let free = size as i64 - 2 * margin - mark;
+                margin + (free as f32 * align).round() as i64
+            };
+            vec![(place(width, mark_width, align_x), place(height, mark_height, align_y))]
+        }
+        StampPosition::Tile => {
+            let (step_x, step_y) = (mark_width + margin, mark_height + margin);
+            (0..height as i64)
+                .step_by(step_y as usize)
+                .flat_map(|y| (0..width as i64).step_by(step_x as usize).map(move |x| (x + margin / 2, y + margin / 2)))
+                .collect()
+        }
+    };
+
+    let opacity = (stamp.opacity * 255.0).round() as u32;
+    for (origin_x, origin_y) in origins {
+        for my in 0..mark_height {
+            let y = origin_y + my;
+            if y < 0 || y >= height as i64 {
+                continue;
+            }
+            for mx in 0..mark_width {
+                let x = origin_x + mx;
+                if x < 0 || x >= width as i64 || excluded(x, y) {
+                    continue;
+                }
+                let source = &mark.pixels[((my * mark_width + mx) * 4) as usize..][..4];
+                let alpha = source[3] as u32 * opacity / 255;
+                if alpha == 0 {
+                    continue;
+                }
+                let color = match pixel_format {
+                    PixelFormat::Rgba => [source[0], source[1], source[2]],
+                    PixelFormat::Bgra => [source[2], source[1], source[0]],
+                };
+                let pixel = &mut image[((y * width as i64 + x) * 4) as usize..][..4];
+                for (channel, c) in pixel.iter_mut().zip(color) {
+                    *channel = ((c as u32 * alpha + *channel as u32 * (255 - alpha)) / 255) as u8;
+                }
+                pixel[3] = (alpha + pixel[3] as u32 * (255 - alpha) / 255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> StampOptions<'static> {
+        StampOptions {
+            text: None,
+            image: None,
+            font_size: None,
+            color: None,
+            opacity: None,
+            position: None,
+            margin: None,
+            exclusions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_from_options() {
+        assert!(Stamp::from_options(options()).is_none());
+        assert!(Stamp::from_options(StampOptions { text: Some("  "), ..options() }).is_none());
+
+        let stamp = Stamp::from_options(StampOptions {
+            text: Some("CONFIDENTIAL"),
+            opacity: Some(2.0),
+            position: Some("Tile"),
+            ..options()
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(stamp.content, StampContent::Text("CONFIDENTIAL".to_string()));
+        assert_eq!(stamp.opacity, 1.0);
+        assert_eq!(stamp.position, StampPosition::Tile);
+        assert_eq!(stamp.font_size, DEFAULT_FONT_SIZE);
+
+        let pixels = [0u8; 8];
+        let invalid = Stamp::from_options(StampOptions {
+            image: Some((&pixels, Some(2), Some(2))),
+            ..options()
+        });
+        assert!(invalid.unwrap().is_err());
+        let stamp = Stamp::from_options(StampOptions {
+            image: Some((&pixels, Some(2), Some(1))),
+            position: Some("bottom-right"),
+            ..options()
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(stamp.position, StampPosition::Align(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_composite() {
+        // 2x1 红色不透明水印，放在 4x2 白色图像的右下角，不透明度 50%
+        let mark = StampImage::new(vec![255, 0, 0, 255, 255, 0, 0, 255], 2, 1).unwrap();
+        let stamp = Stamp {
+            content: StampContent::Image(mark.clone()),
+            font_size: DEFAULT_FONT_SIZE,
+            color: DEFAULT_COLOR,
+            opacity: 0.5,
+            position: StampPosition::Align(1.0, 1.0),
+            margin: 0,
+            exclusions: Vec::new(),
+        };
+        let mut image = vec![255u8; 4 * 2 * 4];
+        composite(&mut image, 4, 2, &stamp, &mark, &[], PixelFormat::Rgba);
+        let pixel = |x: usize, y: usize| &image[(y * 4 + x) * 4..][..4];
+        assert_eq!(pixel(1, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(2, 1), [255, 127, 127, 255]);
+        assert_eq!(pixel(3, 1), [255, 127, 127, 255]);
+        assert_eq!(pixel(3, 0), [255, 255, 255, 255]);
+
+        // BGRA 图像中红色写入第 3 个通道；平铺覆盖整张图
+        let mut image = vec![0u8; 4 * 2 * 4];
+        let tiled = Stamp {
+            position: StampPosition::Tile,
+            opacity: 1.0,
+            ..stamp
+        };
+        composite(&mut image, 4, 2, &tiled, &mark, &[], PixelFormat::Bgra);
+        assert_eq!(&image[..4], [0, 0, 255, 255]);
+        assert_eq!(&image[4..8], [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_stamp_exclusion() {
+        assert!(StampExclusion::from_options(None, 0.0, 0.0, 0.0, 10.0).is_none());
+        assert!(StampExclusion::from_options(None, f64::NAN, 0.0, 10.0, 10.0).is_none());
+        let exclusion = StampExclusion::from_options(Some(2), 10.0, 20.0, 30.0, 40.0).unwrap();
+        assert_eq!((exclusion.left, exclusion.bottom, exclusion.right, exclusion.top), (10.0, 20.0, 40.0, 60.0));
+        assert!(exclusion.applies_to(2));
+        assert!(!exclusion.applies_to(1));
+        assert!(StampExclusion::from_options(None, 0.0, 0.0, 1.0, 1.0).unwrap().applies_to(7));
+    }
+
+    #[test]
+    fn test_composite_exclusions() {
+        // 不透明红色平铺铺满 4x2 图像，右下角 2x1 的排除区域保持原样
+        let mark = StampImage::new(vec![255, 0, 0, 255], 1, 1).unwrap();
+        let stamp = Stamp {
+            content: StampContent::Image(mark.clone()),
+            font_size: DEFAULT_FONT_SIZE,
+            color: DEFAULT_COLOR,
+            opacity: 1.0,
+            position: StampPosition::Tile,
+            margin: 0,
+            exclusions: Vec::new(),
+        };
+        let mut image = vec![255u8; 4 * 2 * 4];
+        let exclusion = Rect::from_corners(2.0, 1.0, 4.0, 2.0);
+        composite(&mut image, 4, 2, &stamp, &mark, &[exclusion], PixelFormat::Rgba);
+        let pixel = |x: usize, y: usize| &image[(y * 4 + x) * 4..][..4];
+        assert_eq!(pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(3, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(1, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(2, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(3, 1), [255, 255, 255, 255]);
+
+        // 不足一个像素的排除区域向外取整，超出图像的部分被裁掉
+        let mut image = vec![255u8; 4 * 2 * 4];
+        let exclusion = Rect::from_corners(0.4, -5.0, 0.6, 0.5);
+        composite(&mut image, 4, 2, &stamp, &mark, &[exclusion], PixelFormat::Rgba);
+        assert_eq!(&image[..4], [255, 255, 255, 255]);
+        assert_eq!(&image[4..8], [255, 0, 0, 255]);
+        assert_eq!(&image[16..20], [255, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,143 @@
+//! 流式渲染并发限制
+//!
+//! 每个活动的流式任务会占用最多 16 MB 的块缓存和一个阻塞的系统线程，
+//! 超出限制的任务在进入阻塞线程之前异步排队，不占用线程和缓存。
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+struct State {
+    /// 活动任务数
+    active: u32,
+    /// 排队任务数
+    queued: u32,
+    /// 全局最大活动任务数，None 表示不限制
+    max_active: Option<u32>,
+    /// 最大排队任务数，None 表示不限制
+    max_queued: Option<u32>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    active: 0,
+    queued: 0,
+    max_active: None,
+    max_queued: None,
+});
+
+/// 有任务结束或限制变化时唤醒排队的任务
+static RELEASED: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// 活动任务许可，析构时释放名额
+pub struct StreamPermit(());
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        STATE.lock().unwrap().active -= 1;
+        RELEASED.notify_waiters();
+    }
+}
+
+/// 排队计数守卫（等待中的 future 被丢弃时也能正确减少计数）
+struct QueuedGuard(());
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        STATE.lock().unwrap().queued -= 1;
+    }
+}
+
+/// 当前限制与计数快照
+pub struct Snapshot {
+    pub active: u32,
+    pub queued: u32,
+    pub max_active: Option<u32>,
+    pub max_queued: Option<u32>,
+}
+
+/// 设置全局限制（None 表示不限制）
+pub fn configure(max_active: Option<u32>, max_queued: Option<u32>) {
+    {
+        let mut state = STATE.lock().unwrap();
+        state.max_active = max_active.map(|m| m.max(1));
+        state.max_queued = max_queued;
+    }
+    // 限制放宽后排队的任务可能可以开始了
+    RELEASED.notify_waiters();
+}
+
+pub fn snapshot() -> Snapshot {
+    let state = STATE.lock().unwrap();
+    Snapshot {
+        active: state.active,
+        queued: state.queued,
+        max_active: state.max_active,
+        max_queued: state.max_queued,
+    }
+}
+
+/// 在全局限制和任务自身的限制都满足时占用一个名额
+fn try_acquire(state: &mut State, per_task: Option<u32>) -> bool {
+    let limit = match (state.max_active, per_task.map(|m| m.max(1))) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    if limit.is_some_and(|limit| state.active >= limit) {
+        return false;
+    }
+    state.active += 1;
+    true
+}
+
+/// 获取活动任务许可，名额不足时排队等待
+///
+/// `per_task` 是调用方指定的上限：只有当前活动任务数低于它时该任务才会开始。
+/// 排队数已满时立即返回错误。
+pub async fn acquire(per_task: Option<u32>) -> Result<StreamPermit, String> {
+    {
+        let mut state = STATE.lock().unwrap();
+        if try_acquire(&mut state, per_task) {
+            return Ok(StreamPermit(()));
+        }
+        if state.max_queued.is_some_and(|max| state.queued >= max) {
+            return Err(format!(
+                "Stream queue is full ({} active, {} queued)",
+                state.active, state.queued
+            ));
+        }
+        state.queued += 1;
+    }
+    let _queued = QueuedGuard(());
+
+    loop {
+        // 先注册通知再检查，避免错过检查与等待之间的释放
+        let released = RELEASED.notified();
+        if try_acquire(&mut STATE.lock().unwrap(), per_task) {
+            return Ok(StreamPermit(()));
+        }
+        released.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_acquire_uses_stricter_limit() {
+        let mut state = State {
+            active: 2,
+            queued: 0,
+            max_active: Some(4),
+            max_queued: None,
+        };
+        assert!(!try_acquire(&mut state, Some(2)));
+        assert!(try_acquire(&mut state, None));
+        assert_eq!(state.active, 3);
+        assert!(try_acquire(&mut state, Some(8)));
+        assert!(!try_acquire(&mut state, None));
+
+        state.max_active = None;
+        assert!(try_acquire(&mut state, None));
+    }
+}
@@ -4,13 +4,17 @@
 //! 用于支持 PDFium 的按需加载，避免一次性下载整个 PDF 文件。
 //!
 //! 关键技术：使用 channel 在 Rust 和 JS 之间同步通信。
+//! JS 交回的 Buffer 不复制，直接作为缓存块的数据（合并读取的响应按块切分时共享同一份）。
 
 use napi::bindgen_prelude::Buffer;
 use napi::threadsafe_function::{
     ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode,
 };
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::{Deref, Range};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 
 /// 数据块请求（传递给 JS 的参数）
@@ -19,21 +23,250 @@ pub struct BlockRequest {
     pub offset: u64,
     pub size: u32,
     /// 请求 ID，用于匹配响应
-    pub request_id: u32,
+    pub request_id: u64,
+    /// 批量请求（取数计划）的各段 (偏移, 长度)，按偏移排序；普通块请求为空
+    ///
+    /// offset/size 覆盖从第一段开始到最后一段结束的整个跨度，JS 可以返回各段数据的拼接，
+    /// 也可以直接返回整个跨度的数据。
+    pub ranges: Vec<(u64, u32)>,
+    /// 整个文件的请求（流式读取效率过低时的回退），offset 为 0，size 为文件大小
+    pub full: bool,
+}
+
+/// JS 交回的响应数据
+pub enum ResponseData {
+    /// `completeStreamRequest` 传入的 Buffer，直接引用 JS 的内存
+    ///
+    /// 以 trait 对象保存：napi 的 Buffer 只在创建处（JS 调用入口）实例化，单元测试不链接 Node API。
+    Shared(Box<dyn AsRef<[u8]> + Send + Sync>),
+    /// Rust 端产生的数据（测试等）
+    Owned(Vec<u8>),
+}
+
+impl Deref for ResponseData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            ResponseData::Shared(bytes) => (**bytes).as_ref(),
+            ResponseData::Owned(data) => data,
+        }
+    }
+}
+
+impl From<Buffer> for ResponseData {
+    fn from(buffer: Buffer) -> Self {
+        ResponseData::Shared(Box::new(JsBytes(buffer)))
+    }
+}
+
+impl From<Vec<u8>> for ResponseData {
+    fn from(data: Vec<u8>) -> Self {
+        ResponseData::Owned(data)
+    }
+}
+
+/// 只读的 JS Buffer
+///
+/// 调用方约定交回后不再修改 Buffer，之后只在 Rust 端只读访问，可以在线程间共享；
+/// 在其他线程释放时 napi 会把引用的释放转交给 JS 线程。
+struct JsBytes(Buffer);
+
+// SAFETY: 只通过 AsRef 只读访问，JS 端交回后不再写入（见 completeStreamRequest 的说明）
+unsafe impl Sync for JsBytes {}
+
+impl AsRef<[u8]> for JsBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// 响应数据中的一段（一个缓存块），合并读取的各块共享同一份响应
+#[derive(Clone)]
+struct Block {
+    data: Arc<ResponseData>,
+    range: Range<usize>,
+}
+
+impl Block {
+    fn slice(data: &Arc<ResponseData>, range: Range<usize>) -> Self {
+        Block {
+            data: Arc::clone(data),
+            range,
+        }
+    }
+}
+
+impl Deref for Block {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[self.range.clone()]
+    }
+}
+
+impl From<Vec<u8>> for Block {
+    fn from(data: Vec<u8>) -> Self {
+        let range = 0..data.len();
+        Block {
+            data: Arc::new(data.into()),
+            range,
+        }
+    }
 }
 
+type Response = Result<ResponseData, String>;
+
 /// 用于接收 JS 响应的 channel sender
-type ResponseSender = mpsc::Sender<Result<Vec<u8>, String>>;
+type ResponseSender = mpsc::Sender<Response>;
 
-/// 缓存块大小（256KB）
+/// 默认缓存块大小（256KB）
 const CACHE_BLOCK_SIZE: u64 = 256 * 1024;
 
-/// 最大缓存块数量
+/// 默认最大缓存块数量
 const MAX_CACHE_BLOCKS: usize = 64;
 
+/// streamBlockSize 的取值范围（16KB - 16MB）
+const BLOCK_SIZE_RANGE: (u64, u64) = (16 * 1024, 16 * 1024 * 1024);
+
+/// streamMaxCacheBlocks 的取值范围（取数计划使用其中一半，至少需要 2 块）
+const CACHE_BLOCKS_RANGE: (usize, usize) = (2, 4096);
+
+/// 最大并行请求数
+pub const MAX_FETCH_LANES: u32 = 8;
+
+/// 合并读取时最多随当前块一起请求的后续块数
+pub const MAX_READAHEAD_BLOCKS: u32 = 32;
+
+/// 预取的块中被用到的比例低于该值时给出调优建议
+const MIN_PREFETCH_USE: f64 = 0.5;
+
+/// 缓存命中率目标，低于目标时给出调优建议
+pub const TARGET_HIT_RATIO: f64 = 0.8;
+
+/// 读取量达到文件大小的该比例时，建议整体下载
+const FULL_DOWNLOAD_FRACTION: f64 = 0.8;
+
+/// 单个任务最多同时等待的请求数（正常读取不超过 1 + MAX_FETCH_LANES 个）
+pub const MAX_PENDING_REQUESTS: usize = 32;
+
+/// 进程内累计的孤立请求数：等待超时或任务结束时仍未得到响应
+static ORPHANED_REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+/// 进程内累计的迟到响应数：请求已超时、已清理或任务已结束后才到达
+static LATE_RESPONSES: AtomicU64 = AtomicU64::new(0);
+
+/// 进程内累计的孤立请求数和迟到响应数
+pub fn orphan_counts() -> (u64, u64) {
+    (ORPHANED_REQUESTS.load(Ordering::Relaxed), LATE_RESPONSES.load(Ordering::Relaxed))
+}
+
+/// 记录一个找不到对应请求的响应
+pub fn record_late_response() {
+    LATE_RESPONSES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 默认等待 JS 响应的超时时间
+const RESPONSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// 等待整个文件的超时时间
+const FULL_RESPONSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// 块缓存参数（streamBlockSize、streamMaxCacheBlocks、streamFetchTimeoutMs）
+///
+/// 默认值适合延迟在几十毫秒的 HTTP 源；按请求计费、延迟较高的对象存储可以用更大的块减少请求数，
+/// 内存紧张时减少缓存块数。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheTuning {
+    /// 缓存块大小（字节），也是单次请求的大小
+    pub block_size: u64,
+    /// 最多缓存的块数，超出时按 LRU 淘汰
+    pub max_cache_blocks: usize,
+    /// 等待 JS 响应一个块的超时时间
+    pub fetch_timeout: std::time::Duration,
+}
+
+impl Default for CacheTuning {
+    fn default() -> Self {
+        Self {
+            block_size: CACHE_BLOCK_SIZE,
+            max_cache_blocks: MAX_CACHE_BLOCKS,
+            fetch_timeout: RESPONSE_TIMEOUT,
+        }
+    }
+}
+
+impl CacheTuning {
+    /// 按选项构造，未指定（或为 0）的使用默认值，块大小和块数限制在允许范围内
+    pub fn new(block_size: Option<u32>, max_cache_blocks: Option<u32>, fetch_timeout_ms: Option<u32>) -> Self {
+        let defaults = Self::default();
+        Self {
+            block_size: block_size
+                .filter(|&size| size > 0)
+                .map_or(defaults.block_size, |size| (size as u64).clamp(BLOCK_SIZE_RANGE.0, BLOCK_SIZE_RANGE.1)),
+            max_cache_blocks: max_cache_blocks
+                .filter(|&blocks| blocks > 0)
+                .map_or(defaults.max_cache_blocks, |blocks| {
+                    (blocks as usize).clamp(CACHE_BLOCKS_RANGE.0, CACHE_BLOCKS_RANGE.1)
+                }),
+            fetch_timeout: fetch_timeout_ms
+                .filter(|&ms| ms > 0)
+                .map_or(defaults.fetch_timeout, |ms| std::time::Duration::from_millis(ms as u64)),
+        }
+    }
+
+    /// 计算缓存块的起始偏移量
+    fn block_offset(&self, offset: u64) -> u64 {
+        (offset / self.block_size) * self.block_size
+    }
+
+    /// 取数计划一次最多获取的块数（保留一半缓存给渲染过程中的其他读取）
+    fn max_planned_blocks(&self) -> usize {
+        self.max_cache_blocks / 2
+    }
+}
+
+/// 改为获取整个文件的条件（opt-in，均未设置时不回退）
+///
+/// 只在文件长度已知时生效。达到任一条件后，下一次缓存未命中时向 JS 请求整个文件，
+/// 之后的读取全部从内存返回；请求失败时记录原因并继续逐块读取。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FallbackPolicy {
+    /// 已获取的字节数占文件大小的比例
+    pub fetched_fraction: Option<f64>,
+    /// 已发出的请求数
+    pub requests: Option<u32>,
+}
+
+impl FallbackPolicy {
+    /// 按当前统计判断是否应当回退，返回原因
+    fn trigger(&self, stats: &StreamerStats, file_size: u64) -> Option<String> {
+        let fraction = stats.total_bytes_fetched as f64 / file_size.max(1) as f64;
+        if self.fetched_fraction.is_some_and(|limit| fraction >= limit) {
+            return Some(format!("Fetched {:.0}% of the file", fraction * 100.0));
+        }
+        if self.requests.is_some_and(|limit| stats.total_requests >= limit) {
+            return Some(format!("Made {} requests", stats.total_requests));
+        }
+        None
+    }
+}
+
+/// 回退到整个文件的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fallback {
+    /// 触发回退的原因
+    pub reason: String,
+    /// 获取整个文件失败的原因（失败后继续逐块读取）
+    pub error: Option<String>,
+}
+
+/// 已发出、尚未消费的请求（请求 ID + 响应接收端）
+type InFlight = (u64, mpsc::Receiver<Response>);
+
 /// LRU 缓存条目
 struct CacheEntry {
-    data: Vec<u8>,
+    data: Block,
     access_order: u64,
 }
 
@@ -48,12 +281,43 @@ pub struct StreamerStats {
     pub cache_misses: u32,
     /// 总下载字节数
     pub total_bytes_fetched: u64,
+    /// 由预取（并行预取或合并读取）提供、随后被读取的块数
+    pub prefetch_hits: u32,
+    /// 预取（并行预取或合并读取）请求的块数，与 prefetch_hits 对比即为预取的有效程度
+    pub prefetched_blocks: u32,
+    /// 调用方预先提供、写入缓存的块数
+    pub seeded_blocks: u32,
+    /// 按取数计划批量获取的块数
+    pub planned_blocks: u32,
+}
+
+/// 单个缓存块在本次任务中的使用情况
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BlockUsage {
+    /// 块大小（字节），尚未取得数据时为 0
+    pub size: u32,
+    /// 读取过该块的页码（升序），0 表示打开文档阶段
+    pub pages: Vec<u32>,
+    /// 通过网络获取的次数（被淘汰后再次读取会重新获取）
+    pub fetches: u32,
+    /// 是否由调用方预先提供
+    pub seeded: bool,
+}
+
+/// 根据本次任务的读取模式给出的调优建议
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningHint {
+    /// 建议类别：FULL_DOWNLOAD、NOT_LINEARIZED、ENABLE_FETCH_LANES、WASTED_PREFETCH、REFETCHED_BLOCKS、LOW_HIT_RATIO
+    pub code: &'static str,
+    pub message: String,
 }
 
 /// 共享状态（用于在 streamer 被 move 后仍能获取统计信息）
 pub struct SharedState {
     /// 任务 ID（用于并发支持）
     task_id: u32,
+    /// 块缓存参数
+    tuning: CacheTuning,
     /// 数据缓存（LRU）
     cache: Mutex<HashMap<u64, CacheEntry>>,
     /// 缓存访问计数器
@@ -61,47 +325,346 @@ pub struct SharedState {
     /// 统计信息
     pub stats: Mutex<StreamerStats>,
     /// 待处理的请求（request_id -> sender）
-    pending_requests: Mutex<HashMap<u32, ResponseSender>>,
+    pending_requests: Mutex<HashMap<u64, ResponseSender>>,
     /// 下一个请求序号（16 位，会与 task_id 组合成完整的 request_id）
     next_request_seq: Mutex<u16>,
+    /// 是否已被取消（取消后不再发起新的数据请求）
+    cancelled: AtomicBool,
+    /// 正在渲染的页码，0 表示打开文档阶段
+    current_page: AtomicU32,
+    /// 每个块的使用情况（块偏移 -> 使用情况），不随缓存淘汰清除
+    block_usage: Mutex<HashMap<u64, BlockUsage>>,
+    /// 合并读取写入缓存、尚未被读取的块（首次读取时计为预取命中，被淘汰时视为浪费）
+    readahead_blocks: Mutex<HashSet<u64>>,
+    /// 文件总大小（长度未知时在 fetcher 报告后设置）
+    file_size: Mutex<Option<u64>>,
+    /// 文件头部是否有线性化字典，读到第一个块之前为 None
+    linearized: Mutex<Option<bool>>,
+    /// 是否在打开文档前按线性化字典预取了首页段落
+    fast_path: AtomicBool,
+    /// 本任务中等待超时或任务结束时仍未响应的请求数
+    orphaned_requests: AtomicU32,
+    /// 取数计划失败的原因（未使用取数计划或计划成功时为 None）
+    plan_error: Mutex<Option<String>>,
+    /// 回退后得到的整个文件，之后的读取都从这里返回
+    full_data: Mutex<Option<Arc<ResponseData>>>,
+    /// 回退到整个文件的结果（未回退时为 None，每个任务最多尝试一次）
+    fallback: Mutex<Option<Fallback>>,
 }
 
 impl SharedState {
-    fn new(task_id: u32) -> Self {
+    fn new(task_id: u32, tuning: CacheTuning) -> Self {
         Self {
             task_id,
+            tuning,
             cache: Mutex::new(HashMap::new()),
             access_counter: Mutex::new(0),
             stats: Mutex::new(StreamerStats::default()),
             pending_requests: Mutex::new(HashMap::new()),
             next_request_seq: Mutex::new(0),
+            cancelled: AtomicBool::new(false),
+            current_page: AtomicU32::new(0),
+            block_usage: Mutex::new(HashMap::new()),
+            readahead_blocks: Mutex::new(HashSet::new()),
+            file_size: Mutex::new(None),
+            linearized: Mutex::new(None),
+            fast_path: AtomicBool::new(false),
+            orphaned_requests: AtomicU32::new(0),
+            plan_error: Mutex::new(None),
+            full_data: Mutex::new(None),
+            fallback: Mutex::new(None),
+        }
+    }
+
+    /// 正在等待 JS 响应的请求数
+    pub fn pending_count(&self) -> usize {
+        self.pending_requests.lock().unwrap().len()
+    }
+
+    /// 本任务中等待超时或任务结束时仍未响应的请求数
+    pub fn orphaned_requests(&self) -> u32 {
+        self.orphaned_requests.load(Ordering::Relaxed)
+    }
+
+    fn record_orphaned(&self, count: u32) {
+        self.orphaned_requests.fetch_add(count, Ordering::Relaxed);
+        ORPHANED_REQUESTS.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// 任务结束时清理仍在等待的请求，记为孤立请求，返回清理的数量
+    ///
+    /// 之后到达的响应找不到请求，记为迟到响应。
+    pub fn release_pending(&self) -> u32 {
+        let count = self.pending_requests.lock().unwrap().drain().count() as u32;
+        self.record_orphaned(count);
+        count
+    }
+
+    /// 清空块缓存和回退时获取的整个文件（常驻文档空闲时释放内存，之后的读取重新逐块获取）
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+        self.readahead_blocks.lock().unwrap().clear();
+        *self.full_data.lock().unwrap() = None;
+    }
+
+    /// 记录取数计划失败的原因（渲染照常逐块读取）
+    pub fn record_plan_error(&self, error: String) {
+        *self.plan_error.lock().unwrap() = Some(error);
+    }
+
+    /// 取数计划失败的原因
+    pub fn plan_error(&self) -> Option<String> {
+        self.plan_error.lock().unwrap().clone()
+    }
+
+    /// 回退到整个文件的结果
+    pub fn fallback(&self) -> Option<Fallback> {
+        self.fallback.lock().unwrap().clone()
+    }
+
+    /// 已经取得整个文件时直接从内存读取（计为缓存命中）
+    fn read_full(&self, offset: u64, size: u32) -> Option<Vec<u8>> {
+        let full = self.full_data.lock().unwrap().clone()?;
+        let start = (offset as usize).min(full.len());
+        let end = start.saturating_add(size as usize).min(full.len());
+        self.stats.lock().unwrap().cache_hits += 1;
+        Some(full[start..end].to_vec())
+    }
+
+    /// 文件是否线性化（按文件开头的 /Linearized 字典判断），尚未读到文件开头时为 None
+    pub fn linearized(&self) -> Option<bool> {
+        *self.linearized.lock().unwrap()
+    }
+
+    /// 记录已按线性化字典预取首页段落
+    pub fn record_fast_path(&self) {
+        self.fast_path.store(true, Ordering::Relaxed);
+    }
+
+    /// 是否使用了线性化文件的快速路径
+    pub fn fast_path(&self) -> bool {
+        self.fast_path.load(Ordering::Relaxed)
+    }
+
+    /// 缓存命中率：直接从缓存或预取得到的读取占全部读取的比例，没有读取时为 1
+    pub fn hit_ratio(&self) -> f64 {
+        let stats = self.stats.lock().unwrap();
+        let hits = stats.cache_hits + stats.prefetch_hits;
+        let reads = hits + stats.cache_misses;
+        if reads == 0 {
+            1.0
+        } else {
+            hits as f64 / reads as f64
+        }
+    }
+
+    /// 根据本次任务观察到的读取模式给出调优建议
+    ///
+    /// `fetch_lanes` 为本次任务使用的并行请求数。
+    pub fn tuning_hints(&self, fetch_lanes: u32) -> Vec<TuningHint> {
+        let stats = self.stats.lock().unwrap().clone();
+        let usage = self.block_usage.lock().unwrap().clone();
+        let file_size = *self.file_size.lock().unwrap();
+        let linearized = self.linearized();
+        let mut hints = Vec::new();
+
+        let fetched_fraction = file_size
+            .filter(|&size| size > 0)
+            .map(|size| stats.total_bytes_fetched as f64 / size as f64);
+        if let Some(fraction) = fetched_fraction {
+            if fraction >= FULL_DOWNLOAD_FRACTION {
+                hints.push(TuningHint {
+                    code: "FULL_DOWNLOAD",
+                    message: format!(
+                        "Fetched {:.0}% of the file; streaming saves little bandwidth, download the whole file instead",
+                        fraction * 100.0
+                    ),
+                });
+            } else if linearized == Some(false) && fraction >= 0.5 {
+                hints.push(TuningHint {
+                    code: "NOT_LINEARIZED",
+                    message: format!(
+                        "Document is not linearized and {:.0}% of the file was fetched; consider a full download or linearizing the file",
+                        fraction * 100.0
+                    ),
+                });
+            }
+        }
+
+        let fetched: Vec<u64> = usage.iter().filter(|(_, u)| u.fetches > 0).map(|(offset, _)| *offset).collect();
+        let sequential = fetched
+            .iter()
+            .filter(|&&offset| {
+                offset >= self.tuning.block_size
+                    && usage.get(&(offset - self.tuning.block_size)).is_some_and(|u| u.fetches > 0)
+            })
+            .count();
+        if fetch_lanes <= 1
+            && stats.prefetched_blocks == 0
+            && stats.cache_misses >= 8
+            && sequential * 4 >= fetched.len() * 3
+        {
+            hints.push(TuningHint {
+                code: "ENABLE_FETCH_LANES",
+                message: format!(
+                    "{} of {} fetched blocks were read sequentially; set fetchLanes (e.g. 4) to request following blocks in parallel, or streamReadahead to fetch them with the current block",
+                    sequential,
+                    fetched.len()
+                ),
+            });
+        }
+
+        if stats.prefetched_blocks >= 8 && (stats.prefetch_hits as f64) < stats.prefetched_blocks as f64 * MIN_PREFETCH_USE {
+            hints.push(TuningHint {
+                code: "WASTED_PREFETCH",
+                message: format!(
+                    "Only {} of {} prefetched blocks were read; lower fetchLanes or streamReadahead to save bandwidth",
+                    stats.prefetch_hits, stats.prefetched_blocks
+                ),
+            });
+        }
+
+        let refetched = usage.values().filter(|u| u.fetches > 1).count();
+        if refetched > 0 {
+            hints.push(TuningHint {
+                code: "REFETCHED_BLOCKS",
+                message: format!(
+                    "{} blocks were evicted from the cache and fetched again; render fewer pages per call, raise streamMaxCacheBlocks or keep the document open",
+                    refetched
+                ),
+            });
+        }
+
+        let hit_ratio = self.hit_ratio();
+        if stats.cache_hits + stats.cache_misses >= 32 && hit_ratio < TARGET_HIT_RATIO {
+            hints.push(TuningHint {
+                code: "LOW_HIT_RATIO",
+                message: format!(
+                    "Cache hit ratio {:.0}% is below the {:.0}% target; pass the start of the file as seedBlocks or download it in full",
+                    hit_ratio * 100.0,
+                    TARGET_HIT_RATIO * 100.0
+                ),
+            });
+        }
+        hints
+    }
+
+    fn set_file_size(&self, size: u64) {
+        *self.file_size.lock().unwrap() = Some(size);
+    }
+
+    /// 记录 fetcher 在响应中报告的文件总长度（长度已知时忽略）
+    pub fn report_file_size(&self, size: u64) {
+        self.file_size.lock().unwrap().get_or_insert(size);
+    }
+
+    fn file_size(&self) -> Option<u64> {
+        *self.file_size.lock().unwrap()
+    }
+
+    /// 读到文件开头时检查线性化字典（位于文件头之后的第一个对象中）
+    fn record_header(&self, data: &[u8]) {
+        let head = &data[..data.len().min(1024)];
+        let linearized = head.windows(b"/Linearized".len()).any(|w| w == b"/Linearized");
+        *self.linearized.lock().unwrap() = Some(linearized);
+    }
+
+    /// 设置正在渲染的页码，之后的块读取都记在该页名下
+    pub fn set_current_page(&self, page_num: u32) {
+        self.current_page.store(page_num, Ordering::Relaxed);
+    }
+
+    /// 记录当前页读取了某个块
+    fn record_block_use(&self, block_offset: u64) {
+        let page_num = self.current_page.load(Ordering::Relaxed);
+        let mut usage = self.block_usage.lock().unwrap();
+        let pages = &mut usage.entry(block_offset).or_default().pages;
+        if let Err(i) = pages.binary_search(&page_num) {
+            pages.insert(i, page_num);
+        }
+    }
+
+    /// 记录块数据的来源（网络获取或调用方提供）
+    fn record_block_data(&self, block_offset: u64, size: usize, seeded: bool) {
+        let mut usage = self.block_usage.lock().unwrap();
+        let entry = usage.entry(block_offset).or_default();
+        entry.size = size as u32;
+        if seeded {
+            entry.seeded = true;
+        } else {
+            entry.fetches += 1;
         }
     }
 
+    /// 被多个页面（或打开文档阶段与页面）读取过的块，按偏移排序
+    pub fn reused_blocks(&self) -> Vec<(u64, BlockUsage)> {
+        let usage = self.block_usage.lock().unwrap();
+        let mut blocks: Vec<(u64, BlockUsage)> = usage
+            .iter()
+            .filter(|(_, u)| u.pages.len() > 1)
+            .map(|(offset, u)| (*offset, u.clone()))
+            .collect();
+        blocks.sort_by_key(|(offset, _)| *offset);
+        blocks
+    }
+
     /// 生成下一个请求 ID
-    /// 格式：高 16 位是 task_id，低 16 位是请求序号
-    fn next_id(&self) -> u32 {
+    /// 格式：低 16 位是请求序号，其上是 task_id（不超过 48 位，JS number 可以精确表示）
+    fn next_id(&self) -> u64 {
         let mut seq = self.next_request_seq.lock().unwrap();
         let current_seq = *seq;
         *seq = seq.wrapping_add(1);
         // 组合 task_id 和 seq：task_id << 16 | seq
-        (self.task_id << 16) | (current_seq as u32)
+        ((self.task_id as u64) << 16) | current_seq as u64
+    }
+
+    /// 注册一个待处理的请求；任务已取消或等待中的请求达到上限时失败
+    fn register_request(&self, request_id: u64, sender: ResponseSender) -> io::Result<()> {
+        let mut pending = self.pending_requests.lock().unwrap();
+        if self.is_cancelled() {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "Stream task cancelled"));
+        }
+        if pending.len() >= MAX_PENDING_REQUESTS {
+            return Err(io::Error::other(format!(
+                "Too many pending stream requests ({}), the fetcher is not answering",
+                MAX_PENDING_REQUESTS
+            )));
+        }
+        pending.insert(request_id, sender);
+        Ok(())
+    }
+
+    /// 完成一个请求，返回请求是否仍在等待（已超时或已清理时为 false，记为迟到响应）
+    pub fn complete_request(&self, request_id: u64, data: Response) -> bool {
+        match self.pending_requests.lock().unwrap().remove(&request_id) {
+            Some(sender) => {
+                let _ = sender.send(data);
+                true
+            }
+            None => {
+                record_late_response();
+                false
+            }
+        }
     }
 
-    /// 注册一个待处理的请求
-    fn register_request(&self, request_id: u32, sender: ResponseSender) {
-        self.pending_requests
-            .lock()
-            .unwrap()
-            .insert(request_id, sender);
+    /// 放弃一个不再需要的请求（例如读取器销毁时尚未使用的预取），不计为孤立请求
+    fn abandon_request(&self, request_id: u64) {
+        self.pending_requests.lock().unwrap().remove(&request_id);
     }
 
-    /// 完成一个请求
-    pub fn complete_request(&self, request_id: u32, data: Result<Vec<u8>, String>) {
-        if let Some(sender) = self.pending_requests.lock().unwrap().remove(&request_id) {
-            let _ = sender.send(data);
+    /// 取消任务：所有待处理的请求立即以错误返回，后续请求直接失败
+    pub fn cancel(&self, reason: &str) {
+        let mut pending = self.pending_requests.lock().unwrap();
+        self.cancelled.store(true, Ordering::SeqCst);
+        for (_, sender) in pending.drain() {
+            let _ = sender.send(Err(reason.to_string()));
         }
     }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
 }
 
 /// 流式 PDF 读取器
@@ -112,29 +675,149 @@ impl SharedState {
 ///
 /// 关键技术：使用独立线程 + tokio runtime 来等待 async JS Promise。
 pub struct JsFileStreamer {
-    /// 文件总大小
-    file_size: u64,
+    /// 文件总大小（None 表示长度未知，首次需要时等待 fetcher 报告）
+    file_size: Option<u64>,
+    /// 长度未知时允许报告的最大长度
+    max_size: Option<u64>,
     /// 当前读取位置
     position: u64,
     /// 线程安全函数，用于回调 JavaScript
     fetcher: ThreadsafeFunction<BlockRequest, ErrorStrategy::CalleeHandled>,
     /// 共享状态
     state: Arc<SharedState>,
+    /// 并行请求数（1 表示逐块阻塞读取）
+    fetch_lanes: u32,
+    /// 顺序读取时随当前块一起请求的后续块数（0 表示不合并读取）
+    readahead: u32,
+    /// 预取中的块（块偏移 -> 请求）
+    prefetching: RefCell<HashMap<u64, InFlight>>,
+    /// 上一次从网络获取的块偏移，用于判断是否顺序读取
+    last_block: Cell<Option<u64>>,
+    /// 改为获取整个文件的条件
+    fallback: FallbackPolicy,
 }
 
 impl JsFileStreamer {
     /// 创建新的流式读取器
+    ///
+    /// `file_size` 为 None 时表示长度未知（例如边上传边渲染），
+    /// fetcher 需要在响应中报告总长度，见 `total_size`。
     pub fn new(
-        file_size: u64,
+        file_size: Option<u64>,
         fetcher: ThreadsafeFunction<BlockRequest, ErrorStrategy::CalleeHandled>,
         task_id: u32,
+        tuning: CacheTuning,
     ) -> Self {
+        let state = SharedState::new(task_id, tuning);
+        if let Some(size) = file_size {
+            state.set_file_size(size);
+        }
         Self {
             file_size,
+            max_size: None,
             position: 0,
             fetcher,
-            state: Arc::new(SharedState::new(task_id)),
+            state: Arc::new(state),
+            fetch_lanes: 1,
+            readahead: 0,
+            prefetching: RefCell::new(HashMap::new()),
+            last_block: Cell::new(None),
+            fallback: FallbackPolicy::default(),
+        }
+    }
+
+    /// 以同一份共享状态（块缓存、统计）创建从头读取的新读取器
+    ///
+    /// PDFium 打开文档会取走读取器，换密码重新打开时使用，已缓存的块不会重新请求。
+    pub fn reopen(&self) -> Self {
+        Self {
+            file_size: self.file_size,
+            max_size: self.max_size,
+            position: 0,
+            fetcher: self.fetcher.clone(),
+            state: Arc::clone(&self.state),
+            fetch_lanes: self.fetch_lanes,
+            readahead: self.readahead,
+            prefetching: RefCell::new(HashMap::new()),
+            last_block: Cell::new(None),
+            fallback: self.fallback,
+        }
+    }
+
+    /// 设置长度未知时允许报告的最大长度，超出时读取失败
+    pub fn with_max_size(mut self, max_size: Option<u64>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// 获取文件总大小
+    ///
+    /// 长度未知时请求第一个块并等待响应，fetcher 需要在响应中报告总长度（`totalSize`）；
+    /// 边上传边渲染时可以等上传完成、知道总长度后再响应。返回的数据短于请求的大小
+    /// 不代表文件结束（上传中的文件随时可能变长），因此不根据数据长度推断文件末尾。
+    pub fn total_size(&mut self) -> io::Result<u64> {
+        if let Some(size) = self.file_size {
+            return Ok(size);
+        }
+
+        if self.state.file_size().is_none() {
+            self.fetch_block(0, self.state.tuning.block_size as u32)?;
+        }
+        let size = self.state.file_size().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Stream length is unknown and the fetcher did not report totalSize",
+            )
+        })?;
+        if let Some(max) = self.max_size.filter(|&max| size > max) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Stream exceeds maximum size of {} bytes", max),
+            ));
+        }
+        self.file_size = Some(size);
+        Ok(size)
+    }
+
+    /// 设置并行请求数
+    ///
+    /// 大于 1 时，检测到顺序读取后会同时请求后续的若干块，
+    /// 以隐藏对象存储的高延迟。随机访问时仍逐块请求，避免浪费带宽。
+    pub fn with_fetch_lanes(mut self, lanes: u32) -> Self {
+        self.fetch_lanes = lanes.clamp(1, MAX_FETCH_LANES);
+        self
+    }
+
+    /// 设置合并读取的块数
+    ///
+    /// 大于 0 时，检测到顺序读取后的缓存未命中会在同一个请求中连同后续的 `blocks` 块一起获取，
+    /// 一次往返换来多块数据；与 fetchLanes 同时使用时，并行预取从合并读取覆盖的范围之后开始。
+    /// 不超过缓存块数的一半，避免预读的块把正在使用的块挤出缓存。
+    pub fn with_readahead(mut self, blocks: u32) -> Self {
+        let limit = MAX_READAHEAD_BLOCKS.min((self.state.tuning.max_cache_blocks / 2) as u32);
+        self.readahead = blocks.min(limit);
+        self
+    }
+
+    /// 设置改为获取整个文件的条件
+    pub fn with_fallback(mut self, fallback: FallbackPolicy) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// 用调用方已有的数据预先填充缓存（例如上传时已经读到的文件开头）
+    ///
+    /// 只有完整覆盖的缓存块会被写入；文件长度已知时，末尾不足一块的部分也可以写入。
+    /// 缓存容量有限，超出容量的预填充块会按 LRU 被淘汰。返回写入的块数。
+    pub fn seed(&self, offset: u64, data: &[u8]) -> u32 {
+        let blocks = seed_ranges(offset, data.len(), self.file_size, self.state.tuning.block_size);
+        for (block_offset, range) in &blocks {
+            let block = Block::from(data[range.clone()].to_vec());
+            self.state.record_block_data(*block_offset, block.len(), true);
+            self.write_to_cache(*block_offset, block);
         }
+        self.state.stats.lock().unwrap().seeded_blocks += blocks.len() as u32;
+        blocks.len() as u32
     }
 
     /// 获取共享状态的引用（用于在 streamer 被 move 后获取统计信息）
@@ -143,20 +826,20 @@ impl JsFileStreamer {
         Arc::clone(&self.state)
     }
 
+    /// 不让 fetcher 阻止进程退出（读取器长期存活时使用）
+    pub fn unref(&mut self, env: &napi::Env) -> napi::Result<()> {
+        self.fetcher.unref(env)
+    }
+
     /// 获取统计信息
     #[allow(dead_code)]
     pub fn get_stats(&self) -> StreamerStats {
         self.state.stats.lock().unwrap().clone()
     }
 
-    /// 计算缓存块的起始偏移量
-    fn cache_block_offset(offset: u64) -> u64 {
-        (offset / CACHE_BLOCK_SIZE) * CACHE_BLOCK_SIZE
-    }
-
     /// 从缓存中读取数据
     fn read_from_cache(&self, offset: u64, size: u32) -> Option<Vec<u8>> {
-        let block_offset = Self::cache_block_offset(offset);
+        let block_offset = self.state.tuning.block_offset(offset);
         let mut cache = self.state.cache.lock().unwrap();
 
         if let Some(entry) = cache.get_mut(&block_offset) {
@@ -171,7 +854,12 @@ impl JsFileStreamer {
             let read_size = (size as usize).min(available);
 
             if read_size > 0 {
-                self.state.stats.lock().unwrap().cache_hits += 1;
+                let mut stats = self.state.stats.lock().unwrap();
+                if self.state.readahead_blocks.lock().unwrap().remove(&block_offset) {
+                    stats.prefetch_hits += 1;
+                } else {
+                    stats.cache_hits += 1;
+                }
                 return Some(entry.data[offset_in_block..offset_in_block + read_size].to_vec());
             }
         }
@@ -180,12 +868,15 @@ impl JsFileStreamer {
     }
 
     /// 将数据写入缓存
-    fn write_to_cache(&self, offset: u64, data: Vec<u8>) {
-        let block_offset = Self::cache_block_offset(offset);
+    fn write_to_cache(&self, offset: u64, data: Block) {
+        let block_offset = self.state.tuning.block_offset(offset);
+        if block_offset == 0 {
+            self.state.record_header(&data);
+        }
         let mut cache = self.state.cache.lock().unwrap();
 
         // 如果缓存已满，删除最旧的条目
-        while cache.len() >= MAX_CACHE_BLOCKS {
+        while cache.len() >= self.state.tuning.max_cache_blocks {
             let oldest_key = cache
                 .iter()
                 .min_by_key(|(_, v)| v.access_order)
@@ -193,6 +884,7 @@ impl JsFileStreamer {
 
             if let Some(key) = oldest_key {
                 cache.remove(&key);
+                self.state.readahead_blocks.lock().unwrap().remove(&key);
             } else {
                 break;
             }
@@ -215,37 +907,286 @@ impl JsFileStreamer {
     /// 这个方法发送请求到 JS，然后阻塞等待响应。
     /// JS 端需要在获取数据后调用 completeRequest 来发送响应。
     fn fetch_block(&self, offset: u64, size: u32) -> io::Result<Vec<u8>> {
-        // 先检查缓存
+        self.state.record_block_use(self.state.tuning.block_offset(offset));
+
+        // 先检查整个文件和缓存
+        if let Some(data) = self.state.read_full(offset, size) {
+            return Ok(data);
+        }
         if let Some(data) = self.read_from_cache(offset, size) {
             return Ok(data);
         }
 
-        self.state.stats.lock().unwrap().cache_misses += 1;
-        self.state.stats.lock().unwrap().total_requests += 1;
+        let block_offset = self.state.tuning.block_offset(offset);
+
+        // 已经在预取的块直接等待其响应，否则发起新请求
+        let prefetched = self.prefetching.borrow_mut().remove(&block_offset);
+        if prefetched.is_none() && self.fall_back_to_full() {
+            if let Some(data) = self.state.read_full(offset, size) {
+                return Ok(data);
+            }
+        }
+        // 顺序读取时合并读取后续的块，或并行请求后续的块
+        let sequential = (self.fetch_lanes > 1 || self.readahead > 0) && self.is_sequential(block_offset);
+        let block_size = self.state.tuning.block_size;
+        let (request_id, rx, blocks) = match prefetched {
+            Some((request_id, rx)) => {
+                self.state.stats.lock().unwrap().prefetch_hits += 1;
+                (request_id, rx, 1)
+            }
+            None => {
+                self.state.stats.lock().unwrap().cache_misses += 1;
+                let blocks = if sequential { 1 + self.readahead_blocks(block_offset) } else { 1 };
+                let (request_id, rx) = self.request_blocks(block_offset, blocks)?;
+                (request_id, rx, blocks)
+            }
+        };
+        let last_block = block_offset + (blocks as u64 - 1) * block_size;
+        if blocks > 1 {
+            // 合并读取覆盖的块随后多半命中缓存，不再经过这里，以最后一块作为下一次顺序判断的起点
+            self.last_block.set(Some(last_block));
+            self.state.stats.lock().unwrap().prefetched_blocks += blocks - 1;
+        }
+        if self.fetch_lanes > 1 && sequential {
+            self.prefetch_after(last_block);
+        }
+
+        let data = Arc::new(self.wait_response(request_id, rx, self.state.tuning.fetch_timeout)?);
+        self.state.stats.lock().unwrap().total_bytes_fetched += data.len() as u64;
+
+        // 按块写入缓存（共享同一份响应，不复制），合并读取的后续块记为预取
+        let chunks = chunk_ranges(data.len(), block_size as usize);
+        let first = Block::slice(&data, chunks.first().cloned().unwrap_or(0..0));
+        for (index, range) in chunks.into_iter().enumerate().skip(1) {
+            let block = block_offset + index as u64 * block_size;
+            self.state.record_block_data(block, range.len(), false);
+            self.write_to_cache(block, Block::slice(&data, range));
+            self.state.readahead_blocks.lock().unwrap().insert(block);
+        }
+        self.state.record_block_data(block_offset, first.len(), false);
+        self.write_to_cache(block_offset, first.clone());
+
+        // 返回请求的部分
+        let offset_in_block = (offset - block_offset) as usize;
+        let available = first.len().saturating_sub(offset_in_block);
+        let read_size = (size as usize).min(available);
+
+        Ok(first[offset_in_block..offset_in_block + read_size].to_vec())
+    }
+
+    /// 合并读取时随 `block_offset` 一起请求的后续块数：不超过文件末尾，遇到已缓存或正在预取的块为止
+    fn readahead_blocks(&self, block_offset: u64) -> u32 {
+        let block_size = self.state.tuning.block_size;
+        let cache = self.state.cache.lock().unwrap();
+        let prefetching = self.prefetching.borrow();
+        (1..=self.readahead)
+            .take_while(|&n| {
+                let next = block_offset + n as u64 * block_size;
+                self.file_size.is_none_or(|size| next < size) && !cache.contains_key(&next) && !prefetching.contains_key(&next)
+            })
+            .count() as u32
+    }
+
+    /// 按取数计划一次批量获取 `ranges`（[start, end)）覆盖的块，已缓存的块跳过
+    ///
+    /// 超过缓存块数一半的部分不获取，渲染时照常逐块读取。返回获取的块数。
+    pub fn fetch_planned(&self, ranges: &[(u64, u64)]) -> io::Result<u32> {
+        let Some(file_size) = self.file_size else {
+            return Ok(0);
+        };
+        let tuning = self.state.tuning;
+        let block_size = |block: u64| tuning.block_size.min(file_size - block) as u32;
+        let (blocks, segments) = self.missing_segments(ranges, file_size);
+        if blocks == 0 {
+            return Ok(0);
+        }
+
+        let (first, _) = segments[0];
+        let (last, last_size) = segments[segments.len() - 1];
+        let span = (last + last_size as u64 - first) as u32;
+        let (request_id, rx) = self.send_request(first, span, segments.clone(), false)?;
+        let data = Arc::new(self.wait_response(request_id, rx, tuning.fetch_timeout)?);
+
+        // 各段数据的拼接，或整个跨度的数据
+        let total: usize = segments.iter().map(|(_, size)| *size as usize).sum();
+        let concatenated = data.len() == total;
+        if !concatenated && data.len() != span as usize {
+            return Err(io::Error::other(format!(
+                "Planned fetch returned {} bytes, expected {} (ranges) or {} (span)",
+                data.len(),
+                total,
+                span
+            )));
+        }
+        self.state.stats.lock().unwrap().total_bytes_fetched += data.len() as u64;
+        let mut cursor = 0;
+        for (offset, size) in segments {
+            let start = if concatenated { cursor } else { (offset - first) as usize };
+            let mut block = offset;
+            while block < offset + size as u64 {
+                let len = block_size(block) as usize;
+                let begin = start + (block - offset) as usize;
+                self.state.record_block_data(block, len, false);
+                self.write_to_cache(block, Block::slice(&data, begin..begin + len));
+                block += tuning.block_size;
+            }
+            cursor += size as usize;
+        }
+        self.state.stats.lock().unwrap().planned_blocks += blocks;
+        Ok(blocks)
+    }
+
+    /// 并发获取 `ranges`（[start, end)）覆盖的块，每段相邻的块一个请求，已缓存的块跳过
+    ///
+    /// 与 [`Self::fetch_planned`] 不同，不使用带 ranges 的批量请求，fetcher 不需要支持多段。
+    /// 获取的块计为预取，超过缓存块数一半的部分不获取。返回获取的块数。
+    pub fn prefetch_ranges(&self, ranges: &[(u64, u64)]) -> io::Result<u32> {
+        let Some(file_size) = self.file_size else {
+            return Ok(0);
+        };
+        let block_size = self.state.tuning.block_size;
+        let (blocks, segments) = self.missing_segments(ranges, file_size);
+        let in_flight = segments
+            .iter()
+            .map(|&(offset, size)| self.send_request(offset, size, Vec::new(), false))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        for ((offset, size), (request_id, rx)) in segments.into_iter().zip(in_flight) {
+            let data = Arc::new(self.wait_response(request_id, rx, self.state.tuning.fetch_timeout)?);
+            if data.len() != size as usize {
+                return Err(io::Error::other(format!(
+                    "Prefetch at {} returned {} bytes, expected {}",
+                    offset,
+                    data.len(),
+                    size
+                )));
+            }
+            self.state.stats.lock().unwrap().total_bytes_fetched += data.len() as u64;
+            for (index, range) in chunk_ranges(data.len(), block_size as usize).into_iter().enumerate() {
+                let block = offset + index as u64 * block_size;
+                self.state.record_block_data(block, range.len(), false);
+                self.write_to_cache(block, Block::slice(&data, range));
+                self.state.readahead_blocks.lock().unwrap().insert(block);
+            }
+        }
+        self.state.stats.lock().unwrap().prefetched_blocks += blocks;
+        Ok(blocks)
+    }
+
+    /// `ranges` 覆盖、尚未缓存的块（不超过缓存块数的一半），相邻的块合并为 (偏移, 长度) 段
+    fn missing_segments(&self, ranges: &[(u64, u64)], file_size: u64) -> (u32, Vec<(u64, u32)>) {
+        let tuning = self.state.tuning;
+        let mut blocks: Vec<u64> = Vec::new();
+        {
+            let cache = self.state.cache.lock().unwrap();
+            for &(start, end) in ranges {
+                let mut block = tuning.block_offset(start);
+                while block < end.min(file_size) {
+                    if !cache.contains_key(&block) {
+                        blocks.push(block);
+                    }
+                    block += tuning.block_size;
+                }
+            }
+        }
+        blocks.sort_unstable();
+        blocks.dedup();
+        blocks.truncate(tuning.max_planned_blocks());
+
+        let block_size = |block: u64| tuning.block_size.min(file_size - block) as u32;
+        let mut segments: Vec<(u64, u32)> = Vec::new();
+        for &block in &blocks {
+            match segments.last_mut() {
+                Some((offset, size)) if *offset + *size as u64 == block => *size += block_size(block),
+                _ => segments.push((block, block_size(block))),
+            }
+        }
+        (blocks.len() as u32, segments)
+    }
 
-        // 计算要获取的块大小（至少获取一个缓存块大小）
-        let block_offset = Self::cache_block_offset(offset);
-        let remaining = self.file_size.saturating_sub(block_offset);
-        let fetch_size = CACHE_BLOCK_SIZE.min(remaining) as u32;
+    /// 向 JS 发出从 `block_offset` 开始、连续 `blocks` 块的请求（不等待响应）
+    fn request_blocks(&self, block_offset: u64, blocks: u32) -> io::Result<InFlight> {
+        // 计算要获取的大小（按整块获取，长度未知时总是请求整块）
+        let span = self.state.tuning.block_size * blocks as u64;
+        let fetch_size = match self.file_size {
+            Some(file_size) => span.min(file_size.saturating_sub(block_offset)) as u32,
+            None => span as u32,
+        };
 
         if fetch_size == 0 {
             return Err(io::Error::new(
                 io::ErrorKind::UnexpectedEof,
-                format!("fetch_size is 0: offset={}, block_offset={}, file_size={}", offset, block_offset, self.file_size),
+                format!("fetch_size is 0: block_offset={}, file_size={:?}", block_offset, self.file_size),
             ));
         }
 
+        self.send_request(block_offset, fetch_size, Vec::new(), false)
+    }
+
+    /// 按回退条件改为获取整个文件，返回之后能否从内存读取
+    ///
+    /// 每个任务最多尝试一次；失败时记录原因，调用方继续逐块读取。
+    fn fall_back_to_full(&self) -> bool {
+        let Some(file_size) = self.file_size.filter(|&size| size > 0 && size <= u32::MAX as u64) else {
+            return false;
+        };
+        let stats = self.state.stats.lock().unwrap().clone();
+        let Some(reason) = self.fallback.trigger(&stats, file_size) else {
+            return false;
+        };
+        {
+            let mut fallback = self.state.fallback.lock().unwrap();
+            if fallback.is_some() {
+                return false;
+            }
+            *fallback = Some(Fallback { reason, error: None });
+        }
+
+        let result = self
+            .send_request(0, file_size as u32, Vec::new(), true)
+            .and_then(|(request_id, rx)| {
+                self.wait_response(request_id, rx, FULL_RESPONSE_TIMEOUT.max(self.state.tuning.fetch_timeout))
+            })
+            .map_err(|e| e.to_string())
+            .and_then(|data| {
+                if data.len() as u64 == file_size {
+                    Ok(data)
+                } else {
+                    Err(format!("Full fetch returned {} bytes, expected {}", data.len(), file_size))
+                }
+            });
+        match result {
+            Ok(data) => {
+                self.state.stats.lock().unwrap().total_bytes_fetched += data.len() as u64;
+                *self.state.full_data.lock().unwrap() = Some(Arc::new(data));
+                // 之后的读取都从整个文件返回，块缓存不再需要
+                self.state.cache.lock().unwrap().clear();
+                true
+            }
+            Err(e) => {
+                if let Some(fallback) = self.state.fallback.lock().unwrap().as_mut() {
+                    fallback.error = Some(e);
+                }
+                false
+            }
+        }
+    }
+
+    /// 注册请求并发送给 JS（不等待响应）
+    fn send_request(&self, offset: u64, size: u32, ranges: Vec<(u64, u32)>, full: bool) -> io::Result<InFlight> {
         // 创建 channel 用于接收响应
-        let (tx, rx) = mpsc::channel::<Result<Vec<u8>, String>>();
+        let (tx, rx) = mpsc::channel::<Response>();
 
         // 生成请求 ID 并注册
         let request_id = self.state.next_id();
-        self.state.register_request(request_id, tx);
+        self.state.register_request(request_id, tx)?;
 
         let request = BlockRequest {
-            offset: block_offset,
-            size: fetch_size,
+            offset,
+            size,
             request_id,
+            ranges,
+            full,
         };
 
         // 发送请求到 JS（非阻塞）
@@ -253,54 +1194,116 @@ impl JsFileStreamer {
 
         if status != napi::Status::Ok {
             // 移除待处理的请求
-            self.state.pending_requests.lock().unwrap().remove(&request_id);
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("ThreadsafeFunction call failed with status: {:?}", status),
-            ));
+            self.state.abandon_request(request_id);
+            return Err(io::Error::other(format!(
+                "ThreadsafeFunction call failed with status: {:?}",
+                status
+            )));
         }
 
-        // 阻塞等待响应（超时 30 秒）
-        let result = rx
-            .recv_timeout(std::time::Duration::from_secs(30))
-            .map_err(|e| {
-                // 移除待处理的请求
-                self.state.pending_requests.lock().unwrap().remove(&request_id);
-                io::Error::new(
-                    io::ErrorKind::TimedOut,
-                    format!("Timeout waiting for JS response: {}", e),
-                )
-            })?;
+        self.state.stats.lock().unwrap().total_requests += 1;
+        Ok((request_id, rx))
+    }
 
-        match result {
-            Ok(data) => {
-                self.state.stats.lock().unwrap().total_bytes_fetched += data.len() as u64;
+    /// 阻塞等待请求的响应
+    fn wait_response(
+        &self,
+        request_id: u64,
+        rx: mpsc::Receiver<Response>,
+        timeout: std::time::Duration,
+    ) -> io::Result<ResponseData> {
+        let result = rx.recv_timeout(timeout).map_err(|e| {
+            // 移除待处理的请求；JS 之后再响应时记为迟到响应
+            if self.state.pending_requests.lock().unwrap().remove(&request_id).is_some() {
+                self.state.record_orphaned(1);
+            }
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Timeout waiting for JS response: {}", e),
+            )
+        })?;
 
-                // 写入缓存
-                self.write_to_cache(block_offset, data.clone());
+        result.map_err(|e| io::Error::other(format!("Failed to fetch block: {}", e)))
+    }
 
-                // 返回请求的部分
-                let offset_in_block = (offset - block_offset) as usize;
-                let available = data.len().saturating_sub(offset_in_block);
-                let read_size = (size as usize).min(available);
+    /// 记录本次获取的块，并判断是否紧接着上一次获取的块
+    fn is_sequential(&self, block_offset: u64) -> bool {
+        let previous = self.last_block.replace(Some(block_offset));
+        block_offset
+            .checked_sub(self.state.tuning.block_size)
+            .is_some_and(|expected| previous == Some(expected))
+    }
 
-                Ok(data[offset_in_block..offset_in_block + read_size].to_vec())
+    /// 为后续 `fetch_lanes - 1` 个块发出预取请求（已缓存或已在预取的块跳过）
+    fn prefetch_after(&self, block_offset: u64) {
+        for lane in 1..self.fetch_lanes as u64 {
+            let next = block_offset + lane * self.state.tuning.block_size;
+            if self.file_size.is_some_and(|size| next >= size) {
+                break;
+            }
+            if self.prefetching.borrow().contains_key(&next)
+                || self.state.cache.lock().unwrap().contains_key(&next)
+            {
+                continue;
+            }
+            // 预取失败不影响当前读取，真正需要该块时会重新请求
+            match self.request_blocks(next, 1) {
+                Ok(in_flight) => {
+                    self.prefetching.borrow_mut().insert(next, in_flight);
+                    self.state.stats.lock().unwrap().prefetched_blocks += 1;
+                }
+                Err(_) => break,
             }
-            Err(e) => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to fetch block: {}", e),
-            )),
+        }
+    }
+}
+
+/// 把长度为 `len` 的响应按 `block_size` 切分成各缓存块在响应中的范围
+fn chunk_ranges(len: usize, block_size: usize) -> Vec<Range<usize>> {
+    (0..len).step_by(block_size).map(|start| start..(start + block_size).min(len)).collect()
+}
+
+/// 预填充数据 `[offset, offset + len)` 完整覆盖的缓存块：(块偏移, 数据中的范围)
+fn seed_ranges(
+    offset: u64,
+    len: usize,
+    file_size: Option<u64>,
+    block_size: u64,
+) -> Vec<(u64, std::ops::Range<usize>)> {
+    let end = offset + len as u64;
+    let mut blocks = Vec::new();
+    let mut block = offset.div_ceil(block_size) * block_size;
+    while block < end {
+        let block_end = match file_size {
+            Some(size) => (block + block_size).min(size),
+            None => block + block_size,
+        };
+        if block_end > end || block_end <= block {
+            break;
+        }
+        blocks.push((block, (block - offset) as usize..(block_end - offset) as usize));
+        block += block_size;
+    }
+    blocks
+}
+
+impl Drop for JsFileStreamer {
+    fn drop(&mut self) {
+        // 未使用的预取请求不再有人等待，提前从表中移除
+        for (request_id, _) in self.prefetching.get_mut().values() {
+            self.state.abandon_request(*request_id);
         }
     }
 }
 
 impl Read for JsFileStreamer {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        if self.position >= self.file_size {
+        let file_size = self.total_size()?;
+        if self.position >= file_size {
             return Ok(0);
         }
 
-        let remaining = self.file_size - self.position;
+        let remaining = file_size - self.position;
         let to_read = (buf.len() as u64).min(remaining) as u32;
 
         if to_read == 0 {
@@ -319,21 +1322,48 @@ impl Read for JsFileStreamer {
 
 impl Seek for JsFileStreamer {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
-        let new_pos = match pos {
-            SeekFrom::Start(offset) => offset as i64,
-            SeekFrom::End(offset) => self.file_size as i64 + offset,
-            SeekFrom::Current(offset) => self.position as i64 + offset,
+        // 只有从末尾定位时才需要文件大小；大小未知时用 maxSize 作为上限，避免为定位扫描整个文件
+        let limit = match pos {
+            SeekFrom::End(_) => Some(self.total_size()?),
+            _ => self.file_size.or(self.max_size),
         };
+        self.position = seek_target(pos, self.position, limit)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(self.position)
+    }
+}
 
-        if new_pos < 0 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Seek to negative position",
-            ));
-        }
+/// 越界的定位请求
+///
+/// 畸形 PDF 的交叉引用表可能让 PDFium 定位到负数或远超文件末尾的偏移，通过网络读取时
+/// 每次越界定位都可能触发无效的请求，因此直接返回错误（可从 `io::Error` 中 downcast 取出）。
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SeekError {
+    #[error("Seek to negative position ({base} + {offset})")]
+    Negative { base: u64, offset: i64 },
+    #[error("Seek position overflows ({base} + {offset})")]
+    Overflow { base: u64, offset: i64 },
+    #[error("Seek past end of file (position {position}, limit {limit})")]
+    PastEnd { position: u64, limit: u64 },
+}
 
-        self.position = new_pos as u64;
-        Ok(self.position)
+/// 计算定位后的位置：允许定位到文件末尾（之后读取返回 0），超出 `limit` 时报错
+fn seek_target(pos: SeekFrom, position: u64, limit: Option<u64>) -> Result<u64, SeekError> {
+    let relative = |base: u64, offset: i64| {
+        base.checked_add_signed(offset).ok_or(if offset < 0 {
+            SeekError::Negative { base, offset }
+        } else {
+            SeekError::Overflow { base, offset }
+        })
+    };
+    let target = match pos {
+        SeekFrom::Start(offset) => offset,
+        SeekFrom::End(offset) => relative(limit.unwrap_or(0), offset)?,
+        SeekFrom::Current(offset) => relative(position, offset)?,
+    };
+    match limit {
+        Some(limit) if target > limit => Err(SeekError::PastEnd { position: target, limit }),
+        _ => Ok(target),
     }
 }
 
@@ -343,15 +1373,204 @@ mod tests {
 
     #[test]
     fn test_cache_block_offset() {
-        assert_eq!(JsFileStreamer::cache_block_offset(0), 0);
-        assert_eq!(JsFileStreamer::cache_block_offset(100), 0);
+        let tuning = CacheTuning::default();
+        assert_eq!(tuning.block_offset(0), 0);
+        assert_eq!(tuning.block_offset(100), 0);
+        assert_eq!(tuning.block_offset(CACHE_BLOCK_SIZE), CACHE_BLOCK_SIZE);
+        assert_eq!(tuning.block_offset(CACHE_BLOCK_SIZE + 100), CACHE_BLOCK_SIZE);
+
+        let tuning = CacheTuning::new(Some(1024 * 1024), None, None);
+        assert_eq!(tuning.block_offset(CACHE_BLOCK_SIZE * 5), 1024 * 1024);
+    }
+
+    #[test]
+    fn test_cache_tuning() {
+        assert_eq!(CacheTuning::new(None, None, None), CacheTuning::default());
+        assert_eq!(CacheTuning::new(Some(0), Some(0), Some(0)), CacheTuning::default());
+
+        let tuning = CacheTuning::new(Some(1), Some(1), Some(5000));
+        assert_eq!(tuning.block_size, 16 * 1024);
+        assert_eq!(tuning.max_cache_blocks, 2);
+        assert_eq!(tuning.max_planned_blocks(), 1);
+        assert_eq!(tuning.fetch_timeout, std::time::Duration::from_secs(5));
+        assert_eq!(CacheTuning::new(Some(u32::MAX), Some(u32::MAX), None).block_size, 16 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_seek_target() {
+        assert_eq!(seek_target(SeekFrom::Start(100), 0, Some(1000)), Ok(100));
+        assert_eq!(seek_target(SeekFrom::Start(1000), 0, Some(1000)), Ok(1000));
+        assert_eq!(seek_target(SeekFrom::End(-10), 0, Some(1000)), Ok(990));
+        assert_eq!(seek_target(SeekFrom::Current(-50), 100, Some(1000)), Ok(50));
+        // 大小未知时不限制上界
+        assert_eq!(seek_target(SeekFrom::Start(u64::MAX), 0, None), Ok(u64::MAX));
+
+        assert_eq!(
+            seek_target(SeekFrom::Start(1001), 0, Some(1000)),
+            Err(SeekError::PastEnd { position: 1001, limit: 1000 })
+        );
+        assert_eq!(
+            seek_target(SeekFrom::End(1), 0, Some(1000)),
+            Err(SeekError::PastEnd { position: 1001, limit: 1000 })
+        );
+        assert_eq!(
+            seek_target(SeekFrom::End(i64::MIN), 0, Some(1000)),
+            Err(SeekError::Negative { base: 1000, offset: i64::MIN })
+        );
         assert_eq!(
-            JsFileStreamer::cache_block_offset(CACHE_BLOCK_SIZE),
-            CACHE_BLOCK_SIZE
+            seek_target(SeekFrom::Current(-101), 100, Some(1000)),
+            Err(SeekError::Negative { base: 100, offset: -101 })
         );
         assert_eq!(
-            JsFileStreamer::cache_block_offset(CACHE_BLOCK_SIZE + 100),
-            CACHE_BLOCK_SIZE
+            seek_target(SeekFrom::Current(i64::MAX), u64::MAX - 1, None),
+            Err(SeekError::Overflow { base: u64::MAX - 1, offset: i64::MAX })
         );
     }
+
+    #[test]
+    fn test_seed_ranges() {
+        let block = CACHE_BLOCK_SIZE as usize;
+        // 从文件开头提供 2.5 块：只写入完整的两块
+        assert_eq!(
+            seed_ranges(0, block * 5 / 2, None, CACHE_BLOCK_SIZE),
+            vec![(0, 0..block), (CACHE_BLOCK_SIZE, block..block * 2)]
+        );
+        // 不从块边界开始：跳过第一个不完整的块
+        assert_eq!(seed_ranges(100, block, None, CACHE_BLOCK_SIZE), vec![]);
+        assert_eq!(seed_ranges(100, block * 2, None, CACHE_BLOCK_SIZE), vec![(CACHE_BLOCK_SIZE, block - 100..block * 2 - 100)]);
+        // 文件长度已知时，末尾不足一块的部分也写入
+        let size = CACHE_BLOCK_SIZE + 10;
+        assert_eq!(
+            seed_ranges(0, size as usize, Some(size), CACHE_BLOCK_SIZE),
+            vec![(0, 0..block), (CACHE_BLOCK_SIZE, block..block + 10)]
+        );
+    }
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(chunk_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(chunk_ranges(8, 4), vec![0..4, 4..8]);
+        assert!(chunk_ranges(0, 4).is_empty());
+
+        // 各块共享同一份响应
+        let data = Arc::new(ResponseData::from(vec![1, 2, 3, 4, 5]));
+        let second = Block::slice(&data, 4..5);
+        assert_eq!(&*second, &[5]);
+        assert_eq!(Arc::strong_count(&data), 2);
+    }
+
+    #[test]
+    fn test_reused_blocks() {
+        let state = SharedState::new(1, CacheTuning::default());
+        state.record_block_use(0);
+        state.record_block_data(0, 1024, false);
+        state.set_current_page(1);
+        state.record_block_use(0);
+        state.record_block_use(CACHE_BLOCK_SIZE);
+        state.set_current_page(2);
+        state.record_block_use(0);
+        state.record_block_use(0);
+
+        let reused = state.reused_blocks();
+        assert_eq!(reused.len(), 1);
+        assert_eq!(
+            reused[0],
+            (0, BlockUsage { size: 1024, pages: vec![0, 1, 2], fetches: 1, seeded: false })
+        );
+    }
+
+    #[test]
+    fn test_report_file_size_keeps_known_size() {
+        let state = SharedState::new(1, CacheTuning::default());
+        assert_eq!(state.file_size(), None);
+        state.report_file_size(4096);
+        state.report_file_size(1024);
+        assert_eq!(state.file_size(), Some(4096));
+    }
+
+    #[test]
+    fn test_tuning_hints() {
+        let state = SharedState::new(1, CacheTuning::default());
+        state.set_file_size(CACHE_BLOCK_SIZE * 10);
+        state.record_header(b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>");
+        assert_eq!(state.linearized(), Some(false));
+        for block in 0..9 {
+            state.record_block_use(block * CACHE_BLOCK_SIZE);
+            state.record_block_data(block * CACHE_BLOCK_SIZE, CACHE_BLOCK_SIZE as usize, false);
+        }
+        state.record_block_data(0, CACHE_BLOCK_SIZE as usize, false);
+        {
+            let mut stats = state.stats.lock().unwrap();
+            stats.cache_misses = 10;
+            stats.cache_hits = 30;
+            stats.total_bytes_fetched = CACHE_BLOCK_SIZE * 9;
+        }
+
+        let codes: Vec<&str> = state.tuning_hints(1).iter().map(|h| h.code).collect();
+        assert_eq!(codes, vec!["FULL_DOWNLOAD", "ENABLE_FETCH_LANES", "REFETCHED_BLOCKS", "LOW_HIT_RATIO"]);
+        // 已开启并行请求时不再建议
+        assert!(!state.tuning_hints(4).iter().any(|h| h.code == "ENABLE_FETCH_LANES"));
+
+        // 预取的块大多没有被读取
+        {
+            let mut stats = state.stats.lock().unwrap();
+            stats.prefetched_blocks = 20;
+            stats.prefetch_hits = 4;
+        }
+        let codes: Vec<&str> = state.tuning_hints(1).iter().map(|h| h.code).collect();
+        assert!(codes.contains(&"WASTED_PREFETCH") && !codes.contains(&"ENABLE_FETCH_LANES"));
+
+        let state = SharedState::new(2, CacheTuning::default());
+        state.record_header(b"%PDF-1.5\n%\xe2\xe3\n1 0 obj\n<< /Linearized 1 /L 1000 >>");
+        assert_eq!(state.linearized(), Some(true));
+        assert!(state.tuning_hints(1).is_empty());
+    }
+
+    #[test]
+    fn test_fallback_trigger() {
+        let stats = StreamerStats {
+            total_requests: 12,
+            total_bytes_fetched: 600,
+            ..Default::default()
+        };
+        assert_eq!(FallbackPolicy::default().trigger(&stats, 1000), None);
+
+        let by_fraction = FallbackPolicy { fetched_fraction: Some(0.5), requests: None };
+        assert_eq!(by_fraction.trigger(&stats, 1000).as_deref(), Some("Fetched 60% of the file"));
+        assert_eq!(by_fraction.trigger(&stats, 2000), None);
+
+        let by_requests = FallbackPolicy { fetched_fraction: None, requests: Some(12) };
+        assert_eq!(by_requests.trigger(&stats, 2000).as_deref(), Some("Made 12 requests"));
+    }
+
+    #[test]
+    fn test_request_id_keeps_task_id_above_16_bits() {
+        let state = SharedState::new(70_000, CacheTuning::default());
+        let first = state.next_id();
+        assert_eq!(first >> 16, 70_000);
+        assert_eq!(state.next_id(), first + 1);
+        assert_ne!(first >> 16, SharedState::new(70_000 & 0xFFFF, CacheTuning::default()).next_id() >> 16);
+    }
+
+    #[test]
+    fn test_pending_request_limit_and_release() {
+        let state = SharedState::new(3, CacheTuning::default());
+        let mut receivers = Vec::new();
+        for id in 0..MAX_PENDING_REQUESTS as u64 {
+            let (tx, rx) = mpsc::channel();
+            state.register_request(id, tx).unwrap();
+            receivers.push(rx);
+        }
+        let (tx, _rx) = mpsc::channel();
+        assert!(state.register_request(999, tx).is_err());
+
+        assert!(state.complete_request(0, Ok(vec![1].into())));
+        assert_eq!(state.pending_count(), MAX_PENDING_REQUESTS - 1);
+
+        // 任务结束时剩余请求记为孤立，之后的响应记为迟到
+        assert_eq!(state.release_pending(), MAX_PENDING_REQUESTS as u32 - 1);
+        assert_eq!(state.pending_count(), 0);
+        assert_eq!(state.orphaned_requests(), MAX_PENDING_REQUESTS as u32 - 1);
+        assert!(!state.complete_request(1, Ok(vec![1].into())));
+    }
 }
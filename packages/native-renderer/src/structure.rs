@@ -0,0 +1,212 @@
+//! 标签 PDF 的结构树
+//!
+//! 读取页面结构树中的图形元素（Figure 等）及其替代文字，并把题注（Caption）
+//! 关联到对应的图形，供无障碍流程给生成的页面图像自动附加替代文字。
+//! pdfium-render 没有封装结构树，这里直接调用 PDFium 的 FPDF_StructTree/StructElement 接口。
+
+use pdfium_render::prelude::*;
+use std::os::raw::{c_int, c_ulong, c_void};
+
+/// 结构树最多展开的层级和元素数（防止损坏文档中的循环引用）
+const MAX_DEPTH: u32 = 64;
+const MAX_ELEMENTS: usize = 10_000;
+
+/// 结构元素
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructNode {
+    /// 结构类型（/S），如 Figure、Caption、P
+    pub kind: String,
+    pub alt_text: Option<String>,
+    pub actual_text: Option<String>,
+    pub title: Option<String>,
+    /// 直接引用的标记内容 ID
+    pub mcids: Vec<i32>,
+    pub children: Vec<StructNode>,
+}
+
+impl StructNode {
+    /// 自身及所有后代引用的标记内容 ID
+    pub fn all_mcids(&self) -> Vec<i32> {
+        let mut ids = self.mcids.clone();
+        for child in &self.children {
+            ids.extend(child.all_mcids());
+        }
+        ids
+    }
+
+    fn is(&self, kind: &str) -> bool {
+        self.kind == kind
+    }
+}
+
+/// 图形元素及关联的题注
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figure<'n> {
+    pub node: &'n StructNode,
+    pub caption: Option<&'n StructNode>,
+}
+
+/// 读取页面的结构树，未打标签的页面返回空列表
+pub fn page_structure(page: &PdfPage) -> Vec<StructNode> {
+    let bindings = page.bindings();
+    let tree = bindings.FPDF_StructTree_GetForPage(bindings.get_handle_from_page(page));
+    if tree.is_null() {
+        return vec![];
+    }
+    let mut budget = MAX_ELEMENTS;
+    let count = bindings.FPDF_StructTree_CountChildren(tree).max(0);
+    let nodes = (0..count)
+        .map(|i| bindings.FPDF_StructTree_GetChildAtIndex(tree, i))
+        .filter(|element| !element.is_null())
+        .filter_map(|element| read_element(bindings, element, 0, &mut budget))
+        .collect();
+    bindings.FPDF_StructTree_Close(tree);
+    nodes
+}
+
+fn read_element(
+    bindings: &dyn PdfiumLibraryBindings,
+    element: FPDF_STRUCTELEMENT,
+    depth: u32,
+    budget: &mut usize,
+) -> Option<StructNode> {
+    if depth > MAX_DEPTH || *budget == 0 {
+        return None;
+    }
+    *budget -= 1;
+
+    let mcid_count = bindings.FPDF_StructElement_GetMarkedContentIdCount(element).max(0);
+    let mcids = (0..mcid_count)
+        .map(|i| bindings.FPDF_StructElement_GetMarkedContentIdAtIndex(element, i))
+        .filter(|id| *id >= 0)
+        .collect();
+    let child_count = bindings.FPDF_StructElement_CountChildren(element).max(0);
+    let children = (0..child_count)
+        .map(|i| bindings.FPDF_StructElement_GetChildAtIndex(element, i))
+        // 标记内容引用（MCR）不是结构元素，已经计入 mcids
+        .filter(|child| !child.is_null())
+        .filter_map(|child| read_element(bindings, child, depth + 1, budget))
+        .collect();
+
+    Some(StructNode {
+        kind: read_string(|buf, len| bindings.FPDF_StructElement_GetType(element, buf, len)).unwrap_or_default(),
+        alt_text: read_string(|buf, len| bindings.FPDF_StructElement_GetAltText(element, buf, len)),
+        actual_text: read_string(|buf, len| bindings.FPDF_StructElement_GetActualText(element, buf, len)),
+        title: read_string(|buf, len| bindings.FPDF_StructElement_GetTitle(element, buf, len)),
+        mcids,
+        children,
+    })
+}
+
+/// 调用返回 UTF-16LE 字符串的 PDFium 接口（先取长度再取内容），空字符串返回 None
+pub fn read_string(mut get: impl FnMut(*mut c_void, c_ulong) -> c_ulong) -> Option<String> {
+    let len = get(std::ptr::null_mut(), 0);
+    if len <= 2 {
+        return None;
+    }
+    let mut buffer = vec![0u8; len as usize];
+    get(buffer.as_mut_ptr() as *mut c_void, len);
+    decode_utf16le(&buffer)
+}
+
+fn decode_utf16le(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    let text = String::from_utf16_lossy(&units).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// 标记内容 ID（不在标记内容中的对象为 -1）
+pub fn marked_content_id(bindings: &dyn PdfiumLibraryBindings, object: &PdfPageObject) -> c_int {
+    bindings.FPDFPageObj_GetMarkedContentID(bindings.get_handle_from_object(object))
+}
+
+/// 找出图形元素并关联题注
+///
+/// Figure 和 Formula 总是列出，其他类型只在带替代文字时列出。题注取图形内部的
+/// Caption，没有时取紧跟其后的兄弟 Caption，再没有时取紧挨其前、且没有被前一个图形占用的 Caption。
+pub fn collect_figures(nodes: &[StructNode]) -> Vec<Figure<'_>> {
+    let mut figures = Vec::new();
+    collect_into(nodes, &mut figures);
+    figures
+}
+
+fn is_figure(node: &StructNode) -> bool {
+    node.is("Figure") || node.is("Formula") || (node.alt_text.is_some() && !node.is("Caption"))
+}
+
+fn collect_into<'n>(siblings: &'n [StructNode], figures: &mut Vec<Figure<'n>>) {
+    for (i, node) in siblings.iter().enumerate() {
+        if is_figure(node) {
+            let inner = node.children.iter().find(|c| c.is("Caption"));
+            let next = siblings.get(i + 1).filter(|n| n.is("Caption"));
+            let previous = i
+                .checked_sub(1)
+                .map(|p| &siblings[p])
+                .filter(|n| n.is("Caption"))
+                // 前一个 Caption 如果紧跟在另一个图形之后，属于那个图形
+                .filter(|_| i < 2 || !is_figure(&siblings[i - 2]));
+            figures.push(Figure {
+                node,
+                caption: inner.or(next).or(previous),
+            });
+            // 图形内部的元素（包括其中的 Caption）不再单独列出
+            continue;
+        }
+        collect_into(&node.children, figures);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(kind: &str, children: Vec<StructNode>) -> StructNode {
+        StructNode {
+            kind: kind.to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+
+    fn alt(mut node: StructNode, text: &str) -> StructNode {
+        node.alt_text = Some(text.to_string());
+        node
+    }
+
+    #[test]
+    fn test_collect_figures_associates_captions() {
+        let tree = vec![node(
+            "Document",
+            vec![
+                node("P", vec![]),
+                alt(node("Figure", vec![]), "chart"),
+                node("Caption", vec![]),
+                node("Caption", vec![]),
+                node("Figure", vec![]),
+                node("Figure", vec![node("Caption", vec![])]),
+                alt(node("Link", vec![]), "homepage"),
+            ],
+        )];
+        let figures = collect_figures(&tree);
+        let siblings = &tree[0].children;
+        assert_eq!(figures.len(), 4);
+        assert_eq!(figures[0].node.alt_text.as_deref(), Some("chart"));
+        assert!(std::ptr::eq(figures[0].caption.unwrap(), &siblings[2]));
+        // 紧挨在前的 Caption 属于前一个图形时不重复关联，这里前面是 siblings[3]
+        assert!(std::ptr::eq(figures[1].caption.unwrap(), &siblings[3]));
+        assert!(std::ptr::eq(figures[2].caption.unwrap(), &siblings[5].children[0]));
+        assert_eq!(figures[3].node.kind, "Link");
+        assert!(figures[3].caption.is_none());
+    }
+
+    #[test]
+    fn test_decode_utf16le() {
+        let bytes: Vec<u8> = "图 1 Chart\0".encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        assert_eq!(decode_utf16le(&bytes).as_deref(), Some("图 1 Chart"));
+        assert_eq!(decode_utf16le(&[0, 0]), None);
+    }
+}
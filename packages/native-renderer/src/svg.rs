@@ -0,0 +1,305 @@
+//! 矢量 SVG 导出（实验性）
+//!
+//! 遍历页面对象，将路径、文字和图片转换为对应的 SVG 元素。
+//! 输出是近似的：不处理裁剪路径、渐变/着色对象和字体嵌入，
+//! 文字使用系统字体按 PDF 的位置和字号排布。
+
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+use pdfium_render::prelude::*;
+use std::fmt::Write;
+use std::io::Cursor;
+
+/// 内嵌 JPEG 图片的质量
+const EMBEDDED_JPEG_QUALITY: u8 = 85;
+
+/// 将单个页面转换为 SVG 文本
+pub fn page_to_svg(document: &PdfDocument, page: &PdfPage) -> Result<String, String> {
+    let bounds = page
+        .boundaries()
+        .crop()
+        .or_else(|_| page.boundaries().media())
+        .map(|b| b.bounds)
+        .map_err(|e| format!("Failed to get page bounds: {}", e))?;
+
+    let left = bounds.left().value;
+    let top = bounds.top().value;
+    let width = bounds.width().value;
+    let height = bounds.height().value;
+    let rotation = page.rotation().unwrap_or(PdfPageRenderRotation::None);
+
+    let (view_width, view_height) = match rotation {
+        PdfPageRenderRotation::Degrees90 | PdfPageRenderRotation::Degrees270 => (height, width),
+        _ => (width, height),
+    };
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}pt" height="{h}pt" viewBox="0 0 {w} {h}">"#,
+        w = num(view_width),
+        h = num(view_height),
+    );
+    // 外层处理页面旋转，内层把 PDF 坐标系（左下原点，Y 向上）翻转为 SVG 坐标系
+    let _ = write!(
+        out,
+        r#"<g transform="{}"><g transform="matrix(1 0 0 -1 {} {})">"#,
+        rotation_transform(rotation, width, height),
+        num(-left),
+        num(top),
+    );
+
+    write_objects(&mut out, document, page.objects().iter());
+
+    out.push_str("</g></g></svg>");
+    Ok(out)
+}
+
+/// 递归输出一组页面对象
+fn write_objects<'a>(
+    out: &mut String,
+    document: &PdfDocument,
+    objects: impl Iterator<Item = PdfPageObject<'a>>,
+) {
+    for object in objects {
+        match &object {
+            PdfPageObject::Path(path) => write_path(out, &object, path),
+            PdfPageObject::Text(text) => write_text(out, &object, text),
+            PdfPageObject::Image(image) => write_image(out, document, &object, image),
+            PdfPageObject::XObjectForm(form) => {
+                let Ok(matrix) = object.matrix() else {
+                    continue;
+                };
+                let _ = write!(out, r#"<g transform="{}">"#, matrix_attr(&matrix));
+                write_objects(out, document, form.iter());
+                out.push_str("</g>");
+            }
+            // 着色对象和不支持的对象直接忽略
+            _ => {}
+        }
+    }
+}
+
+fn write_path(out: &mut String, object: &PdfPageObject, path: &PdfPagePathObject) {
+    let Ok(matrix) = object.matrix() else {
+        return;
+    };
+
+    let mut d = String::new();
+    // PDFium 把一段贝塞尔曲线拆成三个连续的 BezierTo 点（两个控制点 + 终点）
+    let mut bezier: Vec<(f32, f32)> = Vec::with_capacity(3);
+    for segment in path.segments().iter() {
+        let (x, y) = (segment.x().value, segment.y().value);
+        match segment.segment_type() {
+            PdfPathSegmentType::MoveTo => {
+                let _ = write!(d, "M{} {}", num(x), num(y));
+            }
+            PdfPathSegmentType::LineTo => {
+                let _ = write!(d, "L{} {}", num(x), num(y));
+            }
+            PdfPathSegmentType::BezierTo => {
+                bezier.push((x, y));
+                if bezier.len() == 3 {
+                    let _ = write!(
+                        d,
+                        "C{} {} {} {} {} {}",
+                        num(bezier[0].0),
+                        num(bezier[0].1),
+                        num(bezier[1].0),
+                        num(bezier[1].1),
+                        num(bezier[2].0),
+                        num(bezier[2].1),
+                    );
+                    bezier.clear();
+                }
+            }
+            PdfPathSegmentType::Unknown => {}
+        }
+        if segment.is_close() {
+            d.push('Z');
+        }
+    }
+    if d.is_empty() {
+        return;
+    }
+
+    let fill_mode = path.fill_mode().unwrap_or(PdfPathFillMode::None);
+    let stroked = path.is_stroked().unwrap_or(false);
+    if fill_mode == PdfPathFillMode::None && !stroked {
+        return;
+    }
+
+    let _ = write!(out, r#"<path transform="{}" d="{}""#, matrix_attr(&matrix), d);
+    match (fill_mode, object.fill_color()) {
+        (PdfPathFillMode::None, _) | (_, Err(_)) => out.push_str(r#" fill="none""#),
+        (mode, Ok(color)) => {
+            write_color(out, "fill", &color);
+            if mode == PdfPathFillMode::EvenOdd {
+                out.push_str(r#" fill-rule="evenodd""#);
+            }
+        }
+    }
+    if stroked {
+        if let Ok(color) = object.stroke_color() {
+            write_color(out, "stroke", &color);
+        }
+        let stroke_width = object.stroke_width().map(|w| w.value).unwrap_or(1.0);
+        if stroke_width > 0.0 {
+            let _ = write!(out, r#" stroke-width="{}""#, num(stroke_width));
+        } else {
+            // PDF 中线宽 0 表示设备上最细的线
+            out.push_str(r#" stroke-width="1" vector-effect="non-scaling-stroke""#);
+        }
+    }
+    out.push_str("/>");
+}
+
+fn write_text(out: &mut String, object: &PdfPageObject, text: &PdfPageTextObject) {
+    let content = text.text();
+    if content.trim().is_empty() {
+        return;
+    }
+    let Ok(matrix) = object.matrix() else {
+        return;
+    };
+
+    let font = text.font();
+    let family = font.family();
+    let _ = write!(
+        out,
+        r#"<text transform="{} scale(1 -1)" font-size="{}" font-family="{}sans-serif""#,
+        matrix_attr(&matrix),
+        num(text.unscaled_font_size().value),
+        if family.is_empty() { String::new() } else { format!("{}, ", escape_xml(&family)) },
+    );
+    if font.is_italic() {
+        out.push_str(r#" font-style="italic""#);
+    }
+    if text.render_mode() == PdfPageTextRenderMode::Invisible {
+        // OCR 隐藏文字层：保持可选中但不可见
+        out.push_str(r#" fill-opacity="0""#);
+    } else if let Ok(color) = object.fill_color() {
+        write_color(out, "fill", &color);
+    }
+    let _ = write!(out, r#" xml:space="preserve">{}</text>"#, escape_xml(&content));
+}
+
+fn write_image(out: &mut String, document: &PdfDocument, object: &PdfPageObject, image: &PdfPageImageObject) {
+    let Ok(matrix) = object.matrix() else {
+        return;
+    };
+    let Ok(img) = image.get_processed_image(document) else {
+        return;
+    };
+    let Some((mime, data)) = encode_embedded_image(&img) else {
+        return;
+    };
+
+    // 图片占据用户空间的单位正方形，且首行位于 y=1 处
+    let _ = write!(
+        out,
+        r#"<image transform="{} translate(0 1) scale(1 -1)" width="1" height="1" preserveAspectRatio="none" href="data:{};base64,{}"/>"#,
+        matrix_attr(&matrix),
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(data),
+    );
+}
+
+/// 编码内嵌图片：不透明图片使用 JPEG，带透明通道的使用 PNG
+fn encode_embedded_image(img: &DynamicImage) -> Option<(&'static str, Vec<u8>)> {
+    let mut buffer = Cursor::new(Vec::new());
+    if img.color().has_alpha() {
+        img.write_to(&mut buffer, ImageFormat::Png).ok()?;
+        Some(("image/png", buffer.into_inner()))
+    } else {
+        let rgb = img.to_rgb8();
+        JpegEncoder::new_with_quality(&mut buffer, EMBEDDED_JPEG_QUALITY)
+            .encode_image(&rgb)
+            .ok()?;
+        Some(("image/jpeg", buffer.into_inner()))
+    }
+}
+
+fn write_color(out: &mut String, attr: &str, color: &PdfColor) {
+    let _ = write!(
+        out,
+        r#" {}="rgb({},{},{})""#,
+        attr,
+        color.red(),
+        color.green(),
+        color.blue()
+    );
+    if color.alpha() < 255 {
+        let _ = write!(out, r#" {}-opacity="{}""#, attr, num(color.alpha() as f32 / 255.0));
+    }
+}
+
+fn matrix_attr(m: &PdfMatrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        num(m.a()),
+        num(m.b()),
+        num(m.c()),
+        num(m.d()),
+        num(m.e()),
+        num(m.f())
+    )
+}
+
+/// 页面旋转（顺时针）对应的 SVG 变换
+fn rotation_transform(rotation: PdfPageRenderRotation, width: f32, height: f32) -> String {
+    match rotation {
+        PdfPageRenderRotation::Degrees90 => format!("matrix(0 1 -1 0 {} 0)", num(height)),
+        PdfPageRenderRotation::Degrees180 => {
+            format!("matrix(-1 0 0 -1 {} {})", num(width), num(height))
+        }
+        PdfPageRenderRotation::Degrees270 => format!("matrix(0 -1 1 0 0 {})", num(width)),
+        PdfPageRenderRotation::None => "matrix(1 0 0 1 0 0)".to_string(),
+    }
+}
+
+/// 格式化坐标值：最多保留 3 位小数并去掉多余的 0
+fn num(v: f32) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            // XML 1.0 不允许的控制字符
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_trims_zeros() {
+        assert_eq!(num(1.0), "1");
+        assert_eq!(num(1.25), "1.25");
+        assert_eq!(num(-0.0001), "0");
+        assert_eq!(num(612.0), "612");
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b & \"c\"\u{1}"), "a&lt;b &amp; &quot;c&quot;");
+    }
+}
@@ -0,0 +1,461 @@
+//! 合成测试 PDF（generateTestPdf）
+//!
+//! 下游服务的集成测试用它生成覆盖渲染器边界情况的文档（纯文字、整页扫描图、超大页面、
+//! 加密），不需要把二进制样本提交到仓库。直接写出 PDF 语法，不经过 PDFium，
+//! 相同参数生成的字节完全相同。
+
+use image::codecs::jpeg::JpegEncoder;
+use image::ExtendedColorType;
+
+/// 最多生成的页数
+pub const MAX_PAGES: u32 = 1000;
+
+/// 普通页面尺寸（Letter，点）
+const LETTER: (u32, u32) = (612, 792);
+
+/// 超大页面的边长（点）：PDF 1.x 允许的页面尺寸上限（200 英寸）
+const HUGE_SIDE: u32 = 14400;
+
+/// 扫描页面图片的分辨率（DPI）
+const SCAN_DPI: u32 = 150;
+
+/// 扫描页面 JPEG 的质量
+const SCAN_JPEG_QUALITY: u8 = 75;
+
+/// 标准安全处理器的密码填充串
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08, 0x2E, 0x2E, 0x00,
+    0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// 文字页面的示例段落（WinAnsi 可表示的字符）
+const SENTENCES: &[&str] = &[
+    "The quick brown fox jumps over the lazy dog.",
+    "Pack my box with five dozen liquor jugs.",
+    "Sphinx of black quartz, judge my vow.",
+    "How vexingly quick daft zebras jump!",
+    "The five boxing wizards jump quickly.",
+];
+
+/// 页面内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntheticKind {
+    /// 只有文字
+    Text,
+    /// 一张铺满页面的灰度 JPEG，没有文字（识别为扫描件）
+    Image,
+    /// 14400 点见方的页面，按默认尺寸渲染会触发输出格式的尺寸上限
+    Huge,
+    /// 按页依次循环以上三种
+    Mixed,
+}
+
+impl SyntheticKind {
+    pub fn from_str(kind: &str) -> Option<Self> {
+        match kind.to_lowercase().as_str() {
+            "text" => Some(SyntheticKind::Text),
+            "image" => Some(SyntheticKind::Image),
+            "huge" => Some(SyntheticKind::Huge),
+            "mixed" => Some(SyntheticKind::Mixed),
+            _ => None,
+        }
+    }
+
+    /// 第 `index` 页（从 0 开始）的内容
+    fn page_kind(self, index: u32) -> SyntheticKind {
+        match self {
+            SyntheticKind::Mixed => [SyntheticKind::Text, SyntheticKind::Image, SyntheticKind::Huge][index as usize % 3],
+            kind => kind,
+        }
+    }
+}
+
+/// 生成参数
+#[derive(Debug, Clone)]
+pub struct SyntheticSpec {
+    pub kind: SyntheticKind,
+    pub pages: u32,
+    /// 设置后以该用户密码加密（RC4 40 位，标准安全处理器修订版 2），所有者密码相同
+    pub password: Option<String>,
+}
+
+/// 按参数生成 PDF
+pub fn generate(spec: &SyntheticSpec) -> Result<Vec<u8>, String> {
+    if spec.pages == 0 || spec.pages > MAX_PAGES {
+        return Err(format!("pages must be between 1 and {}", MAX_PAGES));
+    }
+
+    let id = md5(format!("{:?}:{}:{}", spec.kind, spec.pages, spec.password.is_some()).as_bytes());
+    let security = spec.password.as_deref().map(|password| Security::new(password, &id));
+
+    // 1 目录、2 页面树、3 字体；之后每页依次为页面、内容流，扫描页面再加一张图片
+    let mut writer = Writer::new(security);
+    let mut kids = Vec::new();
+    let mut next = 4;
+    let mut pages = Vec::new();
+    for index in 0..spec.pages {
+        let kind = spec.kind.page_kind(index);
+        let (page, contents) = (next, next + 1);
+        let image = (kind == SyntheticKind::Image).then_some(next + 2);
+        next += if image.is_some() { 3 } else { 2 };
+        kids.push(format!("{} 0 R", page));
+        pages.push((index + 1, kind, page, contents, image));
+    }
+
+    writer.object(1, b"<< /Type /Catalog /Pages 2 0 R >>");
+    writer.object(
+        2,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), spec.pages).as_bytes(),
+    );
+    writer.object(3, b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>");
+
+    for (page_num, kind, page, contents, image) in pages {
+        let (width, height) = match kind {
+            SyntheticKind::Huge => (HUGE_SIDE, HUGE_SIDE),
+            _ => LETTER,
+        };
+        let resources = match image {
+            Some(image) => format!("<< /XObject << /Im1 {} 0 R >> >>", image),
+            None => "<< /Font << /F1 3 0 R >> >>".to_string(),
+        };
+        writer.object(
+            page,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources {} /Contents {} 0 R >>",
+                width, height, resources, contents
+            )
+            .as_bytes(),
+        );
+
+        match image {
+            Some(image) => {
+                let (pixel_width, pixel_height) = (width * SCAN_DPI / 72, height * SCAN_DPI / 72);
+                let jpeg = scan_image(pixel_width, pixel_height, page_num)?;
+                writer.stream(
+                    image,
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /DCTDecode",
+                        pixel_width, pixel_height
+                    ),
+                    &jpeg,
+                );
+                writer.stream(contents, "", format!("q {} 0 0 {} 0 0 cm /Im1 Do Q", width, height).as_bytes());
+            }
+            None => writer.stream(contents, "", &text_content(page_num, kind, width, height)),
+        }
+    }
+
+    Ok(writer.finish(next - 1, &id))
+}
+
+/// 文字页面的内容流：页眉、若干行示例文字；超大页面的文字按比例放大并分布在整个页面上
+fn text_content(page_num: u32, kind: SyntheticKind, width: u32, height: u32) -> Vec<u8> {
+    let scale = if kind == SyntheticKind::Huge { width / LETTER.0 } else { 1 };
+    let (margin, size, leading) = (72 * scale, 12 * scale, 16 * scale);
+    let mut content = format!(
+        "BT /F1 {} Tf {} {} Td (Synthetic {} page {}) Tj ET\n",
+        size * 2,
+        margin,
+        height - margin,
+        match kind {
+            SyntheticKind::Huge => "huge",
+            _ => "text",
+        },
+        page_num
+    );
+    let lines = (height - 3 * margin) / leading;
+    for line in 0..lines {
+        let sentence = SENTENCES[(line + page_num) as usize % SENTENCES.len()];
+        content.push_str(&format!(
+            "BT /F1 {} Tf {} {} Td ({}) Tj ET\n",
+            size,
+            margin,
+            height - 2 * margin - line * leading,
+            sentence
+        ));
+    }
+    content.into_bytes()
+}
+
+/// 扫描页面的灰度图：偏灰的纸张底色上若干行深色“文字行”，叠加确定性的噪点
+fn scan_image(width: u32, height: u32, page_num: u32) -> Result<Vec<u8>, String> {
+    let mut state = 0x9E37_79B9u32.wrapping_mul(page_num);
+    let line_height = (SCAN_DPI / 6).max(1);
+    let margin = SCAN_DPI;
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let in_line = y >= margin && y < height - margin && (y / line_height).is_multiple_of(2);
+        for x in 0..width {
+            // xorshift 噪点
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let noise = (state % 16) as u8;
+            let inked = in_line && x >= margin && x < width - margin && !(x / (line_height / 2 + 1)).is_multiple_of(7);
+            pixels.push(if inked { 40 + noise } else { 225 + noise });
+        }
+    }
+
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, SCAN_JPEG_QUALITY)
+        .encode(&pixels, width, height, ExtendedColorType::L8)
+        .map_err(|e| format!("Failed to encode scan image: {}", e))?;
+    Ok(jpeg)
+}
+
+/// 顺序写出对象并记录偏移，最后生成 xref 表和 trailer
+struct Writer {
+    out: Vec<u8>,
+    offsets: Vec<(u32, usize)>,
+    security: Option<Security>,
+}
+
+impl Writer {
+    fn new(security: Option<Security>) -> Self {
+        Self {
+            out: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+            offsets: Vec::new(),
+            security,
+        }
+    }
+
+    /// 写出不含字符串的字典对象（字典中没有需要加密的内容）
+    fn object(&mut self, number: u32, body: &[u8]) {
+        self.offsets.push((number, self.out.len()));
+        self.out.extend_from_slice(format!("{} 0 obj\n", number).as_bytes());
+        self.out.extend_from_slice(body);
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    /// 写出流对象，加密时按对象号加密流数据
+    fn stream(&mut self, number: u32, dict: &str, data: &[u8]) {
+        let data = match &self.security {
+            Some(security) => security.encrypt(number, data),
+            None => data.to_vec(),
+        };
+        self.offsets.push((number, self.out.len()));
+        let separator = if dict.is_empty() { "" } else { " " };
+        self.out.extend_from_slice(
+            format!("{} 0 obj\n<< {}{}/Length {} >>\nstream\n", number, dict, separator, data.len()).as_bytes(),
+        );
+        self.out.extend_from_slice(&data);
+        self.out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    fn finish(mut self, last_object: u32, id: &[u8; 16]) -> Vec<u8> {
+        let encrypt = self.security.take().map(|security| {
+            let number = last_object + 1;
+            self.object(
+                number,
+                format!(
+                    "<< /Filter /Standard /V 1 /R 2 /Length 40 /O <{}> /U <{}> /P {} >>",
+                    hex(&security.owner),
+                    hex(&security.user),
+                    security.permissions
+                )
+                .as_bytes(),
+            );
+            number
+        });
+
+        let size = self.offsets.len() + 1;
+        self.offsets.sort_by_key(|&(number, _)| number);
+        let xref = self.out.len();
+        self.out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", size).as_bytes());
+        for (_, offset) in &self.offsets {
+            self.out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        let encrypt = encrypt.map(|number| format!(" /Encrypt {} 0 R", number)).unwrap_or_default();
+        self.out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R{} /ID [<{}> <{}>] >>\nstartxref\n{}\n%%EOF\n",
+                size,
+                encrypt,
+                hex(id),
+                hex(id),
+                xref
+            )
+            .as_bytes(),
+        );
+        self.out
+    }
+}
+
+/// 标准安全处理器修订版 2（RC4 40 位）
+struct Security {
+    key: [u8; 5],
+    owner: [u8; 32],
+    user: [u8; 32],
+    permissions: i32,
+}
+
+impl Security {
+    fn new(password: &str, id: &[u8; 16]) -> Self {
+        // 允许全部操作
+        let permissions: i32 = -4;
+        let padded = pad_password(password);
+
+        // 算法 3：所有者密码与用户密码相同
+        let owner_key = md5(&padded);
+        let mut owner = padded;
+        rc4(&owner_key[..5], &mut owner);
+
+        // 算法 2：文档密钥
+        let mut input = padded.to_vec();
+        input.extend_from_slice(&owner);
+        input.extend_from_slice(&permissions.to_le_bytes());
+        input.extend_from_slice(id);
+        let digest = md5(&input);
+        let mut key = [0u8; 5];
+        key.copy_from_slice(&digest[..5]);
+
+        // 算法 4：用文档密钥加密填充串
+        let mut user = PASSWORD_PADDING;
+        rc4(&key, &mut user);
+
+        Self { key, owner, user, permissions }
+    }
+
+    /// 算法 1：按对象号派生密钥后 RC4 加密
+    fn encrypt(&self, number: u32, data: &[u8]) -> Vec<u8> {
+        let mut input = self.key.to_vec();
+        input.extend_from_slice(&number.to_le_bytes()[..3]);
+        input.extend_from_slice(&[0, 0]);
+        let digest = md5(&input);
+        let mut out = data.to_vec();
+        rc4(&digest[..self.key.len() + 5], &mut out);
+        out
+    }
+}
+
+/// 密码截断或用填充串补齐到 32 字节
+fn pad_password(password: &str) -> [u8; 32] {
+    let bytes = password.as_bytes();
+    let len = bytes.len().min(32);
+    let mut padded = [0u8; 32];
+    padded[..len].copy_from_slice(&bytes[..len]);
+    padded[len..].copy_from_slice(&PASSWORD_PADDING[..32 - len]);
+    padded
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+fn rc4(key: &[u8], data: &mut [u8]) {
+    let mut s: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+        s.swap(i, j as usize);
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    for byte in data.iter_mut() {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(s[i as usize]);
+        s.swap(i as usize, j as usize);
+        *byte ^= s[s[i as usize].wrapping_add(s[j as usize]) as usize];
+    }
+}
+
+fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+    let constants: [u32; 64] = std::array::from_fn(|i| ((i as f64 + 1.0).sin().abs() * 4294967296.0) as u32);
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for chunk in message.chunks_exact(64) {
+        let words: [u32; 16] = std::array::from_fn(|i| u32::from_le_bytes(chunk[i * 4..i * 4 + 4].try_into().unwrap()));
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(constants[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[(i / 16) * 4 + i % 4]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 16];
+    for (i, value) in state.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5_and_rc4() {
+        assert_eq!(hex(&md5(b"")), "D41D8CD98F00B204E9800998ECF8427E");
+        assert_eq!(hex(&md5(b"abc")), "900150983CD24FB0D6963F7D28E17F72");
+        let mut data = b"Plaintext".to_vec();
+        rc4(b"Key", &mut data);
+        assert_eq!(hex(&data), "BBF316E8D940AF0AD3");
+    }
+
+    #[test]
+    fn test_generate_mixed() {
+        let spec = SyntheticSpec {
+            kind: SyntheticKind::Mixed,
+            pages: 3,
+            password: None,
+        };
+        let pdf = generate(&spec).unwrap();
+        assert_eq!(pdf, generate(&spec).unwrap());
+
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Count 3"));
+        assert!(text.contains("/Filter /DCTDecode"));
+        assert!(text.contains("/MediaBox [0 0 14400 14400]"));
+        assert!(text.contains("(Synthetic text page 1) Tj"));
+
+        // xref 中的偏移指向对应的对象
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let entries: Vec<usize> = text[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(entries.len(), 3 + 2 + 3 + 2);
+        for (index, offset) in entries.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", index + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_generate_encrypted() {
+        let spec = SyntheticSpec {
+            kind: SyntheticKind::Text,
+            pages: 1,
+            password: Some("secret".to_string()),
+        };
+        let pdf = generate(&spec).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Encrypt 6 0 R"));
+        assert!(!text.contains("Synthetic text page"));
+        assert!(generate(&SyntheticSpec { pages: 0, ..spec }).is_err());
+    }
+}
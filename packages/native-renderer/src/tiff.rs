@@ -0,0 +1,725 @@
+//! TIFF 编码
+//!
+//! 手写的最小 TIFF 写入器（小端序），支持：
+//! - 二值图像，CCITT Group 4 (T.6) 压缩
+//! - 8 位 RGB 图像，不压缩或 LZW 压缩
+//! - 8 位灰度图像，LZW 压缩
+//!
+//! 写入器按页追加，每页一个 IFD，多页 TIFF（传真、归档）依次追加即可。
+//! 8 位图像按行切成多个条带（每条带约 64KB 未压缩数据），LZW 逐条带独立编码，
+//! 阅读器可以按条带解码，不需要一次解压整页。
+
+use std::collections::HashMap;
+
+/// TIFF 标签类型
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+
+/// 压缩方式
+const COMPRESSION_NONE: u16 = 1;
+const COMPRESSION_CCITT_G4: u16 = 4;
+const COMPRESSION_LZW: u16 = 5;
+
+/// 光度解释
+const PHOTOMETRIC_WHITE_IS_ZERO: u16 = 0;
+const PHOTOMETRIC_BLACK_IS_ZERO: u16 = 1;
+const PHOTOMETRIC_RGB: u16 = 2;
+
+/// 多页 TIFF 中每页的颜色模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TiffColor {
+    /// 二值，CCITT G4 压缩
+    Bilevel,
+    /// 8 位灰度，LZW 压缩
+    Gray,
+    /// 8 位 RGB，LZW 压缩
+    Color,
+}
+
+impl TiffColor {
+    /// 从选项解析，未知的名称返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bilevel" | "bw" => Some(TiffColor::Bilevel),
+            "gray" | "grey" | "grayscale" => Some(TiffColor::Gray),
+            "color" | "colour" | "rgb" => Some(TiffColor::Color),
+            _ => None,
+        }
+    }
+}
+
+/// 8 位图像每个条带的目标大小（未压缩字节数）
+const STRIP_TARGET_BYTES: usize = 64 * 1024;
+
+/// 单个 IFD 条目
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    /// 值（不超过 4 字节时内联）或外部数据
+    value: IfdValue,
+}
+
+enum IfdValue {
+    Inline(u32),
+    External(Vec<u8>),
+}
+
+/// TIFF 写入器
+pub struct TiffWriter {
+    buf: Vec<u8>,
+    /// 上一个 IFD 的 “下一个 IFD 偏移” 字段所在位置
+    next_ifd_pos: usize,
+}
+
+impl Default for TiffWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TiffWriter {
+    pub fn new() -> Self {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"II");
+        buf.extend_from_slice(&42u16.to_le_bytes());
+        // 第一个 IFD 的偏移稍后回填
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        Self { buf, next_ifd_pos: 4 }
+    }
+
+    /// 追加一页二值图像（`bits` 每像素一个字节，非 0 为黑），使用 CCITT G4 压缩
+    pub fn add_bilevel_page(&mut self, bits: &[u8], width: u32, height: u32, dpi: f32) {
+        // G4 的参考行贯穿整页，整页写成一个条带
+        let data = encode_g4(bits, width as usize, height as usize);
+        self.add_page(
+            &[data],
+            height,
+            width,
+            height,
+            dpi,
+            vec![1],
+            COMPRESSION_CCITT_G4,
+            PHOTOMETRIC_WHITE_IS_ZERO,
+        );
+    }
+
+    /// 追加一页 8 位 RGB 图像（不压缩）
+    pub fn add_rgb_page(&mut self, rgb: &[u8], width: u32, height: u32, dpi: f32) {
+        let rows = rows_per_strip(width as usize * 3);
+        let strips: Vec<Vec<u8>> = rgb.chunks(rows * width as usize * 3).map(<[u8]>::to_vec).collect();
+        self.add_page(&strips, rows as u32, width, height, dpi, vec![8, 8, 8], COMPRESSION_NONE, PHOTOMETRIC_RGB);
+    }
+
+    /// 追加一页 8 位 RGB 图像，使用 LZW 压缩
+    pub fn add_lzw_rgb_page(&mut self, rgb: &[u8], width: u32, height: u32, dpi: f32) {
+        let rows = rows_per_strip(width as usize * 3);
+        let strips: Vec<Vec<u8>> = rgb.chunks(rows * width as usize * 3).map(encode_lzw).collect();
+        self.add_page(&strips, rows as u32, width, height, dpi, vec![8, 8, 8], COMPRESSION_LZW, PHOTOMETRIC_RGB);
+    }
+
+    /// 追加一页 8 位灰度图像（0 为黑），使用 LZW 压缩
+    pub fn add_lzw_gray_page(&mut self, gray: &[u8], width: u32, height: u32, dpi: f32) {
+        let rows = rows_per_strip(width as usize);
+        let strips: Vec<Vec<u8>> = gray.chunks(rows * width as usize).map(encode_lzw).collect();
+        self.add_page(&strips, rows as u32, width, height, dpi, vec![8], COMPRESSION_LZW, PHOTOMETRIC_BLACK_IS_ZERO);
+    }
+
+    /// 写入一页：`strips` 为各条带的数据（每条带 `rows_per_strip` 行，最后一条可以更少）
+    #[allow(clippy::too_many_arguments)]
+    fn add_page(
+        &mut self,
+        strips: &[Vec<u8>],
+        rows_per_strip: u32,
+        width: u32,
+        height: u32,
+        dpi: f32,
+        bits_per_sample: Vec<u16>,
+        compression: u16,
+        photometric: u16,
+    ) {
+        // 图像数据，按条带依次写入
+        let mut strip_offsets = Vec::with_capacity(strips.len());
+        for strip in strips {
+            self.align();
+            strip_offsets.push(self.buf.len() as u32);
+            self.buf.extend_from_slice(strip);
+        }
+        let strip_count = strips.len() as u32;
+        let longs = |values: Vec<u32>| match values[..] {
+            [value] => IfdValue::Inline(value),
+            _ => IfdValue::External(values.iter().flat_map(|v| v.to_le_bytes()).collect()),
+        };
+        let strip_offsets = longs(strip_offsets);
+        let strip_byte_counts = longs(strips.iter().map(|strip| strip.len() as u32).collect());
+
+        let samples = bits_per_sample.len() as u32;
+        let bps_value = if samples == 1 {
+            IfdValue::Inline(bits_per_sample[0] as u32)
+        } else {
+            IfdValue::External(bits_per_sample.iter().flat_map(|v| v.to_le_bytes()).collect())
+        };
+        let resolution = rational(dpi);
+
+        let mut entries = vec![
+            IfdEntry { tag: 256, field_type: TYPE_LONG, count: 1, value: IfdValue::Inline(width) },
+            IfdEntry { tag: 257, field_type: TYPE_LONG, count: 1, value: IfdValue::Inline(height) },
+            IfdEntry { tag: 258, field_type: TYPE_SHORT, count: samples, value: bps_value },
+            IfdEntry { tag: 259, field_type: TYPE_SHORT, count: 1, value: IfdValue::Inline(compression as u32) },
+            IfdEntry { tag: 262, field_type: TYPE_SHORT, count: 1, value: IfdValue::Inline(photometric as u32) },
+            IfdEntry { tag: 273, field_type: TYPE_LONG, count: strip_count, value: strip_offsets },
+            IfdEntry { tag: 277, field_type: TYPE_SHORT, count: 1, value: IfdValue::Inline(samples) },
+            IfdEntry { tag: 278, field_type: TYPE_LONG, count: 1, value: IfdValue::Inline(rows_per_strip) },
+            IfdEntry { tag: 279, field_type: TYPE_LONG, count: strip_count, value: strip_byte_counts },
+            IfdEntry { tag: 282, field_type: TYPE_RATIONAL, count: 1, value: IfdValue::External(resolution.clone()) },
+            IfdEntry { tag: 283, field_type: TYPE_RATIONAL, count: 1, value: IfdValue::External(resolution) },
+            IfdEntry { tag: 296, field_type: TYPE_SHORT, count: 1, value: IfdValue::Inline(2) },
+        ];
+
+        // 外部数据写在 IFD 之前，记录偏移
+        let mut external_offsets = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            if let IfdValue::External(bytes) = &entry.value {
+                self.align();
+                external_offsets.push(Some(self.buf.len() as u32));
+                self.buf.extend_from_slice(bytes);
+            } else {
+                external_offsets.push(None);
+            }
+        }
+
+        // 写 IFD 并回填上一个 IFD 的链接
+        self.align();
+        let ifd_offset = self.buf.len() as u32;
+        self.buf[self.next_ifd_pos..self.next_ifd_pos + 4].copy_from_slice(&ifd_offset.to_le_bytes());
+
+        self.buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (entry, external) in entries.iter_mut().zip(external_offsets) {
+            self.buf.extend_from_slice(&entry.tag.to_le_bytes());
+            self.buf.extend_from_slice(&entry.field_type.to_le_bytes());
+            self.buf.extend_from_slice(&entry.count.to_le_bytes());
+            match (&entry.value, external) {
+                (_, Some(offset)) => self.buf.extend_from_slice(&offset.to_le_bytes()),
+                (IfdValue::Inline(v), None) if entry.field_type == TYPE_SHORT => {
+                    self.buf.extend_from_slice(&(*v as u16).to_le_bytes());
+                    self.buf.extend_from_slice(&[0, 0]);
+                }
+                (IfdValue::Inline(v), None) => self.buf.extend_from_slice(&v.to_le_bytes()),
+                (IfdValue::External(_), None) => unreachable!(),
+            }
+        }
+        self.next_ifd_pos = self.buf.len();
+        self.buf.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    /// 完成写入，返回 TIFF 文件内容
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// TIFF 要求偏移按字对齐
+    fn align(&mut self) {
+        if self.buf.len() % 2 == 1 {
+            self.buf.push(0);
+        }
+    }
+}
+
+/// 每个条带的行数：约 STRIP_TARGET_BYTES 字节，至少一行
+fn rows_per_strip(row_bytes: usize) -> usize {
+    (STRIP_TARGET_BYTES / row_bytes.max(1)).max(1)
+}
+
+/// 把 DPI 转换为 RATIONAL（分母固定 100）
+fn rational(dpi: f32) -> Vec<u8> {
+    let num = (dpi.max(1.0) * 100.0).round() as u32;
+    let mut v = num.to_le_bytes().to_vec();
+    v.extend_from_slice(&100u32.to_le_bytes());
+    v
+}
+
+// ==================== LZW 编码 ====================
+
+const LZW_CLEAR: u16 = 256;
+const LZW_EOI: u16 = 257;
+const LZW_FIRST: u16 = 258;
+/// 码表满（12 位）前发出清表码
+const LZW_MAX: u16 = 4094;
+
+/// 将数据编码为 TIFF LZW（MSB 优先，码宽提前一个码增长）
+pub fn encode_lzw(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let mut acc: u64 = 0;
+    let mut nbits: u32 = 0;
+    let mut put = |code: u16, width: u32, out: &mut Vec<u8>| {
+        acc = (acc << width) | code as u64;
+        nbits += width;
+        while nbits >= 8 {
+            nbits -= 8;
+            out.push((acc >> nbits) as u8);
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut width = 9;
+    let mut next = LZW_FIRST;
+    put(LZW_CLEAR, width, &mut out);
+
+    let mut bytes = data.iter();
+    let Some(&first) = bytes.next() else {
+        put(LZW_EOI, width, &mut out);
+        put(0, 7, &mut out);
+        return out;
+    };
+    let mut prefix = first as u16;
+    for &byte in bytes {
+        if let Some(&code) = table.get(&(prefix, byte)) {
+            prefix = code;
+            continue;
+        }
+        put(prefix, width, &mut out);
+        table.insert((prefix, byte), next);
+        next += 1;
+        if next == LZW_MAX {
+            put(LZW_CLEAR, width, &mut out);
+            table.clear();
+            width = 9;
+            next = LZW_FIRST;
+        } else if next > (1 << width) - 1 {
+            width += 1;
+        }
+        prefix = byte as u16;
+    }
+    put(prefix, width, &mut out);
+    // 解码器在读到这个码后才加入上一项，码宽可能在 EOI 前增长
+    if next + 1 > (1 << width) - 1 && width < 12 {
+        width += 1;
+    }
+    put(LZW_EOI, width, &mut out);
+    // 补齐最后一个字节
+    put(0, 7, &mut out);
+    out
+}
+
+// ==================== CCITT Group 4 编码 ====================
+
+/// MSB 优先的位写入器
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u32,
+    nbits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self { out: Vec::new(), acc: 0, nbits: 0 }
+    }
+
+    /// 写入以字符串表示的码字（如 "0011"）
+    fn put_code(&mut self, code: &str) {
+        for c in code.bytes() {
+            self.acc = (self.acc << 1) | (c == b'1') as u32;
+            self.nbits += 1;
+            if self.nbits == 8 {
+                self.out.push(self.acc as u8);
+                self.acc = 0;
+                self.nbits = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.nbits > 0 {
+            self.out.push((self.acc << (8 - self.nbits)) as u8);
+        }
+        self.out
+    }
+}
+
+/// 白色游程终止码（0-63）
+const WHITE_TERMINATING: [&str; 64] = [
+    "00110101", "000111", "0111", "1000", "1011", "1100", "1110", "1111", "10011", "10100",
+    "00111", "01000", "001000", "000011", "110100", "110101", "101010", "101011", "0100111",
+    "0001100", "0001000", "0010111", "0000011", "0000100", "0101000", "0101011", "0010011",
+    "0100100", "0011000", "00000010", "00000011", "00011010", "00011011", "00010010",
+    "00010011", "00010100", "00010101", "00010110", "00010111", "00101000", "00101001",
+    "00101010", "00101011", "00101100", "00101101", "00000100", "00000101", "00001010",
+    "00001011", "01010010", "01010011", "01010100", "01010101", "00100100", "00100101",
+    "01011000", "01011001", "01011010", "01011011", "01001010", "01001011", "00110010",
+    "00110011", "00110100",
+];
+
+/// 白色游程补充码（64-1728，步长 64）
+const WHITE_MAKEUP: [&str; 27] = [
+    "11011", "10010", "010111", "0110111", "00110110", "00110111", "01100100", "01100101",
+    "01101000", "01100111", "011001100", "011001101", "011010010", "011010011", "011010100",
+    "011010101", "011010110", "011010111", "011011000", "011011001", "011011010", "011011011",
+    "010011000", "010011001", "010011010", "011000", "010011011",
+];
+
+/// 黑色游程终止码（0-63）
+const BLACK_TERMINATING: [&str; 64] = [
+    "0000110111", "010", "11", "10", "011", "0011", "0010", "00011", "000101", "000100",
+    "0000100", "0000101", "0000111", "00000100", "00000111", "000011000", "0000010111",
+    "0000011000", "0000001000", "00001100111", "00001101000", "00001101100", "00000110111",
+    "00000101000", "00000010111", "00000011000", "000011001010", "000011001011",
+    "000011001100", "000011001101", "000001101000", "000001101001", "000001101010",
+    "000001101011", "000011010010", "000011010011", "000011010100", "000011010101",
+    "000011010110", "000011010111", "000001101100", "000001101101", "000011011010",
+    "000011011011", "000001010100", "000001010101", "000001010110", "000001010111",
+    "000001100100", "000001100101", "000001010010", "000001010011", "000000100100",
+    "000000110111", "000000111000", "000000100111", "000000101000", "000001011000",
+    "000001011001", "000000101011", "000000101100", "000001011010", "000001100110",
+    "000001100111",
+];
+
+/// 黑色游程补充码（64-1728，步长 64）
+const BLACK_MAKEUP: [&str; 27] = [
+    "0000001111", "000011001000", "000011001001", "000001011011", "000000110011",
+    "000000110100", "000000110101", "0000001101100", "0000001101101", "0000001001010",
+    "0000001001011", "0000001001100", "0000001001101", "0000001110010", "0000001110011",
+    "0000001110100", "0000001110101", "0000001110110", "0000001110111", "0000001010010",
+    "0000001010011", "0000001010100", "0000001010101", "0000001011010", "0000001011011",
+    "0000001100100", "0000001100101",
+];
+
+/// 黑白共用的扩展补充码（1792-2560，步长 64）
+const EXTENDED_MAKEUP: [&str; 13] = [
+    "00000001000", "00000001100", "00000001101", "000000010010", "000000010011",
+    "000000010100", "000000010101", "000000010110", "000000010111", "000000011100",
+    "000000011101", "000000011110", "000000011111",
+];
+
+/// 写入一个游程（补充码 + 终止码）
+fn put_run(w: &mut BitWriter, mut run: usize, black: bool) {
+    let (terminating, makeup) = if black {
+        (&BLACK_TERMINATING, &BLACK_MAKEUP)
+    } else {
+        (&WHITE_TERMINATING, &WHITE_MAKEUP)
+    };
+
+    while run >= 2560 {
+        w.put_code(EXTENDED_MAKEUP[12]);
+        run -= 2560;
+    }
+    if run >= 64 {
+        let m = run / 64;
+        if m <= 27 {
+            w.put_code(makeup[m - 1]);
+        } else {
+            w.put_code(EXTENDED_MAKEUP[m - 28]);
+        }
+        run -= m * 64;
+    }
+    w.put_code(terminating[run]);
+}
+
+/// 在一行中查找 `start` 之后（含）第一个颜色不等于 `color` 的位置，找不到返回行宽
+fn next_change(line: &[u8], start: usize, color: u8) -> usize {
+    line.iter()
+        .skip(start)
+        .position(|&c| c != color)
+        .map(|p| p + start)
+        .unwrap_or(line.len())
+}
+
+/// 将二值图像编码为 CCITT Group 4 数据
+///
+/// `bits` 每像素一个字节，非 0 为黑。参考行初始为全白。
+pub fn encode_g4(bits: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let white_line = vec![0u8; width];
+    let mut reference: Vec<u8> = white_line.clone();
+    let mut coding = vec![0u8; width];
+
+    for y in 0..height {
+        for (dst, &src) in coding.iter_mut().zip(&bits[y * width..(y + 1) * width]) {
+            *dst = (src != 0) as u8;
+        }
+
+        // a0 为 None 表示位于行首之前的虚拟白像素
+        let mut a0: Option<usize> = None;
+        let mut color = 0u8;
+        loop {
+            let start = a0.map(|p| p + 1).unwrap_or(0);
+            let a1 = next_change(&coding, start, color);
+
+            // b1：参考行上 a0 右侧第一个与 a0 颜色相反的变化像素
+            let b1 = {
+                let mut pos = start;
+                loop {
+                    if pos >= width {
+                        break width;
+                    }
+                    let prev = if pos == 0 { 0 } else { reference[pos - 1] };
+                    if reference[pos] != prev && reference[pos] != color {
+                        break pos;
+                    }
+                    pos += 1;
+                }
+            };
+            let b2 = if b1 < width {
+                next_change(&reference, b1, reference[b1])
+            } else {
+                width
+            };
+
+            if b2 < a1 {
+                // 通过模式
+                w.put_code("0001");
+                a0 = Some(b2);
+            } else if a1.abs_diff(b1) <= 3 {
+                // 垂直模式
+                let code = match a1 as isize - b1 as isize {
+                    0 => "1",
+                    1 => "011",
+                    2 => "000011",
+                    3 => "0000011",
+                    -1 => "010",
+                    -2 => "000010",
+                    _ => "0000010",
+                };
+                w.put_code(code);
+                a0 = Some(a1);
+                color ^= 1;
+            } else {
+                // 水平模式
+                let a2 = if a1 < width {
+                    next_change(&coding, a1, coding[a1])
+                } else {
+                    width
+                };
+                w.put_code("001");
+                let run_start = a0.unwrap_or(0);
+                put_run(&mut w, a1 - run_start, color == 1);
+                put_run(&mut w, a2 - a1, color == 0);
+                a0 = Some(a2);
+            }
+
+            if a0.is_some_and(|p| p >= width) {
+                break;
+            }
+        }
+
+        std::mem::swap(&mut reference, &mut coding);
+    }
+
+    // EOFB
+    w.put_code("000000000001");
+    w.put_code("000000000001");
+    w.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DecodedPage {
+        width: u32,
+        height: u32,
+        strips: u32,
+        /// 8 位数据原样返回；1 位数据展开为每像素一个字节，1 为黑（tiff crate 按 0 为黑输出）
+        pixels: Vec<u8>,
+    }
+
+    /// 用 tiff crate 解码生成的文件
+    fn decode_pages(data: &[u8]) -> Vec<DecodedPage> {
+        let mut decoder = tiff::decoder::Decoder::new(std::io::Cursor::new(data)).unwrap();
+        let mut pages = Vec::new();
+        loop {
+            let (width, height) = decoder.dimensions().unwrap();
+            let strips = decoder.strip_count().unwrap();
+            let pixels = match decoder.read_image().unwrap() {
+                tiff::decoder::DecodingResult::U8(pixels) => pixels,
+                other => panic!("unexpected sample type: {:?}", std::mem::discriminant(&other)),
+            };
+            let pixels = if decoder.colortype().unwrap() == tiff::ColorType::Gray(1) {
+                let row_bytes = (width as usize).div_ceil(8);
+                (0..height as usize)
+                    .flat_map(|y| (0..width as usize).map(move |x| (y, x)))
+                    .map(|(y, x)| !(pixels[y * row_bytes + x / 8] >> (7 - x % 8)) & 1)
+                    .collect()
+            } else {
+                pixels
+            };
+            pages.push(DecodedPage { width, height, strips, pixels });
+            if !decoder.more_images() {
+                return pages;
+            }
+            decoder.next_image().unwrap();
+        }
+    }
+
+    /// 类似文字的二值内容：笔画、空行、长黑游程、逐像素交替和噪点
+    fn text_like(width: usize, height: usize, seed: u32) -> Vec<u8> {
+        let mut seed = seed;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            seed >> 16
+        };
+        let mut bits = vec![0u8; width * height];
+        for y in 0..height {
+            let row = &mut bits[y * width..(y + 1) * width];
+            match y % 16 {
+                // 行距
+                0..=2 => {}
+                // 下划线式的长黑游程，超过 2560 时需要多个扩展补充码
+                3 => row.iter_mut().skip(width / 7).for_each(|b| *b = 1),
+                // 逐像素交替，游程全部为 1
+                4 => row.iter_mut().step_by(2).for_each(|b| *b = 1),
+                // 稀疏噪点
+                5 => (0..width / 10).for_each(|_| row[next() as usize % width] = 1),
+                // 字形：长短不一的竖笔和横笔，和上一行相近，以垂直模式为主
+                _ => {
+                    let mut x = (next() as usize) % 5;
+                    while x < width {
+                        let stroke = 1 + next() as usize % 6;
+                        let end = (x + stroke).min(width);
+                        row[x..end].iter_mut().for_each(|b| *b = 1);
+                        x = end + 1 + next() as usize % 9;
+                    }
+                }
+            }
+        }
+        bits
+    }
+
+    #[test]
+    fn test_g4_round_trip() {
+        // 宽度覆盖不是 8 的倍数、小于一个字节、超过 1728（扩展补充码）和 2560（多个补充码）的情况
+        let mut writer = TiffWriter::new();
+        let sizes = [(1, 3), (7, 5), (13, 40), (100, 64), (1001, 48), (1729, 33), (2600, 20), (5203, 17)];
+        let pages: Vec<Vec<u8>> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &(width, height))| text_like(width, height, i as u32 + 1))
+            .collect();
+        for (bits, &(width, height)) in pages.iter().zip(&sizes) {
+            writer.add_bilevel_page(bits, width as u32, height as u32, 200.0);
+        }
+        // 全白、全黑页面
+        writer.add_bilevel_page(&[0; 9 * 4], 9, 4, 200.0);
+        writer.add_bilevel_page(&[1; 9 * 4], 9, 4, 200.0);
+
+        let decoded = decode_pages(&writer.finish());
+        assert_eq!(decoded.len(), sizes.len() + 2);
+        for (page, (bits, &(w, h))) in decoded.iter().zip(pages.iter().zip(&sizes)) {
+            assert_eq!((page.width, page.height, page.strips), (w as u32, h as u32, 1));
+            assert!(page.pixels == *bits, "G4 round trip mismatch at {}x{}", w, h);
+        }
+        assert_eq!(decoded[sizes.len()].pixels, vec![0; 9 * 4]);
+        assert_eq!(decoded[sizes.len() + 1].pixels, vec![1; 9 * 4]);
+    }
+
+    #[test]
+    fn test_strips_round_trip() {
+        // 上半页伪随机噪点让码宽增长到 12 位并触发清表，下半页是渐变（长串）
+        let mut seed = 7u32;
+        let mut pixel = |i: usize, half: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            if i < half { (seed >> 24) as u8 } else { (i % 251) as u8 }
+        };
+        let (gray_width, gray_height) = (700usize, 301usize);
+        let gray: Vec<u8> = (0..gray_width * gray_height).map(|i| pixel(i, gray_width * gray_height / 2)).collect();
+        let (rgb_width, rgb_height) = (517usize, 203usize);
+        let rgb: Vec<u8> = (0..rgb_width * rgb_height * 3).map(|i| pixel(i, rgb_width * rgb_height * 3 / 2)).collect();
+        let tiny = vec![10u8, 20, 30, 40, 50, 60];
+
+        let mut writer = TiffWriter::new();
+        writer.add_lzw_gray_page(&gray, gray_width as u32, gray_height as u32, 300.0);
+        writer.add_lzw_rgb_page(&rgb, rgb_width as u32, rgb_height as u32, 300.0);
+        writer.add_rgb_page(&rgb, rgb_width as u32, rgb_height as u32, 72.0);
+        writer.add_lzw_rgb_page(&tiny, 2, 1, 72.0);
+        let decoded = decode_pages(&writer.finish());
+
+        let expected = [
+            (gray_width, gray_height, &gray, gray_height.div_ceil(rows_per_strip(gray_width))),
+            (rgb_width, rgb_height, &rgb, rgb_height.div_ceil(rows_per_strip(rgb_width * 3))),
+            (rgb_width, rgb_height, &rgb, rgb_height.div_ceil(rows_per_strip(rgb_width * 3))),
+            (2, 1, &tiny, 1),
+        ];
+        assert_eq!(decoded.len(), expected.len());
+        for (page, (width, height, pixels, strips)) in decoded.iter().zip(expected) {
+            assert_eq!((page.width, page.height), (width as u32, height as u32));
+            assert_eq!(page.strips, strips as u32);
+            assert!(page.pixels == *pixels, "strip round trip mismatch at {}x{}", width, height);
+        }
+        assert!(decoded[0].strips > 1 && decoded[1].strips > 1);
+    }
+
+    #[test]
+    fn test_all_white_rows_use_vertical_mode() {
+        // 全白行：每行一个 V0（"1"），4 行 = 4 位，加 24 位 EOFB
+        let data = encode_g4(&[0u8; 32], 8, 4);
+        assert_eq!(data[0] >> 4, 0b1111);
+        assert_eq!(data.len(), 4);
+    }
+
+    /// TIFF LZW 解码（码宽提前一个码增长）
+    fn decode_lzw(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut table: Vec<Vec<u8>> = (0..=255u8).map(|b| vec![b]).chain([vec![], vec![]]).collect();
+        let mut width = 9;
+        let mut pos = 0usize;
+        let mut previous: Option<Vec<u8>> = None;
+        loop {
+            let mut code = 0usize;
+            for _ in 0..width {
+                code = (code << 1) | ((data[pos / 8] >> (7 - pos % 8)) & 1) as usize;
+                pos += 1;
+            }
+            if code == LZW_CLEAR as usize {
+                table.truncate(LZW_FIRST as usize);
+                width = 9;
+                previous = None;
+                continue;
+            }
+            if code == LZW_EOI as usize {
+                return out;
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("invalid code {}", code),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(previous) = previous {
+                table.push([previous, vec![entry[0]]].concat());
+            }
+            previous = Some(entry);
+            if table.len() + 1 >= 1 << width && width < 12 {
+                width += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        // 重复数据让码宽增长到 12 位并触发清表，伪随机数据产生大量短串
+        let mut seed = 12345u32;
+        let noise: Vec<u8> = (0..60_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 24) as u8
+            })
+            .collect();
+        let repeated: Vec<u8> = (0..200_000).map(|i| (i % 7 * 30 + i / 5000) as u8).collect();
+        for data in [vec![], vec![42], b"TOBEORNOTTOBEORTOBEORNOT".to_vec(), noise, repeated] {
+            assert_eq!(decode_lzw(&encode_lzw(&data)), data);
+        }
+    }
+
+    #[test]
+    fn test_tiff_header_and_ifd_link() {
+        let mut writer = TiffWriter::new();
+        writer.add_bilevel_page(&[0, 1, 1, 0], 2, 2, 200.0);
+        let tiff = writer.finish();
+        assert_eq!(&tiff[0..4], b"II*\0");
+        let ifd = u32::from_le_bytes(tiff[4..8].try_into().unwrap()) as usize;
+        let count = u16::from_le_bytes(tiff[ifd..ifd + 2].try_into().unwrap()) as usize;
+        assert_eq!(count, 12);
+        let next = &tiff[ifd + 2 + count * 12..ifd + 6 + count * 12];
+        assert_eq!(next, &[0, 0, 0, 0]);
+    }
+}
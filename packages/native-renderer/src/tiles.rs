@@ -0,0 +1,125 @@
+//! 页面分块
+//!
+//! 超大幅面页面（工程图纸、地图）超出输出格式的尺寸上限时，按固定大小的网格
+//! 切成多块分别编码，保留完整分辨率，供地图式查看器按需加载。
+//!
+//! 深度缩放查看器（OpenSeadragon、Leaflet）按缩放级别组织分块：级别 0 时整页缩放到
+//! 一个分块以内，之后每级放大一倍，每个分块单独渲染，不需要先渲染整页。
+
+/// 分块网格
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileGrid {
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+/// 单个分块在整页中的区域
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileRect {
+    pub row: u32,
+    pub column: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TileGrid {
+    pub fn new(width: u32, height: u32, tile_size: u32) -> Self {
+        let tile_size = tile_size.max(1);
+        Self {
+            width,
+            height,
+            tile_size,
+            columns: width.div_ceil(tile_size).max(1),
+            rows: height.div_ceil(tile_size).max(1),
+        }
+    }
+
+    /// 按行优先顺序列出所有分块（最后一行/列的分块可能小于 tile_size）
+    pub fn tiles(&self) -> impl Iterator<Item = TileRect> + '_ {
+        (0..self.rows).flat_map(move |row| (0..self.columns).filter_map(move |column| self.tile(column, row)))
+    }
+
+    /// 指定行列的分块，超出网格时返回 None
+    pub fn tile(&self, column: u32, row: u32) -> Option<TileRect> {
+        if column >= self.columns || row >= self.rows {
+            return None;
+        }
+        let x = column * self.tile_size;
+        let y = row * self.tile_size;
+        Some(TileRect {
+            row,
+            column,
+            x,
+            y,
+            width: self.tile_size.min(self.width.saturating_sub(x)).max(1),
+            height: self.tile_size.min(self.height.saturating_sub(y)).max(1),
+        })
+    }
+}
+
+/// 缩放级别对应的缩放比例（像素/点）：级别 0 时页面长边等于分块大小
+pub fn zoom_scale(tile_size: u32, longest_side: f64, zoom: u32) -> f64 {
+    tile_size as f64 / longest_side * 2f64.powi(zoom as i32)
+}
+
+/// 缩放比例首次达到 `max_scale` 的级别，即查看器可用的最高级别
+pub fn max_zoom(tile_size: u32, longest_side: f64, max_scale: f64) -> u32 {
+    let base = zoom_scale(tile_size, longest_side, 0);
+    if base >= max_scale {
+        0
+    } else {
+        (max_scale / base).log2().ceil() as u32
+    }
+}
+
+/// 从紧密排列的 4 通道图像中裁出一块
+pub fn crop(image: &[u8], image_width: u32, rect: &TileRect) -> Vec<u8> {
+    let row_bytes = rect.width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_bytes * rect.height as usize);
+    for row in rect.y..rect.y + rect.height {
+        let start = ((row * image_width + rect.x) * 4) as usize;
+        pixels.extend_from_slice(&image[start..start + row_bytes]);
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_covers_page() {
+        let grid = TileGrid::new(250, 100, 100);
+        assert_eq!((grid.columns, grid.rows), (3, 1));
+        let tiles: Vec<_> = grid.tiles().collect();
+        assert_eq!(tiles.len(), 3);
+        assert_eq!((tiles[2].x, tiles[2].width, tiles[2].height), (200, 50, 100));
+    }
+
+    #[test]
+    fn test_zoom_levels() {
+        // A4 纵向（842 点），256 像素分块
+        assert_eq!(zoom_scale(256, 842.0, 0) * 842.0, 256.0);
+        assert_eq!(zoom_scale(256, 842.0, 3), zoom_scale(256, 842.0, 0) * 8.0);
+        // 级别 0 的比例约 0.304，放大到 4 倍需要 4 级（0.304 * 16 ≈ 4.86）
+        assert_eq!(max_zoom(256, 842.0, 4.0), 4);
+        assert_eq!(max_zoom(256, 100.0, 2.0), 0);
+        let grid = TileGrid::new(600, 300, 256);
+        assert_eq!(grid.tile(2, 1).map(|t| (t.x, t.y, t.width, t.height)), Some((512, 256, 88, 44)));
+        assert_eq!(grid.tile(3, 0), None);
+    }
+
+    #[test]
+    fn test_crop() {
+        // 3x2 图像，每个像素的值为其索引
+        let image: Vec<u8> = (0..6u8).flat_map(|i| [i; 4]).collect();
+        let rect = TileRect { row: 0, column: 1, x: 1, y: 0, width: 2, height: 2 };
+        let pixels = crop(&image, 3, &rect);
+        assert_eq!(pixels.chunks(4).map(|p| p[0]).collect::<Vec<_>>(), vec![1, 2, 4, 5]);
+    }
+}
@@ -0,0 +1,205 @@
+//! 单次调用的资源占用统计（options.resourceUsage）
+//!
+//! 多租户服务需要按客户文档核算成本。每次调用创建一个 `UsageMeter`，参与该调用的线程
+//! （调用所在的工作线程和编码线程）通过 `attach` 登记，分离时累计该线程期间消耗的
+//! CPU 时间（getrusage RUSAGE_THREAD，区分用户态和内核态，仅 Linux）。
+//!
+//! 内存按本模块 Rust 代码的堆分配统计：计数分配器把分配归属到当前线程登记的调用。
+//! PDFium 内部的 C++ 分配不经过 Rust 分配器，不计入；在其他线程释放的内存也会
+//! 影响归属，因此峰值是近似值。计数分配器会替换整个进程的 Rust 全局分配器，只在启用
+//! `alloc-usage` 特性时编译，默认构建只统计 CPU 时间。
+
+#[cfg(feature = "alloc-usage")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 一次调用的资源累计
+#[derive(Debug, Default)]
+pub struct UsageMeter {
+    user_micros: AtomicU64,
+    system_micros: AtomicU64,
+    /// 归属到本次调用的净分配字节数（其他线程分配、这里释放时可能为负）
+    #[cfg(feature = "alloc-usage")]
+    current_bytes: AtomicI64,
+    peak_bytes: AtomicI64,
+    allocated_bytes: AtomicU64,
+}
+
+/// 统计结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Usage {
+    pub user_micros: u64,
+    pub system_micros: u64,
+    /// 未启用 `alloc-usage` 特性时为空
+    pub peak_bytes: Option<u64>,
+    pub allocated_bytes: Option<u64>,
+}
+
+impl UsageMeter {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn usage(&self) -> Usage {
+        Usage {
+            user_micros: self.user_micros.load(Ordering::Relaxed),
+            system_micros: self.system_micros.load(Ordering::Relaxed),
+            peak_bytes: cfg!(feature = "alloc-usage").then(|| self.peak_bytes.load(Ordering::Relaxed).max(0) as u64),
+            allocated_bytes: cfg!(feature = "alloc-usage").then(|| self.allocated_bytes.load(Ordering::Relaxed)),
+        }
+    }
+
+    #[cfg(feature = "alloc-usage")]
+    fn record_alloc(&self, delta: i64) {
+        let current = self.current_bytes.fetch_add(delta, Ordering::Relaxed) + delta;
+        if delta > 0 {
+            self.allocated_bytes.fetch_add(delta as u64, Ordering::Relaxed);
+            self.peak_bytes.fetch_max(current, Ordering::Relaxed);
+        }
+    }
+}
+
+thread_local! {
+    /// 当前线程登记的调用；常量初始化且没有析构函数，分配器中访问不会再分配内存
+    static CURRENT: Cell<*const UsageMeter> = const { Cell::new(ptr::null()) };
+}
+
+/// 线程登记，drop 时累计 CPU 时间并恢复之前的登记
+pub struct Attached {
+    meter: Arc<UsageMeter>,
+    previous: *const UsageMeter,
+    start: Option<(u64, u64)>,
+}
+
+/// 把当前线程登记到 `meter`
+pub fn attach(meter: &Arc<UsageMeter>) -> Attached {
+    let previous = CURRENT.with(|current| current.replace(Arc::as_ptr(meter)));
+    Attached {
+        meter: meter.clone(),
+        previous,
+        start: thread_cpu_micros(),
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        if let (Some((user, system)), Some((start_user, start_system))) = (thread_cpu_micros(), self.start) {
+            self.meter.user_micros.fetch_add(user.saturating_sub(start_user), Ordering::Relaxed);
+            self.meter.system_micros.fetch_add(system.saturating_sub(start_system), Ordering::Relaxed);
+        }
+        CURRENT.with(|current| current.set(self.previous));
+    }
+}
+
+/// 当前线程登记的调用，用于让新建的工作线程登记到同一调用
+pub fn current() -> Option<Arc<UsageMeter>> {
+    let meter = CURRENT.with(|current| current.get());
+    if meter.is_null() {
+        return None;
+    }
+    // 登记期间 Attached 持有一个引用，指针一定有效
+    unsafe {
+        Arc::increment_strong_count(meter);
+        Some(Arc::from_raw(meter))
+    }
+}
+
+/// 当前线程消耗的用户态和内核态 CPU 时间（微秒）
+#[cfg(target_os = "linux")]
+pub fn thread_cpu_micros() -> Option<(u64, u64)> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    if unsafe { libc::getrusage(libc::RUSAGE_THREAD, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let usage = unsafe { usage.assume_init() };
+    let micros = |time: libc::timeval| time.tv_sec as u64 * 1_000_000 + time.tv_usec as u64;
+    Some((micros(usage.ru_utime), micros(usage.ru_stime)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn thread_cpu_micros() -> Option<(u64, u64)> {
+    None
+}
+
+/// 把分配量记到当前线程登记的调用上
+#[cfg(feature = "alloc-usage")]
+fn record(delta: i64) {
+    let _ = CURRENT.try_with(|current| {
+        let meter = current.get();
+        if !meter.is_null() {
+            unsafe { (*meter).record_alloc(delta) };
+        }
+    });
+}
+
+/// 系统分配器的计数包装
+#[cfg(feature = "alloc-usage")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-usage")]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size() as i64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(-(layout.size() as i64));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size as i64 - layout.size() as i64);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "alloc-usage")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_tracks_attached_threads() {
+        let meter = UsageMeter::new();
+        {
+            let _attached = attach(&meter);
+            let data = vec![1u8; 4 << 20];
+            assert!(current().is_some_and(|m| Arc::ptr_eq(&m, &meter)));
+            std::hint::black_box(&data);
+        }
+        assert!(current().is_none());
+        let usage = meter.usage();
+        if cfg!(feature = "alloc-usage") {
+            assert!(usage.peak_bytes.is_some_and(|bytes| bytes >= 4 << 20));
+            assert!(usage.allocated_bytes.is_some_and(|bytes| bytes >= 4 << 20));
+
+            // 未登记的线程不计入
+            std::thread::spawn(|| std::hint::black_box(vec![0u8; 8 << 20])).join().unwrap();
+            assert!(meter.usage().peak_bytes.is_some_and(|bytes| bytes < 8 << 20));
+        } else {
+            assert_eq!(usage.peak_bytes, None);
+            assert_eq!(usage.allocated_bytes, None);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! 单页渲染超时（pageTimeoutMs）
+//!
+//! 损坏的 PDF 可能让 PDFium 在一页上运行几分钟，拖住整批渲染。PDFium 的句柄只能在打开文档的线程上使用，
+//! 卡在 FFI 调用中的线程也无法从外部终止，所以渲染不挪到别的线程，而是改用渐进式渲染
+//! （FPDF_RenderPageBitmap_Start / FPDF_RenderPage_Continue）：PDFium 在绘制页面对象的间隙询问是否暂停，
+//! 看门狗回调在超过截止时间后要求暂停，渲染随即放弃，该页以 RENDER_TIMEOUT 失败，批量中的其余页面照常渲染。
+//!
+//! 单个页面对象内部（例如解码一张巨大的图片）没有询问点，超时在该对象绘制完成后才生效。
+//! 与条带渲染一样不经过 FPDF_FFLDraw，表单控件按其外观流随注释绘制。
+
+use crate::raw_chunks;
+use pdfium_render::prelude::*;
+use std::os::raw::{c_int, c_void};
+use std::time::Instant;
+
+// fpdf_progressive.h 中的渲染状态
+const FPDF_RENDER_TOBECONTINUED: c_int = 1;
+const FPDF_RENDER_DONE: c_int = 2;
+
+unsafe extern "C" fn past_deadline(pause: *mut IFSDK_PAUSE) -> FPDF_BOOL {
+    let deadline = &*((*pause).user as *const Instant);
+    (Instant::now() >= *deadline) as FPDF_BOOL
+}
+
+/// 在 `deadline` 之前渲染整页（width x height），返回紧密排列的 4 通道像素；超时返回 `Ok(None)`
+///
+/// `flags` 与 `clear_color` 的含义同 [`raw_chunks::render_band`]。
+pub fn render_page(
+    page: &PdfPage,
+    (width, height): (u32, u32),
+    flags: c_int,
+    clear_color: FPDF_DWORD,
+    deadline: Instant,
+) -> Result<Option<Vec<u8>>, String> {
+    if Instant::now() >= deadline {
+        return Ok(None);
+    }
+
+    let bindings = page.bindings();
+    let bitmap = raw_chunks::create_bitmap(bindings, width, height, clear_color)?;
+    let page_handle = bindings.get_handle_from_page(page);
+    let mut pause = IFSDK_PAUSE {
+        version: 1,
+        NeedToPauseNow: Some(past_deadline),
+        user: &deadline as *const Instant as *mut c_void,
+    };
+
+    let mut status = bindings.FPDF_RenderPageBitmap_Start(
+        bitmap,
+        page_handle,
+        0,
+        0,
+        width as c_int,
+        height as c_int,
+        0,
+        flags,
+        &mut pause,
+    );
+    while status == FPDF_RENDER_TOBECONTINUED && Instant::now() < deadline {
+        status = bindings.FPDF_RenderPage_Continue(page_handle, &mut pause);
+    }
+    bindings.FPDF_RenderPage_Close(page_handle);
+
+    match status {
+        FPDF_RENDER_DONE => raw_chunks::take_pixels(bindings, bitmap, width, height).map(Some),
+        FPDF_RENDER_TOBECONTINUED => {
+            bindings.FPDFBitmap_Destroy(bitmap);
+            Ok(None)
+        }
+        _ => {
+            bindings.FPDFBitmap_Destroy(bitmap);
+            Err("PDFium progressive render failed".to_string())
+        }
+    }
+}
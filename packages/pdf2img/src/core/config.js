@@ -2,6 +2,8 @@
  * PDF2IMG 配置
  */
 
+import { createHash } from 'crypto';
+
 // ==================== 渲染配置 ====================
 export const RENDER_CONFIG = {
     // 目标渲染宽度（像素）
@@ -13,7 +15,7 @@ export const RENDER_CONFIG = {
     // 最大渲染缩放比例
     MAX_RENDER_SCALE: parseFloat(process.env.MAX_RENDER_SCALE) || 4.0,
 
-    // 默认输出格式：webp, png, jpg
+    // 默认输出格式：webp, png, jpg, avif
     OUTPUT_FORMAT: process.env.OUTPUT_FORMAT || 'webp',
 
     // Native Stream 阈值（字节）- 大于此值使用流式加载
@@ -34,6 +36,12 @@ export const ENCODER_CONFIG = {
     
     // PNG 压缩级别（0-9，0不压缩，9最大压缩）
     PNG_COMPRESSION: parseInt(process.env.PNG_COMPRESSION) || 6,
+
+    // AVIF 编码质量（0-100）
+    AVIF_QUALITY: parseInt(process.env.AVIF_QUALITY) || 50,
+
+    // AVIF 编码速度（0-10，0最慢但压缩最好，10最快）
+    AVIF_SPEED: parseInt(process.env.AVIF_SPEED) || 6,
 };
 
 // ==================== 超时配置 ====================
@@ -46,7 +54,68 @@ export const TIMEOUT_CONFIG = {
 };
 
 // ==================== 支持的输出格式 ====================
-export const SUPPORTED_FORMATS = ['webp', 'png', 'jpg', 'jpeg'];
+export const SUPPORTED_FORMATS = ['webp', 'png', 'jpg', 'jpeg', 'avif'];
+
+// ==================== 命名渲染配置 ====================
+const profiles = new Map();
+
+function isPlainObject(value) {
+    return value !== null && typeof value === 'object' && !Array.isArray(value) && !Buffer.isBuffer(value);
+}
+
+/**
+ * 注册命名的渲染配置，替换之前注册的全部配置
+ *
+ * 配置使用与 convert 相同的选项结构（如 `{ "preview": { "targetWidth": 640, "webp": { "quality": 70 } } }`），
+ * 之后的调用通过 `profile` 选项引用。配置只在当前线程的 JS 层展开。
+ *
+ * @param {string} json - 以配置名为键的 JSON 对象
+ * @returns {string[]} 注册的配置名（按名称排序）
+ */
+export function loadProfiles(json) {
+    const parsed = JSON.parse(json);
+    if (!isPlainObject(parsed)) {
+        throw new Error('Profiles must be a JSON object keyed by profile name');
+    }
+    for (const [name, options] of Object.entries(parsed)) {
+        if (!isPlainObject(options)) {
+            throw new Error(`Profile ${name} must be an object`);
+        }
+        for (const field of ['profile', 'correlationId', 'requestId', 'cancelToken']) {
+            if (options[field] !== undefined) {
+                throw new Error(`Profile ${name} must not set ${field}`);
+            }
+        }
+    }
+    profiles.clear();
+    for (const [name, options] of Object.entries(parsed)) {
+        profiles.set(name, options);
+    }
+    return [...profiles.keys()].sort();
+}
+
+/**
+ * 展开选项中的 profile：调用中设置的选项优先，嵌套的编码选项（webp、jpeg 等）逐项合并
+ *
+ * @param {Object} options - 用户选项
+ * @returns {Object} 展开后的选项（不再包含 profile）
+ */
+export function resolveProfile(options = {}) {
+    if (options.profile === undefined || options.profile === null) {
+        return options;
+    }
+    const { profile: name, ...rest } = options;
+    const profile = profiles.get(name);
+    if (!profile) {
+        throw new Error(`Unknown render profile: ${name}`);
+    }
+    const resolved = { ...profile };
+    for (const [key, value] of Object.entries(rest)) {
+        if (value === undefined) continue;
+        resolved[key] = isPlainObject(value) && isPlainObject(profile[key]) ? { ...profile[key], ...value } : value;
+    }
+    return resolved;
+}
 
 /**
  * 合并用户配置与默认配置
@@ -54,24 +123,218 @@ export const SUPPORTED_FORMATS = ['webp', 'png', 'jpg', 'jpeg'];
  * @returns {Object} 合并后的配置（用于原生渲染器）
  */
 export function mergeConfig(userConfig = {}) {
+    userConfig = resolveProfile(userConfig);
     const format = userConfig.format ?? RENDER_CONFIG.OUTPUT_FORMAT;
     
     return {
-        targetWidth: userConfig.targetWidth ?? RENDER_CONFIG.TARGET_RENDER_WIDTH,
+        // dpi 与 targetWidth 互斥：指定 dpi 时不再补默认宽度，maxScale 未指定时由 native 按 dpi 放宽
+        targetWidth: userConfig.dpi ? userConfig.targetWidth : userConfig.targetWidth ?? RENDER_CONFIG.TARGET_RENDER_WIDTH,
+        dpi: userConfig.dpi,
         imageHeavyWidth: userConfig.imageHeavyWidth ?? RENDER_CONFIG.IMAGE_HEAVY_TARGET_WIDTH,
-        maxScale: userConfig.maxScale ?? RENDER_CONFIG.MAX_RENDER_SCALE,
+        maxScale: userConfig.maxScale ?? (userConfig.dpi ? undefined : RENDER_CONFIG.MAX_RENDER_SCALE),
+        // 扫描件降级宽度被 maxScale 限制时按内嵌图片的原始分辨率渲染
+        preferSharpness: userConfig.preferSharpness,
         detectScan: userConfig.detectScan ?? true,
+        // 扫描件识别阈值：图片最小覆盖比例、最多文字对象数
+        scanMinImageCoverage: userConfig.scanMinImageCoverage,
+        scanMaxTextObjects: userConfig.scanMaxTextObjects,
+        // 内存预算：单页像素上限、位图总字节预算，超出时缩小或失败
+        maxPixelsPerPage: userConfig.maxPixelsPerPage,
+        maxTotalBytes: userConfig.maxTotalBytes,
+        memoryBudgetAction: userConfig.memoryBudgetAction,
         format,
         
         // WebP 编码配置
         webpQuality: userConfig.webp?.quality ?? userConfig.quality ?? ENCODER_CONFIG.WEBP_QUALITY,
         webpMethod: userConfig.webp?.method ?? ENCODER_CONFIG.WEBP_METHOD,
+        webpMethodAuto: userConfig.webp?.methodAuto,
         
         // JPEG 编码配置
         jpegQuality: userConfig.jpeg?.quality ?? userConfig.quality ?? ENCODER_CONFIG.JPEG_QUALITY,
+        jpegProgressive: userConfig.jpeg?.progressive,
+        jpegChromaSubsampling: userConfig.jpeg?.chromaSubsampling,
         
         // PNG 编码配置
         pngCompression: userConfig.png?.compressionLevel ?? ENCODER_CONFIG.PNG_COMPRESSION,
+
+        // AVIF 编码配置（原生渲染器使用内置的 ravif 编码）
+        avifQuality: userConfig.avif?.quality ?? userConfig.quality ?? ENCODER_CONFIG.AVIF_QUALITY,
+        avifSpeed: userConfig.avif?.speed ?? ENCODER_CONFIG.AVIF_SPEED,
+
+        // 请求关联 ID（原生层原样回显）
+        correlationId: userConfig.correlationId ?? userConfig.requestId,
+
+        // 总时间预算（毫秒），超出后剩余页面标记为 skipped
+        totalTimeBudgetMs: userConfig.totalTimeBudgetMs,
+
+        // 单页编码时间预算（毫秒），超出后 WebP 改用最快的编码参数
+        encodeBudgetMs: userConfig.encodeBudgetMs,
+        // 单页渲染超时：超出后该页以 RENDER_TIMEOUT 失败
+        pageTimeoutMs: userConfig.pageTimeoutMs,
+
+        // 流式渲染时允许同时活动的流式任务数上限
+        maxConcurrentStreams: userConfig.maxConcurrentStreams,
+
+        // 流式渲染的并行请求数（顺序读取时预取后续块）
+        fetchLanes: userConfig.fetchLanes,
+        // 流式渲染顺序读取时随当前块一起获取的后续块数
+        streamReadahead: userConfig.streamReadahead,
+
+        // 流式渲染前计算页面需要的字节范围，一次批量请求
+        fetchPlan: userConfig.fetchPlan,
+        // 线性化文件打开前预取首页段落（默认开启）
+        linearizedFastPath: userConfig.linearizedFastPath,
+
+        // 流式读取效率过低时改为一次下载整个文件的条件
+        streamFallbackFraction: userConfig.streamFallbackFraction,
+        streamFallbackRequests: userConfig.streamFallbackRequests,
+
+        // 流式渲染的块大小、缓存块数和单块等待超时
+        streamBlockSize: userConfig.streamBlockSize,
+        streamMaxCacheBlocks: userConfig.streamMaxCacheBlocks,
+        streamFetchTimeoutMs: userConfig.streamFetchTimeoutMs,
+
+        // 常驻文档空闲超过该时间后清空流式块缓存
+        documentIdleTimeoutMs: userConfig.documentIdleTimeoutMs,
+
+        // 内嵌缩略图为 JPEG/JPEG 2000 时原样返回
+        thumbnailPassthrough: userConfig.thumbnailPassthrough,
+        // 没有内嵌缩略图的页面按该宽度渲染
+        thumbnailFallbackWidth: userConfig.thumbnailFallbackWidth,
+
+        // 两遍渲染的预览宽度（传入 onPreview 时生效）
+        previewWidth: userConfig.previewWidth,
+
+        // 扫描页面只有一张整页 JPEG/JPEG 2000 图片时原样返回
+        scanPassthrough: userConfig.scanPassthrough,
+
+        // 按页码覆盖目标宽度 / DPI / 扫描件降级宽度
+        pageWidths: userConfig.pageWidths,
+        pageDpi: userConfig.pageDpi,
+        pageScanMode: userConfig.pageScanMode,
+
+        // 按页码覆盖输出格式
+        pageFormats: userConfig.pageFormats,
+
+        // 缩略图拼图列数
+        spriteColumns: userConfig.spriteColumns,
+
+        // 跨页渲染时封面是否单独成页
+        spreadCover: userConfig.spreadCover,
+
+        // 文字位置粒度（getPageTextRects）
+        textGranularity: userConfig.textGranularity,
+
+        // 租户标记（调度器按租户执行限额）
+        tenant: userConfig.tenant,
+
+        // 固定尺寸画布（页面等比缩放后填充）
+        canvasWidth: userConfig.canvasWidth,
+        canvasHeight: userConfig.canvasHeight,
+        padColor: userConfig.padColor,
+        padAlign: userConfig.padAlign,
+
+        // 超出 WebP 尺寸上限时的处理方式
+        oversizeStrategy: userConfig.oversizeStrategy,
+        tileSize: userConfig.tileSize,
+
+        // 重复页判定的最大哈希距离
+        duplicateThreshold: userConfig.duplicateThreshold,
+
+        // 编码器后端（原生编码时生效）
+        encoderBackend: userConfig.encoderBackend,
+
+        // 合成到页面上的高亮区域
+        highlights: userConfig.highlights,
+
+        // 水印：文字或 RGBA 图片，合成到每页渲染结果上
+        stampText: userConfig.stampText,
+        stampImage: userConfig.stampImage,
+        stampImageWidth: userConfig.stampImageWidth,
+        stampImageHeight: userConfig.stampImageHeight,
+        stampFontSize: userConfig.stampFontSize,
+        stampColor: userConfig.stampColor,
+        stampOpacity: userConfig.stampOpacity,
+        stampPosition: userConfig.stampPosition,
+        stampMargin: userConfig.stampMargin,
+        stampExclusions: userConfig.stampExclusions,
+
+        // JPEG 中半透明像素混合的背景色
+        jpegBackground: userConfig.jpegBackground,
+
+        // 渲染结果略超尺寸上限时裁边而不是重新缩放的容差（像素）
+        resizeTolerance: userConfig.resizeTolerance,
+
+        // 页码大于总页数时跳过该页还是整体失败
+        outOfRangePages: userConfig.outOfRangePages,
+
+        // 纯文字页面的灰度编码（原生编码时生效）
+        smartEncoding: userConfig.smartEncoding,
+
+        // 文档加密时依次尝试的候选密码
+        passwords: userConfig.passwords,
+
+        // 文档密码（先于 passwords 尝试）
+        password: userConfig.password,
+
+        // 用同一张位图额外编码的格式（如 ['jpg']）
+        alsoEncode: userConfig.alsoEncode,
+
+        // 渲染前把 CMYK/ICC 图片经 ICC 变换转换到 sRGB，以及 DeviceCMYK 使用的配置文件
+        forceSrgb: userConfig.forceSrgb,
+        cmykProfile: userConfig.cmykProfile,
+
+        // 每页输出图像的亮度统计（直方图、均值、对比度、熵）
+        imageStats: userConfig.imageStats,
+
+        // 每页输出位图的内容哈希（XXH64）
+        contentHash: userConfig.contentHash,
+
+        // 检查页面对象能否正常绘制（renderInfo.objectErrors），每张图片额外解码一次
+        objectDiagnostics: userConfig.objectDiagnostics,
+
+        // 只渲染这些类型的注释（如 ['highlight', 'ink']），未指定时渲染全部注释
+        annotationTypes: userConfig.annotationTypes,
+
+        // 表单控件的渲染方式：render（默认）、flatten、omit
+        formMode: userConfig.formMode,
+
+        // PDFium 渲染开关：注释、表单控件、抗锯齿、LCD 文字优化
+        renderAnnotations: userConfig.renderAnnotations,
+        renderForms: userConfig.renderForms,
+        disableTextAntialiasing: userConfig.disableTextAntialiasing,
+        disableImageAntialiasing: userConfig.disableImageAntialiasing,
+        optimizeForLcd: userConfig.optimizeForLcd,
+
+        // 以透明背景渲染，WebP/PNG 输出保留页面的透明区域
+        preserveAlpha: userConfig.preserveAlpha,
+
+        // 渲染会话中最先渲染并交付的页码（首屏）
+        heroPage: userConfig.heroPage,
+
+        // 在结果中返回每页加载、渲染、编码阶段的起止时间（Unix 毫秒）
+        spanTimings: userConfig.spanTimings,
+
+        // 在结果中返回本次调用的 CPU 时间和内存分配（按客户文档核算成本）
+        resourceUsage: userConfig.resourceUsage,
+
+        // renderPageToRawChunks 每个分块的行数（默认约 4 MB 一块）
+        chunkRows: userConfig.chunkRows,
+
+        // 并行编码的线程数（PDFium 渲染仍依次进行，默认 1）
+        concurrency: userConfig.concurrency,
+
+        // 并行编码时 onPage 的回调顺序：completion（完成顺序，默认）或 input（页码顺序）
+        deliveryOrder: userConfig.deliveryOrder,
+
+        // 取消令牌（createCancelToken），cancelRender 后剩余页面以 CANCELLED 返回
+        cancelToken: userConfig.cancelToken,
+
+        // 确定性输出：关闭内存降级和编码预算降级，onPage 按页码顺序回调
+        deterministic: userConfig.deterministic,
+
+        // renderMultipageTiff 的颜色模式（bilevel 使用 CCITT G4，gray / color 使用 LZW）
+        tiffColor: userConfig.tiffColor,
     };
 }
 
@@ -80,6 +343,61 @@ export function mergeConfig(userConfig = {}) {
  * @param {string} format - 格式名称
  * @returns {string} 文件扩展名
  */
+/**
+ * 打开文档时依次尝试的密码：password 在前，passwords 在后
+ * @param {Object} options - 包含 password / passwords 的选项
+ * @returns {string[]|undefined}
+ */
+export function passwordCandidates(options = {}) {
+    if (!options.password) {
+        return options.passwords;
+    }
+    return [options.password, ...(options.passwords || [])];
+}
+
+// 不影响输出图像的选项，不参与配置哈希
+const NON_OUTPUT_KEYS = new Set(['correlationId', 'deadline', 'cancelToken', 'password', 'passwords', 'spanTimings', 'resourceUsage']);
+
+/**
+ * 按键名排序、去掉 undefined 的 JSON，Buffer 按十六进制字符串输出
+ */
+function canonicalJson(value) {
+    if (Buffer.isBuffer(value)) {
+        return JSON.stringify(value.toString('hex'));
+    }
+    if (Array.isArray(value)) {
+        return `[${value.map(item => canonicalJson(item ?? null)).join(',')}]`;
+    }
+    if (value && typeof value === 'object') {
+        const entries = Object.keys(value)
+            .filter(key => value[key] !== undefined)
+            .sort()
+            .map(key => `${JSON.stringify(key)}:${canonicalJson(value[key])}`);
+        return `{${entries.join(',')}}`;
+    }
+    return JSON.stringify(value ?? null);
+}
+
+/**
+ * 结果中回显的完整配置和配置哈希
+ *
+ * resolvedConfig 为合并默认值、命名配置后的选项（密码替换为 '***'）；
+ * configHash 只包含影响输出的选项，与文档指纹组合即可作为缓存键。
+ * @param {Object} config - 合并后的选项
+ * @returns {{ resolvedConfig: Object, configHash: string }}
+ */
+export function describeConfig(config) {
+    const resolvedConfig = { ...config };
+    if (resolvedConfig.password) resolvedConfig.password = '***';
+    if (resolvedConfig.passwords) resolvedConfig.passwords = resolvedConfig.passwords.map(() => '***');
+    delete resolvedConfig.cancelToken;
+    delete resolvedConfig.deadline;
+
+    const output = Object.fromEntries(Object.entries(config).filter(([key]) => !NON_OUTPUT_KEYS.has(key)));
+    const configHash = createHash('sha256').update(canonicalJson(output)).digest('hex').slice(0, 16);
+    return { resolvedConfig, configHash };
+}
+
 export function getExtension(format) {
     if (format === 'jpeg') return 'jpg';
     return format;
@@ -96,6 +414,8 @@ export function getMimeType(format) {
         png: 'image/png',
         jpg: 'image/jpeg',
         jpeg: 'image/jpeg',
+        jp2: 'image/jp2',
+        avif: 'image/avif',
     };
     return mimeTypes[format] || 'image/webp';
 }
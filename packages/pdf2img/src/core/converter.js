@@ -22,7 +22,7 @@ import { fileURLToPath } from 'url';
 import pLimit from 'p-limit';
 import Piscina from 'piscina';
 import { createLogger } from '../utils/logger.js';
-import { RENDER_CONFIG, TIMEOUT_CONFIG, SUPPORTED_FORMATS, getExtension, getMimeType } from './config.js';
+import { RENDER_CONFIG, TIMEOUT_CONFIG, SUPPORTED_FORMATS, getExtension, getMimeType, passwordCandidates, resolveProfile, describeConfig } from './config.js';
 import * as nativeRenderer from '../renderers/native.js';
 
 const logger = createLogger('Converter');
@@ -55,6 +55,14 @@ function getThreadPool() {
     return piscina;
 }
 
+// ==================== 关闭状态 ====================
+
+// 关闭后不再接收新的转换任务
+let acceptingTasks = true;
+
+// 进行中的转换任务
+const inFlightTasks = new Set();
+
 /**
  * 默认并发限制
  */
@@ -70,6 +78,7 @@ export const InputType = {
     FILE: 'file',
     URL: 'url',
     BUFFER: 'buffer',
+    STREAM: 'stream',  // Node Readable 或 Web ReadableStream
 };
 
 /**
@@ -94,7 +103,10 @@ function detectInputType(input) {
         }
         return InputType.FILE;
     }
-    throw new Error('Invalid input: must be a file path, URL, or Buffer');
+    if (input && (typeof input.pipe === 'function' || typeof input.getReader === 'function')) {
+        return InputType.STREAM;
+    }
+    throw new Error('Invalid input: must be a file path, URL, Buffer, or readable stream');
 }
 
 /**
@@ -130,13 +142,22 @@ async function downloadToTempFile(url) {
         throw new Error(`Failed to download file: ${response.status} ${response.statusText}`);
     }
 
+    return streamToTempFile(response.body);
+}
+
+/**
+ * 将可读流（Node Readable 或 Web ReadableStream）写入临时文件
+ *
+ * 不可随机访问的输入先落盘，再由原生模块按文件路径读取。
+ */
+async function streamToTempFile(source) {
     const tempDir = os.tmpdir();
     const tempFile = path.join(tempDir, `pdf2img_${Date.now()}_${Math.random().toString(36).slice(2)}.pdf`);
     
     const fileStream = fs.createWriteStream(tempFile);
     
     try {
-        await pipeline(response.body, fileStream);
+        await pipeline(source, fileStream);
         return tempFile;
     } catch (err) {
         try {
@@ -150,22 +171,85 @@ async function downloadToTempFile(url) {
  * 保存单个页面到文件
  */
 async function savePageToFile(page, outputDir, prefix, ext) {
+    if (page.success && page.tiles) {
+        return saveTilesToFiles(page, outputDir, prefix, ext);
+    }
     if (!page.success || !page.buffer) {
         return { ...page, outputPath: null };
     }
 
     try {
-        const filename = `${prefix}_${page.pageNum}.${ext}`;
+        // 超出 WebP 尺寸上限改用 PNG 的页面使用实际格式的扩展名
+        const filename = `${prefix}_${page.pageNum}.${page.format ? getExtension(page.format) : ext}`;
         const outputPath = path.join(outputDir, filename);
         await fs.promises.writeFile(outputPath, page.buffer);
 
+        // 附加编码与主图同名，仅扩展名不同
+        const alternates = [];
+        for (const alt of page.alternates ?? []) {
+            if (!alt.buffer) {
+                alternates.push({ format: alt.format, outputPath: null, size: 0, error: alt.error });
+                continue;
+            }
+            const altPath = path.join(outputDir, `${prefix}_${page.pageNum}.${getExtension(alt.format)}`);
+            await fs.promises.writeFile(altPath, alt.buffer);
+            alternates.push({ format: alt.format, outputPath: altPath, size: alt.buffer.length });
+        }
+
         return {
             pageNum: page.pageNum,
             width: page.width,
             height: page.height,
             success: true,
+            format: page.format,
+            renderInfo: page.renderInfo,
+            imageStats: page.imageStats,
+            contentHash: page.contentHash,
+            detectedScan: page.detectedScan,
+            textObjectCount: page.textObjectCount,
+            imageCoverage: page.imageCoverage,
+            downscaledDueToMemory: page.downscaledDueToMemory,
+            spans: page.spans,
             outputPath,
             size: page.buffer.length,
+            alternates: page.alternates && alternates,
+        };
+    } catch (err) {
+        return {
+            pageNum: page.pageNum,
+            width: page.width,
+            height: page.height,
+            success: false,
+            error: `File save failed: ${err.message}`,
+            errorCode: 'OUTPUT_FAILED',
+            outputPath: null,
+        };
+    }
+}
+
+/**
+ * 保存分块页面：每块单独一个文件，文件名为 `${prefix}_${pageNum}_${row}_${column}.${ext}`
+ */
+async function saveTilesToFiles(page, outputDir, prefix, ext) {
+    const { tiles, ...grid } = page.tiles;
+    try {
+        const saved = [];
+        for (const tile of tiles) {
+            const outputPath = path.join(outputDir, `${prefix}_${page.pageNum}_${tile.row}_${tile.column}.${ext}`);
+            await fs.promises.writeFile(outputPath, tile.buffer);
+            const { buffer, ...rect } = tile;
+            saved.push({ ...rect, outputPath, size: buffer.length });
+        }
+        return {
+            pageNum: page.pageNum,
+            width: page.width,
+            height: page.height,
+            success: true,
+            format: page.format,
+            renderInfo: page.renderInfo,
+            outputPath: null,
+            tiles: { ...grid, tiles: saved },
+            size: page.size,
         };
     } catch (err) {
         return {
@@ -174,6 +258,7 @@ async function savePageToFile(page, outputDir, prefix, ext) {
             height: page.height,
             success: false,
             error: `File save failed: ${err.message}`,
+            errorCode: 'OUTPUT_FAILED',
             outputPath: null,
         };
     }
@@ -199,33 +284,59 @@ async function saveToFiles(pages, outputDir, prefix = 'page', format = 'webp', c
  * 上传单个页面到 COS
  */
 async function uploadPageToCos(page, cos, cosConfig, keyPrefix, ext, mimeType) {
+    if (page.success && page.tiles) {
+        return uploadTilesToCos(page, cos, cosConfig, keyPrefix, ext, mimeType);
+    }
     if (!page.success || !page.buffer) {
         return { ...page, cosKey: null };
     }
 
     try {
-        const key = `${keyPrefix}/page_${page.pageNum}.${ext}`;
-
-        await new Promise((resolve, reject) => {
+        const key = `${keyPrefix}/page_${page.pageNum}.${page.format ? getExtension(page.format) : ext}`;
+        const putObject = (Key, Body, ContentType) => new Promise((resolve, reject) => {
             cos.putObject({
                 Bucket: cosConfig.bucket,
                 Region: cosConfig.region,
-                Key: key,
-                Body: page.buffer,
-                ContentType: mimeType,
+                Key,
+                Body,
+                ContentType,
             }, (err) => {
                 if (err) reject(err);
                 else resolve();
             });
         });
 
+        await putObject(key, page.buffer, page.format ? getMimeType(page.format) : mimeType);
+
+        // 附加编码与主图同名，仅扩展名不同
+        const alternates = [];
+        for (const alt of page.alternates ?? []) {
+            if (!alt.buffer) {
+                alternates.push({ format: alt.format, cosKey: null, size: 0, error: alt.error });
+                continue;
+            }
+            const altKey = `${keyPrefix}/page_${page.pageNum}.${getExtension(alt.format)}`;
+            await putObject(altKey, alt.buffer, getMimeType(alt.format));
+            alternates.push({ format: alt.format, cosKey: altKey, size: alt.buffer.length });
+        }
+
         return {
             pageNum: page.pageNum,
             width: page.width,
             height: page.height,
             success: true,
+            format: page.format,
+            renderInfo: page.renderInfo,
+            imageStats: page.imageStats,
+            contentHash: page.contentHash,
+            detectedScan: page.detectedScan,
+            textObjectCount: page.textObjectCount,
+            imageCoverage: page.imageCoverage,
+            downscaledDueToMemory: page.downscaledDueToMemory,
+            spans: page.spans,
             cosKey: key,
             size: page.buffer.length,
+            alternates: page.alternates && alternates,
         };
     } catch (err) {
         return {
@@ -234,6 +345,55 @@ async function uploadPageToCos(page, cos, cosConfig, keyPrefix, ext, mimeType) {
             height: page.height,
             success: false,
             error: `Upload failed: ${err.message}`,
+            errorCode: 'OUTPUT_FAILED',
+            cosKey: null,
+        };
+    }
+}
+
+/**
+ * 上传分块页面：每块单独一个对象，key 为 `${keyPrefix}/page_${pageNum}_${row}_${column}.${ext}`
+ */
+async function uploadTilesToCos(page, cos, cosConfig, keyPrefix, ext, mimeType) {
+    const { tiles, ...grid } = page.tiles;
+    try {
+        const uploaded = [];
+        for (const tile of tiles) {
+            const key = `${keyPrefix}/page_${page.pageNum}_${tile.row}_${tile.column}.${ext}`;
+            await new Promise((resolve, reject) => {
+                cos.putObject({
+                    Bucket: cosConfig.bucket,
+                    Region: cosConfig.region,
+                    Key: key,
+                    Body: tile.buffer,
+                    ContentType: mimeType,
+                }, (err) => {
+                    if (err) reject(err);
+                    else resolve();
+                });
+            });
+            const { buffer, ...rect } = tile;
+            uploaded.push({ ...rect, cosKey: key, size: buffer.length });
+        }
+        return {
+            pageNum: page.pageNum,
+            width: page.width,
+            height: page.height,
+            success: true,
+            format: page.format,
+            renderInfo: page.renderInfo,
+            cosKey: null,
+            tiles: { ...grid, tiles: uploaded },
+            size: page.size,
+        };
+    } catch (err) {
+        return {
+            pageNum: page.pageNum,
+            width: page.width,
+            height: page.height,
+            success: false,
+            error: `Upload failed: ${err.message}`,
+            errorCode: 'OUTPUT_FAILED',
             cosKey: null,
         };
     }
@@ -261,14 +421,35 @@ async function uploadToCos(pages, cosConfig, keyPrefix, format = 'webp', concurr
     return results.sort((a, b) => a.pageNum - b.pageNum);
 }
 
+/**
+ * 汇总各页的资源占用：CPU 时间和累计分配相加，峰值取单页峰值的最大值
+ *
+ * @param {Object[]} pages - 工作线程返回的页面结果
+ * @returns {Object|undefined} 没有页面带 resourceUsage 时为 undefined
+ */
+function sumResourceUsage(pages) {
+    const usages = pages.map(p => p.resourceUsage).filter(Boolean);
+    if (usages.length === 0) {
+        return undefined;
+    }
+    // 原生模块未启用 alloc-usage 特性时没有内存统计
+    const allocated = usages.every(u => u.peakAllocBytes != null);
+    return {
+        cpuUserMs: usages.reduce((sum, u) => sum + u.cpuUserMs, 0),
+        cpuSystemMs: usages.reduce((sum, u) => sum + u.cpuSystemMs, 0),
+        peakAllocBytes: allocated ? Math.max(...usages.map(u => u.peakAllocBytes)) : undefined,
+        totalAllocBytes: allocated ? usages.reduce((sum, u) => sum + u.totalAllocBytes, 0) : undefined,
+    };
+}
+
 /**
  * 使用线程池渲染 PDF 页面
  * 
  * 主线程负责协调，工作线程负责 CPU 密集型任务
  * 
- * @param {string|Buffer} input - 输入
+ * @param {string|Buffer|Readable|ReadableStream} input - 输入
  * @param {string} inputType - 输入类型
- * @param {number[]} pages - 页码数组
+ * @param {number[]|string|Object} pages - 页码数组、范围字符串或 { from, to }
  * @param {Object} options - 选项
  * @returns {Promise<Object>} 渲染结果
  */
@@ -287,27 +468,31 @@ async function renderPages(input, inputType, pages, options) {
             throw new Error(`File not found or not readable: ${input}`);
         }
         filePath = input;
-        numPages = nativeRenderer.getPageCountFromFile(filePath);
+        numPages = nativeRenderer.getPageCountFromFile(filePath, passwordCandidates(options));
     } else if (inputType === InputType.BUFFER) {
         pdfBuffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
-        numPages = nativeRenderer.getPageCount(pdfBuffer);
+        numPages = nativeRenderer.getPageCount(pdfBuffer, passwordCandidates(options));
     } else if (inputType === InputType.URL) {
         const fileSize = await getRemoteFileSize(input);
-        logger.debug(`Remote file size: ${(fileSize / 1024 / 1024).toFixed(2)}MB, downloading...`);
+        logger.debug(`Remote file size: ${(fileSize / 1024 / 1024).toFixed(2)}MB, downloading...`, { correlationId: options.correlationId });
         tempFile = await downloadToTempFile(input);
         filePath = tempFile;
-        numPages = nativeRenderer.getPageCountFromFile(filePath);
+        numPages = nativeRenderer.getPageCountFromFile(filePath, passwordCandidates(options));
+    } else if (inputType === InputType.STREAM) {
+        tempFile = await streamToTempFile(input);
+        filePath = tempFile;
+        numPages = nativeRenderer.getPageCountFromFile(filePath, passwordCandidates(options));
     }
 
-    // 确定目标页码
-    let targetPages;
-    if (pages.length === 0) {
-        targetPages = Array.from({ length: numPages }, (_, i) => i + 1);
-    } else {
-        targetPages = pages.filter(p => p >= 1 && p <= numPages);
+    // 确定目标页码；大于总页数的页码默认标记为跳过，outOfRangePages 为 'fail' 时整体失败；
+    // 范围字符串和 { from, to } 由原生层展开，超出总页数时直接抛出错误
+    const targetPages = nativeRenderer.resolvePages(pages, numPages);
+    const outOfRangePages = Array.isArray(pages) ? pages.filter(p => p > numPages) : [];
+    if (outOfRangePages.length > 0 && options.outOfRangePages === 'fail') {
+        throw new Error(`Page out of range: ${outOfRangePages[0]} (total: ${numPages})`);
     }
 
-    logger.debug(`Rendering ${targetPages.length} pages using thread pool (${threadCount} workers)`);
+    logger.debug(`Rendering ${targetPages.length} pages using thread pool (${threadCount} workers)`, { correlationId: options.correlationId });
 
     // 获取线程池
     const pool = getThreadPool();
@@ -334,16 +519,32 @@ async function renderPages(input, inputType, pages, options) {
 
         // 等待所有页面的并行处理完成
         const results = await Promise.all(tasks);
+        for (const pageNum of outOfRangePages) {
+            results.push({
+                pageNum,
+                success: false,
+                skipped: true,
+                error: `Skipped: page ${pageNum} out of range (total: ${numPages})`,
+                errorCode: 'OUT_OF_RANGE',
+                width: 0,
+                height: 0,
+                buffer: null,
+                renderTime: 0,
+                encodeTime: 0,
+            });
+        }
 
         results.sort((a, b) => a.pageNum - b.pageNum);
 
         return {
             success: true,
             numPages,
+            passwordIndex: results.find(p => p.passwordIndex !== undefined)?.passwordIndex,
             pages: results,
             totalTime: Date.now() - startTime,
             renderTime: results.reduce((sum, p) => sum + (p.renderTime || 0), 0),
             encodeTime: results.reduce((sum, p) => sum + (p.encodeTime || 0), 0),
+            resourceUsage: options.resourceUsage ? sumResourceUsage(results) : undefined,
         };
     } finally {
         // 清理临时文件
@@ -358,9 +559,10 @@ async function renderPages(input, inputType, pages, options) {
 /**
  * PDF 转图片
  *
- * @param {string|Buffer} input - PDF 输入（文件路径、URL 或 Buffer）
+ * @param {string|Buffer|Readable|ReadableStream} input - PDF 输入（文件路径、URL、Buffer 或可读流）
  * @param {Object} options - 转换选项
- * @param {number[]} [options.pages] - 要转换的页码（1-based），空数组表示全部
+ * @param {number[]|string|Object} [options.pages] - 要转换的页码（1-based），空数组表示全部；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
  * @param {string} [options.outputType='buffer'] - 输出类型：'file'、'buffer'、'cos'
  * @param {string} [options.outputDir] - 输出目录（outputType='file' 时必需）
  * @param {string} [options.prefix='page'] - 输出文件名前缀
@@ -369,17 +571,60 @@ async function renderPages(input, inputType, pages, options) {
  * @param {Object} [options.webp] - WebP 编码配置
  * @param {number} [options.webp.quality] - WebP 质量（0-100，默认 80）
  * @param {number} [options.webp.method] - WebP 编码方法（0-6，默认 4，0最快6最慢）
+ * @param {boolean} [options.webp.methodAuto] - 按页面像素数调整编码方法（缩略图提高、超大页面降低），method 作为常规页面的基准
  * @param {Object} [options.jpeg] - JPEG 编码配置
  * @param {number} [options.jpeg.quality] - JPEG 质量（0-100，默认 85）
+ * @param {boolean} [options.jpeg.progressive] - 输出渐进式 JPEG（默认 false）
+ * @param {string} [options.jpeg.chromaSubsampling] - 色度抽样：'444'（默认）或 '420'
  * @param {Object} [options.png] - PNG 编码配置
  * @param {number} [options.png.compressionLevel] - PNG 压缩级别（0-9，默认 6）
+ * @param {Object} [options.avif] - AVIF 编码配置（format 为 'avif' 时）
+ * @param {number} [options.avif.quality] - AVIF 质量（0-100，默认 50）
+ * @param {number} [options.avif.speed] - AVIF 编码速度（0-10，0 最慢但压缩最好，默认 6）
  * @param {Object} [options.cos] - COS 配置（outputType='cos' 时必需）
  * @param {string} [options.cosKeyPrefix] - COS key 前缀
  * @param {number} [options.targetWidth] - 目标渲染宽度（默认 1280）
+ * @param {number} [options.dpi] - 渲染分辨率（如 150、300），按页面物理尺寸计算渲染尺寸，与 targetWidth 互斥
  * @param {number} [options.concurrency] - 文件/上传并发数
+ * @param {string} [options.correlationId] - 请求关联 ID（别名 requestId），回显在结果和日志中
+ * @param {number} [options.totalTimeBudgetMs] - 总时间预算（毫秒），超出后剩余页面标记为 skipped
+ * @param {number} [options.encodeBudgetMs] - 单页编码时间预算（毫秒），超出后 WebP 改用最快的编码参数
+ * @param {number} [options.pageTimeoutMs] - 单页渲染超时（毫秒），超出后该页以 RENDER_TIMEOUT 失败
+ * @param {boolean} [options.deterministic] - 确定性输出：相同输入和选项总是得到逐字节相同的图像（忽略 encodeBudgetMs）
+ * @param {string[]} [options.alsoEncode] - 额外输出的格式（如 ['jpg']），由同一张位图编码
+ * @param {boolean} [options.forceSrgb] - 渲染前把 CMYK/ICC 图片经 ICC 变换转换到 sRGB
+ * @param {Buffer} [options.cmykProfile] - DeviceCMYK 图片使用的 CMYK ICC 配置文件
+ * @param {boolean} [options.imageStats] - 在结果中返回每页的亮度直方图和熵等统计
+ * @param {boolean} [options.contentHash] - 在结果中返回每页输出位图的内容哈希（XXH64），可作为 CDN 缓存的键
+ * @param {boolean} [options.objectDiagnostics] - 检查图片等页面对象能否正常绘制，结果见 renderInfo.objectErrors
+ * @param {string[]} [options.annotationTypes] - 只渲染这些类型的注释（如 ['highlight', 'ink']）
+ * @param {string} [options.formMode] - 表单控件的渲染方式：render（默认）、flatten 或 omit
+ * @param {boolean} [options.renderAnnotations] - 是否渲染注释，默认 true
+ * @param {boolean} [options.renderForms] - 是否渲染表单控件，默认 true（false 等价于 formMode: 'omit'）
+ * @param {boolean} [options.disableTextAntialiasing] - 关闭文字抗锯齿
+ * @param {boolean} [options.disableImageAntialiasing] - 关闭图片抗锯齿
+ * @param {boolean} [options.optimizeForLcd] - 针对 LCD 屏幕优化文字渲染
+ * @param {boolean} [options.preserveAlpha] - 以透明背景渲染，WebP/PNG 输出保留透明区域
+ * @param {boolean} [options.spanTimings] - 在结果中返回每页各阶段的起止时间（spans）
+ * @param {boolean} [options.resourceUsage] - 在结果中返回原生渲染的 CPU 时间和内存分配（resourceUsage，不含 Sharp 编码）
+ * @param {AbortSignal} [options.signal] - 取消信号：触发后不再渲染剩余页面，已完成的页面照常返回
  * @returns {Promise<Object>} 转换结果
  */
 export async function convert(input, options = {}) {
+    if (!acceptingTasks) {
+        throw new Error('Converter is shutting down');
+    }
+
+    const task = convertInternal(input, resolveProfile(options));
+    inFlightTasks.add(task);
+    try {
+        return await task;
+    } finally {
+        inFlightTasks.delete(task);
+    }
+}
+
+async function convertInternal(input, options) {
     const startTime = Date.now();
 
     const {
@@ -391,6 +636,7 @@ export async function convert(input, options = {}) {
         cos: cosConfig,
         cosKeyPrefix = `pdf2img/${Date.now()}`,
         concurrency,
+        correlationId = options.requestId,
         ...renderOptions
     } = options;
 
@@ -399,6 +645,14 @@ export async function convert(input, options = {}) {
     if (!SUPPORTED_FORMATS.includes(normalizedFormat)) {
         throw new Error(`Unsupported format: ${format}. Supported formats: ${SUPPORTED_FORMATS.join(', ')}`);
     }
+    const pageFormats = renderOptions.pageFormats && Object.fromEntries(
+        Object.entries(renderOptions.pageFormats).map(([pageNum, pageFormat]) => [pageNum, String(pageFormat).toLowerCase()])
+    );
+    for (const [pageNum, pageFormat] of Object.entries(pageFormats ?? {})) {
+        if (!SUPPORTED_FORMATS.includes(pageFormat)) {
+            throw new Error(`Unsupported format for page ${pageNum}: ${pageFormat}. Supported formats: ${SUPPORTED_FORMATS.join(', ')}`);
+        }
+    }
 
     // 检查渲染器可用性
     if (!nativeRenderer.isNativeAvailable()) {
@@ -407,7 +661,7 @@ export async function convert(input, options = {}) {
 
     // 检测输入类型
     const inputType = detectInputType(input);
-    logger.debug(`Input type: ${inputType}`);
+    logger.debug(`Input type: ${inputType}`, { correlationId });
 
     // 构建编码选项
     const encodeOptions = {
@@ -415,14 +669,74 @@ export async function convert(input, options = {}) {
         quality: renderOptions.quality,
         webpQuality: renderOptions.webp?.quality,
         webpMethod: renderOptions.webp?.method,
+        webpMethodAuto: renderOptions.webp?.methodAuto,
         jpegQuality: renderOptions.jpeg?.quality,
+        jpegProgressive: renderOptions.jpeg?.progressive,
+        jpegChromaSubsampling: renderOptions.jpeg?.chromaSubsampling,
         pngCompression: renderOptions.png?.compressionLevel,
+        // 编码预算按耗时改变编码参数，确定性输出时不生效
+        encodeBudgetMs: renderOptions.deterministic ? undefined : renderOptions.encodeBudgetMs,
+        pageTimeoutMs: renderOptions.pageTimeoutMs,
+        deterministic: renderOptions.deterministic,
+        avifQuality: renderOptions.avif?.quality,
+        avifSpeed: renderOptions.avif?.speed,
         targetWidth: renderOptions.targetWidth,
+        dpi: renderOptions.dpi,
+        pageWidths: renderOptions.pageWidths,
+        pageDpi: renderOptions.pageDpi,
+        pageScanMode: renderOptions.pageScanMode,
+        pageFormats,
+        canvasWidth: renderOptions.canvasWidth,
+        canvasHeight: renderOptions.canvasHeight,
+        padColor: renderOptions.padColor,
+        padAlign: renderOptions.padAlign,
+        oversizeStrategy: renderOptions.oversizeStrategy,
+        tileSize: renderOptions.tileSize,
+        highlights: renderOptions.highlights,
+        stampText: renderOptions.stampText,
+        stampImage: renderOptions.stampImage,
+        stampImageWidth: renderOptions.stampImageWidth,
+        stampImageHeight: renderOptions.stampImageHeight,
+        stampFontSize: renderOptions.stampFontSize,
+        stampColor: renderOptions.stampColor,
+        stampOpacity: renderOptions.stampOpacity,
+        stampPosition: renderOptions.stampPosition,
+        stampMargin: renderOptions.stampMargin,
+        stampExclusions: renderOptions.stampExclusions,
+        jpegBackground: renderOptions.jpegBackground,
+        outOfRangePages: renderOptions.outOfRangePages,
+        passwords: renderOptions.passwords,
+        password: renderOptions.password,
+        alsoEncode: renderOptions.alsoEncode,
+        forceSrgb: renderOptions.forceSrgb,
+        cmykProfile: renderOptions.cmykProfile,
+        imageStats: renderOptions.imageStats,
+        contentHash: renderOptions.contentHash,
+        objectDiagnostics: renderOptions.objectDiagnostics,
+        annotationTypes: renderOptions.annotationTypes,
+        formMode: renderOptions.formMode,
+        renderAnnotations: renderOptions.renderAnnotations,
+        renderForms: renderOptions.renderForms,
+        disableTextAntialiasing: renderOptions.disableTextAntialiasing,
+        disableImageAntialiasing: renderOptions.disableImageAntialiasing,
+        optimizeForLcd: renderOptions.optimizeForLcd,
+        preserveAlpha: renderOptions.preserveAlpha,
+        spanTimings: renderOptions.spanTimings,
+        resourceUsage: renderOptions.resourceUsage,
         detectScan: renderOptions.detectScan,
+        scanMinImageCoverage: renderOptions.scanMinImageCoverage,
+        scanMaxTextObjects: renderOptions.scanMaxTextObjects,
+        maxPixelsPerPage: renderOptions.maxPixelsPerPage,
+        maxTotalBytes: renderOptions.maxTotalBytes,
+        memoryBudgetAction: renderOptions.memoryBudgetAction,
+        correlationId,
+        // 总时间预算从转换开始计算（包含下载等输入准备），到期后尚未开始的页面由工作线程直接跳过
+        deadline: renderOptions.totalTimeBudgetMs ? startTime + renderOptions.totalTimeBudgetMs : undefined,
     };
 
-    // 使用线程池渲染页面
-    const result = await renderPages(input, inputType, pages, encodeOptions);
+    // 使用线程池渲染页面；signal 触发后尚未完成的页面以 CANCELLED 返回
+    const result = await nativeRenderer.withAbortSignal(renderOptions.signal, cancelToken =>
+        renderPages(input, inputType, pages, { ...encodeOptions, cancelToken }));
 
     // 处理输出
     let outputResult;
@@ -446,15 +760,38 @@ export async function convert(input, options = {}) {
             width: page.width,
             height: page.height,
             success: page.success,
+            skipped: page.skipped,
+            format: page.format,
             buffer: page.success ? page.buffer : null,
+            tiles: page.tiles,
+            alternates: page.alternates,
+            renderInfo: page.renderInfo,
+            imageStats: page.imageStats,
+            contentHash: page.contentHash,
+            detectedScan: page.detectedScan,
+            textObjectCount: page.textObjectCount,
+            imageCoverage: page.imageCoverage,
+            downscaledDueToMemory: page.downscaledDueToMemory,
+            spans: page.spans,
+            clamped: page.clamped ?? false,
+            requestedSize: page.requestedSize,
             error: page.error,
+            errorCode: page.errorCode,
         })).sort((a, b) => a.pageNum - b.pageNum);
     }
 
+    const { resolvedConfig, configHash } = describeConfig(encodeOptions);
+
     return {
         success: true,
+        correlationId,
+        resolvedConfig,
+        configHash,
         numPages: result.numPages,
+        passwordIndex: result.passwordIndex,
         renderedPages: outputResult.filter(p => p.success).length,
+        skippedPages: outputResult.filter(p => p.skipped).length,
+        cancelled: result.pages.some(page => page.errorCode === 'CANCELLED'),
         format: normalizedFormat,
         pages: outputResult,
         timing: {
@@ -465,6 +802,7 @@ export async function convert(input, options = {}) {
         threadPool: {
             workers: threadCount,
         },
+        resourceUsage: result.resourceUsage,
     };
 }
 
@@ -472,15 +810,18 @@ export async function convert(input, options = {}) {
  * 获取 PDF 页数（异步版本）
  *
  * @param {string|Buffer} input - PDF 输入（文件路径或 Buffer）
- * @returns {Promise<number>} 页数
+ * @param {Object} [options] - 选项
+ * @param {string} [options.password] - 文档密码
+ * @param {string[]} [options.passwords] - 文档加密时依次尝试的候选密码
+ * @returns {Promise<number>} 页数（密码错误时抛出 code 为 WRONG_PASSWORD 的错误）
  */
-export async function getPageCount(input) {
+export async function getPageCount(input, options = {}) {
     if (!nativeRenderer.isNativeAvailable()) {
         throw new Error('Native renderer is not available');
     }
 
     if (Buffer.isBuffer(input)) {
-        return nativeRenderer.getPageCount(input);
+        return nativeRenderer.getPageCount(input, passwordCandidates(options));
     }
     
     if (typeof input === 'string') {
@@ -489,7 +830,7 @@ export async function getPageCount(input) {
         } catch {
             throw new Error(`File not found or not readable: ${input}`);
         }
-        return nativeRenderer.getPageCountFromFile(input);
+        return nativeRenderer.getPageCountFromFile(input, passwordCandidates(options));
     }
     
     throw new Error('Invalid input: must be a file path or Buffer');
@@ -563,3 +904,43 @@ export async function destroyThreadPool() {
         logger.info('Thread pool destroyed');
     }
 }
+
+/**
+ * 优雅关闭
+ *
+ * 立即停止接收新的转换任务，等待进行中的任务完成（最多 drainTimeoutMs），
+ * 然后销毁线程池并关闭原生渲染器。用于滚动发布时进程退出前的清理。
+ *
+ * @param {Object} [options] - 关闭选项
+ * @param {number} [options.drainTimeoutMs=30000] - 等待进行中任务的最长时间（毫秒）
+ * @returns {Promise<Object>} { drained, pending, native }
+ */
+export async function shutdown({ drainTimeoutMs = 30000 } = {}) {
+    const startTime = Date.now();
+    acceptingTasks = false;
+
+    logger.info(`Shutting down, waiting for ${inFlightTasks.size} in-flight tasks`);
+
+    let timer;
+    const timeout = new Promise(resolve => {
+        timer = setTimeout(resolve, drainTimeoutMs);
+    });
+    await Promise.race([Promise.allSettled([...inFlightTasks]), timeout]);
+    clearTimeout(timer);
+
+    const pending = inFlightTasks.size;
+    if (pending > 0) {
+        logger.warn(`Drain timeout reached with ${pending} tasks still running`);
+    }
+
+    await destroyThreadPool();
+
+    const remainingMs = Math.max(0, drainTimeoutMs - (Date.now() - startTime));
+    const native = await nativeRenderer.shutdown({ drainTimeoutMs: remainingMs });
+
+    return {
+        drained: pending === 0 && native.drained,
+        pending,
+        native,
+    };
+}
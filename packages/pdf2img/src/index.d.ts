@@ -5,14 +5,401 @@
 export interface RenderOptions {
     /** 目标渲染宽度（像素），默认：1280 */
     targetWidth?: number;
+    /**
+     * 渲染分辨率（DPI，如 150、300），按页面物理尺寸计算渲染尺寸，混合页面尺寸的文档物理分辨率一致
+     * 与 targetWidth 互斥（同时指定时每页以 INVALID_OPTIONS 失败）；未指定 maxScale 时上限按 dpi 放宽
+     */
+    dpi?: number;
     /** 图片密集型页面的目标宽度（像素），默认：1024 */
     imageHeavyWidth?: number;
     /** 最大渲染缩放比例，默认：4.0 */
     maxScale?: number;
+    /**
+     * 扫描件降级宽度超出 maxScale 时，按内嵌图片的原始分辨率渲染（不超过 imageHeavyWidth），默认：false
+     * 小尺寸扫描页面（如收据）不再因 maxScale 而比原图模糊
+     */
+    preferSharpness?: boolean;
     /** WebP 质量 0-100，默认：70 */
     webpQuality?: number;
+    /** WebP 编码配置 */
+    webp?: {
+        /** 质量 0-100 */
+        quality?: number;
+        /** 编码方法 0-6（0 最快，6 压缩最好），默认：4 */
+        method?: number;
+        /**
+         * 按页面像素数调整编码方法，单页编码耗时大致恒定：缩略图提高 method，
+         * 超大页面降低 method；method 作为常规尺寸页面的基准。默认：false
+         */
+        methodAuto?: boolean;
+    };
+    /** JPEG 编码配置 */
+    jpeg?: {
+        /** 质量 0-100，默认：85 */
+        quality?: number;
+        /** 输出渐进式 JPEG，大尺寸扫描页面在网页中先显示模糊的整页再逐步变清晰。默认：false */
+        progressive?: boolean;
+        /** 色度抽样：'444' 不抽样，'420' 色度分辨率减半，照片类页面体积约减少 30%。默认：'444' */
+        chromaSubsampling?: '444' | '420';
+    };
+    /**
+     * AVIF 编码配置（format: 'avif'）
+     * convert 使用 sharp 编码；原生渲染接口使用内置的 ravif 编码，也可以用 registerEncoder 注册其他编码器
+     */
+    avif?: {
+        /** 质量 0-100，默认：50 */
+        quality?: number;
+        /** 编码速度 0-10（0 最慢但压缩最好，10 最快），默认：6 */
+        speed?: number;
+    };
     /** 启用扫描件检测，默认：true */
     detectScan?: boolean;
+    /** 识别为扫描件所需的最小图片覆盖比例（0-1），默认 0：至少有一张图片即可；设为 0.5 等值可避免带小插图的页面被当作扫描件 */
+    scanMinImageCoverage?: number;
+    /** 识别为扫描件时允许的最多文字对象数，默认 0；带 OCR 文字层或页眉页码的扫描件可以适当放宽 */
+    scanMaxTextObjects?: number;
+    /** 单页最多像素数（宽 × 高），超出时按 memoryBudgetAction 处理，默认不限制 */
+    maxPixelsPerPage?: number;
+    /** 同时渲染的位图总字节预算（按 RGBA 4 字节/像素 × 并发数估算），默认不限制 */
+    maxTotalBytes?: number;
+    /** 超出 maxPixelsPerPage / maxTotalBytes 时的处理：'downscale' 等比缩小到预算内（downscaledDueToMemory），'fail' 该页失败（LIMIT_EXCEEDED），默认：'downscale' */
+    memoryBudgetAction?: 'downscale' | 'fail';
+    /** 请求关联 ID，回显在结果和日志中，便于串联 JS 层与原生层的链路 */
+    correlationId?: string;
+    /** correlationId 的别名 */
+    requestId?: string;
+    /** 总时间预算（毫秒），超出后剩余页面不再渲染，标记为 skipped 并返回已完成的结果 */
+    totalTimeBudgetMs?: number;
+    /**
+     * 单页编码时间预算（毫秒），与渲染时间分开计算：WebP 编码超出预算时放弃，
+     * 改用最快的编码方法（method 0）和不超过 75 的质量重新编码（renderInfo.encodeBudgetExceeded）
+     * convert 中 Sharp 的超时以秒为单位（向上取整）；其他格式不能中途放弃，不受影响
+     */
+    encodeBudgetMs?: number;
+    /**
+     * 单页渲染超时（毫秒），默认不限制
+     * 损坏的 PDF 可能让 PDFium 在一页上运行几分钟；超出后放弃该页（errorCode 为 RENDER_TIMEOUT），继续渲染其余页面
+     */
+    pageTimeoutMs?: number;
+    /** 流式渲染时允许同时活动的流式任务数上限，达到后排队等待 */
+    maxConcurrentStreams?: number;
+    /** 流式渲染的并行请求数（1-8，默认 1），顺序读取时同时请求后续的块 */
+    fetchLanes?: number;
+    /**
+     * 流式渲染的合并读取块数（0-32，默认 0，仅 renderFromStream）
+     * 检测到顺序读取后，缓存未命中时在同一个请求中连同后续的若干块一起获取，减少往返；可与 fetchLanes 同时使用
+     */
+    streamReadahead?: number;
+    /**
+     * 流式渲染前读取 xref 表和页面树，算出所请求页面需要的字节范围，以一个带 ranges 的请求批量获取（仅 renderFromStream），默认：false
+     * 只支持传统 xref 表，使用交叉引用流的文件放弃计划（streamStats.fetchPlanError），照常逐块读取
+     */
+    fetchPlan?: boolean;
+    /**
+     * 线性化（Web 优化）文件的快速路径（仅 renderFromStream），默认：true
+     * 请求的页面包含首页时，打开文档前并发获取首页段落、提示表和主交叉引用表，缩短首页时间
+     */
+    linearizedFastPath?: boolean;
+    /**
+     * 流式渲染已获取的字节数达到文件大小的该比例（0-1）时，改为一次下载整个文件（仅 renderFromStream），默认：不回退
+     * 之后的读取全部从内存返回，下载失败时照常逐块读取（streamStats.fallbackError）
+     */
+    streamFallbackFraction?: number;
+    /** 流式渲染已发出的请求数达到该值时改为一次下载整个文件（仅 renderFromStream），默认：不回退 */
+    streamFallbackRequests?: number;
+    /**
+     * 流式渲染的缓存块大小（字节，16KB-16MB，仅 renderFromStream），默认：262144（256KB）
+     * 也是每次范围请求的大小；按请求计费或延迟较高的对象存储可以调大以减少请求数
+     */
+    streamBlockSize?: number;
+    /** 流式渲染最多缓存的块数（2-4096，仅 renderFromStream），默认：64；出现 REFETCHED_BLOCKS 建议时可以调大 */
+    streamMaxCacheBlocks?: number;
+    /** 流式渲染等待一个块返回的超时时间（毫秒，仅 renderFromStream），默认：30000 */
+    streamFetchTimeoutMs?: number;
+    /**
+     * 常驻文档（openDocumentFromUrl）空闲超过该时间（毫秒）后清空流式块缓存，默认：不清空
+     * 句柄仍然可用，之后的渲染按需重新获取数据
+     */
+    documentIdleTimeoutMs?: number;
+    /**
+     * 调用方已有的文件数据（如上传时已经读到的前 1 MB），流式渲染前预先写入缓存（仅 renderFromStream）
+     * 只有完整覆盖的 256KB 缓存块会被使用，streamStats.seededBlocks 为写入的块数
+     */
+    seedBlocks?: Array<{ offset: number; data: Buffer }>;
+    /**
+     * 文档头和页面树解析完成后、任何页面渲染之前回调一次（仅 renderFromStream），
+     * 界面可以先按页数画出翻页骨架；文档加载失败时不回调
+     */
+    onDocument?: (info: StreamDocumentInfo) => void;
+    /** 内嵌缩略图为 JPEG/JPEG 2000 时原样返回，不重新编码（仅 getEmbeddedThumbnails），默认：false */
+    thumbnailPassthrough?: boolean;
+    /** 没有内嵌缩略图的页面改为按该宽度（像素）渲染，结果的 rendered 为 true（仅 getEmbeddedThumbnails），默认：不回退 */
+    thumbnailFallbackWidth?: number;
+    /**
+     * 扫描页面只有一张铺满页面的 JPEG/JPEG 2000 图片时原样返回原图（原始分辨率，不重新编码，仅 renderFrom*），默认：false
+     * 页面结果的 passthrough 为 true、format 为 jpg 或 jp2；需要修改像素的选项（canvas、二值化、滤镜等）开启时照常渲染
+     */
+    scanPassthrough?: boolean;
+    /** 按页码指定目标宽度（像素），如 { 1: 2560 }，其余页面仍使用 targetWidth */
+    pageWidths?: Record<number, number>;
+    /** 按页码指定渲染分辨率（DPI，72 为原始尺寸），同一页同时在 pageWidths 中时以 pageWidths 为准 */
+    pageDpi?: Record<number, number>;
+    /**
+     * 按页码覆盖扫描件降级宽度（不受 detectScan 影响）：
+     * 'full' 即使识别为扫描件也使用 targetWidth，'scan' 始终使用 imageHeavyWidth，'auto' 按检测结果
+     */
+    pageScanMode?: Record<number, 'full' | 'scan' | 'auto'>;
+    /**
+     * 按页码覆盖输出格式，如 { 2: 'png' }（其余页面仍使用 format）
+     *
+     * 同一批次中透明或线稿页面输出 PNG、其余页面输出 WebP；结果的 format 为该页实际使用的格式
+     */
+    pageFormats?: Record<number, 'webp' | 'png' | 'jpg' | 'jpeg' | 'avif'>;
+    /** 缩略图拼图的列数（仅 renderThumbnailStrip），默认排成一行，超出尺寸上限后换行 */
+    spriteColumns?: number;
+    /** 跨页渲染时封面是否单独成页（仅 renderSpreads），默认：true */
+    spreadCover?: boolean;
+    /** 文字位置的粒度（仅 getPageTextRects）：'word' 按词，'run' 按 PDFium 的文字段，默认：'word' */
+    textGranularity?: 'word' | 'run';
+    /** 租户标记（仅 createRenderScheduler 的 schedule），调度器按租户执行并发和 CPU 时间限额 */
+    tenant?: string;
+    /** 固定输出宽度（需同时指定 canvasHeight），页面等比缩放到画布内，优先于 targetWidth 等尺寸设置 */
+    canvasWidth?: number;
+    /** 固定输出高度（需同时指定 canvasWidth） */
+    canvasHeight?: number;
+    /** 画布填充色：'#rgb'、'#rrggbb'、'#rrggbbaa' 或 'transparent'（JPEG 中显示为 jpegBackground），默认：白色 */
+    padColor?: string;
+    /** 页面在画布中的对齐方式，如 'center'、'top'、'bottom-right'，默认：'center' */
+    padAlign?: string;
+    /**
+     * 页面尺寸超出 WebP 上限（单边 16383）或 AVIF 上限（单边 16384）时的处理方式，默认：'downscale'
+     * - 'downscale': 等比缩小到上限以内
+     * - 'fallbackPng': 保持原尺寸，该页改用 PNG 输出（结果中的 format 为 'png'）
+     * - 'tile': 保持原尺寸，切成 tileSize 大小的网格分别编码（结果中的 tiles）
+     */
+    oversizeStrategy?: 'downscale' | 'fallbackPng' | 'tile';
+    /** 分块边长（像素），仅 oversizeStrategy 为 'tile' 时生效，默认：4096 */
+    tileSize?: number;
+    /** 判定为重复页的最大哈希距离（0-64，仅 findDuplicatePages），默认：4 */
+    duplicateThreshold?: number;
+    /**
+     * 原生编码使用的编码器后端，可选值见 getEncoderCapabilities()，默认：'auto'
+     * - 'libwebp': WebP 有损编码（WebP 默认）
+     * - 'image': image crate 纯 Rust 编码器（WebP 为无损）
+     * - registerEncoder() 注册的名称：交给自定义编码器（仅异步接口）
+     */
+    encoderBackend?: string;
+    /** 合成到指定页面上的高亮区域（如评论系统中的用户高亮），以正片叠底方式合成 */
+    highlights?: Highlight[];
+    /** 文字水印，编码前合成到每页上（Helvetica 字体，只支持拉丁字符；其他文字请用 stampImage） */
+    stampText?: string;
+    /** 图片水印：紧密排列的 RGBA 像素（非预乘 alpha），需同时指定 stampImageWidth / stampImageHeight，优先于 stampText */
+    stampImage?: Buffer;
+    /** 图片水印的宽度（像素） */
+    stampImageWidth?: number;
+    /** 图片水印的高度（像素） */
+    stampImageHeight?: number;
+    /** 文字水印的字号（像素），默认：48 */
+    stampFontSize?: number;
+    /** 文字水印的颜色（'#rgb'、'#rrggbb' 或 '#rrggbbaa'），默认：'#808080' */
+    stampColor?: string;
+    /** 水印的不透明度（0-1），默认：0.3 */
+    stampOpacity?: number;
+    /** 水印位置：'center'、'top-left'、'bottom-right' 等，'tile' 表示铺满整页，默认：'center' */
+    stampPosition?: string;
+    /** 水印到图像边缘的距离（像素），tile 时为水印之间的间距，默认：24 */
+    stampMargin?: number;
+    /** 不合成水印的区域（如签名、印章所在的位置） */
+    stampExclusions?: StampExclusion[];
+    /** 输出 JPEG/TIFF 时半透明像素混合的背景色（'#rgb' 或 '#rrggbb'），默认：白色 */
+    jpegBackground?: string;
+    /**
+     * 渲染结果超出尺寸上限的容差（像素），默认：2
+     * 超出量在容差以内时裁掉多余边缘，省去一次 Lanczos 缩放；设为 0 总是缩放
+     */
+    resizeTolerance?: number;
+    /**
+     * 页码大于总页数时的处理方式，默认：'skip'
+     * - 'skip': 该页标记为跳过（skipped: true，errorCode: 'OUT_OF_RANGE'），其余页面正常渲染
+     * - 'fail': 整个调用失败
+     */
+    outOfRangePages?: 'skip' | 'fail';
+    /**
+     * 纯文字页面的编码方式（原生 WebP 编码时生效），默认：'off'
+     * 页面只有文字、没有图片且渲染结果近似灰度时：
+     * - 'grayscale': 转为灰度，用 method 6 有损编码
+     * - 'lossless': 转为灰度后无损编码，文字边缘更锐利
+     */
+    smartEncoding?: 'off' | 'grayscale' | 'lossless';
+    /**
+     * 文档加密时依次尝试的候选密码
+     * 先不带密码打开，遇到密码错误再按顺序尝试；结果的 passwordIndex 为成功的下标
+     */
+    passwords?: string[];
+    /**
+     * 文档密码，先于 passwords 尝试
+     * 密码错误或缺少密码时，页面 errorCode 为 'WRONG_PASSWORD'，getPageCount 抛出 code 为 'WRONG_PASSWORD' 的错误
+     */
+    password?: string;
+    /**
+     * 额外输出的格式（如 ['jpg']），结果在 PageResult.alternates 中
+     * 每页只渲染一次，用同一张位图再编码；与主格式相同的条目被忽略，分块输出的页面不生成
+     */
+    alsoEncode?: Array<'webp' | 'png' | 'jpg' | 'jpeg' | 'avif'>;
+    /**
+     * 渲染前把 CMYK 和 ICCBased 图片经 ICC 变换转换到 sRGB，默认 false
+     * ICCBased 图片使用内嵌的配置文件，DeviceCMYK 图片使用 cmykProfile；
+     * 只处理页面顶层、8 位深度、未旋转的图片，矢量图形和文字的颜色不变
+     */
+    forceSrgb?: boolean;
+    /** DeviceCMYK 图片使用的 CMYK ICC 配置文件（如 FOGRA39、SWOP），仅在 forceSrgb 时生效 */
+    cmykProfile?: Buffer;
+    /**
+     * 在结果中返回每页输出图像的亮度统计（PageResult.imageStats），默认 false
+     * 可据此自动标记过暗、对比度过低的扫描件
+     */
+    imageStats?: boolean;
+    /**
+     * 在结果中返回每页输出位图的内容哈希（PageResult.contentHash，XXH64 的 16 位十六进制），默认 false
+     * 与输出格式和编码参数无关，可作为 CDN 缓存的键；配合 deterministic 时哈希相同的页面输出逐字节相同
+     */
+    contentHash?: boolean;
+    /**
+     * 检查页面对象能否正常绘制（renderInfo.objectErrors），默认 false
+     * PDFium 跳过损坏的图片或不支持的对象时不报错，只留下空白；开启后每张图片额外解码一次
+     */
+    objectDiagnostics?: boolean;
+    /**
+     * 只渲染这些类型的注释，其余注释隐藏；未指定时渲染全部注释，空数组不渲染任何注释
+     * 适合审阅模式导出，例如只保留高亮和手绘批注、隐藏表单控件
+     */
+    annotationTypes?: AnnotationType[];
+    /**
+     * 表单控件的渲染方式，默认：'render'
+     * - 'render'：通过 PDFium 表单环境绘制控件和当前填写的值
+     * - 'flatten'：渲染前把表单控件和注释按打印效果合并进页面内容（不打印的注释不再显示）
+     * - 'omit'：不绘制表单控件
+     */
+    formMode?: 'render' | 'flatten' | 'omit';
+    /** 渲染注释，默认 true；存档渲染和比对工具可关闭以获得不含注释的稳定输出 */
+    renderAnnotations?: boolean;
+    /** 渲染表单控件，默认 true；false 等价于 formMode: 'omit' */
+    renderForms?: boolean;
+    /** 关闭文字抗锯齿，默认 false */
+    disableTextAntialiasing?: boolean;
+    /** 关闭图片抗锯齿，默认 false */
+    disableImageAntialiasing?: boolean;
+    /** 针对 LCD 屏幕优化文字渲染，默认 false */
+    optimizeForLcd?: boolean;
+    /**
+     * 以透明背景渲染，默认 false（白色背景）
+     * 页面实际有透明区域时 WebP/PNG 输出保留 alpha 通道（renderInfo.hasAlpha 为 true），
+     * JPEG/TIFF 仍与 jpegBackground 混合
+     */
+    preserveAlpha?: boolean;
+    /**
+     * 渲染会话（startRenderSession）中最先渲染并交付的页码（1-based）
+     * 该页移到最前，其余页面保持原顺序，界面可以尽快显示首屏
+     */
+    heroPage?: number;
+    /**
+     * 在结果中返回每页加载、渲染、编码阶段的起止时间（PageResult.spans），默认 false
+     * 用于在追踪系统中还原跨越原生边界的真实 span
+     */
+    spanTimings?: boolean;
+    /**
+     * 在结果中返回本次调用的 CPU 时间和内存分配（resourceUsage），默认 false
+     * 用于多租户服务按客户文档核算成本；convert 中只统计原生渲染，不含 Sharp 编码
+     */
+    resourceUsage?: boolean;
+    /** renderPageToRawChunks 每个分块的行数，默认按约 4 MB 一块计算 */
+    chunkRows?: number;
+    /**
+     * 多页渲染时并行编码的线程数，默认 1，最大 16
+     * PDFium 渲染仍依次进行，编码在工作线程中并行执行
+     * （convert 中 concurrency 为保存文件/上传 COS 的并发数，页面已由线程池并行渲染）
+     */
+    concurrency?: number;
+    /**
+     * 并行编码时 onPage 的回调顺序，默认：'completion'
+     * - 'completion': 哪页先完成先回调，延迟最低
+     * - 'input': 按请求的页码顺序回调，先完成的页面等待前面的页面
+     */
+    deliveryOrder?: 'completion' | 'input';
+    /** 取消令牌（createCancelToken），cancelRender 后剩余页面以 CANCELLED 返回 */
+    cancelToken?: number;
+    /**
+     * 确定性输出，默认：false
+     *
+     * 相同的 PDF 和选项总是得到逐字节相同的图像：关闭内存超出水位时的降级渲染和 encodeBudgetMs，
+     * onPage 按页码顺序回调。未嵌入的字体仍使用系统字体替代，不同机器的字体不同时输出可能不同
+     */
+    deterministic?: boolean;
+    /** renderMultipageTiff 的颜色模式：bilevel 使用 CCITT G4，gray / color 使用 LZW，默认：'color' */
+    tiffColor?: 'bilevel' | 'gray' | 'color';
+    /** 取消信号（convert、renderFromBuffer、renderFromFile）：触发后剩余页面不再渲染，返回已完成的页面 */
+    signal?: AbortSignal;
+    /**
+     * 每页渲染完成后立即回调（renderFromBuffer、renderFromFile），界面不必等整批完成
+     * 开启 concurrency 时默认按完成顺序回调（见 deliveryOrder）；指定后返回结果中的页面只有统计，不含 buffer
+     */
+    onPage?: (page: {
+        pageNum: number;
+        width: number;
+        height: number;
+        buffer?: Buffer;
+        success: boolean;
+        error?: string;
+        renderTime: number;
+        encodeTime: number;
+        skipped: boolean;
+    }) => void;
+    /**
+     * 两遍渲染（renderFromBuffer、renderFromFile）：每页先按 previewWidth 渲染一张预览图回调，
+     * 再按正式尺寸渲染，最终结果照常返回（或交给 onPage）；命中渲染缓存的页面没有预览
+     */
+    onPreview?: (page: {
+        pageNum: number;
+        width: number;
+        height: number;
+        buffer?: Buffer;
+        success: boolean;
+        renderTime: number;
+        encodeTime: number;
+    }) => void;
+    /** onPreview 预览图的宽度（像素），不小于 targetWidth 时不生成预览，默认：320 */
+    previewWidth?: number;
+    /** 使用 loadProfiles 注册的命名配置；本次调用中设置的选项优先 */
+    profile?: string;
+}
+
+/** 注释类型（annotationTypes 选项），widget 为表单控件 */
+export type AnnotationType =
+    | 'text' | 'link' | 'freeText' | 'line' | 'square' | 'circle' | 'polygon' | 'polyline'
+    | 'highlight' | 'underline' | 'squiggly' | 'strikeOut' | 'stamp' | 'caret' | 'ink' | 'popup'
+    | 'fileAttachment' | 'sound' | 'movie' | 'widget' | 'screen' | 'printerMark' | 'trapNet'
+    | 'watermark' | 'threeD' | 'richMedia' | 'redact';
+
+/** 高亮区域（PDF 坐标，单位点，原点在页面左下角，与注释的 /Rect 相同） */
+export interface Highlight {
+    /** 页码（1-based） */
+    pageNum: number;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    /** '#rgb'、'#rrggbb' 或 '#rrggbbaa'（alpha 控制强度），默认：黄色 */
+    color?: string;
+}
+
+/** 水印排除区域（PDF 坐标，单位点，原点在页面左下角） */
+export interface StampExclusion {
+    /** 页码（1-based），不指定时对每一页生效 */
+    pageNum?: number;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
 }
 
 export interface CosConfig {
@@ -26,9 +413,33 @@ export interface CosConfig {
     region: string;
 }
 
+/** { from, to } 形式的页码范围，to 为空表示到最后一页 */
+export interface PageRange {
+    /** 起始页（1-based），默认：1 */
+    from?: number;
+    /** 结束页（包含） */
+    to?: number;
+}
+
+/**
+ * 页码参数：页码数组（空数组表示全部页面）、范围字符串（如 "1-5,8,10-"、"all"）或 { from, to }
+ *
+ * 范围由原生层按文档页数展开，格式错误或超出总页数时抛出 INVALID_OPTIONS 错误；
+ * 页码数组中超出总页数的页码按 outOfRangePages 处理。
+ */
+export type PageSelection = number[] | string | PageRange;
+
+/**
+ * 按文档页数展开页码参数（与 convert / renderFrom* 的规则相同）
+ *
+ * @param pages - 页码数组、范围字符串或 { from, to }
+ * @param numPages - 文档总页数
+ */
+export function resolvePages(pages: PageSelection, numPages: number): number[];
+
 export interface ConvertOptions extends RenderOptions {
-    /** 要转换的页码（1-based），空数组表示全部页面 */
-    pages?: number[];
+    /** 要转换的页码（1-based），空数组表示全部页面；也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to } */
+    pages?: PageSelection;
     /** 输出类型：'file'、'buffer' 或 'cos' */
     outputType?: 'file' | 'buffer' | 'cos';
     /** 输出目录（outputType 为 'file' 时必需） */
@@ -60,15 +471,241 @@ export interface PageResult {
     size?: number;
     /** 错误信息（失败时） */
     error?: string;
+    /** 是否跳过（未尝试渲染，区别于渲染失败）：超出总时间预算或页码大于总页数 */
+    skipped?: boolean;
+    /** 失败类别（失败时），便于按类别制定重试策略 */
+    errorCode?: PageErrorCode;
+    /** 该页实际输出格式（超出 WebP 尺寸上限且 oversizeStrategy 为 'fallbackPng' 时为 'png'，scanPassthrough 原样返回时为 'jpg' 或 'jp2'） */
+    format?: string;
+    /** 是否原样返回了扫描页面内嵌的图片（scanPassthrough），此时没有渲染和编码 */
+    passthrough?: boolean;
+    /** 是否识别为扫描件（决定是否使用 imageHeavyWidth），未做识别或渲染失败时为空 */
+    detectedScan?: boolean;
+    /** 页面上的文字对象数（扫描件识别的依据之一） */
+    textObjectCount?: number;
+    /** 图片覆盖页面面积的比例（0-1） */
+    imageCoverage?: number;
+    /** 是否因 maxPixelsPerPage / maxTotalBytes 缩小了渲染尺寸 */
+    downscaledDueToMemory?: boolean;
+    /** 分块结果（oversizeStrategy 为 'tile' 且页面超出尺寸上限时，此时没有整页 buffer） */
+    tiles?: PageTiles;
+    /** 实际生效的渲染参数（成功时），用于排查页面模糊等问题 */
+    renderInfo?: RenderInfo;
+    /** 同一页面的附加编码（指定了 alsoEncode 时） */
+    alternates?: AlternateEncoding[];
+    /** 输出图像的亮度统计（指定了 imageStats 且渲染成功时） */
+    imageStats?: ImageStats;
+    /** 编码前最终位图的内容哈希（指定了 contentHash 且渲染成功时，原样返回的页面没有） */
+    contentHash?: string;
+    /** 各阶段的起止时间（指定了 spanTimings 时） */
+    spans?: PageSpans;
+    /** 输出尺寸是否因单边像素上限（WebP 16383，其他格式 32767）被缩小，分辨率低于请求值 */
+    clamped?: boolean;
+    /** 被缩小前请求的输出尺寸（clamped 时，已知的情况下） */
+    requestedSize?: { width: number; height: number };
+}
+
+/**
+ * 单页各阶段的起止时间（Unix 毫秒，含小数部分）
+ *
+ * 由单调时钟换算，同一进程内不会回退；没有到达的阶段为空（如渲染失败时没有编码阶段）
+ */
+export interface PageSpans {
+    /** 加载页面（取页面、扫描件识别、计算尺寸） */
+    loadStart: number;
+    loadEnd: number;
+    /** PDFium 光栅化 */
+    renderStart?: number;
+    renderEnd?: number;
+    /** 滤镜处理和编码 */
+    encodeStart?: number;
+    encodeEnd?: number;
+}
+
+/**
+ * 单次调用的资源占用（options.resourceUsage）
+ *
+ * CPU 时间包含渲染线程和编码线程（非 Linux 平台为 0）；内存只统计 Rust 侧的堆分配，
+ * PDFium 内部的分配不计入，峰值是近似值。内存统计需要以 alloc-usage 特性构建原生模块，
+ * 默认构建不返回这两个字段
+ */
+export interface ResourceUsage {
+    /** 用户态 CPU 时间（毫秒） */
+    cpuUserMs: number;
+    /** 内核态 CPU 时间（毫秒） */
+    cpuSystemMs: number;
+    /** 已分配未释放内存的峰值（字节） */
+    peakAllocBytes?: number;
+    /** 累计分配的字节数 */
+    totalAllocBytes?: number;
+}
+
+/** 输出图像的亮度统计 */
+export interface ImageStats {
+    /** 256 级亮度直方图（每级的像素数） */
+    histogram: number[];
+    /** 平均亮度（0-255），过暗的页面偏低 */
+    mean: number;
+    /** 亮度标准差，对比度低的页面偏小 */
+    stdDev: number;
+    /** 直方图的香农熵（0-8 位），空白或纯色页面接近 0 */
+    entropy: number;
+    /** 1% 分位亮度 */
+    low: number;
+    /** 99% 分位亮度 */
+    high: number;
+}
+
+/** 同一页面的附加编码结果 */
+export interface AlternateEncoding {
+    /** 输出格式 */
+    format: string;
+    /** 图片 Buffer（outputType 为 'buffer' 时，编码失败为 null） */
+    buffer?: Buffer | null;
+    /** 输出文件路径（outputType 为 'file' 时，与主图同名、扩展名不同） */
+    outputPath?: string | null;
+    /** COS key（outputType 为 'cos' 时） */
+    cosKey?: string | null;
+    /** 图片大小（字节） */
+    size?: number;
+    /** 错误信息（该格式编码失败时） */
+    error?: string;
+}
+
+/** 单页实际生效的渲染和编码参数 */
+export interface RenderInfo {
+    /** 实际缩放比例（1.0 = 72 DPI） */
+    scale: number;
+    /** 决定尺寸的选项 */
+    sizeSource: 'targetWidth' | 'dpi' | 'imageHeavyWidth' | 'imageNative' | 'pageWidths' | 'pageDpi' | 'canvas' | 'region' | 'passthrough';
+    /** 是否识别为扫描件 */
+    isScan: boolean;
+    /** 缩放比例是否被 maxScale 限制 */
+    maxScaleApplied: boolean;
+    /** 扫描件降级宽度是否被 maxScale 限制（小尺寸扫描页面，可开启 preferSharpness） */
+    scaleClamped: boolean;
+    /** 是否因输出格式的尺寸上限被缩小 */
+    dimensionClamped: boolean;
+    /** 渲染后是否又做了一次缩放 */
+    resized: boolean;
+    /** 实际使用的编码器（convert 中为 'sharp'） */
+    encoderBackend: string;
+    /** 编码质量（WebP/JPEG 为 quality，PNG 为压缩级别） */
+    quality?: number;
+    /** WebP 编码方法（仅 WebP，webp.methodAuto 时为按页面尺寸调整后的值） */
+    webpMethod?: number;
+    /** 输出图像的有效分辨率（DPI，包含渲染后的缩放） */
+    dpi?: number;
+    /**
+     * PDF 坐标（点）到输出图像像素的仿射变换 [a, b, c, d, e, f]：
+     * px = a·x + c·y + e，py = b·x + d·y + f。
+     * 已包含页面旋转、裁剪框、缩放和画布偏移，可直接定位按点提取的文字框、链接等。
+     */
+    transform?: number[];
+    /** 是否按纯文字页面做了灰度编码（smartEncoding） */
+    textOptimized: boolean;
+    /** 是否因进程内存超出水位而降级渲染（见 setMemoryWatermark） */
+    memoryDegraded: boolean;
+    /** 编码是否超出 encodeBudgetMs、改用了最快的编码参数 */
+    encodeBudgetExceeded?: boolean;
+    /** 经 ICC 变换转换到 sRGB 的图片数（forceSrgb） */
+    colorConvertedImages: number;
+    /** 输出是否保留了透明区域（preserveAlpha） */
+    hasAlpha: boolean;
+    /** 可能没有画出来的页面对象（objectDiagnostics），未开启时为空 */
+    objectErrors?: ObjectError[];
+}
+
+/** 可能绘制失败的页面对象 */
+export interface ObjectError {
+    /** 页面顶层对象的下标（嵌套在表单 XObject 中的对象报告其所在的顶层对象） */
+    objectIndex: number;
+    /** image：图片无法解码；unsupported：PDFium 不支持的对象类型 */
+    objectType: 'image' | 'unsupported';
+    /** 原因（英文） */
+    reason: string;
+    /** 对象在输出图像中的区域（像素） */
+    region?: { x: number; y: number; width: number; height: number };
+}
+
+/**
+ * 单页失败类别
+ * - OUT_OF_RANGE: 页码大于总页数（skipped 为 true，未尝试渲染）
+ * - CANCELLED: 渲染被取消（signal / cancelRender），skipped 为 true
+ * - RENDER_TIMEOUT: 单页渲染超出 pageTimeoutMs，已放弃该页，其余页面照常渲染
+ * - OUTPUT_FAILED: 渲染成功但保存文件或上传 COS 失败（包括 renderToFiles 写入文件失败）
+ */
+export type PageErrorCode =
+    | 'INVALID_PAGE'
+    | 'LOAD_FAILED'
+    | 'RENDER_FAILED'
+    | 'ENCODE_FAILED'
+    | 'LIMIT_EXCEEDED'
+    | 'INVALID_OPTIONS'
+    | 'TIMEOUT'
+    | 'RENDER_TIMEOUT'
+    | 'CANCELLED'
+    | 'SKIPPED'
+    | 'OUT_OF_RANGE'
+    | 'PDFIUM_UNAVAILABLE'
+    | 'OUTPUT_FAILED'
+    | 'WRONG_PASSWORD';
+
+/** 超大页面的分块网格 */
+export interface PageTiles {
+    /** 分块边长（像素），最后一行/列的分块可能更小 */
+    tileSize: number;
+    /** 列数 */
+    columns: number;
+    /** 行数 */
+    rows: number;
+    /** 分块列表（行优先） */
+    tiles: PageTile[];
+}
+
+/** 单个分块 */
+export interface PageTile {
+    /** 行号（从 0 开始） */
+    row: number;
+    /** 列号（从 0 开始） */
+    column: number;
+    /** 分块在整页图像中的横坐标 */
+    x: number;
+    /** 分块在整页图像中的纵坐标 */
+    y: number;
+    /** 分块宽度 */
+    width: number;
+    /** 分块高度 */
+    height: number;
+    /** 分块图片（outputType 为 'buffer' 时） */
+    buffer?: Buffer;
+    /** 输出文件路径（outputType 为 'file' 时） */
+    outputPath?: string;
+    /** COS key（outputType 为 'cos' 时） */
+    cosKey?: string;
+    /** 分块大小（字节） */
+    size?: number;
 }
 
 export interface ConvertResult {
     /** 是否成功 */
     success: boolean;
+    /** 请求关联 ID（传入时回显） */
+    correlationId?: string;
+    /** 合并默认值和命名配置后的完整选项（密码替换为 '***'） */
+    resolvedConfig: Record<string, unknown>;
+    /** 影响输出的选项的哈希，与文档指纹组合即可作为缓存键 */
+    configHash: string;
     /** PDF 总页数 */
     numPages: number;
+    /** 成功打开文档的密码在 passwords 中的下标（文档未加密时为空） */
+    passwordIndex?: number;
     /** 成功渲染的页数 */
     renderedPages: number;
+    /** 跳过的页数（超出总时间预算、页码大于总页数或已取消） */
+    skippedPages: number;
+    /** 是否因 signal 取消而有页面未完成（这些页面的 errorCode 为 CANCELLED） */
+    cancelled: boolean;
     /** 页面结果数组 */
     pages: PageResult[];
     /** 耗时信息 */
@@ -78,24 +715,30 @@ export interface ConvertResult {
         /** 原生渲染器耗时（毫秒） */
         native: number;
     };
+    /** 各页原生渲染的资源占用汇总（options.resourceUsage；峰值取单页峰值的最大值） */
+    resourceUsage?: ResourceUsage;
 }
 
 /**
  * PDF 转图片
  *
- * @param input - PDF 文件路径、URL 或 Buffer
+ * @param input - PDF 文件路径、URL、Buffer，或 Node Readable / Web ReadableStream（先写入临时文件再渲染）
  * @param options - 转换选项
  * @returns 转换结果
  */
-export function convert(input: string | Buffer, options?: ConvertOptions): Promise<ConvertResult>;
+export function convert(
+    input: string | Buffer | NodeJS.ReadableStream | ReadableStream<Uint8Array>,
+    options?: ConvertOptions
+): Promise<ConvertResult>;
 
 /**
  * 获取 PDF 页数
  *
  * @param input - PDF 文件路径或 Buffer
- * @returns 页数
+ * @param options - password: 文档密码；passwords: 文档加密时依次尝试的候选密码
+ * @returns 页数（密码错误时抛出 code 为 'WRONG_PASSWORD' 的错误）
  */
-export function getPageCount(input: string | Buffer): number;
+export function getPageCount(input: string | Buffer, options?: { password?: string; passwords?: string[] }): number;
 
 /**
  * 检查原生渲染器是否可用
@@ -107,11 +750,45 @@ export function isAvailable(): boolean;
  */
 export function getVersion(): string;
 
+/**
+ * 注册命名的渲染配置，替换之前注册的全部配置
+ *
+ * json 是以配置名为键、选项（与 convert 相同的结构）为值的 JSON 对象，之后的调用通过
+ * profile 选项引用。配置不能包含 profile、correlationId、requestId、cancelToken。
+ *
+ * @returns 注册的配置名（按名称排序）
+ */
+export function loadProfiles(json: string): string[];
+
+/**
+ * 优雅关闭：停止接收新任务，等待进行中的任务完成后销毁线程池并关闭原生渲染器
+ *
+ * @param options.drainTimeoutMs - 等待进行中任务的最长时间（毫秒），默认：30000
+ */
+export function shutdown(options?: { drainTimeoutMs?: number }): Promise<{
+    /** 是否所有任务都已完成 */
+    drained: boolean;
+    /** 超时时仍未完成的转换任务数 */
+    pending: number;
+    /** 原生渲染器的关闭结果 */
+    native: {
+        drained: boolean;
+        remaining: number;
+        cancelledStreams: number;
+        /** 因超时被取消的调度任务数 */
+        cancelledJobs: number;
+        /** 因超时被取消的会话页面数 */
+        cancelledSessionPages: number;
+        elapsed: number;
+    };
+}>;
+
 /** 输入类型常量 */
 export const InputType: {
     FILE: 'file';
     URL: 'url';
     BUFFER: 'buffer';
+    STREAM: 'stream';
 };
 
 /** 输出类型常量 */
@@ -139,48 +816,1597 @@ export const TIMEOUT_CONFIG: {
 /** 检查原生渲染器是否可用 */
 export function isNativeAvailable(): boolean;
 
-/** 从 Buffer 渲染 PDF */
-export function renderFromBuffer(
-    pdfBuffer: Buffer,
+/** 跨页（对开页）渲染结果 */
+export interface SpreadResult {
+    /** 包含的页码（从左到右） */
+    pages: number[];
+    width: number;
+    height: number;
+    buffer: Buffer;
+    success: boolean;
+    error?: string;
+    renderTime: number;
+    encodeTime: number;
+}
+
+/**
+ * 渲染跨页：相邻两页按书籍左右页拼成一张图片（整体宽度约为 targetWidth）
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），返回包含这些页面的跨页；空数组表示全部页面
+ */
+export function renderSpreads(
+    input: string | Buffer,
     pages?: number[],
     options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    spreads: SpreadResult[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/** renderToFiles 的单页结果 */
+export interface PageFileResult {
+    pageNum: number;
+    /** 图像文件路径（失败或分块页面时为空） */
+    path?: string;
+    /** 分块文件路径（oversizeStrategy 为 'tile' 且页面超出尺寸上限时，行优先） */
+    tilePaths?: string[];
+    /** 写入的字节数 */
+    size: number;
+    width: number;
+    height: number;
+    /** 实际输出格式，也是文件扩展名 */
+    format: string;
+    success: boolean;
+    error?: string;
+    /** 写入文件失败时为 'OUTPUT_FAILED' */
+    errorCode?: PageErrorCode;
+    skipped: boolean;
+    renderTime: number;
+    encodeTime: number;
+    /** 写入文件耗时（毫秒） */
+    writeTime: number;
+}
+
+/**
+ * 把 PDF 文件的页面直接渲染到输出目录，图像不经过 Node Buffer
+ *
+ * @param filePath - PDF 文件路径
+ * @param pages - 页码数组（1-based），空数组表示全部页面；也可以是范围字符串或 { from, to }
+ * @param outputDir - 输出目录（不存在时自动创建，同名文件会被覆盖）
+ */
+export function renderToFiles(
+    filePath: string,
+    pages: PageSelection,
+    outputDir: string,
+    options?: RenderOptions & {
+        /** 文件名模板，`%d` 替换为页码，`%0Nd` 补零到 N 位，扩展名按输出格式追加，默认：'page-%d' */
+        filenamePattern?: string;
+    }
 ): Promise<{
     success: boolean;
     numPages: number;
-    pages: Array<{
-        pageNum: number;
-        width: number;
-        height: number;
-        buffer?: Buffer;
-        success: boolean;
-        error?: string;
-        renderTime: number;
-        encodeTime: number;
-    }>;
+    cancelled: boolean;
+    pages: PageFileResult[];
     totalTime: number;
     nativeTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
 }>;
 
-/** 从流渲染 PDF（用于远程 URL） */
-export function renderFromStream(
-    pdfUrl: string,
-    pdfSize: number,
+/** 拼图中单页所在的区域（页面图像已在单元格内居中） */
+export interface SpriteCell {
+    pageNum: number;
+    x: number;
+    y: number;
+    /** 页面图像宽度（失败时为 0） */
+    width: number;
+    height: number;
+    success: boolean;
+    error?: string;
+}
+
+/**
+ * 渲染缩略图拼图（sprite sheet），每页在 cellSize × cellSize 的单元格内等比缩放并居中
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ * @param cellSize - 单元格边长（像素），默认：160
+ */
+export function renderThumbnailStrip(
+    input: string | Buffer,
     pages?: number[],
+    cellSize?: number,
     options?: RenderOptions
-): Promise<{
+): {
     success: boolean;
+    error?: string;
     numPages: number;
-    pages: Array<{
-        pageNum: number;
-        width: number;
-        height: number;
-        buffer?: Buffer;
-        success: boolean;
-        error?: string;
-        renderTime: number;
-        encodeTime: number;
-    }>;
+    buffer: Buffer;
+    width: number;
+    height: number;
+    columns: number;
+    rows: number;
+    cellSize: number;
+    cells: SpriteCell[];
     totalTime: number;
-    nativeTime: number;
-    streamStats?: object;
-}>;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/** 书签树中的一个条目 */
+export interface OutlineItem {
+    title: string;
+    /** 目标页码（书签不指向本文档页面时为空） */
+    pageNum?: number;
+    children: OutlineItem[];
+}
+
+/**
+ * 提取书签（目录）树（不渲染），只解析目标页码；需要目标位置坐标时使用 getOutlineAnchors
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param options - 只使用密码和资源限制
+ */
+export function getOutline(input: string | Buffer, options?: RenderOptions): OutlineItem[];
+
+/** 书签条目及其目标位置 */
+export interface OutlineAnchor {
+    title: string;
+    /** 层级（顶层为 0） */
+    level: number;
+    /** 目标页码（书签不指向本文档页面时为空） */
+    pageNum?: number;
+    /** 目标位置在渲染图像中的横坐标（像素） */
+    x?: number;
+    /** 目标位置在渲染图像中的纵坐标（像素，从图像顶部算起） */
+    y?: number;
+    /** 目标缩放比例 */
+    zoom?: number;
+    /** 目标页渲染图像宽度 */
+    width: number;
+    /** 目标页渲染图像高度 */
+    height: number;
+}
+
+/**
+ * 提取书签（目录），附带目标位置在渲染图像中的坐标（与相同选项下的渲染输出一致）
+ *
+ * @param input - PDF 文件路径或 Buffer
+ */
+export function getOutlineAnchors(input: string | Buffer, options?: RenderOptions): OutlineAnchor[];
+
+/** 页面内嵌缩略图 */
+export interface EmbeddedThumbnail {
+    pageNum: number;
+    /** 页面是否带有内嵌缩略图 */
+    found: boolean;
+    width: number;
+    height: number;
+    /** 缩略图数据（没有缩略图时为空） */
+    buffer: Buffer;
+    /** 重新编码时为输出格式，原样返回时为 'jpg' 或 'jp2' */
+    format?: string;
+    /** 是否为 PDF 中的原始数据 */
+    passthrough: boolean;
+    /** 页面没有内嵌缩略图，按 thumbnailFallbackWidth 渲染得到 */
+    rendered: boolean;
+    error?: string;
+}
+
+/**
+ * 提取页面内嵌的缩略图（不渲染），适合列表视图
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ */
+export function getEmbeddedThumbnails(
+    input: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    thumbnails: EmbeddedThumbnail[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/** 单页概况 */
+export interface PageOverview {
+    pageNum: number;
+    /** 页面宽度（点，已考虑页面旋转） */
+    width: number;
+    /** 页面高度（点，已考虑页面旋转） */
+    height: number;
+    /** 页面旋转角度 */
+    rotation: 0 | 90 | 180 | 270;
+    /** 页面标签（如 'iv'、'A-1'，文档未定义时为空） */
+    label?: string;
+    /** 是否有可提取的文字 */
+    hasText: boolean;
+    /** 页面顶层是否有图片对象 */
+    hasImages: boolean;
+    /** 是否有表单控件以外的注释（决定 noAnnotations 是否会改变渲染结果） */
+    hasAnnotations: boolean;
+    /** 是否有表单控件（决定 noFormData 是否会改变渲染结果） */
+    hasFormFields: boolean;
+    /** 是否被识别为扫描件（有图片、没有文字对象） */
+    isScan: boolean;
+    success: boolean;
+    error?: string;
+}
+
+/**
+ * 一次获取所有页面的尺寸、旋转、标签以及是否有文字和图片（不渲染）
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param options - 只使用资源限制（maxFileSize、maxPageObjects）
+ */
+export function getDocumentLayout(
+    input: string | Buffer,
+    options?: RenderOptions
+): {
+    numPages: number;
+    pages: PageOverview[];
+    /** 被识别为扫描件的页数 */
+    scannedPages: number;
+    /** 扫描件页数占成功分析页数的比例（0-1） */
+    scanRatio: number;
+    /** 文档级判定：至少一半页面是扫描件，可据此选择 OCR 或文字提取流程 */
+    isScanned: boolean;
+    totalTime: number;
+};
+
+/** 单页尺寸信息 */
+export interface PageInfo {
+    pageNum: number;
+    /** 页面宽度（点，已考虑页面旋转） */
+    width: number;
+    /** 页面高度（点，已考虑页面旋转） */
+    height: number;
+    /** 页面旋转角度 */
+    rotation: 0 | 90 | 180 | 270;
+    /** 页面标签（如 'iv'、'A-1'，文档未定义时为空） */
+    label?: string;
+    success: boolean;
+    error?: string;
+}
+
+/** 文档元数据和每页尺寸 */
+export interface DocumentInfo {
+    numPages: number;
+    title?: string;
+    author?: string;
+    subject?: string;
+    keywords?: string;
+    /** 创建文档的应用程序 */
+    creator?: string;
+    /** 生成 PDF 的应用程序 */
+    producer?: string;
+    /** 创建日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+    creationDate?: string;
+    /** 修改日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+    modificationDate?: string;
+    /** PDF 版本（如 '1.7'） */
+    pdfVersion?: string;
+    pages: PageInfo[];
+    totalTime: number;
+}
+
+/**
+ * 获取文档元数据和每页的尺寸、旋转、标签（不渲染、不检查页面内容）
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param options - 只使用密码和资源限制
+ */
+export function getDocumentInfo(input: string | Buffer, options?: RenderOptions): DocumentInfo;
+
+/** 文档附件信息 */
+export interface AttachmentInfo {
+    /** 序号（传给 getAttachment） */
+    index: number;
+    name: string;
+    /** 解压后的字节数 */
+    size: number;
+    /** MIME 类型（按扩展名推断，无法识别时为空） */
+    mimeType?: string;
+    /** 创建日期（ISO 8601） */
+    creationDate?: string;
+    modificationDate?: string;
+}
+
+/**
+ * 列出文档中的附件（如 ZUGFeRD / Factur-X 发票中的 XML），不读取附件内容
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param options - 只使用密码和资源限制
+ */
+export function getAttachments(input: string | Buffer, options?: RenderOptions): AttachmentInfo[];
+
+/**
+ * 取出附件内容，序号无效时抛出错误
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param index - 附件序号（getAttachments 返回的 index）
+ * @param options - 只使用密码和资源限制，附件解压后的大小同样受 maxFileSize 限制
+ */
+export function getAttachment(input: string | Buffer, index: number, options?: RenderOptions): Buffer;
+
+/**
+ * 获取单页的尺寸、旋转和标签（不渲染），页码无效时抛出错误
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based）
+ * @param options - 只使用密码和资源限制
+ */
+export function getPageInfo(input: string | Buffer, pageNum: number, options?: RenderOptions): PageInfo;
+
+/** 一个词或文字段及其位置 */
+export interface TextRect {
+    text: string;
+    /** 左上角坐标和尺寸（点，原点在页面左上角，已考虑页面旋转） */
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    /** 在输出图像中的位置和尺寸（像素，与 renderPages 的输出一致，固定画布时包含画布偏移） */
+    pixelX: number;
+    pixelY: number;
+    pixelWidth: number;
+    pixelHeight: number;
+}
+
+/** 单页文字位置 */
+export interface PageTextRects {
+    pageNum: number;
+    /** 输出图像尺寸（像素） */
+    width: number;
+    height: number;
+    /** 缩放比例（像素/点） */
+    scale: number;
+    /** PDF 坐标（点）到输出图像像素的仿射变换 [a, b, c, d, e, f]，与 renderInfo.transform 相同 */
+    transform: number[];
+    granularity: 'word' | 'run';
+    /** 按页面文字流的顺序排列 */
+    rects: TextRect[];
+}
+
+/**
+ * 获取单页文字（词或文字段）及其位置（不渲染），页码无效时抛出错误
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based）
+ * @param options - 用于确定输出图像尺寸，textGranularity 控制粒度
+ */
+export function getPageTextRects(input: string | Buffer, pageNum: number, options?: RenderOptions): PageTextRects;
+
+/** 页面上的一个注释 */
+export interface PageAnnotation {
+    /** 注释类型，与 annotationTypes 的可选值相同（如 'link'、'highlight'、'widget'），其他类型为 'unknown' */
+    kind: string;
+    /** 在输出图像中的位置和尺寸（像素，与 renderPages 的输出一致） */
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    /** 链接的外部地址 */
+    uri?: string;
+    /** 链接指向的本文档页码（1-based） */
+    destPageNum?: number;
+    /** 注释的文字内容 */
+    contents?: string;
+}
+
+/** 单页注释 */
+export interface PageAnnotations {
+    pageNum: number;
+    /** 输出图像尺寸（像素） */
+    width: number;
+    height: number;
+    /** 缩放比例（像素/点） */
+    scale: number;
+    /** PDF 坐标（点）到输出图像像素的仿射变换 [a, b, c, d, e, f] */
+    transform: number[];
+    annotations: PageAnnotation[];
+}
+
+/**
+ * 获取单页的链接和其他注释及其位置（不渲染），页码无效时抛出错误
+ *
+ * 隐藏的注释不返回。
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based）
+ * @param options - 用于确定输出图像尺寸
+ */
+export function getPageAnnotations(input: string | Buffer, pageNum: number, options?: RenderOptions): PageAnnotations;
+
+/** 表单字段（每个控件一项，同一单选按钮组的各个按钮名称相同） */
+export interface FormField {
+    /** 完整字段名（如 'applicant.name'） */
+    name?: string;
+    kind: 'pushButton' | 'checkbox' | 'radioButton' | 'comboBox' | 'listBox' | 'text' | 'signature' | 'unknown';
+    /** 当前值：文本框的文字、组合框和列表框选中的选项、复选框和单选按钮组的值 */
+    value?: string;
+    /** 复选框和单选按钮是否选中 */
+    checked?: boolean;
+    readOnly: boolean;
+    required: boolean;
+    pageNum: number;
+    /** 控件区域（像素，与相同选项下 renderPages 的输出一致） */
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+/**
+ * 获取表单字段的名称、类型、当前值和位置（不渲染），页码无效时抛出错误
+ *
+ * 渲染时表单的处理方式由 formMode 选项控制。
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based），省略时列出所有页的字段
+ * @param options - 用于确定输出图像尺寸
+ */
+export function getFormFields(input: string | Buffer, pageNum?: number | null, options?: RenderOptions): FormField[];
+
+/** 单页文字统计 */
+export interface PageTextStats {
+    pageNum: number;
+    /** 是否有可提取的文字（没有时通常需要 OCR） */
+    hasText: boolean;
+    /** 字符数（不含空白） */
+    charCount: number;
+    /** 词数（中日韩文字按字计） */
+    wordCount: number;
+    success: boolean;
+    error?: string;
+}
+
+/**
+ * 统计页面文字（不渲染、不返回文字内容），用于决定逐页走索引还是 OCR
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ */
+export function getTextStats(input: string | Buffer, pages?: number[]): PageTextStats[];
+
+/** 页面上的图片对象 */
+export interface PageImage {
+    /** 在页面顶层对象中的序号（从 0 开始） */
+    index: number;
+    /** 图片本身的像素尺寸 */
+    pixelWidth: number;
+    pixelHeight: number;
+    /** 外接矩形（PDF 坐标，单位点，原点在页面左下角） */
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    /** 水平 / 垂直方向的放置分辨率（DPI） */
+    dpiX: number;
+    dpiY: number;
+    /** 有效分辨率：两个方向中较低的一个 */
+    effectiveDpi: number;
+    /** 外接矩形占页面面积的比例（0-1） */
+    coverage: number;
+    /** 每像素位数（无法读取时为 0） */
+    bitsPerPixel: number;
+}
+
+/** 单页图片及其分辨率 */
+export interface PageImages {
+    pageNum: number;
+    /** 页面顶层的图片对象（表单 XObject 内部的图片不列出） */
+    images: PageImage[];
+    /** 覆盖面积最大的图片的有效分辨率（扫描页面即扫描分辨率），没有图片时为空 */
+    dominantDpi?: number;
+    /** 所有图片中最低的有效分辨率，没有图片时为空 */
+    minDpi?: number;
+    success: boolean;
+    error?: string;
+}
+
+/**
+ * 列出页面上的图片及其像素尺寸和放置分辨率（不渲染、不解码图片）
+ *
+ * 上传质量检查可据此在渲染前拒绝扫描分辨率过低的文档（如 dominantDpi < 150）。
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ * @param options - 只使用密码和资源限制（maxFileSize、maxPageObjects）
+ */
+export function getPageImages(input: string | Buffer, pages?: number[], options?: RenderOptions): PageImages[];
+
+/** 一组内容相同或相近的页面 */
+export interface DuplicateCluster {
+    /** 页码（升序） */
+    pages: number[];
+    /** 组内两页之间的最大哈希距离（0 表示完全相同） */
+    maxDistance: number;
+}
+
+/** 合成渲染的一个来源文档 */
+export interface ComposeSource {
+    input: string | Buffer;
+    /** 要取用的页码（1-based，按给出的顺序），为空时取全部页面 */
+    pages?: number[];
+}
+
+/**
+ * 把多个 PDF 的页面拼成一个虚拟文档并渲染（如模板封面 + 上传文件的第 1-3 页）
+ *
+ * 结果中的 pageNum 为合成文档中的页码，pageSources[pageNum - 1] 为该页的来源。
+ */
+export function renderComposedPages(
+    sources: ComposeSource[],
+    options?: RenderOptions
+): Promise<{
+    success: boolean;
+    error?: string;
+    numPages: number;
+    pages: PageResult[];
+    pageSources: Array<{ sourceIndex: number; pageNum: number }>;
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+}>;
+
+/** 批量渲染的一个任务 */
+export interface BatchJob {
+    source: string | Buffer;
+    /** 页码数组（1-based）、范围字符串或 { from, to }，为空时渲染全部页面 */
+    pages?: number[] | string | { from?: number; to?: number };
+    options?: RenderOptions;
+}
+
+/** 批量渲染的结果 */
+export interface BatchRenderResult {
+    /** 各任务的渲染结果，与 jobs 顺序一致 */
+    results: Array<{
+        success: boolean;
+        error?: string;
+        numPages: number;
+        pages: PageResult[];
+        /** 该任务的读取和渲染耗时（毫秒） */
+        totalTime: number;
+        correlationId?: string;
+        resolvedConfig?: string;
+        configHash?: string;
+        passwordIndex?: number;
+        cancelled: boolean;
+    }>;
+    /** 成功的任务数 */
+    succeeded: number;
+    /** 失败的任务数（选项无效、读取或加载失败等） */
+    failed: number;
+    /** 成功渲染的页数 */
+    renderedPages: number;
+    /** 读取文件耗时之和（毫秒，与渲染重叠进行） */
+    loadTime: number;
+    /** 各页渲染耗时之和（毫秒） */
+    renderTime: number;
+    /** 各页编码耗时之和（毫秒） */
+    encodeTime: number;
+    /** 总耗时（毫秒） */
+    totalTime: number;
+}
+
+/**
+ * 在一次调用中渲染多个文档（如缩略图服务一次处理几百个小 PDF）
+ *
+ * 所有任务在原生侧处理，PDFium 只初始化一次，读取线程提前读取文件；单个任务失败只体现在该任务的结果中。
+ *
+ * @param options.concurrency - 读取线程数，默认：CPU 核数（最多 4）
+ */
+export function renderBatch(jobs: BatchJob[], options?: { concurrency?: number }): Promise<BatchRenderResult>;
+
+/** 导出为单页 PDF 的页面 */
+export interface ExtractedPage {
+    pageNum: number;
+    /** 只包含该页的 PDF 数据（失败时为空） */
+    buffer: Buffer;
+    success: boolean;
+    error?: string;
+    errorCode?: PageErrorCode;
+}
+
+/**
+ * 把指定页面分别导出为单页 PDF（复制页面对象，不渲染），用于"只发送这一页"
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ * @param options - 只使用文件大小限制和密码
+ */
+export function extractPages(
+    input: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    pages: ExtractedPage[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/**
+ * 把选中的页面导出为一个新的 PDF（复制页面对象，不渲染），页面顺序与 pages 一致
+ * 适合渲染前拆分文档；书签、表单和文档信息不会复制，任一页码无效时整体失败
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based，空数组表示全部页面）、范围字符串（"1-5,8,10-"、"all"）或 { from, to }
+ * @param options - 只使用文件大小限制和密码
+ */
+export function extractPagesAsPdf(
+    input: string | Buffer,
+    pages?: PageSelection,
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    /** 导出的页码，与新 PDF 中的页面顺序一致 */
+    pageNums: number[];
+    /** 只包含选中页面的 PDF（失败时为空） */
+    buffer: Buffer;
+    totalTime: number;
+    correlationId?: string;
+    resolvedConfig?: string;
+    configHash?: string;
+};
+
+/** 合成测试 PDF 的参数 */
+export interface TestPdfOptions {
+    /**
+     * 页面内容：text（纯文字）、image（整页灰度 JPEG，识别为扫描件）、
+     * huge（14400 点见方的超大页面）、mixed（按页循环以上三种），默认 text
+     */
+    kind?: 'text' | 'image' | 'huge' | 'mixed';
+    /** 页数（1-1000，默认 3） */
+    pages?: number;
+    /** 设置后以该密码加密（RC4 40 位），打开时需要传入 password */
+    password?: string;
+}
+
+/**
+ * 生成合成测试 PDF，下游服务的集成测试不需要在仓库中保存二进制样本
+ *
+ * 不依赖 PDFium 渲染，相同参数生成的字节完全相同。
+ */
+export function generateTestPdf(options?: TestPdfOptions): Buffer;
+
+/** 页面上的矩形区域（点，原点在页面左上角，已考虑页面旋转） */
+export interface PageRegion {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    /** 缩放比例（像素/点），省略时使用 maxScale（默认 4） */
+    scale?: number;
+}
+
+/**
+ * 只渲染页面的一个矩形区域（签名框、插图等），不需要高倍率渲染整页再裁剪
+ *
+ * 区域超出页面的部分被裁掉；输出格式和编码选项与 renderPages 相同，尺寸类选项不生效。
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based）
+ * @param region - 页面上的区域和缩放比例
+ */
+export function renderPageRegion(
+    input: string | Buffer,
+    pageNum: number,
+    region: PageRegion,
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    pages: PageResult[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+    passwordIndex?: number;
+    cancelled: boolean;
+};
+
+/** 深度缩放分块请求 */
+export interface ZoomTileRequest {
+    /** 缩放级别：0 时整页缩放到一个分块以内，每级放大一倍 */
+    zoom: number;
+    /** 分块边长（像素，64-4096），默认 256 */
+    tileSize?: number;
+    /** 只渲染指定列、行的分块（从 0 开始，需同时给出） */
+    x?: number;
+    y?: number;
+}
+
+/** 深度缩放级别的分块渲染结果 */
+export interface ZoomTiles {
+    pageNum: number;
+    zoom: number;
+    /** 最高缩放级别（缩放比例首次达到 maxScale 的级别） */
+    maxZoom: number;
+    /** 该级别的缩放比例（像素/点） */
+    scale: number;
+    /** 该级别下整页图像的尺寸（像素） */
+    width: number;
+    height: number;
+    tileSize: number;
+    columns: number;
+    rows: number;
+    format: string;
+    /** 渲染的分块（行优先，指定 x/y 时只有一块） */
+    tiles: PageTile[];
+    renderTime: number;
+    encodeTime: number;
+    success: boolean;
+    error?: string;
+    errorCode?: PageErrorCode;
+}
+
+/**
+ * 渲染深度缩放查看器（OpenSeadragon、Leaflet 等）的页面分块，每个分块单独渲染
+ *
+ * 不指定 x/y 时渲染该级别的全部分块（最多 256 块）。
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pageNum - 页码（1-based）
+ * @param request - 缩放级别、分块大小和分块坐标
+ * @param options - maxScale 决定最高缩放级别，尺寸类选项不生效
+ */
+export function renderPageTiles(
+    input: string | Buffer,
+    pageNum: number,
+    request: ZoomTileRequest,
+    options?: RenderOptions
+): ZoomTiles;
+
+/** 两个文档中同一页码的布局比较 */
+export interface PageComparison {
+    pageNum: number;
+    /** 文档 A 中的页面尺寸（点，已考虑页面旋转，页面不存在时为空） */
+    widthA?: number;
+    heightA?: number;
+    rotationA?: number;
+    widthB?: number;
+    heightB?: number;
+    rotationB?: number;
+    /** 页面尺寸不同（容差 0.5 点） */
+    sizeChanged: boolean;
+    rotationChanged: boolean;
+    missingInA: boolean;
+    missingInB: boolean;
+    /** 是否有任何差异（页面读取失败也算作有差异） */
+    changed: boolean;
+    error?: string;
+}
+
+/**
+ * 比较两个文档的页数和每页的尺寸、旋转（不渲染），用于决定是否需要生成逐页视觉差异
+ *
+ * @param inputA - 文档 A 的文件路径或 Buffer
+ * @param inputB - 文档 B 的文件路径或 Buffer
+ * @param options - 只使用文件大小限制和密码
+ */
+export function compareDocuments(
+    inputA: string | Buffer,
+    inputB: string | Buffer,
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPagesA: number;
+    numPagesB: number;
+    pageCountChanged: boolean;
+    /** 页数相同且每页的尺寸和旋转都一致 */
+    identicalLayout: boolean;
+    changedPages: number[];
+    pages: PageComparison[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/**
+ * 查找内容相同或相近的页面（感知哈希），用于扫描件重复上传去重
+ *
+ * @param input - PDF 文件路径或 Buffer
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ */
+export function findDuplicatePages(
+    input: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions
+): {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    /** 每页的 64 位感知哈希（16 位十六进制） */
+    hashes: Array<{ pageNum: number; hash?: string; error?: string }>;
+    /** 重复页分组（只包含两页以上的组） */
+    clusters: DuplicateCluster[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+};
+
+/**
+ * 预加载文档并常驻原生内存，返回文档 ID（会话结束时调用 releaseDocument）
+ *
+ * 常驻的是文档数据而不是解析后的文档，每次渲染都会重新解析文档。
+ *
+ * @param options - 只用于文件大小限制
+ */
+export function preloadDocument(input: string | Buffer, options?: RenderOptions): number;
+
+/** 渲染预加载文档的页面，同一文档的请求按提交顺序依次处理 */
+export function renderPreloadedPages(
+    documentId: number,
+    pages: number[],
+    options?: RenderOptions
+): Promise<{
+    success: boolean;
+    error?: string;
+    numPages: number;
+    pages: PageResult[];
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+}>;
+
+/** 释放预加载的文档，返回文档是否存在 */
+export function releaseDocument(documentId: number): boolean;
+
+/** 创建取消令牌，放进 options.cancelToken 后可以用 cancelRender 中止渲染；用完后调用 releaseCancelToken */
+export function createCancelToken(): number;
+
+/** 取消使用该令牌的渲染：正在渲染的一页仍会完成，剩余页面以 CANCELLED 返回；返回令牌是否存在 */
+export function cancelRender(token: number): boolean;
+
+/** 释放取消令牌，返回令牌是否存在 */
+export function releaseCancelToken(token: number): boolean;
+
+/** 常驻文档句柄（openDocument / openDocumentFromUrl 返回） */
+export interface DocumentHandle {
+    /** 文档总页数 */
+    pageCount(): number;
+    /** 成功打开文档的密码在 passwords 中的下标（文档未加密时为空） */
+    readonly passwordIndex?: number;
+    /** 句柄是否已关闭 */
+    readonly closed: boolean;
+    /** 单页概况（尺寸、旋转、标签、是否有文字和图片） */
+    getPageInfo(pageNum: number): Promise<PageOverview>;
+    /** 渲染页面，同一文档的请求按提交顺序依次处理 */
+    renderPages(pages: number[], options?: RenderOptions): Promise<{
+        success: boolean;
+        error?: string;
+        numPages: number;
+        pages: PageResult[];
+        totalTime: number;
+        correlationId?: string;
+        /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+        resolvedConfig?: string;
+        /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+        configHash?: string;
+        passwordIndex?: number;
+    }>;
+    /** 释放文档（否则等到垃圾回收），返回句柄此前是否打开 */
+    close(): boolean;
+}
+
+/**
+ * 打开常驻文档，之后的渲染和查询不再尝试候选密码
+ *
+ * 句柄不保留解析后的文档，每次渲染和查询都重新解析；文档线程只在处理请求时占用 PDFium，
+ * 句柄打开期间其他渲染调用照常进行。
+ *
+ * @param options - 只用于文件大小限制和密码
+ */
+export function openDocument(input: string | Buffer, options?: RenderOptions): Promise<DocumentHandle>;
+
+/** 通过 HTTP Range 请求打开远程常驻文档，渲染时按需获取数据 */
+export function openDocumentFromUrl(
+    pdfUrl: string,
+    pdfSize?: number | null,
+    options?: RenderOptions & { seedBlocks?: Array<{ offset: number; data: Buffer }> }
+): Promise<DocumentHandle>;
+
+/** 调度任务的渲染结果 */
+export interface ScheduledPageResult {
+    /** schedule() 返回的任务 ID */
+    jobId: number;
+    /** 同一次 schedule() 调用的页面共享同一个文档 ID */
+    documentId: number;
+    priority: number;
+    /** 租户标记（options.tenant） */
+    tenant?: string;
+    /** 入队到开始渲染的等待时间（毫秒） */
+    queueTime: number;
+    /** 页面结果（关闭时被取消的任务 errorCode 为 'CANCELLED'） */
+    page: PageResult;
+}
+
+/** 跨文档的优先级渲染调度器 */
+export interface RenderScheduler {
+    id: number;
+    /**
+     * 放入一个文档的若干页，返回各页的任务 ID
+     *
+     * @param priority - 数值大的先渲染，相同优先级按入队顺序，默认：0
+     */
+    schedule(input: string | Buffer, pages: number[], priority?: number, options?: RenderOptions): number[];
+    stats(): {
+        queued: number;
+        running: number;
+        completed: number;
+        /** 有排队、运行中任务或预算用量的租户（未标记的任务 tenant 为空） */
+        tenants: Array<{
+            tenant?: string;
+            queued: number;
+            running: number;
+            /** 当前统计窗口内已用的 CPU 时间（毫秒） */
+            cpuTimeMs: number;
+            overBudget: boolean;
+        }>;
+    };
+    /**
+     * 停止接收新任务，返回被取消的排队任务数
+     *
+     * 默认处理完队列；cancelPending 为 true 时排队中的任务立即以 'CANCELLED' 回调。
+     */
+    close(options?: { cancelPending?: boolean }): number;
+}
+
+/** 渲染调度器选项 */
+export interface SchedulerOptions {
+    /** 每个租户同时渲染的最大页数，默认不限制 */
+    tenantConcurrency?: number;
+    /** 每个租户在统计窗口内的 CPU 时间预算（毫秒）；超出后只在其他租户没有可运行任务时调度 */
+    tenantCpuTimeMs?: number;
+    /** CPU 时间的统计窗口（毫秒），默认：60000 */
+    tenantWindowMs?: number;
+    /** 按租户覆盖 tenantConcurrency / tenantCpuTimeMs */
+    tenantQuotas?: Array<{ tenant: string; concurrency?: number; cpuTimeMs?: number }>;
+}
+
+/**
+ * 创建跨文档的优先级渲染调度器，每页完成后回调结果
+ *
+ * 不再使用时必须调用 close()，否则进程不会退出。
+ */
+export function createRenderScheduler(
+    options: SchedulerOptions | undefined,
+    onResult: (err: Error | null, result: ScheduledPageResult) => void
+): RenderScheduler;
+
+/** 批量渲染会话状态 */
+export interface RenderSessionStatus {
+    status: 'running' | 'paused' | 'completed' | 'cancelled' | 'failed';
+    /** 本次会话要渲染的页数（页码为空时在文档加载后确定） */
+    total: number;
+    /** 已完成的页数（包括失败的页面） */
+    completed: number;
+    /** 尚未开始渲染的页数 */
+    pending: number;
+    /** 被取消的页数 */
+    cancelled: number;
+    /** 已完成、尚未被 poll() 取走的页数 */
+    ready: number;
+    /** PDF 总页数（文档加载前为 0） */
+    numPages: number;
+    /** 文档加载失败的原因 */
+    error?: string;
+    /** 渲染已结束且所有结果都已取走，会话随之释放 */
+    done: boolean;
+}
+
+/** 可暂停、可取消的批量渲染会话 */
+export interface RenderSession {
+    id: number;
+    /** 取走已完成的页面（最多 maxPages 个，默认全部） */
+    poll(maxPages?: number): { pages: PageResult[]; status: RenderSessionStatus };
+    status(): RenderSessionStatus;
+    /** 暂停：正在渲染的页面完成后不再开始新页面，暂停期间不占用 PDFium，返回状态是否改变 */
+    pause(): boolean;
+    /** 恢复暂停的会话，返回状态是否改变 */
+    resume(): boolean;
+    /** 取消剩余页面，返回取消的页数；已完成的页面仍可取走 */
+    cancel(): number;
+}
+
+/**
+ * 开始批量渲染会话，后台逐页渲染，状态保存在原生侧
+ *
+ * 传入 onPage 时每页完成后回调，否则用 poll() 取走结果。
+ *
+ * @param pages - 页码数组（1-based），空数组表示全部页面
+ */
+export function startRenderSession(
+    input: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions,
+    onPage?: (err: Error | null, page: PageResult) => void
+): RenderSession;
+
+/** renderPageToRawChunks 交给回调的一段位图 */
+export interface RawBitmapChunk {
+    /** 分块序号（从 0 开始） */
+    index: number;
+    /** 本段第一行在整页中的行号 */
+    y: number;
+    /** 本段行数 */
+    rows: number;
+    /** 整页宽度 */
+    width: number;
+    /** 整页高度 */
+    height: number;
+    /** 每行字节数（width * 4） */
+    stride: number;
+    pixelFormat: 'rgba' | 'bgra';
+    /** 本段像素数据 */
+    buffer: Buffer;
+    /** 是否为最后一段 */
+    last: boolean;
+}
+
+/**
+ * 按条带渲染单页原始位图，分块交给回调（不持有整页像素）
+ *
+ * onChunk 返回的 Promise 完成前渲染线程最多再准备一个分块；onChunk 抛错或 Promise
+ * 失败时渲染中止。条带直接由 PDFium 渲染，canvas、滤镜和高亮叠加不生效。
+ */
+export function renderPageToRawChunks(
+    input: string | Buffer,
+    pageNum: number,
+    options: RenderOptions | undefined,
+    onChunk: (chunk: RawBitmapChunk) => void | Promise<void>
+): Promise<{
+    success: boolean;
+    error?: string;
+    errorCode?: PageErrorCode;
+    width: number;
+    height: number;
+    channels: number;
+    stride: number;
+    pixelFormat: 'rgba' | 'bgra';
+    /** 已交出的分块数 */
+    chunks: number;
+    renderTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+    passwordIndex?: number;
+}>;
+
+/** 原生渲染器诊断信息（探测过的库路径、加载结果、测试渲染结果与耗时） */
+export function getDiagnostics(): {
+    available: boolean;
+    loadedFrom?: string;
+    probes: Array<{ path: string; loaded: boolean; error?: string; loadTime: number }>;
+    pdfiumApiVersion?: string;
+    moduleVersion?: string;
+    platform?: string;
+    cwd?: string;
+    testRenderOk: boolean;
+    testRenderError?: string;
+    testRenderTime?: number;
+    totalTime?: number;
+    /** 渲染调用的绑定状态：库在运行中丢失且自动重试后仍无法加载时为 'unavailable' */
+    bindingState: 'available' | 'unavailable' | 'unknown';
+    /** bindingState 为 'unavailable' 时为 'PDFIUM_UNAVAILABLE' */
+    errorCode?: 'PDFIUM_UNAVAILABLE';
+    /** 连续绑定失败次数 */
+    bindFailures: number;
+    lastBindError?: string;
+    /** 进入不可用状态的时间（Unix 毫秒） */
+    unavailableSince?: number;
+    /** 经自动重试才重新绑定成功的次数 */
+    rebinds: number;
+};
+
+/** 样本文档预热 / 就绪检查的结果 */
+export interface WarmupReport {
+    /** 文档加载成功且所有页面都渲染成功 */
+    ok: boolean;
+    /** 绑定或加载失败的原因 */
+    error?: string;
+    /** 绑定 PDFium 的耗时（毫秒） */
+    bindTime: number;
+    /** 打开样本文档的耗时（毫秒） */
+    loadTime: number;
+    numPages: number;
+    pages: Array<{
+        pageNum: number;
+        success: boolean;
+        error?: string;
+        errorCode?: PageErrorCode;
+        width: number;
+        height: number;
+        /** 编码后的字节数 */
+        size: number;
+        renderTime: number;
+        encodeTime: number;
+    }>;
+    /** 所有页面编码后的总字节数 */
+    outputBytes: number;
+    totalTime: number;
+}
+
+/**
+ * 用部署实际处理的样本文档预热并检查就绪状态（比 warmup 的内置最小 PDF 更接近真实负载）
+ *
+ * 最多检查 8 页（默认第 1 页），未指定 totalTimeBudgetMs 时预算为 30 秒，不经过渲染缓存；
+ * 失败不抛出异常，原因在结果的 error 和各页的 error 中。
+ */
+export function warmupWithSample(
+    sample: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions
+): Promise<WarmupReport>;
+
+/** 多页 TIFF 渲染结果 */
+export interface MultiPageTiffResult {
+    success: boolean;
+    error?: string;
+    numPages: number;
+    /** TIFF 数据（指定了 outputPath 时为空） */
+    buffer?: Buffer;
+    /** 写入的文件路径 */
+    outputPath?: string;
+    /** TIFF 字节数 */
+    size: number;
+    /** 每页结果，顺序即 TIFF 中的页面顺序；失败的页面不写入 TIFF */
+    pages: Array<{
+        pageNum: number;
+        success: boolean;
+        error?: string;
+        errorCode?: PageErrorCode;
+        width: number;
+        height: number;
+        renderTime: number;
+        encodeTime: number;
+    }>;
+    totalTime: number;
+    correlationId?: string;
+    /** 合并默认值、命名配置和旧字段后的完整渲染配置（文字形式，供日志和排查使用，格式不保证稳定） */
+    resolvedConfig?: string;
+    /** 影响输出的渲染配置的哈希，与文档指纹组合即可作为缓存键 */
+    configHash?: string;
+}
+
+/**
+ * 把多个页面渲染进同一个多页 TIFF（传真、归档）
+ *
+ * 压缩方式由 options.tiffColor 决定；没有任何页面渲染成功时 success 为 false。
+ */
+export function renderMultipageTiff(
+    input: string | Buffer,
+    pages?: number[],
+    options?: RenderOptions & {
+        /** 写入的文件路径；不传时在结果的 buffer 中返回 */
+        outputPath?: string;
+    }
+): Promise<MultiPageTiffResult>;
+
+/**
+ * 设置 PDFium 动态库的文件名和搜索顺序（如发行版的 `libpdfium.so.1`），并重新检测可用性
+ *
+ * @returns 原生渲染器是否可用
+ */
+export function configurePdfiumLibrary(options: {
+    /** 依次尝试的库文件名，也可以是完整路径 */
+    names?: string[];
+    /** 在这些目录中查找 names；不传时交给系统加载器搜索 */
+    dirs?: string[];
+    /** 自定义候选都失败后是否继续尝试默认位置，默认：true */
+    includeDefaults?: boolean;
+    /** 库曾经加载成功、之后绑定失败时的自动重试次数，默认：2 */
+    rebindAttempts?: number;
+    /** 重试间隔（毫秒，第 n 次重试等待 n 倍），默认：100 */
+    rebindDelayMs?: number;
+}): boolean;
+
+/**
+ * 立即加载 PDFium 库并常驻进程，之后的渲染调用直接使用这个库
+ *
+ * 传入 libPath 时只使用该路径的库（适合 Electron、pkg 等打包应用）；
+ * 不传时按 configurePdfiumLibrary 的搜索顺序加载。加载或测试渲染失败时抛出
+ * PDFIUM_UNAVAILABLE 错误。
+ */
+export function initPdfium(libPath?: string): {
+    /** 实际加载的库路径 */
+    loadedFrom?: string;
+    /** 绑定耗时（毫秒） */
+    bindTime: number;
+    /** 测试渲染耗时（毫秒） */
+    testRenderTime: number;
+};
+
+/** 设置流式渲染任务的全局并发限制（不传表示不限制），超出的任务排队等待 */
+export function configureStreamLimits(options: {
+    maxActiveStreams?: number;
+    maxQueuedStreams?: number;
+}): void;
+
+/** 获取流式渲染任务的并发状态 */
+export function getStreamLimitStats(): {
+    active: number;
+    queued: number;
+    maxActiveStreams?: number;
+    maxQueuedStreams?: number;
+};
+
+/**
+ * 获取流式请求状态
+ * orphanedRequests 持续增长说明 fetcher 丢弃了请求而没有回调，lateResponses 增长说明 fetcher 响应太慢
+ */
+export function getStreamRequestStats(): {
+    /** 本线程进行中的流式任务数 */
+    activeTasks: number;
+    /** 本线程正在等待 fetcher 响应的请求数 */
+    pendingRequests: number;
+    /** 单个任务最多同时等待的请求数，超出时读取失败 */
+    maxPendingPerTask: number;
+    /** 进程内累计的孤立请求数（等待超时或任务结束时仍未响应） */
+    orphanedRequests: number;
+    /** 进程内累计的迟到响应数（请求已超时或任务已结束后才响应） */
+    lateResponses: number;
+};
+
+/**
+ * 启用或调整进程级的渲染结果缓存（LRU），传 null 关闭并清空
+ * 同一文档（Buffer 按长度和首尾内容识别，文件按路径、大小和修改时间识别）、同一页、同样配置的结果
+ * 直接从缓存返回；流式渲染和 convert() 的工作线程编码路径不经过缓存
+ */
+export function configureRenderCache(options: {
+    /** 最多缓存的页数，默认：256 */
+    maxEntries?: number;
+    /** 缓存数据的总字节数上限，传 0 关闭缓存 */
+    maxBytes: number;
+} | null): void;
+
+/** 渲染结果缓存统计 */
+export interface RenderCacheStats {
+    enabled: boolean;
+    /** 当前缓存的页数 */
+    entries: number;
+    /** 当前缓存数据的总字节数 */
+    bytes: number;
+    maxEntries: number;
+    maxBytes: number;
+    hits: number;
+    misses: number;
+    /** 因容量不足被淘汰的页数 */
+    evictions: number;
+}
+
+/** 获取渲染结果缓存的统计信息 */
+export function getRenderCacheStats(): RenderCacheStats;
+
+/** 清空渲染结果缓存（保留容量设置），返回清除的页数 */
+export function clearRenderCache(): number;
+
+/**
+ * 设置进程级的软内存水位，传 null 取消
+ * 常驻内存超出水位后，后续页面按 widthFactor 缩小渲染宽度（不低于 minWidth）、改用最快的编码参数，
+ * 而不是失败；内存回落后自动恢复。常驻内存读取自 /proc，其他平台不会降级
+ */
+export function setMemoryWatermark(options: {
+    /** 常驻内存（RSS）上限（字节） */
+    rssBytes: number;
+    /** 超出水位后渲染宽度乘以该系数（0.1-1），默认：0.5 */
+    widthFactor?: number;
+    /** 降级后的最小渲染宽度（像素），默认：640 */
+    minWidth?: number;
+} | null): void;
+
+/** 获取当前常驻内存和水位状态 */
+export function getMemoryStatus(): {
+    /** 当前进程的常驻内存（字节），当前平台读取不到时为空 */
+    residentBytes?: number;
+    /** 当前水位（字节），未设置时为空 */
+    watermarkBytes?: number;
+    /** 是否超出水位 */
+    overWatermark: boolean;
+    /** 进程启动以来降级渲染的页数 */
+    degradedPages: number;
+    /** 空闲回收的 TTL（毫秒），未启用时为空 */
+    idleTtlMs?: number;
+    /** 进行中的任务数（所有模块实例） */
+    activeTasks?: number;
+    /** 进程内没有进行中任务的时长（毫秒） */
+    idleMs?: number;
+    /** 空闲回收和 trimMemory 清除的渲染缓存页数 / 字节数 */
+    reapedCacheEntries?: number;
+    reapedCacheBytes?: number;
+    /** 把空闲堆内存归还给操作系统的次数 */
+    allocatorTrims?: number;
+};
+
+/**
+ * 设置空闲回收（传 null 关闭），适合在流量高峰之间长时间空闲的常驻 worker
+ *
+ * 渲染缓存中超过 idleTtlMs 未使用的页面被清除；进程空闲超过 idleTtlMs 后把空闲堆内存
+ * 归还给操作系统（glibc）。预加载的文档不会被回收
+ */
+export function configureIdleReaper(options: {
+    /** 空闲时间（毫秒） */
+    idleTtlMs: number;
+} | null): void;
+
+/** 立即回收空闲资源：清空渲染缓存、把空闲堆内存归还给操作系统 */
+export function trimMemory(): {
+    /** 清除的渲染缓存页数 */
+    cacheEntries: number;
+    /** 清除的渲染缓存字节数 */
+    cacheBytes: number;
+    /** 是否把空闲堆内存归还给了操作系统（仅 glibc） */
+    allocatorTrimmed: boolean;
+    /** 回收前后的常驻内存（字节） */
+    residentBefore?: number;
+    residentAfter?: number;
+};
+
+/** 已编译的编码器后端 */
+export interface EncoderCapability {
+    /** 输出格式（自定义编码器为注册时声明的格式，未声明时为 'custom'） */
+    format: string;
+    /** 后端名称（encoderBackend 选项的值） */
+    backend: string;
+    /** 是否为该格式的默认后端 */
+    isDefault: boolean;
+    description: string;
+}
+
+/** 查询已编译的编码器后端（包括 registerEncoder 注册的编码器） */
+export function getEncoderCapabilities(): EncoderCapability[];
+
+/** 交给自定义编码器的位图 */
+export interface EncodeRequest {
+    /** 编码器名称 */
+    encoder: string;
+    /** format 选项对应的输出格式 */
+    format: 'webp' | 'png' | 'jpg' | 'tiff' | 'avif';
+    /** 质量参数：jpg 为 jpegQuality，avif 为 avif.quality，其他格式为 webpQuality */
+    quality: number;
+    /** 编码速度（仅 avif，0 最慢但压缩最好，10 最快） */
+    speed?: number;
+    width: number;
+    height: number;
+    /** 每行字节数（width * 4） */
+    stride: number;
+    pixelFormat: 'rgba' | 'bgra';
+    buffer: Buffer;
+}
+
+/**
+ * 注册自定义编码器，之后可通过 encoderBackend: name 选用
+ *
+ * 返回值（或 Promise 的结果）作为该页的编码结果，抛错时该页编码失败。
+ * 只能用于异步渲染接口。
+ */
+export function registerEncoder(
+    name: string,
+    encode: (request: EncodeRequest) => Buffer | Uint8Array | Promise<Buffer | Uint8Array>,
+    options?: {
+        /** 输出格式名（如 'avif'），写入结果的 format */
+        format?: string;
+    }
+): void;
+
+/** 注销自定义编码器，返回是否存在 */
+export function unregisterEncoder(name: string): boolean;
+
+/** 最近一次渲染尝试的记录 */
+export interface RenderAttempt {
+    seq: number;
+    /** 开始时间（Unix 毫秒） */
+    startedAt: number;
+    /** 文档指纹 */
+    source: string;
+    pageNum: number;
+    width: number;
+    height: number;
+    /** 已到达的阶段 */
+    stage: 'loading' | 'rendering' | 'encoding' | 'done' | 'failed';
+    error?: string;
+    elapsed: number;
+}
+
+/** 获取最近的渲染记录（最多 32 条，从旧到新） */
+export function getRecentRenders(): RenderAttempt[];
+
+/** 把最近的渲染记录以 JSON 写入文件 */
+export function dumpRecentRenders(path: string): void;
+
+/** 设置崩溃转储路径，原生模块 panic 时自动写入最近的渲染记录；传 null 取消 */
+export function setCrashDumpPath(path: string | null): void;
+
+/** 从 Buffer 渲染 PDF */
+export function renderFromBuffer(
+    pdfBuffer: Buffer,
+    pages?: PageSelection,
+    options?: RenderOptions
+): Promise<{
+    success: boolean;
+    numPages: number;
+    /** 是否被 options.signal 取消（未完成页面的 skipped 为 true） */
+    cancelled?: boolean;
+    pages: Array<{
+        pageNum: number;
+        width: number;
+        height: number;
+        buffer?: Buffer;
+        success: boolean;
+        error?: string;
+        renderTime: number;
+        encodeTime: number;
+        /** 实际输出格式（scanPassthrough 原样返回时为 jpg 或 jp2） */
+        format: string;
+        /** 是否取自渲染结果缓存（见 configureRenderCache） */
+        cached?: boolean;
+        /** 是否原样返回了扫描页面内嵌的图片（options.scanPassthrough） */
+        passthrough?: boolean;
+        /** 是否识别为扫描件（scanMinImageCoverage / scanMaxTextObjects 为阈值） */
+        detectedScan?: boolean;
+        /** 页面上的文字对象数 */
+        textObjectCount?: number;
+        /** 图片覆盖页面面积的比例（0-1） */
+        imageCoverage?: number;
+        /** 是否因 maxPixelsPerPage / maxTotalBytes 缩小了渲染尺寸 */
+        downscaledDueToMemory?: boolean;
+        /** 输出图像的亮度统计（options.imageStats） */
+        imageStats?: ImageStats;
+        /** 输出位图的内容哈希（options.contentHash） */
+        contentHash?: string;
+        /** 各阶段的起止时间（options.spanTimings） */
+        spans?: PageSpans;
+        /** 输出尺寸是否因单边像素上限被缩小 */
+        clamped?: boolean;
+        /** 被缩小前请求的输出尺寸（clamped 时） */
+        requestedSize?: { width: number; height: number };
+    }>;
+    totalTime: number;
+    nativeTime: number;
+    /** 本次调用的 CPU 时间和内存分配（options.resourceUsage） */
+    resourceUsage?: ResourceUsage;
+}>;
+
+/** 流式渲染中文档加载完成时的信息（onDocument） */
+export interface StreamDocumentInfo {
+    numPages: number;
+    title?: string;
+    author?: string;
+    subject?: string;
+    keywords?: string;
+    creator?: string;
+    producer?: string;
+    /** 创建日期（ISO 8601，无法识别时为 PDF 中的原始值） */
+    creationDate?: string;
+    modificationDate?: string;
+    pdfVersion?: string;
+    /** 文件总大小（字节，长度未知时为探测到的长度） */
+    fileSize: number;
+    /** 调用开始到文档加载完成的耗时（毫秒，含排队时间） */
+    loadTime: number;
+}
+
+/** 从流渲染 PDF（用于远程 URL），pdfSize 未知时传 null（边上传边渲染） */
+export function renderFromStream(
+    pdfUrl: string,
+    pdfSize: number | null,
+    pages?: PageSelection,
+    options?: RenderOptions
+): Promise<{
+    success: boolean;
+    numPages: number;
+    pages: Array<{
+        pageNum: number;
+        width: number;
+        height: number;
+        buffer?: Buffer;
+        success: boolean;
+        error?: string;
+        renderTime: number;
+        encodeTime: number;
+    }>;
+    totalTime: number;
+    nativeTime: number;
+    streamStats?: StreamStats;
+}>;
+
+/** 流式加载统计 */
+export interface StreamStats {
+    totalRequests: number;
+    cacheHits: number;
+    cacheMisses: number;
+    totalBytesFetched: number;
+    /** 因并发限制排队等待的时间（毫秒） */
+    queueTime: number;
+    /** 由预取（fetchLanes 并行预取、streamReadahead 合并读取）提供、随后被读取的块数 */
+    prefetchHits: number;
+    /** 预取请求的块数；prefetchHits 与它的比值即预取的有效程度 */
+    prefetchedBlocks: number;
+    /** 由 seedBlocks 预先写入缓存的块数 */
+    seededBlocks: number;
+    /** 按取数计划（fetchPlan）批量获取的块数 */
+    plannedBlocks: number;
+    /** 取数计划失败的原因 */
+    fetchPlanError?: string;
+    /** 改为下载整个文件的原因（streamFallbackFraction / streamFallbackRequests） */
+    fallbackReason?: string;
+    /** 下载整个文件失败的原因 */
+    fallbackError?: string;
+    /** 被多个页面读取过的块，pages 中 0 表示打开文档阶段；fetches 大于 1 说明块被淘汰后重新获取 */
+    reusedBlocks: Array<{ offset: number; size: number; pages: number[]; fetches: number; seeded: boolean }>;
+    /** 缓存命中率（缓存命中与预取命中占全部读取的比例，目标 0.8） */
+    hitRatio: number;
+    /** 文件是否线性化，未读到文件开头时为空 */
+    linearized?: boolean;
+    /** 是否使用了线性化文件的快速路径（linearizedFastPath） */
+    linearizedFastPath: boolean;
+    /** 根据本次读取模式给出的调优建议 */
+    hints: StreamHint[];
+    /** 等待超时或任务结束时仍未得到响应的请求数 */
+    orphanedRequests: number;
+}
+
+/**
+ * 流式读取的调优建议
+ * - FULL_DOWNLOAD: 读取量接近文件大小，整体下载更合适
+ * - NOT_LINEARIZED: 文档未线性化，页面数据分散在整个文件中
+ * - ENABLE_FETCH_LANES: 大部分块按顺序读取，建议设置 fetchLanes 或 streamReadahead
+ * - WASTED_PREFETCH: 预取的块大多没有被读取，建议调小 fetchLanes 或 streamReadahead
+ * - REFETCHED_BLOCKS: 块被缓存淘汰后重新获取
+ * - LOW_HIT_RATIO: 缓存命中率低于目标
+ */
+export interface StreamHint {
+    code: 'FULL_DOWNLOAD' | 'NOT_LINEARIZED' | 'ENABLE_FETCH_LANES' | 'WASTED_PREFETCH' | 'REFETCHED_BLOCKS' | 'LOW_HIT_RATIO';
+    message: string;
+}
@@ -42,11 +42,12 @@ export {
     getVersion,
     getThreadPoolStats,
     destroyThreadPool,
+    shutdown,
     InputType,
     OutputType,
 } from './core/converter.js';
 
-export { RENDER_CONFIG, TIMEOUT_CONFIG } from './core/config.js';
+export { RENDER_CONFIG, TIMEOUT_CONFIG, loadProfiles } from './core/config.js';
 
 // 导出原生渲染器工具供高级用法
 export {
@@ -55,4 +56,62 @@ export {
     getPageCountFromFile,
     renderPageToRawBitmap,
     renderPageToRawBitmapFromBuffer,
+    renderPageToRawChunks,
+    getEmbeddedThumbnails,
+    findDuplicatePages,
+    extractPages,
+    extractPagesAsPdf,
+    resolvePages,
+    generateTestPdf,
+    compareDocuments,
+    renderPageRegion,
+    renderPageTiles,
+    renderComposedPages,
+    renderBatch,
+    getTextStats,
+    getPageImages,
+    getDocumentLayout,
+    getDocumentInfo,
+    getPageInfo,
+    getAttachments,
+    getAttachment,
+    getPageTextRects,
+    getPageAnnotations,
+    getFormFields,
+    createRenderScheduler,
+    startRenderSession,
+    preloadDocument,
+    renderPreloadedPages,
+    releaseDocument,
+    createCancelToken,
+    cancelRender,
+    releaseCancelToken,
+    openDocument,
+    openDocumentFromUrl,
+    renderThumbnailStrip,
+    renderSpreads,
+    renderToFiles,
+    getOutline,
+    getOutlineAnchors,
+    getDiagnostics,
+    warmupWithSample,
+    renderMultipageTiff,
+    configurePdfiumLibrary,
+    initPdfium,
+    configureStreamLimits,
+    getStreamLimitStats,
+    getStreamRequestStats,
+    setMemoryWatermark,
+    configureRenderCache,
+    getRenderCacheStats,
+    clearRenderCache,
+    getMemoryStatus,
+    configureIdleReaper,
+    trimMemory,
+    getEncoderCapabilities,
+    registerEncoder,
+    unregisterEncoder,
+    getRecentRenders,
+    dumpRecentRenders,
+    setCrashDumpPath,
 } from './renderers/native.js';
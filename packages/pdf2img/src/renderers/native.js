@@ -7,7 +7,7 @@
  */
 
 import { createLogger } from '../utils/logger.js';
-import { mergeConfig, TIMEOUT_CONFIG } from '../core/config.js';
+import { mergeConfig, passwordCandidates, TIMEOUT_CONFIG } from '../core/config.js';
 
 const logger = createLogger('NativeRenderer');
 
@@ -45,67 +45,1257 @@ export function isNativeAvailable() {
     return nativeAvailable;
 }
 
+/**
+ * 把原生错误信息中的错误码前缀（如 `WRONG_PASSWORD: ...`）设置到 err.code
+ */
+function withErrorCode(fn) {
+    try {
+        return fn();
+    } catch (err) {
+        const match = /^([A-Z_]+): /.exec(err?.message || '');
+        if (match && !err.code) {
+            err.code = match[1];
+        }
+        throw err;
+    }
+}
+
 /**
  * 获取 PDF 页数（从 Buffer）
  * @param {Buffer} pdfBuffer - PDF 文件数据
- * @returns {number} 页数
+ * @param {string|string[]} [passwords] - 文档密码，或依次尝试的候选密码
+ * @returns {number} 页数（密码错误时抛出 code 为 WRONG_PASSWORD 的错误）
+ */
+export function getPageCount(pdfBuffer, passwords) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return withErrorCode(() => nativeRenderer.getPageCount(pdfBuffer, passwords));
+}
+
+/**
+ * 获取 PDF 页数（从文件路径）
+ * 
+ * 直接从文件读取，避免在 Node.js 堆中创建大 Buffer
+ * 
+ * @param {string} filePath - PDF 文件路径
+ * @param {string|string[]} [passwords] - 文档密码，或依次尝试的候选密码
+ * @returns {number} 页数（密码错误时抛出 code 为 WRONG_PASSWORD 的错误）
+ */
+export function getPageCountFromFile(filePath, passwords) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return withErrorCode(() => nativeRenderer.getPageCountFromFile(filePath, passwords));
+}
+
+/**
+ * 渲染单页到原始位图（不编码）
+ * 
+ * 只进行 PDFium 渲染，跳过图像编码步骤，返回原始 RGBA 像素数据。
+ * 编码工作可以交给 Sharp 等更高效的库处理。
+ * 
+ * @param {string} filePath - PDF 文件路径
+ * @param {number} pageNum - 页码（从 1 开始）
+ * @param {Object} options - 渲染选项
+ * @returns {Object} { success, buffer, width, height, channels, stride, renderTime, error }
+ */
+export function renderPageToRawBitmap(filePath, pageNum, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    return nativeRenderer.renderPageToRawBitmap(filePath, pageNum, config);
+}
+
+/**
+ * 从 Buffer 渲染单页到原始位图（不编码）
+ * 
+ * @param {Buffer} pdfBuffer - PDF 文件数据
+ * @param {number} pageNum - 页码（从 1 开始）
+ * @param {Object} options - 渲染选项
+ * @returns {Object} { success, buffer, width, height, channels, stride, renderTime, error }
+ */
+export function renderPageToRawBitmapFromBuffer(pdfBuffer, pageNum, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    const buffer = Buffer.isBuffer(pdfBuffer) ? pdfBuffer : Buffer.from(pdfBuffer);
+    return nativeRenderer.renderPageToRawBitmapFromBuffer(buffer, pageNum, config);
+}
+
+/**
+ * 按条带渲染单页原始位图，分块交给回调（不编码，不持有整页像素）
+ *
+ * 适合把超大位图直接写入流式编码器或上传流。onChunk 可以返回 Promise，
+ * 在它完成之前渲染线程最多再准备一个分块；onChunk 抛错或 Promise 失败时渲染中止，
+ * 返回结果的 success 为 false。条带直接由 PDFium 渲染，canvas、滤镜和高亮叠加不生效。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（从 1 开始）
+ * @param {Object} options - 渲染选项
+ * @param {number} [options.chunkRows] - 每个分块的行数（默认约 4 MB 一块）
+ * @param {Function} onChunk - (chunk) => void | Promise<void>，chunk 为 { index, y, rows, width, height, stride, pixelFormat, buffer, last }
+ * @returns {Promise<Object>} { success, error, errorCode, width, height, channels, stride, pixelFormat, chunks, renderTime }
+ */
+export function renderPageToRawChunks(input, pageNum, options = {}, onChunk) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+
+    // 原生侧等待 completeRawChunk 确认后才继续渲染，这里保证每个分块都会被确认
+    const deliver = (err, chunk) => {
+        if (err) {
+            logger.error(`Raw chunk delivery failed: ${err.message}`, { correlationId: config.correlationId });
+            return;
+        }
+        Promise.resolve()
+            .then(() => onChunk(chunk))
+            .then(
+                () => nativeRenderer.completeRawChunk(chunk.taskId, null),
+                (e) => nativeRenderer.completeRawChunk(chunk.taskId, e?.message || String(e))
+            );
+    };
+
+    return nativeRenderer.renderPageToRawChunks(source, pageNum, config, deliver);
+}
+
+/**
+ * 渲染跨页（对开页）
+ *
+ * 相邻两页按书籍左右页拼成一张图片（整体宽度约为 targetWidth），
+ * spreadCover 为 true（默认）时封面单独成页。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），返回包含这些页面的跨页；空数组表示全部页面
+ * @param {Object} options - 渲染选项
+ * @returns {Object} { success, error, numPages, spreads: [{ pages, width, height, buffer, success, error }], totalTime }
+ */
+export function renderSpreads(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.renderSpreadsFromFile(input, pages, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.renderSpreads(buffer, pages, config);
+}
+
+/**
+ * 渲染缩略图拼图（sprite sheet）
+ *
+ * 每页在 cellSize × cellSize 的单元格内等比缩放并居中，拼成一张图片，
+ * 适合查看器的进度条预览。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {number} cellSize - 单元格边长（像素）
+ * @param {Object} options - 渲染选项（format、spriteColumns 等）
+ * @returns {Object} { success, error, numPages, buffer, width, height, columns, rows, cellSize, cells, totalTime }
+ */
+export function renderThumbnailStrip(input, pages = [], cellSize = 160, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.renderThumbnailStripFromFile(input, pages, cellSize, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.renderThumbnailStrip(buffer, pages, cellSize, config);
+}
+
+/**
+ * 提取书签（目录）树（不渲染），只解析目标页码
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只使用密码和资源限制）
+ * @returns {Array<Object>} [{ title, pageNum, children: [...] }]
+ */
+export function getOutline(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getOutlineFromFile(input, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getOutline(buffer, config);
+}
+
+/**
+ * 提取书签（目录），附带目标位置在渲染图像中的坐标
+ *
+ * 坐标与相同选项下渲染输出的图像一致，点击目录条目时可直接滚动到对应位置。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（用于确定输出图像尺寸）
+ * @returns {Array<Object>} [{ title, level, pageNum, x, y, zoom, width, height }]
+ */
+export function getOutlineAnchors(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getOutlineAnchorsFromFile(input, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getOutlineAnchors(buffer, config);
+}
+
+/**
+ * 提取页面内嵌的缩略图（不渲染）
+ *
+ * 没有内嵌缩略图的页面返回 found: false，调用方可回退到正常渲染；
+ * 设置 thumbnailFallbackWidth 时这些页面直接按该宽度渲染（rendered 为 true）。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {Object} options - 渲染选项（format、thumbnailPassthrough、thumbnailFallbackWidth 等）
+ * @returns {Object} { success, error, numPages, thumbnails, totalTime }
+ */
+export function getEmbeddedThumbnails(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getEmbeddedThumbnailsFromFile(input, pages, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getEmbeddedThumbnails(buffer, pages, config);
+}
+
+/**
+ * 一次获取所有页面的尺寸、旋转、标签以及是否有文字和图片（不渲染）
+ *
+ * 查看器打开文档时调用一次即可布局所有页面，再按需渲染；
+ * 结果中的 scanRatio / isScanned 汇总了扫描件检测，可用于选择 OCR 或文字提取流程。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只使用资源限制）
+ * @returns {Object} { numPages, pages: [{ pageNum, width, height, rotation, label, hasText, hasImages, isScan }], scannedPages, scanRatio, isScanned, totalTime }
+ */
+export function getDocumentLayout(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getDocumentLayoutFromFile(input, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getDocumentLayout(buffer, config);
+}
+
+/**
+ * 获取文档元数据和每页的尺寸、旋转、标签（不渲染、不检查页面内容）
+ *
+ * 查看器在渲染任何页面之前据此计算布局，比 getDocumentLayout 更快。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只使用密码和资源限制）
+ * @returns {Object} { numPages, title, author, subject, keywords, creator, producer, creationDate, modificationDate, pdfVersion, pages: [{ pageNum, width, height, rotation, label }], totalTime }
+ */
+export function getDocumentInfo(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getDocumentInfoFromFile(input, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getDocumentInfo(buffer, config);
+}
+
+/**
+ * 列出文档中的附件（如 ZUGFeRD / Factur-X 发票中的 XML），不读取附件内容
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只使用密码和资源限制）
+ * @returns {Array<Object>} [{ index, name, size, mimeType, creationDate, modificationDate }]
+ */
+export function getAttachments(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getAttachmentsFromFile(input, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getAttachments(buffer, config);
+}
+
+/**
+ * 取出附件内容，序号无效时抛出错误
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} index - 附件序号（getAttachments 返回的 index）
+ * @param {Object} options - 渲染选项（只使用密码和资源限制，附件解压后的大小同样受 maxFileSize 限制）
+ * @returns {Buffer} 附件内容
+ */
+export function getAttachment(input, index, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getAttachmentFromFile(input, index, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getAttachment(buffer, index, config);
+}
+
+/**
+ * 获取单页的尺寸、旋转和标签（不渲染），页码无效时抛出错误
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（1-based）
+ * @param {Object} options - 渲染选项（只使用密码和资源限制）
+ * @returns {Object} { pageNum, width, height, rotation, label, success }
+ */
+export function getPageInfo(input, pageNum, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getPageInfoFromFile(input, pageNum, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getPageInfo(buffer, pageNum, config);
+}
+
+/**
+ * 获取单页文字（词或文字段）及其位置（不渲染），页码无效时抛出错误
+ *
+ * 同时返回页面坐标（点）和输出图像坐标（像素），像素坐标与相同选项下 renderPages 的输出一致。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（1-based）
+ * @param {Object} options - 渲染选项（用于确定输出图像尺寸，textGranularity 控制粒度）
+ * @returns {Object} { pageNum, width, height, scale, transform, granularity, rects: [{ text, x, y, width, height, pixelX, pixelY, pixelWidth, pixelHeight }] }
+ */
+export function getPageTextRects(input, pageNum, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getPageTextRectsFromFile(input, pageNum, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getPageTextRects(buffer, pageNum, config);
+}
+
+/**
+ * 获取单页的链接和其他注释及其位置（不渲染），页码无效时抛出错误
+ *
+ * 区域坐标与相同选项下 renderPages 的输出一致，可直接在渲染图上放置可点击区域。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（1-based）
+ * @param {Object} options - 渲染选项（用于确定输出图像尺寸）
+ * @returns {Object} { pageNum, width, height, scale, transform, annotations: [{ kind, x, y, width, height, uri, destPageNum, contents }] }
+ */
+export function getPageAnnotations(input, pageNum, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getPageAnnotationsFromFile(input, pageNum, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getPageAnnotations(buffer, pageNum, config);
+}
+
+/**
+ * 获取表单字段的名称、类型、当前值和位置（不渲染），页码无效时抛出错误
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number|null} pageNum - 页码（1-based），为空时列出所有页的字段
+ * @param {Object} options - 渲染选项（用于确定输出图像尺寸）
+ * @returns {Array<Object>} [{ name, kind, value, checked, readOnly, required, pageNum, x, y, width, height }]
+ */
+export function getFormFields(input, pageNum = null, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getFormFieldsFromFile(input, pageNum, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getFormFields(buffer, pageNum, config);
+}
+
+/**
+ * 统计页面文字（不渲染、不返回文字内容），用于决定逐页走索引还是 OCR
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @returns {Array<Object>} [{ pageNum, hasText, charCount, wordCount, success, error }]
+ */
+export function getTextStats(input, pages = []) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    if (typeof input === 'string') {
+        return nativeRenderer.getTextStatsFromFile(input, pages);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getTextStats(buffer, pages);
+}
+
+/**
+ * 列出页面上的图片及其像素尺寸和放置分辨率（不渲染、不解码图片）
+ *
+ * 上传质量检查可据此在渲染前拒绝扫描分辨率过低的文档（如 dominantDpi < 150）。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {Object} options - 渲染选项（只使用密码和资源限制）
+ * @returns {Array<Object>} [{ pageNum, images: [{ pixelWidth, pixelHeight, dpiX, dpiY, effectiveDpi, coverage, ... }], dominantDpi, minDpi, success, error }]
+ */
+export function getPageImages(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.getPageImagesFromFile(input, pages, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.getPageImages(buffer, pages, config);
+}
+
+/**
+ * 把多个 PDF 的页面拼成一个虚拟文档并渲染（如模板封面 + 上传文件的第 1-3 页）
+ *
+ * @param {Array<{input: string|Buffer, pages?: number[]}>} sources - 来源文档及取用的页码（为空表示全部页面）
+ * @param {Object} options - 渲染选项
+ * @returns {Promise<Object>} { success, error, numPages, pages, pageSources: [{ sourceIndex, pageNum }], totalTime }
+ */
+export function renderComposedPages(sources, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const normalized = sources.map(({ input, pages }) => ({
+        input: typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input),
+        pages,
+    }));
+    return nativeRenderer.renderComposedPages(normalized, mergeConfig(options));
+}
+
+/**
+ * 在一次调用中渲染多个文档（如缩略图服务一次处理几百个小 PDF）
+ *
+ * 所有任务在原生侧处理，PDFium 只初始化一次；结果与 jobs 顺序一致，单个任务失败只体现在该任务的结果中。
+ *
+ * @param {Array<{source: string|Buffer, pages?: number[]|string|Object, options?: Object}>} jobs - 各任务的来源、页码（为空表示全部页面）和渲染选项
+ * @param {Object} options - { concurrency }：读取线程数
+ * @returns {Promise<Object>} { results, succeeded, failed, renderedPages, loadTime, renderTime, encodeTime, totalTime }
+ */
+export function renderBatch(jobs, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const normalized = jobs.map(({ source, pages, options: jobOptions }) => ({
+        source: typeof source === 'string' || Buffer.isBuffer(source) ? source : Buffer.from(source),
+        pageNums: Array.isArray(pages) && pages.length === 0 ? 'all' : pages ?? 'all',
+        options: mergeConfig(jobOptions || {}),
+    }));
+    return nativeRenderer.renderBatch(normalized, options);
+}
+
+/**
+ * 确定目标页码
+ *
+ * 页码数组为空表示全部页面，大于总页数的页码丢弃；范围字符串（"1-5,8,10-"、"all"）和
+ * { from, to } 由原生层展开，格式错误或超出总页数时抛出错误。
+ *
+ * @param {number[]|string|{from?: number, to?: number}} pages - 页码数组或范围
+ * @param {number} numPages - 文档总页数
+ * @returns {number[]} 页码数组
+ */
+export function resolvePages(pages, numPages) {
+    if (Array.isArray(pages)) {
+        return pages.length === 0
+            ? Array.from({ length: numPages }, (_, i) => i + 1)
+            : pages.filter(p => p >= 1 && p <= numPages);
+    }
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return nativeRenderer.resolvePageRanges(pages, numPages);
+}
+
+/**
+ * 把指定页面分别导出为单页 PDF（复制页面对象，不渲染）
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {Object} options - 渲染选项（只使用文件大小限制和密码）
+ * @returns {Object} { success, error, numPages, pages: [{ pageNum, buffer, success, error }], totalTime }
+ */
+export function extractPages(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.extractPages(source, pages, mergeConfig(options));
+}
+
+/**
+ * 把选中的页面导出为一个新的 PDF（复制页面对象，不渲染），用于渲染前拆分文档
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]|string|Object} pages - 页码数组（1-based），空数组表示全部页面；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
+ * @param {Object} options - 渲染选项（只使用文件大小限制和密码）
+ * @returns {Object} { success, error, numPages, pageNums, buffer, totalTime }
+ */
+export function extractPagesAsPdf(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.extractPagesAsPdf(source, pages, mergeConfig(options));
+}
+
+/**
+ * 只渲染页面的一个矩形区域（签名框、插图等），不需要高倍率渲染整页再裁剪
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（1-based）
+ * @param {Object} region - { x, y, width, height, scale }，坐标为点，原点在页面左上角；scale 为像素/点
+ * @param {Object} options - 渲染选项（尺寸类选项不生效）
+ * @returns {Object} { success, error, numPages, pages: [page], totalTime }
+ */
+export function renderPageRegion(input, pageNum, region, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.renderPageRegion(source, pageNum, region, mergeConfig(options));
+}
+
+/**
+ * 渲染深度缩放查看器（OpenSeadragon、Leaflet 等）的页面分块
+ *
+ * 级别 0 时整页缩放到一个分块以内，之后每级放大一倍；每个分块单独渲染，不需要渲染整页。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number} pageNum - 页码（1-based）
+ * @param {Object} request - { zoom, tileSize = 256, x, y }，给出 x/y 时只渲染这一块
+ * @param {Object} options - 渲染选项（maxScale 决定最高缩放级别）
+ * @returns {Object} { success, error, zoom, maxZoom, scale, width, height, tileSize, columns, rows, format, tiles }
+ */
+export function renderPageTiles(input, pageNum, request, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.renderPageTiles(source, pageNum, request, mergeConfig(options));
+}
+
+/**
+ * 比较两个文档的页数和每页的尺寸、旋转（不渲染）
+ *
+ * 按页码对齐页面，用于在生成逐页视觉差异之前判断两个版本的版式是否变化。
+ *
+ * @param {string|Buffer} inputA - 文档 A 的文件路径或 Buffer
+ * @param {string|Buffer} inputB - 文档 B 的文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只使用文件大小限制和密码）
+ * @returns {Object} { success, error, numPagesA, numPagesB, pageCountChanged, identicalLayout, changedPages, pages, totalTime }
+ */
+export function compareDocuments(inputA, inputB, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const toSource = (input) => (typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input));
+    return nativeRenderer.compareDocuments(toSource(inputA), toSource(inputB), mergeConfig(options));
+}
+
+/**
+ * 查找内容相同或相近的页面（感知哈希）
+ *
+ * 每页渲染成小图计算 64 位感知哈希，距离不超过 duplicateThreshold 的页面归为一组。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {Object} options - 渲染选项（duplicateThreshold，默认 4）
+ * @returns {Object} { success, error, numPages, hashes, clusters, totalTime }
+ */
+export function findDuplicatePages(input, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    if (typeof input === 'string') {
+        return nativeRenderer.findDuplicatePagesFromFile(input, pages, config);
+    }
+    const buffer = Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.findDuplicatePages(buffer, pages, config);
+}
+
+/**
+ * 预加载文档并常驻原生内存，返回文档 ID
+ *
+ * 常驻的是文档数据而不是解析后的文档：之后用 renderPreloadedPages 渲染任意页面和
+ * 缩放级别时，每次都重新解析文档，请求之间不占用 PDFium。
+ * 会话结束时调用 releaseDocument 释放。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只用于文件大小限制）
+ * @returns {number} 文档 ID
+ */
+export function preloadDocument(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    return nativeRenderer.preloadDocument(source, config);
+}
+
+/**
+ * 渲染预加载文档的页面
+ *
+ * @param {number} documentId - preloadDocument 返回的文档 ID
+ * @param {number[]} pages - 页码数组（1-based）
+ * @param {Object} options - 渲染选项
+ * @returns {Promise<Object>} { success, error, numPages, pages, totalTime }
+ */
+export function renderPreloadedPages(documentId, pages, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return nativeRenderer.renderPreloadedPages(documentId, pages, mergeConfig(options));
+}
+
+/**
+ * 释放预加载的文档（进行中的渲染完成后销毁）
+ *
+ * @param {number} documentId - 文档 ID
+ * @returns {boolean} 文档是否存在
+ */
+export function releaseDocument(documentId) {
+    if (!nativeAvailable) {
+        return false;
+    }
+    return nativeRenderer.releaseDocument(documentId);
+}
+
+/**
+ * 创建取消令牌，放进 options.cancelToken 后可以用 cancelRender 中止渲染
+ *
+ * @returns {number} 令牌 ID（用完后调用 releaseCancelToken 释放）
+ */
+export function createCancelToken() {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return nativeRenderer.createCancelToken();
+}
+
+/**
+ * 取消使用该令牌的渲染：正在渲染的一页仍会完成，剩余页面以 CANCELLED 错误码返回
+ *
+ * @param {number} token - 令牌 ID
+ * @returns {boolean} 令牌是否存在
+ */
+export function cancelRender(token) {
+    return nativeAvailable && nativeRenderer.cancelRender(token);
+}
+
+/**
+ * 释放取消令牌
+ *
+ * @param {number} token - 令牌 ID
+ * @returns {boolean} 令牌是否存在
+ */
+export function releaseCancelToken(token) {
+    return nativeAvailable && nativeRenderer.releaseCancelToken(token);
+}
+
+/**
+ * 把 AbortSignal 接到取消令牌上：signal 触发时取消渲染，run 结束后释放令牌
+ *
+ * 令牌在进程内共享，工作线程中的渲染也能被主线程取消。
+ *
+ * @param {AbortSignal} [signal] - 取消信号，未指定时直接执行 run
+ * @param {function(number=): Promise<*>} run - 以令牌 ID 执行渲染
+ * @returns {Promise<*>} run 的结果
+ */
+export async function withAbortSignal(signal, run) {
+    if (!signal || !nativeAvailable) {
+        return run(undefined);
+    }
+    const token = nativeRenderer.createCancelToken();
+    const onAbort = () => nativeRenderer.cancelRender(token);
+    if (signal.aborted) {
+        onAbort();
+    } else {
+        signal.addEventListener('abort', onAbort, { once: true });
+    }
+    try {
+        return await run(token);
+    } finally {
+        signal.removeEventListener('abort', onAbort);
+        nativeRenderer.releaseCancelToken(token);
+    }
+}
+
+/**
+ * 常驻文档句柄：打开时确定页数和密码，之后的渲染和查询不再尝试候选密码
+ *
+ * 句柄不保留解析后的文档，每次渲染和查询都重新解析。
+ *
+ * 不再使用时调用 close() 立即释放原生内存（否则等到垃圾回收）。
+ */
+class DocumentHandle {
+    constructor(handle) {
+        this.handle = handle;
+    }
+
+    /** 文档总页数 */
+    pageCount() {
+        return this.handle.pageCount();
+    }
+
+    /** 成功打开文档的密码在 passwords 中的下标（文档未加密时为空） */
+    get passwordIndex() {
+        return this.handle.passwordIndex ?? undefined;
+    }
+
+    /** 句柄是否已关闭 */
+    get closed() {
+        return this.handle.closed;
+    }
+
+    /**
+     * 单页概况（尺寸、旋转、标签、是否有文字和图片）
+     * @param {number} pageNum - 页码（1-based）
+     * @returns {Promise<Object>}
+     */
+    getPageInfo(pageNum) {
+        return this.handle.getPageInfo(pageNum);
+    }
+
+    /**
+     * 渲染页面，同一文档的请求按提交顺序依次处理
+     * @param {number[]} pages - 页码数组（1-based）
+     * @param {Object} options - 渲染选项
+     * @returns {Promise<Object>} { success, error, numPages, pages, totalTime }
+     */
+    renderPages(pages, options = {}) {
+        return this.handle.renderPages(pages, mergeConfig(options));
+    }
+
+    /** 释放文档，返回句柄此前是否打开 */
+    close() {
+        return this.handle.close();
+    }
+}
+
+/**
+ * 打开常驻文档
+ *
+ * 文档线程只在处理请求时占用 PDFium，句柄打开期间其他渲染调用照常进行。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {Object} options - 渲染选项（只用于文件大小限制和密码）
+ * @returns {Promise<DocumentHandle>}
+ */
+export async function openDocument(input, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    const handle = typeof input === 'string'
+        ? await nativeRenderer.PdfDocumentHandle.openFromFile(input, config)
+        : await nativeRenderer.PdfDocumentHandle.openFromBuffer(Buffer.isBuffer(input) ? input : Buffer.from(input), config);
+    return new DocumentHandle(handle);
+}
+
+/**
+ * 通过 HTTP Range 请求打开远程常驻文档，渲染时按需获取数据
+ *
+ * @param {string} pdfUrl - PDF 文件 URL
+ * @param {number} [pdfSize] - PDF 文件大小，未知时传 0 或 null
+ * @param {Object} options - 渲染选项（只用于文件大小限制和密码）
+ * @param {Array<{offset: number, data: Buffer}>} [options.seedBlocks] - 调用方已有的文件数据
+ * @returns {Promise<DocumentHandle>}
+ */
+export async function openDocumentFromUrl(pdfUrl, pdfSize, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    if (!pdfUrl) {
+        throw new Error('pdfUrl is required for stream mode');
+    }
+    const config = mergeConfig(options);
+    const handle = await nativeRenderer.PdfDocumentHandle.openFromStream(
+        pdfSize || 0,
+        config,
+        createRangeFetcher(pdfUrl),
+        options.seedBlocks
+    );
+    return new DocumentHandle(handle);
+}
+
+/**
+ * 创建跨文档的优先级渲染调度器
+ *
+ * 多个文档的页面放入同一个队列，工作线程按优先级（数值大的先渲染）取任务，
+ * 每页完成后调用 onResult(err, { jobId, documentId, priority, tenant, queueTime, page })。
+ * 不再使用时必须调用 close()，否则调度器持有的回调会阻止进程退出。
+ * schedule 的 options.tenant 标记租户，tenantConcurrency / tenantCpuTimeMs 限制单个租户的占用。
+ *
+ * @param {Object} options - { tenantConcurrency, tenantCpuTimeMs, tenantWindowMs, tenantQuotas }
+ * @param {Function} onResult - 每页完成后的回调
+ * @returns {Object} { id, schedule(input, pages, priority, options), stats(), close({ cancelPending }) }
+ */
+export function createRenderScheduler(options = {}, onResult) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const id = nativeRenderer.createRenderScheduler(options, onResult);
+    return {
+        id,
+        schedule(input, pages, priority = 0, renderOptions = {}) {
+            const config = mergeConfig(renderOptions);
+            const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+            return nativeRenderer.scheduleRender(id, source, pages, priority, config);
+        },
+        stats() {
+            return nativeRenderer.getRenderSchedulerStats(id);
+        },
+        close(closeOptions = {}) {
+            return nativeRenderer.closeRenderScheduler(id, closeOptions);
+        },
+    };
+}
+
+/**
+ * 开始可暂停、可取消的批量渲染会话
+ *
+ * 后台线程按顺序逐页渲染，会话状态保存在原生侧。传入 onPage 时每页完成后回调
+ * onPage(err, page)；否则结果暂存，用 poll() 取走。poll() 返回的 status.done 为 true
+ * 时会话已结束并释放。options.heroPage 指定的页面最先渲染并交付。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} pages - 页码数组（1-based），空数组表示全部页面
+ * @param {Object} options - 渲染选项
+ * @param {Function} [onPage] - 每页完成后的回调
+ * @returns {Object} { id, poll(maxPages), status(), pause(), resume(), cancel() }
+ */
+export function startRenderSession(input, pages = [], options = {}, onPage) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    const source = typeof input === 'string' || Buffer.isBuffer(input) ? input : Buffer.from(input);
+    const id = nativeRenderer.startRenderSession(source, pages, config, onPage);
+    return {
+        id,
+        poll(maxPages) {
+            return nativeRenderer.pollRenderSession(id, maxPages);
+        },
+        status() {
+            return nativeRenderer.getRenderSessionStatus(id);
+        },
+        pause() {
+            return nativeRenderer.pauseRenderSession(id);
+        },
+        resume() {
+            return nativeRenderer.resumeRenderSession(id);
+        },
+        cancel() {
+            return nativeRenderer.cancelRenderSession(id);
+        },
+    };
+}
+
+/**
+ * 获取原生渲染器诊断信息
+ *
+ * 原生模块本身加载失败时，返回包含加载错误的结果而不是抛出异常。
+ *
+ * @returns {Object} { available, loadedFrom, probes, pdfiumApiVersion, testRenderOk, testRenderError, ... }
+ */
+export function getDiagnostics() {
+    if (typeof nativeRenderer.getDiagnostics !== 'function') {
+        return {
+            available: false,
+            probes: [],
+            testRenderOk: false,
+            testRenderError: 'Native module not loaded',
+        };
+    }
+    return nativeRenderer.getDiagnostics();
+}
+
+/**
+ * 用部署实际处理的样本文档预热并检查就绪状态
+ *
+ * 走一遍绑定、加载、渲染、编码流程，报告各阶段耗时和输出大小；最多检查 8 页，
+ * 未指定 totalTimeBudgetMs 时预算为 30 秒。失败不抛出异常，原因在结果的 error 中。
+ *
+ * @param {string|Buffer} sample - 样本 PDF 的文件路径或 Buffer
+ * @param {number[]} [pages] - 要检查的页码（默认 [1]）
+ * @param {Object} [options] - 渲染选项（与正式渲染相同，检查结果才有代表性）
+ * @returns {Promise<Object>} { ok, error, bindTime, loadTime, numPages, pages, outputBytes, totalTime }
+ */
+export async function warmupWithSample(sample, pages = [1], options = {}) {
+    if (typeof nativeRenderer.warmupWithSample !== 'function') {
+        return {
+            ok: false,
+            error: 'Native module not loaded',
+            bindTime: 0,
+            loadTime: 0,
+            numPages: 0,
+            pages: [],
+            outputBytes: 0,
+            totalTime: 0,
+        };
+    }
+    const config = mergeConfig(options);
+    return nativeRenderer.warmupWithSample(sample, pages, config);
+}
+
+/**
+ * 把多个页面渲染进同一个多页 TIFF（传真、归档）
+ *
+ * options.tiffColor 选择 bilevel（CCITT G4）、gray 或 color（LZW）。
+ *
+ * @param {string|Buffer} input - PDF 文件路径或 Buffer
+ * @param {number[]} [pages] - 页码数组（从 1 开始），空数组表示全部页面
+ * @param {Object} [options] - 渲染选项；outputPath 指定时写入文件而不返回 buffer
+ * @returns {Promise<Object>} { success, error, numPages, buffer, outputPath, size, pages, totalTime }
+ */
+export async function renderMultipageTiff(input, pages = [], options = {}) {
+    if (typeof nativeRenderer.renderMultipageTiff !== 'function') {
+        throw new Error('Native renderer not available');
+    }
+    const config = mergeConfig(options);
+    return nativeRenderer.renderMultipageTiff(input, pages, config, options.outputPath);
+}
+
+/**
+ * 设置 PDFium 动态库的文件名和搜索顺序，并重新检测可用性
+ *
+ * 用于发行版自带 `libpdfium.so.1` 或改过名的库。模块加载时的检测只查找默认库名，
+ * 调用后会用新的搜索顺序重新检测并预热。
+ *
+ * @param {Object} options - { names, dirs, includeDefaults, rebindAttempts, rebindDelayMs }
+ * @returns {boolean} 原生渲染器是否可用
+ */
+export function configurePdfiumLibrary(options = {}) {
+    if (typeof nativeRenderer.configurePdfiumLibrary !== 'function') {
+        throw new Error('Native module not loaded');
+    }
+    nativeRenderer.configurePdfiumLibrary(options);
+
+    const wasAvailable = nativeAvailable;
+    nativeAvailable = nativeRenderer.isPdfiumAvailable();
+    if (nativeAvailable && !wasAvailable) {
+        try {
+            const warmupTime = nativeRenderer.warmup();
+            logger.info(`Native renderer loaded with custom PDFium library, warmup: ${warmupTime}ms`);
+        } catch (warmupErr) {
+            logger.warn(`Native renderer warmup failed: ${warmupErr.message}`);
+        }
+    }
+    return nativeAvailable;
+}
+
+/**
+ * 立即加载 PDFium 库并常驻进程
+ *
+ * Electron、pkg 等把库打包在自定义位置的应用可以直接传入库的完整路径，
+ * 不再依赖当前目录和系统库搜索。之后的渲染调用直接使用这个库。
+ *
+ * @param {string} [libPath] - 库的完整路径；不传时按 configurePdfiumLibrary 的搜索顺序加载
+ * @returns {Object} { loadedFrom, bindTime, testRenderTime }
+ */
+export function initPdfium(libPath) {
+    if (typeof nativeRenderer.initPdfium !== 'function') {
+        throw new Error('Native module not loaded');
+    }
+    try {
+        const info = nativeRenderer.initPdfium(libPath);
+        nativeAvailable = true;
+        logger.info(`PDFium library loaded from ${info.loadedFrom}, bind: ${info.bindTime}ms`);
+        return info;
+    } catch (e) {
+        nativeAvailable = false;
+        throw e;
+    }
+}
+
+/**
+ * 设置流式渲染任务的全局并发限制
+ *
+ * @param {Object} options - { maxActiveStreams, maxQueuedStreams }，不传表示不限制
  */
-export function getPageCount(pdfBuffer) {
+export function configureStreamLimits(options = {}) {
     if (!nativeAvailable) {
         throw new Error('Native renderer not available');
     }
-    return nativeRenderer.getPageCount(pdfBuffer);
+    nativeRenderer.configureStreamLimits(options);
 }
 
 /**
- * 获取 PDF 页数（从文件路径）
- * 
- * 直接从文件读取，避免在 Node.js 堆中创建大 Buffer
- * 
- * @param {string} filePath - PDF 文件路径
- * @returns {number} 页数
+ * 获取流式渲染任务的并发状态
+ *
+ * @returns {Object} { active, queued, maxActiveStreams, maxQueuedStreams }
+ */
+export function getStreamLimitStats() {
+    if (!nativeAvailable) {
+        return { active: 0, queued: 0 };
+    }
+    return nativeRenderer.getStreamLimitStats();
+}
+
+/**
+ * 获取流式请求状态，用于发现 fetcher 丢失或迟到的请求
+ *
+ * @returns {Object} { activeTasks, pendingRequests, maxPendingPerTask, orphanedRequests, lateResponses }
+ */
+export function getStreamRequestStats() {
+    if (!nativeAvailable) {
+        return { activeTasks: 0, pendingRequests: 0, maxPendingPerTask: 0, orphanedRequests: 0, lateResponses: 0 };
+    }
+    return nativeRenderer.getStreamRequestStats();
+}
+
+/**
+ * 启用或调整进程级的渲染结果缓存（LRU），传 null 关闭并清空
+ *
+ * 同一文档、同一页、同样配置的渲染结果直接从缓存返回（结果的 cached 为 true），
+ * 适合查看器在页面之间来回跳转。
+ *
+ * @param {Object|null} options - { maxEntries, maxBytes }
  */
-export function getPageCountFromFile(filePath) {
+export function configureRenderCache(options) {
     if (!nativeAvailable) {
         throw new Error('Native renderer not available');
     }
-    return nativeRenderer.getPageCountFromFile(filePath);
+    nativeRenderer.configureRenderCache(options ?? null);
 }
 
 /**
- * 渲染单页到原始位图（不编码）
- * 
- * 只进行 PDFium 渲染，跳过图像编码步骤，返回原始 RGBA 像素数据。
- * 编码工作可以交给 Sharp 等更高效的库处理。
- * 
- * @param {string} filePath - PDF 文件路径
- * @param {number} pageNum - 页码（从 1 开始）
- * @param {Object} options - 渲染选项
- * @returns {Object} { success, buffer, width, height, channels, renderTime, error }
+ * 获取渲染结果缓存的统计信息
+ *
+ * @returns {Object} { enabled, entries, bytes, maxEntries, maxBytes, hits, misses, evictions }
  */
-export function renderPageToRawBitmap(filePath, pageNum, options = {}) {
+export function getRenderCacheStats() {
+    if (!nativeAvailable) {
+        return { enabled: false, entries: 0, bytes: 0, maxEntries: 0, maxBytes: 0, hits: 0, misses: 0, evictions: 0 };
+    }
+    return nativeRenderer.getRenderCacheStats();
+}
+
+/**
+ * 清空渲染结果缓存（保留容量设置）
+ *
+ * @returns {number} 清除的页数
+ */
+export function clearRenderCache() {
+    if (!nativeAvailable) {
+        return 0;
+    }
+    return nativeRenderer.clearRenderCache();
+}
+
+/**
+ * 设置进程级的软内存水位，传 null 取消
+ *
+ * 常驻内存超出水位后，后续页面自动缩小渲染宽度、改用最快的编码参数，
+ * 而不是失败；降级的页面 renderInfo.memoryDegraded 为 true。
+ *
+ * @param {Object|null} options - { rssBytes, widthFactor, minWidth }
+ */
+export function setMemoryWatermark(options) {
     if (!nativeAvailable) {
         throw new Error('Native renderer not available');
     }
-    const config = mergeConfig(options);
-    return nativeRenderer.renderPageToRawBitmap(filePath, pageNum, config);
+    nativeRenderer.setMemoryWatermark(options ?? null);
 }
 
 /**
- * 从 Buffer 渲染单页到原始位图（不编码）
- * 
- * @param {Buffer} pdfBuffer - PDF 文件数据
- * @param {number} pageNum - 页码（从 1 开始）
- * @param {Object} options - 渲染选项
- * @returns {Object} { success, buffer, width, height, channels, renderTime, error }
+ * 获取当前常驻内存和水位状态
+ *
+ * @returns {Object} { residentBytes, watermarkBytes, overWatermark, degradedPages, idleTtlMs, activeTasks, idleMs,
+ *   reapedCacheEntries, reapedCacheBytes, allocatorTrims }
  */
-export function renderPageToRawBitmapFromBuffer(pdfBuffer, pageNum, options = {}) {
+export function getMemoryStatus() {
+    if (!nativeAvailable) {
+        return { overWatermark: false, degradedPages: 0 };
+    }
+    return nativeRenderer.getMemoryStatus();
+}
+
+/**
+ * 设置空闲回收，传 null 关闭
+ *
+ * 渲染缓存中超过 idleTtlMs 未使用的页面被清除；进程空闲超过 idleTtlMs 后把空闲堆内存
+ * 归还给操作系统。设置是进程级的。
+ *
+ * @param {Object|null} options - { idleTtlMs }
+ */
+export function configureIdleReaper(options) {
     if (!nativeAvailable) {
         throw new Error('Native renderer not available');
     }
-    const config = mergeConfig(options);
-    const buffer = Buffer.isBuffer(pdfBuffer) ? pdfBuffer : Buffer.from(pdfBuffer);
-    return nativeRenderer.renderPageToRawBitmapFromBuffer(buffer, pageNum, config);
+    nativeRenderer.configureIdleReaper(options ?? null);
+}
+
+/**
+ * 立即回收空闲资源：清空渲染缓存、把空闲堆内存归还给操作系统
+ *
+ * @returns {Object} { cacheEntries, cacheBytes, allocatorTrimmed, residentBefore, residentAfter }
+ */
+export function trimMemory() {
+    if (!nativeAvailable) {
+        return { cacheEntries: 0, cacheBytes: 0, allocatorTrimmed: false };
+    }
+    return nativeRenderer.trimMemory();
+}
+
+/**
+ * 查询已编译的编码器后端（encoderBackend 选项的可选值）
+ *
+ * @returns {Array<Object>} [{ format, backend, isDefault, description }]
+ */
+export function getEncoderCapabilities() {
+    if (!nativeAvailable) {
+        return [];
+    }
+    return nativeRenderer.getEncoderCapabilities();
+}
+
+/**
+ * 注册自定义编码器，之后可通过 encoderBackend: name 选用
+ *
+ * 每页渲染完成后，原始位图交给 encode(request)，返回值（Buffer 或 Promise<Buffer>）
+ * 作为该页的编码结果；抛错或 Promise 失败时该页编码失败。渲染、缩放、批量调度仍在原生侧完成。
+ * 编码器是进程级的，worker 线程中的渲染也可以使用主线程注册的编码器。
+ *
+ * @param {string} name - 编码器名称
+ * @param {Function} encode - (request) => Buffer | Promise<Buffer>，request 为 { encoder, format, quality, width, height, stride, pixelFormat, buffer }
+ * @param {Object} [options]
+ * @param {string} [options.format] - 输出格式名（如 'avif'），写入结果的 format；不传时沿用 format 选项
+ */
+export function registerEncoder(name, encode, options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+
+    // 原生渲染线程等待 completeEncodeRequest，这里保证每个请求都会被交回
+    const handler = (err, request) => {
+        if (err) {
+            logger.error(`Custom encoder ${name} received error: ${err.message}`);
+            return;
+        }
+        Promise.resolve()
+            .then(() => encode(request))
+            .then(
+                (data) => nativeRenderer.completeEncodeRequest(request.requestId, Buffer.from(data), null),
+                (e) => nativeRenderer.completeEncodeRequest(request.requestId, null, e?.message || String(e))
+            );
+    };
+
+    nativeRenderer.registerEncoder(name, handler, options.format);
+}
+
+/**
+ * 注销自定义编码器
+ *
+ * @param {string} name - 编码器名称
+ * @returns {boolean} 是否存在
+ */
+export function unregisterEncoder(name) {
+    if (!nativeAvailable) {
+        return false;
+    }
+    return nativeRenderer.unregisterEncoder(name);
+}
+
+/**
+ * 获取最近的渲染记录（用于事后定位导致 worker 崩溃的文档）
+ *
+ * @returns {Array<Object>} [{ seq, startedAt, source, pageNum, width, height, stage, error, elapsed }]
+ */
+export function getRecentRenders() {
+    if (typeof nativeRenderer.getRecentRenders !== 'function') {
+        return [];
+    }
+    return nativeRenderer.getRecentRenders();
+}
+
+/**
+ * 把最近的渲染记录以 JSON 写入文件
+ *
+ * @param {string} path - 输出文件路径
+ */
+export function dumpRecentRenders(path) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    nativeRenderer.dumpRecentRenders(path);
+}
+
+/**
+ * 设置崩溃转储路径，原生模块 panic 时自动写入最近的渲染记录
+ *
+ * @param {string|null} path - 输出文件路径，null 取消
+ */
+export function setCrashDumpPath(path) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    nativeRenderer.setCrashDumpPath(path ?? null);
+}
+
+/**
+ * 关闭原生渲染器
+ *
+ * 停止接收新任务，等待进行中的渲染和流式任务完成，超时后取消流式任务。
+ *
+ * @param {Object} [options] - { drainTimeoutMs }
+ * @returns {Promise<Object>} { drained, remaining, cancelledStreams, elapsed }
+ */
+export async function shutdown(options = {}) {
+    if (!nativeAvailable) {
+        return { drained: true, remaining: 0, cancelledStreams: 0, elapsed: 0 };
+    }
+    return nativeRenderer.shutdown(options);
+}
+
+/**
+ * 生成合成测试 PDF，供集成测试覆盖纯文字、扫描件、超大页面和加密文档
+ *
+ * @param {Object} options - kind: text/image/huge/mixed（默认 text）；pages: 页数（默认 3）；password: 加密密码
+ * @returns {Buffer} PDF 数据（相同参数生成的字节完全相同）
+ */
+export function generateTestPdf(options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+    return nativeRenderer.generateTestPdf(options);
 }
 
 /**
@@ -118,12 +1308,60 @@ export function getVersion() {
     return nativeRenderer.getVersion();
 }
 
+/**
+ * 把原生单页结果转换为 renderFrom* 返回的页面格式
+ */
+function toPageResult(page) {
+    return {
+        pageNum: page.pageNum,
+        width: page.width,
+        height: page.height,
+        buffer: page.success && page.buffer.length > 0 ? page.buffer : undefined,
+        success: page.success,
+        error: page.error,
+        renderTime: page.renderTime,
+        encodeTime: page.encodeTime,
+        skipped: page.skipped,
+        format: page.format,
+        alternates: page.alternates ?? undefined,
+        cached: page.cached,
+        passthrough: page.passthrough,
+        detectedScan: page.detectedScan ?? undefined,
+        textObjectCount: page.textObjectCount ?? undefined,
+        imageCoverage: page.imageCoverage ?? undefined,
+        downscaledDueToMemory: page.downscaledDueToMemory,
+        imageStats: page.imageStats ?? undefined,
+        contentHash: page.contentHash ?? undefined,
+        spans: page.spans ?? undefined,
+        clamped: page.clamped,
+        requestedSize: page.requestedSize ?? undefined,
+    };
+}
+
+/**
+ * 包装 options.onPage / options.onPreview：原生回调为 (err, page)，转换为 onPage(page)
+ */
+function pageCallback(onPage) {
+    if (!onPage) {
+        return undefined;
+    }
+    return (err, page) => {
+        if (!err) {
+            onPage(toPageResult(page));
+        }
+    };
+}
+
 /**
  * 使用 Native Renderer 渲染 PDF Buffer
  *
  * @param {Buffer} pdfBuffer - PDF 文件数据
- * @param {number[]} pages - 要渲染的页码数组（1-based），空数组表示全部页面
+ * @param {number[]|string|Object} pages - 要渲染的页码数组（1-based），空数组表示全部页面；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
  * @param {Object} options - 渲染选项
+ * @param {AbortSignal} [options.signal] - 触发后剩余页面不再渲染，返回已完成的页面（cancelled 为 true）
+ * @param {function(Object): void} [options.onPage] - 每页完成后立即回调；此时返回结果中的页面不含 buffer
+ * @param {function(Object): void} [options.onPreview] - 每页先交付 previewWidth 宽的预览图，再渲染最终结果
  * @returns {Promise<Object>} 渲染结果
  */
 export async function renderFromBuffer(pdfBuffer, pages = [], options = {}) {
@@ -133,20 +1371,16 @@ export async function renderFromBuffer(pdfBuffer, pages = [], options = {}) {
 
     const config = mergeConfig(options);
     const buffer = Buffer.isBuffer(pdfBuffer) ? pdfBuffer : Buffer.from(pdfBuffer);
-    const numPages = nativeRenderer.getPageCount(buffer);
+    const numPages = getPageCount(buffer, passwordCandidates(config));
 
-    // 确定目标页码
-    let targetPages;
-    if (pages.length === 0) {
-        targetPages = Array.from({ length: numPages }, (_, i) => i + 1);
-    } else {
-        targetPages = pages.filter(p => p >= 1 && p <= numPages);
-    }
+    const targetPages = resolvePages(pages, numPages);
 
-    logger.debug(`Rendering ${targetPages.length} pages from buffer (${(buffer.length / 1024 / 1024).toFixed(2)}MB)`);
+    logger.debug(`Rendering ${targetPages.length} pages from buffer (${(buffer.length / 1024 / 1024).toFixed(2)}MB)`, { correlationId: config.correlationId });
 
     const startTime = Date.now();
-    const result = nativeRenderer.renderPages(buffer, targetPages, config);
+    // 在原生后台线程渲染，大批量页面不阻塞事件循环
+    const result = await withAbortSignal(options.signal, cancelToken =>
+        nativeRenderer.renderPagesAsync(buffer, targetPages, { ...config, cancelToken: cancelToken ?? config.cancelToken }, pageCallback(options.onPage), pageCallback(options.onPreview)));
 
     if (!result.success) {
         throw new Error(result.error || 'Native renderer failed');
@@ -154,19 +1388,16 @@ export async function renderFromBuffer(pdfBuffer, pages = [], options = {}) {
 
     return {
         success: true,
+        correlationId: result.correlationId,
+        resolvedConfig: result.resolvedConfig,
+        configHash: result.configHash,
         numPages,
-        pages: result.pages.map(page => ({
-            pageNum: page.pageNum,
-            width: page.width,
-            height: page.height,
-            buffer: page.success ? page.buffer : undefined,
-            success: page.success,
-            error: page.error,
-            renderTime: page.renderTime,
-            encodeTime: page.encodeTime,
-        })),
+        passwordIndex: result.passwordIndex,
+        cancelled: result.cancelled,
+        pages: result.pages.map(toPageResult),
         totalTime: Date.now() - startTime,
         nativeTime: result.totalTime,
+        resourceUsage: result.resourceUsage ?? undefined,
     };
 }
 
@@ -177,8 +1408,12 @@ export async function renderFromBuffer(pdfBuffer, pages = [], options = {}) {
  * 这是处理本地文件的最高效方式。
  *
  * @param {string} filePath - PDF 文件路径
- * @param {number[]} pages - 要渲染的页码数组（1-based），空数组表示全部页面
+ * @param {number[]|string|Object} pages - 要渲染的页码数组（1-based），空数组表示全部页面；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
  * @param {Object} options - 渲染选项
+ * @param {AbortSignal} [options.signal] - 触发后剩余页面不再渲染，返回已完成的页面（cancelled 为 true）
+ * @param {function(Object): void} [options.onPage] - 每页完成后立即回调；此时返回结果中的页面不含 buffer
+ * @param {function(Object): void} [options.onPreview] - 每页先交付 previewWidth 宽的预览图，再渲染最终结果
  * @returns {Promise<Object>} 渲染结果
  */
 export async function renderFromFile(filePath, pages = [], options = {}) {
@@ -187,20 +1422,15 @@ export async function renderFromFile(filePath, pages = [], options = {}) {
     }
 
     const config = mergeConfig(options);
-    const numPages = nativeRenderer.getPageCountFromFile(filePath);
+    const numPages = getPageCountFromFile(filePath, passwordCandidates(config));
 
-    // 确定目标页码
-    let targetPages;
-    if (pages.length === 0) {
-        targetPages = Array.from({ length: numPages }, (_, i) => i + 1);
-    } else {
-        targetPages = pages.filter(p => p >= 1 && p <= numPages);
-    }
+    const targetPages = resolvePages(pages, numPages);
 
-    logger.debug(`Rendering ${targetPages.length} pages from file: ${filePath}`);
+    logger.debug(`Rendering ${targetPages.length} pages from file: ${filePath}`, { correlationId: config.correlationId });
 
     const startTime = Date.now();
-    const result = nativeRenderer.renderPagesFromFile(filePath, targetPages, config);
+    const result = await withAbortSignal(options.signal, cancelToken =>
+        nativeRenderer.renderPagesFromFile(filePath, targetPages, { ...config, cancelToken: cancelToken ?? config.cancelToken }, pageCallback(options.onPage), pageCallback(options.onPreview)));
 
     if (!result.success) {
         throw new Error(result.error || 'Native renderer failed');
@@ -208,86 +1438,191 @@ export async function renderFromFile(filePath, pages = [], options = {}) {
 
     return {
         success: true,
+        correlationId: result.correlationId,
+        resolvedConfig: result.resolvedConfig,
+        configHash: result.configHash,
         numPages,
-        pages: result.pages.map(page => ({
-            pageNum: page.pageNum,
-            width: page.width,
-            height: page.height,
-            buffer: page.success ? page.buffer : undefined,
-            success: page.success,
-            error: page.error,
-            renderTime: page.renderTime,
-            encodeTime: page.encodeTime,
-        })),
+        passwordIndex: result.passwordIndex,
+        cancelled: result.cancelled,
+        pages: result.pages.map(toPageResult),
         totalTime: Date.now() - startTime,
         nativeTime: result.totalTime,
+        resourceUsage: result.resourceUsage ?? undefined,
     };
 }
 
 /**
- * 使用 Native Stream 渲染远程 PDF
+ * 把 PDF 文件的页面直接渲染到输出目录
  *
- * 通过回调按需获取 PDF 数据，避免一次性下载整个文件
+ * 编码后的图像在 Rust 侧写入文件，不经过 Node Buffer，适合结果最终都要落盘的批量转换。
+ * 文件名模板中 `%d` 替换为页码，`%0Nd` 补零到 N 位，扩展名按实际输出格式追加。
  *
- * @param {string} pdfUrl - PDF 文件 URL
- * @param {number} pdfSize - PDF 文件大小
- * @param {number[]} pages - 要渲染的页码数组（1-based），空数组表示全部页面
+ * @param {string} filePath - PDF 文件路径
+ * @param {number[]|string|Object} pages - 要渲染的页码数组（1-based），空数组表示全部页面；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
+ * @param {string} outputDir - 输出目录（不存在时自动创建）
  * @param {Object} options - 渲染选项
- * @returns {Promise<Object>} 渲染结果
+ * @param {string} [options.filenamePattern='page-%d'] - 文件名模板
+ * @param {AbortSignal} [options.signal] - 触发后剩余页面不再渲染，已写入的文件保留
+ * @returns {Promise<Object>} { success, numPages, cancelled, pages: [{ pageNum, path, size, writeTime, ... }], totalTime, nativeTime }
  */
-export async function renderFromStream(pdfUrl, pdfSize, pages = [], options = {}) {
+export async function renderToFiles(filePath, pages = [], outputDir, options = {}) {
     if (!nativeAvailable) {
         throw new Error('Native renderer not available');
     }
 
-    if (!pdfUrl || !pdfSize) {
-        throw new Error('pdfUrl and pdfSize are required for stream mode');
+    const config = mergeConfig(options);
+    const numPages = getPageCountFromFile(filePath, passwordCandidates(config));
+    const targetPages = resolvePages(pages, numPages);
+
+    logger.debug(`Rendering ${targetPages.length} pages from ${filePath} to ${outputDir}`, { correlationId: config.correlationId });
+
+    const startTime = Date.now();
+    const result = await withAbortSignal(options.signal, cancelToken =>
+        nativeRenderer.renderPagesToFiles(filePath, targetPages, outputDir, options.filenamePattern,
+            { ...config, cancelToken: cancelToken ?? config.cancelToken }));
+
+    if (!result.success) {
+        throw new Error(result.error || 'Native renderer failed');
     }
 
-    const config = mergeConfig(options);
+    return {
+        success: true,
+        correlationId: result.correlationId,
+        resolvedConfig: result.resolvedConfig,
+        configHash: result.configHash,
+        numPages,
+        cancelled: result.cancelled,
+        pages: result.pages,
+        totalTime: Date.now() - startTime,
+        nativeTime: result.totalTime,
+    };
+}
+
+/**
+ * 创建按 HTTP Range 请求获取数据的 fetcher 回调（被 Rust 通过 ThreadsafeFunction 调用）
+ *
+ * 取数计划（fetchPlan）的批量请求带有 ranges，各段并行请求后按顺序拼接返回；
+ * 流式读取效率过低时的回退请求带有 full，不带 Range 头下载整个文件。
+ * 响应带上 Content-Range 中的文件总长度（totalSize），pdfSize 未知时原生层据此确定文件末尾。
+ * 同一轮事件循环内完成的响应合并为一次 completeStreamRequests 调用，Buffer 交给原生侧后不再复制。
+ *
+ * @param {string} pdfUrl - PDF 文件 URL
+ * @returns {Function} fetcher(error, { offset, size, requestId, correlationId, ranges, full })
+ */
+function createRangeFetcher(pdfUrl) {
+    const fetchRange = (start, size) => fetch(pdfUrl, {
+        headers: { 'Range': `bytes=${start}-${start + size - 1}` },
+        signal: AbortSignal.timeout(TIMEOUT_CONFIG.RANGE_REQUEST_TIMEOUT),
+    }).then(async response => {
+        if (!response.ok && response.status !== 206) {
+            throw new Error(`Range request failed with status ${response.status}`);
+        }
+        const data = Buffer.from(await response.arrayBuffer());
+        // 服务器忽略 Range 头时返回整个文件
+        const total = response.status === 206
+            ? /\/(\d+)\s*$/.exec(response.headers.get('content-range') || '')?.[1]
+            : data.length;
+        return { data, totalSize: total === undefined ? undefined : Number(total) };
+    });
 
-    logger.debug(`Stream rendering from ${pdfUrl} (${(pdfSize / 1024 / 1024).toFixed(2)}MB)`);
+    const fetchFull = () => fetch(pdfUrl, {
+        signal: AbortSignal.timeout(TIMEOUT_CONFIG.DOWNLOAD_TIMEOUT),
+    }).then(response => {
+        if (!response.ok) {
+            throw new Error(`Full download failed with status ${response.status}`);
+        }
+        return response.arrayBuffer();
+    });
 
-    /**
-     * fetcher 回调函数 - 被 Rust 通过 ThreadsafeFunction 调用
-     */
-    const fetcher = (error, req) => {
+    let queued = [];
+    const complete = (response) => {
+        if (queued.push(response) === 1) {
+            setImmediate(() => {
+                const responses = queued;
+                queued = [];
+                nativeRenderer.completeStreamRequests(responses);
+            });
+        }
+    };
+
+    return (error, req) => {
         if (error) {
             logger.error(`Fetcher received error: ${error.message}`);
             return;
         }
 
-        const { offset, size, requestId } = req;
+        const { offset, size, requestId, ranges, full } = req;
         const start = Number(offset);
-        const end = start + size - 1;
-
-        fetch(pdfUrl, {
-            headers: { 'Range': `bytes=${start}-${end}` },
-            signal: AbortSignal.timeout(TIMEOUT_CONFIG.RANGE_REQUEST_TIMEOUT),
-        })
-            .then(response => {
-                if (!response.ok && response.status !== 206) {
-                    throw new Error(`Range request failed with status ${response.status}`);
-                }
-                return response.arrayBuffer();
-            })
-            .then(data => {
-                nativeRenderer.completeStreamRequest(requestId, Buffer.from(data), null);
+
+        const fetched = full
+            ? fetchFull().then(part => ({ data: Buffer.from(part), totalSize: part.byteLength }))
+            : ranges
+            ? Promise.all(ranges.map(range => fetchRange(Number(range.offset), range.size)))
+                .then(parts => ({ data: Buffer.concat(parts.map(part => part.data)), totalSize: parts[0].totalSize }))
+            : fetchRange(start, size);
+
+        fetched
+            .then(({ data, totalSize }) => {
+                complete({ requestId, data, totalSize });
             })
             .catch(err => {
-                logger.error(`Fetcher failed (offset=${start}, size=${size}): ${err.message}`);
-                nativeRenderer.completeStreamRequest(requestId, null, err.message);
+                logger.error(`Fetcher failed (offset=${start}, size=${size}): ${err.message}`, { correlationId: req.correlationId });
+                complete({ requestId, error: err.message });
             });
     };
+}
+
+/**
+ * 使用 Native Stream 渲染远程 PDF
+ *
+ * 通过回调按需获取 PDF 数据，避免一次性下载整个文件
+ *
+ * @param {string} pdfUrl - PDF 文件 URL
+ * @param {number} [pdfSize] - PDF 文件大小，未知时传 0 或 null（边上传边渲染）
+ * @param {number[]|string|Object} pages - 要渲染的页码数组（1-based），空数组表示全部页面；
+ *   也可以是范围字符串（"1-5,8,10-"、"all"）或 { from, to }
+ * @param {Object} options - 渲染选项
+ * @param {Array<{offset: number, data: Buffer}>} [options.seedBlocks] - 调用方已有的文件数据（如上传时读到的开头部分），预先写入缓存
+ * @param {function(Object): void} [options.onDocument] - 文档加载完成、任何页面渲染之前回调一次
+ *   { numPages, title, author, ..., pdfVersion, fileSize, loadTime }，界面可以先画出翻页骨架
+ * @returns {Promise<Object>} 渲染结果
+ */
+export async function renderFromStream(pdfUrl, pdfSize, pages = [], options = {}) {
+    if (!nativeAvailable) {
+        throw new Error('Native renderer not available');
+    }
+
+    if (!pdfUrl) {
+        throw new Error('pdfUrl is required for stream mode');
+    }
+
+    const config = mergeConfig(options);
+
+    // pdfSize 未知时传 0，原生层从第一个响应的 totalSize 得到文件长度
+    const sizeLabel = pdfSize ? `${(pdfSize / 1024 / 1024).toFixed(2)}MB` : 'unknown size';
+    logger.debug(`Stream rendering from ${pdfUrl} (${sizeLabel})`, { correlationId: config.correlationId });
+
+    const fetcher = createRangeFetcher(pdfUrl);
 
     const startTime = Date.now();
 
-    // 首次调用获取页数
-    let result = await nativeRenderer.renderPagesFromStream(
-        pdfSize,
-        pages,
+    const onDocument = options.onDocument
+        ? (err, info) => {
+            if (!err) {
+                options.onDocument(info);
+            }
+        }
+        : null;
+
+    // 空数组表示全部页面；范围在原生层加载文档后展开，不需要先单独获取页数
+    const result = await nativeRenderer.renderPagesFromStream(
+        pdfSize || 0,
+        Array.isArray(pages) && pages.length === 0 ? 'all' : pages,
         config,
-        fetcher
+        fetcher,
+        options.seedBlocks,
+        onDocument
     );
 
     if (!result.success) {
@@ -296,34 +1631,17 @@ export async function renderFromStream(pdfUrl, pdfSize, pages = [], options = {}
 
     const numPages = result.numPages;
 
-    // 如果需要渲染所有页面但之前不知道页数
-    if (pages.length === 0 && numPages > 0 && result.pages.length === 0) {
-        const allPages = Array.from({ length: numPages }, (_, i) => i + 1);
-        result = await nativeRenderer.renderPagesFromStream(
-            pdfSize,
-            allPages,
-            config,
-            fetcher
-        );
-
-        if (!result.success) {
-            throw new Error(result.error || 'Native stream renderer failed');
-        }
+    for (const hint of result.streamStats?.hints ?? []) {
+        logger.debug(`Stream tuning hint ${hint.code}: ${hint.message}`, { correlationId: config.correlationId });
     }
 
     return {
         success: true,
+        correlationId: result.correlationId,
+        resolvedConfig: result.resolvedConfig,
+        configHash: result.configHash,
         numPages,
-        pages: result.pages.map(page => ({
-            pageNum: page.pageNum,
-            width: page.width,
-            height: page.height,
-            buffer: page.success ? page.buffer : undefined,
-            success: page.success,
-            error: page.error,
-            renderTime: page.renderTime,
-            encodeTime: page.encodeTime,
-        })),
+        pages: result.pages.map(toPageResult),
         totalTime: Date.now() - startTime,
         nativeTime: result.totalTime,
         streamStats: result.streamStats,
@@ -10,6 +10,32 @@
 
 import sharp from 'sharp';
 
+/** WebP 单边最大像素数 */
+const WEBP_MAX_DIMENSION = 16383;
+
+/** AVIF 单边最大像素数（libheif / 浏览器解码器的默认上限） */
+const AVIF_MAX_DIMENSION = 16384;
+
+/** 编码超出 encodeBudgetMs 后改用的 WebP 质量上限（effort 同时降为 0），与原生渲染器一致 */
+const BUDGET_FALLBACK_QUALITY = 75;
+
+/**
+ * 输出格式的单边像素上限，没有上限的格式返回 Infinity
+ */
+function formatMaxDimension(format) {
+    if (format === 'webp') return WEBP_MAX_DIMENSION;
+    if (format === 'avif') return AVIF_MAX_DIMENSION;
+    return Infinity;
+}
+
+/**
+ * AVIF 编码的 effort（sharp 为 0-9，越大越慢）：由 avifSpeed（0-10，越大越快）换算
+ */
+function avifEffort(options) {
+    const speed = Math.min(10, Math.max(0, options.avifSpeed ?? 6));
+    return Math.round((10 - speed) * 0.9);
+}
+
 // ==================== Native Renderer 懒加载 ====================
 
 let nativeRenderer = null;
@@ -46,13 +72,84 @@ async function initNativeRenderer() {
     return initPromise;
 }
 
+/**
+ * 转换是否已被取消（options.cancelToken，由主线程的 AbortSignal 触发）
+ */
+function isCancelled(options) {
+    return options.cancelToken !== undefined && nativeRenderer.isRenderCancelled(options.cancelToken);
+}
+
+/**
+ * 因取消而未完成的页面
+ */
+function cancelledPage(pageNum, renderTime) {
+    return {
+        pageNum,
+        success: false,
+        skipped: true,
+        error: 'Cancelled',
+        errorCode: 'CANCELLED',
+        width: 0,
+        height: 0,
+        buffer: null,
+        renderTime,
+        encodeTime: 0,
+    };
+}
+
 /**
  * 合并配置
  */
 function mergeConfig(options = {}) {
     return {
-        targetWidth: options.targetWidth ?? 1280,
+        targetWidth: options.dpi ? options.targetWidth : options.targetWidth ?? 1280,
+        dpi: options.dpi,
         detectScan: options.detectScan ?? false,
+        scanMinImageCoverage: options.scanMinImageCoverage,
+        scanMaxTextObjects: options.scanMaxTextObjects,
+        maxPixelsPerPage: options.maxPixelsPerPage,
+        maxTotalBytes: options.maxTotalBytes,
+        memoryBudgetAction: options.memoryBudgetAction,
+        pageTimeoutMs: options.pageTimeoutMs,
+        correlationId: options.correlationId,
+        pageWidths: options.pageWidths,
+        pageDpi: options.pageDpi,
+        pageScanMode: options.pageScanMode,
+        canvasWidth: options.canvasWidth,
+        canvasHeight: options.canvasHeight,
+        padColor: options.padColor,
+        padAlign: options.padAlign,
+        highlights: options.highlights,
+        stampText: options.stampText,
+        stampImage: options.stampImage,
+        stampImageWidth: options.stampImageWidth,
+        stampImageHeight: options.stampImageHeight,
+        stampFontSize: options.stampFontSize,
+        stampColor: options.stampColor,
+        stampOpacity: options.stampOpacity,
+        stampPosition: options.stampPosition,
+        stampMargin: options.stampMargin,
+        stampExclusions: options.stampExclusions,
+        passwords: options.passwords,
+        password: options.password,
+        forceSrgb: options.forceSrgb,
+        cmykProfile: options.cmykProfile,
+        imageStats: options.imageStats,
+        contentHash: options.contentHash,
+        objectDiagnostics: options.objectDiagnostics,
+        annotationTypes: options.annotationTypes,
+        formMode: options.formMode,
+        renderAnnotations: options.renderAnnotations,
+        renderForms: options.renderForms,
+        disableTextAntialiasing: options.disableTextAntialiasing,
+        disableImageAntialiasing: options.disableImageAntialiasing,
+        optimizeForLcd: options.optimizeForLcd,
+        preserveAlpha: options.preserveAlpha,
+        spanTimings: options.spanTimings,
+        resourceUsage: options.resourceUsage,
+        concurrency: options.concurrency,
+        deterministic: options.deterministic,
+        cancelToken: options.cancelToken,
     };
 }
 
@@ -74,29 +171,185 @@ async function encodeWithSharp(rawBitmap, width, height, format, options = {}) {
             channels: 4, // RGBA
         }
     });
+    if (options.timeoutSeconds) {
+        sharpInstance = sharpInstance.timeout({ seconds: options.timeoutSeconds });
+    }
 
     if (format === 'webp') {
         return sharpInstance.webp({
             quality: options.webpQuality || options.quality || 80,
-            effort: options.webpMethod ?? 4,
+            effort: webpEffort(options, width, height),
         }).toBuffer();
     } else if (format === 'png') {
         return sharpInstance.png({
             compressionLevel: options.pngCompression ?? 6,
             adaptiveFiltering: true,
         }).toBuffer();
+    } else if (format === 'avif') {
+        return sharpInstance.avif({
+            quality: options.avifQuality || options.quality || 50,
+            effort: avifEffort(options),
+        }).toBuffer();
     } else if (format === 'jpeg' || format === 'jpg') {
-        // 移除 alpha 通道，与白色背景混合
-        sharpInstance = sharpInstance.flatten({ background: { r: 255, g: 255, b: 255 } });
+        // 移除 alpha 通道，与背景色（默认白色）混合
+        sharpInstance = sharpInstance.flatten({ background: options.jpegBackground || '#ffffff' });
         return sharpInstance.jpeg({
             quality: options.jpegQuality || options.quality || 85,
             mozjpeg: true,
+            ...(options.jpegProgressive !== undefined && { progressive: options.jpegProgressive }),
+            ...(options.jpegChromaSubsampling && {
+                chromaSubsampling: options.jpegChromaSubsampling === '420' ? '4:2:0' : '4:4:4',
+            }),
         }).toBuffer();
     }
     
     throw new Error(`Unsupported format: ${format}`);
 }
 
+/**
+ * 按 encodeBudgetMs 编码 WebP：超时后改用 effort 0、quality 不超过 75 重新编码
+ *
+ * Sharp 的超时以秒为单位，预算向上取整到整秒；其他格式不受预算影响。
+ *
+ * @returns {Promise<{buffer: Buffer, options: Object, budgetExceeded: boolean}>} options 为实际使用的编码参数
+ */
+async function encodeWithBudget(rawBitmap, width, height, format, options = {}) {
+    if (!options.encodeBudgetMs || format !== 'webp') {
+        return { buffer: await encodeWithSharp(rawBitmap, width, height, format, options), options, budgetExceeded: false };
+    }
+    const timeoutSeconds = Math.max(1, Math.ceil(options.encodeBudgetMs / 1000));
+    try {
+        const buffer = await encodeWithSharp(rawBitmap, width, height, format, { ...options, timeoutSeconds });
+        return { buffer, options, budgetExceeded: false };
+    } catch (err) {
+        if (!/timeout/i.test(err.message)) {
+            throw err;
+        }
+    }
+    const fallback = {
+        ...options,
+        webpQuality: Math.min(options.webpQuality || options.quality || 80, BUDGET_FALLBACK_QUALITY),
+        webpMethod: 0,
+        webpMethodAuto: false,
+    };
+    return { buffer: await encodeWithSharp(rawBitmap, width, height, format, fallback), options: fallback, budgetExceeded: true };
+}
+
+/**
+ * 用同一张位图额外编码 options.alsoEncode 中的格式
+ *
+ * 跳过与主格式相同的条目；单个格式编码失败不影响主结果，记录在该条目的 error 中。
+ *
+ * @returns {Promise<Array|undefined>} [{ format, buffer, size, error }]，未指定时为 undefined
+ */
+async function encodeAlternates(rawBitmap, width, height, primaryFormat, options) {
+    if (!options.alsoEncode?.length) {
+        return undefined;
+    }
+    const normalize = (f) => (f.toLowerCase() === 'jpeg' ? 'jpg' : f.toLowerCase());
+    const formats = [...new Set(options.alsoEncode.map(normalize))].filter(f => f !== normalize(primaryFormat));
+    const alternates = [];
+    for (const format of formats) {
+        try {
+            const buffer = await encodeWithSharp(rawBitmap, width, height, format, options);
+            alternates.push({ format, buffer, size: buffer.length });
+        } catch (err) {
+            alternates.push({ format, buffer: null, size: 0, error: err.message });
+        }
+    }
+    return alternates;
+}
+
+/**
+ * 渲染后又缩放时，同步调整 DPI、点到像素的变换和对象区域
+ *
+ * @param {Object} renderInfo - 原生渲染结果的 renderInfo
+ * @param {number} sx - 水平缩放比例
+ * @param {number} sy - 垂直缩放比例
+ * @returns {Object} { dpi, transform, objectErrors }
+ */
+function scaleMapping(renderInfo, sx, sy) {
+    if (!renderInfo.transform) {
+        return {};
+    }
+    const [a, b, c, d, e, f] = renderInfo.transform;
+    return {
+        dpi: renderInfo.dpi * sx,
+        transform: [a * sx, b * sy, c * sx, d * sy, e * sx, f * sy],
+        objectErrors: renderInfo.objectErrors?.map(error => ({
+            ...error,
+            region: error.region && {
+                x: Math.floor(error.region.x * sx),
+                y: Math.floor(error.region.y * sy),
+                width: Math.ceil(error.region.width * sx),
+                height: Math.ceil(error.region.height * sy),
+            },
+        })),
+    };
+}
+
+/**
+ * WebP 编码使用的 effort；webpMethodAuto 时按像素数调整，分档与原生渲染器一致
+ */
+function webpEffort(options, width, height) {
+    const base = options.webpMethod ?? 4;
+    if (!options.webpMethodAuto) {
+        return base;
+    }
+    const pixels = width * height;
+    const shift = pixels <= 500_000 ? 2
+        : pixels <= 1_000_000 ? 1
+        : pixels <= 4_000_000 ? 0
+        : pixels <= 12_000_000 ? -2
+        : -4;
+    return Math.min(6, Math.max(0, base + shift));
+}
+
+/**
+ * Sharp 编码时实际使用的编码参数（与原生渲染结果的 renderInfo 字段一致）
+ *
+ * width/height 为单次编码的图像尺寸（分块页面为分块大小）
+ */
+function sharpEncoderInfo(format, options = {}, width = 0, height = 0) {
+    if (format === 'webp') {
+        return { encoderBackend: 'sharp', quality: options.webpQuality || options.quality || 80, webpMethod: webpEffort(options, width, height) };
+    }
+    if (format === 'png') {
+        return { encoderBackend: 'sharp', quality: options.pngCompression ?? 6 };
+    }
+    if (format === 'avif') {
+        return { encoderBackend: 'sharp', quality: options.avifQuality || options.quality || 50 };
+    }
+    return { encoderBackend: 'sharp', quality: options.jpegQuality || options.quality || 85 };
+}
+
+/**
+ * 把超出尺寸上限的原始位图切成网格分块并逐块编码
+ *
+ * @returns {Promise<Object>} 分块网格 { tileSize, columns, rows, tiles }
+ */
+async function encodeTiles(rawBitmap, width, height, format, options = {}) {
+    const tileSize = Math.min(Math.max(options.tileSize ?? 4096, 256), formatMaxDimension(format));
+    const columns = Math.ceil(width / tileSize);
+    const rows = Math.ceil(height / tileSize);
+    const tiles = [];
+    for (let row = 0; row < rows; row++) {
+        for (let column = 0; column < columns; column++) {
+            const x = column * tileSize;
+            const y = row * tileSize;
+            const tileWidth = Math.min(tileSize, width - x);
+            const tileHeight = Math.min(tileSize, height - y);
+            const pixels = await sharp(rawBitmap, { raw: { width, height, channels: 4 } })
+                .extract({ left: x, top: y, width: tileWidth, height: tileHeight })
+                .raw()
+                .toBuffer();
+            const buffer = await encodeWithSharp(pixels, tileWidth, tileHeight, format, options);
+            tiles.push({ row, column, x, y, width: tileWidth, height: tileHeight, buffer });
+        }
+    }
+    return { tileSize, columns, rows, tiles };
+}
+
 /**
  * 处理单个页面任务
  * 
@@ -120,6 +373,23 @@ export default async function processPage(task) {
             pageNum,
             success: false,
             error: 'Native renderer not available in worker thread',
+            errorCode: 'RENDER_FAILED',
+            width: 0,
+            height: 0,
+            buffer: null,
+            renderTime: 0,
+            encodeTime: 0,
+        };
+    }
+    
+    // 超出总时间预算：不再渲染，标记为跳过
+    if (options.deadline && Date.now() >= options.deadline) {
+        return {
+            pageNum,
+            success: false,
+            skipped: true,
+            error: 'Skipped: total time budget exceeded',
+            errorCode: 'SKIPPED',
             width: 0,
             height: 0,
             buffer: null,
@@ -128,6 +398,11 @@ export default async function processPage(task) {
         };
     }
     
+    // 转换已取消：不再渲染
+    if (isCancelled(options)) {
+        return cancelledPage(pageNum, 0);
+    }
+    
     const config = mergeConfig(options);
     
     try {
@@ -144,6 +419,7 @@ export default async function processPage(task) {
                 pageNum,
                 success: false,
                 error: 'No input provided: filePath or pdfBuffer required',
+                errorCode: 'INVALID_OPTIONS',
                 width: 0,
                 height: 0,
                 buffer: null,
@@ -157,6 +433,7 @@ export default async function processPage(task) {
                 pageNum,
                 success: false,
                 error: rawResult.error || 'Render failed',
+                errorCode: rawResult.errorCode || 'RENDER_FAILED',
                 width: 0,
                 height: 0,
                 buffer: null,
@@ -166,35 +443,108 @@ export default async function processPage(task) {
         }
         
         const renderTime = rawResult.renderTime || 0;
+        if (isCancelled(options)) {
+            return cancelledPage(pageNum, renderTime);
+        }
         const encodeStart = Date.now();
         
-        // 步骤 2: Sharp 编码
-        const format = options.format || 'webp';
-        const encodedBuffer = await encodeWithSharp(
-            rawResult.buffer,
-            rawResult.width,
-            rawResult.height,
-            format,
-            options
-        );
+        // 步骤 2: Sharp 编码（原始位图不受 WebP/AVIF 尺寸上限约束，超限时按 oversizeStrategy 处理）
+        let format = options.pageFormats?.[pageNum] || options.format || 'webp';
+        let { width, height } = rawResult;
+        let bitmap = rawResult.buffer;
+        let resizedForFormat = false;
+        const maxDimension = formatMaxDimension(format);
+        if (width > maxDimension || height > maxDimension) {
+            if (options.oversizeStrategy === 'tile') {
+                const tiles = await encodeTiles(bitmap, width, height, format, options);
+                return {
+                    pageNum,
+                    success: true,
+                    width,
+                    height,
+                    format,
+                    buffer: null,
+                    tiles,
+                    size: tiles.tiles.reduce((sum, tile) => sum + tile.buffer.length, 0),
+                    renderInfo: {
+                        ...rawResult.renderInfo,
+                        ...sharpEncoderInfo(format, options, tiles.tiles[0].width, tiles.tiles[0].height),
+                    },
+                    imageStats: rawResult.imageStats ?? undefined,
+                    contentHash: rawResult.contentHash ?? undefined,
+                    detectedScan: rawResult.detectedScan ?? undefined,
+                    textObjectCount: rawResult.textObjectCount ?? undefined,
+                    imageCoverage: rawResult.imageCoverage ?? undefined,
+                    downscaledDueToMemory: rawResult.downscaledDueToMemory,
+                    renderTime,
+                    encodeTime: Date.now() - encodeStart,
+                };
+            }
+            if (options.oversizeStrategy === 'fallbackPng') {
+                format = 'png';
+            } else {
+                const scale = Math.min(maxDimension / width, maxDimension / height);
+                const resized = await sharp(bitmap, { raw: { width, height, channels: 4 } })
+                    .resize(Math.round(width * scale), Math.round(height * scale), { fit: 'fill' })
+                    .raw()
+                    .toBuffer({ resolveWithObject: true });
+                bitmap = resized.data;
+                ({ width, height } = resized.info);
+                resizedForFormat = true;
+            }
+        }
+        const { buffer: encodedBuffer, options: encodeOptions, budgetExceeded } = await encodeWithBudget(bitmap, width, height, format, options);
+        const alternates = await encodeAlternates(bitmap, width, height, format, options);
         
         const encodeTime = Date.now() - encodeStart;
         
         return {
             pageNum,
             success: true,
-            width: rawResult.width,
-            height: rawResult.height,
+            width,
+            height,
+            format,
             buffer: encodedBuffer,
             size: encodedBuffer.length,
+            alternates,
             renderTime,
             encodeTime,
+            passwordIndex: rawResult.passwordIndex ?? undefined,
+            imageStats: rawResult.imageStats ?? undefined,
+            contentHash: rawResult.contentHash ?? undefined,
+            detectedScan: rawResult.detectedScan ?? undefined,
+            textObjectCount: rawResult.textObjectCount ?? undefined,
+            imageCoverage: rawResult.imageCoverage ?? undefined,
+            downscaledDueToMemory: rawResult.downscaledDueToMemory,
+            // 原生渲染尺寸被上限缩小时原始请求尺寸未知，只有 WebP/AVIF 上限引起的缩小带 requestedSize
+            clamped: Boolean(rawResult.renderInfo?.dimensionClamped) || resizedForFormat,
+            requestedSize: resizedForFormat && !rawResult.renderInfo?.dimensionClamped
+                ? { width: rawResult.width, height: rawResult.height }
+                : undefined,
+            spans: rawResult.spans && {
+                ...rawResult.spans,
+                // 原生侧只整理像素数据，编码在这里由 Sharp 完成
+                encodeEnd: performance.timeOrigin + performance.now(),
+            },
+            // 只包含原生渲染，不含 Sharp 编码
+            resourceUsage: rawResult.resourceUsage ?? undefined,
+            renderInfo: rawResult.renderInfo && {
+                ...rawResult.renderInfo,
+                ...sharpEncoderInfo(format, encodeOptions, width, height),
+                encodeBudgetExceeded: budgetExceeded,
+                ...(resizedForFormat && scaleMapping(rawResult.renderInfo, width / rawResult.width, height / rawResult.height)),
+                resized: rawResult.renderInfo.resized || resizedForFormat,
+                dimensionClamped: rawResult.renderInfo.dimensionClamped || resizedForFormat,
+                // Sharp 编码 JPEG 时与背景色混合，只有 WebP/PNG/AVIF 保留透明区域
+                hasAlpha: rawResult.renderInfo.hasAlpha && (format === 'webp' || format === 'png' || format === 'avif'),
+            },
         };
     } catch (err) {
         return {
             pageNum,
             success: false,
             error: err.message || 'Unknown error',
+            errorCode: 'ENCODE_FAILED',
             width: 0,
             height: 0,
             buffer: null,
@@ -172,6 +172,130 @@ describe('PDF2IMG API 测试', () => {
             assert.ok(result, '应该返回结果');
             assert.ok(result.pages.length > 0, '应该有页面数据');
         });
+
+        it('应该支持可读流输入', async () => {
+            if (!fs.existsSync(TEST_PDF)) {
+                console.log(`跳过测试：测试文件不存在 ${TEST_PDF}`);
+                return;
+            }
+
+            const result = await pdf2img.convert(fs.createReadStream(TEST_PDF), {
+                pages: [1],
+            });
+
+            assert.ok(result, '应该返回结果');
+            assert.ok(result.pages[0].success, '第 1 页应该渲染成功');
+        });
+
+        it('应该支持页码范围字符串', async () => {
+            if (!fs.existsSync(TEST_PDF_1M)) {
+                console.log(`跳过测试：测试文件不存在 ${TEST_PDF_1M}`);
+                return;
+            }
+
+            const result = await pdf2img.convert(TEST_PDF_1M, {
+                pages: '2-3',
+                targetWidth: 200,
+            });
+
+            assert.deepStrictEqual(result.pages.map(page => page.pageNum), [2, 3]);
+            assert.ok(result.pages.every(page => page.success), '范围内的页面都应渲染成功');
+        });
+
+        it('排除区域内不应合成水印', async () => {
+            if (!fs.existsSync(TEST_PDF)) {
+                console.log(`跳过测试：测试文件不存在 ${TEST_PDF}`);
+                return;
+            }
+
+            // 不透明红色平铺铺满整页，排除区域覆盖整页时与不加水印的结果相同
+            const stamp = {
+                pages: [1],
+                targetWidth: 200,
+                stampImage: Buffer.from([255, 0, 0, 255]),
+                stampImageWidth: 1,
+                stampImageHeight: 1,
+                stampOpacity: 1,
+                stampPosition: 'tile',
+                stampMargin: 0,
+            };
+            const plain = await pdf2img.convert(TEST_PDF, { pages: [1], targetWidth: 200 });
+            const stamped = await pdf2img.convert(TEST_PDF, stamp);
+            const excluded = await pdf2img.convert(TEST_PDF, {
+                ...stamp,
+                stampExclusions: [{ x: -10, y: -10, width: 10000, height: 10000 }],
+            });
+            const otherPage = await pdf2img.convert(TEST_PDF, {
+                ...stamp,
+                stampExclusions: [{ pageNum: 2, x: -10, y: -10, width: 10000, height: 10000 }],
+            });
+
+            assert.notStrictEqual(Buffer.compare(stamped.pages[0].buffer, plain.pages[0].buffer), 0, '水印应该改变渲染结果');
+            assert.strictEqual(Buffer.compare(excluded.pages[0].buffer, plain.pages[0].buffer), 0, '排除区域内不应合成水印');
+            assert.strictEqual(Buffer.compare(otherPage.pages[0].buffer, stamped.pages[0].buffer), 0, '其他页的排除区域不应生效');
+        });
+
+        it('内容哈希应该覆盖高亮和水印，与输出格式无关', async () => {
+            if (!fs.existsSync(TEST_PDF)) {
+                console.log(`跳过测试：测试文件不存在 ${TEST_PDF}`);
+                return;
+            }
+
+            const base = { pages: [1], targetWidth: 200, contentHash: true };
+            const webp = await pdf2img.convert(TEST_PDF, base);
+            const png = await pdf2img.convert(TEST_PDF, { ...base, format: 'png' });
+            const highlighted = await pdf2img.convert(TEST_PDF, {
+                ...base,
+                highlights: [{ pageNum: 1, x: 0, y: 0, width: 200, height: 200, color: '#ff0000' }],
+            });
+            const stamped = await pdf2img.convert(TEST_PDF, { ...base, stampText: 'DRAFT' });
+
+            const hash = webp.pages[0].contentHash;
+            assert.match(hash, /^[0-9a-f]{16}$/);
+            assert.strictEqual(png.pages[0].contentHash, hash, '同一位图改用 PNG 输出时哈希应该相同');
+            assert.notStrictEqual(highlighted.pages[0].contentHash, hash, '高亮应该改变哈希');
+            assert.notStrictEqual(stamped.pages[0].contentHash, hash, '水印应该改变哈希');
+        });
+    });
+
+    describe('extractPagesAsPdf', () => {
+        it('导出的文档应该只包含选中的页面', async () => {
+            if (!fs.existsSync(TEST_PDF_1M)) {
+                console.log(`跳过测试：测试文件不存在 ${TEST_PDF_1M}`);
+                return;
+            }
+
+            const extracted = pdf2img.extractPagesAsPdf(fs.readFileSync(TEST_PDF_1M), '2-4');
+            assert.ok(extracted.success, '导出应该成功');
+            assert.ok(extracted.numPages > 4, '原文档页数应该大于 4');
+            assert.deepStrictEqual(extracted.pageNums, [2, 3, 4]);
+            assert.strictEqual(extracted.buffer.subarray(0, 5).toString(), '%PDF-');
+
+            const result = await pdf2img.convert(extracted.buffer, { targetWidth: 200 });
+            assert.strictEqual(result.numPages, 3);
+            assert.ok(result.pages.every(page => page.success), '导出的页面都应渲染成功');
+        });
+    });
+
+    describe('generateTestPdf', () => {
+        it('生成的 PDF 应该可以直接转换', async () => {
+            const pdf = pdf2img.generateTestPdf({ kind: 'mixed', pages: 4 });
+            assert.strictEqual(Buffer.compare(pdf, pdf2img.generateTestPdf({ kind: 'mixed', pages: 4 })), 0, '相同参数应该生成相同的字节');
+
+            const count = await pdf2img.getPageCount(pdf);
+            assert.strictEqual(count, 4);
+        });
+
+        it('加密的 PDF 应该使用密码转换', async () => {
+            const pdf = pdf2img.generateTestPdf({ pages: 2, password: 'secret' });
+
+            const result = await pdf2img.convert(pdf, {
+                pages: [1],
+                targetWidth: 200,
+                password: 'secret',
+            });
+            assert.ok(result.pages[0].success, '使用密码后第 1 页应该渲染成功');
+        });
     });
 
     describe('错误处理', () => {
@@ -0,0 +1,259 @@
+/**
+ * 原生渲染器高级接口测试
+ *
+ * 这些接口会在后台线程里使用 PDFium，每组测试都穿插普通的 renderFromBuffer 调用，
+ * 确认它们不会长期占用 PDFium 而让其他渲染挂起。
+ *
+ * 运行方式：
+ *   node --test test/native.test.js
+ */
+
+import { describe, it, before } from 'node:test';
+import assert from 'node:assert';
+import path from 'path';
+import fs from 'fs';
+import http from 'http';
+import { fileURLToPath } from 'url';
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+const PROJECT_ROOT = path.join(__dirname, '../../..');
+const STATIC_DIR = path.join(PROJECT_ROOT, 'static');
+
+// 测试用 PDF 文件
+const TEST_PDF = path.join(STATIC_DIR, '发票.pdf');
+
+// 普通渲染超过该时间没有返回视为被挂起
+const RENDER_TIMEOUT = 10000;
+
+let native;
+let pdfBuffer;
+
+/**
+ * 普通渲染，超时视为 PDFium 被占用
+ */
+/**
+ * 启动只支持 Range 请求的本地服务器，contentRange 决定 Content-Range 头里的总长度
+ */
+async function startRangeServer(contentRange) {
+    const server = http.createServer((req, res) => {
+        const match = /bytes=(\d+)-(\d+)/.exec(req.headers.range || '');
+        const start = Number(match[1]);
+        const end = Math.min(Number(match[2]), pdfBuffer.length - 1);
+        res.writeHead(206, { 'Content-Range': contentRange(start, end) });
+        res.end(pdfBuffer.subarray(start, end + 1));
+    });
+    await new Promise(resolve => server.listen(0, '127.0.0.1', resolve));
+    return server;
+}
+
+async function plainRender(pages = [1]) {
+    let timer;
+    const timeout = new Promise((_, reject) => {
+        timer = setTimeout(() => reject(new Error('普通渲染被挂起')), RENDER_TIMEOUT);
+    });
+    try {
+        return await Promise.race([native.renderFromBuffer(pdfBuffer, pages), timeout]);
+    } finally {
+        clearTimeout(timer);
+    }
+}
+
+describe('原生渲染器高级接口测试', () => {
+    before(async () => {
+        native = await import('../src/renderers/native.js');
+        if (fs.existsSync(TEST_PDF)) {
+            pdfBuffer = fs.readFileSync(TEST_PDF);
+        }
+    });
+
+    /**
+     * 原生模块或测试文件不可用时跳过
+     */
+    function skipped() {
+        if (!native.isNativeAvailable()) {
+            console.log('跳过测试：原生渲染器不可用');
+            return true;
+        }
+        if (!pdfBuffer) {
+            console.log(`跳过测试：测试文件不存在 ${TEST_PDF}`);
+            return true;
+        }
+        return false;
+    }
+
+    describe('常驻文档', () => {
+        it('预加载期间普通渲染不应被挂起', async () => {
+            if (skipped()) return;
+
+            const documentId = native.preloadDocument(pdfBuffer);
+            try {
+                const plain = await plainRender();
+                assert.ok(plain.success, '普通渲染应该成功');
+                assert.strictEqual(plain.pages[0].success, true);
+
+                const preloaded = await native.renderPreloadedPages(documentId, [1]);
+                assert.ok(preloaded.success, '常驻文档渲染应该成功');
+                assert.strictEqual(preloaded.pages[0].success, true);
+
+                const again = await plainRender();
+                assert.ok(again.success, '常驻文档渲染后普通渲染应该成功');
+            } finally {
+                assert.strictEqual(native.releaseDocument(documentId), true);
+            }
+        });
+    });
+
+    describe('常驻文档句柄', () => {
+        it('句柄打开期间普通渲染不应被挂起', async () => {
+            if (skipped()) return;
+
+            const document = await native.openDocument(pdfBuffer);
+            try {
+                assert.ok(document.pageCount() > 0, '页数应该大于 0');
+
+                const plain = await plainRender();
+                assert.ok(plain.success, '普通渲染应该成功');
+
+                const info = await document.getPageInfo(1);
+                assert.ok(info.width > 0, '页面宽度应该大于 0');
+
+                const rendered = await document.renderPages([1]);
+                assert.ok(rendered.success, '句柄渲染应该成功');
+                assert.strictEqual(rendered.pages[0].success, true);
+
+                const again = await plainRender();
+                assert.ok(again.success, '句柄渲染后普通渲染应该成功');
+            } finally {
+                assert.strictEqual(document.close(), true);
+            }
+        });
+
+        it('空闲超时后句柄仍然可用', async () => {
+            if (skipped()) return;
+
+            const document = await native.openDocument(pdfBuffer, { documentIdleTimeoutMs: 50 });
+            try {
+                await new Promise(resolve => setTimeout(resolve, 200));
+                assert.strictEqual(document.closed, false);
+
+                const rendered = await document.renderPages([1]);
+                assert.ok(rendered.success, '空闲超时后句柄渲染应该成功');
+            } finally {
+                document.close();
+            }
+        });
+    });
+
+    describe('渲染调度器', () => {
+        it('调度器空闲时普通渲染不应被挂起', async () => {
+            if (skipped()) return;
+
+            const results = [];
+            let finished;
+            const done = new Promise(resolve => { finished = resolve; });
+            const scheduler = native.createRenderScheduler({}, (err, result) => {
+                results.push(err ?? result);
+                if (results.length === 2) finished();
+            });
+            try {
+                const jobIds = scheduler.schedule(pdfBuffer, [1, 1], 5);
+                assert.strictEqual(jobIds.length, 2);
+                await done;
+                for (const result of results) {
+                    assert.ok(!(result instanceof Error), '调度任务不应报错');
+                    assert.strictEqual(result.page.success, true);
+                }
+
+                // 工作线程空闲等待新任务，不应继续占用 PDFium
+                const plain = await plainRender();
+                assert.ok(plain.success, '调度器空闲时普通渲染应该成功');
+                assert.strictEqual(scheduler.stats().completed, 2);
+            } finally {
+                scheduler.close();
+            }
+        });
+
+        it('队列很长时普通渲染不必等到队列清空', async () => {
+            if (skipped()) return;
+
+            const total = 64;
+            let completed = 0;
+            let finished;
+            const done = new Promise(resolve => { finished = resolve; });
+            const scheduler = native.createRenderScheduler({}, () => {
+                if (++completed === total) finished();
+            });
+            try {
+                scheduler.schedule(pdfBuffer, new Array(total).fill(1));
+
+                // 工作线程每批任务之后释放 PDFium，普通渲染排在剩余任务之前完成
+                const plain = await plainRender();
+                assert.ok(plain.success, '调度器繁忙时普通渲染应该成功');
+                assert.ok(completed < total, `普通渲染应该在队列清空前完成（已完成 ${completed} 个任务）`);
+                await done;
+            } finally {
+                scheduler.close();
+            }
+        });
+    });
+
+    describe('渲染会话', () => {
+        it('会话暂停期间普通渲染不应被挂起', async () => {
+            if (skipped()) return;
+
+            const session = native.startRenderSession(pdfBuffer, [1, 1, 1]);
+            session.pause();
+            try {
+                const plain = await plainRender();
+                assert.ok(plain.success, '会话暂停期间普通渲染应该成功');
+            } finally {
+                session.resume();
+            }
+
+            // 恢复后重新打开文档，剩余页面继续渲染
+            const pages = [];
+            let polled = session.poll();
+            pages.push(...polled.pages);
+            while (!polled.status.done) {
+                await new Promise(resolve => setTimeout(resolve, 20));
+                polled = session.poll();
+                pages.push(...polled.pages);
+            }
+            assert.strictEqual(polled.status.status, 'completed');
+            assert.strictEqual(pages.length, 3);
+            assert.ok(pages.every(page => page.success), '所有页面都应渲染成功');
+
+            const again = await plainRender();
+            assert.ok(again.success, '会话结束后普通渲染应该成功');
+        });
+    });
+
+    describe('长度未知的流', () => {
+        it('从 Content-Range 得到文件长度', async () => {
+            if (skipped()) return;
+
+            const server = await startRangeServer((start, end) => `bytes ${start}-${end}/${pdfBuffer.length}`);
+            try {
+                const url = `http://127.0.0.1:${server.address().port}/test.pdf`;
+                const result = await native.renderFromStream(url, 0, [1]);
+                assert.ok(result.pages[0].success, '第 1 页应该渲染成功');
+            } finally {
+                server.closeAllConnections();
+                server.close();
+            }
+        });
+
+        it('服务器不报告总长度时应该失败', async () => {
+            if (skipped()) return;
+
+            const server = await startRangeServer((start, end) => `bytes ${start}-${end}/*`);
+            try {
+                const url = `http://127.0.0.1:${server.address().port}/test.pdf`;
+                await assert.rejects(native.renderFromStream(url, 0, [1]), /did not report totalSize/);
+            } finally {
+                server.closeAllConnections();
+                server.close();
+            }
+        });
+    });
+});